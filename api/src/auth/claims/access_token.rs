@@ -1,3 +1,4 @@
+use bigneon_db::models::{OAuthConsent, UserSession};
 use errors::BigNeonError;
 use std::time::Duration;
 use std::time::SystemTime;
@@ -9,6 +10,17 @@ pub struct AccessToken {
     pub sub: String,
     pub iss: String,
     pub exp: u64,
+    /// Scopes the token is limited to, only set for tokens issued to OAuth clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_consent_id: Option<Uuid>,
+    /// Session the token was issued for when the user signed in directly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<Uuid>,
+    /// Set when the user completed two-factor authentication when signing in
+    #[serde(default)]
+    pub two_factor: bool,
 }
 
 impl AccessToken {
@@ -21,9 +33,37 @@ impl AccessToken {
             iss: issuer,
             sub: user_id.hyphenated().to_string(),
             exp,
+            scopes: None,
+            oauth_consent_id: None,
+            session_id: None,
+            two_factor: false,
         }
     }
 
+    pub fn new_for_oauth_consent(
+        consent: &OAuthConsent,
+        scopes: Vec<String>,
+        issuer: String,
+        expiry_in_minutes: &u64,
+    ) -> Self {
+        let mut access_token = AccessToken::new(&consent.user_id, issuer, expiry_in_minutes);
+        access_token.scopes = Some(scopes);
+        access_token.oauth_consent_id = Some(consent.id);
+        access_token
+    }
+
+    pub fn new_for_user_session(
+        session: &UserSession,
+        two_factor: bool,
+        issuer: String,
+        expiry_in_minutes: &u64,
+    ) -> Self {
+        let mut access_token = AccessToken::new(&session.user_id, issuer, expiry_in_minutes);
+        access_token.session_id = Some(session.id);
+        access_token.two_factor = two_factor;
+        access_token
+    }
+
     pub fn get_id(&self) -> Result<Uuid, BigNeonError> {
         Ok(Uuid::parse_str(&self.sub)?)
     }
@@ -1,3 +1,4 @@
+use bigneon_db::models::{OAuthConsent, UserSession};
 use errors::BigNeonError;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
@@ -7,6 +8,17 @@ pub struct RefreshToken {
     pub sub: String,
     pub iss: String,
     pub issued: u64,
+    /// Scopes the token is limited to, only set for tokens issued to OAuth clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_consent_id: Option<Uuid>,
+    /// Session the token was issued for when the user signed in directly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<Uuid>,
+    /// Set when the user completed two-factor authentication when signing in
+    #[serde(default)]
+    pub two_factor: bool,
 }
 
 impl RefreshToken {
@@ -17,9 +29,27 @@ impl RefreshToken {
             iss: issuer,
             sub: user_id.hyphenated().to_string(),
             issued,
+            scopes: None,
+            oauth_consent_id: None,
+            session_id: None,
+            two_factor: false,
         }
     }
 
+    pub fn new_for_oauth_consent(consent: &OAuthConsent, scopes: Vec<String>, issuer: String) -> Self {
+        let mut refresh_token = RefreshToken::new(&consent.user_id, issuer);
+        refresh_token.scopes = Some(scopes);
+        refresh_token.oauth_consent_id = Some(consent.id);
+        refresh_token
+    }
+
+    pub fn new_for_user_session(session: &UserSession, two_factor: bool, issuer: String) -> Self {
+        let mut refresh_token = RefreshToken::new(&session.user_id, issuer);
+        refresh_token.session_id = Some(session.id);
+        refresh_token.two_factor = two_factor;
+        refresh_token
+    }
+
     pub fn get_id(&self) -> Result<Uuid, BigNeonError> {
         Ok(Uuid::parse_str(&self.sub)?)
     }
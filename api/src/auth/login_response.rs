@@ -0,0 +1,33 @@
+use actix_web::Error;
+use actix_web::HttpRequest;
+use actix_web::HttpResponse;
+use actix_web::Responder;
+use auth::TokenResponse;
+use chrono::NaiveDateTime;
+use serde_json;
+
+/// Returned instead of tokens when the user has two-factor authentication enabled, the challenge token is
+/// exchanged for tokens along with a code from the user's authenticator app or a recovery code
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Token(TokenResponse),
+    TwoFactorChallenge(TwoFactorChallengeResponse),
+}
+
+impl Responder for LoginResponse {
+    type Item = HttpResponse;
+    type Error = Error;
+
+    fn respond_to<S>(self, _req: &HttpRequest<S>) -> Result<HttpResponse, Error> {
+        let body = serde_json::to_string(&self)?;
+        Ok(HttpResponse::Ok().content_type("application/json").body(body))
+    }
+}
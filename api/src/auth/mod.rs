@@ -1,5 +1,7 @@
+pub use self::login_response::{LoginResponse, TwoFactorChallengeResponse};
 pub use self::token_response::TokenResponse;
 
 pub mod claims;
+pub mod login_response;
 pub mod token_response;
 pub mod user;
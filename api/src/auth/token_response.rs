@@ -3,16 +3,22 @@ use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use actix_web::Responder;
 use auth::{claims::AccessToken, claims::RefreshToken};
-use bigneon_db::models::User;
+use bigneon_db::models::{OAuthConsent, UserSession};
 use errors::BigNeonError;
 use jwt::{encode, Header};
 use serde_json;
-use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 pub struct TokenResponse {
     pub access_token: String,
     pub refresh_token: String,
+    /// OAuth clients additionally receive the token type, lifetime and granted scopes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 impl Responder for TokenResponse {
@@ -30,40 +36,84 @@ impl TokenResponse {
         TokenResponse {
             access_token: String::from(access_token),
             refresh_token: String::from(refresh_token),
+            token_type: None,
+            expires_in: None,
+            scope: None,
         }
     }
 
-    pub fn create_from_user(
+    /// Tokens issued when the user signs in, two-factor is set once the user has completed two-factor
+    /// authentication
+    pub fn create_from_user_session(
         token_secret: &str,
         token_issuer: &str,
         expiry: &u64,
-        user: &User,
+        session: &UserSession,
+        two_factor: bool,
     ) -> Result<Self, BigNeonError> {
-        let access_token_claims = AccessToken::new(&user.id, token_issuer.to_string(), expiry);
+        let access_token_claims =
+            AccessToken::new_for_user_session(session, two_factor, token_issuer.to_string(), expiry);
         let access_token = encode(&Header::default(), &access_token_claims, token_secret.as_bytes())?;
 
-        let refresh_token_claims = RefreshToken::new(&user.id, token_issuer.to_string());
+        let refresh_token_claims = RefreshToken::new_for_user_session(session, two_factor, token_issuer.to_string());
         let refresh_token = encode(&Header::default(), &refresh_token_claims, token_secret.as_bytes())?;
 
-        Ok(TokenResponse {
-            access_token,
-            refresh_token,
-        })
+        Ok(TokenResponse::new(&access_token, &refresh_token))
+    }
+
+    pub fn create_from_oauth_consent(
+        token_secret: &str,
+        token_issuer: &str,
+        expiry_time_in_minutes: &u64,
+        consent: &OAuthConsent,
+        scopes: Vec<String>,
+    ) -> Result<Self, BigNeonError> {
+        let access_token_claims = AccessToken::new_for_oauth_consent(
+            consent,
+            scopes.clone(),
+            token_issuer.to_string(),
+            expiry_time_in_minutes,
+        );
+        let access_token = encode(&Header::default(), &access_token_claims, token_secret.as_bytes())?;
+
+        let refresh_token_claims =
+            RefreshToken::new_for_oauth_consent(consent, scopes.clone(), token_issuer.to_string());
+        let refresh_token = encode(&Header::default(), &refresh_token_claims, token_secret.as_bytes())?;
+
+        let mut response = TokenResponse::new(&access_token, &refresh_token);
+        response.set_oauth_details(expiry_time_in_minutes, &scopes);
+        Ok(response)
     }
 
     pub fn create_from_refresh_token(
         token_secret: &str,
         token_issuer: &str,
         expiry_time_in_minutes: &u64,
-        user_id: &Uuid,
+        refresh_token: &RefreshToken,
         signed_refresh_token: &str,
     ) -> Result<Self, BigNeonError> {
-        let access_token_claims = AccessToken::new(&user_id, token_issuer.to_string(), expiry_time_in_minutes);
+        let mut access_token_claims = AccessToken::new(
+            &refresh_token.get_id()?,
+            token_issuer.to_string(),
+            expiry_time_in_minutes,
+        );
+        // Tokens refreshed by OAuth clients keep the scopes of the original grant
+        access_token_claims.scopes = refresh_token.scopes.clone();
+        access_token_claims.oauth_consent_id = refresh_token.oauth_consent_id;
+        access_token_claims.session_id = refresh_token.session_id;
+        access_token_claims.two_factor = refresh_token.two_factor;
         let access_token = encode(&Header::default(), &access_token_claims, token_secret.as_bytes())?;
 
-        Ok(TokenResponse {
-            access_token,
-            refresh_token: String::from(signed_refresh_token),
-        })
+        let mut response = TokenResponse::new(&access_token, signed_refresh_token);
+        if let Some(ref scopes) = refresh_token.scopes {
+            response.set_oauth_details(expiry_time_in_minutes, scopes);
+        }
+        Ok(response)
+    }
+
+    fn set_oauth_details(&mut self, expiry_time_in_minutes: &u64, scopes: &[String]) {
+        self.token_type = Some("Bearer".to_string());
+        self.expires_in = Some(expiry_time_in_minutes * 60);
+        self.scope = Some(scopes.join(" "));
     }
 }
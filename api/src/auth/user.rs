@@ -1,6 +1,6 @@
 use actix_web::{HttpRequest, Result};
 use bigneon_db::models::User as DbUser;
-use bigneon_db::models::{scopes, Event, EventUser, Order, Organization, Roles, Scopes};
+use bigneon_db::models::{scopes, ApiKey, Event, EventUser, Order, Organization, Roles, Scopes};
 use bigneon_db::prelude::errors::EnumParseError;
 use bigneon_db::prelude::Optional;
 use diesel::PgConnection;
@@ -14,6 +14,7 @@ use std::collections::HashMap;
 use uuid::Uuid;
 
 const MISSING_PERMISSIONS_MESSAGING: &str = "User does not have the required permissions";
+const TWO_FACTOR_REQUIRED_MESSAGING: &str = "Two-factor authentication is required by this organization";
 
 #[derive(Clone, Debug)]
 pub struct User {
@@ -22,6 +23,12 @@ pub struct User {
     pub ip_address: Option<String>,
     pub uri: String,
     pub method: String,
+    pub api_key: Option<ApiKey>,
+    /// Scopes granted to the OAuth client the user's token was issued to
+    pub token_scopes: Option<Vec<Scopes>>,
+    /// Session the user's access token was issued for
+    pub session_id: Option<Uuid>,
+    pub two_factor_authenticated: bool,
 }
 
 impl User {
@@ -33,9 +40,34 @@ impl User {
             ip_address: request.connection_info().remote().map(|i| i.to_string()),
             uri: request.uri().to_string(),
             method: request.method().to_string(),
+            api_key: None,
+            token_scopes: None,
+            session_id: None,
+            two_factor_authenticated: false,
         })
     }
 
+    /// Authenticates as the key's user, global scopes are not granted so access is limited to the key's
+    /// organization and scopes
+    pub fn new_for_api_key(user: DbUser, api_key: ApiKey, request: &HttpRequest<AppState>) -> User {
+        User {
+            user,
+            global_scopes: Vec::new(),
+            ip_address: request.connection_info().remote().map(|i| i.to_string()),
+            uri: request.uri().to_string(),
+            method: request.method().to_string(),
+            api_key: Some(api_key),
+            token_scopes: None,
+            session_id: None,
+            two_factor_authenticated: false,
+        }
+    }
+
+    /// Whether the request was made by an integration or OAuth client rather than the user directly
+    pub fn is_delegated(&self) -> bool {
+        self.api_key.is_some() || self.token_scopes.is_some()
+    }
+
     pub fn id(&self) -> Uuid {
         self.user.id
     }
@@ -44,6 +76,19 @@ impl User {
         self.user.email.clone()
     }
 
+    /// Organizations can require two-factor authentication for selected roles. API keys are exempt as they
+    /// are issued by an organization administrator for a specific integration.
+    fn two_factor_required(
+        &self,
+        organization: &Organization,
+        connection: &PgConnection,
+    ) -> Result<bool, BigNeonError> {
+        if self.api_key.is_some() || self.two_factor_authenticated {
+            return Ok(false);
+        }
+        Ok(organization.requires_two_factor_for_user(&self.user, connection)?)
+    }
+
     fn check_scope_access(
         &self,
         scope: Scopes,
@@ -52,11 +97,49 @@ impl User {
         connection: Option<&PgConnection>,
         log_on_failure: bool,
     ) -> Result<bool, BigNeonError> {
-        if self.global_scopes.contains(&scope.to_string()) {
-            return Ok(true);
+        let mut logging_data = HashMap::new();
+
+        if let Some(ref api_key) = self.api_key {
+            // Keys are limited to their own organization and scopes on top of their user's permissions
+            let organization_id = organization.map(|o| o.id);
+            if organization_id != Some(api_key.organization_id) || !api_key.scopes()?.contains(&scope) {
+                logging_data.insert("api_key_id", json!(api_key.id));
+                logging_data.insert("api_key_scopes", json!(api_key.scopes));
+                logging_data.insert("organization_id", json!(organization_id));
+                logging_data.insert("accessed_scope", json!(scope.to_string()));
+                if log_on_failure {
+                    self.log_unauthorized_access_attempt(logging_data);
+                }
+                return Ok(false);
+            }
+        } else if let Some(ref token_scopes) = self.token_scopes {
+            // OAuth clients are limited to the granted scopes on top of the user's permissions
+            if !token_scopes.contains(&scope) {
+                logging_data.insert("token_scopes", json!(token_scopes));
+                logging_data.insert("accessed_scope", json!(scope.to_string()));
+                if log_on_failure {
+                    self.log_unauthorized_access_attempt(logging_data);
+                }
+                return Ok(false);
+            }
         }
 
-        let mut logging_data = HashMap::new();
+        if let (Some(organization), Some(connection)) = (organization, connection) {
+            if self.two_factor_required(organization, connection)? {
+                logging_data.insert("organization_id", json!(organization.id));
+                logging_data.insert("accessed_scope", json!(scope.to_string()));
+                logging_data.insert("two_factor_required", json!(true));
+                if log_on_failure {
+                    self.log_unauthorized_access_attempt(logging_data);
+                }
+                return Ok(false);
+            }
+        }
+
+        // Keys and tokens are never granted their user's global scopes
+        if !self.is_delegated() && self.global_scopes.contains(&scope.to_string()) {
+            return Ok(true);
+        }
 
         if let (Some(organization), Some(connection)) = (organization, connection) {
             let organization_scopes = organization.get_scopes_for_user(&self.user, connection)?;
@@ -112,7 +195,8 @@ impl User {
 
     pub fn has_scope_for_order(&self, scope: Scopes, order: &Order, conn: &PgConnection) -> Result<bool, BigNeonError> {
         let mut has_scope = false;
-        for event in order.events(conn)? {
+        let events = order.events(conn)?;
+        for event in &events {
             if self.check_scope_access(
                 scope,
                 Some(&event.organization(conn)?),
@@ -123,6 +207,15 @@ impl User {
                 has_scope = true;
             }
         }
+
+        // Orders containing only season passes are checked against the season pass organization
+        if events.is_empty() {
+            for organization in order.organizations(conn)? {
+                if self.check_scope_access(scope, Some(&organization), None, Some(conn), false)? {
+                    has_scope = true;
+                }
+            }
+        }
         Ok(has_scope)
     }
 
@@ -179,7 +272,7 @@ impl User {
         if self.check_scope_access(scope, Some(organization), Some(event.id), Some(conn), true)? {
             return Ok(());
         }
-        Err(AuthError::new(AuthErrorType::Unauthorized, MISSING_PERMISSIONS_MESSAGING.to_string()).into())
+        Err(self.missing_permissions_error(organization, conn)?)
     }
 
     pub fn requires_scope_for_organization(
@@ -191,7 +284,18 @@ impl User {
         if self.check_scope_access(scope, Some(organization), None, Some(conn), true)? {
             return Ok(());
         }
-        Err(AuthError::new(AuthErrorType::Unauthorized, MISSING_PERMISSIONS_MESSAGING.to_string()).into())
+        Err(self.missing_permissions_error(organization, conn)?)
+    }
+
+    fn missing_permissions_error(
+        &self,
+        organization: &Organization,
+        conn: &PgConnection,
+    ) -> Result<BigNeonError, BigNeonError> {
+        if self.two_factor_required(organization, conn)? {
+            return Ok(AuthError::new(AuthErrorType::Forbidden, TWO_FACTOR_REQUIRED_MESSAGING.to_string()).into());
+        }
+        Ok(AuthError::new(AuthErrorType::Unauthorized, MISSING_PERMISSIONS_MESSAGING.to_string()).into())
     }
 
     pub fn into_optional(self) -> OptionalUser {
@@ -8,6 +8,7 @@ pub mod organization_invites;
 pub mod reports;
 pub mod tickets;
 pub mod user;
+pub mod waitlist;
 
 pub fn insert_event_template_data(
     template_data: &mut TemplateData,
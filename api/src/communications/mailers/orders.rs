@@ -24,44 +24,117 @@ pub fn confirmation_email(
     let mut total_fees = 0;
     let mut total_initial_fees = 0;
     let mut total_refunded_fees = 0;
+    let mut total_tax = 0;
+    // Tax totals per tax rule description, inclusive taxes are listed as part of the total rather than on top
+    let mut tax_breakdown: Vec<(String, bool, i64)> = Vec::new();
 
-    for oi in &display_order.items {
-        match oi.item_type {
-            OrderItemTypes::Tickets => {
-                item_breakdown.push_str(&generate_item_row(
-                    &oi.description,
-                    oi.quantity,
-                    oi.unit_price_in_cents,
-                    false,
-                ));
-                let mut discount_per_ticket = 0;
-
-                if let Some(discount_item) = OrderItem::find(oi.id, conn)?.find_discount_item(conn)? {
-                    discount_per_ticket = discount_item.unit_price_in_cents;
+    // Orders spanning several events list each event's items under its own heading, items not tied to an
+    // event (e.g. season passes) follow at the end
+    let mut sections: Vec<(Option<&str>, Vec<&DisplayOrderItem>)> = display_order
+        .events
+        .iter()
+        .map(|e| {
+            (
+                Some(e.name.as_str()),
+                display_order
+                    .items
+                    .iter()
+                    .filter(|i| e.item_ids.contains(&i.id))
+                    .collect(),
+            )
+        })
+        .collect();
+    sections.push((
+        None,
+        display_order
+            .items
+            .iter()
+            .filter(|i| !display_order.events.iter().any(|e| e.item_ids.contains(&i.id)))
+            .collect(),
+    ));
+    let show_headings = display_order.events.len() > 1;
+
+    for (heading, items) in sections {
+        if let Some(heading) = heading.filter(|_| show_headings) {
+            item_breakdown.push_str(&format!(r#"<tr><th colspan="4">{}</th></tr>"#, heading));
+        }
+        for oi in items {
+            match oi.item_type {
+                OrderItemTypes::Tickets => {
                     item_breakdown.push_str(&generate_item_row(
-                        "Discount",
-                        discount_item.quantity,
-                        discount_item.unit_price_in_cents,
+                        &oi.description,
+                        oi.quantity,
+                        oi.unit_price_in_cents,
                         false,
                     ));
-                }
+                    let mut discount_per_ticket = 0;
 
-                if oi.refunded_quantity > 0 {
+                    if let Some(discount_item) = OrderItem::find(oi.id, conn)?.find_discount_item(conn)? {
+                        discount_per_ticket = discount_item.unit_price_in_cents;
+                        item_breakdown.push_str(&generate_item_row(
+                            "Discount",
+                            discount_item.quantity,
+                            discount_item.unit_price_in_cents,
+                            false,
+                        ));
+                    }
+
+                    if oi.refunded_quantity > 0 {
+                        item_breakdown.push_str(&generate_item_row(
+                            "Refunded",
+                            oi.refunded_quantity,
+                            oi.unit_price_in_cents + discount_per_ticket,
+                            true,
+                        ));
+                    }
+                }
+                OrderItemTypes::ResaleTickets => {
+                    item_breakdown.push_str(&generate_item_row(
+                        &oi.description,
+                        oi.quantity,
+                        oi.unit_price_in_cents,
+                        false,
+                    ));
+                }
+                OrderItemTypes::SeasonPasses => {
                     item_breakdown.push_str(&generate_item_row(
-                        "Refunded",
-                        oi.refunded_quantity,
-                        oi.unit_price_in_cents + discount_per_ticket,
-                        true,
+                        &oi.description,
+                        oi.quantity,
+                        oi.unit_price_in_cents,
+                        false,
                     ));
+                    if oi.refunded_quantity > 0 {
+                        item_breakdown.push_str(&generate_item_row(
+                            "Refunded",
+                            oi.refunded_quantity,
+                            oi.unit_price_in_cents,
+                            true,
+                        ));
+                    }
+                }
+                // Do nothing, included above with ticket for display
+                OrderItemTypes::Discount => (),
+                OrderItemTypes::Tax => {
+                    let inclusive = oi.included_tax_in_cents > 0;
+                    let tax =
+                        (oi.quantity - oi.refunded_quantity) * (oi.unit_price_in_cents + oi.included_tax_in_cents);
+                    if !inclusive {
+                        total_tax += tax;
+                    }
+                    match tax_breakdown
+                        .iter_mut()
+                        .find(|(description, i, _)| description == &oi.description && *i == inclusive)
+                    {
+                        Some(entry) => entry.2 += tax,
+                        None => tax_breakdown.push((oi.description.clone(), inclusive, tax)),
+                    }
+                }
+                _ => {
+                    //Accumulate fees
+                    total_initial_fees += oi.quantity * oi.unit_price_in_cents;
+                    total_refunded_fees += oi.refunded_quantity * oi.unit_price_in_cents;
+                    total_fees += (oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents;
                 }
-            }
-            // Do nothing, included above with ticket for display
-            OrderItemTypes::Discount => (),
-            _ => {
-                //Accumulate fees
-                total_initial_fees += oi.quantity * oi.unit_price_in_cents;
-                total_refunded_fees += oi.refunded_quantity * oi.unit_price_in_cents;
-                total_fees += (oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents;
             }
         }
     }
@@ -80,6 +153,14 @@ pub fn confirmation_email(
             format!("{:.*}", 2, total_refunded_fees as f64 / 100.0)
         ));
     }
+    for (description, inclusive, tax) in tax_breakdown.iter().filter(|(_, _, tax)| *tax > 0) {
+        total_breakdown.push_str(&format!(
+            "<tr><th>{}{}</th><td>{}</td></tr>",
+            if *inclusive { "Includes " } else { "" },
+            description,
+            format!("{:.*}", 2, *tax as f64 / 100.0)
+        ));
+    }
     total_breakdown.push_str(&format!(
         "<tr><th>Order Total</th><td>{}</td></tr>",
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0)
@@ -106,6 +187,7 @@ pub fn confirmation_email(
         format!("{:.*}", 2, total_refunded_fees as f64 / 100.0),
     );
     template_data.insert("total_fees".to_string(), format!("{:.*}", 2, total_fees as f64 / 100.0));
+    template_data.insert("total_tax".to_string(), format!("{:.*}", 2, total_tax as f64 / 100.0));
     template_data.insert(
         "total_price".to_string(),
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0),
@@ -220,8 +302,14 @@ pub fn refund_email(
         .filter(|i| i.1.item_type.is_fee())
         .map(|i| i.0.amount)
         .sum::<i64>();
+    let total_tax = items
+        .iter()
+        .filter(|i| i.1.item_type == OrderItemTypes::Tax)
+        .map(|i| i.0.amount)
+        .sum::<i64>();
 
     template_data.insert("total_fees".to_string(), format!("{:.*}", 2, total_fees as f64 / 100.0));
+    template_data.insert("total_tax".to_string(), format!("{:.*}", 2, total_tax as f64 / 100.0));
     template_data.insert("total_price".to_string(), format!("{:.*}", 2, amount));
     template_data.insert("item_breakdown".to_string(), item_breakdown);
     template_data.insert("tickets_link".to_string(), format!("{}/orders", config.front_end_url));
@@ -38,3 +38,37 @@ pub fn ticket_counts(
 
     Ok(())
 }
+
+pub fn saved_report(
+    email: String,
+    saved_report: &SavedReport,
+    organization: &Organization,
+    attachment: CommAttachment,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon Report: {}", saved_report.name);
+    let template_id = config.email_templates.saved_report.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("report_name".to_string(), saved_report.name.clone());
+    template_data.insert("org".to_string(), organization.name.clone());
+    template_data.insert("filename".to_string(), attachment.filename.clone());
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["saved_reports", "reports"]),
+        None,
+    );
+    communication.attachments = Some(vec![attachment]);
+    communication.queue(conn)?;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub fn offer(
+    email: String,
+    entry: &WaitlistEntry,
+    hold: &Hold,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let ticket_type = entry.ticket_type(conn)?;
+    let event = Event::find(ticket_type.event_id, conn)?;
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "BigNeon Tickets Available".to_string();
+    let template_id = config.email_templates.waitlist_offer.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(&event, &mut extra_data, conn)?;
+    extra_data.insert("ticket_type_name".to_string(), json!(ticket_type.name));
+    extra_data.insert("redemption_code".to_string(), json!(hold.redemption_code));
+    extra_data.insert(
+        "offer_expires_at".to_string(),
+        json!(entry.offer_expires_at.map(|e| e.timestamp())),
+    );
+    extra_data.insert(
+        "offer_link".to_string(),
+        json!(format!(
+            "{}/events/{}/tickets?code={}",
+            config.front_end_url,
+            event.slug(conn)?,
+            hold.redemption_code.clone().unwrap_or("".to_string())
+        )),
+    );
+    extra_data.insert("timestamp".to_string(), json!(Utc::now().timestamp()));
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["waitlist_offer", "waitlist"]),
+        Some(extra_data),
+    );
+    communication.main_table = Some(Tables::WaitlistEntries);
+    communication.main_table_id = Some(entry.id);
+    communication.queue(conn)?;
+
+    Ok(())
+}
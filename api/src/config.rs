@@ -1,13 +1,16 @@
-use bigneon_db::models::{EmailProvider, Environment};
+use bigneon_db::models::{DomainActionTypes, EmailProvider, Environment};
 use bigneon_db::utils::errors::EnumParseError;
 use dotenv::dotenv;
 use errors::{ApplicationError, BigNeonError};
 use itertools::Itertools;
+use std::cmp;
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
 use std::str;
 use std::str::FromStr;
 use tari_client::{HttpTariClient, TariClient, TariTestClient};
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct Config {
@@ -27,6 +30,8 @@ pub struct Config {
     pub facebook_app_secret: Option<String>,
     pub globee_api_key: String,
     pub globee_base_url: String,
+    pub hosted_checkout: Option<HostedCheckoutSettings>,
+    pub oidc_providers: Vec<OidcProviderSettings>,
     pub validate_ipns: bool,
     pub api_base_url: String,
     pub google_recaptcha_secret_key: Option<String>,
@@ -34,6 +39,8 @@ pub struct Config {
     pub block_external_comms: bool,
     pub primary_currency: String,
     pub stripe_secret_key: String,
+    pub stripe_webhook_secret: Option<String>,
+    pub nullify_tickets_on_chargeback: bool,
     pub token_secret: String,
     pub token_issuer: String,
     pub tari_client: Box<dyn TariClient + Send + Sync>,
@@ -61,6 +68,7 @@ pub struct Config {
     pub branch_io_branch_key: String,
     pub max_instances_per_ticket_type: i64,
     pub connection_pool: ConnectionPoolConfig,
+    pub domain_action_monitor: DomainActionMonitorConfig,
     pub ssr_trigger_header: String,
     pub ssr_trigger_value: String,
     pub customer_io: CustomerIoSettings,
@@ -77,6 +85,33 @@ pub struct ConnectionPoolConfig {
     pub max: u32,
 }
 
+#[derive(Clone)]
+pub struct DomainActionMonitorConfig {
+    /// Seconds to wait for work before polling the database again
+    pub poll_period_in_secs: u64,
+    /// Wake up on `NOTIFY` from inserted domain events and actions instead of relying on polling alone.
+    /// LISTEN is unavailable when connecting through a transaction pooling proxy such as pgbouncer.
+    pub listen_for_notifications: bool,
+    /// Maximum number of actions of a type processed at the same time, types not listed are unlimited
+    pub concurrency_limits: HashMap<DomainActionTypes, usize>,
+    pub retry_backoff: RetryBackoff,
+}
+
+/// Failed domain actions are retried after an exponentially increasing delay
+#[derive(Clone, Copy)]
+pub struct RetryBackoff {
+    pub base_in_secs: i64,
+    pub max_in_secs: i64,
+}
+
+impl RetryBackoff {
+    /// Delay before retrying an action that has failed `attempt_count` times before this failure
+    pub fn delay_in_secs(&self, attempt_count: i64) -> i64 {
+        let exponent = cmp::min(cmp::max(attempt_count, 0), 30) as u32;
+        cmp::min(self.base_in_secs.saturating_mul(2i64.pow(exponent)), self.max_in_secs)
+    }
+}
+
 #[derive(Clone)]
 pub struct CubeJs {
     pub secret: String,
@@ -87,7 +122,9 @@ pub struct EmailTemplates {
     pub custom_broadcast: EmailTemplate,
     pub org_invite: EmailTemplate,
     pub password_reset: EmailTemplate,
+    pub saved_report: EmailTemplate,
     pub ticket_count_report: EmailTemplate,
+    pub waitlist_offer: EmailTemplate,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -121,6 +158,24 @@ impl fmt::Display for EmailTemplate {
     }
 }
 
+#[derive(Clone)]
+pub struct HostedCheckoutSettings {
+    pub base_url: String,
+    pub api_key: String,
+    pub secret: String,
+}
+
+#[derive(Clone)]
+pub struct OidcProviderSettings {
+    /// Name used in the login url, e.g. `google`
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    /// Enterprise providers only sign in existing members of this organization
+    pub organization_id: Option<Uuid>,
+}
+
 #[derive(Clone)]
 pub struct CustomerIoSettings {
     pub base_url: String,
@@ -143,17 +198,25 @@ const DOMAIN: &str = "DOMAIN";
 const EMAIL_TEMPLATES_CUSTOM_BROADCAST: &str = "EMAIL_TEMPLATES_CUSTOM_BROADCAST";
 const EMAIL_TEMPLATES_ORG_INVITE: &str = "EMAIL_TEMPLATES_ORG_INVITE";
 const EMAIL_TEMPLATES_PASSWORD_RESET: &str = "EMAIL_TEMPLATES_PASSWORD_RESET";
+const EMAIL_TEMPLATES_SAVED_REPORT: &str = "EMAIL_TEMPLATES_SAVED_REPORT";
 const EMAIL_TEMPLATES_TICKET_COUNT_REPORT: &str = "EMAIL_TEMPLATES_TICKET_COUNT_REPORT";
+const EMAIL_TEMPLATES_WAITLIST_OFFER: &str = "EMAIL_TEMPLATES_WAITLIST_OFFER";
 const ENVIRONMENT: &str = "ENVIRONMENT";
 const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
 const GLOBEE_API_KEY: &str = "GLOBEE_API_KEY";
 const GLOBEE_BASE_URL: &str = "GLOBEE_BASE_URL";
+const HOSTED_CHECKOUT_API_KEY: &str = "HOSTED_CHECKOUT_API_KEY";
+const HOSTED_CHECKOUT_BASE_URL: &str = "HOSTED_CHECKOUT_BASE_URL";
+const HOSTED_CHECKOUT_SECRET: &str = "HOSTED_CHECKOUT_SECRET";
+const OIDC_PROVIDERS: &str = "OIDC_PROVIDERS";
 const VALIDATE_IPNS: &str = "VALIDATE_IPNS";
 const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
+const STRIPE_WEBHOOK_SECRET: &str = "STRIPE_WEBHOOK_SECRET";
+const NULLIFY_TICKETS_ON_CHARGEBACK: &str = "NULLIFY_TICKETS_ON_CHARGEBACK";
 const STATIC_FILE_PATH: &str = "STATIC_FILE_PATH";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
@@ -203,9 +266,52 @@ const MAX_INSTANCES_PER_TICKET_TYPE: &str = "MAX_INSTANCES_PER_TICKET_TYPE";
 const CONNECTION_POOL_MIN: &str = "CONNECTION_POOL_MIN";
 const CONNECTION_POOL_MAX: &str = "CONNECTION_POOL_MAX";
 
+const DOMAIN_ACTION_POLL_PERIOD: &str = "DOMAIN_ACTION_POLL_PERIOD";
+const DOMAIN_ACTION_LISTEN_FOR_NOTIFICATIONS: &str = "DOMAIN_ACTION_LISTEN_FOR_NOTIFICATIONS";
+const DOMAIN_ACTION_CONCURRENCY_LIMITS: &str = "DOMAIN_ACTION_CONCURRENCY_LIMITS";
+const DOMAIN_ACTION_RETRY_BACKOFF_BASE: &str = "DOMAIN_ACTION_RETRY_BACKOFF_BASE";
+const DOMAIN_ACTION_RETRY_BACKOFF_MAX: &str = "DOMAIN_ACTION_RETRY_BACKOFF_MAX";
+
 const SSR_TRIGGER_HEADER: &str = "SSR_TRIGGER_HEADER";
 const SSR_TRIGGER_VALUE: &str = "SSR_TRIGGER_VALUE";
 
+/// Large background jobs are limited by default so they cannot take up all of the connections,
+/// `overrides` is a comma separated list of `DomainActionType:limit` pairs
+pub fn domain_action_concurrency_limits(overrides: Option<&str>) -> HashMap<DomainActionTypes, usize> {
+    let mut limits = HashMap::new();
+    limits.insert(DomainActionTypes::BroadcastPushNotification, 1);
+    limits.insert(DomainActionTypes::ProcessSettlementReport, 1);
+    limits.insert(DomainActionTypes::RegenerateDripActions, 1);
+    limits.insert(DomainActionTypes::SendAutomaticReportEmails, 1);
+    limits.insert(DomainActionTypes::SendSavedReport, 2);
+    limits.insert(DomainActionTypes::SubmitSitemapToSearchEngines, 1);
+    limits.insert(DomainActionTypes::UpdateGenres, 1);
+
+    for pair in overrides
+        .unwrap_or("")
+        .split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+    {
+        let split: Vec<&str> = pair.split(':').collect_vec();
+        if split.len() != 2 {
+            panic!("Invalid {} entry: {}", DOMAIN_ACTION_CONCURRENCY_LIMITS, pair);
+        }
+        let action_type: DomainActionTypes = split[0].trim().parse().unwrap_or_else(|_| {
+            panic!(
+                "Invalid domain action type in {}: {}",
+                DOMAIN_ACTION_CONCURRENCY_LIMITS, pair
+            )
+        });
+        let limit: usize = split[1]
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid limit in {}: {}", DOMAIN_ACTION_CONCURRENCY_LIMITS, pair));
+        limits.insert(action_type, limit);
+    }
+    limits
+}
+
 fn get_env_var(var: &str) -> String {
     env::var(var).unwrap_or_else(|_| panic!("{} must be defined", var))
 }
@@ -248,6 +354,14 @@ impl Config {
 
         let primary_currency = env::var(&PRIMARY_CURRENCY).unwrap_or_else(|_| "usd".to_string());
         let stripe_secret_key = env::var(&STRIPE_SECRET_KEY).unwrap_or_else(|_| "<stripe not enabled>".to_string());
+        let stripe_webhook_secret = env::var(&STRIPE_WEBHOOK_SECRET).ok();
+        let nullify_tickets_on_chargeback = env::var(&NULLIFY_TICKETS_ON_CHARGEBACK)
+            .unwrap_or("false".to_string())
+            .parse()
+            .expect(&format!(
+                "{} is not a valid boolean value",
+                NULLIFY_TICKETS_ON_CHARGEBACK
+            ));
         let token_secret = get_env_var(TOKEN_SECRET);
 
         let token_issuer = get_env_var(TOKEN_ISSUER);
@@ -277,6 +391,38 @@ impl Config {
             _ => "https://test.globee.com/payment-api/v1/".to_string(),
         });
 
+        // Hosted checkout is only enabled when the provider account is configured
+        let hosted_checkout = match (env::var(&HOSTED_CHECKOUT_API_KEY), env::var(&HOSTED_CHECKOUT_SECRET)) {
+            (Ok(api_key), Ok(secret)) => Some(HostedCheckoutSettings {
+                base_url: env::var(&HOSTED_CHECKOUT_BASE_URL).unwrap_or_else(|_| "http://127.0.0.1:7100/".to_string()),
+                api_key,
+                secret,
+            }),
+            _ => None,
+        };
+
+        // Each provider listed in OIDC_PROVIDERS is configured with OIDC_<NAME>_ISSUER, OIDC_<NAME>_CLIENT_ID,
+        // and optionally OIDC_<NAME>_CLIENT_SECRET and OIDC_<NAME>_ORGANIZATION_ID
+        let oidc_providers = env::var(&OIDC_PROVIDERS)
+            .unwrap_or_else(|_| "".to_string())
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                let prefix = format!("OIDC_{}", name.to_uppercase());
+                OidcProviderSettings {
+                    issuer: get_env_var(&format!("{}_ISSUER", prefix)),
+                    client_id: get_env_var(&format!("{}_CLIENT_ID", prefix)),
+                    client_secret: env::var(&format!("{}_CLIENT_SECRET", prefix)).ok(),
+                    organization_id: env::var(&format!("{}_ORGANIZATION_ID", prefix)).ok().map(|id| {
+                        id.parse()
+                            .expect(&format!("{}_ORGANIZATION_ID is not a valid uuid", prefix))
+                    }),
+                    name,
+                }
+            })
+            .collect();
+
         let branch_io_base_url = env::var(&BRANCH_IO_BASE_URL).unwrap_or("https://api2.branch.io/v1".to_string());
         let branch_io_branch_key = get_env_var(BRANCH_IO_BRANCH_KEY);
 
@@ -295,7 +441,9 @@ impl Config {
             custom_broadcast: get_env_var(EMAIL_TEMPLATES_CUSTOM_BROADCAST).parse().unwrap(),
             org_invite: get_env_var(EMAIL_TEMPLATES_ORG_INVITE).parse().unwrap(),
             password_reset: get_env_var(EMAIL_TEMPLATES_PASSWORD_RESET).parse().unwrap(),
+            saved_report: get_env_var(EMAIL_TEMPLATES_SAVED_REPORT).parse().unwrap(),
             ticket_count_report: get_env_var(EMAIL_TEMPLATES_TICKET_COUNT_REPORT).parse().unwrap(),
+            waitlist_offer: get_env_var(EMAIL_TEMPLATES_WAITLIST_OFFER).parse().unwrap(),
         };
 
         let customer_io_base_url = get_env_var(CUSTOMER_IO_BASE_URL);
@@ -366,6 +514,39 @@ impl Config {
                 .unwrap_or(20),
         };
 
+        let domain_action_monitor = DomainActionMonitorConfig {
+            poll_period_in_secs: env::var(DOMAIN_ACTION_POLL_PERIOD)
+                .map(|s| s.parse().expect("Not a valid integer for DOMAIN_ACTION_POLL_PERIOD"))
+                .unwrap_or(5),
+            listen_for_notifications: match env::var(&DOMAIN_ACTION_LISTEN_FOR_NOTIFICATIONS)
+                .unwrap_or_else(|_| "1".to_string())
+                .as_str()
+            {
+                "0" => false,
+                _ => true,
+            },
+            concurrency_limits: domain_action_concurrency_limits(
+                env::var(DOMAIN_ACTION_CONCURRENCY_LIMITS)
+                    .ok()
+                    .as_ref()
+                    .map(|s| s.as_str()),
+            ),
+            retry_backoff: RetryBackoff {
+                base_in_secs: env::var(DOMAIN_ACTION_RETRY_BACKOFF_BASE)
+                    .map(|s| {
+                        s.parse()
+                            .expect("Not a valid integer for DOMAIN_ACTION_RETRY_BACKOFF_BASE")
+                    })
+                    .unwrap_or(60),
+                max_in_secs: env::var(DOMAIN_ACTION_RETRY_BACKOFF_MAX)
+                    .map(|s| {
+                        s.parse()
+                            .expect("Not a valid integer for DOMAIN_ACTION_RETRY_BACKOFF_MAX")
+                    })
+                    .unwrap_or(3600),
+            },
+        };
+
         let ssr_trigger_header = env::var(&SSR_TRIGGER_HEADER).unwrap_or("x-ssr".to_string());
         let ssr_trigger_value = env::var(&SSR_TRIGGER_VALUE).unwrap_or("facebook".to_string());
 
@@ -388,6 +569,8 @@ impl Config {
             facebook_app_secret,
             globee_api_key,
             globee_base_url,
+            hosted_checkout,
+            oidc_providers,
             branch_io_base_url,
             validate_ipns,
             api_base_url,
@@ -396,6 +579,8 @@ impl Config {
             block_external_comms,
             primary_currency,
             stripe_secret_key,
+            stripe_webhook_secret,
+            nullify_tickets_on_chargeback,
             token_secret,
             token_issuer,
             front_end_url,
@@ -423,6 +608,7 @@ impl Config {
             branch_io_branch_key,
             max_instances_per_ticket_type,
             connection_pool,
+            domain_action_monitor,
             ssr_trigger_header,
             ssr_trigger_value,
         }
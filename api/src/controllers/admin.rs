@@ -1,10 +1,25 @@
-use actix_web::{http::StatusCode, HttpResponse, Query};
+use actix_web::{http::StatusCode, HttpResponse, Path};
 use auth::user::{User as AuthUser, User};
-use bigneon_db::models::{DomainAction, Report, Scopes};
+use bigneon_db::models::{
+    DomainAction, DomainActionTypes, DomainEventPublisher, Report, ResalePayout, Scopes, WebhookDelivery,
+};
 use bigneon_db::prelude::{DisplayOrder, Event, Order, Paging, PagingParameters, Payload};
 use db::Connection;
 use errors::*;
-use models::WebPayload;
+use extractors::*;
+use helpers::application;
+use models::{PathParameters, WebPayload};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct ReplayDomainEventsRequest {
+    pub from_seq: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UpdateDomainActionRequest {
+    pub payload: Value,
+}
 
 pub fn admin_ticket_count((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
@@ -22,6 +37,117 @@ pub fn admin_stuck_domain_actions((connection, user): (Connection, AuthUser)) ->
     Ok(HttpResponse::Ok().json(result))
 }
 
+/// Domain actions that exceeded their retries, optionally filtered by `domain_action_type`
+pub fn admin_dead_letter_domain_actions(
+    (connection, query, user): (Connection, Query<PagingParameters>, AuthUser),
+) -> Result<WebPayload<DomainAction>, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_action_type: Option<DomainActionTypes> = match query.get_tag_as_str("domain_action_type") {
+        Some(t) => Some(t.parse()?),
+        None => None,
+    };
+    let payload = DomainAction::find_dead_letters(domain_action_type, query.page(), query.limit(), connection)?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+pub fn admin_show_domain_action(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_action = DomainAction::find(path.id, connection)?;
+    Ok(HttpResponse::Ok().json(domain_action))
+}
+
+/// Edits the payload of a dead letter or stuck domain action
+pub fn admin_update_domain_action(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<UpdateDomainActionRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_action = DomainAction::find(path.id, connection)?;
+    let domain_action = domain_action.update_payload(json.into_inner().payload, connection)?;
+    Ok(HttpResponse::Ok().json(domain_action))
+}
+
+/// Resets the attempts of a dead letter or stuck domain action so it is processed again
+pub fn admin_requeue_domain_action(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_action = DomainAction::find(path.id, connection)?;
+    let domain_action = domain_action.requeue(connection)?;
+    Ok(HttpResponse::Ok().json(domain_action))
+}
+
+pub fn admin_cancel_domain_action(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_action = DomainAction::find(path.id, connection)?;
+    if !domain_action.is_dead_letter() && !domain_action.is_stuck() {
+        return application::unprocessable("Only dead letter or stuck domain actions can be cancelled");
+    }
+    let domain_action = domain_action.set_cancelled(connection)?;
+    Ok(HttpResponse::Ok().json(domain_action))
+}
+
+pub fn admin_webhook_deliveries(
+    (connection, path, query, user): (Connection, Path<PathParameters>, Query<PagingParameters>, AuthUser),
+) -> Result<WebPayload<WebhookDelivery>, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_event_publisher = DomainEventPublisher::find(path.id, connection)?;
+    let deliveries =
+        domain_event_publisher.webhook_deliveries(query.page() as i64, query.limit() as i64, connection)?;
+    Ok(WebPayload::new(StatusCode::OK, deliveries))
+}
+
+/// Publishes the domain events from `from_seq` onwards to the publisher again
+pub fn admin_replay_domain_events(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<ReplayDomainEventsRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let mut domain_event_publisher = DomainEventPublisher::find(path.id, connection)?;
+    domain_event_publisher.replay_from_seq(json.from_seq, connection)?;
+    Ok(HttpResponse::Ok().json(domain_event_publisher))
+}
+
+/// Resale payouts that are still owed to sellers, oldest first
+pub fn admin_pending_resale_payouts(
+    (connection, query, user): (Connection, Query<PagingParameters>, AuthUser),
+) -> Result<WebPayload<ResalePayout>, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let payload = ResalePayout::find_pending(query.page(), query.limit(), connection)?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+/// Records that the seller of a resold ticket has been paid their payout
+pub fn admin_mark_resale_payout_paid(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let resale_payout = ResalePayout::find(path.id, connection)?;
+    let resale_payout = resale_payout.mark_paid(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(resale_payout))
+}
+
 pub fn orders(
     (conn, query, user): (Connection, Query<PagingParameters>, User),
 ) -> Result<WebPayload<DisplayOrder>, BigNeonError> {
@@ -1,9 +1,10 @@
-use actix_web::{http::header, HttpRequest, HttpResponse, Query, State};
+use actix_web::{http::header, HttpRequest, HttpResponse, State};
 use bigneon_db::models::analytics::PageView;
 use bigneon_db::prelude::*;
 use chrono::prelude::*;
 use db::Connection;
 use errors::BigNeonError;
+use extractors::Query;
 use itertools::Itertools;
 use server::AppState;
 use url::Url;
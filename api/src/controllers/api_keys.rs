@@ -0,0 +1,81 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use std::str::FromStr;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct NewApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(ApiKey::find_for_organization(organization.id, connection)?))
+}
+
+pub fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewApiKeyRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.is_delegated() {
+        return application::forbidden("API keys cannot be managed using delegated credentials");
+    }
+
+    // Keys cannot be granted more access than their creator has in the organization
+    let user_scopes = organization.get_scopes_for_user(&user.user, connection)?;
+    let mut scopes = Vec::new();
+    for scope in &json.scopes {
+        let scope = Scopes::from_str(scope)?;
+        if !user_scopes.contains(&scope) {
+            return application::forbidden(&format!("Scope {} cannot be granted to this API key", scope));
+        }
+        scopes.push(scope);
+    }
+
+    let (api_key, key) = ApiKey::create(organization.id, user.id(), json.name.clone(), scopes, json.expires_at)
+        .commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(json!({ "api_key": api_key, "key": key })))
+}
+
+pub fn rotate(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let api_key = ApiKey::find(path.id, connection)?;
+    let organization = Organization::find(api_key.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.is_delegated() {
+        return application::forbidden("API keys cannot be managed using delegated credentials");
+    }
+
+    let (api_key, key) = api_key.rotate(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({ "api_key": api_key, "key": key })))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let api_key = ApiKey::find(path.id, connection)?;
+    let organization = Organization::find(api_key.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.is_delegated() {
+        return application::forbidden("API keys cannot be managed using delegated credentials");
+    }
+
+    Ok(HttpResponse::Ok().json(api_key.revoke(Some(user.id()), connection)?))
+}
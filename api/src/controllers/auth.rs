@@ -1,7 +1,11 @@
 use actix_web::{HttpRequest, HttpResponse, State};
-use auth::{claims::RefreshToken, TokenResponse};
-use bigneon_db::models::{deserialize_unless_blank, User};
+use auth::{claims::RefreshToken, LoginResponse, TokenResponse, TwoFactorChallengeResponse};
+use bigneon_db::models::{
+    deserialize_unless_blank, OAuthConsent, TwoFactorChallenge, TwoFactorCredential, User, UserSession,
+};
+use bigneon_db::utils::errors::Optional;
 use db::Connection;
+use diesel::PgConnection;
 use errors::*;
 use extractors::*;
 use helpers::application;
@@ -26,6 +30,17 @@ pub struct RefreshRequest {
     refresh_token: String,
 }
 
+/// Completes sign in for users with two-factor authentication using either a code from their authenticator
+/// app or one of their recovery codes
+#[derive(Deserialize)]
+pub struct TwoFactorTokenRequest {
+    pub challenge_token: String,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub code: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub recovery_code: Option<String>,
+}
+
 impl LoginRequest {
     pub fn new(email: &str, password: &str) -> Self {
         LoginRequest {
@@ -51,7 +66,7 @@ pub fn token(
         Json<LoginRequest>,
         RequestInfo,
     ),
-) -> Result<TokenResponse, BigNeonError> {
+) -> Result<LoginResponse, BigNeonError> {
     let state = http_request.state();
     let connection_info = http_request.connection_info();
     let remote_ip = connection_info.remote();
@@ -90,17 +105,62 @@ pub fn token(
         return application::unauthorized_with_message(login_failure_messaging, None, Some(login_log_data));
     }
 
-    user.login_domain_event(json!(request_info), connection.get())?;
-    jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone()});
-    let response = TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &state.config.jwt_expiry_time,
-        &user,
-    )?;
+    let response = create_login_response(&http_request, &user, connection.get())?;
+    match response {
+        LoginResponse::Token(_) => {
+            user.login_domain_event(json!(request_info), connection.get())?;
+            jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone()});
+        }
+        LoginResponse::TwoFactorChallenge(_) => {
+            jlog!(Info, "User issued two-factor challenge", {"id": user.id, "email": user.email.clone()});
+        }
+    }
     Ok(response)
 }
 
+pub fn two_factor_token(
+    (http_request, connection, two_factor_request, request_info): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<TwoFactorTokenRequest>,
+        RequestInfo,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let state = http_request.state();
+    let connection = connection.get();
+    let challenge = match TwoFactorChallenge::find_active_by_token(&two_factor_request.challenge_token, connection)
+        .optional()?
+    {
+        Some(challenge) => challenge,
+        None => {
+            return application::unauthorized_with_message("Two-factor challenge is invalid or has expired", None, None)
+        }
+    };
+    let user = User::find(challenge.user_id, connection)?;
+    if TwoFactorChallenge::is_locked_out(user.id, connection)? {
+        jlog!(Info, "User locked out of two-factor authentication", {"id": user.id, "email": user.email.clone()});
+        return Ok(HttpResponse::TooManyRequests()
+            .json(json!({"error": "Too many failed two-factor attempts, please try again later"})));
+    }
+
+    let verified = challenge.verify(
+        two_factor_request.code.as_ref().map(|c| c.as_str()),
+        two_factor_request.recovery_code.as_ref().map(|c| c.as_str()),
+        &state.config.api_keys_encryption_key,
+        connection,
+    )?;
+    if !verified {
+        jlog!(Info, "User failed two-factor authentication", {"id": user.id, "email": user.email.clone()});
+        // Returned as a response rather than an error so the failed attempt is not rolled back
+        return Ok(HttpResponse::Unauthorized().json(json!({"error": "Two-factor code is invalid"})));
+    }
+
+    user.login_domain_event(json!(request_info), connection)?;
+    jlog!(Info, "User logged in via two-factor authentication", {"id": user.id, "email": user.email.clone()});
+    let response = create_session_tokens(&http_request, &user, true, connection)?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
 pub fn token_refresh(
     (state, connection, refresh_request): (State<AppState>, Connection, Json<RefreshRequest>),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -119,14 +179,89 @@ pub fn token_refresh(
         return application::unauthorized_with_message("Invalid token", None, None);
     }
 
+    // Tokens issued to OAuth clients stop refreshing once the user's consent is revoked
+    if let Some(oauth_consent_id) = token.claims.oauth_consent_id {
+        let consent = OAuthConsent::find(oauth_consent_id, connection.get())?;
+        if !consent.is_active() || consent.user_id != user.id {
+            return application::unauthorized_with_message("Invalid token", None, None);
+        }
+    }
+
+    match token.claims.session_id {
+        Some(session_id) => {
+            // Revoked sessions can no longer be refreshed
+            let session = UserSession::find(session_id, connection.get())?;
+            if !session.is_active() || session.user_id != user.id {
+                return application::unauthorized_with_message("Invalid token", None, None);
+            }
+            session.mark_used(connection.get())?;
+        }
+        None if token.claims.oauth_consent_id.is_none() => {
+            // Tokens issued before sessions were recorded cannot be revoked so the user has to sign in again
+            return application::unauthorized_with_message("Invalid token", None, None);
+        }
+        None => {}
+    }
+
     let response = TokenResponse::create_from_refresh_token(
         &state.config.token_secret,
         &state.config.token_issuer,
         &state.config.jwt_expiry_time,
-        &user.id,
+        &token.claims,
         &refresh_request.refresh_token,
     )?;
     jlog!(Info, "User refreshed token", {"id": user.id, "email": user.email.clone()});
 
     Ok(HttpResponse::Ok().json(response))
 }
+
+/// Issues tokens for the user, or a two-factor challenge if the user has two-factor authentication enabled
+pub fn create_login_response(
+    http_request: &HttpRequest<AppState>,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<LoginResponse, BigNeonError> {
+    if TwoFactorCredential::find_enabled_by_user_id(user.id, connection)
+        .optional()?
+        .is_some()
+    {
+        let (challenge, challenge_token) = TwoFactorChallenge::create(user.id).commit(connection)?;
+        return Ok(LoginResponse::TwoFactorChallenge(TwoFactorChallengeResponse {
+            two_factor_required: true,
+            challenge_token,
+            expires_at: challenge.expires_at,
+        }));
+    }
+
+    Ok(LoginResponse::Token(create_session_tokens(
+        http_request,
+        user,
+        false,
+        connection,
+    )?))
+}
+
+/// Records a session for the device the user signed in from and issues tokens bound to it
+fn create_session_tokens(
+    http_request: &HttpRequest<AppState>,
+    user: &User,
+    two_factor: bool,
+    connection: &PgConnection,
+) -> Result<TokenResponse, BigNeonError> {
+    let state = http_request.state();
+    let ip_address = http_request.connection_info().remote().map(|i| i.to_string());
+    let user_agent = http_request
+        .headers()
+        .get("User-Agent")
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.to_string());
+    let session = UserSession::create(user.id, ip_address, user_agent).commit(connection)?;
+
+    Ok(TokenResponse::create_from_user_session(
+        &state.config.token_secret,
+        &state.config.token_issuer,
+        &state.config.jwt_expiry_time,
+        &session,
+        two_factor,
+    )?)
+}
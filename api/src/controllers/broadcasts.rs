@@ -1,5 +1,5 @@
+use actix_web::HttpResponse;
 use actix_web::Path;
-use actix_web::{HttpResponse, Query};
 use auth::user::User;
 use bigneon_db::models::enums::{BroadcastAudience, BroadcastChannel, BroadcastType};
 use bigneon_db::models::scopes::Scopes;
@@ -7,7 +7,7 @@ use bigneon_db::models::{Broadcast, BroadcastEditableAttributes, Organization, P
 use chrono::NaiveDateTime;
 use db::Connection;
 use errors::BigNeonError;
-use extractors::Json;
+use extractors::{Json, Query};
 use models::{PathParameters, WebPayload};
 use reqwest::StatusCode;
 use uuid::Uuid;
@@ -33,6 +33,8 @@ pub struct CartItem {
     pub quantity: u32,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub redemption_code: Option<String>,
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -70,6 +72,7 @@ pub fn update_cart(
             quantity: i.quantity,
             ticket_type_id: i.ticket_type_id,
             redemption_code: i.redemption_code.clone(),
+            seat_ids: i.seat_ids.clone(),
         })
         .collect();
 
@@ -126,6 +129,7 @@ pub fn replace_cart(
             quantity: i.quantity,
             ticket_type_id: i.ticket_type_id,
             redemption_code: i.redemption_code.clone(),
+            seat_ids: i.seat_ids.clone(),
         })
         .collect();
 
@@ -257,6 +261,13 @@ pub fn checkout(
         }
     }
 
+    // Providers expect lower case ISO codes, e.g. usd
+    let currency = order
+        .currency
+        .clone()
+        .unwrap_or_else(|| state.config.primary_currency.clone())
+        .to_lowercase();
+
     let payment_response = match &req.method {
         PaymentRequest::Free => {
             info!("CART: Received checkout for free cart");
@@ -305,7 +316,7 @@ pub fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
+                &currency,
                 provider.clone(),
                 true,
                 false,
@@ -320,7 +331,7 @@ pub fn checkout(
             &mut order,
             None,
             &user,
-            &state.config.primary_currency,
+            &currency,
             *provider,
             false,
             false,
@@ -339,7 +350,7 @@ pub fn checkout(
             &mut order,
             Some(&token),
             &user,
-            &state.config.primary_currency,
+            &currency,
             *provider,
             false,
             *save_payment_method,
@@ -473,19 +484,22 @@ fn checkout_payment_processor(
         return application::unprocessable("Could not complete this cart; only paid orders require payment processing");
     }
 
-    // Can only have one event at a time because there are potentially different
-    // payment gateway settings
-    let mut events = order.events(connection)?;
-    if events.len() != 1 {
-        return application::unprocessable("Can't currently handle more than one event at the moment");
+    let mut organizations = order.organizations(connection)?;
+    if organizations.is_empty() {
+        return application::unprocessable("Could not complete this cart because it contains no events");
+    } else if organizations.len() > 1 && !provider.supports_multiple_organizations() {
+        return application::unprocessable(&format!(
+            "Could not complete this cart; events from multiple organizations cannot be paid using {}",
+            provider
+        ));
     };
 
-    let event = events.remove(0);
+    let organization = organizations.remove(0);
 
-    let client = service_locator.create_payment_processor(provider, &event.organization(connection)?)?;
+    let client = service_locator.create_payment_processor(provider, &organization)?;
     match client.behavior() {
         PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
-            return redirect_to_payment_page(&*behavior, &auth_user.user, order, conn.get(), config);
+            return redirect_to_payment_page(&*behavior, &auth_user.user, order, currency, conn.get(), config);
         }
         PaymentProcessorBehavior::AuthThenComplete(behavior) => {
             let token = if use_stored_payment {
@@ -585,6 +599,26 @@ fn auth_then_complete(
         order.purchase_metadata(connection)?,
     )?;
 
+    if let Some(ref required_action) = auth_result.required_action {
+        // The customer has to confirm the payment (e.g. 3-D Secure), the provider's webhook completes it
+        info!("CART: Payment requires confirmation by the customer");
+        let payment = order.add_credit_card_payment(
+            auth_user.id(),
+            amount,
+            client.payment_provider(),
+            auth_result.id.clone(),
+            PaymentStatus::PendingConfirmation,
+            auth_result.to_json()?,
+            connection,
+        )?;
+        payment.mark_pending_confirmation(Some(auth_user.id()), connection)?;
+        let mut order = Order::find(order.id, connection)?;
+        order.set_browser_data(request_info.user_agent.clone(), true, connection)?;
+        let mut response = json!(order.for_display(None, auth_user.id(), connection)?);
+        response["required_action"] = json!(required_action);
+        return Ok(HttpResponse::Ok().json(response));
+    }
+
     info!("CART: Saving payment to order");
     let payment = match order.add_credit_card_payment(
         auth_user.id(),
@@ -626,6 +660,7 @@ fn redirect_to_payment_page(
     client: &dyn RedirectToPaymentPageBehavior,
     user: &DbUser,
     order: &mut Order,
+    currency: &str,
     conn: &PgConnection,
     config: &Config,
 ) -> Result<HttpResponse, BigNeonError> {
@@ -637,11 +672,17 @@ fn redirect_to_payment_page(
 
     let email = user.email.as_ref().unwrap().to_string();
 
-    let ipn = Some(format!("{}/ipns/globee", config.api_base_url));
+    // IPN routes and external references are namespaced by provider
+    let provider_path = match client.payment_provider() {
+        PaymentProviders::HostedCheckout => "hosted_checkout",
+        _ => "globee",
+    };
+    let ipn = Some(format!("{}/ipns/{}", config.api_base_url, provider_path));
 
     let nonce = random_alpha_string(12);
     let response = client.create_payment_request(
         amount as f64 / 100_f64,
+        currency,
         email,
         order.id,
         ipn,
@@ -659,7 +700,7 @@ fn redirect_to_payment_page(
 
     order.add_checkout_url(user.id, response.redirect_url.clone(), response.expires_at, conn)?;
 
-    let external_reference = format!("globee-{}", response.id);
+    let external_reference = format!("{}-{}", provider_path, response.id);
 
     order.add_provider_payment(
         Some(external_reference),
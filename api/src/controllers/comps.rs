@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, HttpResponse, Path};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
@@ -7,6 +7,7 @@ use db::Connection;
 use errors::BigNeonError;
 use extractors::*;
 use models::{PathParameters, WebPayload, WebResult};
+use uuid::Uuid;
 
 pub fn index(
     (conn, path, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
@@ -57,6 +58,9 @@ pub struct NewCompRequest {
     pub redemption_code: String,
     pub end_at: Option<NaiveDateTime>,
     pub max_per_user: Option<u32>,
+    /// Specific seats to comp in addition to `quantity`
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
 }
 
 pub fn create(
@@ -76,6 +80,7 @@ pub fn create(
         new_comp.end_at,
         new_comp.max_per_user,
         new_comp.quantity,
+        new_comp.seat_ids,
         conn,
     )?;
 
@@ -91,10 +96,14 @@ pub fn update(
     user.requires_scope_for_organization(Scopes::CompWrite, &comp.organization(conn)?, conn)?;
     let req = req.into_inner();
     let quantity = req.quantity;
+    let seat_ids = req.seat_ids.clone();
     let hold = comp.update(req.into(), conn)?;
     if quantity.is_some() {
         hold.set_quantity(Some(user.id()), quantity.unwrap(), conn)?;
     }
+    if let Some(seat_ids) = seat_ids {
+        hold.add_seats(Some(user.id()), seat_ids, conn)?;
+    }
 
     let comp = hold.into_display(conn)?;
     Ok(HttpResponse::Ok().json(comp))
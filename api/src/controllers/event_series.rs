@@ -0,0 +1,61 @@
+use actix_web::{http::StatusCode, HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::BigNeonError;
+use extractors::*;
+use helpers::application;
+use models::{PathParameters, WebResult};
+
+#[derive(Deserialize, Serialize)]
+pub struct NewEventSeriesRequest {
+    pub frequency: RecurrenceFrequencies,
+    pub frequency_interval: Option<i32>,
+    pub occurrences: i32,
+}
+
+pub fn show((conn, path, user): (Connection, Path<PathParameters>, User)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let event_series = EventSeries::find(path.id, conn)?;
+    user.requires_scope_for_organization(Scopes::OrgReadEvents, &event_series.organization(conn)?, conn)?;
+
+    Ok(HttpResponse::Ok().json(event_series.for_display(conn)?))
+}
+
+pub fn create(
+    (conn, path, series_request, user): (Connection, Path<PathParameters>, Json<NewEventSeriesRequest>, User),
+) -> Result<WebResult<DisplayEventSeries>, BigNeonError> {
+    let conn = conn.get();
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(Scopes::EventWrite, &event.organization(conn)?, &event, conn)?;
+
+    let event_series = EventSeries::create(
+        &event,
+        series_request.frequency,
+        series_request.frequency_interval.unwrap_or(1),
+        series_request.occurrences,
+        user.id(),
+    )
+    .commit(conn)?;
+
+    Ok(WebResult::new(StatusCode::CREATED, event_series.for_display(conn)?))
+}
+
+/// Updates the event and the occurrences that follow it in its series, copying its ticket types and pricing to them
+pub fn update_following(
+    (conn, path, attributes, user): (Connection, Path<PathParameters>, Json<EventEditableAttributes>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let event = Event::find(path.id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::EventWrite, &organization, &event, conn)?;
+    user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, &organization, &event, conn)?;
+
+    let event_series = match event.event_series_id {
+        Some(event_series_id) => EventSeries::find(event_series_id, conn)?,
+        None => return application::unprocessable("Event does not belong to a series"),
+    };
+    let events = event_series.update_following(&event, attributes.into_inner(), Some(user.id()), conn)?;
+
+    Ok(HttpResponse::Ok().json(events))
+}
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
 use auth::user::User as AuthUser;
 use bigneon_db::dev::times;
 use bigneon_db::prelude::*;
@@ -45,6 +45,9 @@ pub struct SearchParameters {
     updated_at: Option<String>,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     category: Option<EventTypes>,
+    /// Export format, exports include up to `MAXIMUM_EXPORT_ROWS` rows instead of a single page
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    format: Option<String>,
 }
 
 impl From<SearchParameters> for Paging {
@@ -69,6 +72,9 @@ impl From<SearchParameters> for Paging {
         if let Some(ref i) = s.end_utc {
             default_tags.insert("end_utc".to_owned(), json!(i));
         }
+        if let Some(ref i) = s.format {
+            default_tags.insert("format".to_owned(), json!(i));
+        }
 
         PagingParameters {
             page: s.page,
@@ -571,6 +577,12 @@ pub fn unpublish(
     Ok(HttpResponse::Ok().finish())
 }
 
+pub fn seats((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection.get();
+    let event = Event::find(path.id, conn)?;
+    Ok(HttpResponse::Ok().json(Seat::find_for_event(event.id, conn)?))
+}
+
 pub fn ticket_holder_count(
     (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -597,6 +609,11 @@ pub fn redeem_ticket(
     ),
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
+    // Season pass entitlements are scanned like tickets at each event covered by the pass
+    if let Some(entitlement) = SeasonPassEntitlement::find(parameters.ticket_instance_id, connection).optional()? {
+        return redeem_season_pass_entitlement(&entitlement, parameters.id, &redeem_parameters, &auth_user, connection);
+    }
+
     let ticket = TicketInstance::find_for_processing(parameters.ticket_instance_id, parameters.id, connection)?;
     let db_event = Event::find(ticket.event_id, connection)?;
     let organization = db_event.organization(connection)?;
@@ -635,6 +652,9 @@ pub fn redeem_ticket(
             Ok(HttpResponse::BadRequest()
                 .json(json!({"error": "Ticket has pending transfer in progress.".to_string()})))
         }
+        RedeemResults::TicketListedForResale => {
+            Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket is listed for resale.".to_string()})))
+        }
         RedeemResults::TicketAlreadyRedeemed => Ok(HttpResponse::Conflict().json(json!({
         "error": "Ticket has already been redeemed.".to_string(),
         "redeemed_by": redeemable.redeemed_by,
@@ -646,6 +666,97 @@ pub fn redeem_ticket(
     }
 }
 
+fn redeem_season_pass_entitlement(
+    entitlement: &SeasonPassEntitlement,
+    event_id: Uuid,
+    redeem_parameters: &TicketRedeemRequest,
+    auth_user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<HttpResponse, BigNeonError> {
+    let db_event = Event::find(event_id, connection)?;
+    let organization = db_event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &db_event, connection)?;
+
+    let result = SeasonPassEntitlement::redeem(
+        entitlement.id,
+        db_event.id,
+        redeem_parameters.redeem_key.clone(),
+        auth_user.id(),
+        redeem_parameters.check_in_source.unwrap_or(CheckInSource::GuestList),
+        connection,
+    )?;
+
+    match result {
+        RedeemResults::TicketRedeemSuccess => Ok(HttpResponse::Ok().json(entitlement.for_display(connection)?)),
+        RedeemResults::TicketAlreadyRedeemed => Ok(HttpResponse::Conflict()
+            .json(json!({"error": "Season pass has already been redeemed for this event.".to_string()}))),
+        _ => {
+            Ok(HttpResponse::BadRequest().json(json!({"error": "Season pass is invalid for this event.".to_string()})))
+        }
+    }
+}
+
+pub fn redemption_bundle(
+    (connection, path, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    Ok(HttpResponse::Ok().json(OfflineRedemptionBundle::create_for_event(&event, connection)?))
+}
+
+pub fn redemption_key(
+    (connection, path, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    Ok(HttpResponse::Ok().json(OfflineRedemptionKey::for_event(&event, connection)?))
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct SyncRedemptionsRequest {
+    pub redemptions: Vec<OfflineRedemption>,
+}
+
+pub fn sync_redemptions(
+    (connection, path, json, auth_user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<SyncRedemptionsRequest>,
+        AuthUser,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    let results = OfflineRedemption::sync(event.id, json.into_inner().redemptions, auth_user.id(), connection)?;
+
+    //Redeem tickets on chain
+    for result in results.iter().filter(|r| r.status == OfflineRedemptionStatus::Redeemed) {
+        let ticket = TicketInstance::find(result.ticket_instance_id, connection)?;
+        let asset = Asset::find(ticket.asset_id, connection)?;
+        if let Some(blockchain_asset_id) = asset.blockchain_asset_id {
+            let wallet = Wallet::find(ticket.wallet_id, connection)?;
+            state.config.tari_client.modify_asset_redeem_token(
+                &wallet.secret_key,
+                &wallet.public_key,
+                &blockchain_asset_id,
+                vec![ticket.token_id as u64],
+            )?;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 pub fn show_from_organizations(
     (connection, path, paging, user): (Connection, Path<PathParameters>, Query<PagingParameters>, AuthUser),
 ) -> Result<WebPayload<EventSummaryResult>, BigNeonError> {
@@ -789,6 +900,38 @@ pub fn create(
     Ok(HttpResponse::Created().json(event))
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct DuplicateEventRequest {
+    pub event_start: NaiveDateTime,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+}
+
+pub fn duplicate(
+    (connection, parameters, duplicate_request, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<DuplicateEventRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::EventWrite, &organization, &event, connection)?;
+
+    let duplicate_request = duplicate_request.into_inner();
+    let new_event = event.duplicate(
+        duplicate_request.event_start,
+        duplicate_request.name,
+        user.id(),
+        connection,
+    )?;
+
+    create_domain_action_event(new_event.id, connection);
+    Ok(HttpResponse::Created().json(new_event))
+}
+
 pub fn update(
     (connection, parameters, event_parameters, user): (
         Connection,
@@ -982,6 +1125,9 @@ pub struct GuestListQueryParameters {
     pub query: Option<String>,
     pub page: Option<u32>,
     pub limit: Option<i32>,
+    /// Export format, exports include up to `MAXIMUM_EXPORT_ROWS` rows instead of a single page
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub format: Option<String>,
 }
 
 impl From<GuestListQueryParameters> for Paging {
@@ -989,6 +1135,9 @@ impl From<GuestListQueryParameters> for Paging {
         let mut default_tags: HashMap<String, Value> = HashMap::new();
         default_tags.insert("query".to_owned(), json!(s.query.clone()));
         default_tags.insert("changes_since".to_owned(), json!(s.changes_since.clone()));
+        if let Some(ref format) = s.format {
+            default_tags.insert("format".to_owned(), json!(format));
+        }
 
         //TODO Replace u32::MAX with our default of 100
         let limit: u32 = match s.limit {
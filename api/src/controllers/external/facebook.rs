@@ -1,8 +1,8 @@
-use actix_web::{HttpResponse, State};
+use actix_web::{HttpRequest, HttpResponse, State};
 use auth::user::User as AuthUser;
-use auth::TokenResponse;
 use bigneon_db::prelude::*;
 use bigneon_db::validators::{append_validation_error, create_validation_error};
+use controllers::auth;
 use db::Connection;
 use errors::*;
 use extractors::*;
@@ -30,8 +30,8 @@ struct FacebookGraphResponse {
 
 // TODO: Not covered by tests
 pub fn web_login(
-    (state, connection, auth_token, auth_user): (
-        State<AppState>,
+    (http_request, connection, auth_token, auth_user): (
+        HttpRequest<AppState>,
         Connection,
         Json<FacebookWebLoginToken>,
         OptionalUser,
@@ -65,12 +65,7 @@ pub fn web_login(
             vec![],
             connection,
         )?;
-        let response = TokenResponse::create_from_user(
-            &state.config.token_secret,
-            &state.config.token_issuer,
-            &state.config.jwt_expiry_time,
-            &auth_user.user,
-        )?;
+        let response = auth::create_login_response(&http_request, &auth_user.user, connection)?;
         return Ok(HttpResponse::Ok().json(response));
     }
 
@@ -125,12 +120,7 @@ pub fn web_login(
             }
         }
     };
-    let response = TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &state.config.jwt_expiry_time,
-        &user,
-    )?;
+    let response = auth::create_login_response(&http_request, &user, connection)?;
     Ok(HttpResponse::Ok().json(response))
 }
 
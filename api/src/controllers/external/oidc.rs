@@ -0,0 +1,249 @@
+use actix_web::{HttpRequest, HttpResponse, Path};
+use bigneon_db::prelude::*;
+use config::{Config, OidcProviderSettings};
+use controllers::auth;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use jwt::{decode, encode, Header, Validation};
+use log::Level::Info;
+use models::StringPathParameters;
+use oidc::{IdTokenClaims, OidcClient};
+use server::AppState;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+const OIDC_SCOPES: [&str; 3] = ["openid", "email", "profile"];
+/// The user must finish signing in at the provider within this time of starting
+const NONCE_EXPIRY_SECONDS: u64 = 600;
+
+/// Signed nonce issued by `start`, ties the ID token to a sign in started with this API for the provider
+#[derive(Deserialize, Serialize)]
+struct NonceClaims {
+    iss: String,
+    /// Name of the provider
+    sub: String,
+    jti: Uuid,
+    exp: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OidcStartResponse {
+    /// Nonce to send with the authentication request to the provider and then with the login request
+    pub nonce: String,
+    pub client_id: String,
+    pub scope: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct OidcLoginRequest {
+    /// ID token obtained by the client, e.g. from the Google or Apple sign in SDKs
+    pub id_token: Option<String>,
+    /// Authorization code returned to the client's redirect URI, exchanged by the API for an ID token
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    /// Nonce issued by the start endpoint and sent with the authentication request, must match the ID token
+    pub nonce: Option<String>,
+    #[serde(default)]
+    pub link_to_user_id: bool,
+}
+
+/// Starts signing in with one of the configured OpenID Connect providers, issuing the nonce the client
+/// sends with its authentication request
+pub fn start(
+    (http_request, path): (HttpRequest<AppState>, Path<StringPathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let config = &http_request.state().config;
+    let provider = match find_provider(config, &path.id) {
+        Some(provider) => provider,
+        None => return application::not_found(),
+    };
+
+    let claims = NonceClaims {
+        iss: config.token_issuer.clone(),
+        sub: provider.name.clone(),
+        jti: Uuid::new_v4(),
+        exp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + NONCE_EXPIRY_SECONDS,
+    };
+    Ok(HttpResponse::Ok().json(OidcStartResponse {
+        nonce: encode(&Header::default(), &claims, config.token_secret.as_bytes())?,
+        client_id: provider.client_id,
+        scope: OIDC_SCOPES.join(" "),
+    }))
+}
+
+/// Signs in using an ID token issued by one of the configured OpenID Connect providers. Users are
+/// matched by their previous logins with the provider, then by verified email address, and otherwise
+/// registered. Providers configured for an organization only sign in existing members of it, who
+/// link the login to their account after signing in with `link_to_user_id`.
+pub fn login(
+    (http_request, connection, path, json, auth_user): (
+        HttpRequest<AppState>,
+        Connection,
+        Path<StringPathParameters>,
+        Json<OidcLoginRequest>,
+        OptionalUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let provider = match find_provider(&http_request.state().config, &path.id) {
+        Some(provider) => provider,
+        None => return application::not_found(),
+    };
+
+    let (claims, access_token) = validate_login(&http_request.state().config, &provider, &json)?;
+    let site = provider.issuer.clone();
+    let scopes: Vec<String> = OIDC_SCOPES.iter().map(|s| s.to_string()).collect();
+
+    if json.link_to_user_id {
+        let auth_user = match auth_user.into_inner() {
+            Some(auth_user) => auth_user,
+            None => {
+                return application::unauthorized_with_message("User must be logged in to link this login", None, None)
+            }
+        };
+        if let Some(ref organization_id) = provider.organization_id {
+            check_organization_member(*organization_id, &auth_user.user, connection)?;
+        }
+        auth_user.user.add_or_replace_external_login(
+            Some(auth_user.id()),
+            claims.sub.clone(),
+            site,
+            access_token,
+            scopes,
+            connection,
+        )?;
+        let response = auth::create_login_response(&http_request, &auth_user.user, connection)?;
+        return Ok(HttpResponse::Ok().json(response));
+    }
+
+    let user = match ExternalLogin::find_user(&claims.sub, &site, connection)? {
+        Some(external_login) => User::find(external_login.user_id, connection)?,
+        None => {
+            // The organization's provider vouching for an email address does not prove the user owns
+            // the account, members sign in to the account first and then link the login to it
+            if provider.organization_id.is_some() {
+                return application::unauthorized_with_message(
+                    "Sign in to link this login to your account",
+                    None,
+                    None,
+                );
+            }
+            // Only verified addresses are trusted to link to an existing account
+            let existing_user = match claims.verified_email() {
+                Some(email) => User::find_by_email(&email, connection).optional()?,
+                None => None,
+            };
+            match existing_user {
+                Some(user) => {
+                    user.add_external_login(None, claims.sub.clone(), site, access_token, scopes, connection)?;
+                    user
+                }
+                None => User::create_from_external_login(
+                    claims.sub.clone(),
+                    claims.given_name.clone().unwrap_or("".to_string()),
+                    claims.family_name.clone().unwrap_or("".to_string()),
+                    claims.verified_email(),
+                    site,
+                    access_token,
+                    scopes,
+                    None,
+                    connection,
+                )?,
+            }
+        }
+    };
+
+    if let Some(ref organization_id) = provider.organization_id {
+        check_organization_member(*organization_id, &user, connection)?;
+    }
+
+    jlog!(Info, "User signed in with OpenID Connect", { "user_id": user.id, "provider": &provider.name });
+    let response = auth::create_login_response(&http_request, &user, connection)?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn find_provider(config: &Config, name: &str) -> Option<OidcProviderSettings> {
+    config.oidc_providers.iter().find(|p| p.name == name).cloned()
+}
+
+/// Validates the ID token sent by the client or obtained by exchanging the authorization code,
+/// returning its claims along with the provider access token when one was issued
+fn validate_login(
+    config: &Config,
+    provider: &OidcProviderSettings,
+    request: &OidcLoginRequest,
+) -> Result<(IdTokenClaims, String), BigNeonError> {
+    let nonce = match request.nonce {
+        Some(ref nonce) => nonce.as_str(),
+        None => {
+            return Err(ApplicationError::new_with_type(
+                ApplicationErrorType::Unprocessable,
+                "A nonce issued by the start endpoint is required".to_string(),
+            )
+            .into())
+        }
+    };
+    validate_nonce(config, provider, nonce)?;
+    let client = OidcClient::new(
+        provider.issuer.clone(),
+        provider.client_id.clone(),
+        provider.client_secret.clone(),
+    );
+    let nonce = Some(nonce);
+
+    if let Some(ref id_token) = request.id_token {
+        let metadata = client.discover()?;
+        return Ok((client.validate_id_token(&metadata, id_token, nonce)?, "".to_string()));
+    }
+
+    match (&request.code, &request.redirect_uri) {
+        (Some(code), Some(redirect_uri)) => {
+            let metadata = client.discover()?;
+            let token_response = client.exchange_code(
+                &metadata,
+                code,
+                redirect_uri,
+                request.code_verifier.as_ref().map(|v| v.as_str()),
+            )?;
+            let claims = client.validate_id_token(&metadata, &token_response.id_token, nonce)?;
+            Ok((claims, token_response.access_token))
+        }
+        _ => Err(ApplicationError::new_with_type(
+            ApplicationErrorType::Unprocessable,
+            "An ID token or an authorization code and redirect URI are required".to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Checks the nonce was issued by `start` for this provider and has not expired
+fn validate_nonce(config: &Config, provider: &OidcProviderSettings, nonce: &str) -> Result<(), BigNeonError> {
+    let valid = match decode::<NonceClaims>(nonce, config.token_secret.as_bytes(), &Validation::default()) {
+        Ok(token) => token.claims.iss == config.token_issuer && token.claims.sub == provider.name,
+        Err(_) => false,
+    };
+    if !valid {
+        return Err(AuthError::new(
+            AuthErrorType::Unauthorized,
+            "Nonce is invalid or has expired".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn check_organization_member(
+    organization_id: Uuid,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let organization = Organization::find(organization_id, connection)?;
+    if !organization.is_member(user, connection)? {
+        return application::forbidden("User is not a member of this organization");
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
@@ -24,6 +24,9 @@ pub struct CreateHoldRequest {
     pub ticket_type_id: Uuid,
     pub end_at: Option<NaiveDateTime>,
     pub max_per_user: Option<u32>,
+    /// Specific seats to hold in addition to `quantity`
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Default, Deserialize, Serialize)]
@@ -43,6 +46,9 @@ pub struct UpdateHoldRequest {
     pub end_at: Option<Option<NaiveDateTime>>,
     #[serde(default, deserialize_with = "double_option::deserialize")]
     pub max_per_user: Option<Option<i64>>,
+    /// Specific seats to add to the hold in addition to `quantity`
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
 }
 
 impl From<UpdateHoldRequest> for UpdateHoldAttributes {
@@ -82,6 +88,9 @@ pub fn create(
     .commit(Some(user.id()), conn)?;
 
     hold.set_quantity(Some(user.id()), req.quantity, conn)?;
+    if let Some(ref seat_ids) = req.seat_ids {
+        hold.add_seats(Some(user.id()), seat_ids.clone(), conn)?;
+    }
 
     #[derive(Serialize)]
     struct R {
@@ -125,10 +134,14 @@ pub fn update(
     let hold = Hold::find(path.id, conn)?;
     user.requires_scope_for_organization_event(Scopes::HoldWrite, &hold.organization(conn)?, &hold.event(conn)?, conn)?;
     let quantity = req.quantity;
+    let seat_ids = req.seat_ids.clone();
     let hold = hold.update(req.into_inner().into(), conn)?;
     if let Some(quantity) = quantity {
         hold.set_quantity(Some(user.id()), quantity, conn)?;
     }
+    if let Some(seat_ids) = seat_ids {
+        hold.add_seats(Some(user.id()), seat_ids, conn)?;
+    }
 
     Ok(HttpResponse::Ok().json(hold))
 }
@@ -1,11 +1,19 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpRequest, HttpResponse};
 use bigneon_db::prelude::*;
 use bigneon_db::utils::dates::IntoDateBuilder;
 use db::Connection;
+use domain_events::executors::ProviderIPN;
 use errors::BigNeonError;
 use extractors::Json;
 use globee::GlobeeIpnRequest;
+use helpers::application;
+use hosted_checkout;
+use hosted_checkout::Checkout;
 use log::Level::Debug;
+use serde_json;
+use server::AppState;
+use stripe;
+use stripe::WebhookEvent;
 use uuid::Uuid;
 
 pub fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Result<HttpResponse, BigNeonError> {
@@ -33,3 +41,73 @@ pub fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Result<Http
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub fn hosted_checkout(
+    (request, body, conn): (HttpRequest<AppState>, String, Connection),
+) -> Result<HttpResponse, BigNeonError> {
+    let settings = match request.state().config.hosted_checkout {
+        Some(ref settings) => settings,
+        None => return application::not_found(),
+    };
+    // The signature covers the raw body so it must be checked before deserializing
+    let is_valid = match request.headers().get(hosted_checkout::SIGNATURE_HEADER) {
+        Some(signature) => hosted_checkout::verify(&settings.secret, &body, signature.to_str()?),
+        None => false,
+    };
+    if !is_valid {
+        return application::forbidden("IPN signature is invalid");
+    }
+
+    let data: Checkout = serde_json::from_str(&body)?;
+    jlog!(Debug, "Hosted checkout IPN received", { "data": &data });
+    let order_id = data.reference.parse::<Uuid>()?;
+    let mut action = DomainAction::create(
+        None,
+        DomainActionTypes::HostedCheckoutIPN,
+        None,
+        json!(data),
+        Some(Tables::Orders),
+        Some(order_id),
+    );
+    action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+    action.max_attempt_count = 5;
+    action.commit(conn.get())?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn stripe(
+    (request, body, conn): (HttpRequest<AppState>, String, Connection),
+) -> Result<HttpResponse, BigNeonError> {
+    let secret = match request.state().config.stripe_webhook_secret {
+        Some(ref secret) => secret,
+        None => return application::not_found(),
+    };
+    let is_valid = match request.headers().get(stripe::SIGNATURE_HEADER) {
+        Some(signature) => stripe::verify_signature(secret, signature.to_str()?, &body),
+        None => false,
+    };
+    if !is_valid {
+        return application::forbidden("Webhook signature is invalid");
+    }
+
+    let data: WebhookEvent = serde_json::from_str(&body)?;
+    jlog!(Debug, "Stripe webhook received", { "id": &data.id, "type": &data.event_type });
+    let ipn = ProviderIPN {
+        provider: PaymentProviders::Stripe,
+        data: json!(data),
+    };
+    let mut action = DomainAction::create(
+        None,
+        DomainActionTypes::PaymentProviderIPN,
+        None,
+        json!(ipn),
+        None,
+        None,
+    );
+    action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+    action.max_attempt_count = 5;
+    action.commit(conn.get())?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,5 +1,6 @@
 pub mod admin;
 pub mod analytics;
+pub mod api_keys;
 pub mod artists;
 pub mod auth;
 pub mod broadcasts;
@@ -7,12 +8,15 @@ pub mod cart;
 pub mod codes;
 pub mod comps;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod events;
 pub mod external;
 pub mod genres;
 pub mod holds;
 pub mod ipns;
 pub mod notes;
+pub mod oauth;
+pub mod oauth_clients;
 pub mod orders;
 pub mod organization_invites;
 pub mod organizations;
@@ -22,15 +26,24 @@ pub mod payments;
 pub mod redemption_codes;
 pub mod regions;
 pub mod reports;
+pub mod resale_listings;
+pub mod saved_reports;
+pub mod season_passes;
 pub mod settlement_adjustments;
 pub mod settlements;
 pub mod sitemap_gen;
 pub mod slugs;
+pub mod stage_sections;
 pub mod stages;
 pub mod status;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
+pub mod two_factor;
 pub mod user_invites;
+pub mod user_sessions;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
+pub mod webhooks;
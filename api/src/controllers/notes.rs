@@ -1,10 +1,10 @@
+use actix_web::HttpResponse;
 use actix_web::Path;
-use actix_web::{HttpResponse, Query};
 use auth::user::User;
 use bigneon_db::prelude::*;
 use db::Connection;
 use errors::*;
-use extractors::Json;
+use extractors::{Json, Query};
 use helpers::application;
 use models::*;
 use reqwest::StatusCode;
@@ -0,0 +1,299 @@
+use actix_web::{http::StatusCode, Form, HttpResponse, Path, State};
+use auth::claims::RefreshToken;
+use auth::user::User as AuthUser;
+use auth::TokenResponse;
+use bigneon_db::prelude::*;
+use controllers::auth::{self, RefreshRequest};
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use jwt::{decode, Validation};
+use log::Level::Info;
+use models::PathParameters;
+use server::AppState;
+use std::str::FromStr;
+use url::Url;
+use uuid::Uuid;
+
+const CODE_CHALLENGE_METHOD: &str = "S256";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuthorizeRequest {
+    pub client_id: String,
+    pub redirect_uri: String,
+    /// Space separated list of requested scopes
+    pub scope: String,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+    pub state: Option<String>,
+}
+
+/// Token requests are form encoded as per RFC 6749
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RevokeRequest {
+    pub token: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OAuthTokenClaims {
+    oauth_consent_id: Option<Uuid>,
+}
+
+/// Details of the authorization request for display on the consent screen
+pub fn authorize_details(
+    (connection, query, user): (Connection, Query<AuthorizeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("OAuth clients cannot be authorized using delegated credentials");
+    }
+    let (oauth_client, scopes) = validate_authorize_request(&query, connection)?;
+    let granted_scopes = match OAuthConsent::find_active(oauth_client.id, user.id(), connection).optional()? {
+        Some(consent) => consent.scopes,
+        None => Vec::new(),
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "client_name": oauth_client.name,
+        "organization_id": oauth_client.organization_id,
+        "scopes": scopes,
+        "granted_scopes": granted_scopes
+    })))
+}
+
+/// Records the user's consent and returns the client redirect containing the authorization code
+pub fn authorize(
+    (connection, json, user): (Connection, Json<AuthorizeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("OAuth clients cannot be authorized using delegated credentials");
+    }
+    let (oauth_client, scopes) = validate_authorize_request(&json, connection)?;
+
+    let consent = OAuthConsent::grant(&oauth_client, user.id(), scopes.clone(), connection)?;
+    let (_, code) = OAuthAuthorizationCode::create(
+        consent.id,
+        json.redirect_uri.clone(),
+        scopes,
+        json.code_challenge.clone(),
+    )
+    .commit(connection)?;
+
+    let mut redirect_uri = Url::parse(&json.redirect_uri)?;
+    {
+        let mut query_pairs = redirect_uri.query_pairs_mut();
+        query_pairs.append_pair("code", &code);
+        if let Some(ref state) = json.state {
+            query_pairs.append_pair("state", state);
+        }
+    }
+    jlog!(Info, "User authorized OAuth client", {"user_id": user.id(), "oauth_client_id": oauth_client.id});
+
+    Ok(HttpResponse::Ok().json(json!({ "redirect_uri": redirect_uri.to_string() })))
+}
+
+pub fn token(
+    (state, connection, form): (State<AppState>, Connection, Form<TokenRequest>),
+) -> Result<HttpResponse, BigNeonError> {
+    let oauth_client = match authenticate_client(&form.client_id, &form.client_secret, connection.get())? {
+        Some(oauth_client) => oauth_client,
+        None => {
+            return oauth_error(
+                StatusCode::UNAUTHORIZED,
+                "invalid_client",
+                "Client authentication failed",
+            )
+        }
+    };
+
+    match form.grant_type.as_str() {
+        "authorization_code" => {
+            let (code, redirect_uri, code_verifier) = match (&form.code, &form.redirect_uri, &form.code_verifier) {
+                (Some(code), Some(redirect_uri), Some(code_verifier)) => (code, redirect_uri, code_verifier),
+                _ => {
+                    return oauth_error(
+                        StatusCode::BAD_REQUEST,
+                        "invalid_request",
+                        "code, redirect_uri and code_verifier are required",
+                    );
+                }
+            };
+
+            // Errors are returned as responses rather than propagated so a consent revoked due to
+            // authorization code reuse is not rolled back
+            let (authorization_code, consent) = match OAuthAuthorizationCode::redeem(
+                code,
+                &oauth_client,
+                redirect_uri,
+                code_verifier,
+                connection.get(),
+            ) {
+                Ok(result) => result,
+                Err(e) => {
+                    let description = match e.error_code {
+                        ErrorCode::BusinessProcessError => e.cause,
+                        _ => None,
+                    };
+                    let description = description.unwrap_or("Authorization code is invalid".to_string());
+                    return oauth_error(StatusCode::BAD_REQUEST, "invalid_grant", &description);
+                }
+            };
+
+            let response = TokenResponse::create_from_oauth_consent(
+                &state.config.token_secret,
+                &state.config.token_issuer,
+                &state.config.jwt_expiry_time,
+                &consent,
+                authorization_code.scopes,
+            )?;
+            jlog!(Info, "OAuth client issued token", {"user_id": consent.user_id, "oauth_client_id": oauth_client.id});
+            Ok(HttpResponse::Ok().json(response))
+        }
+        "refresh_token" => {
+            let refresh_token = match form.refresh_token {
+                Some(ref refresh_token) => refresh_token.clone(),
+                None => return oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "refresh_token is required"),
+            };
+
+            let mut validation = Validation::default();
+            validation.validate_exp = false;
+            let claims = match decode::<RefreshToken>(&refresh_token, state.config.token_secret.as_bytes(), &validation)
+            {
+                Ok(token) => token.claims,
+                Err(_) => return oauth_error(StatusCode::BAD_REQUEST, "invalid_grant", "Refresh token is invalid"),
+            };
+            let consent = match claims.oauth_consent_id {
+                Some(oauth_consent_id) => OAuthConsent::find(oauth_consent_id, connection.get())?,
+                None => return oauth_error(StatusCode::BAD_REQUEST, "invalid_grant", "Refresh token is invalid"),
+            };
+            if consent.oauth_client_id != oauth_client.id {
+                return oauth_error(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_grant",
+                    "Refresh token was not issued to this client",
+                );
+            }
+
+            auth::token_refresh((state, connection, Json(RefreshRequest::new(&refresh_token))))
+        }
+        _ => oauth_error(
+            StatusCode::BAD_REQUEST,
+            "unsupported_grant_type",
+            "Grant type is not supported",
+        ),
+    }
+}
+
+/// Revokes the consent the token was issued under, invalidating every token issued with it. As per
+/// RFC 7009 the response is the same whether or not the token was valid.
+pub fn revoke(
+    (state, connection, form): (State<AppState>, Connection, Form<RevokeRequest>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let oauth_client = match authenticate_client(&form.client_id, &form.client_secret, connection)? {
+        Some(oauth_client) => oauth_client,
+        None => {
+            return oauth_error(
+                StatusCode::UNAUTHORIZED,
+                "invalid_client",
+                "Client authentication failed",
+            )
+        }
+    };
+
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+    if let Ok(token) = decode::<OAuthTokenClaims>(&form.token, state.config.token_secret.as_bytes(), &validation) {
+        if let Some(oauth_consent_id) = token.claims.oauth_consent_id {
+            if let Some(consent) = OAuthConsent::find(oauth_consent_id, connection).optional()? {
+                if consent.oauth_client_id == oauth_client.id {
+                    consent.revoke(None, connection)?;
+                }
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+pub fn consents((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    if user.is_delegated() {
+        return application::forbidden("OAuth consents cannot be viewed using delegated credentials");
+    }
+    Ok(HttpResponse::Ok().json(OAuthConsent::find_for_user(user.id(), connection.get())?))
+}
+
+pub fn destroy_consent(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("OAuth consents cannot be revoked using delegated credentials");
+    }
+    let consent = OAuthConsent::find(path.id, connection)?;
+    if consent.user_id != user.id() {
+        return application::forbidden("User does not have access to this consent");
+    }
+
+    consent.revoke(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn validate_authorize_request(
+    request: &AuthorizeRequest,
+    connection: &PgConnection,
+) -> Result<(OAuthClient, Vec<Scopes>), BigNeonError> {
+    let oauth_client = match OAuthClient::find_active_by_client_id(&request.client_id, connection).optional()? {
+        Some(oauth_client) => oauth_client,
+        None => return application::unprocessable("Client is invalid"),
+    };
+    if !oauth_client.is_valid_redirect_uri(&request.redirect_uri) {
+        return application::unprocessable("Redirect URI is not registered for this client");
+    }
+    if request.code_challenge_method != CODE_CHALLENGE_METHOD || request.code_challenge.is_empty() {
+        return application::unprocessable("An S256 code challenge is required");
+    }
+
+    let client_scopes = oauth_client.scopes()?;
+    let mut scopes = Vec::new();
+    for scope in request.scope.split_whitespace() {
+        match Scopes::from_str(scope) {
+            Ok(scope) if client_scopes.contains(&scope) => scopes.push(scope),
+            _ => return application::unprocessable(&format!("Scope {} is not allowed for this client", scope)),
+        }
+    }
+    if scopes.is_empty() {
+        return application::unprocessable("At least one scope is required");
+    }
+
+    Ok((oauth_client, scopes))
+}
+
+fn authenticate_client(
+    client_id: &str,
+    client_secret: &Option<String>,
+    connection: &PgConnection,
+) -> Result<Option<OAuthClient>, BigNeonError> {
+    let oauth_client = OAuthClient::find_active_by_client_id(client_id, connection).optional()?;
+    Ok(oauth_client.filter(|c| c.authenticate(client_secret.as_ref().map(|s| s.as_str()))))
+}
+
+fn oauth_error(status: StatusCode, error: &str, description: &str) -> Result<HttpResponse, BigNeonError> {
+    Ok(HttpResponse::build(status).json(json!({ "error": error, "error_description": description })))
+}
@@ -0,0 +1,91 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use std::str::FromStr;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct NewOAuthClientRequest {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    /// Confidential clients are issued a secret, public clients such as mobile apps rely on PKCE alone
+    #[serde(default)]
+    pub confidential: bool,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(OAuthClient::find_for_organization(organization.id, connection)?))
+}
+
+pub fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewOAuthClientRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.is_delegated() {
+        return application::forbidden("OAuth clients cannot be managed using delegated credentials");
+    }
+
+    let mut scopes = Vec::new();
+    for scope in &json.scopes {
+        scopes.push(Scopes::from_str(scope)?);
+    }
+    let mut oauth_client = OAuthClient::create(
+        organization.id,
+        json.name.clone(),
+        json.redirect_uris.clone(),
+        scopes,
+        user.id(),
+    )
+    .commit(connection)?;
+
+    let mut client_secret = None;
+    if json.confidential {
+        let (updated_oauth_client, secret) = oauth_client.generate_secret(Some(user.id()), connection)?;
+        oauth_client = updated_oauth_client;
+        client_secret = Some(secret);
+    }
+
+    Ok(HttpResponse::Created().json(json!({ "oauth_client": oauth_client, "client_secret": client_secret })))
+}
+
+/// Replaces the client secret, the previous secret stops working immediately
+pub fn generate_secret(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let oauth_client = OAuthClient::find(path.id, connection)?;
+    let organization = Organization::find(oauth_client.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.is_delegated() {
+        return application::forbidden("OAuth clients cannot be managed using delegated credentials");
+    }
+
+    let (oauth_client, client_secret) = oauth_client.generate_secret(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({ "oauth_client": oauth_client, "client_secret": client_secret })))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let oauth_client = OAuthClient::find(path.id, connection)?;
+    let organization = Organization::find(oauth_client.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.is_delegated() {
+        return application::forbidden("OAuth clients cannot be managed using delegated credentials");
+    }
+
+    Ok(HttpResponse::Ok().json(oauth_client.revoke(Some(user.id()), connection)?))
+}
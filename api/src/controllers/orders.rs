@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
 use auth::user::User;
 use bigneon_db::models::User as DbUser;
 use bigneon_db::models::*;
@@ -17,6 +17,7 @@ use server::AppState;
 use std::cmp;
 use std::collections::HashMap;
 use utils::serializers::default_as_false;
+use utils::ServiceLocator;
 use uuid::Uuid;
 
 pub fn index(
@@ -282,12 +283,18 @@ pub fn refund(
 
             let amount_to_refund = cmp::min(refund_due - amount_refunded, remaining_balance);
             let mut refund_data = None;
-            if !manual_override && payment.payment_method == PaymentMethods::CreditCard {
+            let gateway_refund = payment.payment_method == PaymentMethods::CreditCard
+                || (payment.payment_method == PaymentMethods::Provider
+                    && ServiceLocator::is_refund_supported(payment.provider.to_string()));
+            if !manual_override && gateway_refund {
                 let mut organizations = order.organizations(connection)?;
-                if organizations.len() != 1 {
-                    return Err(application::internal_server_error::<HttpResponse>(
-                        "Cannot process refunds for orders that contain more than one event",
-                    )
+                if organizations.is_empty()
+                    || (organizations.len() > 1 && !payment.provider.supports_multiple_organizations())
+                {
+                    return Err(application::internal_server_error::<HttpResponse>(&format!(
+                        "Cannot process {} refunds for orders that contain more than one organization",
+                        payment.provider
+                    ))
                     .unwrap_err());
                 }
                 let organization = organizations.remove(0);
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::NaiveDateTime;
@@ -25,6 +25,7 @@ pub struct FeeScheduleWithRanges {
     pub version: i16,
     pub created_at: NaiveDateTime,
     pub ranges: Vec<FeeScheduleRange>,
+    pub currency: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -58,6 +59,8 @@ pub struct NewOrganizationRequest {
     pub cc_fee_percent: Option<f32>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -149,6 +152,12 @@ pub fn create(
             None => state.config.max_instances_per_ticket_type,
         }),
         settlement_type: new_organization.settlement_type,
+        currency: Some(
+            new_organization
+                .currency
+                .clone()
+                .unwrap_or_else(|| state.config.primary_currency.to_uppercase()),
+        ),
     };
 
     let mut organization = new_organization_with_fee_schedule.commit(
@@ -178,6 +187,13 @@ pub fn update(
     let mut organization = Organization::find(parameters.id, conn)?;
     let organization_update = organization_parameters.into_inner();
 
+    if organization_update.two_factor_required_roles.is_some() {
+        user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, conn)?;
+    }
+    if organization_update.resale_company_fee_percent.is_some() {
+        // The company's share is set by admins, like the fee schedule
+        user.requires_scope(Scopes::OrgAdmin)?;
+    }
     if organization_update.settlement_type.is_some() {
         user.requires_scope_for_organization(Scopes::OrgModifySettlementType, &organization, conn)?;
     } else if organization_update.max_instances_per_ticket_type.is_some() {
@@ -313,7 +329,7 @@ pub fn list_organization_members(
     Ok(WebPayload::new(StatusCode::OK, payload))
 }
 
-#[derive(Serialize, PartialEq, Debug)]
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
 pub struct DisplayOrganizationUser {
     pub user_id: Option<Uuid>,
     pub first_name: Option<String>,
@@ -341,6 +357,7 @@ pub fn show_fee_schedule(
         version: fee_schedule.version,
         created_at: fee_schedule.created_at,
         ranges: fee_schedule_ranges,
+        currency: fee_schedule.currency,
     }))
 }
 
@@ -358,7 +375,7 @@ pub fn add_fee_schedule(
     let fee_schedule = new_fee_schedule.commit(Some(user.id()), connection)?;
     let fee_schedule_ranges = fee_schedule.ranges(connection)?;
 
-    Organization::find(parameters.id, connection)?.add_fee_schedule(&fee_schedule, connection)?;
+    let organization = Organization::find(parameters.id, connection)?.add_fee_schedule(&fee_schedule, connection)?;
 
     Ok(HttpResponse::Created().json(FeeScheduleWithRanges {
         id: fee_schedule.id,
@@ -366,6 +383,7 @@ pub fn add_fee_schedule(
         version: fee_schedule.version,
         created_at: fee_schedule.created_at,
         ranges: fee_schedule_ranges,
+        currency: organization.currency,
     }))
 }
 
@@ -1,9 +1,9 @@
-use actix_web::{HttpResponse, State};
-use auth::TokenResponse;
+use actix_web::{HttpRequest, HttpResponse, State};
 use bigneon_db::models::concerns::users::password_resetable::*;
 use bigneon_db::models::User;
 use bigneon_db::utils::errors::Optional;
 use communications::mailers;
+use controllers::auth;
 use db::Connection;
 use errors::*;
 use extractors::*;
@@ -45,19 +45,16 @@ pub fn create(
 }
 
 pub fn update(
-    (state, connection, parameters): (State<AppState>, Connection, Json<UpdatePasswordResetParameters>),
+    (http_request, connection, parameters): (HttpRequest<AppState>, Connection, Json<UpdatePasswordResetParameters>),
 ) -> Result<HttpResponse, BigNeonError> {
     let user =
         User::consume_password_reset_token(&parameters.password_reset_token, &parameters.password, connection.get())
             .optional()?;
 
     match user {
-        Some(user) => Ok(HttpResponse::Ok().json(&TokenResponse::create_from_user(
-            &state.config.token_secret,
-            &state.config.token_issuer,
-            &state.config.jwt_expiry_time,
-            &user,
-        )?)),
+        Some(user) => {
+            Ok(HttpResponse::Ok().json(&auth::create_login_response(&http_request, &user, connection.get())?))
+        }
         None => application::unprocessable("Password has already been reset."),
     }
 }
@@ -1,19 +1,23 @@
 use crate::db::Connection;
 use actix_web::HttpResponse;
 use actix_web::Path;
-use actix_web::Query;
 use actix_web::State;
 use bigneon_db::prelude::*;
 use errors::*;
-use extractors::OptionalUser;
+use extractors::{OptionalUser, Query};
 use helpers::application;
+use hosted_checkout;
 use log::Level::Debug;
+use payments::hosted_checkout::external_reference;
 use server::AppState;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryParams {
     pub success: bool,
+    // Appended by hosted checkout providers when returning the customer
+    pub checkout_id: Option<String>,
+    pub signature: Option<String>,
 }
 
 // The nonce is in the path so that it is not cached.
@@ -60,6 +64,23 @@ pub fn callback(
         None => return application::not_found(),
     };
 
+    if payment.provider == PaymentProviders::HostedCheckout {
+        let is_valid = match (&state.config.hosted_checkout, &query.checkout_id, &query.signature) {
+            (Some(settings), Some(checkout_id), Some(signature)) => {
+                payment.external_reference == Some(external_reference(checkout_id))
+                    && hosted_checkout::verify(
+                        &settings.secret,
+                        &hosted_checkout::return_message(checkout_id, &order.id.to_string(), query.success),
+                        signature,
+                    )
+            }
+            _ => false,
+        };
+        if !is_valid {
+            return application::forbidden("Payment callback signature is invalid");
+        }
+    }
+
     // We specifically don't count this as a payment confirmation, that will be done via the IPN
     // Just redirect to page accordingly
 
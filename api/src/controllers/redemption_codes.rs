@@ -1,9 +1,10 @@
+use actix_web::HttpResponse;
 use actix_web::Path;
-use actix_web::{HttpResponse, Query};
 use bigneon_db::prelude::*;
 use chrono::NaiveDateTime;
 use db::Connection;
 use errors::BigNeonError;
+use extractors::Query;
 use helpers::application;
 use models::*;
 use uuid::Uuid;
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, HttpResponse, Path};
 use auth::user::User;
 use bigneon_db::models::*;
 use db::Connection;
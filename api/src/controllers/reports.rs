@@ -1,12 +1,14 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse, Path};
 use auth::user::User as AuthUser;
 use bigneon_db::models::*;
 use chrono::prelude::*;
 use db::Connection;
 use errors::*;
+use extractors::Query;
 use helpers::application;
 use models::{PathParameters, WebPayload};
 use serde_json::Value;
+use server::AppState;
 use std::collections::HashMap;
 use std::str;
 use uuid::Uuid;
@@ -21,6 +23,29 @@ pub struct ReportQueryParameters {
     query: Option<String>,
     page: Option<u32>,
     limit: Option<u32>,
+    /// Export format, exports include up to `MAXIMUM_EXPORT_ROWS` rows instead of a single page
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    format: Option<String>,
+}
+
+impl ReportQueryParameters {
+    fn export_format(&self) -> Option<ReportFormats> {
+        self.format.as_ref().and_then(|f| f.parse().ok())
+    }
+
+    fn page(&self) -> u32 {
+        if self.export_format().is_some() {
+            return 0;
+        }
+        self.page.unwrap_or(0)
+    }
+
+    fn limit(&self) -> u32 {
+        if self.export_format().is_some() {
+            return MAXIMUM_EXPORT_ROWS;
+        }
+        self.limit.unwrap_or(100)
+    }
 }
 
 impl From<ReportQueryParameters> for Paging {
@@ -39,6 +64,9 @@ impl From<ReportQueryParameters> for Paging {
             query_tags.insert("event_id".to_owned(), json!(event_id));
         }
         query_tags.insert("report".to_owned(), json!(s.report.clone()));
+        if let Some(ref format) = s.format {
+            query_tags.insert("format".to_owned(), json!(format));
+        }
 
         PagingParameters {
             page: s.page,
@@ -52,11 +80,19 @@ impl From<ReportQueryParameters> for Paging {
 }
 
 pub fn get_report(
-    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+    (http_request, connection, query, path, user): (
+        HttpRequest<AppState>,
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
 ) -> Result<HttpResponse, BigNeonError> {
     match query.report.trim() {
         "box_office_sales_summary" => box_office_sales_summary((connection, query, path, user)),
-        "transaction_details" => Ok(transaction_detail_report((connection, query, path, user))?.into_http_response()?),
+        "transaction_details" => {
+            transaction_detail_report((connection, query, path, user))?.into_response(&http_request)
+        }
         "event_summary" => event_summary_report((connection, query, path, user)),
         "weekly_settlement" => weekly_settlement_report((connection, query, path, user)),
         "ticket_count" => ticket_counts((connection, query, path, user)),
@@ -99,8 +135,8 @@ pub fn transaction_detail_report(
         Some(path.id),
         query.start_utc,
         query.end_utc,
-        query.page.unwrap_or(0),
-        query.limit.unwrap_or(100),
+        query.page(),
+        query.limit(),
         connection,
     )?;
     Ok(WebPayload::new(StatusCode::OK, result))
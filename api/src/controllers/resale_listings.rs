@@ -0,0 +1,71 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateResaleListingRequest {
+    pub ticket_instance_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+pub fn index((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let mut listings: Vec<DisplayResaleListing> = vec![];
+    for listing in ResaleListing::find_for_user(user.id(), connection)? {
+        listings.push(listing.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(listings))
+}
+
+pub fn event_index((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let mut listings: Vec<DisplayResaleListing> = vec![];
+    for listing in ResaleListing::find_active_for_event(event.id, connection)? {
+        listings.push(listing.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(listings))
+}
+
+pub fn create(
+    (connection, json, user): (Connection, Json<CreateResaleListingRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let listing = ResaleListing::create(json.ticket_instance_id, user.id(), json.price_in_cents).commit(connection)?;
+    Ok(HttpResponse::Created().json(listing.for_display(connection)?))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let listing = ResaleListing::find(path.id, connection)?;
+    if listing.seller_user_id != user.id() {
+        return application::forbidden("You do not have access to this resale listing");
+    }
+
+    listing.cancel(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+pub fn add_to_cart(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let listing = ResaleListing::find(path.id, connection)?;
+    if listing.seller_user_id == user.id() {
+        return application::unprocessable("You cannot purchase your own resale listing");
+    }
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.add_resale_listing(user.id(), &listing, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
@@ -0,0 +1,139 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewSavedReportRequest {
+    pub name: String,
+    pub report_type: SavedReportTypes,
+    #[serde(default)]
+    pub filters: SavedReportFilters,
+    /// Columns to include in order, all of the report type's columns are included when empty
+    #[serde(default)]
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub format: ReportFormats,
+    /// Cron expression evaluated in the organization's timezone, e.g. `0 6 * * 1` for 6AM every Monday
+    pub schedule: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewSavedReportSubscriberRequest {
+    pub email: String,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(SavedReport::find_for_organization(organization.id, connection)?))
+}
+
+pub fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewSavedReportRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+
+    let json = json.into_inner();
+    let saved_report = SavedReport::create(
+        organization.id,
+        json.name,
+        json.report_type,
+        json.filters,
+        json.columns,
+        json.format,
+        json.schedule,
+        user.id(),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(saved_report))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let saved_report = find_authorized(path.id, &user, connection)?;
+    Ok(HttpResponse::Ok().json(saved_report))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<SavedReportEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let saved_report = find_authorized(path.id, &user, connection)?;
+    let saved_report = saved_report.update(json.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(saved_report))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let saved_report = find_authorized(path.id, &user, connection)?;
+    saved_report.destroy(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Runs the report immediately, returning the selected columns and rows
+pub fn run(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let saved_report = find_authorized(path.id, &user, connection)?;
+    Ok(HttpResponse::Ok().json(saved_report.run(connection)?))
+}
+
+pub fn subscribers(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let saved_report = find_authorized(path.id, &user, connection)?;
+    Ok(HttpResponse::Ok().json(saved_report.subscribers(connection)?))
+}
+
+pub fn add_subscriber(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewSavedReportSubscriberRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let saved_report = find_authorized(path.id, &user, connection)?;
+    let subscriber =
+        SavedReportSubscriber::create(saved_report.id, json.email.clone()).commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(subscriber))
+}
+
+pub fn remove_subscriber(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscriber = SavedReportSubscriber::find(path.id, connection)?;
+    find_authorized(subscriber.saved_report_id, &user, connection)?;
+    subscriber.destroy(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn find_authorized(id: Uuid, user: &AuthUser, connection: &PgConnection) -> Result<SavedReport, BigNeonError> {
+    let saved_report = SavedReport::find(id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReports, &saved_report.organization(connection)?, connection)?;
+    Ok(saved_report)
+}
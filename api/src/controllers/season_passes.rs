@@ -0,0 +1,105 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateSeasonPassRequest {
+    pub name: String,
+    pub price_in_cents: i64,
+    #[serde(default)]
+    pub event_ids: Vec<Uuid>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct UpdateSeasonPassRequest {
+    pub name: Option<String>,
+    pub price_in_cents: Option<i64>,
+    pub event_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct AddSeasonPassToCartRequest {
+    pub quantity: u32,
+}
+
+pub fn index((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    let mut season_passes: Vec<DisplaySeasonPass> = vec![];
+    for season_pass in SeasonPass::find_for_organization(organization.id, connection)? {
+        season_passes.push(season_pass.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(season_passes))
+}
+
+pub fn show((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let season_pass = SeasonPass::find(path.id, connection)?;
+    Ok(HttpResponse::Ok().json(season_pass.for_display(connection)?))
+}
+
+pub fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateSeasonPassRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let season_pass =
+        SeasonPass::create(organization.id, json.name, json.price_in_cents).commit(Some(user.id()), connection)?;
+    season_pass.set_events(&json.event_ids, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(season_pass.for_display(connection)?))
+}
+
+pub fn update(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<UpdateSeasonPassRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let season_pass = SeasonPass::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &season_pass.organization(connection)?, connection)?;
+
+    let json = json.into_inner();
+    let season_pass = season_pass.update(
+        SeasonPassEditableAttributes {
+            name: json.name,
+            price_in_cents: json.price_in_cents,
+        },
+        Some(user.id()),
+        connection,
+    )?;
+    if let Some(event_ids) = json.event_ids {
+        season_pass.set_events(&event_ids, Some(user.id()), connection)?;
+    }
+
+    Ok(HttpResponse::Ok().json(season_pass.for_display(connection)?))
+}
+
+pub fn add_to_cart(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<AddSeasonPassToCartRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let season_pass = SeasonPass::find(path.id, connection)?;
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.add_season_pass(user.id(), &season_pass, json.quantity, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+pub fn entitlements((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let mut entitlements: Vec<DisplaySeasonPassEntitlement> = vec![];
+    for entitlement in SeasonPassEntitlement::find_for_user(user.id(), connection)? {
+        entitlements.push(entitlement.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(entitlements))
+}
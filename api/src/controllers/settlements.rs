@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
 use auth::user::User as AuthUser;
 use bigneon_db::models::*;
 use chrono::prelude::*;
@@ -14,6 +14,8 @@ pub struct NewSettlementRequest {
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub comment: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 pub fn index(
@@ -32,7 +34,7 @@ pub fn index(
     let payload = Settlement::find_for_organization(
         path.id,
         Some(query.limit()),
-        Some(query.page() * query.limit()),
+        Some(query.page()),
         // Hide settlements for default settlement period where users lack settlement read early scope
         state.config.settlement_period_in_days.is_none()
             && !user.has_scope_for_organization(Scopes::SettlementReadEarly, &organization, connection)?,
@@ -48,7 +50,7 @@ pub fn create(
     let connection = connection.get();
     let organization = Organization::find(path.id, connection)?;
     user.requires_scope_for_organization(Scopes::SettlementWrite, &organization, connection)?;
-    let new_settlement = Settlement::create(
+    let mut settlement = Settlement::create(
         organization.id,
         new_settlement.start_time,
         new_settlement.end_time,
@@ -56,7 +58,8 @@ pub fn create(
         new_settlement.comment.clone(),
         organization.settlement_type == SettlementTypes::PostEvent,
     );
-    let settlement = new_settlement.commit(Some(user.user), connection)?;
+    settlement.currency = new_settlement.currency.clone();
+    let settlement = settlement.commit(Some(user.user), connection)?;
     Ok(HttpResponse::Created().json(&settlement))
 }
 
@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, Path, Query, State};
+use actix_web::{HttpResponse, Path, State};
 use auth::user::User as AuthUser;
 use bigneon_db::prelude::*;
 use controllers::events::{self, *};
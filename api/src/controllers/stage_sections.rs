@@ -0,0 +1,73 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+
+#[derive(Deserialize)]
+pub struct CreateStageSectionRow {
+    pub name: String,
+    pub seat_count: u32,
+}
+
+#[derive(Deserialize)]
+pub struct CreateStageSection {
+    pub name: String,
+    #[serde(default)]
+    pub rank: i32,
+    #[serde(default)]
+    pub rows: Vec<CreateStageSectionRow>,
+}
+
+pub fn index((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&stage.seat_map(connection)?))
+}
+
+pub fn create(
+    (connection, parameters, create_section, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateStageSection>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+    requires_venue_write(&user, &stage, connection)?;
+
+    let create_section = create_section.into_inner();
+    let section = StageSection::create(stage.id, create_section.name, create_section.rank).commit(connection)?;
+    for row in create_section.rows {
+        section.add_row(row.name, row.seat_count, connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(&section.for_display(connection)?))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let section = StageSection::find(parameters.id, connection)?;
+    requires_venue_write(&user, &section.stage(connection)?, connection)?;
+
+    section.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn requires_venue_write(user: &AuthUser, stage: &Stage, connection: &PgConnection) -> Result<(), BigNeonError> {
+    let venue = Venue::find(stage.venue_id, connection)?;
+    if !venue.is_private || venue.organization_id.is_none() {
+        user.requires_scope(Scopes::VenueWrite)?;
+    } else {
+        let organization = venue.organization(connection)?.unwrap();
+        user.requires_scope_for_organization(Scopes::VenueWrite, &organization, connection)?;
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, Path, Query};
+use actix_web::{HttpResponse, Path};
 use auth::user::User as AuthUser;
 use bigneon_db::models::*;
 use db::Connection;
@@ -1,12 +1,33 @@
 use actix_web::HttpResponse;
+use bigneon_db::models::{DomainActionWorker, DomainActionWorkerTypes};
 use bigneon_db::utils::migration;
+use chrono::NaiveDateTime;
 use db::Connection;
 use diesel::PgConnection;
 use errors::*;
 use log::Level::*;
+use uuid::Uuid;
 
 static mut IS_OK: bool = false;
 
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct WorkerStatus {
+    pub id: Uuid,
+    pub worker_type: DomainActionWorkerTypes,
+    pub is_leader: bool,
+    pub healthy: bool,
+    pub last_heartbeat_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct WorkersStatus {
+    /// At least one healthy worker is processing domain actions
+    pub processing_actions: bool,
+    /// The healthy worker currently elected to publish domain events, if any
+    pub event_publisher_leader_id: Option<Uuid>,
+    pub workers: Vec<WorkerStatus>,
+}
+
 pub fn check(connection: (Connection)) -> Result<HttpResponse, BigNeonError> {
     if unsafe { IS_OK } {
         return Ok(HttpResponse::Ok().finish());
@@ -26,6 +47,28 @@ pub fn check(connection: (Connection)) -> Result<HttpResponse, BigNeonError> {
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Health of the domain action monitor workers across all instances
+pub fn workers(connection: Connection) -> Result<HttpResponse, BigNeonError> {
+    let workers: Vec<WorkerStatus> = DomainActionWorker::find_running(connection.get())?
+        .into_iter()
+        .map(|worker| WorkerStatus {
+            id: worker.id,
+            worker_type: worker.worker_type,
+            is_leader: worker.is_leader,
+            healthy: worker.is_healthy(),
+            last_heartbeat_at: worker.last_heartbeat_at,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(WorkersStatus {
+        processing_actions: workers
+            .iter()
+            .any(|w| w.healthy && w.worker_type == DomainActionWorkerTypes::Actions),
+        event_publisher_leader_id: workers.iter().find(|w| w.healthy && w.is_leader).map(|w| w.id),
+        workers,
+    }))
+}
+
 fn check_migrations(conn: &PgConnection) -> Result<(), ApplicationError> {
     migration::has_pending_migrations(conn)
         .map_err(|_err| ApplicationError::new("Error while checking migrations".to_string()))
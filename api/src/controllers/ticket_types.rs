@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, Path, Query, State};
+use actix_web::{HttpResponse, Path, State};
 use auth::user::User;
 use bigneon_db::dev::times;
 use bigneon_db::models::*;
@@ -25,6 +25,15 @@ pub struct CreateTicketPricingRequest {
     pub is_box_office_only: Option<bool>,
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct CreateTicketPricingRuleRequest {
+    pub name: String,
+    pub rule_type: TicketPricingRuleTypes,
+    pub threshold: i64,
+    pub price_in_cents: Option<i64>,
+    pub price_increase_percent: Option<i32>,
+}
+
 #[derive(Clone, Deserialize)]
 pub struct CreateMultipleTicketTypeRequest {
     pub ticket_types: Vec<CreateTicketTypeRequest>,
@@ -42,6 +51,8 @@ pub struct CreateTicketTypeRequest {
     pub end_date_type: Option<TicketTypeEndDateType>,
     #[serde(default)]
     pub ticket_pricing: Vec<CreateTicketPricingRequest>,
+    #[serde(default)]
+    pub pricing_rules: Vec<CreateTicketPricingRuleRequest>,
     pub increment: Option<i32>,
     pub limit_per_person: i32,
     pub price_in_cents: i64,
@@ -69,6 +80,7 @@ impl Default for CreateTicketTypeRequest {
             end_date: None,
             end_date_type: Some(TicketTypeEndDateType::Manual),
             ticket_pricing: vec![],
+            pricing_rules: vec![],
             increment: None,
             limit_per_person: 0,
             price_in_cents: 0,
@@ -103,6 +115,7 @@ pub struct UpdateTicketTypeRequest {
     pub end_date: Option<Option<NaiveDateTime>>,
     pub end_date_type: Option<TicketTypeEndDateType>,
     pub ticket_pricing: Option<Vec<UpdateTicketPricingRequest>>,
+    pub pricing_rules: Option<Vec<CreateTicketPricingRuleRequest>>,
     pub increment: Option<i32>,
     pub limit_per_person: Option<i32>,
     pub price_in_cents: Option<i64>,
@@ -200,6 +213,33 @@ pub fn index(
     Ok(HttpResponse::Ok().json(&payload))
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct AssignSeatsRequest {
+    pub seat_ids: Vec<Uuid>,
+}
+
+pub fn assign_seats(
+    (connection, path, data, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<AssignSeatsRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.event_id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, &organization, &event, connection)?;
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != event.id {
+        return application::unprocessable("Ticket type does not belong to this event");
+    }
+    ticket_type.assign_seats(&data.seat_ids, Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Ok().json(Seat::find_for_event(event.id, connection)?))
+}
+
 pub fn cancel(
     (connection, path, user, state): (Connection, Path<EventTicketPathParameters>, User, State<AppState>),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -294,6 +334,7 @@ pub fn update(
                     "Could not complete capacity increase because the asset has not been assigned on the blockchain",
                 ),
             }
+            WaitlistEntry::offer_available_tickets(ticket_type.id, Some(user.id()), connection)?;
         } else if valid_ticket_count > requested_capacity {
             jlog!(Debug, "Update ticket type: Capacity decreased", {"ticket_type_id": path.ticket_type_id, "new_capacity": requested_capacity, "old_capacity": valid_ticket_count});
             let nullify_ticket_count = valid_ticket_count - requested_capacity;
@@ -394,6 +435,14 @@ pub fn update(
         updated_ticket_type.validate_ticket_pricing(connection)?;
     }
 
+    if let Some(ref data_pricing_rules) = data.pricing_rules {
+        //Replace the rules which have not been triggered yet, triggered rules are kept as history
+        for pricing_rule in TicketPricingRule::find_for_ticket_type(updated_ticket_type.id, true, connection)? {
+            pricing_rule.destroy(Some(user.id()), connection)?;
+        }
+        add_pricing_rules(&updated_ticket_type, data_pricing_rules, &user, connection)?;
+    }
+
     let result = AdminDisplayTicketType::from_ticket_type(
         &(TicketType::find(path.ticket_type_id, connection)?),
         &FeeSchedule::find(fee_schedule_id, connection)?,
@@ -445,6 +494,26 @@ fn nullify_tickets(
     Ok(())
 }
 
+fn add_pricing_rules(
+    ticket_type: &TicketType,
+    pricing_rules: &[CreateTicketPricingRuleRequest],
+    user: &User,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    for pricing_rule in pricing_rules {
+        TicketPricingRule::create(
+            ticket_type.id,
+            pricing_rule.name.clone(),
+            pricing_rule.rule_type,
+            pricing_rule.threshold,
+            pricing_rule.price_in_cents,
+            pricing_rule.price_increase_percent,
+        )
+        .commit(Some(user.id()), connection)?;
+    }
+    Ok(())
+}
+
 fn create_ticket_types(
     event: &Event,
     organization: &Organization,
@@ -512,6 +581,7 @@ fn create_ticket_types(
         }
 
         ticket_type.validate_ticket_pricing(connection)?;
+        add_pricing_rules(&ticket_type, &ticket_type_data.pricing_rules, user, connection)?;
         results.push((ticket_type_data, ticket_type));
     }
 
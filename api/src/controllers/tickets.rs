@@ -1,5 +1,5 @@
 use actix_web::State;
-use actix_web::{HttpResponse, Path, Query};
+use actix_web::{HttpResponse, Path};
 use auth::user::User;
 use bigneon_db::models::User as DbUser;
 use bigneon_db::prelude::*;
@@ -132,6 +132,20 @@ pub fn show_redeemable_ticket(
     Ok(HttpResponse::Ok().json(&redeemable_ticket))
 }
 
+pub fn signed_payload(
+    (connection, parameters, auth_user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket = TicketInstance::find(parameters.id, connection)?;
+    let organization = ticket.organization(connection)?;
+
+    if ticket.owner(connection).optional()?.map(|u| u.id) != Some(auth_user.id()) {
+        auth_user.requires_scope_for_organization(Scopes::TicketRead, &organization, connection)?;
+    }
+
+    Ok(HttpResponse::Ok().json(&SignedTicketPayload::create(&ticket, connection)?))
+}
+
 pub fn send_via_email_or_phone(
     (connection, send_tickets_request, auth_user, state): (Connection, Json<SendTicketsRequest>, User, State<AppState>),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
 use auth::user::User;
 use bigneon_db::models::{User as DbUser, *};
 use chrono::prelude::*;
@@ -7,6 +7,7 @@ use controllers::tickets::transfer_tickets_on_blockchain;
 use db::Connection;
 use diesel::PgConnection;
 use errors::*;
+use extractors::Query;
 use helpers::application;
 use itertools::Itertools;
 use models::*;
@@ -105,7 +106,10 @@ pub fn activity(
 
     let payload = auth_user.user.transfer_activity_by_event_tickets(
         paging_query.page(),
-        paging_query.limit.unwrap_or(std::u32::MAX),
+        paging_query
+            .limit
+            .filter(|_| paging_query.export_format().is_none())
+            .unwrap_or(std::u32::MAX),
         paging_query.dir.unwrap_or(SortingDir::Desc),
         past_or_upcoming_query
             .past_or_upcoming
@@ -0,0 +1,128 @@
+use actix_web::{HttpResponse, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use bigneon_db::utils::totp;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use log::Level::Info;
+use server::AppState;
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct TwoFactorCodeRequest {
+    /// Code from the user's authenticator app
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub code: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub recovery_code: Option<String>,
+}
+
+pub fn show((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let credential = TwoFactorCredential::find_enabled_by_user_id(user.id(), connection).optional()?;
+    let remaining_recovery_codes = match credential {
+        Some(_) => TwoFactorRecoveryCode::remaining_for_user(user.id(), connection)?,
+        None => 0,
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "enabled": credential.is_some(),
+        "enabled_at": credential.and_then(|c| c.enabled_at),
+        "remaining_recovery_codes": remaining_recovery_codes
+    })))
+}
+
+/// Starts enrollment, the secret is shown to the user or scanned from the provisioning URI by their
+/// authenticator app and two-factor authentication is enabled once a code is confirmed
+pub fn create(
+    (state, connection, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("Two-factor authentication cannot be managed using delegated credentials");
+    }
+
+    let (_, secret) =
+        TwoFactorCredential::start_enrollment(user.id(), &state.config.api_keys_encryption_key, connection)?;
+    let account_name = user.email().unwrap_or(user.id().to_string());
+    let provisioning_uri = totp::provisioning_uri(&secret, &account_name, &state.config.app_name);
+
+    Ok(HttpResponse::Created().json(json!({ "secret": secret, "provisioning_uri": provisioning_uri })))
+}
+
+pub fn enable(
+    (state, connection, json, user): (State<AppState>, Connection, Json<TwoFactorCodeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("Two-factor authentication cannot be managed using delegated credentials");
+    }
+    let code = match json.code {
+        Some(ref code) => code,
+        None => return application::unprocessable("Two-factor code is required"),
+    };
+
+    let credential = TwoFactorCredential::find_by_user_id(user.id(), connection)?;
+    let (_, recovery_codes) = credential.enable(code, &state.config.api_keys_encryption_key, connection)?;
+    jlog!(Info, "User enabled two-factor authentication", {"id": user.id(), "email": user.email()});
+
+    Ok(HttpResponse::Ok().json(json!({ "recovery_codes": recovery_codes })))
+}
+
+/// Replaces the user's recovery codes, previous recovery codes stop working
+pub fn regenerate_recovery_codes(
+    (state, connection, json, user): (State<AppState>, Connection, Json<TwoFactorCodeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("Two-factor authentication cannot be managed using delegated credentials");
+    }
+
+    let credential = TwoFactorCredential::find_enabled_by_user_id(user.id(), connection)?;
+    if !verify(&credential, &json, &state.config.api_keys_encryption_key, connection)? {
+        return application::unprocessable("Two-factor code is invalid");
+    }
+
+    let recovery_codes = TwoFactorRecoveryCode::generate_for_user(user.id(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({ "recovery_codes": recovery_codes })))
+}
+
+pub fn destroy(
+    (state, connection, json, user): (State<AppState>, Connection, Json<TwoFactorCodeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("Two-factor authentication cannot be managed using delegated credentials");
+    }
+
+    let credential = TwoFactorCredential::find_enabled_by_user_id(user.id(), connection)?;
+    if !verify(&credential, &json, &state.config.api_keys_encryption_key, connection)? {
+        return application::unprocessable("Two-factor code is invalid");
+    }
+
+    credential.disable(Some(user.id()), connection)?;
+    jlog!(Info, "User disabled two-factor authentication", {"id": user.id(), "email": user.email()});
+
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Changes to an enabled credential require a current code or one of the user's recovery codes
+fn verify(
+    credential: &TwoFactorCredential,
+    request: &TwoFactorCodeRequest,
+    encryption_key: &str,
+    connection: &PgConnection,
+) -> Result<bool, BigNeonError> {
+    match (&request.code, &request.recovery_code) {
+        (Some(code), _) => Ok(credential.verify_code(code, encryption_key, connection)?),
+        (None, Some(recovery_code)) => Ok(TwoFactorRecoveryCode::redeem(
+            credential.user_id,
+            recovery_code,
+            connection,
+        )?),
+        (None, None) => Ok(false),
+    }
+}
@@ -0,0 +1,80 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use log::Level::Info;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayUserSession {
+    pub id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_used_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    /// Whether this is the session the request was made with
+    pub current: bool,
+}
+
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    if user.is_delegated() {
+        return application::forbidden("Sessions cannot be managed using delegated credentials");
+    }
+
+    let sessions: Vec<DisplayUserSession> = UserSession::find_active_for_user(user.id(), connection.get())?
+        .into_iter()
+        .map(|session| DisplayUserSession {
+            id: session.id,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            last_used_at: session.last_used_at,
+            created_at: session.created_at,
+            current: user.session_id == Some(session.id),
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.is_delegated() {
+        return application::forbidden("Sessions cannot be managed using delegated credentials");
+    }
+    let session = UserSession::find(path.id, connection)?;
+    if session.user_id != user.id() {
+        return application::forbidden("User does not have access to this session");
+    }
+
+    session.revoke(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Signs the user out of every device including the one making the request
+pub fn destroy_all((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    if user.is_delegated() {
+        return application::forbidden("Sessions cannot be managed using delegated credentials");
+    }
+
+    UserSession::revoke_all_for_user(user.id(), Some(user.id()), connection.get())?;
+    jlog!(Info, "User signed out of all sessions", {"id": user.id(), "email": user.email()});
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Allows an admin to sign a user out of every device
+pub fn destroy_for_user(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let target_user = User::find(path.id, connection)?;
+
+    UserSession::revoke_all_for_user(target_user.id, Some(user.id()), connection)?;
+    jlog!(Info, "Admin signed user out of all sessions", {"id": target_user.id, "admin_id": user.id()});
+    Ok(HttpResponse::Ok().json(json!({})))
+}
@@ -1,6 +1,6 @@
 use actix_web;
 use actix_web::Responder;
-use actix_web::{http::StatusCode, HttpRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse, Path};
 use auth::user::User as AuthUser;
 use bigneon_db::prelude::*;
 use communications::mailers;
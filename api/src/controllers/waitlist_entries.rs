@@ -0,0 +1,43 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::{EventTicketPathParameters, PathParameters};
+
+pub fn index((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let mut entries: Vec<DisplayWaitlistEntry> = vec![];
+    for entry in WaitlistEntry::find_active_for_user(user.id(), connection)? {
+        entries.push(entry.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+pub fn create(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::unprocessable("Ticket type does not belong to this event");
+    }
+
+    let entry = WaitlistEntry::create(ticket_type.id, user.id()).commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(entry.for_display(connection)?))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let entry = WaitlistEntry::find(path.id, connection)?;
+    if entry.user_id != user.id() {
+        return application::forbidden("You do not have access to this waitlist entry");
+    }
+
+    entry.cancel(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
@@ -0,0 +1,164 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use models::{OrganizationWebhookPathParameters, PathParameters, WebPayload};
+use serde_json::{self, Value};
+use server::AppState;
+use std::collections::HashMap;
+use utils::webhook;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewWebhookRequest {
+    pub event_types: Vec<DomainEventTypes>,
+    pub webhook_url: String,
+    #[serde(default)]
+    pub import_historic_events: bool,
+    pub adapter: Option<WebhookAdapters>,
+    pub adapter_config: Option<Value>,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWebhooks, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(DomainEventPublisher::find_for_organization(
+        organization.id,
+        connection,
+    )?))
+}
+
+/// Creates the webhook, the response includes the signing secret used to verify its requests
+pub fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewWebhookRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWebhooks, &organization, connection)?;
+
+    let json = json.into_inner();
+    webhook::validate_public_webhook_url(&json.webhook_url)?;
+    let mut new_domain_event_publisher = DomainEventPublisher::create(
+        Some(organization.id),
+        json.event_types,
+        json.webhook_url,
+        json.import_historic_events,
+    );
+    new_domain_event_publisher.adapter = json.adapter;
+    new_domain_event_publisher.adapter_config = json.adapter_config;
+    let domain_event_publisher = new_domain_event_publisher.commit(connection)?;
+    Ok(HttpResponse::Created().json(with_signing_secret(&domain_event_publisher)?))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let domain_event_publisher = find_authorized(&path, &user, connection)?;
+    Ok(HttpResponse::Ok().json(domain_event_publisher))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrganizationWebhookPathParameters>,
+        Json<DomainEventPublisherEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let domain_event_publisher = find_authorized(&path, &user, connection)?;
+    if let Some(ref webhook_url) = json.webhook_url {
+        webhook::validate_public_webhook_url(webhook_url)?;
+    }
+    let domain_event_publisher = domain_event_publisher.update(&json, connection)?;
+    Ok(HttpResponse::Ok().json(domain_event_publisher))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let domain_event_publisher = find_authorized(&path, &user, connection)?;
+    domain_event_publisher.delete(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Replaces the signing secret, the response includes the new secret
+pub fn rotate_secret(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let domain_event_publisher = find_authorized(&path, &user, connection)?;
+    let domain_event_publisher = domain_event_publisher.rotate_signing_secret(connection)?;
+    Ok(HttpResponse::Ok().json(with_signing_secret(&domain_event_publisher)?))
+}
+
+/// Sends a test request to the webhook immediately, returning the recorded delivery without the response body
+pub fn ping(
+    (state, connection, path, user): (
+        State<AppState>,
+        Connection,
+        Path<OrganizationWebhookPathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let domain_event_publisher = find_authorized(&path, &user, connection)?;
+
+    let mut body: HashMap<String, Value> = HashMap::new();
+    body.insert("webhook_event_type".to_string(), json!("ping"));
+    body.insert("webhook_id".to_string(), json!(domain_event_publisher.id));
+    body.insert(
+        "organization_id".to_string(),
+        json!(domain_event_publisher.organization_id),
+    );
+    body.insert("timestamp".to_string(), json!(Utc::now().timestamp()));
+    let delivery = webhook::deliver(&domain_event_publisher, body, None, None, 1, connection, &state.config)?;
+    Ok(HttpResponse::Ok().json(DisplayWebhookDelivery::from(delivery)))
+}
+
+pub fn deliveries(
+    (connection, path, query, user): (
+        Connection,
+        Path<OrganizationWebhookPathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<WebPayload<DisplayWebhookDelivery>, BigNeonError> {
+    let connection = connection.get();
+    let domain_event_publisher = find_authorized(&path, &user, connection)?;
+    let deliveries =
+        domain_event_publisher.webhook_deliveries(query.page() as i64, query.limit() as i64, connection)?;
+    let deliveries = Payload::new(
+        deliveries.data.into_iter().map(|d| d.into()).collect(),
+        deliveries.paging,
+    );
+    Ok(WebPayload::new(StatusCode::OK, deliveries))
+}
+
+fn find_authorized(
+    path: &OrganizationWebhookPathParameters,
+    user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<DomainEventPublisher, BigNeonError> {
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWebhooks, &organization, connection)?;
+    let domain_event_publisher = DomainEventPublisher::find(path.webhook_id, connection)?;
+    if domain_event_publisher.organization_id != Some(organization.id) || domain_event_publisher.deleted_at.is_some() {
+        return Err(NotFoundError {}.into());
+    }
+    Ok(domain_event_publisher)
+}
+
+fn with_signing_secret(domain_event_publisher: &DomainEventPublisher) -> Result<Value, BigNeonError> {
+    let mut value = serde_json::to_value(domain_event_publisher)?;
+    value["signing_secret"] = json!(domain_event_publisher.signing_secret);
+    Ok(value)
+}
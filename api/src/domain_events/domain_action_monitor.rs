@@ -1,7 +1,10 @@
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
 use std::thread::JoinHandle;
 use std::time::Duration;
 use std::{cmp, thread};
@@ -12,18 +15,24 @@ use bigneon_db::prelude::*;
 use config::Config;
 use db::*;
 use domain_events::errors::DomainActionError;
+use domain_events::leader_election::LeaderElection;
+use domain_events::monitor_metrics::MonitorMetrics;
+use domain_events::notification_listener::NotificationListener;
 use domain_events::routing::{DomainActionExecutor, DomainActionRouter};
+use domain_events::worker_heartbeat::WorkerHeartbeat;
 use logging::*;
 use tokio::prelude::*;
 use tokio::runtime::current_thread;
 use tokio::runtime::Runtime;
 use tokio::timer::Timeout;
+use uuid::Uuid;
 
 pub struct DomainActionMonitor {
     config: Config,
     database: Database,
     worker_threads: Vec<(Sender<()>, JoinHandle<Result<(), DomainActionError>>)>,
     interval: u64,
+    stopped: Arc<AtomicBool>,
 }
 
 impl DomainActionMonitor {
@@ -33,9 +42,15 @@ impl DomainActionMonitor {
             database,
             worker_threads: vec![],
             interval: poll_period_in_secs,
+            stopped: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Set once any of the worker threads has stopped, after which the others are stopping too
+    pub fn stopped_flag(&self) -> Arc<AtomicBool> {
+        self.stopped.clone()
+    }
+
     pub fn run_til_empty(&self) -> Result<(), DomainActionError> {
         let router = DomainActionMonitor::create_router(&self.config);
 
@@ -46,12 +61,17 @@ impl DomainActionMonitor {
                 &self.database,
                 &router,
                 cmp::max(1, self.config.connection_pool.max / 2) as usize,
+                &self.config.domain_action_monitor.concurrency_limits,
+                None,
             )?;
 
             let mut runtime = current_thread::Runtime::new().unwrap();
 
             for (executor, domain_action, connection) in futures {
-                let timeout = Timeout::new(executor.execute(domain_action, connection), Duration::from_secs(55));
+                let future = executor
+                    .execute(domain_action, connection)
+                    .with_retry_backoff(self.config.domain_action_monitor.retry_backoff);
+                let timeout = Timeout::new(future, Duration::from_secs(55));
 
                 runtime
                     .block_on(timeout.or_else(|err| {
@@ -69,7 +89,11 @@ impl DomainActionMonitor {
         Ok(())
     }
 
-    fn find_and_publish_events(config: &Config, database: &Database) -> Result<usize, DomainActionError> {
+    fn find_and_publish_events(
+        config: &Config,
+        database: &Database,
+        metrics: &mut MonitorMetrics,
+    ) -> Result<usize, DomainActionError> {
         let conn = database.get_connection()?;
 
         let connection = conn.get();
@@ -111,6 +135,7 @@ impl DomainActionMonitor {
             }
             events_published += 1;
             conn.commit_transaction()?;
+            metrics.record_processed(event.created_at);
         }
 
         Ok(events_published)
@@ -122,18 +147,35 @@ impl DomainActionMonitor {
         interval: u64,
         rx: Receiver<()>,
     ) -> Result<(), DomainActionError> {
+        let mut listener = NotificationListener::new(&config, DOMAIN_EVENTS_NOTIFICATION_CHANNEL);
+        let mut metrics = MonitorMetrics::new("domain_events");
+        let mut heartbeat = WorkerHeartbeat::start(DomainActionWorkerTypes::Events, &database)?;
+        let mut leader_election = LeaderElection::new(&config);
+
         loop {
             if rx.try_recv().is_ok() {
                 jlog!(Info, "bigneon::domain_actions", "Stopping events processor", {});
                 break;
             }
 
+            // Only one instance publishes events so that publishers do not receive them twice
+            let is_leader = leader_election.is_leader();
+            heartbeat.beat_if_due(is_leader, &database);
+            if !is_leader {
+                metrics.record_wake_up(listener.wait(Duration::from_secs(interval)));
+                continue;
+            }
+
             // Domain Monitor main loop
-            if DomainActionMonitor::find_and_publish_events(&config, &database)? == 0 {
+            if DomainActionMonitor::find_and_publish_events(&config, &database, &mut metrics)? == 0 {
                 //                jlog!(Info, "bigneon::domain_events", "No events founds, sleeping", {});
-                thread::sleep(Duration::from_secs(interval));
+                metrics.record_wake_up(listener.wait(Duration::from_secs(interval)));
             }
+            metrics.report_if_due();
         }
+
+        leader_election.resign();
+        heartbeat.stop(&database)?;
         Ok(())
     }
 
@@ -148,6 +190,8 @@ impl DomainActionMonitor {
         database: &Database,
         router: &'a DomainActionRouter,
         limit: usize,
+        concurrency_limits: &HashMap<DomainActionTypes, usize>,
+        worker_id: Option<Uuid>,
     ) -> Result<Vec<(&'a dyn DomainActionExecutor, DomainAction, Connection)>, DomainActionError> {
         let connection = database.get_connection()?;
 
@@ -166,13 +210,20 @@ impl DomainActionMonitor {
         );
 
         let mut result = vec![];
+        let mut busy_counts = DomainAction::busy_counts_by_type(connection.get())?;
 
         // //Process actions
         let len = pending_actions.len();
-        for (index, action) in pending_actions.into_iter().enumerate() {
-            if limit < index {
+        for action in pending_actions {
+            if limit < result.len() {
                 break;
             }
+            if let Some(concurrency_limit) = concurrency_limits.get(&action.domain_action_type) {
+                if busy_counts.get(&action.domain_action_type).cloned().unwrap_or(0) >= *concurrency_limit {
+                    jlog!(Trace, "bigneon::domain_actions", "Concurrency limit reached for action type", {"id": action.id, "domain_action_type": action.domain_action_type, "concurrency_limit": concurrency_limit});
+                    continue;
+                }
+            }
             jlog! {Info, &format!("Pending Action: {}", action.domain_action_type), {"id":action.id, "domain_action_type": action.domain_action_type}};
             let connection = connection.get();
             let per_action_connection = match database.get_connection() {
@@ -183,14 +234,14 @@ impl DomainActionMonitor {
                     Info,
                     "bigneon::domain_actions",
                     "Hit connection pool maximum",
-                    { "number_of_connections_used": index, "pending_actions": len, "connection_error": e.description() }
+                    { "number_of_connections_used": result.len(), "pending_actions": len, "connection_error": e.description() }
                     );
 
                     break;
                 }
             };
 
-            match action.set_busy(60, connection) {
+            match action.set_busy_for_worker(60, worker_id, connection) {
                 Ok(_) => {}
                 Err(e) => match e.error_code {
                     ErrorCode::ConcurrencyError => {
@@ -200,6 +251,7 @@ impl DomainActionMonitor {
                     _ => return Err(e.into()),
                 },
             };
+            *busy_counts.entry(action.domain_action_type).or_insert(0) += 1;
             let command = router.get_executor_for(action.domain_action_type);
             if command.is_none() {
                 action.set_errored("Not executor has been created for this action type", &connection)?;
@@ -229,25 +281,38 @@ impl DomainActionMonitor {
         let router = DomainActionMonitor::create_router(&conf);
 
         let mut runtime = Runtime::new()?;
+        let mut listener = NotificationListener::new(&conf, DOMAIN_ACTIONS_NOTIFICATION_CHANNEL);
+        let mut metrics = MonitorMetrics::new("domain_actions");
+        let mut heartbeat = WorkerHeartbeat::start(DomainActionWorkerTypes::Actions, &database)?;
 
         loop {
             if rx.try_recv().is_ok() {
                 jlog!(Info, "bigneon::domain_actions", "Stopping actions processor", {});
                 break;
             }
+
+            if heartbeat.beat_if_due(false, &database) {
+                DomainActionMonitor::release_actions_from_dead_workers(&database)?;
+            }
             //Check for actions that are due to be processed
 
             let actions = DomainActionMonitor::find_actions(
                 &database,
                 &router,
                 cmp::max(1, conf.connection_pool.max / 2) as usize,
+                &conf.domain_action_monitor.concurrency_limits,
+                Some(heartbeat.id()),
             )?;
 
             if actions.len() == 0 {
-                thread::sleep(Duration::from_secs(interval));
+                metrics.record_wake_up(listener.wait(Duration::from_secs(interval)));
             } else {
                 for (command, action, connection) in actions {
-                    let timeout = Timeout::new(command.execute(action, connection), Duration::from_secs(55));
+                    metrics.record_processed(action.scheduled_at);
+                    let future = command
+                        .execute(action, connection)
+                        .with_retry_backoff(conf.domain_action_monitor.retry_backoff);
+                    let timeout = Timeout::new(future, Duration::from_secs(55));
 
                     runtime.spawn(timeout.or_else(|err| {
                         jlog! {Error,"bigneon::domain_actions", "Action:  failed", {"error": err.to_string()}};
@@ -255,7 +320,35 @@ impl DomainActionMonitor {
                     }));
                 }
             }
+            metrics.report_if_due();
+        }
+
+        // Let actions that have already been checked out finish rather than abandoning them
+        jlog!(
+            Info,
+            "bigneon::domain_actions",
+            "Waiting for running actions to finish",
+            {}
+        );
+        if runtime.shutdown_on_idle().wait().is_err() {
+            jlog!(
+                Error,
+                "bigneon::domain_actions",
+                "Could not wait for running actions to finish",
+                {}
+            );
+        }
+        heartbeat.stop(&database)?;
+        Ok(())
+    }
+
+    fn release_actions_from_dead_workers(database: &Database) -> Result<(), DomainActionError> {
+        let connection = database.get_connection()?;
+        let released = DomainActionWorker::release_actions_from_dead_workers(connection.get())?;
+        if released > 0 {
+            jlog!(Warn, "bigneon::domain_actions", "Released actions from dead workers", {"released": released});
         }
+        DomainActionWorker::delete_stale(connection.get())?;
         Ok(())
     }
 
@@ -273,6 +366,7 @@ impl DomainActionMonitor {
             let config = self.config.clone();
             let database = self.database.clone();
             let interval = self.interval;
+            let stopped = self.stopped.clone();
 
             // Create a worker thread to run domain actions
             self.worker_threads.push((
@@ -287,6 +381,7 @@ impl DomainActionMonitor {
                         e
                     });
 
+                    stopped.store(true, Ordering::SeqCst);
                     for signal in actions_stop_signals {
                         match signal.send(()) {
                             Ok(_) => (),
@@ -307,6 +402,7 @@ impl DomainActionMonitor {
             let interval = self.interval;
 
             let config = self.config.clone();
+            let stopped = self.stopped.clone();
 
             // Create a worker thread to publish events to subscribers
             self.worker_threads.push((
@@ -322,6 +418,7 @@ impl DomainActionMonitor {
                             e
                         });
 
+                    stopped.store(true, Ordering::SeqCst);
                     for signal in events_stop_signals {
                         match signal.send(()) {
                             Ok(_) => (),
@@ -349,9 +446,11 @@ impl DomainActionMonitor {
         }
     }
 
+    /// Signals the worker threads to stop and waits for them to finish their running work
     pub fn stop(&mut self) {
         for w in self.worker_threads.drain(..) {
-            w.0.send(()).unwrap();
+            // The thread may already have stopped if the other worker thread failed
+            let _ = w.0.send(());
             w.1.join().unwrap().unwrap();
         }
     }
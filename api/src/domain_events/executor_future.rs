@@ -1,5 +1,7 @@
 use bigneon_db::prelude::*;
 use chrono::prelude::*;
+use chrono::Duration;
+use config::RetryBackoff;
 use db::Connection;
 use errors::BigNeonError;
 use futures::Async;
@@ -13,6 +15,7 @@ pub struct ExecutorFuture {
     action: DomainAction,
     conn: Connection,
     inner: Box<dyn Future<Item = (), Error = BigNeonError>>,
+    retry_backoff: Option<RetryBackoff>,
 }
 
 unsafe impl Send for ExecutorFuture {}
@@ -28,8 +31,15 @@ impl ExecutorFuture {
             conn,
             started_at: Utc::now().naive_utc(),
             inner: future,
+            retry_backoff: None,
         }
     }
+
+    /// Failed actions are retried after the backoff delay instead of once their checkout expires
+    pub fn with_retry_backoff(mut self, retry_backoff: RetryBackoff) -> ExecutorFuture {
+        self.retry_backoff = Some(retry_backoff);
+        self
+    }
 }
 
 impl Future for ExecutorFuture {
@@ -70,7 +80,11 @@ impl Future for ExecutorFuture {
                    });
                 self.conn.rollback_transaction()?;
 
-                self.action.set_failed(&desc, self.conn.get())?;
+                let attempt_count = self.action.attempt_count;
+                let retry_at = self
+                    .retry_backoff
+                    .map(|backoff| Utc::now().naive_utc() + Duration::seconds(backoff.delay_in_secs(attempt_count)));
+                self.action.set_failed_and_retry_at(&desc, retry_at, self.conn.get())?;
                 return Err(e);
             }
         }
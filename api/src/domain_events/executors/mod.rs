@@ -1,21 +1,35 @@
 pub use self::broadcast_push_notification::*;
+pub use self::expire_waitlist_offer::*;
+pub use self::offer_waitlist_tickets::*;
+pub use self::process_hosted_checkout_ipn::*;
 pub use self::process_payment_ipn::*;
 pub use self::process_settlement_report::*;
+pub use self::process_stripe_webhook::*;
 pub use self::process_transfer_drip_event::*;
 pub use self::regenerate_drip_actions::*;
 pub use self::send_automatic_report_emails::*;
 pub use self::send_communication::*;
 pub use self::send_order_complete::*;
+pub use self::send_saved_report::*;
+pub use self::send_waitlist_offer::*;
+pub use self::send_webhook::*;
 pub use self::submit_sitemap_to_search_engines::*;
 pub use self::update_genres::*;
 
 mod broadcast_push_notification;
+mod expire_waitlist_offer;
+mod offer_waitlist_tickets;
+mod process_hosted_checkout_ipn;
 mod process_payment_ipn;
 mod process_settlement_report;
+mod process_stripe_webhook;
 mod process_transfer_drip_event;
 mod regenerate_drip_actions;
 mod send_automatic_report_emails;
 mod send_communication;
 mod send_order_complete;
+mod send_saved_report;
+mod send_waitlist_offer;
+mod send_webhook;
 mod submit_sitemap_to_search_engines;
 mod update_genres;
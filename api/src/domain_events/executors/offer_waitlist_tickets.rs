@@ -0,0 +1,37 @@
+use bigneon_db::prelude::*;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct OfferWaitlistTicketsExecutor {}
+
+impl DomainActionExecutor for OfferWaitlistTicketsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Offer waitlist tickets action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl OfferWaitlistTicketsExecutor {
+    pub fn new() -> OfferWaitlistTicketsExecutor {
+        OfferWaitlistTicketsExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let ticket_type_id = action.main_table_id.ok_or(ApplicationError::new(
+            "No ticket type id supplied in the action".to_string(),
+        ))?;
+        WaitlistEntry::offer_expired_reservations(ticket_type_id, conn)?;
+
+        Ok(())
+    }
+}
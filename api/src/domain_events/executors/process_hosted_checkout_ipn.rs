@@ -0,0 +1,122 @@
+use futures::future;
+use log::Level::{Debug, Error};
+use uuid::Uuid;
+
+use bigneon_db::prelude::*;
+use config::{Config, HostedCheckoutSettings};
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::ApplicationError;
+use errors::BigNeonError;
+use hosted_checkout::{Checkout, CheckoutStatus, HostedCheckoutClient};
+use payments::hosted_checkout::external_reference;
+
+pub struct ProcessHostedCheckoutIPNExecutor {
+    hosted_checkout: Option<HostedCheckoutSettings>,
+    validate_ipn: bool,
+    primary_currency: String,
+}
+
+impl DomainActionExecutor for ProcessHostedCheckoutIPNExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Hosted checkout IPN processor failed", {"action_id": action.id, "main_table_id":action.main_table_id,  "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessHostedCheckoutIPNExecutor {
+    pub fn new(config: &Config) -> ProcessHostedCheckoutIPNExecutor {
+        ProcessHostedCheckoutIPNExecutor {
+            hosted_checkout: config.hosted_checkout.clone(),
+            validate_ipn: config.validate_ipns,
+            primary_currency: config.primary_currency.clone(),
+        }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let mut checkout: Checkout = serde_json::from_value(action.payload.clone())?;
+
+        if self.validate_ipn {
+            let settings = self
+                .hosted_checkout
+                .as_ref()
+                .ok_or_else(|| ApplicationError::new("Hosted checkout is not configured".to_string()))?;
+            let client = HostedCheckoutClient::new(settings.api_key.clone(), settings.base_url.clone());
+            let validated_checkout = client.get_checkout(&checkout.id)?;
+            if validated_checkout.reference != checkout.reference {
+                return Err(ApplicationError::new("Invalid IPN, the reference has changed".to_string()).into());
+            }
+            checkout = validated_checkout;
+        }
+
+        let order_id = Uuid::parse_str(&checkout.reference)?;
+        let connection = conn.get();
+        let mut order = Order::find(order_id, connection)?;
+
+        // Checkouts are created in the order currency, one in any other currency is not a payment for this order
+        let currency = order.currency.as_ref().unwrap_or(&self.primary_currency);
+        if !checkout.currency.eq_ignore_ascii_case(currency) {
+            return Err(ApplicationError::new("Invalid IPN, the currency does not match the order".to_string()).into());
+        }
+
+        // Lock the order to prevent other processes from adding/updating payments
+        order.lock_version(connection)?;
+
+        // If expired attempt to refresh cart
+        if order.is_expired() && (order.status == OrderStatus::PendingPayment || order.status == OrderStatus::Draft) {
+            match order.try_refresh_expired_cart(None, connection) {
+                Ok(_) => jlog!(Debug, "IPN: refreshed expired cart", {"checkout_id": checkout.id, "order_id": order.id}),
+                Err(_) => jlog!(Debug, "IPN: Attempted to refresh expired cart but failed", {"checkout_id": checkout.id, "order_id": order.id}),
+            }
+        }
+
+        let external_reference = external_reference(&checkout.id);
+        let status = match checkout.status {
+            CheckoutStatus::Pending => PaymentStatus::Unpaid,
+            CheckoutStatus::Paid => PaymentStatus::Completed,
+            CheckoutStatus::Cancelled | CheckoutStatus::Expired => PaymentStatus::Cancelled,
+            CheckoutStatus::Refunded => PaymentStatus::Refunded,
+        };
+
+        jlog!(Debug, &format!("IPN status:{}", status), {"checkout_id": checkout.id, "order_id": order_id, "status": status});
+
+        let payment = match Payment::find_by_order(order_id, &external_reference, connection).optional()? {
+            Some(p) => p,
+            None => {
+                jlog!(Debug, "IPN: No payment found, creating new payment", {"checkout_id": checkout.id, "order_id": order_id, "status": status});
+
+                order.add_provider_payment(
+                    Some(external_reference.clone()),
+                    PaymentProviders::HostedCheckout,
+                    None,
+                    checkout.amount_in_cents,
+                    status,
+                    None,
+                    action.payload.clone(),
+                    connection,
+                )?
+            }
+        };
+
+        match status {
+            PaymentStatus::Completed => {
+                // The IPN can come in before the customer has been returned to the success page
+                if order.status == OrderStatus::Draft {
+                    payment.mark_pending_ipn(None, connection)?;
+                }
+                payment.update_amount(None, checkout.amount_in_cents, connection)?;
+                payment.mark_complete(json!(checkout), None, connection)?;
+            }
+            PaymentStatus::Cancelled => payment.mark_cancelled(json!(checkout), None, connection)?,
+            _ => payment.add_ipn(status, json!(checkout), None, connection)?,
+        }
+
+        Ok(())
+    }
+}
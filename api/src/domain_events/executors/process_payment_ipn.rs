@@ -6,17 +6,26 @@ use bigneon_db::prelude::*;
 use config::Config;
 use db::Connection;
 use domain_events::executor_future::ExecutorFuture;
+use domain_events::executors::ProcessStripeWebhookExecutor;
 use domain_events::routing::DomainActionExecutor;
 use errors::ApplicationError;
 use errors::BigNeonError;
 use globee::GlobeeClient;
 use globee::GlobeeIpnRequest;
 
+/// IPN received from a payment provider other than Globee, whose IPNs are stored as received
+#[derive(Deserialize, Serialize)]
+pub struct ProviderIPN {
+    pub provider: PaymentProviders,
+    pub data: serde_json::Value,
+}
+
 pub struct ProcessPaymentIPNExecutor {
     globee_api_key: String,
     globee_base_url: String,
     validate_ipn: bool,
     api_keys_encryption_key: String,
+    stripe_webhook: ProcessStripeWebhookExecutor,
 }
 
 impl DomainActionExecutor for ProcessPaymentIPNExecutor {
@@ -38,10 +47,20 @@ impl ProcessPaymentIPNExecutor {
             globee_base_url: config.globee_base_url.clone(),
             validate_ipn: config.validate_ipns,
             api_keys_encryption_key: config.api_keys_encryption_key.clone(),
+            stripe_webhook: ProcessStripeWebhookExecutor::new(config),
         }
     }
 
     pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        if let Ok(ipn) = serde_json::from_value::<ProviderIPN>(action.payload.clone()) {
+            return match ipn.provider {
+                PaymentProviders::Stripe => self
+                    .stripe_webhook
+                    .process_event(serde_json::from_value(ipn.data)?, conn),
+                _ => Err(ApplicationError::new(format!("IPNs from {} are not supported", ipn.provider)).into()),
+            };
+        }
+
         let mut ipn: GlobeeIpnRequest = serde_json::from_value(action.payload.clone())?;
         if ipn.custom_payment_id.is_none() {
             return Err(
@@ -0,0 +1,162 @@
+use futures::future;
+use log::Level::{Debug, Error, Info};
+
+use bigneon_db::prelude::*;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::BigNeonError;
+use stripe::{Dispute, PaymentIntent, PaymentIntentStatus, StripeClient, WebhookEvent};
+
+pub struct ProcessStripeWebhookExecutor {
+    stripe_secret_key: String,
+    nullify_tickets_on_chargeback: bool,
+}
+
+impl DomainActionExecutor for ProcessStripeWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Stripe webhook processor failed", {"action_id": action.id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessStripeWebhookExecutor {
+    pub fn new(config: &Config) -> ProcessStripeWebhookExecutor {
+        ProcessStripeWebhookExecutor {
+            stripe_secret_key: config.stripe_secret_key.clone(),
+            nullify_tickets_on_chargeback: config.nullify_tickets_on_chargeback,
+        }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        self.process_event(serde_json::from_value(action.payload.clone())?, conn)
+    }
+
+    pub fn process_event(&self, event: WebhookEvent, conn: &Connection) -> Result<(), BigNeonError> {
+        jlog!(Debug, "Processing Stripe webhook", {"event_id": &event.id, "type": &event.event_type});
+
+        match event.event_type.as_str() {
+            "payment_intent.amount_capturable_updated"
+            | "payment_intent.succeeded"
+            | "payment_intent.payment_failed"
+            | "payment_intent.canceled" => {
+                let payment_intent = PaymentIntent::from_raw(&event.data.object.to_string())?;
+                self.process_payment_intent(&event, &payment_intent, conn)
+            }
+            "charge.dispute.created" | "charge.dispute.funds_withdrawn" => {
+                let dispute = Dispute::from_raw(&event.data.object.to_string())?;
+                self.process_dispute(&event, &dispute, conn)
+            }
+            _ => {
+                jlog!(Debug, "Ignoring Stripe webhook", {"event_id": &event.id, "type": &event.event_type});
+                Ok(())
+            }
+        }
+    }
+
+    /// Payments awaiting confirmation (e.g. 3-D Secure) are completed or failed once the customer
+    /// has finished with Stripe. Payments in any other status were handled during checkout.
+    fn process_payment_intent(
+        &self,
+        event: &WebhookEvent,
+        payment_intent: &PaymentIntent,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let connection = conn.get();
+        let payment = match Payment::find_by_external_reference(
+            PaymentProviders::Stripe,
+            &payment_intent.id,
+            connection,
+        )
+        .optional()?
+        {
+            Some(p) => p,
+            None => {
+                jlog!(Info, "Stripe webhook: No payment found for payment intent", {"event_id": &event.id, "payment_intent_id": &payment_intent.id});
+                return Ok(());
+            }
+        };
+        if payment.status != PaymentStatus::PendingConfirmation {
+            return Ok(());
+        }
+
+        // Lock the order to prevent other processes from adding/updating payments
+        let mut order = Order::find(payment.order_id, connection)?;
+        order.lock_version(connection)?;
+
+        if order.is_expired() && order.status == OrderStatus::PendingPayment {
+            match order.try_refresh_expired_cart(None, connection) {
+                Ok(_) => jlog!(Debug, "Stripe webhook: refreshed expired cart", {"payment_intent_id": &payment_intent.id, "order_id": order.id}),
+                Err(_) => jlog!(Debug, "Stripe webhook: Attempted to refresh expired cart but failed", {"payment_intent_id": &payment_intent.id, "order_id": order.id}),
+            }
+        }
+
+        let client = StripeClient::new(self.stripe_secret_key.clone());
+        match payment_intent.status {
+            PaymentIntentStatus::RequiresCapture | PaymentIntentStatus::Succeeded => {
+                let raw_data = if payment_intent.status == PaymentIntentStatus::RequiresCapture {
+                    client.capture_payment_intent(&payment_intent.id)?.to_json()
+                } else {
+                    payment_intent.to_json()
+                };
+                if let Err(e) = payment.mark_complete(serde_json::from_str(&raw_data)?, None, connection) {
+                    client.refund(&payment_intent.id)?;
+                    return Err(e.into());
+                }
+            }
+            PaymentIntentStatus::RequiresPaymentMethod | PaymentIntentStatus::Canceled => {
+                payment.mark_failed(event.data.object.clone(), None, connection)?
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Disputed payments are charged back to the organizations of the order
+    fn process_dispute(&self, event: &WebhookEvent, dispute: &Dispute, conn: &Connection) -> Result<(), BigNeonError> {
+        let connection = conn.get();
+        // Charges created by payment intents are stored against the payment intent
+        let mut payment = None;
+        for external_reference in dispute.payment_intent.iter().chain(Some(&dispute.charge)) {
+            payment = Payment::find_by_external_reference(PaymentProviders::Stripe, external_reference, connection)
+                .optional()?;
+            if payment.is_some() {
+                break;
+            }
+        }
+        let payment = match payment {
+            Some(p) => p,
+            None => {
+                jlog!(Info, "Stripe webhook: No payment found for dispute", {"event_id": &event.id, "dispute_id": &dispute.id, "charge_id": &dispute.charge});
+                return Ok(());
+            }
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentProviderIPN,
+            format!("Payment disputed: {}", dispute.reason),
+            Tables::Payments,
+            Some(payment.id),
+            None,
+            Some(event.data.object.clone()),
+        )
+        .commit(connection)?;
+
+        let mut order = Order::find(payment.order_id, connection)?;
+        let note = format!(
+            "Chargeback for order {} (Stripe dispute {}, reason: {})",
+            order.id, dispute.id, dispute.reason
+        );
+        let adjustments = order.record_chargeback(note, self.nullify_tickets_on_chargeback, None, connection)?;
+        jlog!(Info, "Stripe webhook: Recorded chargeback", {"dispute_id": &dispute.id, "order_id": order.id, "settlement_adjustment_count": adjustments.len()});
+
+        Ok(())
+    }
+}
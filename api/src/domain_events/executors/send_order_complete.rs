@@ -43,8 +43,13 @@ impl SendOrderCompleteExecutor {
         let mut wallet_id_per_asset: HashMap<Uuid, Uuid> = HashMap::new();
 
         for oi in order.items(conn)? {
+            // Season passes and their fees are not tied to an event or tokens
+            let event_id = match oi.event_id {
+                Some(event_id) => event_id,
+                None => continue,
+            };
             let tickets = TicketInstance::find_for_order_item(oi.id, conn)?;
-            let event = Event::find(oi.event_id.unwrap(), conn)?;
+            let event = Event::find(event_id, conn)?;
 
             let wallet = Wallet::find_default_for_organization(event.organization_id, conn)?;
             for ticket in tickets {
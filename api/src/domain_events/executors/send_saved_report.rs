@@ -0,0 +1,112 @@
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use diesel::Connection as DieselConnection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use utils::spreadsheet::{self, ExportLocale};
+
+pub struct SendSavedReportExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendSavedReportExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send saved report action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendSavedReportExecutor {
+    pub fn new(config: Config) -> SendSavedReportExecutor {
+        SendSavedReportExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let saved_report_id = action.main_table_id.ok_or(ApplicationError::new(
+            "No saved report id supplied in the action".to_string(),
+        ))?;
+        // Deleted reports are not found and have nothing left to send
+        let saved_report = match SavedReport::find(saved_report_id, conn).optional()? {
+            Some(saved_report) => saved_report,
+            None => return Ok(()),
+        };
+        let organization = saved_report.organization(conn)?;
+
+        let table = saved_report.run(conn)?;
+        let content = spreadsheet::render(saved_report.format, &table, ExportLocale::default())?;
+        let attachment = CommAttachment::new(
+            attachment_filename(&saved_report, &organization)?,
+            spreadsheet::content_type(saved_report.format).to_string(),
+            &content,
+        );
+
+        let mut recipients = vec![];
+        let mut failed_recipients = vec![];
+        for subscriber in saved_report.subscribers(conn)? {
+            // Subscribers that were already sent this run are skipped if the action is run again
+            if subscriber.was_sent_run(action.scheduled_at) {
+                continue;
+            }
+            // Each subscriber is sent the report within a savepoint so that a failure does not abort the others
+            match conn.transaction::<_, BigNeonError, _>(|| {
+                mailers::reports::saved_report(
+                    subscriber.email.clone(),
+                    &saved_report,
+                    &organization,
+                    attachment.clone(),
+                    &self.config,
+                    conn,
+                )?;
+                subscriber.mark_sent(conn)?;
+                Ok(())
+            }) {
+                Ok(_) => recipients.push(subscriber.email),
+                Err(error) => {
+                    jlog!(Error, "Failed to send saved report to subscriber", {"saved_report_id": saved_report.id, "email": subscriber.email, "error": error.to_string()});
+                    failed_recipients.push(subscriber.email);
+                }
+            }
+        }
+
+        saved_report.mark_sent(action.id, recipients, failed_recipients, conn)?;
+
+        Ok(())
+    }
+}
+
+/// Report name followed by the date it was run in the organization's timezone, e.g. `Weekly Sales 2020-02-13.csv`
+fn attachment_filename(saved_report: &SavedReport, organization: &Organization) -> Result<String, BigNeonError> {
+    let name: String = saved_report
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let date = organization
+        .timezone()?
+        .from_utc_datetime(&Utc::now().naive_utc())
+        .format("%Y-%m-%d");
+    Ok(format!(
+        "{} {}.{}",
+        name.trim(),
+        date,
+        spreadsheet::file_extension(saved_report.format)
+    ))
+}
@@ -0,0 +1,56 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct SendWaitlistOfferExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendWaitlistOfferExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send waitlist offer action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendWaitlistOfferExecutor {
+    pub fn new(config: Config) -> SendWaitlistOfferExecutor {
+        SendWaitlistOfferExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let entry = WaitlistEntry::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No waitlist entry id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+
+        // Offer was withdrawn or has already lapsed
+        if entry.status != WaitlistEntryStatus::Offered {
+            return Ok(());
+        }
+
+        let hold = entry
+            .hold(conn)?
+            .ok_or(ApplicationError::new("Waitlist offer has no hold".to_string()))?;
+        let user = User::find(entry.user_id, conn)?;
+        if let Some(email) = user.email {
+            mailers::waitlist::offer(email, &entry, &hold, &self.config, conn)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use bigneon_db::prelude::*;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::{Error, Trace};
+use serde_json::{self, Value};
+use std::collections::HashMap;
+use utils::webhook;
+
+pub struct SendWebhookExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send webhook action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendWebhookExecutor {
+    pub fn new(config: Config) -> SendWebhookExecutor {
+        SendWebhookExecutor { config }
+    }
+
+    /// Delivers the webhook and records the attempt. A failed delivery is retried by queueing the
+    /// next attempt rather than failing the action, so that the attempt is still recorded.
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let domain_event_publisher_id = action.main_table_id.ok_or(ApplicationError::new(
+            "No domain event publisher id supplied in the action".to_string(),
+        ))?;
+        let domain_event_publisher = DomainEventPublisher::find(domain_event_publisher_id, conn)?;
+        // Deleted publishers no longer receive webhooks
+        if domain_event_publisher.deleted_at.is_some() {
+            return Ok(());
+        }
+        let payload: SendWebhookPayload = serde_json::from_value(action.payload.clone())?;
+
+        if self.config.environment == Environment::Test || self.config.block_external_comms {
+            jlog!(Trace, "Blocked webhook", { "domain_event_publisher_id": domain_event_publisher.id, "payload": &payload.body });
+            return Ok(());
+        }
+
+        let body: HashMap<String, Value> = serde_json::from_value(payload.body.clone())?;
+        let delivery = webhook::deliver(
+            &domain_event_publisher,
+            body,
+            action.domain_event_id,
+            Some(action.id),
+            payload.attempt,
+            conn,
+            &self.config,
+        )?;
+
+        if !delivery.succeeded {
+            if payload.attempt < WEBHOOK_MAX_ATTEMPTS {
+                domain_event_publisher.queue_webhook(
+                    action.domain_event_id,
+                    payload.body,
+                    payload.attempt + 1,
+                    conn,
+                )?;
+            } else {
+                jlog!(Error, "bigneon::domain_actions", "Webhook delivery attempts exceeded", {
+                    "domain_event_publisher_id": domain_event_publisher.id,
+                    "domain_event_id": action.domain_event_id,
+                    "webhook_delivery_id": delivery.id
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
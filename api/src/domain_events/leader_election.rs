@@ -0,0 +1,75 @@
+use bigneon_db::prelude::*;
+use config::Config;
+use diesel::{Connection, PgConnection};
+use log::Level::*;
+use logging::*;
+
+/// Elects a single worker across all instances to publish domain events to actions, using a
+/// Postgres advisory lock. The lock belongs to a dedicated connection rather than one from the
+/// pool so that it is released by the database as soon as the process holding it dies.
+pub struct LeaderElection {
+    database_url: String,
+    connection: Option<PgConnection>,
+    is_leader: bool,
+}
+
+impl LeaderElection {
+    pub fn new(config: &Config) -> LeaderElection {
+        LeaderElection {
+            database_url: config.database_url.clone(),
+            connection: None,
+            is_leader: false,
+        }
+    }
+
+    /// Checks that leadership is still held, or attempts to become the leader if not
+    pub fn is_leader(&mut self) -> bool {
+        if self.connection.is_none() {
+            match PgConnection::establish(&self.database_url) {
+                Ok(connection) => self.connection = Some(connection),
+                Err(e) => {
+                    jlog!(Error, "bigneon::domain_actions", "Could not connect for leader election", {"error": e.to_string()});
+                    self.is_leader = false;
+                    return false;
+                }
+            }
+        }
+
+        let result = {
+            let connection = self.connection.as_ref().unwrap();
+            if self.is_leader {
+                DomainActionWorker::holds_event_publisher_leadership(connection)
+            } else {
+                DomainActionWorker::try_acquire_event_publisher_leadership(connection)
+            }
+        };
+
+        match result {
+            Ok(is_leader) => {
+                if is_leader != self.is_leader {
+                    jlog!(Info, "bigneon::domain_actions", "Event publisher leadership changed", {"is_leader": is_leader});
+                }
+                self.is_leader = is_leader;
+            }
+            Err(e) => {
+                jlog!(Error, "bigneon::domain_actions", "Leader election failed", {"error": e.to_string()});
+                // Closing the connection releases the lock if it is still held
+                self.connection = None;
+                self.is_leader = false;
+            }
+        }
+        self.is_leader
+    }
+
+    /// Gives up leadership so another instance can take over without waiting for this one to exit
+    pub fn resign(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            if self.is_leader {
+                if let Err(e) = DomainActionWorker::release_event_publisher_leadership(&connection) {
+                    jlog!(Error, "bigneon::domain_actions", "Could not release event publisher leadership", {"error": e.to_string()});
+                }
+            }
+        }
+        self.is_leader = false;
+    }
+}
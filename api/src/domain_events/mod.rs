@@ -1,7 +1,13 @@
 pub use self::domain_action_monitor::*;
+pub use self::monitor_metrics::*;
+pub use self::notification_listener::*;
 
 mod domain_action_monitor;
 mod errors;
 mod executor_future;
 pub mod executors;
+mod leader_election;
+mod monitor_metrics;
+mod notification_listener;
 mod routing;
+mod worker_heartbeat;
@@ -0,0 +1,102 @@
+use chrono::prelude::*;
+use domain_events::notification_listener::WakeUpReason;
+use log::Level::*;
+use logging::*;
+use std::cmp;
+use std::time::{Duration, Instant};
+
+/// How often the aggregated metrics are logged and reset
+const REPORTING_PERIOD_IN_SECS: u64 = 60;
+
+/// Latency and throughput of domain event publishing or domain action processing. Latency is
+/// measured from when the work became due (event inserted, action scheduled) to when it was picked up.
+pub struct MonitorMetrics {
+    name: &'static str,
+    period_started_at: Instant,
+    processed: u64,
+    total_latency_ms: i64,
+    max_latency_ms: i64,
+    notified_wake_ups: u64,
+    polled_wake_ups: u64,
+}
+
+impl MonitorMetrics {
+    pub fn new(name: &'static str) -> MonitorMetrics {
+        MonitorMetrics {
+            name,
+            period_started_at: Instant::now(),
+            processed: 0,
+            total_latency_ms: 0,
+            max_latency_ms: 0,
+            notified_wake_ups: 0,
+            polled_wake_ups: 0,
+        }
+    }
+
+    pub fn record_wake_up(&mut self, reason: WakeUpReason) {
+        match reason {
+            WakeUpReason::Notified => self.notified_wake_ups += 1,
+            WakeUpReason::Polled => self.polled_wake_ups += 1,
+        }
+    }
+
+    pub fn record_processed(&mut self, due_at: NaiveDateTime) {
+        // Clock differences between servers can make work appear to be picked up before it was due
+        let latency_ms = cmp::max(0, (Utc::now().naive_utc() - due_at).num_milliseconds());
+        self.processed += 1;
+        self.total_latency_ms += latency_ms;
+        self.max_latency_ms = cmp::max(self.max_latency_ms, latency_ms);
+    }
+
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    pub fn average_latency_ms(&self) -> i64 {
+        if self.processed == 0 {
+            return 0;
+        }
+        self.total_latency_ms / self.processed as i64
+    }
+
+    pub fn max_latency_ms(&self) -> i64 {
+        self.max_latency_ms
+    }
+
+    pub fn notified_wake_ups(&self) -> u64 {
+        self.notified_wake_ups
+    }
+
+    pub fn polled_wake_ups(&self) -> u64 {
+        self.polled_wake_ups
+    }
+
+    /// Throughput over the current reporting period
+    pub fn processed_per_minute(&self) -> f64 {
+        let elapsed = self.period_started_at.elapsed();
+        let elapsed_secs = elapsed.as_secs() as f64 + elapsed.subsec_millis() as f64 / 1000.0;
+        if elapsed_secs == 0.0 {
+            return 0.0;
+        }
+        self.processed as f64 * 60.0 / elapsed_secs
+    }
+
+    /// Logs the metrics once the reporting period has passed and starts a new period
+    pub fn report_if_due(&mut self) {
+        if self.period_started_at.elapsed() < Duration::from_secs(REPORTING_PERIOD_IN_SECS) {
+            return;
+        }
+
+        jlog!(Info, "bigneon::domain_actions", "Domain monitor metrics", {
+            "monitor": self.name,
+            "processed": self.processed,
+            "processed_per_minute": self.processed_per_minute(),
+            "average_latency_ms": self.average_latency_ms(),
+            "max_latency_ms": self.max_latency_ms,
+            "notified_wake_ups": self.notified_wake_ups,
+            "polled_wake_ups": self.polled_wake_ups
+        });
+
+        *self = MonitorMetrics::new(self.name);
+    }
+}
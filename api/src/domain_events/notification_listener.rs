@@ -0,0 +1,100 @@
+use config::Config;
+use fallible_iterator::FallibleIterator;
+use log::Level::*;
+use logging::*;
+use postgres::{Connection, TlsMode};
+use std::cmp;
+use std::thread;
+use std::time::Duration;
+
+/// Used in place of the poll period while not listening, matching the polling used before notifications
+const DISCONNECTED_POLL_PERIOD_IN_SECS: u64 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WakeUpReason {
+    Notified,
+    Polled,
+}
+
+/// Blocks until Postgres notifies a channel, so that inserted domain events and actions are picked
+/// up immediately rather than at the end of the poll period. Polling remains the fallback for
+/// scheduled work and for when the listening connection cannot be established.
+pub struct NotificationListener {
+    database_url: String,
+    channel: &'static str,
+    enabled: bool,
+    connection: Option<Connection>,
+}
+
+impl NotificationListener {
+    pub fn new(config: &Config, channel: &'static str) -> NotificationListener {
+        let mut listener = NotificationListener {
+            database_url: config.database_url.clone(),
+            channel,
+            enabled: config.domain_action_monitor.listen_for_notifications,
+            connection: None,
+        };
+        // Listen before the first query so no notifications are missed between it and the first wait
+        listener.connect();
+        listener
+    }
+
+    pub fn is_listening(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    pub fn wait(&mut self, poll_period: Duration) -> WakeUpReason {
+        if !self.is_listening() && !self.connect() {
+            thread::sleep(cmp::min(
+                poll_period,
+                Duration::from_secs(DISCONNECTED_POLL_PERIOD_IN_SECS),
+            ));
+            return WakeUpReason::Polled;
+        }
+
+        let result = {
+            let connection = self.connection.as_ref().unwrap();
+            let notifications = connection.notifications();
+            notifications.timeout_iter(poll_period).next().and_then(|notification| {
+                if notification.is_some() {
+                    // Drain notifications that have already arrived, one wake up covers them all
+                    while notifications.iter().next()?.is_some() {}
+                }
+                Ok(notification)
+            })
+        };
+
+        match result {
+            Ok(Some(_)) => WakeUpReason::Notified,
+            Ok(None) => WakeUpReason::Polled,
+            Err(e) => {
+                jlog!(Warn, "bigneon::domain_actions", "Lost notification listener connection", {"channel": self.channel, "error": e.to_string()});
+                self.connection = None;
+                WakeUpReason::Polled
+            }
+        }
+    }
+
+    fn connect(&mut self) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let result = Connection::connect(self.database_url.as_str(), TlsMode::None).and_then(|connection| {
+            connection.execute(&format!("LISTEN {}", self.channel), &[])?;
+            Ok(connection)
+        });
+
+        match result {
+            Ok(connection) => {
+                jlog!(Info, "bigneon::domain_actions", "Listening for notifications", {"channel": self.channel});
+                self.connection = Some(connection);
+                true
+            }
+            Err(e) => {
+                jlog!(Warn, "bigneon::domain_actions", "Could not listen for notifications, polling instead", {"channel": self.channel, "error": e.to_string()});
+                false
+            }
+        }
+    }
+}
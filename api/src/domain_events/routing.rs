@@ -48,10 +48,17 @@ impl DomainActionRouter {
             match action_type {
                 Communication => Box::new(SendCommunicationExecutor::new(conf)),
                 BroadcastPushNotification => Box::new(BroadcastPushNotificationExecutor::new(&conf)),
+                ExpireWaitlistOffer => Box::new(ExpireWaitlistOfferExecutor::new()),
+                HostedCheckoutIPN => Box::new(ProcessHostedCheckoutIPNExecutor::new(&conf)),
+                OfferWaitlistTickets => Box::new(OfferWaitlistTicketsExecutor::new()),
 
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
                 RegenerateDripActions => Box::new(RegenerateDripActionsExecutor::new(conf)),
                 SendPurchaseCompletedCommunication => Box::new(SendOrderCompleteExecutor::new(conf)),
+                SendSavedReport => Box::new(SendSavedReportExecutor::new(conf)),
+                SendWaitlistOfferCommunication => Box::new(SendWaitlistOfferExecutor::new(conf)),
+                SendWebhook => Box::new(SendWebhookExecutor::new(conf)),
+                StripeWebhook => Box::new(ProcessStripeWebhookExecutor::new(&conf)),
                 UpdateGenres => Box::new(UpdateGenresExecutor::new()),
                 ProcessSettlementReport => Box::new(ProcessSettlementReportExecutor::new(conf)),
                 ProcessTransferDrip => Box::new(ProcessTransferDripEventExecutor::new(conf)),
@@ -71,6 +78,15 @@ impl DomainActionRouter {
         self.add_executor(BroadcastPushNotification, find_executor(BroadcastPushNotification))
             .expect("Configuration error");
 
+        self.add_executor(ExpireWaitlistOffer, find_executor(ExpireWaitlistOffer))
+            .expect("Configuration error");
+
+        self.add_executor(HostedCheckoutIPN, find_executor(HostedCheckoutIPN))
+            .expect("Configuration error");
+
+        self.add_executor(OfferWaitlistTickets, find_executor(OfferWaitlistTickets))
+            .expect("Configuration error");
+
         self.add_executor(PaymentProviderIPN, find_executor(PaymentProviderIPN))
             .expect("Configuration error");
 
@@ -83,6 +99,9 @@ impl DomainActionRouter {
         self.add_executor(RegenerateDripActions, find_executor(RegenerateDripActions))
             .expect("Configuration error");
 
+        self.add_executor(StripeWebhook, find_executor(StripeWebhook))
+            .expect("Configuration error");
+
         self.add_executor(UpdateGenres, find_executor(UpdateGenres))
             .expect("Configuration error");
 
@@ -94,6 +113,15 @@ impl DomainActionRouter {
             find_executor(SendPurchaseCompletedCommunication),
         )
         .expect("Configuration error");
+        self.add_executor(SendSavedReport, find_executor(SendSavedReport))
+            .expect("Configuration error");
+        self.add_executor(
+            SendWaitlistOfferCommunication,
+            find_executor(SendWaitlistOfferCommunication),
+        )
+        .expect("Configuration error");
+        self.add_executor(SendWebhook, find_executor(SendWebhook))
+            .expect("Configuration error");
         self.add_executor(
             SubmitSitemapToSearchEngines,
             find_executor(SubmitSitemapToSearchEngines),
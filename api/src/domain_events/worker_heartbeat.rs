@@ -0,0 +1,79 @@
+use bigneon_db::prelude::*;
+use db::Database;
+use domain_events::errors::DomainActionError;
+use log::Level::*;
+use logging::*;
+use std::env;
+use std::process;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Heartbeats are sent well within `WORKER_HEARTBEAT_TIMEOUT_SECONDS` so a busy database does not
+/// cause healthy workers to be treated as dead
+const HEARTBEAT_INTERVAL_IN_SECS: u64 = 10;
+
+/// Registers a monitor thread as a `DomainActionWorker` and keeps its heartbeat up to date
+pub struct WorkerHeartbeat {
+    worker: DomainActionWorker,
+    last_heartbeat: Instant,
+}
+
+impl WorkerHeartbeat {
+    pub fn start(
+        worker_type: DomainActionWorkerTypes,
+        database: &Database,
+    ) -> Result<WorkerHeartbeat, DomainActionError> {
+        let connection = database.get_connection()?;
+        let name = format!(
+            "{}:{}",
+            env::var("HOSTNAME").unwrap_or_else(|_| "unknown".to_string()),
+            process::id()
+        );
+        let worker = DomainActionWorker::create(name, worker_type).commit(connection.get())?;
+        jlog!(Info, "bigneon::domain_actions", "Worker started", {"worker_id": worker.id, "name": &worker.name, "worker_type": worker.worker_type});
+
+        Ok(WorkerHeartbeat {
+            worker,
+            last_heartbeat: Instant::now(),
+        })
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.worker.id
+    }
+
+    /// Sends a heartbeat if one is due or the worker's leadership has changed, returning whether
+    /// one was sent. Failures are logged rather than returned, other workers will release this
+    /// worker's actions if it is unable to send heartbeats for long enough.
+    pub fn beat_if_due(&mut self, is_leader: bool, database: &Database) -> bool {
+        if self.worker.is_leader == is_leader
+            && self.last_heartbeat.elapsed() < Duration::from_secs(HEARTBEAT_INTERVAL_IN_SECS)
+        {
+            return false;
+        }
+
+        let result = database
+            .get_connection()
+            .map_err(DomainActionError::from)
+            .and_then(|connection| Ok(self.worker.heartbeat(is_leader, connection.get())?));
+
+        match result {
+            Ok(worker) => {
+                self.worker = worker;
+                self.last_heartbeat = Instant::now();
+                true
+            }
+            Err(e) => {
+                jlog!(Error, "bigneon::domain_actions", "Could not send worker heartbeat", {"worker_id": self.worker.id, "error": e.to_string()});
+                false
+            }
+        }
+    }
+
+    pub fn stop(&self, database: &Database) -> Result<(), DomainActionError> {
+        let connection = database.get_connection()?;
+        self.worker.set_stopped(connection.get())?;
+        jlog!(Info, "bigneon::domain_actions", "Worker stopped", {"worker_id": self.worker.id});
+        Ok(())
+    }
+}
@@ -8,7 +8,9 @@ use errors::AuthError;
 use errors::*;
 use facebook::prelude::FacebookError;
 use globee::GlobeeError;
+use hosted_checkout::HostedCheckoutError;
 use jwt::errors::Error as JwtError;
+use oidc::OidcError;
 use payments::PaymentProcessorError;
 use r2d2;
 use reqwest;
@@ -16,6 +18,7 @@ use reqwest::header::ToStrError as ReqwestToStrError;
 use serde_json::Error as SerdeError;
 use std::error::Error;
 use std::fmt;
+use stripe::StripeError;
 use tari_client::TariError;
 use twilio::TwilioError;
 use url;
@@ -47,9 +50,12 @@ error_conversion!(ParseError);
 error_conversion!(PaymentProcessorError);
 error_conversion!(ReqwestToStrError);
 error_conversion!(SerdeError);
+error_conversion!(StripeError);
 error_conversion!(TariError);
 error_conversion!(UuidParseError);
 error_conversion!(GlobeeError);
+error_conversion!(HostedCheckoutError);
+error_conversion!(OidcError);
 error_conversion!(BranchError);
 error_conversion!(FacebookError);
 error_conversion!(chrono::ParseError);
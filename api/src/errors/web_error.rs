@@ -7,7 +7,9 @@ use diesel::result::Error as DieselError;
 use errors::*;
 use facebook::prelude::FacebookError;
 use globee::GlobeeError;
+use hosted_checkout::HostedCheckoutError;
 use jwt::errors::{Error as JwtError, ErrorKind as JwtErrorKind};
+use oidc::OidcError;
 use payments::PaymentProcessorError;
 use r2d2;
 use reqwest::header::ToStrError as ReqwestToStrError;
@@ -90,6 +92,28 @@ impl ConvertToWebError for GlobeeError {
     }
 }
 
+impl ConvertToWebError for HostedCheckoutError {
+    fn to_response(&self) -> HttpResponse {
+        error!("Hosted checkout error: {}", self);
+        internal_error("Internal error")
+    }
+}
+
+impl ConvertToWebError for OidcError {
+    fn to_response(&self) -> HttpResponse {
+        match self {
+            OidcError::InvalidIdToken(_) | OidcError::TokenError(_) => {
+                warn!("OpenID Connect error: {}", self);
+                unauthorized("Invalid ID token")
+            }
+            _ => {
+                error!("OpenID Connect error: {}", self);
+                internal_error("Internal error")
+            }
+        }
+    }
+}
+
 impl ConvertToWebError for BranchError {
     fn to_response(&self) -> HttpResponse {
         error!("Branch error: {}", self);
@@ -1,9 +1,11 @@
 pub use self::json::*;
 pub use self::optional_user::*;
+pub use self::query::*;
 pub use self::request_info::*;
 pub use self::user::*;
 
 mod json;
 mod optional_user;
+mod query;
 mod request_info;
 mod user;
@@ -0,0 +1,62 @@
+// Extractor based on Actix-Web's Query extractor which also reads the export format from the `Accept` header
+// https://github.com/actix/actix-web/blob/master/src/extractor.rs
+
+use actix_web::error::{Error, ErrorBadRequest};
+use actix_web::{FromRequest, HttpRequest};
+use middleware::requested_format;
+use serde::de::DeserializeOwned;
+use serde_urlencoded;
+use std::ops::Deref;
+use url::form_urlencoded;
+
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, S> FromRequest<S> for Query<T>
+where
+    T: DeserializeOwned,
+{
+    type Config = ();
+    type Result = Result<Self, Error>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest<S>, _cfg: &Self::Config) -> Self::Result {
+        serde_urlencoded::from_str::<T>(&query_string(req))
+            .map_err(ErrorBadRequest)
+            .map(Query)
+    }
+}
+
+/// Query string of the request. An export format negotiated with the `Accept` header is added as the `format`
+/// parameter so that exports are not paged whichever way they were requested, see
+/// `PagingParameters::export_format`.
+pub fn query_string<S>(req: &HttpRequest<S>) -> String {
+    let query_string = req.query_string();
+    let has_format = form_urlencoded::parse(query_string.as_bytes()).any(|(key, _)| key == "format");
+    match requested_format(req) {
+        Some(format) if !has_format => {
+            let format_parameter = form_urlencoded::Serializer::new(String::new())
+                .append_pair("format", &format.to_string())
+                .finish();
+            if query_string.is_empty() {
+                format_parameter
+            } else {
+                format!("{}&{}", query_string, format_parameter)
+            }
+        }
+        _ => query_string.to_string(),
+    }
+}
@@ -2,11 +2,16 @@ use actix_web::error::*;
 use actix_web::{FromRequest, HttpRequest};
 use auth::claims;
 use auth::user::User;
-use bigneon_db::models::User as DbUser;
+use bigneon_db::models::{ApiKey, OAuthConsent, Scopes, User as DbUser, UserSession};
+use diesel::PgConnection;
 use errors::*;
 use jwt::{decode, Validation};
-use middleware::RequestConnection;
+use log::Level::Info;
+use middleware::{DelegatedAccessAllowed, RequestConnection};
 use server::AppState;
+use std::collections::HashMap;
+use std::str::FromStr;
+use uuid::Uuid;
 
 impl FromRequest<AppState> for User {
     type Config = ();
@@ -19,8 +24,15 @@ impl FromRequest<AppState> for User {
                     .to_str()
                     .map_err(|e| BigNeonError::from(e))?
                     .split_whitespace();
-                if str::ne(parts.next().unwrap_or("None"), "Bearer") {
-                    return Err(ErrorUnauthorized("Authorization scheme not supported"));
+                match parts.next().unwrap_or("None") {
+                    "Bearer" => {}
+                    "ApiKey" => {
+                        return match parts.next() {
+                            Some(key) => user_for_api_key(req, key),
+                            None => Err(ErrorUnauthorized("No API key provided")),
+                        };
+                    }
+                    _ => return Err(ErrorUnauthorized("Authorization scheme not supported")),
                 }
 
                 match parts.next() {
@@ -33,9 +45,28 @@ impl FromRequest<AppState> for User {
                         .map_err(|e| BigNeonError::from(e))?;
                         let connection = req.connection()?;
                         match DbUser::find(token.claims.get_id()?, connection.get()) {
-                            Ok(user) => Ok(
-                                User::new(user, req).map_err(|_| ErrorUnauthorized("User has invalid role data"))?
-                            ),
+                            Ok(user) => {
+                                let mut user = User::new(user, req)
+                                    .map_err(|_| ErrorUnauthorized("User has invalid role data"))?;
+                                user.two_factor_authenticated = token.claims.two_factor;
+                                if let Some(session_id) = token.claims.session_id {
+                                    check_session_active(session_id, &user, connection.get())?;
+                                    user.session_id = Some(session_id);
+                                } else if token.claims.oauth_consent_id.is_none() {
+                                    // Tokens issued before sessions were recorded cannot be revoked
+                                    return Err(ErrorUnauthorized("Access token is not linked to a session"));
+                                }
+                                if let Some(oauth_consent_id) = token.claims.oauth_consent_id {
+                                    user.token_scopes = Some(oauth_token_scopes(
+                                        oauth_consent_id,
+                                        &user,
+                                        &token.claims,
+                                        connection.get(),
+                                    )?);
+                                    check_delegated_access(req, &user)?;
+                                }
+                                Ok(user)
+                            }
                             Err(e) => Err(ErrorInternalServerError(e)),
                         }
                     }
@@ -48,3 +79,62 @@ impl FromRequest<AppState> for User {
         }
     }
 }
+
+fn user_for_api_key(req: &HttpRequest<AppState>, key: &str) -> Result<User, Error> {
+    let connection = req.connection()?;
+    let connection = connection.get();
+    let api_key = ApiKey::find_active_by_key(key, connection).map_err(|_| ErrorUnauthorized("Invalid API key"))?;
+    let user = DbUser::find(api_key.user_id, connection).map_err(|e| ErrorInternalServerError(e))?;
+    let user = User::new_for_api_key(user, api_key.clone(), req);
+    check_delegated_access(req, &user)?;
+
+    api_key
+        .mark_used(user.ip_address.clone(), connection)
+        .map_err(|e| ErrorInternalServerError(e))?;
+    jlog!(Info, "API key used", {
+        "api_key_id": api_key.id,
+        "organization_id": api_key.organization_id,
+        "user_id": user.id(),
+        "ip_address": &user.ip_address,
+        "url": &user.uri,
+        "method": &user.method
+    });
+
+    Ok(user)
+}
+
+/// Delegated credentials are only accepted by resources that opt in with `AllowDelegatedAccess`
+fn check_delegated_access(req: &HttpRequest<AppState>, user: &User) -> Result<(), Error> {
+    if req.extensions().get::<DelegatedAccessAllowed>().is_none() {
+        user.log_unauthorized_access_attempt(HashMap::new());
+        return Err(ErrorForbidden("Delegated credentials cannot be used for this endpoint"));
+    }
+    Ok(())
+}
+
+/// Access tokens stop working as soon as the session they were issued for is revoked
+fn check_session_active(session_id: Uuid, user: &User, connection: &PgConnection) -> Result<(), Error> {
+    let session = UserSession::find(session_id, connection).map_err(|e| ErrorInternalServerError(e))?;
+    if !session.is_active() || session.user_id != user.id() {
+        return Err(ErrorUnauthorized("Session has been revoked"));
+    }
+    Ok(())
+}
+
+fn oauth_token_scopes(
+    oauth_consent_id: Uuid,
+    user: &User,
+    claims: &claims::AccessToken,
+    connection: &PgConnection,
+) -> Result<Vec<Scopes>, Error> {
+    let consent = OAuthConsent::find(oauth_consent_id, connection).map_err(|e| ErrorInternalServerError(e))?;
+    if !consent.is_active() || consent.user_id != user.id() {
+        return Err(ErrorUnauthorized("Access token has been revoked"));
+    }
+
+    let mut scopes = Vec::new();
+    for scope in claims.scopes.clone().unwrap_or(Vec::new()) {
+        scopes.push(Scopes::from_str(&scope).map_err(|_| ErrorUnauthorized("Access token has invalid scopes"))?);
+    }
+    Ok(scopes)
+}
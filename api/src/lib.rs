@@ -10,13 +10,16 @@ extern crate actix_web;
 extern crate bigneon_db;
 extern crate branch_rs;
 extern crate chrono;
+extern crate csv;
 extern crate customer_io;
 extern crate diesel;
 extern crate dotenv;
 extern crate expo_server_sdk as expo;
 extern crate facebook;
+extern crate fallible_iterator;
 extern crate futures;
 extern crate globee;
+extern crate hosted_checkout;
 extern crate itertools;
 extern crate jsonwebtoken as jwt;
 #[macro_use]
@@ -27,7 +30,9 @@ extern crate log;
 extern crate logging;
 #[macro_use]
 extern crate macros;
+extern crate oidc;
 extern crate phonenumber;
+extern crate postgres;
 extern crate r2d2;
 extern crate regex;
 extern crate reqwest;
@@ -36,6 +41,7 @@ extern crate serde;
 extern crate serde_derive;
 #[macro_use]
 extern crate serde_json;
+extern crate serde_urlencoded;
 extern crate serde_with;
 extern crate stripe;
 extern crate tari_client;
@@ -47,6 +53,7 @@ extern crate validator;
 #[macro_use]
 extern crate validator_derive;
 extern crate sitemap;
+extern crate zip;
 
 pub mod auth;
 pub mod communications;
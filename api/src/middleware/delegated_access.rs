@@ -0,0 +1,25 @@
+use actix_web::middleware::{Middleware, Started};
+use actix_web::{HttpRequest, Result};
+use server::AppState;
+
+/// Marks the request as allowed to be authenticated by an API key or OAuth access token
+pub struct DelegatedAccessAllowed;
+
+/// API keys and OAuth access tokens are limited by their scopes, which are only enforced by the scope
+/// checks in controllers. Delegated credentials are therefore rejected by the `User` extractor unless the
+/// resource opts in with this middleware, which should only be added to resources where every route
+/// requires a scope before doing anything on the user's behalf.
+pub struct AllowDelegatedAccess {}
+
+impl AllowDelegatedAccess {
+    pub fn new() -> AllowDelegatedAccess {
+        AllowDelegatedAccess {}
+    }
+}
+
+impl Middleware<AppState> for AllowDelegatedAccess {
+    fn start(&self, request: &HttpRequest<AppState>) -> Result<Started> {
+        request.extensions_mut().insert(DelegatedAccessAllowed);
+        Ok(Started::Done)
+    }
+}
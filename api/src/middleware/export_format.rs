@@ -0,0 +1,175 @@
+use actix_web::http::header;
+use actix_web::http::header::HeaderValue;
+use actix_web::middleware::{Middleware, Response};
+use actix_web::{Binary, Body, Error, HttpRequest, HttpResponse, Result};
+use bigneon_db::models::{ReportFormats, ReportTable};
+use errors::{ApplicationError, BigNeonError};
+use futures::stream;
+use serde_json;
+use serde_json::Value;
+use server::AppState;
+use url::form_urlencoded;
+use utils::spreadsheet::{self, ExportLocale};
+use uuid::Uuid;
+
+/// Converts list responses (a `Payload` or a JSON array) into CSV or XLSX files when the client asks for one
+/// with the `format` query parameter or the `Accept` header, either of which removes the paging limit, see
+/// `extractors::Query`. `WebPayload` responses are exported from their rows directly, this middleware converts
+/// the JSON body of handlers that build their own response.
+pub struct ExportFormat;
+
+impl ExportFormat {
+    pub fn new() -> ExportFormat {
+        ExportFormat {}
+    }
+}
+
+/// Format requested with the `format` query parameter, falling back to the `Accept` header
+pub fn requested_format<S>(req: &HttpRequest<S>) -> Option<ReportFormats> {
+    if let Some(format) = query_parameter(req, "format") {
+        return format.parse().ok();
+    }
+
+    let accept = req.headers().get(header::ACCEPT)?.to_str().ok()?;
+    accept
+        .split(',')
+        .filter_map(|media_range| {
+            let mut parts = media_range.split(';').map(|p| p.trim());
+            let media_type = parts.next()?;
+            if parts.any(|p| p == "q=0" || p == "q=0.0") {
+                return None;
+            }
+            match media_type {
+                "text/csv" => Some(ReportFormats::Csv),
+                m if m == spreadsheet::content_type(ReportFormats::Xlsx) => Some(ReportFormats::Xlsx),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+/// Locale from the `locale` query parameter, falling back to the first `Accept-Language` entry
+pub fn requested_locale<S>(req: &HttpRequest<S>) -> ExportLocale {
+    let tag = query_parameter(req, "locale").or_else(|| {
+        req.headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.split(',').next())
+            .map(|l| l.split(';').next().unwrap_or("").to_string())
+    });
+    tag.map(|t| ExportLocale::from_language_tag(&t)).unwrap_or_default()
+}
+
+fn query_parameter<S>(req: &HttpRequest<S>, name: &str) -> Option<String> {
+    form_urlencoded::parse(req.query_string().as_bytes())
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// File name for the export, e.g. `guests.csv` for `/events/{id}/guests`
+fn file_name<S>(req: &HttpRequest<S>, format: ReportFormats) -> String {
+    let name = query_parameter(req, "report").unwrap_or_else(|| {
+        req.path()
+            .rsplit('/')
+            .find(|segment| !segment.is_empty() && segment.parse::<Uuid>().is_err())
+            .unwrap_or("export")
+            .to_string()
+    });
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("{}.{}", name, spreadsheet::file_extension(format))
+}
+
+/// Replaces the response body with the table written in the requested format. CSV rows are written as the body is
+/// streamed to the client, XLSX files are compressed so the workbook is written in full.
+pub fn set_export_body<S>(
+    req: &HttpRequest<S>,
+    resp: &mut HttpResponse,
+    format: ReportFormats,
+    table: ReportTable,
+) -> Result<(), BigNeonError> {
+    let body = match format {
+        ReportFormats::Csv => csv_body(table, requested_locale(req))?,
+        ReportFormats::Xlsx => Body::Binary(spreadsheet::to_xlsx(&table)?.into()),
+    };
+    let headers = resp.headers_mut();
+    headers.remove(header::ETAG);
+    headers.remove(header::CONTENT_LENGTH);
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(spreadsheet::content_type(format)),
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", file_name(req, format)))
+            .map_err(|e| ApplicationError::new(e.to_string()))?,
+    );
+    headers.insert(header::VARY, HeaderValue::from_static("Accept"));
+    resp.set_body(body);
+    Ok(())
+}
+
+fn csv_body(table: ReportTable, locale: ExportLocale) -> Result<Body, BigNeonError> {
+    let header = spreadsheet::csv_header(&table.columns, locale)?;
+    let records = Some(Ok(header))
+        .into_iter()
+        .chain(
+            table
+                .rows
+                .into_iter()
+                .map(move |row| spreadsheet::csv_record(&row, locale)),
+        )
+        .map(|record| {
+            record
+                .map(|record| {
+                    let mut binary = Binary::from(record);
+                    binary.take()
+                })
+                .map_err(Error::from)
+        });
+    Ok(Body::Streaming(Box::new(stream::iter_result(records))))
+}
+
+impl Middleware<AppState> for ExportFormat {
+    fn response(&self, req: &HttpRequest<AppState>, mut resp: HttpResponse) -> Result<Response> {
+        if !resp.status().is_success() {
+            return Ok(Response::Done(resp));
+        }
+        let format = match requested_format(req) {
+            Some(format) => format,
+            None => return Ok(Response::Done(resp)),
+        };
+        // Responses that were already exported, or are not JSON, are left as they are
+        let is_json = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(|content_type| content_type.starts_with("application/json"))
+            .unwrap_or(false);
+        if !is_json {
+            return Ok(Response::Done(resp));
+        }
+
+        let rows = match resp.body() {
+            Body::Binary(binary) => match serde_json::from_slice::<Value>(binary.as_ref()) {
+                Ok(Value::Object(mut payload)) => match (payload.remove("data"), payload.contains_key("paging")) {
+                    (Some(Value::Array(data)), true) => Some(data),
+                    _ => None,
+                },
+                Ok(Value::Array(data)) => Some(data),
+                _ => None,
+            },
+            _ => None,
+        };
+        // Responses that are not lists are left as JSON
+        let rows = match rows {
+            Some(rows) => rows,
+            None => return Ok(Response::Done(resp)),
+        };
+
+        set_export_body(req, &mut resp, format, spreadsheet::table_from_json(&rows))?;
+        Ok(Response::Done(resp))
+    }
+}
@@ -1,9 +1,13 @@
 pub use self::app_version_header::*;
 pub use self::big_neon_logger::*;
 pub use self::database_transaction::*;
+pub use self::delegated_access::*;
+pub use self::export_format::*;
 pub use self::metatags::*;
 
 mod app_version_header;
 mod big_neon_logger;
 mod database_transaction;
+mod delegated_access;
+mod export_format;
 mod metatags;
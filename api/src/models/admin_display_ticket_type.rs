@@ -19,6 +19,7 @@ pub struct AdminDisplayTicketType {
     pub increment: u32,
     pub limit_per_person: u32,
     pub ticket_pricing: Vec<DisplayTicketPricing>,
+    pub pricing_rules: Vec<TicketPricingRule>,
     pub price_in_cents: i64,
     pub visibility: TicketTypeVisibility,
     pub parent_id: Option<Uuid>,
@@ -69,6 +70,7 @@ impl AdminDisplayTicketType {
             end_date: ticket_type.end_date,
             end_date_type: ticket_type.end_date_type,
             ticket_pricing: ticket_pricing_list.clone(),
+            pricing_rules: ticket_type.pricing_rules(conn)?,
             available,
             capacity,
             increment: ticket_type.increment as u32,
@@ -57,6 +57,12 @@ pub struct OrganizationInvitePathParameters {
     pub invite_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct OrganizationWebhookPathParameters {
+    pub id: Uuid, // Organization Id
+    pub webhook_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct CompPathParameters {
     pub hold_id: Uuid,
@@ -4,7 +4,10 @@ use actix_web::HttpResponse;
 use actix_web::{Error, Responder};
 use bigneon_db::models::Payload;
 use errors::BigNeonError;
+use middleware::{requested_format, set_export_body};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
+use utils::spreadsheet;
 
 #[derive(Debug)]
 pub struct WebPayload<T>(StatusCode, Payload<T>);
@@ -34,15 +37,34 @@ where
     }
 }
 
+impl<T> WebPayload<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Responds with the payload as JSON, or with its rows as a file when an export format was requested.
+    /// Rows are written straight from the data rather than converting the JSON response.
+    pub fn into_response<S>(self, req: &HttpRequest<S>) -> Result<HttpResponse, BigNeonError> {
+        match requested_format(req) {
+            Some(format) if self.0.is_success() => {
+                let table = spreadsheet::table_from_rows(&self.1.data)?;
+                let mut response = HttpResponse::new(self.0);
+                set_export_body(req, &mut response, format, table)?;
+                Ok(response)
+            }
+            _ => self.into_http_response(),
+        }
+    }
+}
+
 impl<T> Responder for WebPayload<T>
 where
-    T: Serialize,
+    T: Serialize + DeserializeOwned,
 {
     type Item = HttpResponse;
     type Error = Error;
 
-    fn respond_to<S>(self, _req: &HttpRequest<S>) -> Result<HttpResponse, Error> {
-        Ok(self.into_http_response()?)
+    fn respond_to<S>(self, req: &HttpRequest<S>) -> Result<HttpResponse, Error> {
+        Ok(self.into_response(req)?)
     }
 }
 
@@ -1,6 +1,14 @@
 pub struct ChargeAuthResult {
     pub id: String,
     pub raw: String,
+    /// Set when the customer has to complete an action (e.g. 3-D Secure) before the charge can be completed
+    pub required_action: Option<RequiredAction>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RequiredAction {
+    /// Secret used by the client to complete the action with the payment provider
+    pub client_secret: String,
 }
 
 impl ChargeAuthResult {
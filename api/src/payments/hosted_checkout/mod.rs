@@ -0,0 +1,126 @@
+use bigneon_db::models::PaymentProviders;
+use hosted_checkout::*;
+use payments::*;
+use uuid::Uuid;
+
+/// Payments are stored with an external reference of `hosted_checkout-<checkout id>`
+const EXTERNAL_REFERENCE_PREFIX: &str = "hosted_checkout-";
+
+pub fn external_reference(checkout_id: &str) -> String {
+    format!("{}{}", EXTERNAL_REFERENCE_PREFIX, checkout_id)
+}
+
+fn checkout_id(external_reference: &str) -> &str {
+    external_reference.trim_start_matches(EXTERNAL_REFERENCE_PREFIX)
+}
+
+pub struct HostedCheckoutPaymentProcessor {
+    api_key: String,
+    base_url: String,
+}
+
+impl HostedCheckoutPaymentProcessor {
+    pub fn new(api_key: String, base_url: String) -> HostedCheckoutPaymentProcessor {
+        HostedCheckoutPaymentProcessor { api_key, base_url }
+    }
+
+    fn client(&self) -> HostedCheckoutClient {
+        HostedCheckoutClient::new(self.api_key.clone(), self.base_url.clone())
+    }
+}
+
+impl PaymentProcessor for HostedCheckoutPaymentProcessor {
+    fn behavior(&self) -> PaymentProcessorBehavior {
+        PaymentProcessorBehavior::RedirectToPaymentPage(Box::new(HostedCheckoutPaymentProcessorBehavior {
+            client: self.client(),
+        }))
+    }
+
+    fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let client = self.client();
+        let checkout = client.get_checkout(checkout_id(auth_token))?;
+        let checkout = client.refund(&checkout.id, checkout.amount_in_cents - checkout.refunded_in_cents)?;
+        Ok(ChargeAuthResult {
+            id: checkout.id.clone(),
+            raw: serde_json::to_string(&checkout).map_err(HostedCheckoutError::from)?,
+            required_action: None,
+        })
+    }
+
+    fn update_metadata(
+        &self,
+        _charge_id: &str,
+        _metadata: Vec<(String, String)>,
+    ) -> Result<UpdateMetadataResult, PaymentProcessorError> {
+        Err(PaymentProcessorError {
+            description: "Updating metadata is not supported by this gateway".to_string(),
+            cause: None,
+            validation_response: None,
+        })
+    }
+
+    fn partial_refund(&self, auth_token: &str, amount: i64) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let checkout = self.client().refund(checkout_id(auth_token), amount)?;
+        Ok(ChargeAuthResult {
+            id: checkout.id.clone(),
+            raw: serde_json::to_string(&checkout).map_err(HostedCheckoutError::from)?,
+            required_action: None,
+        })
+    }
+}
+
+pub struct HostedCheckoutPaymentProcessorBehavior {
+    client: HostedCheckoutClient,
+}
+
+impl RedirectToPaymentPageBehavior for HostedCheckoutPaymentProcessorBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::HostedCheckout
+    }
+
+    fn create_payment_request(
+        &self,
+        amount: f64,
+        currency: &str,
+        email: String,
+        payment_id: Uuid,
+        ipn_url: Option<String>,
+        success_url: Option<String>,
+        cancel_url: Option<String>,
+    ) -> Result<RedirectInfo, PaymentProcessorError> {
+        let (success_url, cancel_url) = match (success_url, cancel_url) {
+            (Some(success_url), Some(cancel_url)) => (success_url, cancel_url),
+            _ => {
+                return Err(PaymentProcessorError {
+                    description: "Success and cancel urls are required for hosted checkouts".to_string(),
+                    cause: None,
+                    validation_response: None,
+                });
+            }
+        };
+        let checkout = self.client.create_checkout(&CheckoutRequest {
+            amount_in_cents: (amount * 100f64).round() as i64,
+            currency: currency.to_uppercase(),
+            email,
+            reference: payment_id.to_string(),
+            ipn_url,
+            success_url,
+            cancel_url,
+        })?;
+        Ok(RedirectInfo {
+            id: checkout.id,
+            redirect_url: checkout.redirect_url,
+            expires_at: checkout.expires_at,
+        })
+    }
+}
+
+impl From<HostedCheckoutError> for PaymentProcessorError {
+    fn from(e: HostedCheckoutError) -> Self {
+        PaymentProcessorError {
+            description: e.to_string(),
+            cause: Some(Box::new(e)),
+            validation_response: None,
+        }
+    }
+}
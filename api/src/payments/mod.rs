@@ -8,6 +8,7 @@ pub use self::update_metadata_result::*;
 mod charge_auth_result;
 mod charge_result;
 pub mod globee;
+pub mod hosted_checkout;
 pub mod payment_processor;
 mod payment_processor_error;
 mod repeat_charge_token;
@@ -1,5 +1,6 @@
 use bigneon_db::models::PaymentProviders;
 use payments::*;
+use stripe::PaymentIntentStatus;
 use stripe::StripeClient;
 use stripe::StripeError;
 
@@ -74,6 +75,7 @@ impl PaymentProcessor for StripePaymentProcessor {
         Ok(self.client.refund(auth_token).map(|r| ChargeAuthResult {
             id: r.id,
             raw: r.raw_data,
+            required_action: None,
         })?)
     }
 
@@ -84,6 +86,7 @@ impl PaymentProcessor for StripePaymentProcessor {
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
+                required_action: None,
             })?)
     }
 }
@@ -129,17 +132,31 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
-        Ok(self
+        let payment_intent = self
             .client
-            .auth(token, amount, currency, description, metadata)
-            .map(|r| ChargeAuthResult {
-                id: r.id,
-                raw: r.raw_data,
-            })?)
+            .create_payment_intent(token, amount, currency, description, metadata)?;
+        let required_action = match payment_intent.status {
+            PaymentIntentStatus::RequiresCapture => None,
+            PaymentIntentStatus::RequiresAction => Some(RequiredAction {
+                client_secret: payment_intent.client_secret.clone().unwrap_or_default(),
+            }),
+            status => {
+                return Err(PaymentProcessorError {
+                    description: format!("Unexpected payment intent status: {:?}", status),
+                    cause: None,
+                    validation_response: Some("Card could not be authorized".to_string()),
+                });
+            }
+        };
+        Ok(ChargeAuthResult {
+            id: payment_intent.id,
+            raw: payment_intent.raw_data,
+            required_action,
+        })
     }
 
-    fn complete_authed_charge(&self, _auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
-        Ok(self.client.complete(_auth_token).map(|r| ChargeResult {
+    fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        Ok(self.client.capture_payment_intent(auth_token).map(|r| ChargeResult {
             id: r.id,
             raw: r.raw_data,
         })?)
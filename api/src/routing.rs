@@ -1,12 +1,38 @@
 use actix_web::middleware::cors::CorsBuilder;
 use actix_web::{http::Method, App, HttpResponse};
 use controllers::*;
+use middleware::AllowDelegatedAccess;
 use server::AppState;
 
 pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     // Please try to keep in alphabetical order
 
-    app.resource("/admin/stuck_domain_actions", |r| {
+    app.resource("/admin/dead_letter_domain_actions", |r| {
+        r.method(Method::GET).with(admin::admin_dead_letter_domain_actions);
+    })
+    .resource("/admin/domain_actions/{id}/cancel", |r| {
+        r.method(Method::POST).with(admin::admin_cancel_domain_action);
+    })
+    .resource("/admin/domain_actions/{id}/requeue", |r| {
+        r.method(Method::POST).with(admin::admin_requeue_domain_action);
+    })
+    .resource("/admin/domain_actions/{id}", |r| {
+        r.method(Method::GET).with(admin::admin_show_domain_action);
+        r.method(Method::PUT).with(admin::admin_update_domain_action);
+    })
+    .resource("/admin/domain_event_publishers/{id}/replay", |r| {
+        r.method(Method::POST).with(admin::admin_replay_domain_events);
+    })
+    .resource("/admin/domain_event_publishers/{id}/webhook_deliveries", |r| {
+        r.method(Method::GET).with(admin::admin_webhook_deliveries);
+    })
+    .resource("/admin/resale_payouts/{id}/paid", |r| {
+        r.method(Method::POST).with(admin::admin_mark_resale_payout_paid);
+    })
+    .resource("/admin/resale_payouts", |r| {
+        r.method(Method::GET).with(admin::admin_pending_resale_payouts);
+    })
+    .resource("/admin/stuck_domain_actions", |r| {
         r.method(Method::GET).with(admin::admin_stuck_domain_actions);
     })
     .resource("/admin/ticket_count", |r| {
@@ -18,10 +44,19 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/a/t", |r| {
         r.method(Method::GET).with(analytics::track);
     })
+    .resource("/api_keys/{id}/rotate", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(api_keys::rotate);
+    })
+    .resource("/api_keys/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::DELETE).with(api_keys::destroy);
+    })
     .resource("/artists/search", |r| {
         r.method(Method::GET).with(artists::search);
     })
     .resource("/artists/{id}/toggle_privacy", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::PUT).with(artists::toggle_privacy);
     })
     .resource("/artists/{id}", |r| {
@@ -36,7 +71,11 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/auth/token/refresh", |r| {
         r.method(Method::POST).with(auth::token_refresh)
     })
+    .resource("/auth/token/two_factor", |r| {
+        r.method(Method::POST).with(auth::two_factor_token)
+    })
     .resource("/broadcasts/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(broadcasts::show);
         r.method(Method::PUT).with(broadcasts::update);
         r.method(Method::DELETE).with(broadcasts::delete);
@@ -57,21 +96,29 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(cart::checkout);
     })
     .resource("/codes/{id}/link", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(codes::link);
     })
     .resource("/codes/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(codes::show);
         r.method(Method::PUT).with(codes::update);
         r.method(Method::DELETE).with(codes::destroy);
     })
     .resource("/comps/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(comps::show);
         r.method(Method::PATCH).with(comps::update);
         r.method(Method::DELETE).with(comps::destroy);
     })
     .resource("/event_report_subscribers/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(event_report_subscribers::destroy);
     })
+    .resource("/event_series/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(event_series::show);
+    })
     .resource("/events", |r| {
         r.method(Method::GET).with(events::index);
         r.method(Method::POST).with(events::create);
@@ -85,84 +132,139 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::DELETE).with(events::cancel);
     })
     .resource("/events/{id}/delete", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(events::delete);
     })
     .resource("/events/{id}/artists", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(events::add_artist);
         r.method(Method::PUT).with(events::update_artists);
     })
     .resource("/events/{id}/ticket_holder_count", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::ticket_holder_count);
     })
     .resource("/events/{id}/codes", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::codes);
         r.method(Method::POST).with(codes::create);
     })
     .resource("/events/{id}/dashboard", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::dashboard);
     })
+    .resource("/events/{id}/duplicate", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(events::duplicate);
+    })
     .resource("/events/{id}/guests", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::guest_list);
     })
     .resource("/events/{id}/holds", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(holds::create);
         r.method(Method::GET).with(events::holds);
     })
     .resource("/events/{id}/interest", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::list_interested_users);
         r.method(Method::POST).with(events::add_interest);
         r.method(Method::DELETE).with(events::remove_interest);
     })
     .resource("/events/{id}/publish", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(events::publish);
     })
     .resource("/events/{id}/broadcasts", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(broadcasts::create);
         r.method(Method::GET).with(broadcasts::index);
     })
     .resource("/events/{id}/links", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(events::create_link);
     })
     .resource("/events/{id}/redeem/{ticket_instance_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(events::redeem_ticket);
     })
+    .resource("/events/{id}/redemption_bundle", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(events::redemption_bundle);
+    })
+    .resource("/events/{id}/redemption_key", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(events::redemption_key);
+    })
+    .resource("/events/{id}/redemptions/sync", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(events::sync_redemptions);
+    })
     .resource("/events/{id}/report_subscribers", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(event_report_subscribers::index);
         r.method(Method::POST).with(event_report_subscribers::create);
     })
+    .resource("/events/{id}/resale_listings", |r| {
+        r.method(Method::GET).with(resale_listings::event_index);
+    })
+    .resource("/events/{id}/seats", |r| {
+        r.method(Method::GET).with(events::seats);
+    })
+    .resource("/events/{id}/series", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(event_series::create);
+        r.method(Method::PUT).with(event_series::update_following);
+    })
     .resource("/events/{id}/tickets", |r| {
         r.method(Method::GET).with(tickets::index);
     })
     .resource("/events/{id}/ticket_types", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(ticket_types::index);
         r.method(Method::POST).with(ticket_types::create);
     })
     .resource("/events/{id}/ticket_types/multiple", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(ticket_types::create_multiple);
     })
     .resource("/events/{event_id}/ticket_types/{ticket_type_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::PATCH).with(ticket_types::update);
         r.method(Method::DELETE).with(ticket_types::cancel);
     })
+    .resource("/events/{event_id}/ticket_types/{ticket_type_id}/seats", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(ticket_types::assign_seats);
+    })
+    .resource("/events/{event_id}/ticket_types/{ticket_type_id}/waitlist", |r| {
+        r.method(Method::POST).with(waitlist_entries::create);
+    })
     .resource("/events/{id}/unpublish", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(events::unpublish);
     })
     .resource("/events/{id}/users", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::users);
     })
     .resource("/events/{id}/users/invites", |r| {
         r.method(Method::POST).with(organization_invites::create_for_event);
     })
     .resource("/events/{id}/users/invites/{invite_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(organization_invites::destroy);
     })
     .resource("/events/{id}/users/{user_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(events::remove_user);
     })
     .resource("/external/facebook/pages", |r| {
         r.method(Method::GET).with(external::facebook::pages)
     })
     .resource("/external/facebook/events", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(external::facebook::create_event);
     })
     .resource("/external/facebook/web_login", |r| {
@@ -171,6 +273,12 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/external/facebook/scopes", |r| {
         r.method(Method::GET).with(external::facebook::scopes);
     })
+    .resource("/external/oidc/{id}/login", |r| {
+        r.method(Method::POST).with(external::oidc::login);
+    })
+    .resource("/external/oidc/{id}/start", |r| {
+        r.method(Method::POST).with(external::oidc::start);
+    })
     .resource("/external/facebook", |r| {
         r.method(Method::DELETE).with(external::facebook::disconnect);
     })
@@ -186,20 +294,31 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/ipns/globee", |r| {
         r.method(Method::POST).with(ipns::globee);
     })
+    .resource("/ipns/hosted_checkout", |r| {
+        r.method(Method::POST).with(ipns::hosted_checkout);
+    })
+    .resource("/ipns/stripe", |r| {
+        r.method(Method::POST).with(ipns::stripe);
+    })
     .resource("/holds/{id}/comps", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(comps::index);
         r.method(Method::POST).with(comps::create);
     })
     .resource("/holds/{id}/split", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(holds::split);
     })
     .resource("/holds/{id}/children", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(holds::children);
     })
     .resource("/holds/{id}/link", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(holds::link);
     })
     .resource("/holds/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::PATCH).with(holds::update);
         r.method(Method::GET).with(holds::show);
         r.method(Method::DELETE).with(holds::destroy);
@@ -211,10 +330,36 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(notes::index);
         r.method(Method::POST).with(notes::create);
     })
+    .resource("/oauth/authorize", |r| {
+        r.method(Method::GET).with(oauth::authorize_details);
+        r.method(Method::POST).with(oauth::authorize);
+    })
+    .resource("/oauth/consents/{id}", |r| {
+        r.method(Method::DELETE).with(oauth::destroy_consent);
+    })
+    .resource("/oauth/consents", |r| {
+        r.method(Method::GET).with(oauth::consents);
+    })
+    .resource("/oauth/revoke", |r| {
+        r.method(Method::POST).with(oauth::revoke);
+    })
+    .resource("/oauth/token", |r| {
+        r.method(Method::POST).with(oauth::token);
+    })
+    .resource("/oauth_clients/{id}/secret", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(oauth_clients::generate_secret);
+    })
+    .resource("/oauth_clients/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::DELETE).with(oauth_clients::destroy);
+    })
     .resource("/orders", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(orders::index);
     })
     .resource("/orders/{id}/activity", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(orders::activity);
     })
     .resource("/orders/{id}/details", |r| {
@@ -224,6 +369,7 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PATCH).with(orders::refund);
     })
     .resource("/orders/{id}/resend_confirmation", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(orders::resend_confirmation);
     })
     .resource("/orders/{id}/send_box_office_instructions", |r| {
@@ -243,51 +389,106 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(organizations::add_artist);
     })
     .resource("/organizations/{id}/events", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::show_from_organizations);
     })
     .resource("/organizations/{id}/export_event_data", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(events::export_event_data);
     })
     .resource("/organizations/{id}/fans/{user_id}/activity", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(users::activity);
     })
     .resource("/organizations/{id}/fans/{user_id}/history", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(users::history);
     })
+    .resource("/organizations/{id}/api_keys", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(api_keys::index);
+        r.method(Method::POST).with(api_keys::create);
+    })
     .resource("/organizations/{id}/fans/{user_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(users::profile);
     })
     .resource("/organizations/{id}/fee_schedule", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(organizations::show_fee_schedule);
         r.method(Method::POST).with(organizations::add_fee_schedule);
     })
     .resource("/organizations/{id}/fans", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(organizations::search_fans);
     })
     .resource("/organizations/{id}/invites/{invite_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(organization_invites::destroy);
     })
+    .resource("/organizations/{id}/season_passes", |r| {
+        r.method(Method::GET).with(season_passes::index);
+        r.method(Method::POST).with(season_passes::create);
+    })
     .resource("/organizations/{id}/settlements", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(settlements::index);
         r.method(Method::POST).with(settlements::create);
     })
     .resource("/organizations/{id}/invites", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(organization_invites::index);
         r.method(Method::POST).with(organization_invites::create);
     })
+    .resource("/organizations/{id}/oauth_clients", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(oauth_clients::index);
+        r.method(Method::POST).with(oauth_clients::create);
+    })
+    .resource("/organizations/{id}/saved_reports", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(saved_reports::index);
+        r.method(Method::POST).with(saved_reports::create);
+    })
     .resource("/organizations/{id}/users", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(organizations::add_or_replace_user);
         r.method(Method::PUT).with(organizations::add_or_replace_user);
         r.method(Method::GET).with(organizations::list_organization_members);
     })
     .resource("/organizations/{id}/users/{user_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(organizations::remove_user);
     })
     .resource("/organizations/{id}/venues", |r| {
         r.method(Method::GET).with(venues::show_from_organizations);
         r.method(Method::POST).with(organizations::add_venue);
     })
+    .resource("/organizations/{id}/webhooks/{webhook_id}/deliveries", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(webhooks::deliveries);
+    })
+    .resource("/organizations/{id}/webhooks/{webhook_id}/ping", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(webhooks::ping);
+    })
+    .resource("/organizations/{id}/webhooks/{webhook_id}/rotate_secret", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::POST).with(webhooks::rotate_secret);
+    })
+    .resource("/organizations/{id}/webhooks/{webhook_id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(webhooks::show);
+        r.method(Method::PUT).with(webhooks::update);
+        r.method(Method::DELETE).with(webhooks::destroy);
+    })
+    .resource("/organizations/{id}/webhooks", |r| {
+        r.middleware(AllowDelegatedAccess::new());
+        r.method(Method::GET).with(webhooks::index);
+        r.method(Method::POST).with(webhooks::create);
+    })
     .resource("/organizations/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(organizations::show);
         r.method(Method::PATCH).with(organizations::update);
     })
@@ -319,34 +520,91 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/reports/{id}", |r| {
         r.method(Method::GET).with(reports::get_report);
     })
+    .resource("/resale_listings/{id}/cart", |r| {
+        r.method(Method::POST).with(resale_listings::add_to_cart);
+    })
+    .resource("/resale_listings/{id}", |r| {
+        r.method(Method::DELETE).with(resale_listings::destroy);
+    })
+    .resource("/resale_listings", |r| {
+        r.method(Method::GET).with(resale_listings::index);
+        r.method(Method::POST).with(resale_listings::create);
+    })
+    .resource("/saved_report_subscribers/{id}", |r| {
+        r.method(Method::DELETE).with(saved_reports::remove_subscriber);
+    })
+    .resource("/saved_reports/{id}/run", |r| {
+        r.method(Method::GET).with(saved_reports::run);
+    })
+    .resource("/saved_reports/{id}/subscribers", |r| {
+        r.method(Method::GET).with(saved_reports::subscribers);
+        r.method(Method::POST).with(saved_reports::add_subscriber);
+    })
+    .resource("/saved_reports/{id}", |r| {
+        r.method(Method::GET).with(saved_reports::show);
+        r.method(Method::PUT).with(saved_reports::update);
+        r.method(Method::DELETE).with(saved_reports::destroy);
+    })
+    .resource("/season_pass_entitlements", |r| {
+        r.method(Method::GET).with(season_passes::entitlements);
+    })
+    .resource("/season_passes/{id}/cart", |r| {
+        r.method(Method::POST).with(season_passes::add_to_cart);
+    })
+    .resource("/season_passes/{id}", |r| {
+        r.method(Method::GET).with(season_passes::show);
+        r.method(Method::PATCH).with(season_passes::update);
+    })
     .resource("/slugs/{id}", |r| {
         r.method(Method::GET).with(slugs::show);
         r.method(Method::PUT).with(slugs::update);
     })
     .resource("/status", |r| r.method(Method::GET).with(status::check))
+    .resource("/status/workers", |r| r.method(Method::GET).with(status::workers))
+    .resource("/stage_sections/{id}", |r| {
+        r.method(Method::DELETE).with(stage_sections::destroy);
+    })
+    .resource("/stages/{id}/sections", |r| {
+        r.method(Method::GET).with(stage_sections::index);
+        r.method(Method::POST).with(stage_sections::create);
+    })
     .resource("/stages/{id}", |r| {
         r.method(Method::GET).with(stages::show);
         r.method(Method::PUT).with(stages::update);
         r.method(Method::DELETE).with(stages::delete);
     })
     .resource("/settlement_adjustments/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::DELETE).with(settlement_adjustments::destroy);
     })
     .resource("/settlements/{id}/adjustments", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(settlement_adjustments::index);
         r.method(Method::POST).with(settlement_adjustments::create);
     })
     .resource("/settlements/{id}", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(settlements::show);
         r.method(Method::DELETE).with(settlements::destroy);
     })
+    .resource("/tax_rules/{id}", |r| {
+        r.method(Method::GET).with(tax_rules::show);
+        r.method(Method::PUT).with(tax_rules::update);
+    })
+    .resource("/tax_rules", |r| {
+        r.method(Method::GET).with(tax_rules::index);
+        r.method(Method::POST).with(tax_rules::create)
+    })
     .resource("/tickets/transfer", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(tickets::transfer_authorization);
     })
     .resource("/tickets/receive", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(tickets::receive_transfer);
     })
     .resource("/tickets/send", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(tickets::send_via_email_or_phone);
     })
     .resource("/tickets/{id}", |r| {
@@ -359,10 +617,14 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/tickets/{id}/redeem", |r| {
         r.method(Method::GET).with(tickets::show_redeemable_ticket);
     })
+    .resource("/tickets/{id}/signed_payload", |r| {
+        r.method(Method::GET).with(tickets::signed_payload);
+    })
     .resource("/transfers/transfer_key/{id}", |r| {
         r.method(Method::GET).with(transfers::show_by_transfer_key);
     })
     .resource("/transfers/activity", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::GET).with(transfers::activity);
     })
     .resource("/transfers/{id}", |r| {
@@ -375,6 +637,24 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(users::current_user);
         r.method(Method::PUT).with(users::update_current_user);
     })
+    .resource("/users/me/sessions/{id}", |r| {
+        r.method(Method::DELETE).with(user_sessions::destroy);
+    })
+    .resource("/users/me/sessions", |r| {
+        r.method(Method::GET).with(user_sessions::index);
+        r.method(Method::DELETE).with(user_sessions::destroy_all);
+    })
+    .resource("/users/me/two_factor/enable", |r| {
+        r.method(Method::POST).with(two_factor::enable);
+    })
+    .resource("/users/me/two_factor/recovery_codes", |r| {
+        r.method(Method::POST).with(two_factor::regenerate_recovery_codes);
+    })
+    .resource("/users/me/two_factor", |r| {
+        r.method(Method::GET).with(two_factor::show);
+        r.method(Method::POST).with(two_factor::create);
+        r.method(Method::DELETE).with(two_factor::destroy);
+    })
     .resource("/users/register", |r| r.method(Method::POST).with(users::register))
     .resource("/users/{id}/tokens", |r| {
         r.method(Method::GET)
@@ -399,7 +679,11 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/users/{id}/organizations", |r| {
         r.method(Method::GET).with(users::list_organizations);
     })
+    .resource("/users/{id}/sessions", |r| {
+        r.method(Method::DELETE).with(user_sessions::destroy_for_user);
+    })
     .resource("/venues/{id}/organizations", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::POST).with(venues::add_to_organization);
     })
     .resource("/venues/{id}/stages", |r| {
@@ -407,6 +691,7 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(stages::index);
     })
     .resource("/venues/{id}/toggle_privacy", |r| {
+        r.middleware(AllowDelegatedAccess::new());
         r.method(Method::PUT).with(venues::toggle_privacy);
     })
     .resource("/venues/{id}", |r| {
@@ -417,6 +702,12 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(venues::index);
         r.method(Method::POST).with(venues::create);
     })
+    .resource("/waitlist_entries/{id}", |r| {
+        r.method(Method::DELETE).with(waitlist_entries::destroy);
+    })
+    .resource("/waitlist_entries", |r| {
+        r.method(Method::GET).with(waitlist_entries::index);
+    })
     .resource("/sitemap.xml", |r| {
         r.method(Method::GET).with(sitemap_gen::index);
     })
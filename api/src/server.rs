@@ -1,3 +1,5 @@
+use actix_web::actix::actors::signal::{ProcessSignals, Signal, SignalType, Subscribe};
+use actix_web::actix::{Actor, AsyncContext, Context, Handler, System, SystemService};
 use actix_web::http;
 use actix_web::middleware::cors::Cors;
 use actix_web::{fs::StaticFiles, server, App};
@@ -5,9 +7,12 @@ use bigneon_db::utils::errors::DatabaseError;
 use config::Config;
 use db::*;
 use domain_events::DomainActionMonitor;
-use log::Level::Debug;
-use middleware::{AppVersionHeader, BigNeonLogger, DatabaseTransaction, Metatags};
+use log::Level::{Debug, Info};
+use middleware::{AppVersionHeader, BigNeonLogger, DatabaseTransaction, ExportFormat, Metatags};
 use routing;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use utils::spotify;
 use utils::ServiceLocator;
 
@@ -36,6 +41,39 @@ pub struct Server {
     pub config: Config,
 }
 
+/// Stops the actix system on a shutdown signal or once the domain action monitor has stopped,
+/// used to keep the process alive when only running the domain action monitor
+struct DomainActionMonitorShutdown {
+    monitor_stopped: Arc<AtomicBool>,
+}
+
+impl Actor for DomainActionMonitorShutdown {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ProcessSignals::from_registry().do_send(Subscribe(ctx.address().recipient()));
+        ctx.run_interval(Duration::from_secs(1), |act, _ctx| {
+            if act.monitor_stopped.load(Ordering::SeqCst) {
+                System::current().stop();
+            }
+        });
+    }
+}
+
+impl Handler<Signal> for DomainActionMonitorShutdown {
+    type Result = ();
+
+    fn handle(&mut self, msg: Signal, _ctx: &mut Context<Self>) {
+        match msg.0 {
+            SignalType::Int | SignalType::Term | SignalType::Quit => {
+                jlog!(Info, "bigneon_api::server", "Shutdown signal received, draining domain action monitor", {});
+                System::current().stop();
+            }
+            _ => (),
+        }
+    }
+}
+
 impl Server {
     pub fn start(
         config: Config,
@@ -50,7 +88,11 @@ impl Server {
         let database = Database::from_config(&config);
         let database_ro = Database::readonly_from_config(&config);
 
-        let mut domain_action_monitor = DomainActionMonitor::new(config.clone(), database.clone(), 1);
+        let mut domain_action_monitor = DomainActionMonitor::new(
+            config.clone(),
+            database.clone(),
+            config.domain_action_monitor.poll_period_in_secs,
+        );
         if process_actions_til_empty {
             domain_action_monitor.run_til_empty().unwrap();
             return;
@@ -80,6 +122,7 @@ impl Server {
                         .middleware(BigNeonLogger::new(LOGGER_FORMAT))
                         .middleware(DatabaseTransaction::new())
                         .middleware(AppVersionHeader::new())
+                        .middleware(ExportFormat::new())
                         .middleware(Metatags::new(
                             conf.ssr_trigger_header.clone(),
                             conf.ssr_trigger_value.clone(),
@@ -102,7 +145,7 @@ impl Server {
                                         .unwrap(),
                                 ])
                                 .allowed_header(http::header::CONTENT_TYPE)
-                                .expose_headers(vec!["x-app-version"])
+                                .expose_headers(vec!["x-app-version", "content-disposition"])
                                 .max_age(3600);
 
                             routing::routes(&mut cors_config)
@@ -127,8 +170,14 @@ impl Server {
             if process_actions || process_events {
                 domain_action_monitor.stop()
             }
-        } else {
-            domain_action_monitor.wait_for_end();
+        } else if process_actions || process_events {
+            let system = System::new("bigneon-domain-actions");
+            DomainActionMonitorShutdown {
+                monitor_stopped: domain_action_monitor.stopped_flag(),
+            }
+            .start();
+            system.run();
+            domain_action_monitor.stop();
         }
     }
 }
@@ -103,6 +103,7 @@ fn send_email_template(
     };
 
     match template.provider {
+        // Customer.io events do not support attachments
         EmailProvider::CustomerIo => {
             // At some point there was some confusion and now we have both `extra_data` and
             // `template_data` which are both the same thing. This is because only emails use
@@ -148,6 +149,9 @@ fn send_email_template(
                 communication.template_data.as_ref().unwrap(),
                 communication.categories.clone(),
                 Some(sendgrid_extra_data),
+                communication
+                    .attachments
+                    .map(|attachments| attachments.into_iter().map(|a| a.into()).collect()),
             )
         } // Customer IO
     }
@@ -9,6 +9,7 @@ pub mod google_recaptcha;
 pub mod sendgrid;
 pub mod serializers;
 mod service_locator;
+pub mod spreadsheet;
 pub mod spotify;
 pub mod twilio;
 pub mod webhook;
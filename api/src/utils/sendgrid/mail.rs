@@ -115,6 +115,7 @@ pub fn send_email_template_async(
     template_data: &[TemplateData],
     categories: Option<Vec<String>>,
     unique_args: Option<HashMap<String, String>>,
+    attachments: Option<Vec<SGAttachment>>,
 ) -> Box<dyn Future<Item = (), Error = BigNeonError>> {
     Box::new(if dest_email_addresses.len() != template_data.len() {
         Either::A(future::err(
@@ -138,6 +139,7 @@ pub fn send_email_template_async(
         sg_message.content.push(msg_content);
         sg_message.unique_args = unique_args;
         sg_message.category = categories;
+        sg_message.attachments = attachments;
 
         Either::B(sg_message.send_async(&sg_api_key))
     })
@@ -186,6 +188,25 @@ impl SGContent {
     }
 }
 
+#[derive(Clone, Serialize)]
+pub struct SGAttachment {
+    /// Base64 encoded file contents
+    pub content: String,
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub filename: String,
+}
+
+impl From<CommAttachment> for SGAttachment {
+    fn from(attachment: CommAttachment) -> Self {
+        SGAttachment {
+            content: attachment.content,
+            content_type: attachment.content_type,
+            filename: attachment.filename,
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct SGPersonalization {
     pub to: Vec<SGEmail>,
@@ -233,6 +254,8 @@ pub struct SGMailMessage {
     pub unique_args: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<SGAttachment>>,
 }
 
 impl SGMailMessage {
@@ -245,6 +268,7 @@ impl SGMailMessage {
             template_id: None,
             unique_args: None,
             category: None,
+            attachments: None,
         }
     }
 
@@ -304,6 +328,25 @@ mod tests {
         test_msg.unique_args = Some(map);
         test_msg.category = Some(vec!["cat1".to_string(), "cat2".to_string()]);
         let actual = json!(test_msg).to_string();
-        assert_eq!(r#"{"category":["cat1","cat2"],"content":[],"from":{"email":""},"personalizations":[],"unique_args":{"k_one":"v_one","k_two":"v_two"}}"#, actual);
+        assert_eq!(
+            r#"{"category":["cat1","cat2"],"content":[],"from":{"email":""},"personalizations":[],"unique_args":{"k_one":"v_one","k_two":"v_two"}}"#,
+            actual
+        );
+    }
+
+    #[test]
+    pub fn serialize_mail_message_with_attachments() {
+        let mut test_msg = SGMailMessage::new();
+        test_msg.attachments = Some(vec![CommAttachment::new(
+            "report.csv".to_string(),
+            "text/csv".to_string(),
+            b"a,b\n",
+        )
+        .into()]);
+        let actual = json!(test_msg).to_string();
+        assert_eq!(
+            r#"{"attachments":[{"content":"YSxiCg==","filename":"report.csv","type":"text/csv"}],"content":[],"from":{"email":""},"personalizations":[]}"#,
+            actual
+        );
     }
 }
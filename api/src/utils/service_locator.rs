@@ -1,9 +1,10 @@
 use bigneon_db::prelude::*;
 use bigneon_db::services::CountryLookup;
 use bigneon_db::utils::errors::DatabaseError;
-use config::Config;
+use config::{Config, HostedCheckoutSettings};
 use errors::*;
 use payments::globee::GlobeePaymentProcessor;
+use payments::hosted_checkout::HostedCheckoutPaymentProcessor;
 use payments::stripe::StripePaymentProcessor;
 use payments::PaymentProcessor;
 use utils::deep_linker::BranchDeepLinker;
@@ -13,6 +14,7 @@ pub struct ServiceLocator {
     stripe_secret_key: String,
     globee_api_key: String,
     globee_base_url: String,
+    hosted_checkout: Option<HostedCheckoutSettings>,
     branch_io_base_url: String,
     branch_io_branch_key: String,
     api_keys_encryption_key: String,
@@ -32,6 +34,7 @@ impl ServiceLocator {
             stripe_secret_key: config.stripe_secret_key.clone(),
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
+            hosted_checkout: config.hosted_checkout.clone(),
             branch_io_base_url: config.branch_io_base_url.clone(),
             branch_io_branch_key: config.branch_io_branch_key.clone(),
             api_keys_encryption_key: config.api_keys_encryption_key.clone(),
@@ -60,6 +63,13 @@ impl ServiceLocator {
                     self.globee_base_url.clone(),
                 )))
             }
+            PaymentProviders::HostedCheckout => match self.hosted_checkout {
+                Some(ref settings) => Ok(Box::new(HostedCheckoutPaymentProcessor::new(
+                    settings.api_key.clone(),
+                    settings.base_url.clone(),
+                ))),
+                None => Err(ApplicationError::new("Hosted checkout is not configured".into()).into()),
+            },
             // External is not valid for service locator
             PaymentProviders::Free | PaymentProviders::External => {
                 return Err(ApplicationError::new("Unknown payment provider".into()).into());
@@ -78,6 +88,7 @@ impl ServiceLocator {
         match provider.to_lowercase().as_str() {
             "stripe" => true,
             "globee" => false,
+            "hostedcheckout" => true,
             "external" => false,
             _ => false,
         }
@@ -0,0 +1,465 @@
+use bigneon_db::prelude::*;
+use csv;
+use errors::*;
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use serde::Serialize;
+use serde_json;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const SHEET_NAME: &str = "Report";
+
+/// Languages that write decimals with a comma, spreadsheet applications expect semicolon delimited CSV files for these
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "af", "bg", "cs", "da", "de", "el", "es", "fi", "fr", "hr", "hu", "id", "it", "nb", "nl", "nn", "no", "pl", "pt",
+    "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+</Types>"#;
+
+const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#;
+
+const WORKBOOK_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+</Relationships>"#;
+
+/// Number formatting used when writing CSV files. XLSX files store numbers as numeric cells which are
+/// displayed in the reader's locale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExportLocale {
+    pub decimal_separator: char,
+    pub delimiter: u8,
+}
+
+impl Default for ExportLocale {
+    fn default() -> ExportLocale {
+        ExportLocale {
+            decimal_separator: '.',
+            delimiter: b',',
+        }
+    }
+}
+
+impl ExportLocale {
+    /// Locale for a language tag such as `de-DE` or `en`, unknown languages use the default
+    pub fn from_language_tag(tag: &str) -> ExportLocale {
+        let language = tag
+            .split(|c| c == '-' || c == '_')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_lowercase();
+        if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) {
+            ExportLocale {
+                decimal_separator: ',',
+                delimiter: b';',
+            }
+        } else {
+            ExportLocale::default()
+        }
+    }
+}
+
+pub fn content_type(format: ReportFormats) -> &'static str {
+    match format {
+        ReportFormats::Csv => "text/csv",
+        ReportFormats::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    }
+}
+
+pub fn file_extension(format: ReportFormats) -> &'static str {
+    match format {
+        ReportFormats::Csv => "csv",
+        ReportFormats::Xlsx => "xlsx",
+    }
+}
+
+pub fn render(format: ReportFormats, table: &ReportTable, locale: ExportLocale) -> Result<Vec<u8>, BigNeonError> {
+    match format {
+        ReportFormats::Csv => to_csv(table, locale),
+        ReportFormats::Xlsx => to_xlsx(table),
+    }
+}
+
+/// Builds a table from JSON rows such as a `Payload`'s data. Nested objects are flattened into dotted column
+/// names, e.g. `event.name`, and columns are sorted by name so headers do not depend on the order of fields.
+pub fn table_from_json(rows: &[Value]) -> ReportTable {
+    let flattened: Vec<BTreeMap<String, Value>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = BTreeMap::new();
+            flatten_json("", row, &mut cells);
+            cells
+        })
+        .collect();
+    let names: BTreeSet<String> = flattened.iter().flat_map(|cells| cells.keys().cloned()).collect();
+    // A field that is null in some rows and an object in others only gets the object's columns
+    let columns: Vec<String> = names
+        .iter()
+        .filter(|name| {
+            let prefix = format!("{}.", name);
+            !names.iter().any(|other| other.starts_with(&prefix))
+        })
+        .cloned()
+        .collect();
+    let rows = flattened
+        .into_iter()
+        .map(|mut cells| {
+            columns
+                .iter()
+                .map(|column| cells.remove(column).unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    ReportTable { columns, rows }
+}
+
+/// Builds a table from typed rows, see `table_from_json`. Without any rows the header is taken from the
+/// row type's fields so that empty exports still have column headers.
+pub fn table_from_rows<T>(rows: &[T]) -> Result<ReportTable, BigNeonError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    if rows.is_empty() {
+        let mut columns = field_names::<T>();
+        columns.sort();
+        return Ok(ReportTable { columns, rows: vec![] });
+    }
+    let rows = rows
+        .iter()
+        .map(|row| serde_json::to_value(row))
+        .collect::<Result<Vec<Value>, _>>()?;
+    Ok(table_from_json(&rows))
+}
+
+/// Top level field names of a struct, read from its `Deserialize` implementation as there is no value to
+/// serialize. Types other than structs, including structs with flattened fields, have no field names.
+pub fn field_names<T: DeserializeOwned>() -> Vec<String> {
+    let mut names = vec![];
+    // Field names are recorded before the deserializer gives up, the error is expected
+    let _ = T::deserialize(FieldNames(&mut names));
+    names
+}
+
+struct FieldNames<'a>(&'a mut Vec<String>);
+
+impl<'de, 'a> Deserializer<'de> for FieldNames<'a> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("Only struct field names can be read"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.extend(fields.iter().map(|field| field.to_string()));
+        self.deserialize_any(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option unit unit_struct
+        newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Writes the table as CSV with the column names as the header row
+pub fn to_csv(table: &ReportTable, locale: ExportLocale) -> Result<Vec<u8>, BigNeonError> {
+    let mut csv = csv_header(&table.columns, locale)?;
+    for row in &table.rows {
+        csv.extend(csv_record(row, locale)?);
+    }
+    Ok(csv)
+}
+
+/// CSV header row for the column names
+pub fn csv_header(columns: &[String], locale: ExportLocale) -> Result<Vec<u8>, BigNeonError> {
+    write_csv_record(columns.iter().cloned(), locale)
+}
+
+/// CSV row for the values, decimals are written with the locale's decimal separator
+pub fn csv_record(row: &[Value], locale: ExportLocale) -> Result<Vec<u8>, BigNeonError> {
+    write_csv_record(
+        row.iter().map(|value| match value {
+            Value::Number(number) if number.is_f64() => {
+                number.to_string().replace('.', &locale.decimal_separator.to_string())
+            }
+            _ => cell_text(value),
+        }),
+        locale,
+    )
+}
+
+fn write_csv_record<I: Iterator<Item = String>>(fields: I, locale: ExportLocale) -> Result<Vec<u8>, BigNeonError> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(locale.delimiter)
+        .from_writer(vec![]);
+    writer.write_record(fields).map_err(spreadsheet_error)?;
+    writer
+        .into_inner()
+        .map_err(|e| ApplicationError::new(format!("Could not write spreadsheet: {}", e)).into())
+}
+
+/// Writes the table as a single sheet Office Open XML workbook. Numbers are written as numeric cells,
+/// everything else as inline strings so no shared strings table is needed.
+pub fn to_xlsx(table: &ReportTable) -> Result<Vec<u8>, BigNeonError> {
+    let mut sheet = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    let header: Vec<Value> = table.columns.iter().map(|c| json!(c)).collect();
+    for (row_index, row) in Some(&header).into_iter().chain(table.rows.iter()).enumerate() {
+        sheet.push_str(&format!(r#"<row r="{}">"#, row_index + 1));
+        for (column_index, value) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(column_index), row_index + 1);
+            match value {
+                Value::Null => continue,
+                Value::Number(number) => {
+                    sheet.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, number));
+                }
+                _ => {
+                    sheet.push_str(&format!(
+                        r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                        reference,
+                        escape_xml(&cell_text(value))
+                    ));
+                }
+            }
+        }
+        sheet.push_str("</row>");
+    }
+    sheet.push_str("</sheetData></worksheet>");
+
+    let workbook = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        SHEET_NAME
+    );
+
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in &[
+        ("[Content_Types].xml", CONTENT_TYPES_XML),
+        ("_rels/.rels", RELS_XML),
+        ("xl/workbook.xml", workbook.as_str()),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS_XML),
+        ("xl/worksheets/sheet1.xml", sheet.as_str()),
+    ] {
+        zip.start_file(*name, options).map_err(spreadsheet_error)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    Ok(zip.finish().map_err(spreadsheet_error)?.into_inner())
+}
+
+fn spreadsheet_error<E: ToString>(error: E) -> BigNeonError {
+    ApplicationError::new(format!("Could not write spreadsheet: {}", error.to_string())).into()
+}
+
+fn flatten_json(prefix: &str, value: &Value, cells: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, field) in fields {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_json(&column, field, cells);
+            }
+        }
+        _ => {
+            let column = if prefix.is_empty() { "value" } else { prefix };
+            cells.insert(column.to_string(), value.clone());
+        }
+    }
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::String(text) => text.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Spreadsheet column name for the zero based index, e.g. 0 is `A` and 27 is `AB`
+fn column_name(index: usize) -> String {
+    let mut name = vec![];
+    let mut index = index + 1;
+    while index > 0 {
+        let remainder = (index - 1) % 26;
+        name.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+    name.into_iter().collect()
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            // Control characters other than whitespace are not allowed in XML
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn table() -> ReportTable {
+        ReportTable {
+            columns: vec!["name".to_string(), "quantity".to_string(), "code".to_string()],
+            rows: vec![
+                vec![json!("Early \"Bird\", GA"), json!(2), Value::Null],
+                vec![json!("VIP <& more>"), json!(1), json!("CODE")],
+            ],
+        }
+    }
+
+    #[test]
+    fn csv() {
+        let csv = String::from_utf8(to_csv(&table(), ExportLocale::default()).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "name,quantity,code\n\"Early \"\"Bird\"\", GA\",2,\nVIP <& more>,1,CODE\n"
+        );
+    }
+
+    #[test]
+    fn csv_for_locale() {
+        let table = ReportTable {
+            columns: vec!["name".to_string(), "rate".to_string(), "quantity".to_string()],
+            rows: vec![vec![json!("GA; Early"), json!(2.5), json!(1000)]],
+        };
+        let csv = String::from_utf8(to_csv(&table, ExportLocale::from_language_tag("de-DE")).unwrap()).unwrap();
+        assert_eq!(csv, "name;rate;quantity\n\"GA; Early\";2,5;1000\n");
+        let csv = String::from_utf8(to_csv(&table, ExportLocale::from_language_tag("en-US")).unwrap()).unwrap();
+        assert_eq!(csv, "name,rate,quantity\nGA; Early,2.5,1000\n");
+    }
+
+    #[test]
+    fn export_locale_from_language_tag() {
+        assert_eq!(ExportLocale::from_language_tag("en-US"), ExportLocale::default());
+        assert_eq!(ExportLocale::from_language_tag("*"), ExportLocale::default());
+        assert_eq!(ExportLocale::from_language_tag("fr_CA").decimal_separator, ',');
+        assert_eq!(ExportLocale::from_language_tag("PT-br").delimiter, b';');
+    }
+
+    #[test]
+    fn table_from_json_rows() {
+        let table = table_from_json(&[
+            json!({"name": "GA", "event": {"name": "Event1", "venue": {"city": "Cape Town"}}, "tags": ["a"]}),
+            json!({"name": "VIP", "event": null, "redeemed_at": "2020-01-01T10:00:00"}),
+        ]);
+        assert_eq!(
+            table.columns,
+            vec!["event.name", "event.venue.city", "name", "redeemed_at", "tags"]
+        );
+        assert_eq!(
+            table.rows,
+            vec![
+                vec![
+                    json!("Event1"),
+                    json!("Cape Town"),
+                    json!("GA"),
+                    Value::Null,
+                    json!(["a"])
+                ],
+                vec![
+                    Value::Null,
+                    Value::Null,
+                    json!("VIP"),
+                    json!("2020-01-01T10:00:00"),
+                    Value::Null
+                ],
+            ]
+        );
+
+        let table = table_from_json(&[json!("a"), json!("b")]);
+        assert_eq!(table.columns, vec!["value"]);
+        assert_eq!(table.rows, vec![vec![json!("a")], vec![json!("b")]]);
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct Row {
+        name: String,
+        #[serde(rename = "count")]
+        quantity: u32,
+        code: Option<String>,
+    }
+
+    #[test]
+    fn table_from_typed_rows() {
+        let rows = vec![Row {
+            name: "GA".to_string(),
+            quantity: 2,
+            code: None,
+        }];
+        let table = table_from_rows(&rows).unwrap();
+        assert_eq!(table.columns, vec!["code", "count", "name"]);
+        assert_eq!(table.rows, vec![vec![Value::Null, json!(2), json!("GA")]]);
+
+        // Empty tables still have the columns of the row type
+        let table = table_from_rows::<Row>(&[]).unwrap();
+        assert_eq!(table.columns, vec!["code", "count", "name"]);
+        assert!(table.rows.is_empty());
+
+        assert_eq!(field_names::<Row>(), vec!["name", "count", "code"]);
+        assert!(field_names::<String>().is_empty());
+    }
+
+    #[test]
+    fn xlsx() {
+        let xlsx = to_xlsx(&table()).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(xlsx)).unwrap();
+        assert!(archive.by_name("xl/workbook.xml").is_ok());
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(sheet.contains(r#"<c r="A1" t="inlineStr"><is><t xml:space="preserve">name</t></is></c>"#));
+        assert!(sheet.contains(r#"<c r="B2"><v>2</v></c>"#));
+        assert!(!sheet.contains(r#"<c r="C2""#));
+        assert!(sheet.contains("VIP &lt;&amp; more&gt;"));
+    }
+
+    #[test]
+    fn column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+}
@@ -4,7 +4,9 @@ use diesel::PgConnection;
 use errors::*;
 use serde_json;
 use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
 use tokio::prelude::*;
+use url::Url;
 use utils::webhook_adapters::{CustomerIoWebhookAdapter, NullAdapter, WebhookAdapter};
 use uuid::Uuid;
 
@@ -30,26 +32,143 @@ pub fn send_webhook(
 ) -> Result<(), BigNeonError> {
     let adapter = match domain_event_publisher_id {
         None => Box::new(NullAdapter::new()) as Box<dyn WebhookAdapter>,
-        Some(id) => {
-            let domain_event_publisher = DomainEventPublisher::find(id, conn)?;
-            let mut adapter = match domain_event_publisher.adapter {
-                None => Box::new(NullAdapter::new()) as Box<dyn WebhookAdapter>,
-                Some(x) => match x {
-                    WebhookAdapters::CustomerIo => {
-                        Box::new(CustomerIoWebhookAdapter::new(config)) as Box<dyn WebhookAdapter>
-                    }
-                },
-            };
-
-            if let Some(config) = domain_event_publisher.adapter_config {
-                adapter.initialize(config);
-            };
-
-            adapter
-        }
+        Some(id) => adapter_for(&DomainEventPublisher::find(id, conn)?, config),
     };
 
     let payload: HashMap<String, serde_json::Value> = serde_json::from_str(body)?;
 
-    adapter.send(webhook_urls, payload)
+    let response = adapter.send(webhook_urls, payload)?;
+    if !response.is_success() {
+        return Err(
+            ApplicationError::new(format!("Webhook request failed with status {}", response.status_code)).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Sends `body` to the publisher's webhook, recording the attempt and its response
+pub fn deliver(
+    domain_event_publisher: &DomainEventPublisher,
+    body: HashMap<String, serde_json::Value>,
+    domain_event_id: Option<Uuid>,
+    domain_action_id: Option<Uuid>,
+    attempt: i64,
+    conn: &PgConnection,
+    config: &Config,
+) -> Result<WebhookDelivery, BigNeonError> {
+    let mut delivery = WebhookDelivery::create(
+        domain_event_publisher.id,
+        domain_event_id,
+        domain_action_id,
+        domain_event_publisher.webhook_url.clone(),
+        attempt,
+    );
+    if domain_event_publisher.organization_id.is_some() {
+        if let Err(e) = validate_public_webhook_url(&domain_event_publisher.webhook_url) {
+            delivery.error = Some(e.to_string());
+            return Ok(delivery.commit(conn)?);
+        }
+    }
+    if config.environment == Environment::Test || config.block_external_comms {
+        delivery.error = Some("External communications are blocked".to_string());
+        return Ok(delivery.commit(conn)?);
+    }
+
+    match adapter_for(domain_event_publisher, config).send(&[domain_event_publisher.webhook_url.clone()], body) {
+        Ok(response) => {
+            delivery.succeeded = response.is_success();
+            delivery.status_code = Some(response.status_code as i32);
+            delivery.response_body = Some(response.body);
+        }
+        Err(e) => delivery.error = Some(e.to_string()),
+    }
+    Ok(delivery.commit(conn)?)
+}
+
+/// Organization webhooks may only be sent to public addresses so they cannot be used to reach internal
+/// services. The host is resolved when validating, so this is checked again before each delivery.
+pub fn validate_public_webhook_url(webhook_url: &str) -> Result<(), BigNeonError> {
+    let invalid_url = || ApplicationError::unprocessable("Webhook URL must be a public http or https URL");
+    let url = Url::parse(webhook_url).map_err(|_| invalid_url())?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(invalid_url().into());
+    }
+    let host = url.host_str().ok_or_else(invalid_url)?;
+    let port = url.port_or_known_default().ok_or_else(invalid_url)?;
+    // IPv6 hosts are bracketed in URLs
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let addresses: Vec<IpAddr> = (host, port)
+        .to_socket_addrs()
+        .map_err(|_| ApplicationError::unprocessable("Webhook URL host could not be resolved"))?
+        .map(|address| address.ip())
+        .collect();
+    if addresses.is_empty() || addresses.iter().any(|address| !is_public_address(address)) {
+        return Err(invalid_url().into());
+    }
+    Ok(())
+}
+
+fn is_public_address(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_ipv4_address(address),
+        IpAddr::V6(address) => {
+            if let Some(address) = address.to_ipv4() {
+                // IPv4 mapped and compatible addresses reach the IPv4 address, :: and ::1 are handled below
+                if !address.is_unspecified() && address != Ipv4Addr::new(0, 0, 0, 1) {
+                    return is_public_ipv4_address(&address);
+                }
+            }
+            is_public_ipv6_address(address)
+        }
+    }
+}
+
+fn is_public_ipv4_address(address: &Ipv4Addr) -> bool {
+    let octets = address.octets();
+    !(address.is_private()
+        || address.is_loopback()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_unspecified()
+        || address.is_multicast()
+        // 0.0.0.0/8 "this network"
+        || octets[0] == 0
+        // 100.64.0.0/10 carrier-grade NAT
+        || (octets[0] == 100 && octets[1] & 0xc0 == 64)
+        // 192.0.0.0/24 IETF protocol assignments
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // 198.18.0.0/15 benchmarking
+        || (octets[0] == 198 && octets[1] & 0xfe == 18)
+        // 240.0.0.0/4 reserved
+        || octets[0] >= 240)
+}
+
+fn is_public_ipv6_address(address: &Ipv6Addr) -> bool {
+    let first_segment = address.segments()[0];
+    !(address.is_loopback()
+        || address.is_unspecified()
+        || address.is_multicast()
+        // fc00::/7 unique local
+        || first_segment & 0xfe00 == 0xfc00
+        // fe80::/10 link local
+        || first_segment & 0xffc0 == 0xfe80)
+}
+
+/// Adapter delivering the publisher's webhooks, without an adapter configured webhooks are posted
+/// to the publisher's URL signed with its signing secret
+pub fn adapter_for(domain_event_publisher: &DomainEventPublisher, config: &Config) -> Box<dyn WebhookAdapter> {
+    let mut adapter = match domain_event_publisher.adapter {
+        None => Box::new(NullAdapter::signed_by(domain_event_publisher.clone())) as Box<dyn WebhookAdapter>,
+        Some(x) => match x {
+            WebhookAdapters::CustomerIo => Box::new(CustomerIoWebhookAdapter::new(config)) as Box<dyn WebhookAdapter>,
+        },
+    };
+
+    if let Some(ref adapter_config) = domain_event_publisher.adapter_config {
+        adapter.initialize(adapter_config.clone());
+    };
+
+    adapter
 }
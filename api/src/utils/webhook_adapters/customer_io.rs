@@ -5,7 +5,7 @@ use log::Level::Debug;
 use serde_json::Value;
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
-use utils::webhook_adapters::WebhookAdapter;
+use utils::webhook_adapters::{WebhookAdapter, WebhookResponse};
 
 pub struct CustomerIoWebhookAdapter {
     site_id: String,
@@ -29,7 +29,11 @@ impl WebhookAdapter for CustomerIoWebhookAdapter {
         self.api_key = config["api_key"].as_str().unwrap().to_string();
     }
 
-    fn send(&self, _webhook_urls: &[String], payload: HashMap<String, Value, RandomState>) -> Result<(), BigNeonError> {
+    fn send(
+        &self,
+        _webhook_urls: &[String],
+        payload: HashMap<String, Value, RandomState>,
+    ) -> Result<WebhookResponse, BigNeonError> {
         let client = reqwest::Client::new();
         let mut payload = payload;
         payload.insert("environment".to_string(), json!(self.environment));
@@ -40,7 +44,10 @@ impl WebhookAdapter for CustomerIoWebhookAdapter {
                     // For user created messages, send a pre event to create the user in customer.io
 
                     if webhook_event_type == "temporary_user_created" || webhook_event_type == "user_created" {
-                        self.send_user_created_message(&payload, &user_id)?;
+                        let response = self.send_user_created_message(&payload, &user_id)?;
+                        if !response.is_success() {
+                            return Ok(response);
+                        }
                     };
 
                     client
@@ -58,9 +65,7 @@ impl WebhookAdapter for CustomerIoWebhookAdapter {
             None => client.post("https://track.customer.io/api/v1/events").json(&payload),
         };
 
-        self.send_request(client, &payload)?;
-
-        Ok(())
+        self.send_request(client, &payload)
     }
 }
 
@@ -69,30 +74,27 @@ impl CustomerIoWebhookAdapter {
         &self,
         client: reqwest::RequestBuilder,
         payload: &HashMap<String, Value, RandomState>,
-    ) -> Result<(), BigNeonError> {
+    ) -> Result<WebhookResponse, BigNeonError> {
         jlog!(
             Debug,
             "bigneon::domain_actions",
             "Sending event/customer to customer.io",
             { "payload": &payload }
         );
-        let mut resp = client
+        let resp = client
             .basic_auth(&self.site_id, Some(&self.api_key))
             .send()
             .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
-        let text = resp
-            .text()
-            .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
-        jlog!(Debug, "bigneon::domain_actions", "Response from customer.io", {"text": text, "status": resp.status().to_string()});
-        resp.error_for_status()?;
-        Ok(())
+        let response = WebhookResponse::from_response(resp);
+        jlog!(Debug, "bigneon::domain_actions", "Response from customer.io", {"text": &response.body, "status": response.status_code});
+        Ok(response)
     }
 
     fn send_user_created_message(
         &self,
         payload: &HashMap<String, Value, RandomState>,
         user_id: &str,
-    ) -> Result<(), BigNeonError> {
+    ) -> Result<WebhookResponse, BigNeonError> {
         let client = reqwest::Client::new();
         let client = client
             .put(&format!("https://track.customer.io/api/v1/customers/{}", user_id))
@@ -11,5 +11,29 @@ pub use self::null_adapter::*;
 
 pub trait WebhookAdapter {
     fn initialize(&mut self, config: Value);
-    fn send(&self, webhook_urls: &[String], payload: HashMap<String, Value, RandomState>) -> Result<(), BigNeonError>;
+    fn send(
+        &self,
+        webhook_urls: &[String],
+        payload: HashMap<String, Value, RandomState>,
+    ) -> Result<WebhookResponse, BigNeonError>;
+}
+
+/// Status and body of the response received for a webhook request
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookResponse {
+    pub status_code: u16,
+    pub body: String,
+}
+
+impl WebhookResponse {
+    pub fn from_response(mut response: reqwest::Response) -> WebhookResponse {
+        WebhookResponse {
+            status_code: response.status().as_u16(),
+            body: response.text().unwrap_or_default(),
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status_code >= 200 && self.status_code < 300
+    }
 }
@@ -1,37 +1,70 @@
+use bigneon_db::models::*;
+use chrono::prelude::*;
 use errors::{ApplicationError, BigNeonError};
 use log::Level::Debug;
-use serde_json::Value;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{self, Value};
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
-use utils::webhook_adapters::WebhookAdapter;
+use utils::webhook_adapters::{WebhookAdapter, WebhookResponse};
 
-pub struct NullAdapter {}
+pub struct NullAdapter {
+    domain_event_publisher: Option<DomainEventPublisher>,
+}
 
 impl NullAdapter {
     pub fn new() -> NullAdapter {
-        NullAdapter {}
+        NullAdapter {
+            domain_event_publisher: None,
+        }
+    }
+
+    /// Signs each request with the publisher's signing secret
+    pub fn signed_by(domain_event_publisher: DomainEventPublisher) -> NullAdapter {
+        NullAdapter {
+            domain_event_publisher: Some(domain_event_publisher),
+        }
     }
 }
 
 impl WebhookAdapter for NullAdapter {
     fn initialize(&mut self, _config: Value) {}
 
-    fn send(&self, webhook_urls: &[String], payload: HashMap<String, Value, RandomState>) -> Result<(), BigNeonError> {
-        let client = reqwest::Client::new();
+    fn send(
+        &self,
+        webhook_urls: &[String],
+        payload: HashMap<String, Value, RandomState>,
+    ) -> Result<WebhookResponse, BigNeonError> {
+        // Redirects are not followed as they could lead to addresses webhooks are not allowed to reach
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::RedirectPolicy::none())
+            .build()
+            .map_err(|_err| ApplicationError::new("Error building webhook client".to_string()))?;
+        let body = serde_json::to_string(&payload)?;
+        let mut response = None;
         for webhook_url in webhook_urls {
-            let mut resp = client
+            let mut request = client
                 .post(webhook_url)
-                .json(&payload)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(ref domain_event_publisher) = self.domain_event_publisher {
+                let timestamp = Utc::now().timestamp();
+                request = request
+                    .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+                    .header(WEBHOOK_SIGNATURE_HEADER, domain_event_publisher.sign(timestamp, &body));
+            }
+            let resp = request
                 .send()
                 .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
+            let webhook_response = WebhookResponse::from_response(resp);
 
-            let text = resp
-                .text()
-                .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
-
-            jlog!(Debug, "bigneon::domain_actions", "Response from customer.io", {"text": text, "status": resp.status().to_string()});
-            resp.error_for_status()?;
+            jlog!(Debug, "bigneon::domain_actions", "Response from webhook", {"text": &webhook_response.body, "status": webhook_response.status_code});
+            if !webhook_response.is_success() {
+                return Ok(webhook_response);
+            }
+            response = Some(webhook_response);
         }
-        Ok(())
+
+        response.ok_or_else(|| ApplicationError::new("No webhook URL to send to".to_string()).into())
     }
 }
@@ -0,0 +1,294 @@
+use actix_web::{FromRequest, HttpResponse, Path};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::api_keys::{self, NewApiKeyRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::middleware::DelegatedAccessAllowed;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::api_keys::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::api_keys::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::api_keys::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::api_keys::index(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn index_door_person() {
+        base::api_keys::index(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn index_promoter() {
+        base::api_keys::index(Roles::Promoter, false);
+    }
+    #[test]
+    fn index_promoter_read_only() {
+        base::api_keys::index(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn index_org_admin() {
+        base::api_keys::index(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn index_box_office() {
+        base::api_keys::index(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::api_keys::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::api_keys::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::api_keys::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::api_keys::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::api_keys::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::api_keys::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::api_keys::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::api_keys::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::api_keys::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod rotate_tests {
+    use super::*;
+    #[test]
+    fn rotate_org_member() {
+        base::api_keys::rotate(Roles::OrgMember, false);
+    }
+    #[test]
+    fn rotate_admin() {
+        base::api_keys::rotate(Roles::Admin, true);
+    }
+    #[test]
+    fn rotate_user() {
+        base::api_keys::rotate(Roles::User, false);
+    }
+    #[test]
+    fn rotate_org_owner() {
+        base::api_keys::rotate(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn rotate_door_person() {
+        base::api_keys::rotate(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn rotate_promoter() {
+        base::api_keys::rotate(Roles::Promoter, false);
+    }
+    #[test]
+    fn rotate_promoter_read_only() {
+        base::api_keys::rotate(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn rotate_org_admin() {
+        base::api_keys::rotate(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn rotate_box_office() {
+        base::api_keys::rotate(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::api_keys::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::api_keys::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::api_keys::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::api_keys::destroy(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::api_keys::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_promoter() {
+        base::api_keys::destroy(Roles::Promoter, false);
+    }
+    #[test]
+    fn destroy_promoter_read_only() {
+        base::api_keys::destroy(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::api_keys::destroy(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::api_keys::destroy(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn create_with_scope_not_held_by_user() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let json = Json(NewApiKeyRequest {
+        name: "Settlements".to_string(),
+        scopes: vec!["settlement:write".to_string()],
+        expires_at: None,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_forbidden(
+        &response,
+        Some("Scope settlement:write cannot be granted to this API key"),
+    );
+}
+
+#[test]
+fn create_with_api_key() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let (api_key, _) = ApiKey::create(
+        organization.id,
+        user.id,
+        "CRM".to_string(),
+        vec![Scopes::OrgAdminUsers, Scopes::OrgFans],
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let test_request = TestRequest::create();
+    let auth_user = AuthUser::new_for_api_key(user, api_key, &test_request.request);
+
+    let json = Json(NewApiKeyRequest {
+        name: "CRM".to_string(),
+        scopes: vec!["org:fans".to_string()],
+        expires_at: None,
+    });
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_forbidden(
+        &response,
+        Some("API keys cannot be managed using delegated credentials"),
+    );
+}
+
+#[test]
+fn api_key_scope_access() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let organization2 = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let (api_key, _) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    let test_request = TestRequest::create();
+    let auth_user = AuthUser::new_for_api_key(user, api_key, &test_request.request);
+
+    // Limited to the key's scopes within its organization
+    assert!(auth_user
+        .has_scope_for_organization(Scopes::OrgFans, &organization, connection)
+        .unwrap());
+    assert!(!auth_user
+        .has_scope_for_organization(Scopes::EventWrite, &organization, connection)
+        .unwrap());
+    assert!(!auth_user
+        .has_scope_for_organization(Scopes::OrgFans, &organization2, connection)
+        .unwrap());
+    assert!(!auth_user.has_scope(Scopes::OrgFans).unwrap());
+
+    // Removing the user from the organization removes the key's access
+    organization.remove_user(auth_user.id(), connection).unwrap();
+    assert!(!auth_user
+        .has_scope_for_organization(Scopes::OrgFans, &organization, connection)
+        .unwrap());
+}
+
+#[test]
+fn api_key_delegated_access() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let path = format!("/organizations/{}/fans", organization.id);
+
+    // Rejected unless the resource allows delegated access
+    let test_request = support::create_api_key_request(&path, &user, &organization, vec![Scopes::OrgFans], &database);
+    support::expects_delegated_credentials_rejected(&test_request);
+
+    let test_request = support::create_api_key_request(&path, &user, &organization, vec![Scopes::OrgFans], &database);
+    test_request.request.extensions_mut().insert(DelegatedAccessAllowed);
+    let auth_user = AuthUser::extract(&test_request.request).unwrap();
+    assert_eq!(auth_user.id(), user.id);
+    assert!(auth_user.is_delegated());
+    assert!(auth_user
+        .has_scope_for_organization(Scopes::OrgFans, &organization, database.connection.get())
+        .unwrap());
+}
@@ -1,9 +1,13 @@
-use actix_web::{http::StatusCode, HttpResponse};
-use bigneon_api::auth::{claims::AccessToken, claims::RefreshToken, TokenResponse};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::auth::{claims::AccessToken, claims::RefreshToken, LoginResponse, TokenResponse};
 use bigneon_api::controllers::auth;
-use bigneon_api::controllers::auth::{LoginRequest, RefreshRequest};
+use bigneon_api::controllers::auth::{LoginRequest, RefreshRequest, TwoFactorTokenRequest};
 use bigneon_api::extractors::*;
 use bigneon_api::models::*;
+use bigneon_db::models::{DomainEvent, DomainEventTypes, Tables, TwoFactorChallenge, TwoFactorCredential, UserSession};
+use bigneon_db::utils::totp;
+use chrono::prelude::*;
 use jwt::{decode, encode, Header, Validation};
 use serde_json;
 use support;
@@ -22,17 +26,23 @@ fn token() {
         .with_password(password.to_string())
         .finish();
 
+    let connection = database.connection.get();
+
     let test_request = TestRequest::create();
     let state = test_request.extract_state();
     let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
 
-    let response: TokenResponse = auth::token((
+    let response = match auth::token((
         test_request.request,
-        database.connection.into(),
+        database.connection.clone().into(),
         json,
         RequestInfo { user_agent: None },
     ))
-    .unwrap();
+    .unwrap()
+    {
+        LoginResponse::Token(response) => response,
+        LoginResponse::TwoFactorChallenge(_) => panic!("Expected token response"),
+    };
 
     let access_token = decode::<AccessToken>(
         &response.access_token,
@@ -52,6 +62,11 @@ fn token() {
 
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
     assert_eq!(refresh_token.claims.get_id().unwrap(), user.id);
+
+    let sessions = UserSession::find_active_for_user(user.id, connection).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(access_token.claims.session_id, Some(sessions[0].id));
+    assert_eq!(refresh_token.claims.session_id, Some(sessions[0].id));
 }
 
 #[test]
@@ -96,16 +111,19 @@ fn token_incorrect_password() {
 fn token_refresh() {
     let database = TestDatabase::new();
     let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None)
+        .commit(database.connection.get())
+        .unwrap();
 
     let test_request = TestRequest::create();
     let state = test_request.extract_state();
     let token_secret = &state.config.token_secret.clone();
-    let refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let refresh_token_claims = RefreshToken::new_for_user_session(&session, false, state.config.token_issuer.clone());
     let refresh_token = encode(&Header::default(), &refresh_token_claims, token_secret.as_bytes()).unwrap();
 
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json)).into();
+    let response: HttpResponse = auth::token_refresh((state, database.connection.clone().into(), json)).into();
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
     let response: TokenResponse = serde_json::from_str(&body).unwrap();
@@ -114,6 +132,90 @@ fn token_refresh() {
         decode::<AccessToken>(&response.access_token, token_secret.as_bytes(), &Validation::default()).unwrap();
     assert_eq!(response.refresh_token, refresh_token);
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert_eq!(access_token.claims.session_id, Some(session.id));
+
+    let domain_events = DomainEvent::find(
+        Tables::UserSessions,
+        Some(session.id),
+        Some(DomainEventTypes::UserSessionRefreshed),
+        database.connection.get(),
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn token_refresh_without_session() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let token_secret = &state.config.token_secret.clone();
+    let refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let refresh_token = encode(&Header::default(), &refresh_token_claims, token_secret.as_bytes()).unwrap();
+
+    let json = Json(RefreshRequest::new(&refresh_token));
+
+    // Tokens issued before sessions were recorded would otherwise survive signing out everywhere
+    let response: HttpResponse = auth::token_refresh((state, database.connection.clone().into(), json)).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Invalid token"}).to_string());
+    assert!(UserSession::find_active_for_user(user.id, database.connection.get())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn access_token_without_session() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None)
+        .commit(database.connection.get())
+        .unwrap();
+    let config = TestRequest::test_config();
+
+    let access_token_claims = AccessToken::new(&user.id, config.token_issuer.clone(), &config.jwt_expiry_time);
+    let access_token = encode(&Header::default(), &access_token_claims, config.token_secret.as_bytes()).unwrap();
+    let test_request = support::create_authorized_request("/users/me", format!("Bearer {}", access_token), &database);
+    let error = AuthUser::extract(&test_request.request).err().unwrap();
+    assert_eq!(
+        error.as_response_error().error_response().status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let access_token_claims =
+        AccessToken::new_for_user_session(&session, false, config.token_issuer.clone(), &config.jwt_expiry_time);
+    let access_token = encode(&Header::default(), &access_token_claims, config.token_secret.as_bytes()).unwrap();
+    let test_request = support::create_authorized_request("/users/me", format!("Bearer {}", access_token), &database);
+    let auth_user = AuthUser::extract(&test_request.request).unwrap();
+    assert_eq!(auth_user.id(), user.id);
+    assert_eq!(auth_user.session_id, Some(session.id));
+}
+
+#[test]
+fn token_refresh_revoked_session() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None)
+        .commit(database.connection.get())
+        .unwrap();
+    session.revoke(Some(user.id), database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let token_secret = &state.config.token_secret.clone();
+    let refresh_token_claims = RefreshToken::new_for_user_session(&session, false, state.config.token_issuer.clone());
+    let refresh_token = encode(&Header::default(), &refresh_token_claims, token_secret.as_bytes()).unwrap();
+
+    let json = Json(RefreshRequest::new(&refresh_token));
+
+    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Invalid token"}).to_string());
 }
 
 #[test]
@@ -211,12 +313,16 @@ fn token_refreshed_after_password_change() {
 
     let user = database.create_user().finish();
     let password_modified_timestamp = user.password_modified_at.timestamp() as u64;
+    let session = UserSession::create(user.id, None, None)
+        .commit(database.connection.get())
+        .unwrap();
 
     let test_request = TestRequest::create();
 
     let state = test_request.extract_state();
     let token_secret = &state.config.token_secret.clone();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let mut refresh_token_claims =
+        RefreshToken::new_for_user_session(&session, false, state.config.token_issuer.clone());
 
     // Issued a second after the latest password
     refresh_token_claims.issued = password_modified_timestamp + 1;
@@ -234,3 +340,185 @@ fn token_refreshed_after_password_change() {
     assert_eq!(response.refresh_token, refresh_token);
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
 }
+
+/// Enables two-factor authentication for the user returning the secret and recovery codes
+fn enable_two_factor(user_id: Uuid, database: &TestDatabase) -> (String, Vec<String>) {
+    let connection = database.connection.get();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let (credential, secret) =
+        TwoFactorCredential::start_enrollment(user_id, &state.config.api_keys_encryption_key, connection).unwrap();
+    let code = totp::generate_code(&secret, totp::time_step(Utc::now().timestamp())).unwrap();
+    let (_, recovery_codes) = credential
+        .enable(&code, &state.config.api_keys_encryption_key, connection)
+        .unwrap();
+    (secret, recovery_codes)
+}
+
+#[test]
+fn token_with_two_factor_enabled() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    enable_two_factor(user.id, &database);
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+
+    match response {
+        LoginResponse::TwoFactorChallenge(challenge) => {
+            assert!(challenge.two_factor_required);
+            let two_factor_challenge =
+                TwoFactorChallenge::find_active_by_token(&challenge.challenge_token, database.connection.get())
+                    .unwrap();
+            assert_eq!(two_factor_challenge.user_id, user.id);
+        }
+        LoginResponse::Token(_) => panic!("Expected two-factor challenge"),
+    }
+}
+
+#[test]
+fn two_factor_token() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (secret, _) = enable_two_factor(user.id, &database);
+    let (_, challenge_token) = TwoFactorChallenge::create(user.id)
+        .commit(database.connection.get())
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(TwoFactorTokenRequest {
+        challenge_token: challenge_token.clone(),
+        code: Some(totp::generate_code(&secret, totp::time_step(Utc::now().timestamp()) + 5).unwrap()),
+        recovery_code: None,
+    });
+    let response: HttpResponse = auth::two_factor_token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let challenge = TwoFactorChallenge::find_active_by_token(&challenge_token, database.connection.get()).unwrap();
+    assert_eq!(challenge.failed_attempts, 1);
+
+    // The code used during enrollment cannot be replayed so the next code is used
+    let code = totp::generate_code(&secret, totp::time_step(Utc::now().timestamp()) + 1).unwrap();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let token_secret = state.config.token_secret.clone();
+    let json = Json(TwoFactorTokenRequest {
+        challenge_token: challenge_token.clone(),
+        code: Some(code),
+        recovery_code: None,
+    });
+    let response: HttpResponse = auth::two_factor_token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let access_token =
+        decode::<AccessToken>(&response.access_token, token_secret.as_bytes(), &Validation::default()).unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert!(access_token.claims.two_factor);
+
+    // Challenges can only be completed once
+    assert!(TwoFactorChallenge::find_active_by_token(&challenge_token, database.connection.get()).is_err());
+}
+
+#[test]
+fn two_factor_token_locked_out() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (secret, _) = enable_two_factor(user.id, &database);
+
+    // Failed attempts are counted across challenges
+    for _ in 0..2 {
+        let (challenge, _) = TwoFactorChallenge::create(user.id)
+            .commit(database.connection.get())
+            .unwrap();
+        for _ in 0..5 {
+            assert!(!challenge
+                .verify(None, Some("invalid"), "", database.connection.get())
+                .unwrap());
+        }
+    }
+
+    let (_, challenge_token) = TwoFactorChallenge::create(user.id)
+        .commit(database.connection.get())
+        .unwrap();
+    let test_request = TestRequest::create();
+    let json = Json(TwoFactorTokenRequest {
+        challenge_token,
+        code: Some(totp::generate_code(&secret, totp::time_step(Utc::now().timestamp()) + 1).unwrap()),
+        recovery_code: None,
+    });
+    let response: HttpResponse = auth::two_factor_token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[test]
+fn two_factor_token_with_recovery_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (_, recovery_codes) = enable_two_factor(user.id, &database);
+    let (_, challenge_token) = TwoFactorChallenge::create(user.id)
+        .commit(database.connection.get())
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(TwoFactorTokenRequest {
+        challenge_token,
+        code: None,
+        recovery_code: Some(recovery_codes[0].clone()),
+    });
+    let response: HttpResponse = auth::two_factor_token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Recovery codes are single use
+    let (_, challenge_token) = TwoFactorChallenge::create(user.id)
+        .commit(database.connection.get())
+        .unwrap();
+    let test_request = TestRequest::create();
+    let json = Json(TwoFactorTokenRequest {
+        challenge_token,
+        code: None,
+        recovery_code: Some(recovery_codes[0].clone()),
+    });
+    let response: HttpResponse = auth::two_factor_token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
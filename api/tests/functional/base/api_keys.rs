@@ -0,0 +1,137 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::api_keys::{self, NewApiKeyRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let organization2 = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let (api_key, _) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    ApiKey::create(
+        organization2.id,
+        user.id,
+        "CRM".to_string(),
+        vec![Scopes::OrgFans],
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response = api_keys::index((database.connection.clone().into(), path, auth_user));
+
+    if should_succeed {
+        let response = response.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let returned_api_keys: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(returned_api_keys.len(), 1);
+        assert_eq!(returned_api_keys[0]["id"], json!(api_key.id));
+        assert!(returned_api_keys[0].get("key_hash").is_none());
+    } else {
+        assert_eq!(
+            response.err().unwrap().to_string(),
+            "User does not have the required permissions"
+        );
+    }
+}
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let json = Json(NewApiKeyRequest {
+        name: "Box office".to_string(),
+        scopes: vec!["event:data-read".to_string()],
+        expires_at: None,
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let key = result["key"].as_str().unwrap();
+    let api_key = ApiKey::find_active_by_key(key, connection).unwrap();
+    assert_eq!(result["api_key"]["id"], json!(api_key.id));
+    assert_eq!(api_key.organization_id, organization.id);
+    assert_eq!(api_key.user_id, user.id);
+    assert_eq!(api_key.scopes().unwrap(), vec![Scopes::EventDataRead]);
+}
+
+pub fn rotate(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let (api_key, key) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = api_key.id;
+    let response: HttpResponse = api_keys::rotate((database.connection.clone().into(), path, auth_user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(ApiKey::find_active_by_key(&key, connection).is_ok());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let rotated_key = result["key"].as_str().unwrap();
+    assert!(ApiKey::find_active_by_key(&key, connection).is_err());
+    assert_eq!(
+        ApiKey::find_active_by_key(rotated_key, connection).unwrap().id,
+        api_key.id
+    );
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let (api_key, key) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = api_key.id;
+    let response: HttpResponse = api_keys::destroy((database.connection.clone().into(), path, auth_user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(ApiKey::find_active_by_key(&key, connection).is_ok());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(ApiKey::find_active_by_key(&key, connection).is_err());
+    assert!(ApiKey::find(api_key.id, connection).unwrap().revoked_at.is_some());
+}
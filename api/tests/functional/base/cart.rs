@@ -42,6 +42,7 @@ pub fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
     });
@@ -113,6 +114,7 @@ pub fn replace_box_office_pricing(role: Roles, should_test_succeed: bool) {
             ticket_type_id: old_ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -136,6 +138,7 @@ pub fn replace_box_office_pricing(role: Roles, should_test_succeed: bool) {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
     });
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::comps::{self, NewCompRequest};
 use bigneon_api::controllers::holds::UpdateHoldRequest;
 use bigneon_api::extractors::*;
@@ -112,6 +112,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         redemption_code: "OHHNOHEREITCOMES".to_string(),
         end_at: None,
         max_per_user: None,
+        seat_ids: None,
     });
 
     let test_request = TestRequest::create();
@@ -0,0 +1,107 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_series::{self, NewEventSeriesRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use chrono::Duration;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn show(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let event_series = EventSeries::create(&event, RecurrenceFrequencies::Weekly, 1, 2, user.id)
+        .commit(connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event_series.id;
+    let response: HttpResponse = event_series::show((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_event_series: DisplayEventSeries = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_event_series.event_series.id, event_series.id);
+    assert_eq!(display_event_series.events.len(), 2);
+}
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+
+    let json = Json(NewEventSeriesRequest {
+        frequency: RecurrenceFrequencies::Weekly,
+        frequency_interval: None,
+        occurrences: 4,
+    });
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        event_series::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(Event::find(event.id, connection).unwrap().event_series_id.is_none());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_event_series: DisplayEventSeries = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_event_series.event_series.template_event_id, event.id);
+    assert_eq!(display_event_series.event_series.frequency_interval, 1);
+    assert_eq!(display_event_series.events.len(), 4);
+    assert_eq!(
+        display_event_series.events[3].event_start,
+        event.event_start.map(|d| d + Duration::weeks(3))
+    );
+}
+
+pub fn update_following(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let event_series = EventSeries::create(&event, RecurrenceFrequencies::Weekly, 1, 3, user.id)
+        .commit(connection)
+        .unwrap();
+
+    let json = Json(EventEditableAttributes {
+        name: Some("New Series Name".to_string()),
+        ..Default::default()
+    });
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        event_series::update_following((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let events: Vec<Event> = serde_json::from_str(&body).unwrap();
+    assert_eq!(events.len(), 3);
+    for event in event_series.events(connection).unwrap() {
+        assert_eq!(event.name, "New Series Name");
+    }
+}
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::events;
 use bigneon_api::controllers::events::*;
 use bigneon_api::extractors::*;
@@ -196,6 +196,40 @@ pub fn update(role: Roles, should_test_succeed: bool) {
     }
 }
 
+pub fn duplicate(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let event_start = event.event_start.unwrap() + Duration::days(7);
+    let json = Json(DuplicateEventRequest {
+        event_start,
+        name: None,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse = events::duplicate((database.connection.into(), path, json, auth_user)).into();
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let new_event: Event = serde_json::from_str(&body).unwrap();
+        assert_ne!(new_event.id, event.id);
+        assert_eq!(new_event.name, event.name);
+        assert_eq!(new_event.status, EventStatus::Draft);
+        assert_eq!(new_event.event_start, Some(event_start));
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
 pub fn delete(role: Roles, should_test_succeed: bool) {
     let database = TestDatabase::new();
     let connection = database.connection.get();
@@ -482,6 +516,7 @@ pub fn dashboard(role: Roles, should_test_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -570,6 +605,39 @@ pub fn guest_list(role: Roles, should_test_succeed: bool) {
     }
 }
 
+pub fn redemption_bundle(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = database.create_user().finish();
+    let tickets = database.create_purchased_tickets(&buyer, ticket_type.id, 2);
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        events::redemption_bundle((database.connection.clone().into(), path, auth_user)).into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let bundle: OfflineRedemptionBundle = support::unwrap_body_to_object(&response).unwrap();
+        assert_eq!(bundle.event_id, event.id);
+        assert_eq!(bundle.tickets.len(), tickets.len());
+        let redemption_key = OfflineRedemptionKey::for_event(&event, connection).unwrap();
+        assert!(bundle.verify(&redemption_key.public_key));
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
 pub fn codes(role: Roles, should_test_succeed: bool) {
     let database = TestDatabase::new();
     let connection = database.connection.get();
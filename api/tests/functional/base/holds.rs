@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::holds;
 use bigneon_api::controllers::holds::*;
 use bigneon_api::extractors::*;
@@ -35,6 +35,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         max_per_user: None,
         quantity: 2,
         ticket_type_id: event.ticket_types(true, None, database.connection.get()).unwrap()[0].id,
+        seat_ids: None,
     });
 
     let test_request = TestRequest::create();
@@ -1,21 +1,28 @@
+pub mod api_keys;
 pub mod artists;
 pub mod cart;
 pub mod codes;
 pub mod comps;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod events;
 pub mod holds;
 pub mod notes;
+pub mod oauth_clients;
 pub mod orders;
 pub mod organization_invites;
 pub mod organizations;
 pub mod regions;
 pub mod reports;
+pub mod saved_reports;
 pub mod settlement_adjustments;
 pub mod settlements;
+pub mod stage_sections;
 pub mod stages;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
 pub mod users;
 pub mod venues;
+pub mod webhooks;
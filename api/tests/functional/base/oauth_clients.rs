@@ -0,0 +1,135 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::oauth_clients::{self, NewOAuthClientRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_oauth_client(database: &TestDatabase, organization: &Organization, user: &User) -> OAuthClient {
+    OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(database.connection.get())
+    .unwrap()
+}
+
+pub fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let organization2 = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    create_oauth_client(&database, &organization2, &user);
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response = oauth_clients::index((database.connection.clone().into(), path, auth_user));
+
+    if should_succeed {
+        let response = response.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let returned_oauth_clients: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(returned_oauth_clients.len(), 1);
+        assert_eq!(returned_oauth_clients[0]["id"], json!(oauth_client.id));
+        assert!(returned_oauth_clients[0].get("client_secret_hash").is_none());
+    } else {
+        assert_eq!(
+            response.err().unwrap().to_string(),
+            "User does not have the required permissions"
+        );
+    }
+}
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let json = Json(NewOAuthClientRequest {
+        name: "Ticket widget".to_string(),
+        redirect_uris: vec!["https://example.com/callback".to_string()],
+        scopes: vec!["event:read".to_string()],
+        confidential: true,
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        oauth_clients::create((database.connection.clone().into(), path, json, auth_user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let client_id = result["oauth_client"]["client_id"].as_str().unwrap();
+    let client_secret = result["client_secret"].as_str().unwrap();
+    let oauth_client = OAuthClient::find_active_by_client_id(client_id, connection).unwrap();
+    assert_eq!(oauth_client.organization_id, organization.id);
+    assert_eq!(oauth_client.scopes().unwrap(), vec![Scopes::EventRead]);
+    assert!(oauth_client.authenticate(Some(client_secret)));
+}
+
+pub fn generate_secret(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = oauth_client.id;
+    let response: HttpResponse =
+        oauth_clients::generate_secret((database.connection.clone().into(), path, auth_user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(!OAuthClient::find(oauth_client.id, connection)
+            .unwrap()
+            .is_confidential());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let client_secret = result["client_secret"].as_str().unwrap();
+    let oauth_client = OAuthClient::find(oauth_client.id, connection).unwrap();
+    assert!(oauth_client.authenticate(Some(client_secret)));
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = oauth_client.id;
+    let response: HttpResponse = oauth_clients::destroy((database.connection.clone().into(), path, auth_user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(OAuthClient::find_active_by_client_id(&oauth_client.client_id, connection).is_ok());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(OAuthClient::find_active_by_client_id(&oauth_client.client_id, connection).is_err());
+}
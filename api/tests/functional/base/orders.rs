@@ -122,6 +122,7 @@ pub fn activity(role: Roles, should_test_true: bool) {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -234,6 +235,7 @@ pub fn details(role: Roles, should_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -383,6 +385,7 @@ pub fn refund(role: Roles, manual_override: bool, should_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1,5 +1,5 @@
 use actix_web::ResponseError;
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::organizations;
 use bigneon_api::controllers::organizations::*;
 use bigneon_api::extractors::*;
@@ -177,6 +177,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         globee_api_key: None,
         max_instances_per_ticket_type: Some(11000),
         settlement_type: None,
+        currency: None,
     });
 
     let test_request = TestRequest::create_with_uri("/organizations");
@@ -274,6 +275,9 @@ pub fn update_restricted_field(restricted_field: &str, role: Roles, should_succe
         "max_instances_per_ticket_type" => {
             attributes.max_instances_per_ticket_type = Some(11000);
         }
+        "resale_company_fee_percent" => {
+            attributes.resale_company_fee_percent = Some(5.0);
+        }
         _ => panic!("Unexpected restricted field"),
     }
 
@@ -509,6 +513,7 @@ pub fn show_fee_schedule(role: Roles, should_succeed: bool) {
         version: i64,
         created_at: NaiveDateTime,
         ranges: Vec<FeeScheduleRange>,
+        currency: String,
     }
 
     let expected_data = FeeScheduleWithRanges {
@@ -517,6 +522,7 @@ pub fn show_fee_schedule(role: Roles, should_succeed: bool) {
         version: 0,
         created_at: fee_schedule.created_at,
         ranges: fee_schedule_ranges,
+        currency: fee_schedule.currency,
     };
 
     let expected_json = serde_json::to_string(&expected_data).unwrap();
@@ -571,4 +577,5 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
     let body = support::unwrap_body_to_string(&response).unwrap();
     let result: FeeScheduleWithRanges = serde_json::from_str(&body).unwrap();
     assert_eq!(result.name, "Fees".to_string());
+    assert_eq!(result.currency, organization.currency);
 }
@@ -1,6 +1,7 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::reports::{self, *};
 use bigneon_api::errors::BigNeonError;
+use bigneon_api::extractors::Query;
 use bigneon_api::models::{PathParameters, WebPayload};
 use bigneon_db::models::*;
 use bigneon_db::schema::orders;
@@ -118,6 +119,7 @@ pub fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
                         total_sales_in_cents: 300,
+                        currency: organization.currency.clone(),
                     },
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event2".to_string()),
@@ -126,10 +128,12 @@ pub fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
                         total_sales_in_cents: 300,
+                        currency: organization.currency.clone(),
                     },
                 ],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::CreditCard,
+                    currency: organization.currency.clone(),
                     quantity: 4,
                     total_sales_in_cents: 600,
                 }],
@@ -144,9 +148,11 @@ pub fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                     face_value_in_cents: 150,
                     revenue_share_value_in_cents: 0,
                     total_sales_in_cents: 300,
+                    currency: organization.currency.clone(),
                 }],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::CreditCard,
+                    currency: organization.currency.clone(),
                     quantity: 2,
                     total_sales_in_cents: 300,
                 }],
@@ -154,6 +160,7 @@ pub fn box_office_sales_summary(role: Roles, should_succeed: bool) {
         ],
         payments: vec![BoxOfficeSalesSummaryPaymentRow {
             payment_type: ExternalPaymentType::CreditCard,
+            currency: organization.currency.clone(),
             quantity: 6,
             total_sales_in_cents: 900,
         }],
@@ -400,6 +407,12 @@ fn build_transaction_report_row(
         event_fee_client_in_cents: organization.client_event_fee_in_cents,
         event_fee_client_in_cents_total: organization.client_event_fee_in_cents,
         fee_range_id: Some(fee_schedule_range.id),
+        item_type: OrderItemTypes::Tickets,
+        resale_client_fee_in_cents: 0,
+        currency: event.currency.clone(),
+        tax_in_cents: 0,
+        tax_in_cents_total: 0,
+        tax_inclusive: false,
         order_type: OrderTypes::Cart,
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
         payment_provider: Some(PaymentProviders::Stripe.to_string()),
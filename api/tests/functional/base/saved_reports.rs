@@ -0,0 +1,121 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::saved_reports::{self, NewSavedReportRequest, NewSavedReportSubscriberRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let saved_report = database.create_saved_report().with_organization(&organization).finish();
+    database.create_saved_report().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = saved_reports::index((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let returned_saved_reports: Vec<SavedReport> = serde_json::from_str(&body).unwrap();
+    assert_eq!(returned_saved_reports.len(), 1);
+    assert_eq!(returned_saved_reports[0].id, saved_report.id);
+}
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let json = Json(NewSavedReportRequest {
+        name: "Weekly sales".to_string(),
+        report_type: SavedReportTypes::TransactionDetails,
+        filters: SavedReportFilters::default(),
+        columns: vec!["event_name".to_string(), "gross".to_string()],
+        format: ReportFormats::Xlsx,
+        schedule: Some("0 6 * * 1".to_string()),
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        saved_reports::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(SavedReport::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let saved_report: SavedReport = serde_json::from_str(&body).unwrap();
+    assert_eq!(saved_report.organization_id, organization.id);
+    assert_eq!(saved_report.created_by_user_id, user.id);
+    assert_eq!(saved_report.format, ReportFormats::Xlsx);
+    assert!(saved_report.next_run_at.is_some());
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let saved_report = database.create_saved_report().with_organization(&organization).finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = saved_report.id;
+    let response: HttpResponse = saved_reports::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(SavedReport::find(saved_report.id, connection).is_ok());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(SavedReport::find(saved_report.id, connection).is_err());
+}
+
+pub fn add_subscriber(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let saved_report = database.create_saved_report().with_organization(&organization).finish();
+
+    let json = Json(NewSavedReportSubscriberRequest {
+        email: "Subscriber@tari.com".to_string(),
+    });
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = saved_report.id;
+    let response: HttpResponse =
+        saved_reports::add_subscriber((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(saved_report.subscribers(connection).unwrap().is_empty());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let subscribers = saved_report.subscribers(connection).unwrap();
+    assert_eq!(subscribers.len(), 1);
+    assert_eq!(subscribers[0].email, "subscriber@tari.com");
+}
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::settlements::{self, *};
 use bigneon_api::extractors::*;
 use bigneon_api::models::PathParameters;
@@ -28,6 +28,7 @@ pub fn create(role: Roles, should_succeed: bool) {
         comment: Some(comment.clone()),
         start_time,
         end_time,
+        currency: None,
     });
 
     let test_request = TestRequest::create();
@@ -46,6 +47,7 @@ pub fn create(role: Roles, should_succeed: bool) {
     assert_eq!(settlement.start_time.timestamp(), start_time.timestamp());
     assert_eq!(settlement.end_time.timestamp(), end_time.timestamp());
     assert_eq!(settlement.only_finished_events, false);
+    assert_eq!(settlement.currency, organization.currency);
 }
 
 pub fn index(role: Roles, should_succeed: bool) {
@@ -0,0 +1,70 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::stage_sections;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{DisplayStageSection, Roles, StageSection};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let name = "Orchestra";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(stage_sections::CreateStageSection {
+        name: name.to_string(),
+        rank: 0,
+        rows: vec![
+            stage_sections::CreateStageSectionRow {
+                name: "A".to_string(),
+                seat_count: 10,
+            },
+            stage_sections::CreateStageSectionRow {
+                name: "B".to_string(),
+                seat_count: 12,
+            },
+        ],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = stage.id;
+    let response: HttpResponse = stage_sections::create((database.connection.into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let section: DisplayStageSection = serde_json::from_str(&body).unwrap();
+    assert_eq!(section.name, name);
+    assert_eq!(section.seats.len(), 22);
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let section = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+
+    let user = support::create_auth_user(role, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = section.id;
+
+    let response: HttpResponse = stage_sections::destroy((database.connection.clone().into(), path, user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(StageSection::find(section.id, connection).is_err());
+}
@@ -0,0 +1,62 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::tax_rules;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{NewTaxRule, Roles, TaxRule, TaxRuleEditableAttributes};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let name = "Sales Tax";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(NewTaxRule {
+        name: name.to_string(),
+        country: "US".to_string(),
+        state: Some("California".to_string()),
+        ticket_tax_percent: 7.25,
+        fee_tax_percent: 0f32,
+        inclusive: false,
+    });
+
+    let response: HttpResponse = tax_rules::create((database.connection.into(), json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rule.name, name);
+    assert_eq!(tax_rule.ticket_tax_percent, 7.25);
+}
+
+pub fn update(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let tax_rule = database.create_tax_rule().finish();
+
+    let user = support::create_auth_user(role, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rule.id;
+
+    let mut attributes: TaxRuleEditableAttributes = Default::default();
+    attributes.ticket_tax_percent = Some(20f32);
+    attributes.inclusive = Some(true);
+    let json = Json(attributes);
+
+    let response: HttpResponse = tax_rules::update((database.connection.into(), path, json, user)).into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_tax_rule.ticket_tax_percent, 20f32);
+    assert!(updated_tax_rule.inclusive);
+}
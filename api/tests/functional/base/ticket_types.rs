@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::ticket_types;
 use bigneon_api::controllers::ticket_types::*;
 use bigneon_api::extractors::*;
@@ -53,6 +53,7 @@ pub fn show_other_user_ticket(role: Roles, should_test_succeed: bool) {
             transfer_key: None,
             transfer_address: None,
             check_in_source: None,
+            seat_label: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -118,6 +119,7 @@ pub fn update(role: Roles, owns_ticket: bool, should_test_succeed: bool) {
             transfer_key: None,
             transfer_address: None,
             check_in_source: None,
+            seat_label: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -217,6 +219,7 @@ pub fn show_redeemable_ticket(role: Roles, should_test_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1,6 +1,7 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::transfers::{self, *};
 use bigneon_api::errors::BigNeonError;
+use bigneon_api::extractors::Query;
 use bigneon_api::models::*;
 use bigneon_db::prelude::*;
 use chrono::prelude::*;
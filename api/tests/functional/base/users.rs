@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::users::{self, *};
 use bigneon_api::errors::*;
 use bigneon_api::extractors::*;
@@ -104,6 +104,7 @@ pub fn activity(role: Roles, should_test_true: bool) {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -189,6 +190,7 @@ pub fn history(role: Roles, should_test_true: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
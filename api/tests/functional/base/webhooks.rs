@@ -0,0 +1,289 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::webhooks::{self, NewWebhookRequest};
+use bigneon_api::extractors::{Json, Query};
+use bigneon_api::models::{OrganizationWebhookPathParameters, PathParameters};
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+    database.create_domain_event_publisher().finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = webhooks::index((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let returned_webhooks: Vec<DomainEventPublisher> = serde_json::from_str(&body).unwrap();
+    assert_eq!(returned_webhooks, vec![domain_event_publisher]);
+}
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    let json = Json(NewWebhookRequest {
+        event_types: vec![DomainEventTypes::EventPublished, DomainEventTypes::OrderCompleted],
+        webhook_url: "https://example.com/webhooks".to_string(),
+        import_historic_events: false,
+        adapter: None,
+        adapter_config: None,
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = webhooks::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(DomainEventPublisher::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let domain_event_publisher: DomainEventPublisher = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(domain_event_publisher.organization_id, Some(organization.id));
+    assert_eq!(
+        domain_event_publisher.event_types,
+        vec![DomainEventTypes::EventPublished, DomainEventTypes::OrderCompleted]
+    );
+    let domain_event_publisher = DomainEventPublisher::find(domain_event_publisher.id, connection).unwrap();
+    assert_eq!(value["signing_secret"], json!(domain_event_publisher.signing_secret));
+}
+
+pub fn show(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let path = webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::show((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(value.get("signing_secret").is_none());
+    let returned_webhook: DomainEventPublisher = serde_json::from_value(value).unwrap();
+    assert_eq!(returned_webhook, domain_event_publisher);
+}
+
+pub fn update(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+
+    let json = Json(DomainEventPublisherEditableAttributes {
+        event_types: Some(vec![DomainEventTypes::UserCreated]),
+        import_historic_events: Some(true),
+        adapter: Some(Some(WebhookAdapters::CustomerIo)),
+        adapter_config: Some(Some(json!({"site_id": "site", "api_key": "key"}))),
+        ..Default::default()
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let path = webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::update((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        let domain_event_publisher = DomainEventPublisher::find(domain_event_publisher.id, connection).unwrap();
+        assert_eq!(domain_event_publisher.adapter, None);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let domain_event_publisher: DomainEventPublisher = serde_json::from_str(&body).unwrap();
+    assert_eq!(domain_event_publisher.event_types, vec![DomainEventTypes::UserCreated]);
+    assert!(domain_event_publisher.import_historic_events);
+    assert_eq!(domain_event_publisher.adapter, Some(WebhookAdapters::CustomerIo));
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let path = webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert_eq!(
+            DomainEventPublisher::find_for_organization(organization.id, connection)
+                .unwrap()
+                .len(),
+            1
+        );
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(DomainEventPublisher::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+pub fn rotate_secret(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let path = webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::rotate_secret((database.connection.clone().into(), path, auth_user)).into();
+
+    let rotated_domain_event_publisher = DomainEventPublisher::find(domain_event_publisher.id, connection).unwrap();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert_eq!(
+            rotated_domain_event_publisher.signing_secret,
+            domain_event_publisher.signing_secret
+        );
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(
+        rotated_domain_event_publisher.signing_secret,
+        domain_event_publisher.signing_secret
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        value["signing_secret"],
+        json!(rotated_domain_event_publisher.signing_secret)
+    );
+}
+
+pub fn ping(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let path = webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::ping((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    ))
+    .into();
+
+    let deliveries = domain_event_publisher.webhook_deliveries(0, 100, connection).unwrap();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(deliveries.data.is_empty());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(!body.contains("response_body"));
+    let delivery: DisplayWebhookDelivery = serde_json::from_str(&body).unwrap();
+    assert_eq!(deliveries.data.len(), 1);
+    assert_eq!(DisplayWebhookDelivery::from(deliveries.data[0].clone()), delivery);
+    // External requests are blocked in tests
+    assert!(!delivery.succeeded);
+    assert_eq!(delivery.domain_event_id, None);
+    assert_eq!(delivery.webhook_url, domain_event_publisher.webhook_url);
+}
+
+pub fn deliveries(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+    let mut delivery = WebhookDelivery::create(
+        domain_event_publisher.id,
+        None,
+        None,
+        domain_event_publisher.webhook_url.clone(),
+        1,
+    );
+    delivery.status_code = Some(200);
+    delivery.response_body = Some("Internal response".to_string());
+    delivery.succeeded = true;
+    let delivery = delivery.commit(connection).unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri("/?page=0&limit=10");
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let path = webhook_path(&organization, &domain_event_publisher);
+    let response = webhooks::deliveries((database.connection.clone().into(), path, query_parameters, auth_user));
+
+    if !should_succeed {
+        support::expects_unauthorized(&response.unwrap_err().into_inner().to_response());
+        return;
+    }
+    let response = response.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.payload().data, vec![DisplayWebhookDelivery::from(delivery)]);
+    assert_eq!(response.payload().paging.total, 1);
+}
+
+pub fn webhook_path(
+    organization: &Organization,
+    domain_event_publisher: &DomainEventPublisher,
+) -> Path<OrganizationWebhookPathParameters> {
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_id"]);
+    let mut path = Path::<OrganizationWebhookPathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.webhook_id = domain_event_publisher.id;
+    path
+}
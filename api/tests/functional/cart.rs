@@ -1,10 +1,10 @@
 use actix_web::Path;
-use actix_web::Query;
 use actix_web::{http::StatusCode, FromRequest, HttpResponse};
+use bigneon_api::config::HostedCheckoutSettings;
 use bigneon_api::controllers;
 use bigneon_api::controllers::cart;
 use bigneon_api::controllers::cart::*;
-use bigneon_api::domain_events::executors::ProcessPaymentIPNExecutor;
+use bigneon_api::domain_events::executors::{ProcessHostedCheckoutIPNExecutor, ProcessPaymentIPNExecutor};
 use bigneon_api::extractors::*;
 use bigneon_api::models::*;
 use bigneon_db::models::*;
@@ -18,6 +18,8 @@ use globee::Customer;
 use globee::Email;
 use globee::GlobeeIpnRequest;
 use globee::PaymentDetails;
+use hosted_checkout::{self, CheckoutStatus, HostedCheckoutClient};
+use reqwest;
 use serde_json;
 use support::database::TestDatabase;
 use support::test_request::TestRequest;
@@ -128,6 +130,7 @@ fn destroy() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -179,6 +182,7 @@ fn update() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
     });
@@ -225,6 +229,7 @@ fn update_with_draft_event() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
         box_office_pricing: None,
@@ -264,11 +269,13 @@ fn update_multiple() {
                 ticket_type_id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             cart::CartItem {
                 ticket_type_id: ticket_type_id2,
                 quantity: 3,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
     });
@@ -331,6 +338,7 @@ fn add_with_increment() {
             ticket_type_id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -379,6 +387,7 @@ fn update_with_increment_failure_invalid_quantity() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -418,6 +427,7 @@ fn update_with_existing_cart() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -456,6 +466,7 @@ fn reduce() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -479,6 +490,7 @@ fn reduce() {
             ticket_type_id,
             quantity: 6,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -520,6 +532,7 @@ fn remove() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -544,6 +557,7 @@ fn remove() {
             ticket_type_id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -587,6 +601,7 @@ fn remove_with_increment() {
             ticket_type_id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -611,6 +626,7 @@ fn remove_with_increment() {
             ticket_type_id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -657,6 +673,7 @@ fn remove_with_increment_failure_invalid_quantity() {
             ticket_type_id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -681,6 +698,7 @@ fn remove_with_increment_failure_invalid_quantity() {
             ticket_type_id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -961,6 +979,7 @@ fn clear_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1005,6 +1024,7 @@ fn checkout_fails_for_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1172,3 +1192,257 @@ fn checkout_provider_globee() {
     let order = Order::find(order.id, conn).unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 }
+
+#[test]
+fn checkout_provider_multiple_organizations() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let event2 = database
+        .create_event()
+        .with_organization(&database.create_organization().finish())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let mut cart = database.create_cart().for_user(&user).for_event(&event).finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 10,
+                redemption_code: None,
+                seat_ids: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: ticket_type2.id,
+                quantity: 2,
+                redemption_code: None,
+                seat_ids: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.organizations(connection).unwrap().len(), 2);
+    let request = TestRequest::create();
+
+    // Split payouts are only supported through Stripe
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        method: PaymentRequest::Provider {
+            provider: PaymentProviders::Globee,
+        },
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let expected_json = HttpResponse::new(StatusCode::UNPROCESSABLE_ENTITY)
+        .into_builder()
+        .json(json!({
+            "error": "Could not complete this cart; events from multiple organizations cannot be paid using Globee"
+        }));
+    let expected_text = unwrap_body_to_string(&expected_json).unwrap();
+    let body = unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_text);
+
+    let cart = Order::find(cart.id, connection).unwrap();
+    assert_eq!(cart.status, OrderStatus::Draft);
+    assert!(cart.payments(connection).unwrap().is_empty());
+}
+
+#[test]
+fn checkout_provider_hosted_checkout() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    database.create_cart().for_user(&user).for_event(&event).finish();
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let mut config = TestRequest::test_config();
+    config.hosted_checkout = Some(HostedCheckoutSettings {
+        base_url: hosted_checkout::mock::start("test_api_key", "test_secret"),
+        api_key: "test_api_key".to_string(),
+        secret: "test_secret".to_string(),
+    });
+    config.validate_ipns = true;
+    let request = TestRequest::create_with_config("/", vec![], config.clone(), Vec::new());
+
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        method: PaymentRequest::Provider {
+            provider: PaymentProviders::HostedCheckout,
+        },
+    });
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user.clone(),
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = unwrap_body_to_string(&response).unwrap();
+    let order: DisplayOrder = serde_json::from_str(body).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+
+    let db_order = Order::find(order.id, conn).unwrap();
+    let db_payment = &db_order.payments(conn).unwrap()[0];
+    assert_eq!(db_payment.provider, PaymentProviders::HostedCheckout);
+    assert_eq!(db_payment.status, PaymentStatus::Requested);
+
+    // Customer pays on the provider's page and is sent back with a signed callback
+    let response = reqwest::Client::builder()
+        .redirect(reqwest::RedirectPolicy::none())
+        .build()
+        .unwrap()
+        .get(&db_order.checkout_url.unwrap())
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FOUND);
+    let location = reqwest::Url::parse(response.headers()["Location"].to_str().unwrap()).unwrap();
+    let url = format!("{}?{}", location.path(), location.query().unwrap());
+    let request = TestRequest::create_with_config(&url, vec!["nonce", "id"], config.clone(), Vec::new());
+    let mut path = Path::<controllers::payments::PathParams>::extract(&request.request).unwrap();
+    path.nonce = db_payment.url_nonce.clone().unwrap();
+    path.id = order.id;
+    let query = Query::<controllers::payments::QueryParams>::extract(&request.request).unwrap();
+    let checkout_id = query.checkout_id.clone().unwrap();
+
+    // Tampered callbacks are rejected
+    let mut tampered_query = Query::<controllers::payments::QueryParams>::extract(&request.request).unwrap();
+    tampered_query.signature = Some(hosted_checkout::sign("another_secret", &checkout_id));
+    let mut tampered_path = Path::<controllers::payments::PathParams>::extract(&request.request).unwrap();
+    tampered_path.nonce = path.nonce.clone();
+    tampered_path.id = order.id;
+    let response: HttpResponse = controllers::payments::callback((
+        tampered_query,
+        tampered_path,
+        database.connection.clone().into(),
+        request.extract_state(),
+        OptionalUser(Some(user.clone())),
+    ))
+    .into();
+    support::expects_forbidden(&response, Some("Payment callback signature is invalid"));
+
+    let response = controllers::payments::callback((
+        query,
+        path,
+        database.connection.clone().into(),
+        request.extract_state(),
+        OptionalUser(Some(user)),
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::PendingPayment);
+
+    // IPNs must be signed by the provider
+    let checkout = HostedCheckoutClient::new(
+        "test_api_key".to_string(),
+        config.hosted_checkout.clone().unwrap().base_url,
+    )
+    .get_checkout(&checkout_id)
+    .unwrap();
+    assert_eq!(checkout.status, CheckoutStatus::Paid);
+    assert_eq!(checkout.currency, order.currency.clone().unwrap().to_uppercase());
+    let body = serde_json::to_string(&checkout).unwrap();
+    let request = TestRequest::create_with_config("/ipns/hosted_checkout", vec![], config.clone(), Vec::new());
+    let response: HttpResponse = controllers::ipns::hosted_checkout((
+        request.request.clone(),
+        body.clone(),
+        database.connection.clone().into(),
+    ))
+    .into();
+    support::expects_forbidden(&response, Some("IPN signature is invalid"));
+
+    let request = TestRequest::create_with_config(
+        "/ipns/hosted_checkout",
+        vec![],
+        config.clone(),
+        vec![(
+            hosted_checkout::SIGNATURE_HEADER,
+            hosted_checkout::sign("test_secret", &body),
+        )],
+    );
+    let response =
+        controllers::ipns::hosted_checkout((request.request.clone(), body, database.connection.clone().into()))
+            .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut domain_actions = DomainAction::find_pending(Some(DomainActionTypes::HostedCheckoutIPN), conn).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let domain_action = domain_actions.remove(0);
+    assert_eq!(domain_action.main_table_id, Some(order.id));
+
+    // Checkouts in another currency are not payments for the order
+    let mut other_currency_action = domain_action.clone();
+    other_currency_action.payload["currency"] = json!("EUR");
+    let mut unvalidated_config = config.clone();
+    unvalidated_config.validate_ipns = false;
+    assert!(ProcessHostedCheckoutIPNExecutor::new(&unvalidated_config)
+        .perform_job(&other_currency_action, &database.connection.clone())
+        .is_err());
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::PendingPayment);
+
+    ProcessHostedCheckoutIPNExecutor::new(&config)
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payment = &order.payments(conn).unwrap()[0];
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.amount, checkout.amount_in_cents);
+}
+
+#[test]
+fn checkout_with_delegated_credentials() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    database.create_cart().for_user(&user).for_event(&event).finish();
+
+    // Keys and tokens can not spend the payment methods saved by their user
+    let test_request = support::create_api_key_request(
+        "/cart/checkout",
+        &user,
+        &organization,
+        vec![Scopes::OrderMakeExternalPayment],
+        &database,
+    );
+    support::expects_delegated_credentials_rejected(&test_request);
+
+    let test_request = support::create_oauth_request(
+        "/cart/checkout",
+        &user,
+        &organization,
+        vec![Scopes::OrderMakeExternalPayment],
+        &database,
+    );
+    support::expects_delegated_credentials_rejected(&test_request);
+}
@@ -9,6 +9,7 @@ use functional::base;
 use support;
 use support::database::TestDatabase;
 use support::test_request::TestRequest;
+use uuid::Uuid;
 
 #[cfg(test)]
 mod index_tests {
@@ -237,6 +238,7 @@ fn create_with_validation_errors() {
         redemption_code: "OHHHYEAAAH".to_string(),
         end_at: None,
         max_per_user: None,
+        seat_ids: None,
     });
 
     let test_request = TestRequest::create();
@@ -282,3 +284,67 @@ fn update_with_validation_errors() {
     assert_eq!(email[0].code, "email");
     assert_eq!(&email[0].message.clone().unwrap().into_owned(), "Email is invalid");
 }
+
+#[test]
+fn create_and_update_with_seats() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let event = database
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let section = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    let seat_ids: Vec<Uuid> = section
+        .add_row("A".to_string(), 3, connection)
+        .unwrap()
+        .iter()
+        .map(|s| s.id)
+        .collect();
+    ticket_type.assign_seats(&seat_ids, None, connection).unwrap();
+    let hold = database
+        .create_hold()
+        .with_hold_type(HoldTypes::Comp)
+        .with_ticket_type_id(ticket_type.id)
+        .with_quantity(0)
+        .finish();
+    hold.add_seats(None, seat_ids.clone(), connection).unwrap();
+    let organization = event.organization(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let json = Json(NewCompRequest {
+        name: "Seated Comp".to_string(),
+        email: None,
+        phone: None,
+        quantity: 0,
+        redemption_code: "SEATEDCOMP".to_string(),
+        end_at: None,
+        max_per_user: None,
+        seat_ids: Some(vec![seat_ids[0]]),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = hold.id;
+    let response = comps::create((database.connection.clone(), json, path, auth_user.clone())).unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let comp = Hold::find_by_redemption_code("SEATEDCOMP", None, connection).unwrap();
+    assert_eq!(comp.quantity(connection).unwrap(), (1, 1));
+
+    let json = Json(UpdateHoldRequest {
+        seat_ids: Some(vec![seat_ids[1]]),
+        ..Default::default()
+    });
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = comp.id;
+    let response: HttpResponse = comps::update((database.connection.clone(), json, path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(comp.quantity(connection).unwrap(), (2, 2));
+    assert_eq!(hold.quantity(connection).unwrap(), (1, 1));
+}
@@ -0,0 +1,149 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_series;
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod show_tests {
+    use super::*;
+    #[test]
+    fn show_org_member() {
+        base::event_series::show(Roles::OrgMember, true);
+    }
+    #[test]
+    fn show_admin() {
+        base::event_series::show(Roles::Admin, true);
+    }
+    #[test]
+    fn show_user() {
+        base::event_series::show(Roles::User, false);
+    }
+    #[test]
+    fn show_org_owner() {
+        base::event_series::show(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn show_door_person() {
+        base::event_series::show(Roles::DoorPerson, true);
+    }
+    #[test]
+    fn show_promoter() {
+        base::event_series::show(Roles::Promoter, true);
+    }
+    #[test]
+    fn show_promoter_read_only() {
+        base::event_series::show(Roles::PromoterReadOnly, true);
+    }
+    #[test]
+    fn show_org_admin() {
+        base::event_series::show(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn show_box_office() {
+        base::event_series::show(Roles::OrgBoxOffice, true);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_series::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_series::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_series::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_series::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::event_series::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::event_series::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::event_series::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::event_series::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::event_series::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod update_following_tests {
+    use super::*;
+    #[test]
+    fn update_following_org_member() {
+        base::event_series::update_following(Roles::OrgMember, true);
+    }
+    #[test]
+    fn update_following_admin() {
+        base::event_series::update_following(Roles::Admin, true);
+    }
+    #[test]
+    fn update_following_user() {
+        base::event_series::update_following(Roles::User, false);
+    }
+    #[test]
+    fn update_following_org_owner() {
+        base::event_series::update_following(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn update_following_door_person() {
+        base::event_series::update_following(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn update_following_promoter() {
+        base::event_series::update_following(Roles::Promoter, true);
+    }
+    #[test]
+    fn update_following_promoter_read_only() {
+        base::event_series::update_following(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn update_following_org_admin() {
+        base::event_series::update_following(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn update_following_box_office() {
+        base::event_series::update_following(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn update_following_outside_of_series() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(EventEditableAttributes::default());
+    let response: HttpResponse =
+        event_series::update_following((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -1,4 +1,3 @@
-use actix_web::Query;
 use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::events;
 use bigneon_api::controllers::events::*;
@@ -1460,6 +1459,56 @@ mod update_tests {
     }
 }
 
+#[cfg(test)]
+mod duplicate_tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_org_member() {
+        base::events::duplicate(Roles::OrgMember, true);
+    }
+
+    #[test]
+    fn duplicate_admin() {
+        base::events::duplicate(Roles::Admin, true);
+    }
+
+    #[test]
+    fn duplicate_user() {
+        base::events::duplicate(Roles::User, false);
+    }
+
+    #[test]
+    fn duplicate_org_owner() {
+        base::events::duplicate(Roles::OrgOwner, true);
+    }
+
+    #[test]
+    fn duplicate_door_person() {
+        base::events::duplicate(Roles::DoorPerson, false);
+    }
+
+    #[test]
+    fn duplicate_promoter() {
+        base::events::duplicate(Roles::Promoter, true);
+    }
+
+    #[test]
+    fn duplicate_promoter_read_only() {
+        base::events::duplicate(Roles::PromoterReadOnly, false);
+    }
+
+    #[test]
+    fn duplicate_org_admin() {
+        base::events::duplicate(Roles::OrgAdmin, true);
+    }
+
+    #[test]
+    fn duplicate_box_office() {
+        base::events::duplicate(Roles::OrgBoxOffice, false);
+    }
+}
+
 #[cfg(test)]
 mod cancel_tests {
     use super::*;
@@ -2025,6 +2074,56 @@ mod export_event_data_tests {
     }
 }
 
+#[cfg(test)]
+mod redemption_bundle_tests {
+    use super::*;
+
+    #[test]
+    fn redemption_bundle_org_member() {
+        base::events::redemption_bundle(Roles::OrgMember, true);
+    }
+
+    #[test]
+    fn redemption_bundle_admin() {
+        base::events::redemption_bundle(Roles::Admin, true);
+    }
+
+    #[test]
+    fn redemption_bundle_user() {
+        base::events::redemption_bundle(Roles::User, false);
+    }
+
+    #[test]
+    fn redemption_bundle_org_owner() {
+        base::events::redemption_bundle(Roles::OrgOwner, true);
+    }
+
+    #[test]
+    fn redemption_bundle_door_person() {
+        base::events::redemption_bundle(Roles::DoorPerson, true);
+    }
+
+    #[test]
+    fn redemption_bundle_promoter() {
+        base::events::redemption_bundle(Roles::Promoter, false);
+    }
+
+    #[test]
+    fn redemption_bundle_promoter_read_only() {
+        base::events::redemption_bundle(Roles::PromoterReadOnly, false);
+    }
+
+    #[test]
+    fn redemption_bundle_org_admin() {
+        base::events::redemption_bundle(Roles::OrgAdmin, true);
+    }
+
+    #[test]
+    fn redemption_bundle_box_office() {
+        base::events::redemption_bundle(Roles::OrgBoxOffice, true);
+    }
+}
+
 #[test]
 pub fn delete_fails_has_ticket_in_cart() {
     let database = TestDatabase::new();
@@ -2048,6 +2147,7 @@ pub fn delete_fails_has_ticket_in_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2121,6 +2221,7 @@ fn dashboard_with_default_range() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2288,3 +2389,156 @@ pub fn event_venue_entry(
         event_end: event.event_end,
     }
 }
+
+#[test]
+fn redemption_key() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let other_event = database.create_event().with_organization(&organization).finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket = database.create_purchased_tickets(&user, ticket_type.id, 1).remove(0);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::DoorPerson, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        events::redemption_key((database.connection.clone().into(), path, auth_user.clone())).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let redemption_key: OfflineRedemptionKey = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(redemption_key.event_id, event.id);
+
+    // Ticket payloads and bundles are verified with the key fetched from this endpoint
+    let payload = SignedTicketPayload::create(&ticket, connection).unwrap();
+    assert!(payload.verify(&redemption_key.public_key));
+    let bundle = OfflineRedemptionBundle::create_for_event(&event, connection).unwrap();
+    assert!(bundle.verify(&redemption_key.public_key));
+    let entry = bundle
+        .tickets
+        .iter()
+        .find(|t| t.ticket_instance_id == ticket.id)
+        .unwrap();
+    assert_eq!(
+        entry.redeem_key_hash,
+        OfflineRedemptionTicket::redeem_key_hash(
+            &redemption_key.hash_secret,
+            ticket.id,
+            &ticket.redeem_key.clone().unwrap()
+        )
+    );
+
+    // Each event has its own key
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = other_event.id;
+    let response: HttpResponse = events::redemption_key((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let other_redemption_key: OfflineRedemptionKey = support::unwrap_body_to_object(&response).unwrap();
+    assert_ne!(other_redemption_key.public_key, redemption_key.public_key);
+    assert!(!payload.verify(&other_redemption_key.public_key));
+
+    // Users without redeem access to the event cannot fetch the key
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = events::redemption_key((database.connection.clone().into(), path, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn sync_redemptions() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = database.create_user().finish();
+    let tickets = database.create_purchased_tickets(&buyer, ticket_type.id, 2);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::DoorPerson, Some(&organization), &database);
+    let redeemed_at = dates::now().add_minutes(-10).finish();
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(SyncRedemptionsRequest {
+        redemptions: vec![
+            OfflineRedemption {
+                ticket_instance_id: tickets[0].id,
+                redeem_key: tickets[0].redeem_key.clone().unwrap(),
+                redeemed_at,
+            },
+            OfflineRedemption {
+                ticket_instance_id: tickets[1].id,
+                redeem_key: "WrongKey".to_string(),
+                redeemed_at,
+            },
+        ],
+    });
+    let response: HttpResponse = events::sync_redemptions((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user.clone(),
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results: Vec<OfflineRedemptionResult> = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(results.len(), 2);
+    let result = results.iter().find(|r| r.ticket_instance_id == tickets[0].id).unwrap();
+    assert_eq!(result.status, OfflineRedemptionStatus::Redeemed);
+    let result = results.iter().find(|r| r.ticket_instance_id == tickets[1].id).unwrap();
+    assert_eq!(result.status, OfflineRedemptionStatus::Invalid);
+    let ticket = TicketInstance::find(tickets[0].id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+
+    // A second scanner syncing the same ticket gets a double scan
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(SyncRedemptionsRequest {
+        redemptions: vec![OfflineRedemption {
+            ticket_instance_id: tickets[0].id,
+            redeem_key: tickets[0].redeem_key.clone().unwrap(),
+            redeemed_at: dates::now().add_minutes(-5).finish(),
+        }],
+    });
+    let response: HttpResponse = events::sync_redemptions((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results: Vec<OfflineRedemptionResult> = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(results[0].status, OfflineRedemptionStatus::DoubleScan);
+    assert_eq!(results[0].first_redeemed_at, ticket.redeemed_at);
+    assert_eq!(results[0].first_redeemed_by_user_id, Some(user.id));
+
+    // Users without redeem access to the event cannot sync
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(SyncRedemptionsRequest { redemptions: vec![] });
+    let response: HttpResponse = events::sync_redemptions((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
@@ -0,0 +1,212 @@
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::{Middleware, Response};
+use actix_web::{FromRequest, HttpResponse, Responder};
+use bigneon_api::extractors::Query;
+use bigneon_api::middleware::ExportFormat;
+use bigneon_api::models::WebPayload;
+use bigneon_db::models::*;
+use chrono::NaiveDate;
+use serde_json;
+use support;
+use support::test_request::TestRequest;
+
+fn export(uri: &str, headers: Vec<(&'static str, String)>, response: HttpResponse) -> HttpResponse {
+    let test_request = TestRequest::create_with_config(uri, vec!["id"], TestRequest::test_config(), headers);
+    match ExportFormat::new().response(&test_request.request, response).unwrap() {
+        Response::Done(response) => response,
+        _ => panic!("Expected response"),
+    }
+}
+
+fn guests() -> Payload<serde_json::Value> {
+    Payload::from_data(
+        vec![
+            json!({"email": "guest@tari.com", "ticket": {"price_in_cents": 1500, "fee": 1.5}}),
+            json!({"email": "other@tari.com", "ticket": null}),
+        ],
+        0,
+        100,
+    )
+}
+
+#[test]
+fn csv_from_format_parameter() {
+    let mut response = export(
+        "/events/0f85443e-9e70-45ba-bf28-0f59c183856f/guests?format=csv",
+        vec![],
+        HttpResponse::Ok().json(guests()),
+    );
+    assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv");
+    assert_eq!(
+        response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+        "attachment; filename=\"guests.csv\""
+    );
+    assert_eq!(
+        support::take_body_to_string(&mut response).unwrap(),
+        "email,ticket.fee,ticket.price_in_cents\nguest@tari.com,1.5,1500\nother@tari.com,,\n"
+    );
+}
+
+#[test]
+fn csv_for_accept_language() {
+    let mut response = export(
+        "/admin/orders",
+        vec![
+            ("Accept", "text/csv".to_string()),
+            ("Accept-Language", "de-DE,de;q=0.9,en;q=0.8".to_string()),
+        ],
+        HttpResponse::Ok().json(guests()),
+    );
+    assert_eq!(
+        response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+        "attachment; filename=\"orders.csv\""
+    );
+    assert_eq!(
+        support::take_body_to_string(&mut response).unwrap(),
+        "email;ticket.fee;ticket.price_in_cents\nguest@tari.com;1,5;1500\nother@tari.com;;\n"
+    );
+}
+
+#[test]
+fn xlsx_from_accept_header() {
+    let response = export(
+        "/reports/0f85443e-9e70-45ba-bf28-0f59c183856f?report=transaction_details",
+        vec![(
+            "Accept",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+        )],
+        HttpResponse::Ok().json(vec![json!({"event_name": "Event1"})]),
+    );
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    );
+    assert_eq!(
+        response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+        "attachment; filename=\"transaction_details.xlsx\""
+    );
+}
+
+#[test]
+fn json_unless_requested() {
+    let response = export(
+        "/admin/orders",
+        vec![("Accept", "application/json, text/plain, */*".to_string())],
+        HttpResponse::Ok().json(guests()),
+    );
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+
+    // Responses that are not lists are not converted
+    let response = export(
+        "/events/0f85443e-9e70-45ba-bf28-0f59c183856f?format=csv",
+        vec![],
+        HttpResponse::Ok().json(json!({"id": "0f85443e-9e70-45ba-bf28-0f59c183856f"})),
+    );
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+
+    // Errors are not converted
+    let response = export(
+        "/admin/orders?format=csv",
+        vec![],
+        HttpResponse::Unauthorized().json(json!({"error": "Unauthorized"})),
+    );
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+}
+
+#[test]
+fn csv_from_web_payload() {
+    let test_request = TestRequest::create_with_config(
+        "/organizations/0f85443e-9e70-45ba-bf28-0f59c183856f/invites",
+        vec![],
+        TestRequest::test_config(),
+        vec![("Accept", "text/csv".to_string())],
+    );
+    let invite = DisplayInvite {
+        id: "0f85443e-9e70-45ba-bf28-0f59c183856f".parse().unwrap(),
+        organization_name: "Organization".to_string(),
+        inviter_name: "Inviter".to_string(),
+        expires_at: NaiveDate::from_ymd(2020, 1, 1).and_hms(10, 0, 0),
+    };
+    let web_payload = WebPayload::new(StatusCode::OK, Payload::from_data(vec![invite], 0, 100));
+    let mut response = web_payload.respond_to(&test_request.request).unwrap();
+    assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv");
+    assert_eq!(
+        response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+        "attachment; filename=\"invites.csv\""
+    );
+    assert_eq!(
+        support::take_body_to_string(&mut response).unwrap(),
+        "expires_at,id,inviter_name,organization_name\n\
+         2020-01-01T10:00:00,0f85443e-9e70-45ba-bf28-0f59c183856f,Inviter,Organization\n"
+    );
+
+    // The middleware leaves responses that were already exported alone
+    let response = export(
+        "/organizations/0f85443e-9e70-45ba-bf28-0f59c183856f/invites",
+        vec![("Accept", "text/csv".to_string())],
+        response,
+    );
+    assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv");
+}
+
+#[test]
+fn csv_header_for_empty_web_payload() {
+    let test_request = TestRequest::create_with_config(
+        "/organizations/0f85443e-9e70-45ba-bf28-0f59c183856f/invites?format=csv",
+        vec![],
+        TestRequest::test_config(),
+        vec![],
+    );
+    let web_payload: WebPayload<DisplayInvite> = WebPayload::new(StatusCode::OK, Payload::from_data(vec![], 0, 100));
+    let mut response = web_payload.respond_to(&test_request.request).unwrap();
+    assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv");
+    assert_eq!(
+        support::take_body_to_string(&mut response).unwrap(),
+        "expires_at,id,inviter_name,organization_name\n"
+    );
+}
+
+#[test]
+fn accept_header_lifts_paging() {
+    let test_request = TestRequest::create_with_config(
+        "/admin/orders?page=2&limit=10",
+        vec![],
+        TestRequest::test_config(),
+        vec![("Accept", "text/csv".to_string())],
+    );
+    let query = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    assert_eq!(query.export_format(), Some(ReportFormats::Csv));
+    assert_eq!(query.page(), 0);
+    assert_eq!(query.limit(), MAXIMUM_EXPORT_ROWS);
+
+    // The format parameter takes precedence over the header
+    let test_request = TestRequest::create_with_config(
+        "/admin/orders?format=xlsx",
+        vec![],
+        TestRequest::test_config(),
+        vec![("Accept", "text/csv".to_string())],
+    );
+    let query = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    assert_eq!(query.export_format(), Some(ReportFormats::Xlsx));
+
+    // JSON requests keep their paging
+    let test_request = TestRequest::create_with_config(
+        "/admin/orders?page=2&limit=10",
+        vec![],
+        TestRequest::test_config(),
+        vec![("Accept", "application/json".to_string())],
+    );
+    let query = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    assert_eq!(query.export_format(), None);
+    assert_eq!(query.page(), 2);
+    assert_eq!(query.limit(), 10);
+}
@@ -198,6 +198,7 @@ fn create_with_validation_errors() {
         max_per_user: None,
         quantity: 2,
         ticket_type_id: event.ticket_types(true, None, database.connection.get()).unwrap()[0].id,
+        seat_ids: None,
     });
 
     let test_request = TestRequest::create();
@@ -276,6 +277,7 @@ pub fn read_hold() {
         max_per_user: None,
         quantity: 2,
         ticket_type_id: event.ticket_types(true, None, database.connection.get()).unwrap()[0].id,
+        seat_ids: None,
     });
 
     let test_request = TestRequest::create();
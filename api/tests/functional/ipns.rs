@@ -0,0 +1,119 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::ipns;
+use bigneon_api::domain_events::executors::ProcessPaymentIPNExecutor;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use stripe;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn stripe_dispute() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().with_fees().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let mut order = database
+        .create_cart()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(conn).unwrap();
+    order
+        .add_credit_card_payment(
+            user.id,
+            total,
+            PaymentProviders::Stripe,
+            "pi_test".to_string(),
+            PaymentStatus::Completed,
+            json!({}),
+            conn,
+        )
+        .unwrap();
+    let order = Order::find(order.id, conn).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let settlement = database.create_settlement().with_organization(&organization).finish();
+
+    let mut config = TestRequest::test_config();
+    config.stripe_webhook_secret = Some("whsec_test".to_string());
+    config.nullify_tickets_on_chargeback = true;
+
+    let body = json!({
+        "id": "evt_test",
+        "type": "charge.dispute.created",
+        "created": Utc::now().timestamp(),
+        "data": {
+            "object": {
+                "id": "dp_test",
+                "object": "dispute",
+                "amount": total,
+                "currency": "usd",
+                "charge": "ch_test",
+                "payment_intent": "pi_test",
+                "reason": "fraudulent",
+                "status": "needs_response"
+            }
+        }
+    })
+    .to_string();
+
+    // Webhooks must be signed by Stripe
+    let request = TestRequest::create_with_config("/ipns/stripe", vec![], config.clone(), Vec::new());
+    let response: HttpResponse = ipns::stripe((
+        request.request.clone(),
+        body.clone(),
+        database.connection.clone().into(),
+    ))
+    .into();
+    support::expects_forbidden(&response, Some("Webhook signature is invalid"));
+
+    let request = TestRequest::create_with_config(
+        "/ipns/stripe",
+        vec![],
+        config.clone(),
+        vec![(
+            stripe::SIGNATURE_HEADER,
+            stripe::sign("whsec_test", Utc::now().timestamp(), &body),
+        )],
+    );
+    let response = ipns::stripe((request.request.clone(), body, database.connection.clone().into())).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut domain_actions = DomainAction::find_pending(Some(DomainActionTypes::PaymentProviderIPN), conn).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let domain_action = domain_actions.remove(0);
+    let executor = ProcessPaymentIPNExecutor::new(&config);
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+
+    let order = Order::find(order.id, conn).unwrap();
+    assert!(order.disputed_at.is_some());
+    let adjustments = settlement.adjustments(conn).unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustments[0].order_id, Some(order.id));
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, conn).unwrap() {
+        assert_eq!(
+            TicketInstance::find(ticket_id, conn).unwrap().status,
+            TicketInstanceStatus::Nullified
+        );
+    }
+
+    // Stripe retries deliveries, the chargeback is only recorded once
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+    assert_eq!(settlement.adjustments(conn).unwrap().len(), 1);
+}
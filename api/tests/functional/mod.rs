@@ -1,3 +1,4 @@
+mod api_keys;
 mod artists;
 mod auth;
 mod base;
@@ -6,10 +7,16 @@ mod cart;
 mod codes;
 mod comps;
 mod event_report_subscribers;
+mod event_series;
 mod events;
+mod export_format;
 mod genres;
 mod holds;
+mod ipns;
 mod notes;
+mod oauth;
+mod oauth_clients;
+mod oidc;
 mod orders;
 mod organization_invites;
 mod organizations;
@@ -18,14 +25,22 @@ mod payment_methods;
 mod redemption_codes;
 mod regions;
 mod reports;
+mod saved_reports;
+mod season_passes;
 mod settlement_adjustments;
 mod settlements;
 mod sitemap;
 mod slugs;
+mod stage_sections;
 mod stages;
+mod tax_rules;
 mod ticket_types;
 mod tickets;
 mod transfers;
+mod two_factor;
 mod user_invites;
+mod user_sessions;
 mod users;
 mod venues;
+mod waitlist_entries;
+mod webhooks;
@@ -0,0 +1,340 @@
+use actix_web::{http::StatusCode, Form, FromRequest, HttpResponse, Path};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::auth::{claims::AccessToken, TokenResponse};
+use bigneon_api::controllers::oauth::{self, AuthorizeRequest, RevokeRequest, TokenRequest};
+use bigneon_api::extractors::{Json, Query};
+use bigneon_api::middleware::DelegatedAccessAllowed;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use jwt::{decode, Validation};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+// Example from RFC 7636 Appendix B
+const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+const REDIRECT_URI: &str = "https://example.com/callback";
+
+fn authorize_request(oauth_client: &OAuthClient, scope: &str) -> AuthorizeRequest {
+    AuthorizeRequest {
+        client_id: oauth_client.client_id.clone(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        scope: scope.to_string(),
+        code_challenge: CODE_CHALLENGE.to_string(),
+        code_challenge_method: "S256".to_string(),
+        state: Some("xyz".to_string()),
+    }
+}
+
+fn authorization_code_request(oauth_client: &OAuthClient, code: &str) -> TokenRequest {
+    TokenRequest {
+        grant_type: "authorization_code".to_string(),
+        client_id: oauth_client.client_id.clone(),
+        client_secret: None,
+        code: Some(code.to_string()),
+        redirect_uri: Some(REDIRECT_URI.to_string()),
+        code_verifier: Some(CODE_VERIFIER.to_string()),
+        refresh_token: None,
+    }
+}
+
+fn create_oauth_client(database: &TestDatabase, organization: &Organization, user: &User) -> OAuthClient {
+    OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec![REDIRECT_URI.to_string()],
+        vec![Scopes::EventRead, Scopes::OrderRead],
+        user.id,
+    )
+    .commit(database.connection.get())
+    .unwrap()
+}
+
+/// Authorizes the client on behalf of the user returning the authorization code from the redirect
+fn authorize(database: &TestDatabase, oauth_client: &OAuthClient, user: &User) -> String {
+    let auth_user = support::create_auth_user_from_user(user, Roles::User, None, database);
+    let json = Json(authorize_request(oauth_client, "event:read"));
+    let response: HttpResponse = oauth::authorize((database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let redirect_uri = body["redirect_uri"].as_str().unwrap();
+    assert!(redirect_uri.starts_with(&format!("{}?code=", REDIRECT_URI)));
+    assert!(redirect_uri.ends_with("&state=xyz"));
+
+    redirect_uri.split(|c| c == '=' || c == '&').nth(1).unwrap().to_string()
+}
+
+fn exchange_code(database: &TestDatabase, oauth_client: &OAuthClient, code: &str) -> TokenResponse {
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let form = Form(authorization_code_request(oauth_client, code));
+    let response: HttpResponse = oauth::token((state, database.connection.clone().into(), form)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    serde_json::from_str(&body).unwrap()
+}
+
+#[test]
+fn authorize_details() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/oauth/authorize?client_id={}&redirect_uri={}&scope=event:read&code_challenge={}&code_challenge_method=S256",
+        oauth_client.client_id, REDIRECT_URI, CODE_CHALLENGE
+    ));
+    let query = Query::<AuthorizeRequest>::extract(&test_request.request).unwrap();
+    let response: HttpResponse =
+        oauth::authorize_details((database.connection.clone().into(), query, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["client_name"], json!("Ticket widget"));
+    assert_eq!(body["scopes"], json!(["event:read"]));
+    assert_eq!(body["granted_scopes"], json!([]));
+}
+
+#[test]
+fn authorize_invalid_scope() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(authorize_request(&oauth_client, "event:read org:admin"));
+    let response = oauth::authorize((database.connection.clone().into(), json, auth_user));
+    assert_eq!(
+        response.err().unwrap().to_string(),
+        "Scope org:admin is not allowed for this client"
+    );
+}
+
+#[test]
+fn authorize_unregistered_redirect_uri() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let mut request = authorize_request(&oauth_client, "event:read");
+    request.redirect_uri = "https://attacker.com/callback".to_string();
+    let response = oauth::authorize((database.connection.clone().into(), Json(request), auth_user));
+    assert_eq!(
+        response.err().unwrap().to_string(),
+        "Redirect URI is not registered for this client"
+    );
+}
+
+#[test]
+fn token_authorization_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let code = authorize(&database, &oauth_client, &user);
+
+    let response = exchange_code(&database, &oauth_client, &code);
+    assert_eq!(response.token_type, Some("Bearer".to_string()));
+    assert_eq!(response.scope, Some("event:read".to_string()));
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let access_token = decode::<AccessToken>(
+        &response.access_token,
+        state.config.token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert_eq!(access_token.claims.scopes, Some(vec!["event:read".to_string()]));
+    let consent = OAuthConsent::find_active(oauth_client.id, user.id, database.connection.get()).unwrap();
+    assert_eq!(access_token.claims.oauth_consent_id, Some(consent.id));
+}
+
+#[test]
+fn token_authorization_code_reused() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let code = authorize(&database, &oauth_client, &user);
+    exchange_code(&database, &oauth_client, &code);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let form = Form(authorization_code_request(&oauth_client, &code));
+    let response: HttpResponse = oauth::token((state, database.connection.clone().into(), form)).into();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["error"], json!("invalid_grant"));
+
+    // Consent is revoked as the code has likely been intercepted
+    assert!(OAuthConsent::find_active(oauth_client.id, user.id, database.connection.get()).is_err());
+}
+
+#[test]
+fn token_invalid_client() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let (oauth_client, _) = oauth_client.generate_secret(None, database.connection.get()).unwrap();
+    let code = authorize(&database, &oauth_client, &user);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let mut request = authorization_code_request(&oauth_client, &code);
+    request.client_secret = Some("incorrect".to_string());
+    let response: HttpResponse = oauth::token((state, database.connection.clone().into(), Form(request))).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["error"], json!("invalid_client"));
+}
+
+#[test]
+fn token_refresh_token() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let code = authorize(&database, &oauth_client, &user);
+    let token_response = exchange_code(&database, &oauth_client, &code);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let form = Form(TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        client_id: oauth_client.client_id.clone(),
+        client_secret: None,
+        code: None,
+        redirect_uri: None,
+        code_verifier: None,
+        refresh_token: Some(token_response.refresh_token.clone()),
+    });
+    let response: HttpResponse = oauth::token((state, database.connection.clone().into(), form)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(response.refresh_token, token_response.refresh_token);
+    assert_eq!(response.scope, Some("event:read".to_string()));
+}
+
+#[test]
+fn revoke() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    let code = authorize(&database, &oauth_client, &user);
+    let token_response = exchange_code(&database, &oauth_client, &code);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let form = Form(RevokeRequest {
+        token: token_response.access_token,
+        client_id: oauth_client.client_id.clone(),
+        client_secret: None,
+    });
+    let response: HttpResponse = oauth::revoke((state, database.connection.clone().into(), form)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(OAuthConsent::find_active(oauth_client.id, user.id, database.connection.get()).is_err());
+
+    // Refresh tokens issued under the consent no longer work
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let form = Form(TokenRequest {
+        grant_type: "refresh_token".to_string(),
+        client_id: oauth_client.client_id.clone(),
+        client_secret: None,
+        code: None,
+        redirect_uri: None,
+        code_verifier: None,
+        refresh_token: Some(token_response.refresh_token),
+    });
+    let response: HttpResponse = oauth::token((state, database.connection.clone().into(), form)).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn destroy_consent() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let oauth_client = create_oauth_client(&database, &organization, &user);
+    authorize(&database, &oauth_client, &user);
+    let consent = OAuthConsent::find_active(oauth_client.id, user.id, database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = consent.id;
+    let auth_user = support::create_auth_user_from_user(&user2, Roles::User, None, &database);
+    let response: HttpResponse = oauth::destroy_consent((database.connection.clone().into(), path, auth_user)).into();
+    support::expects_forbidden(&response, Some("User does not have access to this consent"));
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = consent.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = oauth::destroy_consent((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!OAuthConsent::find(consent.id, database.connection.get())
+        .unwrap()
+        .is_active());
+}
+
+#[test]
+fn access_token_delegated_access() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let path = format!("/organizations/{}/events", organization.id);
+
+    // Rejected unless the resource allows delegated access
+    let test_request = support::create_oauth_request(&path, &user, &organization, vec![Scopes::EventRead], &database);
+    support::expects_delegated_credentials_rejected(&test_request);
+
+    let test_request = support::create_oauth_request(&path, &user, &organization, vec![Scopes::EventRead], &database);
+    test_request.request.extensions_mut().insert(DelegatedAccessAllowed);
+    let auth_user = AuthUser::extract(&test_request.request).unwrap();
+    assert_eq!(auth_user.id(), user.id);
+    assert_eq!(auth_user.token_scopes, Some(vec![Scopes::EventRead]));
+}
+
+#[test]
+fn access_token_excludes_global_scopes() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user = user.add_role(Roles::Admin, connection).unwrap();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let other_organization = database.create_organization().finish();
+    let path = format!("/organizations/{}/events", organization.id);
+
+    // Admins are limited to the token's scopes in the organizations they belong to
+    let test_request = support::create_oauth_request(&path, &user, &organization, vec![Scopes::EventRead], &database);
+    test_request.request.extensions_mut().insert(DelegatedAccessAllowed);
+    let auth_user = AuthUser::extract(&test_request.request).unwrap();
+    assert!(auth_user
+        .has_scope_for_organization(Scopes::EventRead, &organization, connection)
+        .unwrap());
+    assert!(!auth_user
+        .has_scope_for_organization(Scopes::EventRead, &other_organization, connection)
+        .unwrap());
+    assert!(!auth_user.has_scope(Scopes::EventRead).unwrap());
+}
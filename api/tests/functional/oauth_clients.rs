@@ -0,0 +1,211 @@
+use actix_web::{FromRequest, HttpResponse, Path};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::oauth_clients::{self, NewOAuthClientRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::oauth_clients::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::oauth_clients::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::oauth_clients::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::oauth_clients::index(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn index_door_person() {
+        base::oauth_clients::index(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn index_promoter() {
+        base::oauth_clients::index(Roles::Promoter, false);
+    }
+    #[test]
+    fn index_promoter_read_only() {
+        base::oauth_clients::index(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn index_org_admin() {
+        base::oauth_clients::index(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn index_box_office() {
+        base::oauth_clients::index(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::oauth_clients::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::oauth_clients::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::oauth_clients::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::oauth_clients::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::oauth_clients::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::oauth_clients::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::oauth_clients::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::oauth_clients::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::oauth_clients::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod generate_secret_tests {
+    use super::*;
+    #[test]
+    fn generate_secret_org_member() {
+        base::oauth_clients::generate_secret(Roles::OrgMember, false);
+    }
+    #[test]
+    fn generate_secret_admin() {
+        base::oauth_clients::generate_secret(Roles::Admin, true);
+    }
+    #[test]
+    fn generate_secret_user() {
+        base::oauth_clients::generate_secret(Roles::User, false);
+    }
+    #[test]
+    fn generate_secret_org_owner() {
+        base::oauth_clients::generate_secret(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn generate_secret_door_person() {
+        base::oauth_clients::generate_secret(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn generate_secret_promoter() {
+        base::oauth_clients::generate_secret(Roles::Promoter, false);
+    }
+    #[test]
+    fn generate_secret_promoter_read_only() {
+        base::oauth_clients::generate_secret(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn generate_secret_org_admin() {
+        base::oauth_clients::generate_secret(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn generate_secret_box_office() {
+        base::oauth_clients::generate_secret(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::oauth_clients::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::oauth_clients::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::oauth_clients::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::oauth_clients::destroy(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::oauth_clients::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_promoter() {
+        base::oauth_clients::destroy(Roles::Promoter, false);
+    }
+    #[test]
+    fn destroy_promoter_read_only() {
+        base::oauth_clients::destroy(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::oauth_clients::destroy(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::oauth_clients::destroy(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn create_with_api_key() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let (api_key, _) = ApiKey::create(
+        organization.id,
+        user.id,
+        "CRM".to_string(),
+        vec![Scopes::OrgAdminUsers],
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let test_request = TestRequest::create();
+    let auth_user = AuthUser::new_for_api_key(user, api_key, &test_request.request);
+
+    let json = Json(NewOAuthClientRequest {
+        name: "Ticket widget".to_string(),
+        redirect_uris: vec!["https://example.com/callback".to_string()],
+        scopes: vec!["event:read".to_string()],
+        confidential: false,
+    });
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        oauth_clients::create((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_forbidden(
+        &response,
+        Some("OAuth clients cannot be managed using delegated credentials"),
+    );
+}
@@ -0,0 +1,390 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::auth::{claims::AccessToken, TokenResponse};
+use bigneon_api::config::{Config, OidcProviderSettings};
+use bigneon_api::controllers::external::oidc::{self, OidcLoginRequest, OidcStartResponse};
+use bigneon_api::extractors::*;
+use bigneon_api::models::StringPathParameters;
+use bigneon_db::prelude::*;
+use jwt::{decode, Validation};
+use oidc::mock;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+const CLIENT_ID: &str = "test_client_id";
+const CLIENT_SECRET: &str = "test_client_secret";
+
+fn config_with_provider(organization_id: Option<Uuid>) -> (Config, String) {
+    let issuer = mock::start(CLIENT_ID, CLIENT_SECRET);
+    let mut config = TestRequest::test_config();
+    config.oidc_providers = vec![OidcProviderSettings {
+        name: "mock".to_string(),
+        issuer: issuer.clone(),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        organization_id,
+    }];
+    (config, issuer)
+}
+
+fn login(database: &TestDatabase, config: Config, json: OidcLoginRequest, auth_user: OptionalUser) -> HttpResponse {
+    let test_request = TestRequest::create_with_config("/", vec!["id"], config, Vec::new());
+    let mut path = Path::<StringPathParameters>::extract(&test_request.request).unwrap();
+    path.id = "mock".to_string();
+    oidc::login((
+        test_request.request,
+        database.connection.clone().into(),
+        path,
+        Json(json),
+        auth_user,
+    ))
+    .into()
+}
+
+fn start(config: Config, provider_name: &str) -> HttpResponse {
+    let test_request = TestRequest::create_with_config("/", vec!["id"], config, Vec::new());
+    let mut path = Path::<StringPathParameters>::extract(&test_request.request).unwrap();
+    path.id = provider_name.to_string();
+    oidc::start((test_request.request, path)).into()
+}
+
+fn nonce(config: &Config) -> String {
+    let response = start(config.clone(), "mock");
+    assert_eq!(response.status(), StatusCode::OK);
+    let start_response: OidcStartResponse = support::unwrap_body_to_object(&response).unwrap();
+    start_response.nonce
+}
+
+fn id_token_request(
+    config: &Config,
+    issuer: &str,
+    sub: &str,
+    email: Option<&str>,
+    email_verified: bool,
+) -> OidcLoginRequest {
+    let nonce = nonce(config);
+    let mut claims = mock::id_token_claims(issuer, CLIENT_ID, sub);
+    claims.nonce = Some(nonce.clone());
+    claims.email = email.map(|e| e.to_string());
+    claims.email_verified = Some(email_verified);
+    claims.given_name = Some("Oidc".to_string());
+    claims.family_name = Some("User".to_string());
+    OidcLoginRequest {
+        id_token: Some(mock::sign_id_token(&claims)),
+        nonce: Some(nonce),
+        ..Default::default()
+    }
+}
+
+fn logged_in_user_id(response: &HttpResponse, config: &Config) -> Uuid {
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(response).unwrap();
+    let token_response: TokenResponse = serde_json::from_str(body).unwrap();
+    let access_token = decode::<AccessToken>(
+        &token_response.access_token,
+        config.token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .unwrap();
+    access_token.claims.get_id().unwrap()
+}
+
+#[test]
+fn start_issues_nonce() {
+    let (config, _) = config_with_provider(None);
+    let response = start(config.clone(), "mock");
+    assert_eq!(response.status(), StatusCode::OK);
+    let start_response: OidcStartResponse = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(start_response.client_id, CLIENT_ID);
+    assert_eq!(start_response.scope, "openid email profile");
+    assert_ne!(start_response.nonce, nonce(&config));
+
+    let response = start(config, "unknown");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn login_creates_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (config, issuer) = config_with_provider(None);
+    let sub = Uuid::new_v4().to_string();
+
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(&config, &issuer, &sub, Some("oidc_user@tari.com"), true),
+        OptionalUser(None),
+    );
+    let user_id = logged_in_user_id(&response, &config);
+    let user = User::find(user_id, connection).unwrap();
+    assert_eq!(user.email, Some("oidc_user@tari.com".to_string()));
+    assert_eq!(user.first_name, Some("Oidc".to_string()));
+    let external_login = ExternalLogin::find_user(&sub, &issuer, connection).unwrap().unwrap();
+    assert_eq!(external_login.user_id, user_id);
+
+    // Signing in again uses the same user
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(&config, &issuer, &sub, Some("oidc_user@tari.com"), true),
+        OptionalUser(None),
+    );
+    assert_eq!(logged_in_user_id(&response, &config), user_id);
+}
+
+#[test]
+fn login_links_user_with_verified_email() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (config, issuer) = config_with_provider(None);
+    let user = database
+        .create_user()
+        .with_email("oidc_user@tari.com".to_string())
+        .finish();
+
+    // Unverified email addresses are not trusted to link to the existing account
+    let sub = Uuid::new_v4().to_string();
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(&config, &issuer, &sub, Some("oidc_user@tari.com"), false),
+        OptionalUser(None),
+    );
+    let user_id = logged_in_user_id(&response, &config);
+    assert_ne!(user_id, user.id);
+    assert_eq!(User::find(user_id, connection).unwrap().email, None);
+
+    let sub = Uuid::new_v4().to_string();
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(&config, &issuer, &sub, Some("oidc_user@tari.com"), true),
+        OptionalUser(None),
+    );
+    assert_eq!(logged_in_user_id(&response, &config), user.id);
+    let external_login = ExternalLogin::find_user(&sub, &issuer, connection).unwrap().unwrap();
+    assert_eq!(external_login.user_id, user.id);
+}
+
+#[test]
+fn login_with_authorization_code() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (config, issuer) = config_with_provider(None);
+    let user = database
+        .create_user()
+        .with_email("oidc_user@tari.com".to_string())
+        .finish();
+    let sub = Uuid::new_v4().to_string();
+    let redirect_uri = "http://localhost/callback";
+    let code_nonce = nonce(&config);
+    let code = mock::authorize(
+        &issuer,
+        CLIENT_ID,
+        redirect_uri,
+        &sub,
+        Some("oidc_user@tari.com"),
+        Some(&code_nonce),
+    );
+
+    let json = OidcLoginRequest {
+        code: Some(code),
+        redirect_uri: Some(redirect_uri.to_string()),
+        nonce: Some(code_nonce),
+        ..Default::default()
+    };
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(logged_in_user_id(&response, &config), user.id);
+    let external_login = ExternalLogin::find_user(&sub, &issuer, connection).unwrap().unwrap();
+    assert_eq!(external_login.user_id, user.id);
+    assert_ne!(external_login.access_token, "");
+
+    // Requests without an ID token or code are rejected
+    let json = OidcLoginRequest {
+        nonce: Some(nonce(&config)),
+        ..Default::default()
+    };
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn login_with_invalid_id_token() {
+    let database = TestDatabase::new();
+    let (config, issuer) = config_with_provider(None);
+    let sub = Uuid::new_v4().to_string();
+
+    // Token issued to another client
+    let client_nonce = nonce(&config);
+    let mut claims = mock::id_token_claims(&issuer, "other_client_id", &sub);
+    claims.nonce = Some(client_nonce.clone());
+    let json = OidcLoginRequest {
+        id_token: Some(mock::sign_id_token(&claims)),
+        nonce: Some(client_nonce),
+        ..Default::default()
+    };
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Nonce is required
+    let mut json = id_token_request(&config, &issuer, &sub, None, false);
+    json.nonce = None;
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Nonce was not issued by the start endpoint
+    let mut claims = mock::id_token_claims(&issuer, CLIENT_ID, &sub);
+    claims.nonce = Some("nonce".to_string());
+    let json = OidcLoginRequest {
+        id_token: Some(mock::sign_id_token(&claims)),
+        nonce: Some("nonce".to_string()),
+        ..Default::default()
+    };
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Nonce was issued for another sign in than the ID token
+    let mut json = id_token_request(&config, &issuer, &sub, None, false);
+    json.nonce = Some(nonce(&config));
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Nonce was issued for another provider
+    let mut other_config = config.clone();
+    other_config.oidc_providers[0].name = "other".to_string();
+    let response = start(other_config, "other");
+    let other_nonce = support::unwrap_body_to_object::<OidcStartResponse>(&response)
+        .unwrap()
+        .nonce;
+    let mut claims = mock::id_token_claims(&issuer, CLIENT_ID, &sub);
+    claims.nonce = Some(other_nonce.clone());
+    let json = OidcLoginRequest {
+        id_token: Some(mock::sign_id_token(&claims)),
+        nonce: Some(other_nonce),
+        ..Default::default()
+    };
+    let response = login(&database, config.clone(), json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Malformed token
+    let json = OidcLoginRequest {
+        id_token: Some("invalid".to_string()),
+        nonce: Some(nonce(&config)),
+        ..Default::default()
+    };
+    let response = login(&database, config, json, OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(ExternalLogin::find_user(&sub, &issuer, database.connection.get())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn login_with_unknown_provider() {
+    let database = TestDatabase::new();
+    let config = TestRequest::test_config();
+    let response = login(&database, config, OidcLoginRequest::default(), OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn login_link_to_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (config, issuer) = config_with_provider(None);
+    let user = database.create_user().finish();
+    let sub = Uuid::new_v4().to_string();
+
+    let mut json = id_token_request(&config, &issuer, &sub, None, false);
+    json.link_to_user_id = true;
+    let response = login(&database, config.clone(), json.clone(), OptionalUser(None));
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response = login(&database, config.clone(), json, auth_user.into_optional());
+    assert_eq!(logged_in_user_id(&response, &config), user.id);
+    let external_login = ExternalLogin::find_user(&sub, &issuer, connection).unwrap().unwrap();
+    assert_eq!(external_login.user_id, user.id);
+}
+
+#[test]
+fn login_organization_provider() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let member = database
+        .create_user()
+        .with_email("member@tari.com".to_string())
+        .finish();
+    let organization = database
+        .create_organization()
+        .with_member(&member, Roles::OrgMember)
+        .finish();
+    let non_member = database
+        .create_user()
+        .with_email("non_member@tari.com".to_string())
+        .finish();
+    let (config, issuer) = config_with_provider(Some(organization.id));
+
+    // Verified email addresses are not trusted to link to the member's account
+    let sub = Uuid::new_v4().to_string();
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(&config, &issuer, &sub, Some("member@tari.com"), true),
+        OptionalUser(None),
+    );
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        support::unwrap_body_to_string(&response).unwrap(),
+        json!({"error": "Sign in to link this login to your account"}).to_string()
+    );
+    assert!(ExternalLogin::find_user(&sub, &issuer, connection).unwrap().is_none());
+
+    // Members link the login once signed in to their account
+    let mut json = id_token_request(&config, &issuer, &sub, Some("member@tari.com"), true);
+    json.link_to_user_id = true;
+    let auth_user = support::create_auth_user_from_user(&member, Roles::User, None, &database);
+    let response = login(&database, config.clone(), json, auth_user.into_optional());
+    assert_eq!(logged_in_user_id(&response, &config), member.id);
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(&config, &issuer, &sub, Some("member@tari.com"), true),
+        OptionalUser(None),
+    );
+    assert_eq!(logged_in_user_id(&response, &config), member.id);
+
+    let mut json = id_token_request(
+        &config,
+        &issuer,
+        &Uuid::new_v4().to_string(),
+        Some("non_member@tari.com"),
+        true,
+    );
+    json.link_to_user_id = true;
+    let auth_user = support::create_auth_user_from_user(&non_member, Roles::User, None, &database);
+    let response = login(&database, config.clone(), json, auth_user.into_optional());
+    support::expects_forbidden(&response, Some("User is not a member of this organization"));
+
+    // Users are never registered through organization providers
+    let response = login(
+        &database,
+        config.clone(),
+        id_token_request(
+            &config,
+            &issuer,
+            &Uuid::new_v4().to_string(),
+            Some("new_user@tari.com"),
+            true,
+        ),
+        OptionalUser(None),
+    );
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(User::find_by_email("new_user@tari.com", database.connection.get())
+        .optional()
+        .unwrap()
+        .is_none());
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use chrono::prelude::*;
 use diesel;
 use diesel::prelude::*;
@@ -8,7 +8,7 @@ use serde_json;
 use uuid::Uuid;
 
 use bigneon_api::controllers::orders::{self, *};
-use bigneon_api::extractors::Json;
+use bigneon_api::extractors::{Json, Query};
 use bigneon_api::models::PathParameters;
 use bigneon_db::models::*;
 use bigneon_db::schema;
@@ -500,6 +500,7 @@ pub fn refund_for_non_refundable_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -607,6 +608,7 @@ pub fn refund_hold_ticket() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -678,6 +680,7 @@ pub fn refund_hold_ticket() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
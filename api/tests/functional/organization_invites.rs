@@ -1,6 +1,6 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::organization_invites::{self, InviteResponseQuery};
-use bigneon_api::extractors::OptionalUser;
+use bigneon_api::extractors::{OptionalUser, Query};
 use bigneon_api::models::OrganizationInvitePathParameters;
 use bigneon_db::models::*;
 use functional::base;
@@ -456,6 +456,47 @@ mod update_tests_with_max_tickets_per_ticket_type {
     }
 }
 
+#[cfg(test)]
+mod update_tests_with_resale_company_fee_percent {
+    use super::*;
+    #[test]
+    fn update_org_member() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::OrgMember, false);
+    }
+    #[test]
+    fn update_admin() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::Admin, true);
+    }
+    #[test]
+    fn update_user() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::User, false);
+    }
+    #[test]
+    fn update_org_owner() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::OrgOwner, false);
+    }
+    #[test]
+    fn update_door_person() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::DoorPerson, false);
+    }
+    #[test]
+    fn update_promoter() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::Promoter, false);
+    }
+    #[test]
+    fn update_promoter_read_only() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn update_org_admin() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn update_box_office() {
+        organizations::update_restricted_field("resale_company_fee_percent", Roles::OrgBoxOffice, false);
+    }
+}
+
 #[cfg(test)]
 mod list_organization_members_tests {
     use super::*;
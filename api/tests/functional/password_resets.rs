@@ -77,7 +77,7 @@ fn update() {
         password_reset_token: user.password_reset_token.unwrap(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse = password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert!(user.password_reset_token.is_none());
@@ -127,7 +127,7 @@ fn update_expired_token() {
         password_reset_token: token,
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse = password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -153,7 +153,7 @@ fn update_incorrect_token() {
         password_reset_token: Uuid::new_v4(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse = password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
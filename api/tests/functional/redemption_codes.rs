@@ -1,5 +1,6 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::redemption_codes::{self, *};
+use bigneon_api::extractors::Query;
 use bigneon_api::models::UserDisplayTicketType;
 use bigneon_db::prelude::*;
 use serde_json;
@@ -1,5 +1,6 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::regions;
+use bigneon_api::extractors::Query;
 use bigneon_api::models::PathParameters;
 use bigneon_db::models::*;
 use functional::base;
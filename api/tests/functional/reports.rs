@@ -31,6 +31,7 @@ pub fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         true,
         false,
@@ -98,6 +99,7 @@ pub fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -193,6 +195,7 @@ pub fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code,
+            seat_ids: None,
         }],
         false,
         false,
@@ -287,6 +290,7 @@ pub fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code,
+            seat_ids: None,
         }],
         false,
         false,
@@ -0,0 +1,255 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::saved_reports;
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::saved_reports::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::saved_reports::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::saved_reports::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::saved_reports::index(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn index_door_person() {
+        base::saved_reports::index(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn index_promoter() {
+        base::saved_reports::index(Roles::Promoter, false);
+    }
+    #[test]
+    fn index_promoter_read_only() {
+        base::saved_reports::index(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn index_org_admin() {
+        base::saved_reports::index(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn index_box_office() {
+        base::saved_reports::index(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::saved_reports::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::saved_reports::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::saved_reports::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::saved_reports::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::saved_reports::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::saved_reports::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::saved_reports::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::saved_reports::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::saved_reports::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::saved_reports::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::saved_reports::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::saved_reports::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::saved_reports::destroy(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::saved_reports::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_promoter() {
+        base::saved_reports::destroy(Roles::Promoter, false);
+    }
+    #[test]
+    fn destroy_promoter_read_only() {
+        base::saved_reports::destroy(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::saved_reports::destroy(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::saved_reports::destroy(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod add_subscriber_tests {
+    use super::*;
+    #[test]
+    fn add_subscriber_org_member() {
+        base::saved_reports::add_subscriber(Roles::OrgMember, false);
+    }
+    #[test]
+    fn add_subscriber_admin() {
+        base::saved_reports::add_subscriber(Roles::Admin, true);
+    }
+    #[test]
+    fn add_subscriber_user() {
+        base::saved_reports::add_subscriber(Roles::User, false);
+    }
+    #[test]
+    fn add_subscriber_org_owner() {
+        base::saved_reports::add_subscriber(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn add_subscriber_door_person() {
+        base::saved_reports::add_subscriber(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn add_subscriber_promoter() {
+        base::saved_reports::add_subscriber(Roles::Promoter, false);
+    }
+    #[test]
+    fn add_subscriber_promoter_read_only() {
+        base::saved_reports::add_subscriber(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn add_subscriber_org_admin() {
+        base::saved_reports::add_subscriber(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn add_subscriber_box_office() {
+        base::saved_reports::add_subscriber(Roles::OrgBoxOffice, false);
+    }
+}
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let saved_report = database
+        .create_saved_report()
+        .with_organization(&organization)
+        .with_schedule("0 6 * * 1")
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = saved_report.id;
+    let json = Json(SavedReportEditableAttributes {
+        name: Some("Monthly sales".to_string()),
+        schedule: Some(None),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        saved_reports::update((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let saved_report = SavedReport::find(saved_report.id, connection).unwrap();
+    assert_eq!(saved_report.name, "Monthly sales");
+    assert_eq!(saved_report.schedule, None);
+    assert_eq!(saved_report.next_run_at, None);
+}
+
+#[test]
+fn update_with_invalid_schedule() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let saved_report = database.create_saved_report().with_organization(&organization).finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = saved_report.id;
+    let json = Json(SavedReportEditableAttributes {
+        schedule: Some(Some("every monday".to_string())),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        saved_reports::update((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn run() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let saved_report = database
+        .create_saved_report()
+        .with_organization(&organization)
+        .with_report_type(SavedReportTypes::TicketCounts)
+        .with_columns(vec!["event_name", "ticket_name"])
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = saved_report.id;
+    let response: HttpResponse = saved_reports::run((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let table: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(table["columns"], json!(["event_name", "ticket_name"]));
+}
@@ -0,0 +1,255 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::season_passes::{
+    self, AddSeasonPassToCartRequest, CreateSeasonPassRequest, UpdateSeasonPassRequest,
+};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    SeasonPass::create(other_organization.id, "Other".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = season_passes::index((database.connection.clone().into(), path)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let found_season_passes: Vec<DisplaySeasonPass> = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(found_season_passes, vec![season_pass.for_display(connection).unwrap()]);
+}
+
+#[test]
+fn show() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass.set_events(&[event.id], None, connection).unwrap();
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = season_pass.id;
+    let response: HttpResponse = season_passes::show((database.connection.clone().into(), path)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let display_season_pass: DisplaySeasonPass = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(display_season_pass.id, season_pass.id);
+    assert_eq!(display_season_pass.events.len(), 1);
+    assert_eq!(display_season_pass.events[0].id, event.id);
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgAdmin, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateSeasonPassRequest {
+        name: "Season".to_string(),
+        price_in_cents: 3000,
+        event_ids: vec![event.id],
+    });
+    let response: HttpResponse =
+        season_passes::create((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let display_season_pass: DisplaySeasonPass = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(display_season_pass.organization_id, organization.id);
+    assert_eq!(display_season_pass.name, "Season".to_string());
+    assert_eq!(display_season_pass.price_in_cents, 3000);
+    assert_eq!(display_season_pass.events.len(), 1);
+    assert_eq!(
+        SeasonPass::find(display_season_pass.id, connection)
+            .unwrap()
+            .organization_id,
+        organization.id
+    );
+}
+
+#[test]
+fn create_with_invalid_events() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let other_event = database.create_event().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgAdmin, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateSeasonPassRequest {
+        name: "Season".to_string(),
+        price_in_cents: 3000,
+        event_ids: vec![other_event.id],
+    });
+    let response: HttpResponse =
+        season_passes::create((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn create_unauthorized() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateSeasonPassRequest {
+        name: "Season".to_string(),
+        price_in_cents: 3000,
+        event_ids: vec![],
+    });
+    let response: HttpResponse =
+        season_passes::create((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_unauthorized(&response);
+    assert!(SeasonPass::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let event2 = database.create_event().with_organization(&organization).finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass.set_events(&[event.id], None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgAdmin, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = season_pass.id;
+    let json = Json(UpdateSeasonPassRequest {
+        name: Some("Full Season".to_string()),
+        event_ids: Some(vec![event.id, event2.id]),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        season_passes::update((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let display_season_pass: DisplaySeasonPass = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(display_season_pass.name, "Full Season".to_string());
+    assert_eq!(display_season_pass.price_in_cents, 3000);
+    assert_eq!(display_season_pass.events.len(), 2);
+
+    // Users outside of the organization cannot update the season pass
+    let other_user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = season_pass.id;
+    let json = Json(UpdateSeasonPassRequest {
+        price_in_cents: Some(1),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        season_passes::update((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_unauthorized(&response);
+    assert_eq!(
+        SeasonPass::find(season_pass.id, connection).unwrap().price_in_cents,
+        3000
+    );
+}
+
+#[test]
+fn add_to_cart() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_fees().finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = season_pass.id;
+    let json = Json(AddSeasonPassToCartRequest { quantity: 2 });
+    let response: HttpResponse =
+        season_passes::add_to_cart((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let display_order: DisplayOrder = support::unwrap_body_to_object(&response).unwrap();
+    let season_pass_item = display_order
+        .items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::SeasonPasses)
+        .unwrap();
+    assert_eq!(season_pass_item.season_pass_id, Some(season_pass.id));
+    assert_eq!(season_pass_item.event_id, None);
+    assert_eq!(season_pass_item.quantity, 2);
+    assert_eq!(season_pass_item.unit_price_in_cents, 3000);
+    assert!(display_order
+        .items
+        .iter()
+        .any(|i| i.item_type == OrderItemTypes::PerUnitFees && i.parent_id == Some(season_pass_item.id)));
+}
+
+#[test]
+fn entitlements() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass.set_events(&[event.id], None, connection).unwrap();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_season_pass(user.id, &season_pass, 1, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = season_passes::entitlements((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entitlements: Vec<DisplaySeasonPassEntitlement> = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(entitlements.len(), 1);
+    assert_eq!(entitlements[0].season_pass.id, season_pass.id);
+    assert_eq!(entitlements[0].status, SeasonPassEntitlementStatus::Active);
+    assert!(entitlements[0].redeemed_event_ids.is_empty());
+
+    // Other users do not see the entitlements
+    let other_user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+    let response: HttpResponse = season_passes::entitlements((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entitlements: Vec<DisplaySeasonPassEntitlement> = support::unwrap_body_to_object(&response).unwrap();
+    assert!(entitlements.is_empty());
+}
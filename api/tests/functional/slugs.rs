@@ -1,4 +1,3 @@
-use actix_web::Query;
 use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::events::*;
 use bigneon_api::controllers::slugs;
@@ -0,0 +1,84 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::stage_sections::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::stage_sections::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::stage_sections::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::stage_sections::create(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_door_person() {
+        base::stage_sections::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::stage_sections::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::stage_sections::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::stage_sections::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::stage_sections::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::stage_sections::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::stage_sections::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::stage_sections::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::stage_sections::destroy(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::stage_sections::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_promoter() {
+        base::stage_sections::destroy(Roles::Promoter, false);
+    }
+    #[test]
+    fn destroy_promoter_read_only() {
+        base::stage_sections::destroy(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::stage_sections::destroy(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::stage_sections::destroy(Roles::OrgBoxOffice, false);
+    }
+}
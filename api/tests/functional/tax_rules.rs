@@ -0,0 +1,123 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::tax_rules;
+use bigneon_api::extractors::Query;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let tax_rule = database.create_tax_rule().with_country("GB".into()).finish();
+    let tax_rule2 = database.create_tax_rule().with_country("US".into()).finish();
+
+    let test_request = TestRequest::create_with_uri(&format!("/limits?"));
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response = tax_rules::index((database.connection.into(), query_parameters)).unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.payload().data, vec![tax_rule, tax_rule2]);
+}
+
+#[test]
+fn show() {
+    let database = TestDatabase::new();
+    let tax_rule = database.create_tax_rule().finish();
+    let tax_rule_expected_json = serde_json::to_string(&tax_rule).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rule.id;
+
+    let response: HttpResponse = tax_rules::show((database.connection.into(), path)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, tax_rule_expected_json);
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::tax_rules::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::tax_rules::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::tax_rules::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::tax_rules::create(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_door_person() {
+        base::tax_rules::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::tax_rules::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::tax_rules::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::tax_rules::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::tax_rules::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[test]
+    fn update_org_member() {
+        base::tax_rules::update(Roles::OrgMember, false);
+    }
+    #[test]
+    fn update_admin() {
+        base::tax_rules::update(Roles::Admin, true);
+    }
+    #[test]
+    fn update_user() {
+        base::tax_rules::update(Roles::User, false);
+    }
+    #[test]
+    fn update_org_owner() {
+        base::tax_rules::update(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn update_door_person() {
+        base::tax_rules::update(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn update_promoter() {
+        base::tax_rules::update(Roles::Promoter, false);
+    }
+    #[test]
+    fn update_promoter_read_only() {
+        base::tax_rules::update(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn update_org_admin() {
+        base::tax_rules::update(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn update_box_office() {
+        base::tax_rules::update(Roles::OrgBoxOffice, false);
+    }
+}
@@ -746,6 +746,173 @@ pub fn update_with_overlapping_periods() {
     assert_eq!(deserialized_response.error, "Validation error");
 }
 
+#[test]
+pub fn create_with_pricing_rules() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let request_data = CreateTicketTypeRequest {
+        name: "VIP".into(),
+        capacity: 100,
+        start_date: Some(NaiveDate::from_ymd(2018, 5, 1).and_hms(6, 20, 21)),
+        end_date: Some(NaiveDate::from_ymd(2055, 7, 3).and_hms(9, 23, 23)),
+        pricing_rules: vec![
+            CreateTicketPricingRuleRequest {
+                name: "Second tier".to_string(),
+                rule_type: TicketPricingRuleTypes::SoldQuantity,
+                threshold: 50,
+                price_in_cents: Some(5000),
+                price_increase_percent: None,
+            },
+            CreateTicketPricingRuleRequest {
+                name: "Last tickets".to_string(),
+                rule_type: TicketPricingRuleTypes::RemainingInventory,
+                threshold: 10,
+                price_in_cents: None,
+                price_increase_percent: Some(20),
+            },
+        ],
+        price_in_cents: 3000,
+        rank: 1,
+        ..Default::default()
+    };
+    let response: HttpResponse = ticket_types::create((
+        database.connection.clone().into(),
+        path,
+        Json(request_data),
+        auth_user,
+        state,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let ticket_type = &event.ticket_types(true, None, database.connection.get()).unwrap()[0];
+    let pricing_rules = ticket_type.pricing_rules(database.connection.get()).unwrap();
+    assert_eq!(pricing_rules.len(), 2);
+    assert_eq!(pricing_rules[0].name, "Second tier".to_string());
+    assert_eq!(pricing_rules[0].rule_type, TicketPricingRuleTypes::SoldQuantity);
+    assert_eq!(pricing_rules[0].threshold, 50);
+    assert_eq!(pricing_rules[0].price_in_cents, Some(5000));
+    assert_eq!(pricing_rules[0].triggered_at, None);
+    assert_eq!(pricing_rules[1].name, "Last tickets".to_string());
+    assert_eq!(pricing_rules[1].rule_type, TicketPricingRuleTypes::RemainingInventory);
+    assert_eq!(pricing_rules[1].price_increase_percent, Some(20));
+}
+
+#[test]
+pub fn create_with_invalid_pricing_rule() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let request_data = CreateTicketTypeRequest {
+        name: "VIP".into(),
+        capacity: 100,
+        start_date: Some(NaiveDate::from_ymd(2018, 5, 1).and_hms(6, 20, 21)),
+        end_date: Some(NaiveDate::from_ymd(2055, 7, 3).and_hms(9, 23, 23)),
+        pricing_rules: vec![CreateTicketPricingRuleRequest {
+            name: "Second tier".to_string(),
+            rule_type: TicketPricingRuleTypes::SoldQuantity,
+            threshold: 50,
+            price_in_cents: None,
+            price_increase_percent: None,
+        }],
+        price_in_cents: 3000,
+        rank: 1,
+        ..Default::default()
+    };
+    let response: HttpResponse =
+        ticket_types::create((database.connection.into(), path, Json(request_data), auth_user, state)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation_response = support::validation_response_from_response(&response).unwrap();
+    let price_errors = validation_response.fields.get("price_in_cents").unwrap();
+    assert_eq!(price_errors[0].code, "price_or_increase_required");
+}
+
+#[test]
+pub fn update_with_pricing_rules() {
+    let database = TestDatabase::new();
+    let request = TestRequest::create();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let conn = database.connection.get();
+    let ticket_type = &event.ticket_types(true, None, conn).unwrap()[0];
+    let triggered_rule = TicketPricingRule::create(
+        ticket_type.id,
+        "Triggered".to_string(),
+        TicketPricingRuleTypes::SoldQuantity,
+        0,
+        Some(4000),
+        None,
+    )
+    .commit(None, conn)
+    .unwrap();
+    database.create_order().for_event(&event).quantity(1).finish();
+    let triggered_rule = TicketPricingRule::find(triggered_rule.id, conn).unwrap();
+    assert!(triggered_rule.triggered_at.is_some());
+    let pending_rule = TicketPricingRule::create(
+        ticket_type.id,
+        "Pending".to_string(),
+        TicketPricingRuleTypes::SoldQuantity,
+        50,
+        Some(5000),
+        None,
+    )
+    .commit(None, conn)
+    .unwrap();
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let request_data = UpdateTicketTypeRequest {
+        pricing_rules: Some(vec![CreateTicketPricingRuleRequest {
+            name: "Replacement".to_string(),
+            rule_type: TicketPricingRuleTypes::RemainingInventory,
+            threshold: 5,
+            price_in_cents: None,
+            price_increase_percent: Some(10),
+        }]),
+        ..Default::default()
+    };
+    let response: HttpResponse = ticket_types::update((
+        database.connection.clone().into(),
+        path,
+        Json(request_data),
+        auth_user,
+        request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Pending rules are replaced, triggered rules are kept as history
+    let pricing_rules = ticket_type.pricing_rules(conn).unwrap();
+    assert_eq!(pricing_rules.len(), 2);
+    assert_eq!(pricing_rules[0], triggered_rule);
+    assert_eq!(pricing_rules[1].name, "Replacement".to_string());
+    assert!(TicketPricingRule::find(pending_rule.id, conn).is_err());
+}
+
 #[test]
 pub fn cancel_with_sold_tickets_and_hold() {
     let database = TestDatabase::new();
@@ -782,11 +949,13 @@ pub fn cancel_with_sold_tickets_and_hold() {
                 ticket_type_id: created_ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: created_ticket_type.id,
                 quantity: 5,
                 redemption_code: hold.redemption_code,
+                seat_ids: None,
             },
         ],
         false,
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use chrono::prelude::*;
 use serde_json;
 use uuid::Uuid;
@@ -53,6 +53,7 @@ pub fn index() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -77,6 +78,7 @@ pub fn index() {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -131,6 +133,7 @@ pub fn index() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        seat_label: None,
     };
     assert_eq!(vec![expected_ticket.clone()], found_data.data);
     // Test without specified event
@@ -157,6 +160,7 @@ pub fn index() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        seat_label: None,
     };
     assert_eq!(
         vec![
@@ -231,6 +235,7 @@ pub fn show() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -269,6 +274,7 @@ pub fn show() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        seat_label: None,
     };
 
     let expected_result = ShowTicketResponse {
@@ -509,6 +515,7 @@ fn ticket_transfer_authorization() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -662,6 +669,7 @@ fn receive_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -735,6 +743,7 @@ fn receive_ticket_transfer_fails_cancelled_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -772,3 +781,48 @@ fn receive_ticket_transfer_fails_cancelled_transfer() {
     let body = support::unwrap_body_to_string(&response).unwrap();
     assert_eq!(body, json!({"error": "The transfer has been cancelled."}).to_string());
 }
+
+#[test]
+fn signed_payload() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = database.create_user().finish();
+    let ticket = database.create_purchased_tickets(&user, ticket_type.id, 1).remove(0);
+    let redemption_key = OfflineRedemptionKey::for_event(&event, connection).unwrap();
+    let request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+
+    // Ticket owner
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
+    path.id = ticket.id;
+    let response: HttpResponse = tickets::signed_payload((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let payload: SignedTicketPayload = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(payload.ticket_instance_id, ticket.id);
+    assert_eq!(payload.event_id, event.id);
+    assert_eq!(Some(payload.redeem_key.clone()), ticket.redeem_key);
+    assert!(payload.verify(&redemption_key.public_key));
+
+    // Organization member
+    let org_member = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&org_member, Roles::OrgMember, Some(&organization), &database);
+    let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
+    path.id = ticket.id;
+    let response: HttpResponse = tickets::signed_payload((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Other users
+    let other_user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
+    path.id = ticket.id;
+    let response: HttpResponse = tickets::signed_payload((database.connection.clone().into(), path, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
@@ -0,0 +1,187 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::organizations;
+use bigneon_api::controllers::two_factor::{self, TwoFactorCodeRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::totp;
+use chrono::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn code_request(code: Option<String>, recovery_code: Option<String>) -> Json<TwoFactorCodeRequest> {
+    Json(TwoFactorCodeRequest { code, recovery_code })
+}
+
+/// Starts enrollment through the API returning the secret
+fn start_enrollment(database: &TestDatabase, user: &User) -> String {
+    let auth_user = support::create_auth_user_from_user(user, Roles::User, None, database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let response: HttpResponse = two_factor::create((state, database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let provisioning_uri = body["provisioning_uri"].as_str().unwrap();
+    assert!(provisioning_uri.starts_with("otpauth://totp/"));
+    body["secret"].as_str().unwrap().to_string()
+}
+
+fn current_code(secret: &str, offset: i64) -> String {
+    totp::generate_code(secret, totp::time_step(Utc::now().timestamp()) + offset).unwrap()
+}
+
+#[test]
+fn enable() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let secret = start_enrollment(&database, &user);
+
+    // Not enabled until a code is confirmed
+    assert!(TwoFactorCredential::find_enabled_by_user_id(user.id, database.connection.get()).is_err());
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(Some(current_code(&secret, 5)), None);
+    let response: HttpResponse =
+        two_factor::enable((state, database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Two-factor code is invalid"}).to_string());
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(Some(current_code(&secret, 0)), None);
+    let response: HttpResponse =
+        two_factor::enable((state, database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["recovery_codes"].as_array().unwrap().len(), 10);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = two_factor::show((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["enabled"], json!(true));
+    assert_eq!(body["remaining_recovery_codes"], json!(10));
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let secret = start_enrollment(&database, &user);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(Some(current_code(&secret, 0)), None);
+    let response: HttpResponse =
+        two_factor::enable((state, database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The code used to enable cannot be replayed
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(Some(current_code(&secret, 0)), None);
+    let response: HttpResponse =
+        two_factor::destroy((state, database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Two-factor code is invalid"}).to_string());
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(Some(current_code(&secret, 1)), None);
+    let response: HttpResponse =
+        two_factor::destroy((state, database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(TwoFactorCredential::find_by_user_id(user.id, database.connection.get()).is_err());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, database.connection.get()).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn regenerate_recovery_codes() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let secret = start_enrollment(&database, &user);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(Some(current_code(&secret, 0)), None);
+    let response: HttpResponse =
+        two_factor::enable((state, database.connection.clone().into(), json, auth_user)).into();
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let recovery_code = body["recovery_codes"][0].as_str().unwrap().to_string();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = code_request(None, Some(recovery_code.clone()));
+    let response: HttpResponse =
+        two_factor::regenerate_recovery_codes((state, database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let recovery_codes = body["recovery_codes"].as_array().unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+    assert!(!recovery_codes.contains(&json!(recovery_code)));
+}
+
+#[test]
+fn organization_requires_two_factor() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                two_factor_required_roles: Some(vec![Roles::OrgOwner]),
+                ..Default::default()
+            },
+            None,
+            &state.config.api_keys_encryption_key,
+            connection,
+        )
+        .unwrap();
+
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        organizations::show_fee_schedule((database.connection.clone().into(), path, auth_user.clone())).into();
+    support::expects_forbidden(
+        &response,
+        Some("Two-factor authentication is required by this organization"),
+    );
+
+    auth_user.two_factor_authenticated = true;
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        organizations::show_fee_schedule((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Roles not selected by the organization are unaffected
+    let user2 = database.create_user().finish();
+    let auth_user2 = support::create_auth_user_from_user(&user2, Roles::OrgMember, Some(&organization), &database);
+    assert!(auth_user2
+        .has_scope_for_organization(Scopes::OrgRead, &organization, connection)
+        .unwrap());
+}
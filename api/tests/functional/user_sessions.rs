@@ -0,0 +1,121 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::user_sessions::{self, DisplayUserSession};
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use bigneon_db::schema::user_sessions;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, Some("127.0.0.1".to_string()), Some("Browser".to_string()))
+        .commit(connection)
+        .unwrap();
+    let session2 = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let session3 = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    session3.revoke(Some(user.id), connection).unwrap();
+    let session2: UserSession = diesel::update(&session2)
+        .set(user_sessions::last_used_at.eq(Utc::now().naive_utc() - Duration::days(1)))
+        .get_result(connection)
+        .unwrap();
+
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    auth_user.session_id = Some(session.id);
+    let response: HttpResponse = user_sessions::index((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let sessions: Vec<DisplayUserSession> = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        sessions.iter().map(|s| (s.id, s.current)).collect::<Vec<_>>(),
+        vec![(session.id, true), (session2.id, false)]
+    );
+    assert_eq!(sessions[0].ip_address, Some("127.0.0.1".to_string()));
+    assert_eq!(sessions[0].user_agent, Some("Browser".to_string()));
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let session2 = UserSession::create(user2.id, None, None).commit(connection).unwrap();
+
+    // Sessions belonging to other users cannot be revoked
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = session2.id;
+    let response: HttpResponse =
+        user_sessions::destroy((database.connection.clone().into(), path, auth_user.clone())).into();
+    support::expects_forbidden(&response, Some("User does not have access to this session"));
+    assert!(UserSession::find(session2.id, connection).unwrap().is_active());
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = session.id;
+    let response: HttpResponse = user_sessions::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+}
+
+#[test]
+fn destroy_all() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user2.id, None, None).commit(connection).unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = user_sessions::destroy_all((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        UserSession::find_active_for_user(user2.id, connection).unwrap().len(),
+        1
+    );
+}
+
+#[test]
+fn destroy_for_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let admin = database.create_user().finish();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse =
+        user_sessions::destroy_for_user((database.connection.clone().into(), path, auth_user)).into();
+    support::expects_unauthorized(&response);
+    assert_eq!(UserSession::find_active_for_user(user.id, connection).unwrap().len(), 1);
+
+    let auth_user = support::create_auth_user_from_user(&admin, Roles::Admin, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse =
+        user_sessions::destroy_for_user((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+}
@@ -562,6 +562,7 @@ fn current_user_organization_owner() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "settlement:read",
@@ -726,3 +727,22 @@ fn update_current_user_address_exists() {
         "Email is already in use"
     );
 }
+
+#[test]
+fn update_current_user_with_delegated_credentials() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+
+    // Changing the email address of the user behind a key or token would allow taking over the account
+    let test_request =
+        support::create_api_key_request("/users/me", &user, &organization, vec![Scopes::OrgFans], &database);
+    support::expects_delegated_credentials_rejected(&test_request);
+
+    let test_request =
+        support::create_oauth_request("/users/me", &user, &organization, vec![Scopes::EventRead], &database);
+    support::expects_delegated_credentials_rejected(&test_request);
+}
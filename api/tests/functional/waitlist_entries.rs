@@ -0,0 +1,184 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::waitlist_entries;
+use bigneon_api::models::{EventTicketPathParameters, PathParameters};
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let cancelled_entry = WaitlistEntry::create(ticket_type.id, user2.id)
+        .commit(Some(user2.id), connection)
+        .unwrap()
+        .cancel(Some(user2.id), connection)
+        .unwrap();
+    assert_eq!(cancelled_entry.status, WaitlistEntryStatus::Cancelled);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = waitlist_entries::index((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let expected_json = serde_json::to_string(&vec![entry.for_display(connection).unwrap()]).unwrap();
+    assert_eq!(body, expected_json);
+
+    // Inactive entries are not listed
+    let auth_user = support::create_auth_user_from_user(&user2, Roles::User, None, &database);
+    let response: HttpResponse = waitlist_entries::index((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entries: Vec<DisplayWaitlistEntry> = support::unwrap_body_to_object(&response).unwrap();
+    assert!(entries.is_empty());
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let response: HttpResponse = waitlist_entries::create((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let display_entry: DisplayWaitlistEntry = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(display_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(display_entry.status, WaitlistEntryStatus::Waiting);
+    assert_eq!(display_entry.position, Some(1));
+    assert_eq!(display_entry.redemption_code, None);
+
+    let entry = WaitlistEntry::find(display_entry.id, connection).unwrap();
+    assert_eq!(entry.user_id, user.id);
+}
+
+#[test]
+fn create_with_tickets_available() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let response: HttpResponse = waitlist_entries::create((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation_response = support::validation_response_from_response(&response).unwrap();
+    let ticket_type_errors = validation_response.fields.get("ticket_type_id").unwrap();
+    assert_eq!(
+        &ticket_type_errors[0].message.clone().unwrap().into_owned(),
+        "Tickets are still available for this ticket type"
+    );
+}
+
+#[test]
+fn create_for_other_event() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let other_event = database.create_event().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = other_event.id;
+    path.ticket_type_id = ticket_type.id;
+    let response: HttpResponse = waitlist_entries::create((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(WaitlistEntry::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+    let user = database.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = entry.id;
+    let response: HttpResponse =
+        waitlist_entries::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Cancelled);
+}
+
+#[test]
+fn destroy_other_users_entry() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+    let user = database.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let other_user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = entry.id;
+    let response: HttpResponse =
+        waitlist_entries::destroy((database.connection.clone().into(), path, auth_user)).into();
+    support::expects_forbidden(&response, Some("You do not have access to this waitlist entry"));
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Waiting);
+}
@@ -0,0 +1,473 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::webhooks::{self, NewWebhookRequest};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::webhooks::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::webhooks::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::webhooks::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::webhooks::index(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn index_door_person() {
+        base::webhooks::index(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn index_promoter() {
+        base::webhooks::index(Roles::Promoter, false);
+    }
+    #[test]
+    fn index_promoter_read_only() {
+        base::webhooks::index(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn index_org_admin() {
+        base::webhooks::index(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn index_box_office() {
+        base::webhooks::index(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::webhooks::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::webhooks::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::webhooks::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::webhooks::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::webhooks::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::webhooks::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::webhooks::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::webhooks::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::webhooks::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod show_tests {
+    use super::*;
+    #[test]
+    fn show_org_member() {
+        base::webhooks::show(Roles::OrgMember, false);
+    }
+    #[test]
+    fn show_admin() {
+        base::webhooks::show(Roles::Admin, true);
+    }
+    #[test]
+    fn show_user() {
+        base::webhooks::show(Roles::User, false);
+    }
+    #[test]
+    fn show_org_owner() {
+        base::webhooks::show(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn show_door_person() {
+        base::webhooks::show(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn show_promoter() {
+        base::webhooks::show(Roles::Promoter, false);
+    }
+    #[test]
+    fn show_promoter_read_only() {
+        base::webhooks::show(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn show_org_admin() {
+        base::webhooks::show(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn show_box_office() {
+        base::webhooks::show(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[test]
+    fn update_org_member() {
+        base::webhooks::update(Roles::OrgMember, false);
+    }
+    #[test]
+    fn update_admin() {
+        base::webhooks::update(Roles::Admin, true);
+    }
+    #[test]
+    fn update_user() {
+        base::webhooks::update(Roles::User, false);
+    }
+    #[test]
+    fn update_org_owner() {
+        base::webhooks::update(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn update_door_person() {
+        base::webhooks::update(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn update_promoter() {
+        base::webhooks::update(Roles::Promoter, false);
+    }
+    #[test]
+    fn update_promoter_read_only() {
+        base::webhooks::update(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn update_org_admin() {
+        base::webhooks::update(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn update_box_office() {
+        base::webhooks::update(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::webhooks::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::webhooks::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::webhooks::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::webhooks::destroy(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::webhooks::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_promoter() {
+        base::webhooks::destroy(Roles::Promoter, false);
+    }
+    #[test]
+    fn destroy_promoter_read_only() {
+        base::webhooks::destroy(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::webhooks::destroy(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::webhooks::destroy(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod rotate_secret_tests {
+    use super::*;
+    #[test]
+    fn rotate_secret_org_member() {
+        base::webhooks::rotate_secret(Roles::OrgMember, false);
+    }
+    #[test]
+    fn rotate_secret_admin() {
+        base::webhooks::rotate_secret(Roles::Admin, true);
+    }
+    #[test]
+    fn rotate_secret_user() {
+        base::webhooks::rotate_secret(Roles::User, false);
+    }
+    #[test]
+    fn rotate_secret_org_owner() {
+        base::webhooks::rotate_secret(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn rotate_secret_door_person() {
+        base::webhooks::rotate_secret(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn rotate_secret_promoter() {
+        base::webhooks::rotate_secret(Roles::Promoter, false);
+    }
+    #[test]
+    fn rotate_secret_promoter_read_only() {
+        base::webhooks::rotate_secret(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn rotate_secret_org_admin() {
+        base::webhooks::rotate_secret(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn rotate_secret_box_office() {
+        base::webhooks::rotate_secret(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod ping_tests {
+    use super::*;
+    #[test]
+    fn ping_org_member() {
+        base::webhooks::ping(Roles::OrgMember, false);
+    }
+    #[test]
+    fn ping_admin() {
+        base::webhooks::ping(Roles::Admin, true);
+    }
+    #[test]
+    fn ping_user() {
+        base::webhooks::ping(Roles::User, false);
+    }
+    #[test]
+    fn ping_org_owner() {
+        base::webhooks::ping(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn ping_door_person() {
+        base::webhooks::ping(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn ping_promoter() {
+        base::webhooks::ping(Roles::Promoter, false);
+    }
+    #[test]
+    fn ping_promoter_read_only() {
+        base::webhooks::ping(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn ping_org_admin() {
+        base::webhooks::ping(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn ping_box_office() {
+        base::webhooks::ping(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod deliveries_tests {
+    use super::*;
+    #[test]
+    fn deliveries_org_member() {
+        base::webhooks::deliveries(Roles::OrgMember, false);
+    }
+    #[test]
+    fn deliveries_admin() {
+        base::webhooks::deliveries(Roles::Admin, true);
+    }
+    #[test]
+    fn deliveries_user() {
+        base::webhooks::deliveries(Roles::User, false);
+    }
+    #[test]
+    fn deliveries_org_owner() {
+        base::webhooks::deliveries(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn deliveries_door_person() {
+        base::webhooks::deliveries(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn deliveries_promoter() {
+        base::webhooks::deliveries(Roles::Promoter, false);
+    }
+    #[test]
+    fn deliveries_promoter_read_only() {
+        base::webhooks::deliveries(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn deliveries_org_admin() {
+        base::webhooks::deliveries(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn deliveries_box_office() {
+        base::webhooks::deliveries(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn show_webhook_from_other_organization() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&other_organization)
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let path = base::webhooks::webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::show((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn show_deleted_webhook() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+    domain_event_publisher.clone().delete(connection).unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let path = base::webhooks::webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::show((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn create_with_non_public_webhook_url() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+
+    for webhook_url in vec![
+        "http://127.0.0.1:8088/admin",
+        "http://localhost/admin",
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.5/",
+        "http://192.168.1.1/",
+        "http://[::1]/",
+        "http://[::ffff:127.0.0.1]/",
+        "file:///etc/passwd",
+    ] {
+        let json = Json(NewWebhookRequest {
+            event_types: vec![DomainEventTypes::EventPublished],
+            webhook_url: webhook_url.to_string(),
+            import_historic_events: false,
+            adapter: None,
+            adapter_config: None,
+        });
+        let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+        let test_request = TestRequest::create();
+        let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+        path.id = organization.id;
+        let response: HttpResponse =
+            webhooks::create((database.connection.clone().into(), path, json, auth_user)).into();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY, "{}", webhook_url);
+    }
+    assert!(DomainEventPublisher::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn update_with_non_public_webhook_url() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+
+    let json = Json(DomainEventPublisherEditableAttributes {
+        webhook_url: Some("http://169.254.169.254/latest/meta-data".to_string()),
+        ..Default::default()
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let path = base::webhooks::webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::update((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        DomainEventPublisher::find(domain_event_publisher.id, connection)
+            .unwrap()
+            .webhook_url,
+        domain_event_publisher.webhook_url
+    );
+}
+
+#[test]
+fn ping_non_public_webhook_url() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    // Webhooks created before URLs were checked, or whose host now resolves to a private address
+    let domain_event_publisher = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_webhook_url("http://169.254.169.254/latest/meta-data".to_string())
+        .finish();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let path = base::webhooks::webhook_path(&organization, &domain_event_publisher);
+    let response: HttpResponse = webhooks::ping((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let delivery: DisplayWebhookDelivery = serde_json::from_str(&body).unwrap();
+    assert!(!delivery.succeeded);
+    assert_eq!(delivery.status_code, None);
+    assert_eq!(
+        delivery.error,
+        Some("Webhook URL must be a public http or https URL".to_string())
+    );
+    let deliveries = domain_event_publisher.webhook_deliveries(0, 100, connection).unwrap();
+    assert_eq!(deliveries.data[0].response_body, None);
+}
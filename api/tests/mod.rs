@@ -10,6 +10,7 @@ extern crate bigneon_api;
 extern crate bigneon_db;
 extern crate chrono;
 extern crate diesel;
+extern crate futures;
 #[macro_use]
 extern crate macros;
 #[macro_use]
@@ -17,8 +18,12 @@ extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
 extern crate globee;
+extern crate hosted_checkout;
 extern crate jsonwebtoken as jwt;
+extern crate oidc;
+extern crate reqwest;
 extern crate serde;
+extern crate stripe;
 extern crate uuid;
 extern crate validator;
 
@@ -98,6 +98,10 @@ impl TestDatabase {
         PaymentMethodBuilder::new(self.connection.get())
     }
 
+    pub fn create_saved_report(&self) -> SavedReportBuilder {
+        SavedReportBuilder::new(self.connection.get())
+    }
+
     pub fn create_slug(&self) -> SlugBuilder {
         SlugBuilder::new(self.connection.get())
     }
@@ -106,6 +110,10 @@ impl TestDatabase {
         RegionBuilder::new(self.connection.get())
     }
 
+    pub fn create_tax_rule(&self) -> TaxRuleBuilder {
+        TaxRuleBuilder::new(self.connection.get())
+    }
+
     pub fn create_user(&self) -> UserBuilder {
         UserBuilder::new(self.connection.get())
     }
@@ -154,6 +162,7 @@ impl TestDatabase {
                 ticket_type_id,
                 quantity,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
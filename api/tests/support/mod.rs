@@ -1,9 +1,12 @@
 pub mod database;
 pub mod test_request;
 
-use actix_web::{http::StatusCode, Body::Binary, HttpResponse};
+use actix_web::{http::StatusCode, Body, Body::Binary, FromRequest, HttpResponse};
+use bigneon_api::auth::claims::AccessToken;
 use bigneon_api::auth::user::User as AuthUser;
-use bigneon_db::models::{Organization, Roles, User};
+use bigneon_db::models::{ApiKey, OAuthClient, OAuthConsent, Organization, Roles, Scopes, User};
+use futures::{Future, Stream};
+use jwt::{encode, Header};
 use serde::Deserialize;
 use serde_json;
 use std::collections::HashMap;
@@ -26,6 +29,18 @@ pub fn unwrap_body_to_string(response: &HttpResponse) -> Result<&str, &'static s
     }
 }
 
+/// Reads a body that is either binary or streamed, taking it from the response
+pub fn take_body_to_string(response: &mut HttpResponse) -> Result<String, &'static str> {
+    match response.replace_body(Body::Empty) {
+        Binary(binary) => Ok(str::from_utf8(binary.as_ref()).unwrap().to_string()),
+        Body::Streaming(stream) => {
+            let body = stream.concat2().wait().map_err(|_| "Could not read response body")?;
+            Ok(str::from_utf8(&body).unwrap().to_string())
+        }
+        _ => Err("Unexpected response body"),
+    }
+}
+
 pub fn unwrap_body_to_object<'a, T>(response: &'a HttpResponse) -> Result<T, &'static str>
 where
     T: Deserialize<'a>,
@@ -77,6 +92,71 @@ pub fn create_auth_user_from_user(
     }
 }
 
+/// Request authenticated with a new API key of the user's, the request shares the test database
+/// connection so the `User` extractor can find the key
+pub fn create_api_key_request(
+    path: &str,
+    user: &User,
+    organization: &Organization,
+    scopes: Vec<Scopes>,
+    database: &TestDatabase,
+) -> TestRequest {
+    let (_, key) = ApiKey::create(organization.id, user.id, "Integration".to_string(), scopes, None)
+        .commit(None, database.connection.get())
+        .unwrap();
+    create_authorized_request(path, format!("ApiKey {}", key), database)
+}
+
+/// Request authenticated with an access token issued to an OAuth client of the organization
+pub fn create_oauth_request(
+    path: &str,
+    user: &User,
+    organization: &Organization,
+    scopes: Vec<Scopes>,
+    database: &TestDatabase,
+) -> TestRequest {
+    let connection = database.connection.get();
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Integration".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        scopes.clone(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let consent = OAuthConsent::grant(&oauth_client, user.id, scopes.clone(), connection).unwrap();
+    let config = TestRequest::test_config();
+    let claims = AccessToken::new_for_oauth_consent(
+        &consent,
+        scopes.iter().map(|s| s.to_string()).collect(),
+        config.token_issuer.clone(),
+        &config.jwt_expiry_time,
+    );
+    let access_token = encode(&Header::default(), &claims, config.token_secret.as_bytes()).unwrap();
+    create_authorized_request(path, format!("Bearer {}", access_token), database)
+}
+
+pub fn create_authorized_request(path: &str, authorization: String, database: &TestDatabase) -> TestRequest {
+    let test_request = TestRequest::create_with_config(
+        path,
+        Vec::new(),
+        TestRequest::test_config(),
+        vec![("Authorization", authorization)],
+    );
+    test_request
+        .request
+        .extensions_mut()
+        .insert(database.connection.clone());
+    test_request
+}
+
+pub fn expects_delegated_credentials_rejected(test_request: &TestRequest) {
+    let error = AuthUser::extract(&test_request.request).err().unwrap();
+    let response = error.as_response_error().error_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
 pub fn expects_unauthorized(response: &HttpResponse) {
     let expected_json =
         HttpResponse::Unauthorized().json(json!({"error": "User does not have the required permissions"}));
@@ -1,6 +1,7 @@
-use actix_web::{test, FromRequest, HttpRequest, Path, Query, State};
+use actix_web::{test, FromRequest, HttpRequest, Path, State};
 use bigneon_api::config::Config;
 use bigneon_api::db::Database;
+use bigneon_api::extractors::Query;
 use bigneon_api::server::AppState;
 use bigneon_api::utils::spotify;
 use bigneon_db::models::Environment;
@@ -21,11 +22,24 @@ impl TestRequest {
     }
 
     pub fn create_with_uri_custom_params(path: &str, params: Vec<&'static str>) -> TestRequest {
+        TestRequest::create_with_config(path, params, TestRequest::test_config(), Vec::new())
+    }
+
+    pub fn test_config() -> Config {
         let mut config = Config::new(Environment::Test);
         config.token_secret = "test_secret".into();
         config.token_issuer = "bn-api-test".into();
         config.api_keys_encryption_key = "test_encryption_key".to_string();
         config.google_recaptcha_secret_key = None;
+        config
+    }
+
+    pub fn create_with_config(
+        path: &str,
+        params: Vec<&'static str>,
+        config: Config,
+        headers: Vec<(&'static str, String)>,
+    ) -> TestRequest {
         if config.spotify_auth_token.is_some() {
             spotify::SINGLETON.set_auth_token(&config.spotify_auth_token.clone().unwrap());
         }
@@ -50,6 +64,10 @@ impl TestRequest {
             request = request.param(param, "0f85443e-9e70-45ba-bf28-0f59c183856f");
         }
 
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
         TestRequest {
             request: request.finish(),
             config,
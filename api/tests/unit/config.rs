@@ -0,0 +1,28 @@
+use bigneon_api::config::{domain_action_concurrency_limits, RetryBackoff};
+use bigneon_db::models::DomainActionTypes;
+
+#[test]
+fn retry_backoff_delay_in_secs() {
+    let retry_backoff = RetryBackoff {
+        base_in_secs: 60,
+        max_in_secs: 3600,
+    };
+    assert_eq!(retry_backoff.delay_in_secs(0), 60);
+    assert_eq!(retry_backoff.delay_in_secs(1), 120);
+    assert_eq!(retry_backoff.delay_in_secs(3), 480);
+    assert_eq!(retry_backoff.delay_in_secs(10), 3600);
+    assert_eq!(retry_backoff.delay_in_secs(100), 3600);
+}
+
+#[test]
+fn concurrency_limits() {
+    let limits = domain_action_concurrency_limits(None);
+    assert_eq!(limits.get(&DomainActionTypes::BroadcastPushNotification), Some(&1));
+    assert_eq!(limits.get(&DomainActionTypes::ProcessSettlementReport), Some(&1));
+    assert_eq!(limits.get(&DomainActionTypes::SendPurchaseCompletedCommunication), None);
+
+    let limits = domain_action_concurrency_limits(Some("BroadcastPushNotification:3, Communication:10"));
+    assert_eq!(limits.get(&DomainActionTypes::BroadcastPushNotification), Some(&3));
+    assert_eq!(limits.get(&DomainActionTypes::Communication), Some(&10));
+    assert_eq!(limits.get(&DomainActionTypes::ProcessSettlementReport), Some(&1));
+}
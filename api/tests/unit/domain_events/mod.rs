@@ -0,0 +1 @@
+pub mod monitor_metrics;
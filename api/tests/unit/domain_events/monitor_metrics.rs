@@ -0,0 +1,38 @@
+use bigneon_api::domain_events::{MonitorMetrics, WakeUpReason};
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn record_processed() {
+    let mut metrics = MonitorMetrics::new("domain_actions");
+    assert_eq!(metrics.processed(), 0);
+    assert_eq!(metrics.average_latency_ms(), 0);
+
+    let now = Utc::now().naive_utc();
+    metrics.record_processed(now - Duration::seconds(2));
+    metrics.record_processed(now - Duration::seconds(4));
+    assert_eq!(metrics.processed(), 2);
+    assert!(metrics.max_latency_ms() >= 4000);
+    assert!(metrics.average_latency_ms() >= 3000);
+    assert!(metrics.average_latency_ms() < metrics.max_latency_ms());
+    assert!(metrics.processed_per_minute() > 0.0);
+
+    // Work due in the future is treated as picked up immediately
+    metrics.record_processed(now + Duration::seconds(60));
+    assert_eq!(metrics.processed(), 3);
+    assert!(metrics.max_latency_ms() < 60000);
+}
+
+#[test]
+fn record_wake_up() {
+    let mut metrics = MonitorMetrics::new("domain_events");
+    metrics.record_wake_up(WakeUpReason::Notified);
+    metrics.record_wake_up(WakeUpReason::Notified);
+    metrics.record_wake_up(WakeUpReason::Polled);
+    assert_eq!(metrics.notified_wake_ups(), 2);
+    assert_eq!(metrics.polled_wake_ups(), 1);
+
+    // Metrics are only reset once the reporting period has passed
+    metrics.report_if_due();
+    assert_eq!(metrics.notified_wake_ups(), 2);
+}
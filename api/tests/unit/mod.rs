@@ -1,3 +1,5 @@
+pub mod config;
+pub mod domain_events;
 pub mod helpers;
 pub mod mailers;
 pub mod models;
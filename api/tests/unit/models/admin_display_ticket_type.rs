@@ -91,6 +91,7 @@ fn from_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 100,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -109,6 +110,7 @@ fn from_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 90,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
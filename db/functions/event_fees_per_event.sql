@@ -12,7 +12,8 @@ CREATE OR REPLACE FUNCTION event_fees_per_event("start" TIMESTAMP, "end" TIMESTA
         event_id                      UUID,
         per_order_company_online_fees BIGINT,
         per_order_client_online_fees  BIGINT,
-        per_order_total_fees_in_cents BIGINT
+        per_order_total_fees_in_cents BIGINT,
+        currency                      TEXT
     )
 AS
 $body$
@@ -29,7 +30,8 @@ SELECT e.organization_id           AS organization_id,
        CAST(COALESCE(SUM((COALESCE(oi.unit_price_in_cents, 0) *
                           (COALESCE(oi.quantity, 0) - COALESCE(oi.refunded_quantity, 0))))
                          FILTER (WHERE p.is_box_office IS FALSE),
-                     0) AS BIGINT) AS per_order_total_fees_in_cents
+                     0) AS BIGINT) AS per_order_total_fees_in_cents,
+       oi.currency                 AS currency
 FROM order_items oi
          LEFT JOIN orders o on oi.order_id = o.id
          LEFT JOIN events e on oi.event_id = e.id
@@ -44,6 +46,6 @@ FROM order_items oi
 WHERE oi.item_type = 'EventFees'
   AND ($1 IS NULL OR o.paid_at >= $1)
   AND ($2 IS NULL OR o.paid_at <= $2)
-GROUP BY e.organization_id, e_.id;
+GROUP BY e.organization_id, e_.id, oi.currency;
 $body$
     LANGUAGE SQL;
@@ -34,30 +34,32 @@ AND ($4 IS NULL OR o.paid_at <= $4)
 AND oi.event_id = $2
 AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
-AND o.settlement_id IS NULL
+AND oi.settlement_id IS NULL
 AND o.status = 'Paid'
 AND oi.parent_id IS NULL
 AND o.box_office_pricing IS FALSE;
 
 -- Add refund items to the order items temp table
 INSERT INTO order_item_ids(id, refund_id)
-SELECT DISTINCT COALESCE(oi.parent_id, oi.id), r.id
+-- Tax on fees is a grandchild of the ticket it applies to
+SELECT DISTINCT COALESCE(oi_parent.parent_id, oi.parent_id, oi.id), r.id
 FROM refunds r
 INNER JOIN refund_items ri ON ri.refund_id = r.id
 INNER JOIN order_items oi ON oi.id = ri.order_item_id
 INNER JOIN orders o on oi.order_id = o.id
+LEFT JOIN order_items oi_parent ON oi_parent.id = oi.parent_id
 WHERE oi.event_id = $2
 AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
 AND (start_override IS NULL OR r.created_at >= start_override)
 AND ($3 IS NULL OR r.created_at >= $3)
 AND ($4 IS NULL OR r.created_at <= $4)
-AND o.settlement_id is distinct from $1
+AND COALESCE(oi_parent.settlement_id, oi.settlement_id) is distinct from $1
 AND ri.amount > 0
-AND r.settlement_id IS NULL
+AND ri.settlement_id IS NULL
 AND o.box_office_pricing IS FALSE;
 
-INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type)
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, tax_in_cents, settlement_entry_type)
 SELECT -- Group result set by face price to prevent multiple records for holds that match code discounts
   entries.settlement_id,
   entries.event_id,
@@ -66,19 +68,23 @@ SELECT -- Group result set by face price to prevent multiple records for holds t
   entries.revenue_share_value_in_cents,
   SUM(online_sold_quantity),
   SUM(fee_sold_quantity),
-  SUM(online_sold_quantity) * entries.face_value_in_cents + SUM(fee_sold_quantity) * entries.revenue_share_value_in_cents,
+  SUM(online_sold_quantity) * entries.face_value_in_cents + SUM(fee_sold_quantity) * entries.revenue_share_value_in_cents
+    + SUM(online_sold_quantity) * entries.charged_tax_in_cents,
+  SUM(online_sold_quantity) * entries.tax_per_unit_in_cents,
   entries.settlement_entry_type
 FROM (
   SELECT
     $1 as settlement_id,
     oi.event_id,
     oi.ticket_type_id,
-    CASE oi.item_type WHEN 'EventFees' THEN 0 ELSE CAST(oi.unit_price_in_cents + COALESCE(oi_promo_code.unit_price_in_cents, 0) AS BIGINT) END as face_value_in_cents,
+    CASE oi.item_type WHEN 'EventFees' THEN 0 WHEN 'ResaleTickets' THEN 0 ELSE CAST(oi.unit_price_in_cents + COALESCE(oi_promo_code.unit_price_in_cents, 0) AS BIGINT) END as face_value_in_cents,
     -- Event fees record list the fee as part of the revenue share for that item with 0 face value
-    CASE oi.item_type WHEN 'EventFees' THEN CAST(oi.client_fee_in_cents AS BIGINT) ELSE CAST(COALESCE(oi_t_fees.client_fee_in_cents, 0) AS BIGINT) END as revenue_share_value_in_cents,
-    -- Event fees list their quantity in the fee_sold_quantity field
+    -- Resale tickets are paid out to the seller so only the client share of the resale fee is settled
+    CASE oi.item_type WHEN 'EventFees' THEN CAST(oi.client_fee_in_cents AS BIGINT) WHEN 'ResaleTickets' THEN CAST(oi.client_fee_in_cents AS BIGINT) ELSE CAST(COALESCE(oi_t_fees.client_fee_in_cents, 0) AS BIGINT) END as revenue_share_value_in_cents,
+    -- Event fees and resale tickets list their quantity in the fee_sold_quantity field
     CASE oi.item_type
       WHEN 'EventFees' THEN 0
+      WHEN 'ResaleTickets' THEN 0
       ELSE
         CASE WHEN oi_r.quantity IS NOT NULL THEN
           CAST(-SUM(oi_r.quantity) AS BIGINT)
@@ -86,8 +92,8 @@ FROM (
           CAST(SUM(oi.quantity) AS BIGINT)
         END
     END as online_sold_quantity,
-    CASE oi.item_type
-      WHEN 'EventFees' THEN
+    CASE
+      WHEN oi.item_type IN ('EventFees', 'ResaleTickets') THEN
         CASE WHEN oi_r.quantity IS NOT NULL THEN
           CAST(-SUM(oi_r.quantity) AS BIGINT)
         ELSE
@@ -100,7 +106,10 @@ FROM (
           CAST(SUM(COALESCE(oi_t_fees.quantity, 0)) AS BIGINT)
         END
     END as fee_sold_quantity,
-    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' ELSE 'TicketType' END as settlement_entry_type
+    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' WHEN 'ResaleTickets' THEN 'ResaleFees' ELSE 'TicketType' END as settlement_entry_type,
+    -- Only ticket tax is settled, exclusive tax is collected on top of the face value while inclusive tax is part of it
+    CASE oi.item_type WHEN 'Tickets' THEN CAST(COALESCE(oi_tax.unit_price_in_cents, 0) AS BIGINT) ELSE 0 END as charged_tax_in_cents,
+    CASE oi.item_type WHEN 'Tickets' THEN CAST(COALESCE(oi_tax.unit_price_in_cents + oi_tax.included_tax_in_cents, 0) AS BIGINT) ELSE 0 END as tax_per_unit_in_cents
   FROM order_items oi
   INNER JOIN order_item_ids oi_ids ON oi.id = oi_ids.id
   INNER JOIN orders o ON oi.order_id = o.id
@@ -108,6 +117,7 @@ FROM (
   LEFT JOIN order_items oi_promo_code ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
   LEFT JOIN order_items oi_t_fees ON oi_t_fees.parent_id = oi.id AND oi_t_fees.item_type = 'PerUnitFees'
   LEFT JOIN refund_items oi_t_fees_r ON oi_t_fees_r.order_item_id = oi_t_fees.id AND oi_t_fees_r.refund_id = oi_ids.refund_id
+  LEFT JOIN order_items oi_tax ON oi_tax.parent_id = oi.id AND oi_tax.item_type = 'Tax'
   GROUP BY
     oi.item_type,
     oi.event_id,
@@ -117,7 +127,9 @@ FROM (
     oi_t_fees.client_fee_in_cents,
     oi_promo_code.unit_price_in_cents,
     oi_t_fees_r.quantity,
-    oi_r.quantity
+    oi_r.quantity,
+    oi_tax.unit_price_in_cents,
+    oi_tax.included_tax_in_cents
 ) entries
   GROUP BY
     entries.settlement_id,
@@ -125,7 +137,9 @@ FROM (
     entries.ticket_type_id,
     entries.face_value_in_cents,
     entries.revenue_share_value_in_cents,
-    entries.settlement_entry_type
+    entries.settlement_entry_type,
+    entries.charged_tax_in_cents,
+    entries.tax_per_unit_in_cents
   -- Filter out any records where the sum of their quantities is 0
   -- Negative indicates a refund settlement adjustment, positive purchases
   HAVING
@@ -134,7 +148,19 @@ FROM (
     (SUM(fee_sold_quantity) <> 0 AND revenue_share_value_in_cents > 0)
 ;
 
--- Update associated orders as part of this settlement
+-- Update associated order items, their fees and taxes as part of this settlement, orders spanning several events
+-- are settled per event
+UPDATE order_items SET settlement_id = $1
+FROM order_item_ids oi_ids
+WHERE (
+  order_items.id = oi_ids.id
+  OR order_items.parent_id = oi_ids.id
+  OR order_items.parent_id IN (SELECT c.id FROM order_items c WHERE c.parent_id = oi_ids.id)
+)
+AND order_items.settlement_id IS NULL
+AND oi_ids.refund_id IS NULL;
+
+-- Orders reference the first settlement they were included in
 UPDATE orders SET settlement_id = $1
 FROM order_item_ids oi_ids
 JOIN order_items oi ON oi.id = oi_ids.id
@@ -143,6 +169,19 @@ WHERE orders.id = o.id
 AND orders.settlement_id IS NULL
 AND oi_ids.refund_id IS NULL;
 
+-- Update refund items that occurred during this settlement for order items in this settlement
+UPDATE refund_items SET settlement_id = $1
+FROM order_item_ids oi_ids
+JOIN order_items oi ON (
+  oi.id = oi_ids.id
+  OR oi.parent_id = oi_ids.id
+  OR oi.parent_id IN (SELECT c.id FROM order_items c WHERE c.parent_id = oi_ids.id)
+)
+WHERE refund_items.refund_id = oi_ids.refund_id
+AND refund_items.order_item_id = oi.id
+AND refund_items.settlement_id IS NULL
+AND oi_ids.refund_id IS NOT NULL;
+
 -- Update refunds that occurred during this settlement for orders in this settlement
 UPDATE refunds SET settlement_id = $1
 FROM order_item_ids oi_ids
@@ -0,0 +1,121 @@
+--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------
+-- PROCESS SETTLEMENT FOR SEASON PASSES
+--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------
+-- Season passes are not tied to an event so their sales and refunds are settled in the period they happened in
+DROP FUNCTION IF EXISTS process_settlement_for_season_passes(settlement_id UUID, start TIMESTAMP, "end" TIMESTAMP);
+CREATE OR REPLACE FUNCTION process_settlement_for_season_passes(settlement_id UUID, start TIMESTAMP, "end" TIMESTAMP) RETURNS void AS $$
+BEGIN
+
+CREATE TEMP TABLE season_pass_order_item_ids (
+   id UUID,
+   refund_id UUID
+);
+
+INSERT INTO season_pass_order_item_ids(id, refund_id)
+SELECT oi.id, NULL
+FROM order_items oi
+INNER JOIN orders o ON oi.order_id = o.id
+INNER JOIN season_passes sp ON oi.season_pass_id = sp.id
+INNER JOIN settlements s ON s.id = $1 AND s.organization_id = sp.organization_id
+WHERE o.paid_at >= $2
+AND o.paid_at <= $3
+AND oi.item_type = 'SeasonPasses'
+AND oi.settlement_id IS NULL
+AND o.status = 'Paid'
+AND COALESCE(o.currency, s.currency) = s.currency;
+
+-- Add refund items to the order items temp table, fees are children of the season pass item
+INSERT INTO season_pass_order_item_ids(id, refund_id)
+SELECT DISTINCT oi_pass.id, r.id
+FROM refunds r
+INNER JOIN refund_items ri ON ri.refund_id = r.id
+INNER JOIN order_items oi ON oi.id = ri.order_item_id
+INNER JOIN order_items oi_pass ON oi_pass.id = COALESCE(oi.parent_id, oi.id)
+INNER JOIN orders o ON oi.order_id = o.id
+INNER JOIN season_passes sp ON oi_pass.season_pass_id = sp.id
+INNER JOIN settlements s ON s.id = $1 AND s.organization_id = sp.organization_id
+WHERE oi_pass.item_type = 'SeasonPasses'
+AND r.created_at >= $2
+AND r.created_at <= $3
+AND oi_pass.settlement_id IS DISTINCT FROM $1
+AND ri.amount > 0
+AND ri.settlement_id IS NULL
+AND COALESCE(o.currency, s.currency) = s.currency;
+
+INSERT INTO season_pass_settlement_entries (settlement_id, season_pass_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents)
+SELECT
+  entries.settlement_id,
+  entries.season_pass_id,
+  entries.face_value_in_cents,
+  entries.revenue_share_value_in_cents,
+  SUM(online_sold_quantity),
+  SUM(fee_sold_quantity),
+  SUM(online_sold_quantity) * entries.face_value_in_cents + SUM(fee_sold_quantity) * entries.revenue_share_value_in_cents
+FROM (
+  SELECT
+    $1 as settlement_id,
+    oi.season_pass_id,
+    CAST(oi.unit_price_in_cents AS BIGINT) as face_value_in_cents,
+    CAST(COALESCE(oi_fees.client_fee_in_cents, 0) AS BIGINT) as revenue_share_value_in_cents,
+    -- Refunds are recorded as negative quantities
+    CASE WHEN oi_ids.refund_id IS NOT NULL THEN
+      CAST(-COALESCE(oi_r.quantity, 0) AS BIGINT)
+    ELSE
+      CAST(oi.quantity AS BIGINT)
+    END as online_sold_quantity,
+    CASE WHEN oi_ids.refund_id IS NOT NULL THEN
+      CAST(-COALESCE(oi_fees_r.quantity, 0) AS BIGINT)
+    ELSE
+      CAST(COALESCE(oi_fees.quantity, 0) AS BIGINT)
+    END as fee_sold_quantity
+  FROM order_items oi
+  INNER JOIN season_pass_order_item_ids oi_ids ON oi.id = oi_ids.id
+  LEFT JOIN refund_items oi_r ON oi_r.order_item_id = oi.id AND oi_r.refund_id = oi_ids.refund_id
+  LEFT JOIN order_items oi_fees ON oi_fees.parent_id = oi.id AND oi_fees.item_type = 'PerUnitFees'
+  LEFT JOIN refund_items oi_fees_r ON oi_fees_r.order_item_id = oi_fees.id AND oi_fees_r.refund_id = oi_ids.refund_id
+) entries
+  GROUP BY
+    entries.settlement_id,
+    entries.season_pass_id,
+    entries.face_value_in_cents,
+    entries.revenue_share_value_in_cents
+  -- Filter out any records where the sum of their quantities is 0
+  HAVING
+    (SUM(online_sold_quantity) <> 0 AND face_value_in_cents > 0)
+  OR
+    (SUM(fee_sold_quantity) <> 0 AND revenue_share_value_in_cents > 0)
+;
+
+-- Update the season pass order items and their fees as part of this settlement
+UPDATE order_items SET settlement_id = $1
+FROM season_pass_order_item_ids oi_ids
+WHERE (order_items.id = oi_ids.id OR order_items.parent_id = oi_ids.id)
+AND order_items.settlement_id IS NULL
+AND oi_ids.refund_id IS NULL;
+
+-- Orders reference the first settlement they were included in
+UPDATE orders SET settlement_id = $1
+FROM season_pass_order_item_ids oi_ids
+JOIN order_items oi ON oi.id = oi_ids.id
+WHERE orders.id = oi.order_id
+AND orders.settlement_id IS NULL
+AND oi_ids.refund_id IS NULL;
+
+-- Update refund items that occurred during this settlement for season passes
+UPDATE refund_items SET settlement_id = $1
+FROM season_pass_order_item_ids oi_ids
+JOIN order_items oi ON (oi.id = oi_ids.id OR oi.parent_id = oi_ids.id)
+WHERE refund_items.refund_id = oi_ids.refund_id
+AND refund_items.order_item_id = oi.id
+AND refund_items.settlement_id IS NULL
+AND oi_ids.refund_id IS NOT NULL;
+
+UPDATE refunds SET settlement_id = $1
+FROM season_pass_order_item_ids oi_ids
+WHERE refunds.id = oi_ids.refund_id
+AND refunds.settlement_id IS NULL
+AND oi_ids.refund_id IS NOT NULL;
+
+DROP TABLE season_pass_order_item_ids;
+
+END $$ LANGUAGE 'plpgsql';
@@ -44,8 +44,8 @@ CREATE OR REPLACE FUNCTION ticket_sales_per_ticket_pricing(start TIMESTAMP, "end
                 per_order_company_online_fees      BIGINT,
                 per_order_client_online_fees       BIGINT,
                 per_order_total_fees_in_cents      BIGINT,
-                user_count                         BIGINT
-
+                user_count                         BIGINT,
+                currency                           TEXT
             )
 AS
 $body$
@@ -147,7 +147,8 @@ SELECT e.organization_id
        CAST(0 AS BIGINT)                                                                                        AS per_order_client_online_fees,
        CAST(0 AS BIGINT)                                                                                        AS per_order_total_fees_in_cents,
        --End of injected values
-       CAST(COUNT(DISTINCT (COALESCE(o.on_behalf_of_user_id, o.user_id))) AS BIGINT)                            AS user_count
+       CAST(COUNT(DISTINCT (COALESCE(o.on_behalf_of_user_id, o.user_id))) AS BIGINT)                            AS user_count,
+       e.currency                                                                                               AS currency
 
 FROM order_items oi
          LEFT JOIN order_items oi_promo_code
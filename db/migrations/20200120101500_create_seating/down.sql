@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_ticket_instances_seat_id;
+
+ALTER TABLE ticket_instances
+    DROP seat_id;
+
+DROP INDEX IF EXISTS index_seats_stage_section_id_row_name_seat_number;
+DROP TABLE IF EXISTS seats;
+DROP INDEX IF EXISTS index_stage_sections_stage_id_name;
+DROP TABLE IF EXISTS stage_sections;
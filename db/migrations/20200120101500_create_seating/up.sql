@@ -0,0 +1,29 @@
+CREATE TABLE stage_sections
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    stage_id   UUID      NOT NULL REFERENCES stages (id) ON DELETE CASCADE,
+    name       TEXT      NOT NULL,
+    rank       INTEGER   NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_stage_sections_stage_id_name ON stage_sections (stage_id, name);
+
+CREATE TABLE seats
+(
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    stage_section_id UUID      NOT NULL REFERENCES stage_sections (id) ON DELETE CASCADE,
+    row_name         TEXT      NOT NULL,
+    seat_number      TEXT      NOT NULL,
+    rank             INTEGER   NOT NULL DEFAULT 0,
+    created_at       TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at       TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_seats_stage_section_id_row_name_seat_number ON seats (stage_section_id, row_name, seat_number);
+
+ALTER TABLE ticket_instances
+    ADD seat_id UUID NULL REFERENCES seats (id);
+
+CREATE INDEX index_ticket_instances_seat_id ON ticket_instances (seat_id);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_waitlist_entries_ticket_type_id_user_id_active;
+DROP INDEX IF EXISTS index_waitlist_entries_user_id;
+DROP INDEX IF EXISTS index_waitlist_entries_ticket_type_id_status;
+DROP TABLE IF EXISTS waitlist_entries;
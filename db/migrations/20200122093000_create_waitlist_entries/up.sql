@@ -0,0 +1,17 @@
+CREATE TABLE waitlist_entries
+(
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_type_id   UUID      NOT NULL REFERENCES ticket_types (id),
+    user_id          UUID      NOT NULL REFERENCES users (id),
+    status           TEXT      NOT NULL DEFAULT 'Waiting',
+    hold_id          UUID      NULL REFERENCES holds (id),
+    offer_expires_at TIMESTAMP NULL,
+    created_at       TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at       TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_waitlist_entries_ticket_type_id_status ON waitlist_entries (ticket_type_id, status);
+CREATE INDEX index_waitlist_entries_user_id ON waitlist_entries (user_id);
+-- A fan can only be in the queue once per ticket type while their entry is active
+CREATE UNIQUE INDEX index_waitlist_entries_ticket_type_id_user_id_active ON waitlist_entries (ticket_type_id, user_id)
+    WHERE status IN ('Waiting', 'Offered');
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_resale_listings_ticket_instance_id_active;
+DROP INDEX IF EXISTS index_resale_listings_order_item_id;
+DROP INDEX IF EXISTS index_resale_listings_seller_user_id;
+DROP TABLE IF EXISTS resale_listings;
+
+ALTER TABLE organizations
+    DROP max_resale_price_percent,
+    DROP resale_company_fee_percent,
+    DROP resale_client_fee_percent;
@@ -0,0 +1,26 @@
+ALTER TABLE organizations
+    ADD max_resale_price_percent BIGINT NULL,
+    ADD resale_company_fee_percent REAL NOT NULL DEFAULT 0,
+    ADD resale_client_fee_percent REAL NOT NULL DEFAULT 0;
+
+CREATE TABLE resale_listings
+(
+    id                     UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_instance_id     UUID      NOT NULL REFERENCES ticket_instances (id),
+    seller_user_id         UUID      NOT NULL REFERENCES users (id),
+    price_in_cents         BIGINT    NOT NULL,
+    status                 TEXT      NOT NULL DEFAULT 'Active',
+    order_item_id          UUID      NULL REFERENCES order_items (id) ON DELETE SET NULL,
+    reserved_until         TIMESTAMP NULL,
+    buyer_user_id          UUID      NULL REFERENCES users (id),
+    seller_payout_in_cents BIGINT    NULL,
+    sold_at                TIMESTAMP NULL,
+    created_at             TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at             TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_resale_listings_seller_user_id ON resale_listings (seller_user_id);
+CREATE INDEX index_resale_listings_order_item_id ON resale_listings (order_item_id);
+-- A ticket can only be listed once at a time
+CREATE UNIQUE INDEX index_resale_listings_ticket_instance_id_active ON resale_listings (ticket_instance_id)
+    WHERE status = 'Active';
@@ -0,0 +1,14 @@
+DROP INDEX IF EXISTS index_season_pass_redemptions_event_id;
+DROP INDEX IF EXISTS index_season_pass_redemptions_entitlement_id_event_id;
+DROP TABLE IF EXISTS season_pass_redemptions;
+DROP INDEX IF EXISTS index_season_pass_entitlements_order_item_id;
+DROP INDEX IF EXISTS index_season_pass_entitlements_user_id;
+DROP TABLE IF EXISTS season_pass_entitlements;
+DROP INDEX IF EXISTS index_order_items_season_pass_id;
+ALTER TABLE order_items
+    DROP season_pass_id;
+DROP INDEX IF EXISTS index_season_pass_events_event_id;
+DROP INDEX IF EXISTS index_season_pass_events_season_pass_id_event_id;
+DROP TABLE IF EXISTS season_pass_events;
+DROP INDEX IF EXISTS index_season_passes_organization_id;
+DROP TABLE IF EXISTS season_passes;
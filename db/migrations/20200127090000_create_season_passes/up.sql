@@ -0,0 +1,58 @@
+CREATE TABLE season_passes
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    name            TEXT      NOT NULL,
+    price_in_cents  BIGINT    NOT NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_season_passes_organization_id ON season_passes (organization_id);
+
+CREATE TABLE season_pass_events
+(
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    season_pass_id UUID      NOT NULL REFERENCES season_passes (id) ON DELETE CASCADE,
+    event_id       UUID      NOT NULL REFERENCES events (id),
+    created_at     TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_season_pass_events_season_pass_id_event_id ON season_pass_events (season_pass_id, event_id);
+CREATE INDEX index_season_pass_events_event_id ON season_pass_events (event_id);
+
+ALTER TABLE order_items
+    ADD season_pass_id UUID NULL REFERENCES season_passes (id);
+
+CREATE INDEX index_order_items_season_pass_id ON order_items (season_pass_id);
+
+CREATE TABLE season_pass_entitlements
+(
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    season_pass_id UUID      NOT NULL REFERENCES season_passes (id),
+    order_item_id  UUID      NOT NULL REFERENCES order_items (id),
+    user_id        UUID      NOT NULL REFERENCES users (id),
+    redeem_key     TEXT      NOT NULL,
+    status         TEXT      NOT NULL DEFAULT 'Active',
+    created_at     TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_season_pass_entitlements_user_id ON season_pass_entitlements (user_id);
+CREATE INDEX index_season_pass_entitlements_order_item_id ON season_pass_entitlements (order_item_id);
+
+CREATE TABLE season_pass_redemptions
+(
+    id                          UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    season_pass_entitlement_id  UUID      NOT NULL REFERENCES season_pass_entitlements (id),
+    event_id                    UUID      NOT NULL REFERENCES events (id),
+    redeemed_by_user_id         UUID      NOT NULL REFERENCES users (id),
+    check_in_source             TEXT      NOT NULL,
+    created_at                  TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at                  TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- An entitlement grants a single entry per event
+CREATE UNIQUE INDEX index_season_pass_redemptions_entitlement_id_event_id ON season_pass_redemptions (season_pass_entitlement_id, event_id);
+CREATE INDEX index_season_pass_redemptions_event_id ON season_pass_redemptions (event_id);
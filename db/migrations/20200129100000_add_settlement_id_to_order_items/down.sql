@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_refund_items_settlement_id;
+DROP INDEX IF EXISTS index_order_items_settlement_id;
+
+ALTER TABLE refund_items
+    DROP settlement_id;
+ALTER TABLE order_items
+    DROP settlement_id;
@@ -0,0 +1,20 @@
+-- Orders can span events from several organizations, each settled separately
+ALTER TABLE order_items
+    ADD settlement_id UUID NULL REFERENCES settlements (id);
+ALTER TABLE refund_items
+    ADD settlement_id UUID NULL REFERENCES settlements (id);
+
+CREATE INDEX index_order_items_settlement_id ON order_items (settlement_id);
+CREATE INDEX index_refund_items_settlement_id ON refund_items (settlement_id);
+
+UPDATE order_items
+SET settlement_id = o.settlement_id
+FROM orders o
+WHERE order_items.order_id = o.id
+AND o.settlement_id IS NOT NULL;
+
+UPDATE refund_items
+SET settlement_id = r.settlement_id
+FROM refunds r
+WHERE refund_items.refund_id = r.id
+AND r.settlement_id IS NOT NULL;
@@ -0,0 +1,17 @@
+CREATE TABLE ticket_pricing_rules
+(
+    id                     UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_type_id         UUID      NOT NULL REFERENCES ticket_types (id),
+    name                   TEXT      NOT NULL,
+    rule_type              TEXT      NOT NULL,
+    threshold              BIGINT    NOT NULL CHECK (threshold >= 0),
+    price_in_cents         BIGINT    NULL,
+    price_increase_percent INT       NULL,
+    ticket_pricing_id      UUID      NULL REFERENCES ticket_pricing (id),
+    triggered_at           TIMESTAMP NULL,
+    created_at             TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at             TIMESTAMP NOT NULL DEFAULT now(),
+    CHECK ((price_in_cents IS NULL) <> (price_increase_percent IS NULL))
+);
+
+CREATE INDEX index_ticket_pricing_rules_ticket_type_id ON ticket_pricing_rules (ticket_type_id);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_settlement_adjustments_order_id;
+
+ALTER TABLE settlement_adjustments
+    DROP order_id;
+ALTER TABLE orders
+    DROP disputed_at;
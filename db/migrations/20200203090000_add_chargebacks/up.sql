@@ -0,0 +1,9 @@
+-- Orders are flagged when the customer disputes the payment with their bank
+ALTER TABLE orders
+    ADD disputed_at TIMESTAMP NULL;
+
+-- Chargebacks are linked to the disputed order
+ALTER TABLE settlement_adjustments
+    ADD order_id UUID NULL REFERENCES orders (id);
+
+CREATE INDEX index_settlement_adjustments_order_id ON settlement_adjustments (order_id);
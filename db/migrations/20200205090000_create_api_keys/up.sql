@@ -0,0 +1,22 @@
+-- Organization owned credentials for server-to-server integrations, only the key hash is stored
+CREATE TABLE api_keys
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    user_id         UUID      NOT NULL REFERENCES users (id),
+    name            TEXT      NOT NULL,
+    key_prefix      TEXT      NOT NULL,
+    key_hash        TEXT      NOT NULL,
+    scopes          TEXT[]    NOT NULL,
+    expires_at      TIMESTAMP NULL,
+    last_used_at    TIMESTAMP NULL,
+    last_used_ip    TEXT      NULL,
+    rotated_at      TIMESTAMP NULL,
+    revoked_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_api_keys_organization_id ON api_keys (organization_id);
+CREATE INDEX index_api_keys_user_id ON api_keys (user_id);
+CREATE UNIQUE INDEX index_api_keys_key_hash ON api_keys (key_hash);
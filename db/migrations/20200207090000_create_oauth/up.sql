@@ -0,0 +1,51 @@
+-- Third-party applications registered by an organization to act on behalf of users
+CREATE TABLE oauth_clients
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id    UUID      NOT NULL REFERENCES organizations (id),
+    name               TEXT      NOT NULL,
+    client_id          TEXT      NOT NULL,
+    client_secret_hash TEXT      NULL,
+    redirect_uris      TEXT[]    NOT NULL,
+    scopes             TEXT[]    NOT NULL,
+    created_by_user_id UUID      NOT NULL REFERENCES users (id),
+    revoked_at         TIMESTAMP NULL,
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_oauth_clients_client_id ON oauth_clients (client_id);
+CREATE INDEX index_oauth_clients_organization_id ON oauth_clients (organization_id);
+
+-- Scopes a user has granted to a client, revoking the consent invalidates all tokens issued under it
+CREATE TABLE oauth_consents
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    oauth_client_id UUID      NOT NULL REFERENCES oauth_clients (id),
+    user_id         UUID      NOT NULL REFERENCES users (id),
+    scopes          TEXT[]    NOT NULL,
+    revoked_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_oauth_consents_oauth_client_id_user_id ON oauth_consents (oauth_client_id, user_id);
+CREATE INDEX index_oauth_consents_user_id ON oauth_consents (user_id);
+
+-- Single use authorization codes, exchanged for tokens with the PKCE code verifier
+CREATE TABLE oauth_authorization_codes
+(
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    oauth_consent_id UUID      NOT NULL REFERENCES oauth_consents (id),
+    code_hash        TEXT      NOT NULL,
+    redirect_uri     TEXT      NOT NULL,
+    scopes           TEXT[]    NOT NULL,
+    code_challenge   TEXT      NOT NULL,
+    expires_at       TIMESTAMP NOT NULL,
+    used_at          TIMESTAMP NULL,
+    created_at       TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at       TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_oauth_authorization_codes_code_hash ON oauth_authorization_codes (code_hash);
+CREATE INDEX index_oauth_authorization_codes_oauth_consent_id ON oauth_authorization_codes (oauth_consent_id);
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS two_factor_challenges;
+DROP TABLE IF EXISTS two_factor_recovery_codes;
+DROP TABLE IF EXISTS two_factor_credentials;
+
+ALTER TABLE organizations
+    DROP two_factor_required_roles;
@@ -0,0 +1,45 @@
+ALTER TABLE organizations
+    ADD two_factor_required_roles TEXT[] NOT NULL DEFAULT '{}';
+
+-- TOTP secret for a user's authenticator app, the secret is encrypted when an encryption key is configured
+CREATE TABLE two_factor_credentials
+(
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id             UUID      NOT NULL REFERENCES users (id),
+    secret              TEXT      NOT NULL,
+    enabled_at          TIMESTAMP NULL,
+    last_used_time_step BIGINT    NULL,
+    created_at          TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_two_factor_credentials_user_id ON two_factor_credentials (user_id);
+
+-- Single use codes for signing in when the authenticator app is unavailable, only the hash is stored
+CREATE TABLE two_factor_recovery_codes
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id    UUID      NOT NULL REFERENCES users (id),
+    code_hash  TEXT      NOT NULL,
+    used_at    TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_two_factor_recovery_codes_user_id ON two_factor_recovery_codes (user_id);
+
+-- Second login step issued once the password has been verified
+CREATE TABLE two_factor_challenges
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id         UUID      NOT NULL REFERENCES users (id),
+    token_hash      TEXT      NOT NULL,
+    failed_attempts INT       NOT NULL DEFAULT 0,
+    expires_at      TIMESTAMP NOT NULL,
+    completed_at    TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_two_factor_challenges_user_id ON two_factor_challenges (user_id);
+CREATE UNIQUE INDEX index_two_factor_challenges_token_hash ON two_factor_challenges (token_hash);
@@ -0,0 +1,14 @@
+-- Server side record of a signed in device, refresh tokens reference the session so it can be revoked
+CREATE TABLE user_sessions
+(
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id      UUID      NOT NULL REFERENCES users (id),
+    ip_address   TEXT      NULL,
+    user_agent   TEXT      NULL,
+    last_used_at TIMESTAMP NOT NULL DEFAULT now(),
+    revoked_at   TIMESTAMP NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at   TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_user_sessions_user_id ON user_sessions (user_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS saved_report_subscribers;
+DROP TABLE IF EXISTS saved_reports;
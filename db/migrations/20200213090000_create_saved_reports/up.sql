@@ -0,0 +1,31 @@
+-- Report definitions saved by an organization, optionally run on a cron schedule and emailed to subscribers
+CREATE TABLE saved_reports
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id    UUID      NOT NULL REFERENCES organizations (id),
+    name               TEXT      NOT NULL,
+    report_type        TEXT      NOT NULL,
+    filters            JSONB     NOT NULL DEFAULT '{}',
+    columns            TEXT[]    NOT NULL DEFAULT '{}',
+    format             TEXT      NOT NULL DEFAULT 'Csv',
+    schedule           TEXT      NULL,
+    next_run_at        TIMESTAMP NULL,
+    last_run_at        TIMESTAMP NULL,
+    created_by_user_id UUID      NOT NULL REFERENCES users (id),
+    deleted_at         TIMESTAMP NULL,
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_saved_reports_organization_id ON saved_reports (organization_id);
+
+CREATE TABLE saved_report_subscribers
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    saved_report_id UUID      NOT NULL REFERENCES saved_reports (id),
+    email           TEXT      NOT NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_saved_report_subscribers_saved_report_id_email ON saved_report_subscribers (saved_report_id, email);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_events_event_series_id_event_series_index;
+
+ALTER TABLE events
+    DROP COLUMN event_series_index,
+    DROP COLUMN event_series_id;
+
+DROP TABLE IF EXISTS event_series;
@@ -0,0 +1,21 @@
+-- Recurrence definitions, each occurrence is a copy of the template event with its dates shifted
+CREATE TABLE event_series
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id    UUID      NOT NULL REFERENCES organizations (id),
+    template_event_id  UUID      NOT NULL REFERENCES events (id),
+    frequency          TEXT      NOT NULL,
+    frequency_interval INTEGER   NOT NULL DEFAULT 1,
+    occurrences        INTEGER   NOT NULL,
+    created_by_user_id UUID      NOT NULL REFERENCES users (id),
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_series_organization_id ON event_series (organization_id);
+
+ALTER TABLE events
+    ADD event_series_id UUID NULL REFERENCES event_series (id),
+    ADD event_series_index INTEGER NULL;
+
+CREATE UNIQUE INDEX index_events_event_series_id_event_series_index ON events (event_series_id, event_series_index);
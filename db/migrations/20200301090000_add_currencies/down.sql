@@ -0,0 +1,26 @@
+ALTER TABLE settlements
+    DROP COLUMN currency;
+
+ALTER TABLE refunds
+    DROP COLUMN currency;
+
+ALTER TABLE payments
+    DROP COLUMN currency;
+
+ALTER TABLE order_items
+    DROP COLUMN currency;
+
+ALTER TABLE orders
+    DROP COLUMN currency;
+
+ALTER TABLE ticket_pricing
+    DROP COLUMN currency;
+
+ALTER TABLE fee_schedules
+    DROP COLUMN currency;
+
+ALTER TABLE events
+    DROP COLUMN currency;
+
+ALTER TABLE organizations
+    DROP COLUMN currency;
@@ -0,0 +1,79 @@
+-- Amounts in cents are in the currency of the row they belong to, inherited from the organization down to the event,
+-- its pricing and the orders placed for it
+ALTER TABLE organizations
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE events
+    ADD currency TEXT NULL;
+UPDATE events e
+SET currency = o.currency
+FROM organizations o
+WHERE o.id = e.organization_id;
+ALTER TABLE events
+    ALTER COLUMN currency SET NOT NULL;
+
+-- Fee schedules are created before being attached to an organization, which then sets the currency
+ALTER TABLE fee_schedules
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+UPDATE fee_schedules fs
+SET currency = o.currency
+FROM organizations o
+WHERE o.id = fs.organization_id;
+
+ALTER TABLE ticket_pricing
+    ADD currency TEXT NULL;
+UPDATE ticket_pricing tp
+SET currency = e.currency
+FROM ticket_types tt
+         JOIN events e ON e.id = tt.event_id
+WHERE tt.id = tp.ticket_type_id;
+ALTER TABLE ticket_pricing
+    ALTER COLUMN currency SET NOT NULL;
+
+-- Set when the first item is added to the order, all other items must match it
+ALTER TABLE orders
+    ADD currency TEXT NULL;
+UPDATE orders o
+SET currency = (
+    SELECT e.currency
+    FROM order_items oi
+             JOIN events e ON e.id = oi.event_id
+    WHERE oi.order_id = o.id
+    LIMIT 1
+);
+
+ALTER TABLE order_items
+    ADD currency TEXT NULL;
+UPDATE order_items oi
+SET currency = COALESCE(o.currency, 'USD')
+FROM orders o
+WHERE o.id = oi.order_id;
+ALTER TABLE order_items
+    ALTER COLUMN currency SET NOT NULL;
+
+ALTER TABLE payments
+    ADD currency TEXT NULL;
+UPDATE payments p
+SET currency = COALESCE(o.currency, 'USD')
+FROM orders o
+WHERE o.id = p.order_id;
+ALTER TABLE payments
+    ALTER COLUMN currency SET NOT NULL;
+
+ALTER TABLE refunds
+    ADD currency TEXT NULL;
+UPDATE refunds r
+SET currency = COALESCE(o.currency, 'USD')
+FROM orders o
+WHERE o.id = r.order_id;
+ALTER TABLE refunds
+    ALTER COLUMN currency SET NOT NULL;
+
+ALTER TABLE settlements
+    ADD currency TEXT NULL;
+UPDATE settlements s
+SET currency = o.currency
+FROM organizations o
+WHERE o.id = s.organization_id;
+ALTER TABLE settlements
+    ALTER COLUMN currency SET NOT NULL;
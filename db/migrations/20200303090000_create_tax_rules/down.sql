@@ -0,0 +1,12 @@
+ALTER TABLE settlement_entries
+    DROP COLUMN tax_in_cents;
+
+DROP INDEX IF EXISTS index_order_items_tax_rule_id;
+
+ALTER TABLE order_items
+    DROP COLUMN included_tax_in_cents,
+    DROP COLUMN tax_rule_id;
+
+DROP INDEX IF EXISTS index_tax_rules_country_state;
+
+DROP TABLE IF EXISTS tax_rules;
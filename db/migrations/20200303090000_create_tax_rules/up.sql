@@ -0,0 +1,27 @@
+-- Sales tax / VAT charged on events held in a jurisdiction, a rule for the venue's state takes precedence over the
+-- country wide rule (state NULL). Inclusive rules are already part of the ticket and fee prices.
+CREATE TABLE tax_rules
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    name               TEXT      NOT NULL,
+    country            TEXT      NOT NULL,
+    state              TEXT      NULL,
+    ticket_tax_percent REAL      NOT NULL DEFAULT 0,
+    fee_tax_percent    REAL      NOT NULL DEFAULT 0,
+    inclusive          BOOLEAN   NOT NULL DEFAULT 'F',
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_tax_rules_country_state ON tax_rules (LOWER(country), LOWER(COALESCE(state, '')));
+
+-- Tax items are children of the item they are charged on, inclusive tax is recorded per unit in
+-- included_tax_in_cents and is not added to the order total
+ALTER TABLE order_items
+    ADD tax_rule_id UUID NULL REFERENCES tax_rules (id),
+    ADD included_tax_in_cents BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX index_order_items_tax_rule_id ON order_items (tax_rule_id);
+
+ALTER TABLE settlement_entries
+    ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_webhook_deliveries_domain_action_id;
+DROP INDEX IF EXISTS index_webhook_deliveries_domain_event_id;
+DROP INDEX IF EXISTS index_webhook_deliveries_domain_event_publisher_id;
+
+DROP TABLE IF EXISTS webhook_deliveries;
+
+ALTER TABLE domain_event_publishers
+    DROP COLUMN signing_secret;
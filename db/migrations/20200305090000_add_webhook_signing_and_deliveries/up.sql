@@ -0,0 +1,24 @@
+-- Secret used to sign outbound webhooks so receivers can authenticate them
+ALTER TABLE domain_event_publishers
+    ADD signing_secret TEXT NOT NULL DEFAULT encode(gen_random_bytes(32), 'hex');
+
+-- One row per webhook delivery attempt
+CREATE TABLE webhook_deliveries
+(
+    id                        UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    domain_event_publisher_id UUID      NOT NULL REFERENCES domain_event_publishers (id),
+    domain_event_id           UUID      NULL REFERENCES domain_events (id),
+    domain_action_id          UUID      NULL REFERENCES domain_actions (id),
+    webhook_url               TEXT      NOT NULL,
+    attempt                   BIGINT    NOT NULL,
+    status_code               INTEGER   NULL,
+    response_body             TEXT      NULL,
+    error                     TEXT      NULL,
+    succeeded                 BOOLEAN   NOT NULL,
+    created_at                TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at                TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_webhook_deliveries_domain_event_publisher_id ON webhook_deliveries (domain_event_publisher_id);
+CREATE INDEX index_webhook_deliveries_domain_event_id ON webhook_deliveries (domain_event_id);
+CREATE INDEX index_webhook_deliveries_domain_action_id ON webhook_deliveries (domain_action_id);
@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS domain_actions_notify_insert ON domain_actions;
+DROP FUNCTION IF EXISTS notify_domain_actions_inserted();
+DROP TRIGGER IF EXISTS domain_events_notify_insert ON domain_events;
+DROP FUNCTION IF EXISTS notify_domain_events_inserted();
@@ -0,0 +1,23 @@
+-- Statement level triggers so that bulk inserts raise a single notification, identical
+-- notifications raised within a transaction are also collapsed into one by Postgres
+CREATE OR REPLACE FUNCTION notify_domain_events_inserted() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('domain_events', '');
+    RETURN NULL;
+END $$ LANGUAGE 'plpgsql';
+
+CREATE TRIGGER domain_events_notify_insert
+    AFTER INSERT ON domain_events
+    FOR EACH STATEMENT
+    EXECUTE PROCEDURE notify_domain_events_inserted();
+
+CREATE OR REPLACE FUNCTION notify_domain_actions_inserted() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('domain_actions', '');
+    RETURN NULL;
+END $$ LANGUAGE 'plpgsql';
+
+CREATE TRIGGER domain_actions_notify_insert
+    AFTER INSERT ON domain_actions
+    FOR EACH STATEMENT
+    EXECUTE PROCEDURE notify_domain_actions_inserted();
@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS domain_actions_notify_requeue ON domain_actions;
+DROP INDEX IF EXISTS index_domain_actions_status_priority_scheduled_at;
+ALTER TABLE domain_actions
+    DROP priority;
@@ -0,0 +1,21 @@
+ALTER TABLE domain_actions
+    ADD priority INTEGER NOT NULL DEFAULT 0;
+
+-- Matches DomainActionTypes::default_priority for actions that have not yet been processed
+UPDATE domain_actions
+SET priority = CASE
+    WHEN domain_action_type IN ('HostedCheckoutIPN', 'PaymentProviderIPN', 'SendPurchaseCompletedCommunication', 'StripeWebhook') THEN 100
+    WHEN domain_action_type IN ('Communication', 'ExpireWaitlistOffer', 'SendWaitlistOfferCommunication') THEN 50
+    WHEN domain_action_type IN ('ProcessTransferDrip', 'SendWebhook') THEN 0
+    ELSE -50
+END
+WHERE status = 'Pending';
+
+CREATE INDEX index_domain_actions_status_priority_scheduled_at ON domain_actions (status, priority DESC, scheduled_at);
+
+-- Requeued actions are updated rather than inserted, notify for those as well
+CREATE TRIGGER domain_actions_notify_requeue
+    AFTER UPDATE OF status ON domain_actions
+    FOR EACH ROW
+    WHEN (NEW.status = 'Pending' AND OLD.status <> 'Pending')
+    EXECUTE PROCEDURE notify_domain_actions_inserted();
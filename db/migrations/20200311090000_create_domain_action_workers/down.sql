@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_domain_actions_locked_by_worker_id;
+ALTER TABLE domain_actions
+    DROP locked_by_worker_id;
+DROP TABLE IF EXISTS domain_action_workers;
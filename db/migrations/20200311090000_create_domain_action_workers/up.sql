@@ -0,0 +1,18 @@
+CREATE TABLE domain_action_workers
+(
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  name TEXT NOT NULL,
+  worker_type TEXT NOT NULL,
+  is_leader BOOLEAN NOT NULL DEFAULT FALSE,
+  last_heartbeat_at TIMESTAMP NOT NULL DEFAULT now(),
+  stopped_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_domain_action_workers_last_heartbeat_at ON domain_action_workers (last_heartbeat_at);
+
+ALTER TABLE domain_actions
+    ADD locked_by_worker_id UUID NULL REFERENCES domain_action_workers (id) ON DELETE SET NULL;
+
+CREATE INDEX index_domain_actions_locked_by_worker_id ON domain_actions (locked_by_worker_id);
@@ -0,0 +1,13 @@
+-- Offline redemption bundles and ticket payloads are signed with a key pair per event so a key given to
+-- scanners for one event cannot verify data signed for any other event of the organization
+CREATE TABLE event_redemption_keys
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id   UUID      NOT NULL REFERENCES events (id),
+    secret_key TEXT      NOT NULL,
+    public_key TEXT      NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_redemption_keys_event_id ON event_redemption_keys (event_id);
@@ -0,0 +1,18 @@
+-- Adjustments for organizations without a pending settlement, they are added to the organization's next
+-- settlement when it is created
+CREATE TABLE pending_settlement_adjustments
+(
+    id                         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id            UUID      NOT NULL REFERENCES organizations (id),
+    order_id                   UUID      NULL REFERENCES orders (id),
+    amount_in_cents            BIGINT    NOT NULL,
+    note                       TEXT      NULL,
+    settlement_adjustment_type TEXT      NOT NULL,
+    currency                   TEXT      NOT NULL,
+    settlement_adjustment_id   UUID      NULL REFERENCES settlement_adjustments (id) ON DELETE SET NULL,
+    created_at                 TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at                 TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_pending_settlement_adjustments_organization_id ON pending_settlement_adjustments (organization_id);
+CREATE INDEX index_pending_settlement_adjustments_order_id ON pending_settlement_adjustments (order_id);
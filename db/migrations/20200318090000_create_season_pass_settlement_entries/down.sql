@@ -0,0 +1,2 @@
+DROP FUNCTION IF EXISTS process_settlement_for_season_passes(settlement_id UUID, start TIMESTAMP, "end" TIMESTAMP);
+DROP TABLE IF EXISTS season_pass_settlement_entries;
@@ -0,0 +1,17 @@
+-- Season passes are not sold for a single event so their sales are settled separately from event entries
+CREATE TABLE season_pass_settlement_entries
+(
+    id                           UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    settlement_id                UUID      NOT NULL REFERENCES settlements (id) ON DELETE CASCADE,
+    season_pass_id               UUID      NOT NULL REFERENCES season_passes (id),
+    face_value_in_cents          BIGINT    NOT NULL,
+    revenue_share_value_in_cents BIGINT    NOT NULL,
+    online_sold_quantity         BIGINT    NOT NULL,
+    fee_sold_quantity            BIGINT    NOT NULL,
+    total_sales_in_cents         BIGINT    NOT NULL,
+    created_at                   TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at                   TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_season_pass_settlement_entries_settlement_id ON season_pass_settlement_entries (settlement_id);
+CREATE INDEX index_season_pass_settlement_entries_season_pass_id ON season_pass_settlement_entries (season_pass_id);
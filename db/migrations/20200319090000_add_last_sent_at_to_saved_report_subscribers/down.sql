@@ -0,0 +1,2 @@
+ALTER TABLE saved_report_subscribers
+    DROP last_sent_at;
@@ -0,0 +1,3 @@
+-- When the subscriber was last sent the saved report, runs that are retried skip subscribers that already received them
+ALTER TABLE saved_report_subscribers
+    ADD last_sent_at TIMESTAMP NULL;
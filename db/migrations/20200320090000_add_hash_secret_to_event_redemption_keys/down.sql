@@ -0,0 +1,2 @@
+ALTER TABLE event_redemption_keys
+    DROP hash_secret;
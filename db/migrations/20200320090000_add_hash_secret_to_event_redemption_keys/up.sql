@@ -0,0 +1,4 @@
+-- Redeem keys in offline redemption bundles are hashed with a secret per event which scanners fetch with the public
+-- key, so the short redeem keys cannot be recovered from a bundle by trying every key
+ALTER TABLE event_redemption_keys
+    ADD hash_secret TEXT NOT NULL DEFAULT encode(gen_random_bytes(32), 'hex');
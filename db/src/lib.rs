@@ -16,6 +16,8 @@ extern crate diesel_migrations;
 
 extern crate argon2rs;
 extern crate backtrace;
+extern crate base32;
+extern crate base64;
 extern crate bigneon_http;
 extern crate chrono;
 extern crate chrono_tz;
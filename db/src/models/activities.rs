@@ -784,6 +784,11 @@ impl ActivityItem {
             pub company_fee_in_cents: i64,
             pub client_fee_in_cents: i64,
             pub refunded_quantity: i64,
+            pub season_pass_id: Option<Uuid>,
+            pub settlement_id: Option<Uuid>,
+            pub currency: String,
+            pub tax_rule_id: Option<Uuid>,
+            pub included_tax_in_cents: i64,
         };
 
         let refund_ids: Vec<Uuid> = refund_data.iter().map(|r| r.refund_id).collect();
@@ -813,6 +818,11 @@ impl ActivityItem {
                 order_items::company_fee_in_cents,
                 order_items::client_fee_in_cents,
                 order_items::refunded_quantity,
+                order_items::season_pass_id,
+                order_items::settlement_id,
+                order_items::currency,
+                order_items::tax_rule_id,
+                order_items::included_tax_in_cents,
             ))
             .order_by(refunds::id)
             .load(conn)
@@ -848,6 +858,11 @@ impl ActivityItem {
                     company_fee_in_cents: item.company_fee_in_cents,
                     client_fee_in_cents: item.client_fee_in_cents,
                     refunded_quantity: item.refunded_quantity,
+                    season_pass_id: item.season_pass_id,
+                    settlement_id: item.settlement_id,
+                    currency: item.currency.clone(),
+                    tax_rule_id: item.tax_rule_id,
+                    included_tax_in_cents: item.included_tax_in_cents,
                 };
                 refund_items.push(RefundActivityItem {
                     id: item.id,
@@ -0,0 +1,240 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::api_keys;
+use std::str::FromStr;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validators::{self, *};
+
+const KEY_PREFIX_LENGTH: usize = 8;
+const KEY_SECRET_LENGTH: usize = 40;
+
+/// Organization owned credential used by server-to-server integrations in place of a user token.
+/// Only a hash of the key is stored, the plain text key is returned once on creation and rotation.
+/// Requests made with the key act on behalf of `user_id` but are limited to `scopes` within the organization.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "api_keys"]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub last_used_ip: Option<String>,
+    pub rotated_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "api_keys"]
+pub struct NewApiKey {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl NewApiKey {
+    /// Creates the key, returning it alongside the plain text key which cannot be retrieved again
+    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(ApiKey, String), DatabaseError> {
+        self.validate_record()?;
+        let (key_prefix, key) = ApiKey::generate_key();
+        let result: ApiKey = diesel::insert_into(api_keys::table)
+            .values((
+                &self,
+                api_keys::key_prefix.eq(&key_prefix),
+                api_keys::key_hash.eq(sha256::digest(&key)),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ApiKeyCreated,
+            format!("API key '{}' created", result.name),
+            Tables::ApiKeys,
+            Some(result.id),
+            current_user_id,
+            Some(json!({ "organization_id": result.organization_id, "scopes": result.scopes })),
+        )
+        .commit(conn)?;
+
+        Ok((result, key))
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if self.name.trim().is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "name",
+                Err(create_validation_error("required", "Name is required")),
+            );
+        }
+        if self.scopes.is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "scopes",
+                Err(create_validation_error("required", "At least one scope is required")),
+            );
+        } else if self.scopes.iter().any(|s| Scopes::from_str(s).is_err()) {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "scopes",
+                Err(create_validation_error("invalid_scope", "Scope is not recognized")),
+            );
+        }
+
+        Ok(validation_errors?)
+    }
+}
+
+impl ApiKey {
+    pub fn create(
+        organization_id: Uuid,
+        user_id: Uuid,
+        name: String,
+        scopes: Vec<Scopes>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> NewApiKey {
+        NewApiKey {
+            organization_id,
+            user_id,
+            name,
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            expires_at,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        api_keys::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading API key")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<ApiKey>, DatabaseError> {
+        api_keys::table
+            .filter(api_keys::organization_id.eq(organization_id))
+            .order_by(api_keys::created_at)
+            .then_order_by(api_keys::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load API keys for organization")
+    }
+
+    /// Finds the active key matching the plain text key, revoked and expired keys are not returned
+    pub fn find_active_by_key(key: &str, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        api_keys::table
+            .filter(api_keys::key_hash.eq(sha256::digest(key)))
+            .filter(api_keys::revoked_at.is_null())
+            .filter(
+                api_keys::expires_at
+                    .is_null()
+                    .or(api_keys::expires_at.gt(dsl::now.nullable())),
+            )
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading API key")
+    }
+
+    pub fn scopes(&self) -> Result<Vec<Scopes>, DatabaseError> {
+        let mut scopes = Vec::new();
+        for scope in &self.scopes {
+            scopes.push(Scopes::from_str(scope)?);
+        }
+        Ok(scopes)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map(|e| e > Utc::now().naive_utc()).unwrap_or(true)
+    }
+
+    /// Replaces the key with a newly generated one, the previous key stops working immediately
+    pub fn rotate(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(ApiKey, String), DatabaseError> {
+        if self.revoked_at.is_some() {
+            return DatabaseError::business_process_error("Revoked API keys cannot be rotated");
+        }
+
+        let (key_prefix, key) = ApiKey::generate_key();
+        let result: ApiKey = diesel::update(self)
+            .set((
+                api_keys::key_prefix.eq(&key_prefix),
+                api_keys::key_hash.eq(sha256::digest(&key)),
+                api_keys::rotated_at.eq(dsl::now.nullable()),
+                api_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not rotate API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ApiKeyRotated,
+            format!("API key '{}' rotated", result.name),
+            Tables::ApiKeys,
+            Some(result.id),
+            current_user_id,
+            Some(json!({ "previous_key_prefix": self.key_prefix, "key_prefix": result.key_prefix })),
+        )
+        .commit(conn)?;
+
+        Ok((result, key))
+    }
+
+    pub fn revoke(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        if self.revoked_at.is_some() {
+            return Ok(self.clone());
+        }
+
+        let result: ApiKey = diesel::update(self)
+            .set((
+                api_keys::revoked_at.eq(dsl::now.nullable()),
+                api_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ApiKeyRevoked,
+            format!("API key '{}' revoked", result.name),
+            Tables::ApiKeys,
+            Some(result.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Records when and from where the key was last used
+    pub fn mark_used(&self, ip_address: Option<String>, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        diesel::update(self)
+            .set((
+                api_keys::last_used_at.eq(dsl::now.nullable()),
+                api_keys::last_used_ip.eq(ip_address),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not record API key usage")
+    }
+
+    /// Generates a new key in the form `bn_<prefix>_<secret>`, the prefix is kept to identify the key
+    fn generate_key() -> (String, String) {
+        let key_prefix = random_alpha_string(KEY_PREFIX_LENGTH);
+        let key = format!("bn_{}_{}", key_prefix, random_alpha_string(KEY_SECRET_LENGTH));
+        (key_prefix, key)
+    }
+}
@@ -1,3 +1,4 @@
+use base64;
 use diesel::PgConnection;
 use itertools::Itertools;
 use models::*;
@@ -46,6 +47,25 @@ impl CommAddress {
         self.addresses.push(address.clone());
     }
 }
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CommAttachment {
+    pub filename: String,
+    pub content_type: String,
+    /// Base64 encoded file contents
+    pub content: String,
+}
+
+impl CommAttachment {
+    pub fn new(filename: String, content_type: String, content: &[u8]) -> CommAttachment {
+        CommAttachment {
+            filename,
+            content_type,
+            content: base64::encode(content),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Communication {
     pub comm_type: CommunicationType,
@@ -59,6 +79,7 @@ pub struct Communication {
     pub extra_data: Option<HashMap<String, Value>>,
     pub main_table: Option<Tables>,
     pub main_table_id: Option<Uuid>,
+    pub attachments: Option<Vec<CommAttachment>>,
 }
 
 impl Communication {
@@ -85,6 +106,7 @@ impl Communication {
             extra_data,
             main_table_id: None,
             main_table: None,
+            attachments: None,
         }
     }
 
@@ -0,0 +1,170 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::dsl::sql;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use models::*;
+use schema::{domain_action_workers, domain_actions};
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Workers that have not sent a heartbeat for this long are considered dead
+pub const WORKER_HEARTBEAT_TIMEOUT_SECONDS: i64 = 60;
+/// Session level advisory lock held by the single worker publishing domain events to actions
+const EVENT_PUBLISHER_LEADER_LOCK_ID: i64 = 4_180_512;
+/// Dead and stopped workers are kept for this long so their history can be inspected
+const WORKER_RETENTION_DAYS: i64 = 7;
+
+/// A thread of a `DomainActionMonitor`, processing either domain actions or domain events
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "domain_action_workers"]
+pub struct DomainActionWorker {
+    pub id: Uuid,
+    pub name: String,
+    pub worker_type: DomainActionWorkerTypes,
+    pub is_leader: bool,
+    pub last_heartbeat_at: NaiveDateTime,
+    pub stopped_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl DomainActionWorker {
+    pub fn create(name: String, worker_type: DomainActionWorkerTypes) -> NewDomainActionWorker {
+        NewDomainActionWorker { name, worker_type }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<DomainActionWorker, DatabaseError> {
+        domain_action_workers::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain action worker")
+    }
+
+    /// Running workers, including those that have recently stopped sending heartbeats
+    pub fn find_running(conn: &PgConnection) -> Result<Vec<DomainActionWorker>, DatabaseError> {
+        let since = Utc::now().naive_utc() - Duration::hours(1);
+        domain_action_workers::table
+            .filter(domain_action_workers::stopped_at.is_null())
+            .filter(domain_action_workers::last_heartbeat_at.gt(since))
+            .order_by(domain_action_workers::worker_type.asc())
+            .then_order_by(domain_action_workers::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain action workers")
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.stopped_at.is_none()
+            && self.last_heartbeat_at >= Utc::now().naive_utc() - Duration::seconds(WORKER_HEARTBEAT_TIMEOUT_SECONDS)
+    }
+
+    pub fn heartbeat(&self, is_leader: bool, conn: &PgConnection) -> Result<DomainActionWorker, DatabaseError> {
+        diesel::update(self)
+            .set((
+                domain_action_workers::is_leader.eq(is_leader),
+                domain_action_workers::last_heartbeat_at.eq(dsl::now),
+                domain_action_workers::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update domain action worker")
+    }
+
+    pub fn set_stopped(&self, conn: &PgConnection) -> Result<DomainActionWorker, DatabaseError> {
+        diesel::update(self)
+            .set((
+                domain_action_workers::is_leader.eq(false),
+                domain_action_workers::stopped_at.eq(dsl::now.nullable()),
+                domain_action_workers::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update domain action worker")
+    }
+
+    /// Makes actions checked out to workers that stopped or died available to other workers
+    /// immediately, rather than once their checkout expires. Returns the number of released actions.
+    pub fn release_actions_from_dead_workers(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let dead_worker_ids: Vec<Uuid> = domain_action_workers::table
+            .filter(domain_action_workers::stopped_at.is_not_null().or(
+                domain_action_workers::last_heartbeat_at.lt(now - Duration::seconds(WORKER_HEARTBEAT_TIMEOUT_SECONDS)),
+            ))
+            .filter(domain_action_workers::last_heartbeat_at.gt(now - Duration::days(WORKER_RETENTION_DAYS)))
+            .select(domain_action_workers::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load dead domain action workers")?;
+
+        if dead_worker_ids.is_empty() {
+            return Ok(0);
+        }
+
+        diesel::update(
+            domain_actions::table
+                .filter(domain_actions::status.eq(DomainActionStatus::Pending))
+                .filter(domain_actions::blocked_until.gt(now))
+                .filter(domain_actions::locked_by_worker_id.eq_any(dead_worker_ids)),
+        )
+        .set((
+            domain_actions::blocked_until.eq(now),
+            domain_actions::locked_by_worker_id.eq(None::<Uuid>),
+            domain_actions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not release domain actions")
+    }
+
+    /// Removes workers that have not been seen within the retention period
+    pub fn delete_stale(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let since = Utc::now().naive_utc() - Duration::days(WORKER_RETENTION_DAYS);
+        diesel::delete(domain_action_workers::table.filter(domain_action_workers::last_heartbeat_at.lt(since)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete stale domain action workers")
+    }
+
+    /// Attempts to become the event publisher leader. The lock is held by the database session
+    /// so `conn` must be a dedicated connection kept open for as long as the worker is leader.
+    pub fn try_acquire_event_publisher_leadership(conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(sql::<Bool>(&format!(
+            "pg_try_advisory_lock({})",
+            EVENT_PUBLISHER_LEADER_LOCK_ID
+        )))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not acquire event publisher leadership")
+    }
+
+    pub fn holds_event_publisher_leadership(conn: &PgConnection) -> Result<bool, DatabaseError> {
+        // Advisory locks on a bigint are split over classid (high bits) and objid (low bits)
+        diesel::select(sql::<Bool>(&format!(
+            "EXISTS (SELECT 1 FROM pg_locks WHERE locktype = 'advisory' AND granted AND pid = pg_backend_pid() \
+             AND classid = 0 AND objid = {} AND objsubid = 1)",
+            EVENT_PUBLISHER_LEADER_LOCK_ID
+        )))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check event publisher leadership")
+    }
+
+    pub fn release_event_publisher_leadership(conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(sql::<Bool>(&format!(
+            "pg_advisory_unlock({})",
+            EVENT_PUBLISHER_LEADER_LOCK_ID
+        )))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not release event publisher leadership")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "domain_action_workers"]
+pub struct NewDomainActionWorker {
+    pub name: String,
+    pub worker_type: DomainActionWorkerTypes,
+}
+
+impl NewDomainActionWorker {
+    pub fn commit(self, conn: &PgConnection) -> Result<DomainActionWorker, DatabaseError> {
+        diesel::insert_into(domain_action_workers::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not insert domain action worker")
+    }
+}
@@ -3,13 +3,20 @@ use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
 use models::enums::*;
+use models::Payload;
 use schema::*;
 use serde_json;
+use std::cmp;
+use std::collections::HashMap;
 use utils::dates;
 use utils::errors::*;
+use utils::pagination::Paginate;
 use uuid::Uuid;
 
-#[derive(Clone, Debug, Serialize, PartialEq, Identifiable, Queryable, QueryableByName)]
+/// Channel notified by a database trigger whenever domain actions are inserted
+pub const DOMAIN_ACTIONS_NOTIFICATION_CHANNEL: &str = "domain_actions";
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Identifiable, Queryable, QueryableByName)]
 #[table_name = "domain_actions"]
 pub struct DomainAction {
     pub id: Uuid,
@@ -29,6 +36,8 @@ pub struct DomainAction {
     pub blocked_until: NaiveDateTime,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub priority: i32,
+    pub locked_by_worker_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Deserialize)]
@@ -63,6 +72,7 @@ impl DomainAction {
             max_attempt_count: 3,
             status: DomainActionStatus::Pending,
             blocked_until: dates::now().add_seconds(-30).finish(),
+            priority: domain_action_type.default_priority(),
         }
     }
 
@@ -79,6 +89,51 @@ impl DomainAction {
         Ok(result)
     }
 
+    /// Actions which will not be retried as they failed too many times
+    pub fn find_dead_letters(
+        domain_action_type: Option<DomainActionTypes>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<DomainAction>, DatabaseError> {
+        let mut query = domain_actions::table
+            .filter(domain_actions::status.eq(DomainActionStatus::RetriesExceeded))
+            .into_boxed();
+
+        if let Some(action_type) = domain_action_type {
+            query = query.filter(domain_actions::domain_action_type.eq(action_type));
+        }
+
+        let (actions, total) = query
+            .order_by(domain_actions::updated_at.desc())
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find dead letter domain actions")?;
+
+        let mut payload = Payload::from_data(actions, page, limit);
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    /// Number of actions of each type that are currently checked out to a process
+    pub fn busy_counts_by_type(conn: &PgConnection) -> Result<HashMap<DomainActionTypes, usize>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let busy_action_types: Vec<DomainActionTypes> = domain_actions::table
+            .filter(domain_actions::status.eq(DomainActionStatus::Pending))
+            .filter(domain_actions::blocked_until.gt(now))
+            .filter(domain_actions::expires_at.gt(now))
+            .select(domain_actions::domain_action_type)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load busy domain actions")?;
+
+        let mut counts = HashMap::new();
+        for action_type in busy_action_types {
+            *counts.entry(action_type).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
     pub fn find_by_resource(
         main_table: Option<Tables>,
         main_table_id: Option<Uuid>,
@@ -130,10 +185,21 @@ impl DomainAction {
         }
 
         query
+            .order_by(domain_actions::priority.desc())
+            .then_order_by(domain_actions::scheduled_at.asc())
             .load(conn)
             .to_db_error(ErrorCode::QueryError, "Error loading domain actions")
     }
 
+    pub fn is_dead_letter(&self) -> bool {
+        self.status == DomainActionStatus::RetriesExceeded
+    }
+
+    /// Matches the actions returned by `find_stuck`
+    pub fn is_stuck(&self) -> bool {
+        self.status == DomainActionStatus::Pending && self.blocked_until + Duration::minutes(1) < Utc::now().naive_utc()
+    }
+
     /// This method returns true if a pending/busy domain action
     /// exists for the given `domain_action_type`, `main_table` and `main_table_id`
     /// otherwise false.
@@ -165,6 +231,16 @@ impl DomainAction {
     }
 
     pub fn set_busy(&self, timeout: i64, conn: &PgConnection) -> Result<(), DatabaseError> {
+        self.set_busy_for_worker(timeout, None, conn)
+    }
+
+    /// As with `set_busy`, recording the worker so the action can be released if the worker dies
+    pub fn set_busy_for_worker(
+        &self,
+        timeout: i64,
+        worker_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         let timeout = Utc::now().naive_utc() + Duration::seconds(timeout);
         let db_blocked = DomainAction::find(self.id, conn)?;
         if db_blocked.blocked_until > Utc::now().naive_utc() {
@@ -174,6 +250,7 @@ impl DomainAction {
             .filter(domain_actions::blocked_until.le(dsl::now))
             .set((
                 domain_actions::blocked_until.eq(timeout),
+                domain_actions::locked_by_worker_id.eq(worker_id),
                 domain_actions::updated_at.eq(dsl::now),
             ))
             .get_result(conn)
@@ -211,6 +288,17 @@ impl DomainAction {
     /// action should not be retried, use `errored` instead. If the number of retries
     /// is exceeded, the status will changed to `RetriedExceeded`.
     pub fn set_failed(&self, reason: &str, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        self.set_failed_and_retry_at(reason, None, conn)
+    }
+
+    /// As with `set_failed` but the action is retried at `retry_at` rather than once the
+    /// current checkout expires.
+    pub fn set_failed_and_retry_at(
+        &self,
+        reason: &str,
+        retry_at: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<DomainAction, DatabaseError> {
         if self.max_attempt_count <= self.attempt_count + 1 {
             diesel::update(self)
                 .set((
@@ -222,6 +310,18 @@ impl DomainAction {
                 ))
                 .get_result(conn)
                 .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
+        } else if let Some(retry_at) = retry_at {
+            diesel::update(self)
+                .set((
+                    domain_actions::last_failure_reason.eq(reason),
+                    domain_actions::attempt_count.eq(self.attempt_count + 1),
+                    domain_actions::blocked_until.eq(retry_at),
+                    // Waiting to be retried rather than checked out to the worker
+                    domain_actions::locked_by_worker_id.eq(None::<Uuid>),
+                    domain_actions::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
         } else {
             // Intentionally leave checked out
             diesel::update(self)
@@ -250,6 +350,50 @@ impl DomainAction {
             .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
     }
 
+    /// Replaces the payload of an action that will not be retried, usually before requeueing it
+    pub fn update_payload(
+        &self,
+        payload: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<DomainAction, DatabaseError> {
+        if !self.is_dead_letter() && !self.is_stuck() {
+            return DatabaseError::business_process_error(
+                "Only dead letter or stuck domain actions can have their payload edited",
+            );
+        }
+
+        diesel::update(self)
+            .set((
+                domain_actions::payload.eq(payload),
+                domain_actions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
+    }
+
+    /// Makes a dead letter or stuck action pending again with its attempts reset. The action
+    /// is given the same amount of time to complete as it was originally.
+    pub fn requeue(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        if !self.is_dead_letter() && !self.is_stuck() {
+            return DatabaseError::business_process_error("Only dead letter or stuck domain actions can be requeued");
+        }
+
+        let now = Utc::now().naive_utc();
+        let window = cmp::max(self.expires_at - self.scheduled_at, Duration::minutes(15));
+        diesel::update(self)
+            .set((
+                domain_actions::status.eq(DomainActionStatus::Pending),
+                domain_actions::attempt_count.eq(0),
+                domain_actions::scheduled_at.eq(now),
+                domain_actions::expires_at.eq(now + window),
+                domain_actions::blocked_until.eq(now),
+                domain_actions::locked_by_worker_id.eq(None::<Uuid>),
+                domain_actions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
+    }
+
     pub fn update(
         &self,
         attributes: &DomainActionEditableAttributes,
@@ -278,6 +422,7 @@ pub struct NewDomainAction {
     pub max_attempt_count: i64,
     pub status: DomainActionStatus,
     pub blocked_until: NaiveDateTime,
+    pub priority: i32,
 }
 
 impl NewDomainAction {
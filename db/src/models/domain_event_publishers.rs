@@ -3,17 +3,24 @@ use diesel;
 use diesel::expression::dsl;
 use diesel::pg::expression::dsl::any;
 use diesel::prelude::*;
+use diesel::sql_types::Text;
 use itertools::Itertools;
 use models::*;
-use schema::{domain_event_published, domain_event_publishers};
+use schema::{domain_event_published, domain_event_publishers, domain_events};
 use serde_json::Value;
+use serde_with::rust::double_option;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use utils::dates::{self, IntoDateBuilder};
 use utils::errors::*;
+use utils::hash::hmac_sha256;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationErrors};
+use validators::{self, *};
 
-pub static SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTypes] = &[
+/// Domain event types with a tailored webhook payload, other event types are published with the
+/// generic payload built from the domain event itself
+pub static DOMAIN_EVENT_TYPES_WITH_DETAILED_PAYLOADS: &'static [DomainEventTypes] = &[
     DomainEventTypes::TransferTicketStarted,
     DomainEventTypes::TransferTicketCancelled,
     DomainEventTypes::TransferTicketCompleted,
@@ -25,7 +32,20 @@ pub static SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTyp
     DomainEventTypes::PushNotificationTokenCreated,
 ];
 
-#[derive(Clone, Debug, Serialize, Identifiable, Queryable, QueryableByName)]
+pub const WEBHOOK_SIGNATURE_HEADER: &'static str = "X-BigNeon-Signature";
+pub const WEBHOOK_TIMESTAMP_HEADER: &'static str = "X-BigNeon-Timestamp";
+/// Number of delivery attempts made for a webhook before it is given up on
+pub const WEBHOOK_MAX_ATTEMPTS: i64 = 8;
+const WEBHOOK_RETRY_BASE_SECONDS: i64 = 30;
+
+/// Payload of a `SendWebhook` domain action
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SendWebhookPayload {
+    pub body: Value,
+    pub attempt: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Identifiable, Queryable, QueryableByName)]
 #[table_name = "domain_event_publishers"]
 pub struct DomainEventPublisher {
     pub id: Uuid,
@@ -39,6 +59,8 @@ pub struct DomainEventPublisher {
     pub deleted_at: Option<NaiveDateTime>,
     pub adapter: Option<WebhookAdapters>,
     pub adapter_config: Option<Value>,
+    #[serde(default, skip_serializing)]
+    pub signing_secret: String,
 }
 
 impl Eq for DomainEventPublisher {}
@@ -55,15 +77,35 @@ impl Hash for DomainEventPublisher {
     }
 }
 
-#[derive(AsChangeset, Deserialize, Validate)]
+#[derive(AsChangeset, Default, Deserialize, Validate)]
 #[table_name = "domain_event_publishers"]
 pub struct DomainEventPublisherEditableAttributes {
     #[validate(url(message = "Webhook URL is invalid"))]
     pub webhook_url: Option<String>,
     pub import_historic_events: Option<bool>,
+    pub event_types: Option<Vec<DomainEventTypes>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub adapter: Option<Option<WebhookAdapters>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub adapter_config: Option<Option<Value>>,
 }
 
 impl DomainEventPublisher {
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DomainEventPublisher>, DatabaseError> {
+        domain_event_publishers::table
+            .filter(domain_event_publishers::organization_id.eq(organization_id))
+            .filter(domain_event_publishers::deleted_at.is_null())
+            .order_by(domain_event_publishers::created_at.asc())
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load Domain Event Publishers for organization",
+            )
+    }
+
     pub fn find_all(conn: &PgConnection) -> Result<Vec<DomainEventPublisher>, DatabaseError> {
         domain_event_publishers::table
             .filter(domain_event_publishers::deleted_at.is_null())
@@ -171,6 +213,8 @@ impl DomainEventPublisher {
                 // No filter for publisher
                 domain_event_publishers::organization_id
                     .is_null()
+                    // Domain event belongs to publisher organization
+                    .or(domain_events::organization_id.eq(domain_event_publishers::organization_id))
                     // Found user connected to publisher organization
                     .or(organization_interactions::id.is_not_null())
                     // Found temporary user connected to publisher organization
@@ -227,24 +271,94 @@ impl DomainEventPublisher {
         }
 
         for webhook_payload in domain_event.webhook_payloads(front_end_url, conn)? {
-            let mut comms = Communication::new(
-                CommunicationType::Webhook,
-                "Domain Event Webhook".to_string(),
-                Some(json!(webhook_payload).to_string()),
-                None,
-                CommAddress::from(self.webhook_url.clone()),
-                None,
-                None,
-                Some(vec!["webhooks"]),
-                None,
-            );
-            comms.main_table = Some(Tables::DomainEventPublishers);
-            comms.main_table_id = Some(self.id);
-            comms.queue(conn)?;
+            self.queue_webhook(Some(domain_event.id), json!(webhook_payload), 1, conn)?;
         }
 
         Ok(())
     }
+
+    /// Queues delivery of `body` to the publisher's webhook. Retries of a failed delivery are
+    /// queued with the next `attempt` and scheduled with an exponential backoff.
+    pub fn queue_webhook(
+        &self,
+        domain_event_id: Option<Uuid>,
+        body: Value,
+        attempt: i64,
+        conn: &PgConnection,
+    ) -> Result<DomainAction, DatabaseError> {
+        let mut action = DomainAction::create(
+            domain_event_id,
+            DomainActionTypes::SendWebhook,
+            None,
+            json!(SendWebhookPayload { body, attempt }),
+            Some(Tables::DomainEventPublishers),
+            Some(self.id),
+        );
+        action.expires_at = action.scheduled_at.into_builder().add_days(1).finish();
+        action.schedule_at(
+            dates::now()
+                .add_seconds(DomainEventPublisher::webhook_retry_delay(attempt))
+                .finish(),
+        );
+        action.commit(conn)
+    }
+
+    /// Seconds to wait before making the given delivery attempt, doubling after each failure
+    pub fn webhook_retry_delay(attempt: i64) -> i64 {
+        if attempt <= 1 {
+            return 0;
+        }
+        WEBHOOK_RETRY_BASE_SECONDS * 2i64.pow((attempt - 2).min(16) as u32)
+    }
+
+    /// Hex encoded HMAC-SHA256 of `{timestamp}.{body}` keyed with the publisher's signing secret,
+    /// sent in the `X-BigNeon-Signature` header alongside the `X-BigNeon-Timestamp` header
+    pub fn sign(&self, timestamp: i64, body: &str) -> String {
+        hmac_sha256::sign(&self.signing_secret, &format!("{}.{}", timestamp, body))
+    }
+
+    /// Publishes the domain events from `seq` onwards to this publisher again
+    pub fn replay_from_seq(&mut self, seq: i64, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(
+            domain_event_published::table
+                .filter(domain_event_published::domain_event_publisher_id.eq(self.id))
+                .filter(
+                    domain_event_published::domain_event_id.eq_any(
+                        domain_events::table
+                            .filter(domain_events::seq.ge(seq))
+                            .select(domain_events::id),
+                    ),
+                ),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove published domain events")?;
+
+        if self
+            .last_domain_event_seq
+            .map(|last_seq| last_seq >= seq)
+            .unwrap_or(false)
+        {
+            let publisher: DomainEventPublisher = diesel::update(domain_event_publishers::table.find(self.id))
+                .set((
+                    domain_event_publishers::last_domain_event_seq.eq(seq - 1),
+                    domain_event_publishers::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update domain event publisher")?;
+            self.last_domain_event_seq = publisher.last_domain_event_seq;
+        }
+
+        Ok(())
+    }
+
+    pub fn webhook_deliveries(
+        &self,
+        page: i64,
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Payload<WebhookDelivery>, DatabaseError> {
+        WebhookDelivery::find_by_domain_event_publisher_id(self.id, page, limit, conn)
+    }
     pub fn update_last_domain_event_seq(
         &mut self,
         last_domain_event_seq: i64,
@@ -293,6 +407,8 @@ impl DomainEventPublisher {
             event_types,
             webhook_url,
             import_historic_events,
+            adapter: None,
+            adapter_config: None,
         }
     }
 
@@ -311,11 +427,74 @@ impl DomainEventPublisher {
         attributes: &DomainEventPublisherEditableAttributes,
         conn: &PgConnection,
     ) -> Result<DomainEventPublisher, DatabaseError> {
+        attributes.validate()?;
+        validate_record(
+            attributes.event_types.as_ref().unwrap_or(&self.event_types),
+            attributes.adapter.unwrap_or(self.adapter),
+            attributes
+                .adapter_config
+                .as_ref()
+                .unwrap_or(&self.adapter_config)
+                .as_ref(),
+        )?;
         diesel::update(self)
             .set((attributes, domain_event_publishers::updated_at.eq(dsl::now)))
             .get_result(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not update domain event publisher")
     }
+
+    /// Replaces the signing secret, webhooks signed with the previous secret will no longer verify
+    pub fn rotate_signing_secret(&self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        diesel::update(self)
+            .set((
+                domain_event_publishers::signing_secret.eq(dsl::sql::<Text>("encode(gen_random_bytes(32), 'hex')")),
+                domain_event_publishers::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not rotate domain event publisher signing secret",
+            )
+    }
+}
+
+fn validate_record(
+    event_types: &[DomainEventTypes],
+    adapter: Option<WebhookAdapters>,
+    adapter_config: Option<&Value>,
+) -> Result<(), DatabaseError> {
+    let mut validation_errors: Result<(), ValidationErrors> = Ok(());
+    if event_types.is_empty() {
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "event_types",
+            Err(create_validation_error(
+                "required",
+                "At least one event type is required",
+            )),
+        );
+    }
+
+    match adapter {
+        Some(WebhookAdapters::CustomerIo) => {
+            let has_credentials = adapter_config
+                .map(|config| config["site_id"].is_string() && config["api_key"].is_string())
+                .unwrap_or(false);
+            if !has_credentials {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "adapter_config",
+                    Err(create_validation_error(
+                        "invalid_adapter_config",
+                        "Customer.io adapter requires a site_id and api_key",
+                    )),
+                );
+            }
+        }
+        None => (),
+    }
+
+    Ok(validation_errors?)
 }
 
 #[derive(Clone, Deserialize, Insertable, Validate)]
@@ -326,10 +505,14 @@ pub struct NewDomainEventPublisher {
     #[validate(url(message = "Webhook URL is invalid"))]
     pub webhook_url: String,
     pub import_historic_events: bool,
+    pub adapter: Option<WebhookAdapters>,
+    pub adapter_config: Option<Value>,
 }
 
 impl NewDomainEventPublisher {
     pub fn commit(self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        self.validate()?;
+        validate_record(&self.event_types, self.adapter, self.adapter_config.as_ref())?;
         diesel::insert_into(domain_event_publishers::table)
             .values(self)
             .get_result(conn)
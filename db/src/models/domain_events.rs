@@ -8,8 +8,12 @@ use serde_json;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use utils::errors::*;
+use utils::text;
 use uuid::Uuid;
 
+/// Channel notified by a database trigger whenever domain events are inserted
+pub const DOMAIN_EVENTS_NOTIFICATION_CHANNEL: &str = "domain_events";
+
 #[derive(Clone, Debug, PartialEq, Identifiable, Queryable, Serialize, Deserialize)]
 pub struct DomainEvent {
     pub id: Uuid,
@@ -56,9 +60,39 @@ impl DomainEvent {
             main_id,
             user_id,
             created_at: None,
+            organization_id: None,
         }
     }
 
+    /// Organization owning the main record, events are only published to that organization's webhooks
+    fn find_organization_id(
+        main_table: Tables,
+        main_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Option<Uuid>, DatabaseError> {
+        let main_id = match main_id {
+            Some(main_id) => main_id,
+            None => return Ok(None),
+        };
+
+        Ok(match main_table {
+            Tables::Organizations => Some(main_id),
+            Tables::Events => Event::find(main_id, conn).optional()?.map(|e| e.organization_id),
+            Tables::Orders => match Order::find(main_id, conn).optional()? {
+                Some(order) => {
+                    let organizations = order.organizations(conn)?;
+                    if organizations.len() == 1 {
+                        Some(organizations[0].id)
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            },
+            _ => None,
+        })
+    }
+
     pub fn find_after_seq(after_seq: i64, limit: u32, conn: &PgConnection) -> Result<Vec<DomainEvent>, DatabaseError> {
         domain_events::table
             .filter(domain_events::seq.gt(after_seq))
@@ -75,6 +109,10 @@ impl DomainEvent {
         front_end_url: &str,
         conn: &PgConnection,
     ) -> Result<Vec<HashMap<String, serde_json::Value>>, DatabaseError> {
+        if !DOMAIN_EVENT_TYPES_WITH_DETAILED_PAYLOADS.contains(&self.event_type) {
+            return Ok(vec![self.generic_webhook_payload()]);
+        }
+
         let mut result: Vec<HashMap<String, serde_json::Value>> = Vec::new();
         let main_id = self.main_id.ok_or_else(|| {
             DatabaseError::new(
@@ -207,6 +245,23 @@ impl DomainEvent {
         Ok(result)
     }
 
+    /// Payload published for event types without a tailored payload
+    fn generic_webhook_payload(&self) -> HashMap<String, serde_json::Value> {
+        let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+        data.insert(
+            "webhook_event_type".to_string(),
+            json!(text::to_snake_case(&self.event_type.to_string())),
+        );
+        data.insert("domain_event_id".to_string(), json!(self.id));
+        data.insert("display_text".to_string(), json!(self.display_text));
+        data.insert("main_table".to_string(), json!(self.main_table));
+        data.insert("main_id".to_string(), json!(self.main_id));
+        data.insert("organization_id".to_string(), json!(self.organization_id));
+        data.insert("event_data".to_string(), json!(self.event_data));
+        data.insert("timestamp".to_string(), json!(self.created_at.timestamp()));
+        data
+    }
+
     fn order_payload_data(
         conn: &PgConnection,
         data: &mut HashMap<String, Value>,
@@ -239,6 +294,8 @@ impl DomainEvent {
         let mut refunded_fees_total = 0;
         let mut discount_total = 0;
         let mut refunded_discount_total = 0;
+        let mut tax_total = 0;
+        let mut refunded_tax_total = 0;
         let mut j_items = Vec::<R>::new();
         for item in order.items(conn)? {
             let item_total = item.unit_price_in_cents * item.quantity;
@@ -253,11 +310,15 @@ impl DomainEvent {
             });
 
             match item.item_type {
-                OrderItemTypes::Tickets => {
+                OrderItemTypes::Tickets | OrderItemTypes::ResaleTickets => {
                     count = count + item.quantity - item.refunded_quantity;
                     sub_total = sub_total + item_total;
                     refunded_sub_total = refunded_sub_total + refunded_total;
                 }
+                OrderItemTypes::SeasonPasses => {
+                    sub_total = sub_total + item_total;
+                    refunded_sub_total = refunded_sub_total + refunded_total;
+                }
                 OrderItemTypes::Discount => {
                     discount_total = discount_total + item_total;
                     refunded_discount_total = refunded_discount_total + refunded_total;
@@ -266,6 +327,10 @@ impl DomainEvent {
                     fees_total = fees_total + item_total;
                     refunded_fees_total = refunded_fees_total + refunded_total;
                 }
+                OrderItemTypes::Tax => {
+                    tax_total = tax_total + item_total;
+                    refunded_tax_total = refunded_tax_total + refunded_total;
+                }
             }
         }
 
@@ -277,6 +342,8 @@ impl DomainEvent {
         data.insert("refunded_fees_total".to_string(), json!(refunded_fees_total));
         data.insert("discount_total".to_string(), json!(discount_total));
         data.insert("refunded_discount_total".to_string(), json!(refunded_discount_total));
+        data.insert("tax_total".to_string(), json!(tax_total));
+        data.insert("refunded_tax_total".to_string(), json!(refunded_tax_total));
 
         data.insert(
             "user_id".to_string(),
@@ -492,10 +559,14 @@ pub struct NewDomainEvent {
     pub main_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
     pub created_at: Option<NaiveDateTime>,
+    pub organization_id: Option<Uuid>,
 }
 
 impl NewDomainEvent {
-    pub fn commit(self, conn: &PgConnection) -> Result<DomainEvent, DatabaseError> {
+    pub fn commit(mut self, conn: &PgConnection) -> Result<DomainEvent, DatabaseError> {
+        if self.organization_id.is_none() {
+            self.organization_id = DomainEvent::find_organization_id(self.main_table, self.main_id, conn)?;
+        }
         let result: DomainEvent = diesel::insert_into(domain_events::table)
             .values(&self)
             .get_result(conn)
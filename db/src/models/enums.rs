@@ -90,6 +90,9 @@ string_enum! { CodeTypes [Access, Discount] }
 string_enum! { CommunicationChannelType [Email, Sms, Push, Webhook]}
 string_enum! { CommunicationType [EmailTemplate, Sms, Push, Webhook]}
 string_enum! { DomainEventTypes [
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    ApiKeyRotated,
     CodeCreated,
     CodeDeleted,
     CodeUpdated,
@@ -98,10 +101,12 @@ string_enum! { DomainEventTypes [
     EventCancelled,
     EventCreated,
     EventDeleted,
+    EventDuplicated,
     EventInterestCreated,
     EventPublished,
     EventReportSubscriberCreated,
     EventReportSubscriberDeleted,
+    EventSeriesCreated,
     EventUpdated,
     EventUnpublished,
     ExternalLoginCreated,
@@ -114,6 +119,7 @@ string_enum! { DomainEventTypes [
     OrderBehalfOfUserChanged,
     OrderCompleted,
     OrderCreated,
+    OrderDisputed,
     OrderRefund,
     OrderResendConfirmationTriggered,
     OrderStatusUpdated,
@@ -121,6 +127,11 @@ string_enum! { DomainEventTypes [
     OrganizationCreated,
     NoteCreated,
     NoteDeleted,
+    OAuthClientCreated,
+    OAuthClientRevoked,
+    OAuthClientSecretGenerated,
+    OAuthConsentGranted,
+    OAuthConsentRevoked,
     PaymentCancelled,
     PaymentCreated,
     PaymentCompleted,
@@ -132,10 +143,28 @@ string_enum! { DomainEventTypes [
     UserCreated,
     UserLogin,
     UserRegistration,
+    UserSessionCreated,
+    UserSessionRefreshed,
+    UserSessionRevoked,
     UserUpdated,
     LostPassword,
     PurchaseCompleted,
     PushNotificationTokenCreated,
+    ResaleListingCancelled,
+    ResaleListingCreated,
+    ResaleListingSold,
+    ResalePayoutPaid,
+    SavedReportCreated,
+    SavedReportDeleted,
+    SavedReportSent,
+    SavedReportSubscriberCreated,
+    SavedReportSubscriberDeleted,
+    SavedReportUpdated,
+    SeasonPassCreated,
+    SeasonPassEntitlementPurchased,
+    SeasonPassEntitlementRedeemed,
+    SeasonPassEntitlementRefunded,
+    SeasonPassUpdated,
     SettlementReportProcessed,
     TransferTicketDripSourceSent,
     TransferTicketDripDestinationSent,
@@ -149,27 +178,47 @@ string_enum! { DomainEventTypes [
     TicketInstancePurchased,
     TicketInstanceRedeemed,
     TicketInstanceReleasedFromHold,
+    TicketInstanceSeatAssigned,
     TicketInstanceUpdated,
     TicketPricingAdded,
     TicketPricingCreated,
     TicketPricingDeleted,
+    TicketPricingRuleCreated,
+    TicketPricingRuleDeleted,
     TicketPricingSalesStarted,
     TicketPricingUpdated,
     TicketTypeCreated,
     TicketTypeSalesStarted,
     TicketTypeSoldOut,
-    TicketTypeUpdated
+    TicketTypeUpdated,
+    TwoFactorDisabled,
+    TwoFactorEnabled,
+    TwoFactorRecoveryCodeUsed,
+    TwoFactorRecoveryCodesGenerated,
+    WaitlistEntryCancelled,
+    WaitlistEntryCreated,
+    WaitlistOfferCreated,
+    WaitlistOfferExpired,
+    WaitlistOfferPurchased
 ]}
 string_enum! { DomainActionTypes [
     BroadcastPushNotification,
     // Email/SMS/Push Communication
     Communication,
+    ExpireWaitlistOffer,
+    HostedCheckoutIPN,
+    OfferWaitlistTickets,
     PaymentProviderIPN,
     ProcessSettlementReport,
     ProcessTransferDrip,
     RegenerateDripActions,
     SendAutomaticReportEmails,
     SendPurchaseCompletedCommunication,
+    SendSavedReport,
+    SendWaitlistOfferCommunication,
+    SendWebhook,
+    // Stripe webhooks received before they were stored as PaymentProviderIPN actions
+    StripeWebhook,
     SubmitSitemapToSearchEngines,
     UpdateGenres
 ]}
@@ -177,6 +226,7 @@ string_enum! { BroadcastStatus [Pending, InProgress, Completed, Cancelled]}
 string_enum! { BroadcastChannel [PushNotification, Email]}
 string_enum! { BroadcastType [Custom, LastCall]}
 string_enum! { DomainActionStatus [Pending, RetriesExceeded, Errored, Success, Cancelled]}
+string_enum! { DomainActionWorkerTypes [Actions, Events]}
 string_enum! { EmailProvider [Sendgrid, CustomerIo]}
 string_enum! { Environment [Development, Production, Staging, Test]}
 string_enum! { EventStatus [Draft,Closed,Published,Offline]}
@@ -187,35 +237,44 @@ string_enum! { ExternalPaymentType [Cash, CreditCard, Voucher]}
 string_enum! { FanSortField [FirstName, LastName, Email, Phone, OrganizationId, UserCreated, Orders, FirstOrder, LastOrder, Revenue, FirstInteracted, LastInteracted] }
 string_enum! { HistoryType [Purchase]}
 string_enum! { HoldTypes [Discount, Comp] }
+string_enum! { OfflineRedemptionStatus [DoubleScan, Invalid, ListedForResale, Redeemed, TransferInProcess] }
 string_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees]}
+string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees, ResaleTickets, SeasonPasses, Tax]}
 string_enum! { OrderTypes [Cart, BackOffice] }
 string_enum! { PaymentMethods [CreditCard, External, Free, Provider] }
-string_enum! { PaymentProviders [External, Globee, Free, Stripe] }
+string_enum! { PaymentProviders [External, Globee, Free, HostedCheckout, Stripe] }
 string_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
 string_enum! { PastOrUpcoming [Past,Upcoming]}
 string_enum! { Platforms [Web, App, BoxOffice]}
+string_enum! { RecurrenceFrequencies [Daily, Weekly, Monthly]}
+string_enum! { ReportFormats [Csv, Xlsx]}
 string_enum! { ReportTypes [TicketCounts]}
+string_enum! { ResaleListingStatus [Active, Sold, Cancelled] }
+string_enum! { ResalePayoutStatus [Pending, Paid] }
 string_enum! { Roles [Admin, DoorPerson, OrgAdmin, OrgBoxOffice, OrgMember, OrgOwner, PrismIntegration, Promoter, PromoterReadOnly, User, Super] }
+string_enum! { SavedReportTypes [PromoCode, TicketCounts, TransactionDetails]}
+string_enum! { SeasonPassEntitlementStatus [Active, Refunded] }
 string_enum! { SettlementStatus[PendingSettlement, RequiresAudit, SettledInFull] }
 string_enum! { SettlementTypes [Rolling, PostEvent]}
 string_enum! { SettlementAdjustmentTypes [ManualCredit, ManualDeduction, Chargeback]}
-string_enum! { SettlementEntryTypes [EventFees, TicketType]}
+string_enum! { SettlementEntryTypes [EventFees, TicketType, ResaleFees]}
 string_enum! { SlugTypes[ Event, Organization, Venue, City, Genre, CityGenre ] }
 string_enum! { SortingDir[ Asc, Desc ] }
 string_enum! { SourceOrDestination [Destination,Source]}
 string_enum! { Tables [
-    Artists, Broadcasts, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, ExternalLogins, FeeSchedules,
-    Holds, Orders, Organizations, Notes, Payments, PaymentMethods, PushNotificationTokens, TemporaryUsers, TicketInstances, TicketTypes,
-    TicketPricing, Transfers, Users, Venues, Genres
+    ApiKeys, Artists, Broadcasts, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, EventSeries, ExternalLogins, FeeSchedules,
+    Holds, OAuthClients, OAuthConsents, Orders, Organizations, Notes, Payments, PaymentMethods, PushNotificationTokens, ResaleListings, ResalePayouts, SavedReports, SeasonPasses, SeasonPassEntitlements, TemporaryUsers, TicketInstances, TicketTypes,
+    TicketPricing, TicketPricingRules, Transfers, Users, UserSessions, Venues, Genres, WaitlistEntries
 ] }
 string_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
+string_enum! { TicketPricingRuleTypes [SoldQuantity, RemainingInventory] }
 string_enum! { TicketPricingStatus [Published, Deleted, Default] }
 string_enum! { TicketTypeEndDateType [DoorTime, EventEnd, EventStart, Manual] }
 string_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, OnSaleSoon, SaleEnded, Cancelled, Deleted] }
 string_enum! { TicketTypeVisibility [ Always, Hidden, WhenAvailable ]}
 string_enum! { TransferMessageType [Email, Phone] }
 string_enum! { TransferStatus [Pending, Cancelled, Completed, EventEnded] }
+string_enum! { WaitlistEntryStatus [Waiting, Offered, Purchased, Expired, Cancelled] }
 string_enum! { WebhookAdapters [CustomerIo]}
 
 impl Roles {
@@ -260,12 +319,28 @@ impl Default for BroadcastAudience {
     }
 }
 
+impl Default for ReportFormats {
+    fn default() -> ReportFormats {
+        ReportFormats::Csv
+    }
+}
+
 impl Tables {
     pub fn table_name(&self) -> String {
         self.to_string().to_ascii_lowercase()
     }
 }
 
+impl PaymentProviders {
+    /// Stripe charges through the platform account and organizations are paid out through
+    /// settlements, so it is the only provider able to take a single payment for an order that
+    /// spans several organizations. The other gateways are configured per organization and do
+    /// not support split payouts.
+    pub fn supports_multiple_organizations(self) -> bool {
+        self == PaymentProviders::Stripe
+    }
+}
+
 impl OrderItemTypes {
     pub fn is_fee(self) -> bool {
         self == OrderItemTypes::PerUnitFees
@@ -274,6 +349,31 @@ impl OrderItemTypes {
     }
 }
 
+impl DomainActionTypes {
+    /// Pending actions with a higher priority are processed first, so that actions the user is
+    /// waiting on such as purchase receipts are not held up behind large background jobs
+    pub fn default_priority(self) -> i32 {
+        match self {
+            DomainActionTypes::HostedCheckoutIPN
+            | DomainActionTypes::PaymentProviderIPN
+            | DomainActionTypes::SendPurchaseCompletedCommunication
+            | DomainActionTypes::StripeWebhook => 100,
+            DomainActionTypes::Communication
+            | DomainActionTypes::ExpireWaitlistOffer
+            | DomainActionTypes::OfferWaitlistTickets
+            | DomainActionTypes::SendWaitlistOfferCommunication => 50,
+            DomainActionTypes::ProcessTransferDrip | DomainActionTypes::SendWebhook => 0,
+            DomainActionTypes::BroadcastPushNotification
+            | DomainActionTypes::ProcessSettlementReport
+            | DomainActionTypes::RegenerateDripActions
+            | DomainActionTypes::SendAutomaticReportEmails
+            | DomainActionTypes::SendSavedReport
+            | DomainActionTypes::SubmitSitemapToSearchEngines
+            | DomainActionTypes::UpdateGenres => -50,
+        }
+    }
+}
+
 #[test]
 fn get_event_limited_roles() {
     assert_eq!(
@@ -308,3 +408,19 @@ fn parse() {
 fn to_table_name() {
     assert_eq!(Tables::Events.table_name(), "events");
 }
+
+#[test]
+fn default_priority() {
+    assert!(
+        DomainActionTypes::SendPurchaseCompletedCommunication.default_priority()
+            > DomainActionTypes::Communication.default_priority()
+    );
+    assert!(
+        DomainActionTypes::Communication.default_priority()
+            > DomainActionTypes::BroadcastPushNotification.default_priority()
+    );
+    assert_eq!(
+        DomainActionTypes::ProcessSettlementReport.default_priority(),
+        DomainActionTypes::BroadcastPushNotification.default_priority()
+    );
+}
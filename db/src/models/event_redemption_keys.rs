@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use schema::event_redemption_keys;
+use tari_client::*;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Key pair used to sign the offline redemption data of a single event. The public key is handed to
+/// scanners separately from the signed data, see `OfflineRedemptionBundle::verify`, along with the
+/// secret redeem keys are hashed with.
+#[derive(Clone, Debug, Identifiable, PartialEq, Queryable)]
+#[table_name = "event_redemption_keys"]
+pub struct EventRedemptionKey {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub secret_key: String,
+    pub public_key: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub hash_secret: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "event_redemption_keys"]
+struct NewEventRedemptionKey {
+    event_id: Uuid,
+    secret_key: String,
+    public_key: String,
+}
+
+impl EventRedemptionKey {
+    /// Finds the key pair for the event, generating it the first time the event's redemption data is signed
+    pub fn find_or_create_for_event(event_id: Uuid, conn: &PgConnection) -> Result<EventRedemptionKey, DatabaseError> {
+        let (secret_key, public_key) = cryptographic_keypair();
+        diesel::insert_into(event_redemption_keys::table)
+            .values(&NewEventRedemptionKey {
+                event_id,
+                secret_key: convert_bytes_to_hexstring(&secret_key),
+                public_key: convert_bytes_to_hexstring(&public_key),
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event redemption key")?;
+
+        event_redemption_keys::table
+            .filter(event_redemption_keys::event_id.eq(event_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event redemption key")
+    }
+
+    /// Signs the message with the event's secret key
+    pub fn sign(&self, message: &str) -> Result<String, DatabaseError> {
+        Ok(convert_bytes_to_hexstring(&cryptographic_signature(
+            &message.to_string(),
+            &convert_hexstring_to_bytes(&self.secret_key),
+        )?))
+    }
+}
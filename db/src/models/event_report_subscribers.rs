@@ -9,7 +9,7 @@ use utils::errors::*;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Identifiable, PartialEq, Queryable, Serialize)]
+#[derive(Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
 #[table_name = "event_report_subscribers"]
 pub struct EventReportSubscriber {
     pub id: Uuid,
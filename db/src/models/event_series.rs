@@ -0,0 +1,432 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_series, events, ticket_types};
+use std::collections::HashMap;
+use utils::dates::DateShift;
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+const MAXIMUM_OCCURRENCES: i32 = 104;
+
+/// Recurrence of a template event, e.g. every Friday for 12 weeks. Each occurrence is a copy of the template made
+/// with `Event::duplicate_with_shift` and numbered by `event_series_index`, the template being occurrence 0.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "event_series"]
+pub struct EventSeries {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub frequency: RecurrenceFrequencies,
+    pub frequency_interval: i32,
+    pub occurrences: i32,
+    pub created_by_user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "event_series"]
+pub struct NewEventSeries {
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub frequency: RecurrenceFrequencies,
+    pub frequency_interval: i32,
+    pub occurrences: i32,
+    pub created_by_user_id: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayEventSeries {
+    #[serde(flatten)]
+    pub event_series: EventSeries,
+    pub events: Vec<Event>,
+}
+
+impl NewEventSeries {
+    pub fn commit(self, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        self.validate_record()?;
+        let template = Event::find(self.template_event_id, conn)?;
+        if template.event_series_id.is_some() {
+            return DatabaseError::business_process_error("Event already belongs to a series");
+        }
+        if template.event_start.is_none() {
+            return DatabaseError::business_process_error("Event must have an event start to be repeated");
+        }
+
+        let result: EventSeries = diesel::insert_into(event_series::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event series")?;
+        result.set_occurrence(&template, 0, conn)?;
+
+        let timezone = template.timezone(conn)?;
+        for index in 1..result.occurrences {
+            let event = template.duplicate_with_shift(
+                result.shift_for(index, timezone),
+                None,
+                result.created_by_user_id,
+                conn,
+            )?;
+            result.set_occurrence(&event, index, conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesCreated,
+            format!("Event series created from '{}'", template.name),
+            Tables::EventSeries,
+            Some(result.id),
+            Some(result.created_by_user_id),
+            Some(json!({
+                "template_event_id": result.template_event_id,
+                "frequency": result.frequency,
+                "frequency_interval": result.frequency_interval,
+                "occurrences": result.occurrences
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "frequency_interval",
+            validators::validate_greater_than_or_equal(
+                self.frequency_interval,
+                1,
+                "frequency_interval_lt_1",
+                "Interval must be at least 1",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "occurrences",
+            validators::validate_greater_than_or_equal(
+                self.occurrences,
+                2,
+                "occurrences_lt_2",
+                "A series must have at least 2 occurrences",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "occurrences",
+            validators::validate_less_than_or_equal(
+                self.occurrences,
+                MAXIMUM_OCCURRENCES,
+                "occurrences_gt_max",
+                "A series cannot have more than 104 occurrences",
+            ),
+        );
+
+        Ok(validation_errors?)
+    }
+}
+
+impl EventSeries {
+    pub fn create(
+        template: &Event,
+        frequency: RecurrenceFrequencies,
+        frequency_interval: i32,
+        occurrences: i32,
+        created_by_user_id: Uuid,
+    ) -> NewEventSeries {
+        NewEventSeries {
+            organization_id: template.organization_id,
+            template_event_id: template.id,
+            frequency,
+            frequency_interval,
+            occurrences,
+            created_by_user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        event_series::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event series")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    /// Occurrences that have not been deleted, in order
+    pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .order_by(events::event_series_index)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for event series")
+    }
+
+    pub fn for_display(self, conn: &PgConnection) -> Result<DisplayEventSeries, DatabaseError> {
+        let events = self.events(conn)?;
+        Ok(DisplayEventSeries {
+            event_series: self,
+            events,
+        })
+    }
+
+    /// Shift from the template to the occurrence at `index`
+    pub fn shift_for(&self, index: i32, timezone: Tz) -> DateShift {
+        let steps = self.frequency_interval * index;
+        match self.frequency {
+            RecurrenceFrequencies::Daily => DateShift::new(0, Duration::days(steps as i64), timezone),
+            RecurrenceFrequencies::Weekly => DateShift::new(0, Duration::weeks(steps as i64), timezone),
+            RecurrenceFrequencies::Monthly => DateShift::new(steps, Duration::zero(), timezone),
+        }
+    }
+
+    /// Updates `event` and copies the changes to the occurrences after it that have not started, been cancelled or
+    /// been deleted. Dates are moved by the distance between the occurrences so each keeps its own day.
+    ///
+    /// The ticket types of `event` and their pricing tiers are copied to the following occurrences as well, see
+    /// `update_ticket_types`. Holds and codes are managed per occurrence.
+    pub fn update_following(
+        &self,
+        event: &Event,
+        attributes: EventEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let index = match (event.event_series_id, event.event_series_index) {
+            (Some(event_series_id), Some(index)) if event_series_id == self.id => index,
+            _ => return DatabaseError::business_process_error("Event does not belong to this series"),
+        };
+        let event_start = match event.event_start {
+            Some(event_start) => event_start,
+            None => return DatabaseError::business_process_error("Event must have an event start to update a series"),
+        };
+        let following: Vec<Event> = events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::event_series_index.gt(index))
+            .filter(events::event_start.gt(dsl::now.nullable()))
+            .filter(events::deleted_at.is_null())
+            .filter(events::cancelled_at.is_null())
+            .order_by(events::event_series_index)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load following events for event series",
+            )?;
+
+        let timezone = event.timezone(conn)?;
+        let mut result = vec![event.update(current_user_id, attributes.clone(), conn)?];
+        for occurrence in following {
+            let shift = match occurrence.event_start {
+                Some(occurrence_start) => DateShift::between(event_start, occurrence_start, timezone),
+                None => continue,
+            };
+            let shift_date = |date: Option<NaiveDateTime>| date.map(|d| shift.apply(d));
+            let mut occurrence_attributes = attributes.clone();
+            occurrence_attributes.event_start = shift_date(attributes.event_start);
+            occurrence_attributes.door_time = shift_date(attributes.door_time);
+            occurrence_attributes.event_end = shift_date(attributes.event_end);
+            occurrence_attributes.redeem_date = shift_date(attributes.redeem_date);
+            occurrence_attributes.publish_date = attributes.publish_date.map(shift_date);
+            // Cancelling is done one occurrence at a time
+            occurrence_attributes.cancelled_at = None;
+            let occurrence = occurrence.update(current_user_id, occurrence_attributes, conn)?;
+            self.update_ticket_types(event, &occurrence, shift, current_user_id, conn)?;
+            result.push(occurrence);
+        }
+
+        Ok(result)
+    }
+
+    /// Copies the ticket types of `event` and their pricing tiers to `occurrence` with dates moved by `shift`.
+    /// Ticket types are matched by name, or by rank when they have been renamed. Ticket types missing from the
+    /// occurrence are created and ticket types that only exist on the occurrence are left as they are. Capacity
+    /// and pricing rules are managed per occurrence, as are tiers added by a triggered pricing rule.
+    fn update_ticket_types(
+        &self,
+        event: &Event,
+        occurrence: &Event,
+        shift: DateShift,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let active_ticket_types = |event_id: Uuid| -> Result<Vec<TicketType>, DatabaseError> {
+            ticket_types::table
+                .filter(ticket_types::event_id.eq(event_id))
+                .filter(ticket_types::deleted_at.is_null())
+                .filter(ticket_types::status.ne(TicketTypeStatus::Cancelled))
+                .order_by(ticket_types::rank)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load ticket types for event")
+        };
+        let mut pending_ticket_types = active_ticket_types(event.id)?;
+        let mut occurrence_ticket_types = active_ticket_types(occurrence.id)?;
+
+        let mut matches: HashMap<Uuid, TicketType> = HashMap::new();
+        for ticket_type in pending_ticket_types.iter() {
+            if let Some(position) = occurrence_ticket_types
+                .iter()
+                .position(|tt| tt.name == ticket_type.name)
+            {
+                matches.insert(ticket_type.id, occurrence_ticket_types.remove(position));
+            }
+        }
+        let renamed: Vec<&TicketType> = pending_ticket_types
+            .iter()
+            .filter(|tt| !matches.contains_key(&tt.id))
+            .collect();
+        for ticket_type in renamed {
+            if let Some(position) = occurrence_ticket_types
+                .iter()
+                .position(|tt| tt.rank == ticket_type.rank)
+            {
+                matches.insert(ticket_type.id, occurrence_ticket_types.remove(position));
+            }
+        }
+
+        // Parents are updated first so children can refer to their copies
+        let shift_date = |date: Option<NaiveDateTime>| date.map(|d| shift.apply(d));
+        let mut ticket_type_ids: HashMap<Uuid, Uuid> = HashMap::new();
+        while !pending_ticket_types.is_empty() {
+            let position = pending_ticket_types
+                .iter()
+                .position(|tt| tt.parent_id.map(|p| ticket_type_ids.contains_key(&p)).unwrap_or(true));
+            let ticket_type = match position {
+                Some(position) => pending_ticket_types.remove(position),
+                None => pending_ticket_types.remove(0),
+            };
+            let start_date = match ticket_type.parent_id {
+                Some(_) if position.is_some() => shift_date(ticket_type.start_date),
+                _ => Some(shift.apply(ticket_type.start_date(conn)?)),
+            };
+            let parent_id = ticket_type
+                .parent_id
+                .and_then(|parent_id| ticket_type_ids.get(&parent_id).cloned());
+
+            let occurrence_ticket_type = match matches.remove(&ticket_type.id) {
+                Some(occurrence_ticket_type) => occurrence_ticket_type.update(
+                    TicketTypeEditableAttributes {
+                        name: Some(ticket_type.name.clone()),
+                        description: Some(ticket_type.description.clone()),
+                        start_date: Some(start_date),
+                        end_date: Some(shift_date(ticket_type.end_date)),
+                        increment: Some(ticket_type.increment),
+                        limit_per_person: Some(ticket_type.limit_per_person),
+                        price_in_cents: Some(ticket_type.price_in_cents),
+                        visibility: Some(ticket_type.visibility),
+                        parent_id: Some(parent_id),
+                        additional_fee_in_cents: Some(ticket_type.additional_fee_in_cents),
+                        end_date_type: Some(ticket_type.end_date_type),
+                        web_sales_enabled: Some(ticket_type.web_sales_enabled),
+                        box_office_sales_enabled: Some(ticket_type.box_office_sales_enabled),
+                        app_sales_enabled: Some(ticket_type.app_sales_enabled),
+                        rank: None,
+                    },
+                    current_user_id,
+                    conn,
+                )?,
+                None => occurrence.add_ticket_type(
+                    ticket_type.name.clone(),
+                    ticket_type.description.clone(),
+                    ticket_type.valid_ticket_count(conn)?,
+                    start_date,
+                    shift_date(ticket_type.end_date),
+                    ticket_type.end_date_type,
+                    None,
+                    Some(ticket_type.increment),
+                    ticket_type.limit_per_person,
+                    ticket_type.price_in_cents,
+                    ticket_type.visibility,
+                    parent_id,
+                    ticket_type.additional_fee_in_cents,
+                    ticket_type.app_sales_enabled,
+                    ticket_type.web_sales_enabled,
+                    ticket_type.box_office_sales_enabled,
+                    current_user_id,
+                    conn,
+                )?,
+            };
+
+            EventSeries::update_ticket_pricing(&ticket_type, &occurrence_ticket_type, shift, current_user_id, conn)?;
+            ticket_type_ids.insert(ticket_type.id, occurrence_ticket_type.id);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the pricing tiers of `occurrence_ticket_type` with those of `ticket_type`, matched by name. Tiers in
+    /// use by orders are replaced by `TicketPricing::update` so existing carts keep their price.
+    fn update_ticket_pricing(
+        ticket_type: &TicketType,
+        occurrence_ticket_type: &TicketType,
+        shift: DateShift,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        // Tiers added by a triggered pricing rule belong to the rule rather than the ticket type
+        let manual_ticket_pricing = |ticket_type: &TicketType| -> Result<Vec<TicketPricing>, DatabaseError> {
+            let pricing_rules = ticket_type.pricing_rules(conn)?;
+            Ok(ticket_type
+                .valid_ticket_pricing(false, conn)?
+                .into_iter()
+                .filter(|tp| !pricing_rules.iter().any(|r| r.ticket_pricing_id == Some(tp.id)))
+                .collect())
+        };
+        let ticket_pricing = manual_ticket_pricing(ticket_type)?;
+        let occurrence_ticket_pricing = manual_ticket_pricing(occurrence_ticket_type)?;
+
+        for occurrence_pricing in occurrence_ticket_pricing.iter() {
+            if !ticket_pricing.iter().any(|tp| tp.name == occurrence_pricing.name) {
+                occurrence_pricing.destroy(current_user_id, conn)?;
+            }
+        }
+        for pricing in ticket_pricing {
+            match occurrence_ticket_pricing.iter().find(|tp| tp.name == pricing.name) {
+                Some(occurrence_pricing) => {
+                    occurrence_pricing.update(
+                        TicketPricingEditableAttributes {
+                            name: None,
+                            price_in_cents: Some(pricing.price_in_cents),
+                            start_date: Some(shift.apply(pricing.start_date)),
+                            end_date: Some(shift.apply(pricing.end_date)),
+                            is_box_office_only: Some(pricing.is_box_office_only),
+                        },
+                        current_user_id,
+                        conn,
+                    )?;
+                }
+                None => {
+                    occurrence_ticket_type.add_ticket_pricing(
+                        pricing.name,
+                        shift.apply(pricing.start_date),
+                        shift.apply(pricing.end_date),
+                        pricing.price_in_cents,
+                        pricing.is_box_office_only,
+                        Some(pricing.status),
+                        current_user_id,
+                        conn,
+                    )?;
+                }
+            }
+        }
+
+        occurrence_ticket_type.validate_ticket_pricing(conn)
+    }
+
+    fn set_occurrence(&self, event: &Event, index: i32, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        diesel::update(event)
+            .set((
+                events::event_series_id.eq(self.id),
+                events::event_series_index.eq(index),
+                events::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add event to event series")
+    }
+}
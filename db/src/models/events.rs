@@ -13,8 +13,9 @@ use itertools::Itertools;
 use log::Level;
 use models::*;
 use schema::{
-    artists, assets, event_artists, event_genres, events, genres, order_items, orders, organization_users,
-    organizations, payments, ticket_instances, ticket_types, transfer_tickets, transfers, users, venues, wallets,
+    artists, assets, codes, event_artists, event_genres, events, genres, holds, order_items, orders,
+    organization_users, organizations, payments, ticket_instances, ticket_types, transfer_tickets, transfers, users,
+    venues, wallets,
 };
 use serde_json::Value;
 use serde_with::rust::double_option;
@@ -23,6 +24,7 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use time::Duration;
+use utils::dates::DateShift;
 use utils::errors::*;
 use utils::pagination::*;
 use utils::text;
@@ -71,6 +73,9 @@ pub struct Event {
     pub slug_id: Option<Uuid>,
     pub facebook_event_id: Option<String>,
     pub settled_at: Option<NaiveDateTime>,
+    pub event_series_id: Option<Uuid>,
+    pub event_series_index: Option<i32>,
+    pub currency: String,
 }
 
 impl PartialOrd for Event {
@@ -124,6 +129,8 @@ pub struct NewEvent {
     pub extra_admin_data: Option<Value>,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub facebook_event_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 pub enum TicketHoldersCountType {
@@ -149,7 +156,15 @@ impl NewEvent {
             None => (),
         }
 
-        validators::append_validation_error(
+        // Fees are charged from the organization's fee schedule so the event must be priced in its currency
+        let organization = Organization::find(new_event.organization_id, conn)?;
+        let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, conn)?;
+        if new_event.currency.is_none() {
+            new_event.currency = Some(organization.currency);
+        }
+        let currency = new_event.currency.clone().unwrap_or_default();
+
+        let validation_errors = validators::append_validation_error(
             Ok(()),
             "event.event_end",
             validators::n_date_valid(
@@ -160,7 +175,25 @@ impl NewEvent {
                 "event_start",
                 "event_end",
             ),
-        )?;
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "currency",
+            validators::validate_currency(&currency),
+        );
+        let validation_errors = if currency != fee_schedule.currency {
+            validators::append_validation_error(
+                validation_errors,
+                "currency",
+                Err(create_validation_error(
+                    "currency_does_not_match_fee_schedule",
+                    "Currency must match the currency of the organization's fee schedule",
+                )),
+            )
+        } else {
+            validation_errors
+        };
+        validation_errors?;
 
         let result: Event = diesel::insert_into(events::table)
             .values(&new_event)
@@ -204,7 +237,7 @@ impl NewEvent {
     }
 }
 
-#[derive(AsChangeset, Default, Deserialize, Validate, Serialize)]
+#[derive(AsChangeset, Clone, Default, Deserialize, Validate, Serialize)]
 #[table_name = "events"]
 pub struct EventEditableAttributes {
     pub name: Option<String>,
@@ -564,6 +597,243 @@ impl Event {
         Ok(result)
     }
 
+    /// Timezone used to interpret the event's local dates, the venue's when it has one or else the organization's
+    pub fn timezone(&self, conn: &PgConnection) -> Result<Tz, DatabaseError> {
+        let timezone = match self.venue(conn)? {
+            Some(venue) => Some(venue.timezone),
+            None => self.organization(conn)?.timezone,
+        };
+        Ok(timezone.and_then(|t| t.parse().ok()).unwrap_or(Tz::UTC))
+    }
+
+    /// Copies the event as a new draft starting at `event_start` along with its ticket types, pricing, holds, codes
+    /// and artists. Every date is moved by the same amount of local time as the event start.
+    pub fn duplicate(
+        &self,
+        event_start: NaiveDateTime,
+        name: Option<String>,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Event, DatabaseError> {
+        let previous_start = match self.event_start {
+            Some(previous_start) => previous_start,
+            None => return DatabaseError::business_process_error("Event must have an event start to be duplicated"),
+        };
+        let shift = DateShift::between(previous_start, event_start, self.timezone(conn)?);
+        self.duplicate_with_shift(shift, name, current_user_id, conn)
+    }
+
+    pub(crate) fn duplicate_with_shift(
+        &self,
+        shift: DateShift,
+        name: Option<String>,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Event, DatabaseError> {
+        let shift_date = |date: Option<NaiveDateTime>| date.map(|d| shift.apply(d));
+        let new_event = NewEvent {
+            name: name.unwrap_or_else(|| self.name.clone()),
+            organization_id: self.organization_id,
+            venue_id: self.venue_id,
+            event_start: shift_date(self.event_start),
+            door_time: shift_date(self.door_time),
+            status: EventStatus::Draft,
+            publish_date: shift_date(self.publish_date),
+            redeem_date: shift_date(self.redeem_date),
+            promo_image_url: self.promo_image_url.clone(),
+            cover_image_url: self.cover_image_url.clone(),
+            additional_info: self.additional_info.clone(),
+            age_limit: self.age_limit.clone(),
+            top_line_info: self.top_line_info.clone(),
+            video_url: self.video_url.clone(),
+            is_external: self.is_external,
+            external_url: self.external_url.clone(),
+            override_status: self.override_status,
+            event_end: shift_date(self.event_end),
+            event_type: self.event_type,
+            private_access_code: self.private_access_code.clone(),
+            facebook_pixel_key: self.facebook_pixel_key.clone(),
+            extra_admin_data: self.extra_admin_data.clone(),
+            facebook_event_id: None,
+            currency: Some(self.currency.clone()),
+        };
+        let event = new_event.commit(Some(current_user_id), conn)?;
+        let event: Event = diesel::update(&event)
+            .set((
+                events::client_fee_in_cents.eq(self.client_fee_in_cents),
+                events::company_fee_in_cents.eq(self.company_fee_in_cents),
+                events::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not copy fees to duplicated event")?;
+
+        // Parents are created first so children can refer to their copies
+        let mut pending_ticket_types: Vec<TicketType> = ticket_types::table
+            .filter(ticket_types::event_id.eq(self.id))
+            .filter(ticket_types::deleted_at.is_null())
+            .filter(ticket_types::status.ne(TicketTypeStatus::Cancelled))
+            .order_by(ticket_types::rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types for event")?;
+        let mut ticket_type_ids: HashMap<Uuid, Uuid> = HashMap::new();
+        while !pending_ticket_types.is_empty() {
+            let position = pending_ticket_types
+                .iter()
+                .position(|tt| tt.parent_id.map(|p| ticket_type_ids.contains_key(&p)).unwrap_or(true));
+            let ticket_type = match position {
+                Some(position) => pending_ticket_types.remove(position),
+                // Parent was cancelled or deleted, the copies start on their own dates instead
+                None => pending_ticket_types.remove(0),
+            };
+            let new_ticket_type = event.add_ticket_type(
+                ticket_type.name.clone(),
+                ticket_type.description.clone(),
+                ticket_type.valid_ticket_count(conn)?,
+                match ticket_type.parent_id {
+                    Some(_) if position.is_some() => shift_date(ticket_type.start_date),
+                    _ => Some(shift.apply(ticket_type.start_date(conn)?)),
+                },
+                shift_date(ticket_type.end_date),
+                ticket_type.end_date_type,
+                None,
+                Some(ticket_type.increment),
+                ticket_type.limit_per_person,
+                ticket_type.price_in_cents,
+                ticket_type.visibility,
+                ticket_type
+                    .parent_id
+                    .and_then(|parent_id| ticket_type_ids.get(&parent_id).cloned()),
+                ticket_type.additional_fee_in_cents,
+                ticket_type.app_sales_enabled,
+                ticket_type.web_sales_enabled,
+                ticket_type.box_office_sales_enabled,
+                Some(current_user_id),
+                conn,
+            )?;
+
+            let pricing_rules = ticket_type.pricing_rules(conn)?;
+            for ticket_pricing in ticket_type.valid_ticket_pricing(false, conn)? {
+                // Tiers added by a triggered pricing rule are recreated when the copied rule triggers
+                if pricing_rules
+                    .iter()
+                    .any(|r| r.ticket_pricing_id == Some(ticket_pricing.id))
+                {
+                    continue;
+                }
+                new_ticket_type.add_ticket_pricing(
+                    ticket_pricing.name,
+                    shift.apply(ticket_pricing.start_date),
+                    shift.apply(ticket_pricing.end_date),
+                    ticket_pricing.price_in_cents,
+                    ticket_pricing.is_box_office_only,
+                    Some(ticket_pricing.status),
+                    Some(current_user_id),
+                    conn,
+                )?;
+            }
+            for pricing_rule in pricing_rules {
+                TicketPricingRule::create(
+                    new_ticket_type.id,
+                    pricing_rule.name,
+                    pricing_rule.rule_type,
+                    pricing_rule.threshold,
+                    pricing_rule.price_in_cents,
+                    pricing_rule.price_increase_percent,
+                )
+                .commit(Some(current_user_id), conn)?;
+            }
+            ticket_type_ids.insert(ticket_type.id, new_ticket_type.id);
+        }
+
+        // Comps split from a hold belong to specific people so only their quantity is carried over
+        let holds: Vec<Hold> = holds::table
+            .filter(holds::event_id.eq(self.id))
+            .filter(holds::parent_hold_id.is_null())
+            .filter(holds::deleted_at.is_null())
+            .order_by(holds::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load holds for event")?;
+        for hold in holds {
+            let ticket_type_id = match ticket_type_ids.get(&hold.ticket_type_id) {
+                Some(ticket_type_id) => *ticket_type_id,
+                None => continue,
+            };
+            let (quantity, _) = TicketInstance::count_for_hold(hold.id, hold.ticket_type_id, true, conn)?;
+            let new_hold = Hold::create_hold(
+                hold.name,
+                event.id,
+                hold.redemption_code,
+                hold.discount_in_cents.map(|d| d as u32),
+                shift_date(hold.end_at),
+                hold.max_per_user.map(|m| m as u32),
+                hold.hold_type,
+                ticket_type_id,
+            )
+            .commit(Some(current_user_id), conn)?;
+            new_hold.set_quantity(Some(current_user_id), quantity, conn)?;
+        }
+
+        let codes: Vec<Code> = codes::table
+            .filter(codes::event_id.eq(self.id))
+            .filter(codes::deleted_at.is_null())
+            .order_by(codes::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load codes for event")?;
+        for code in codes {
+            let code_ticket_type_ids: Vec<Uuid> = TicketType::find_for_code(code.id, conn)?
+                .iter()
+                .filter_map(|tt| ticket_type_ids.get(&tt.id).cloned())
+                .collect();
+            if code_ticket_type_ids.is_empty() {
+                continue;
+            }
+            Code::create(
+                code.name,
+                event.id,
+                code.code_type,
+                code.redemption_code,
+                code.max_uses as u32,
+                code.discount_in_cents.map(|d| d as u32),
+                code.discount_as_percentage.map(|d| d as u32),
+                shift.apply(code.start_date),
+                shift.apply(code.end_date),
+                code.max_tickets_per_user.map(|m| m as u32),
+            )
+            .commit(Some(current_user_id), conn)?
+            .update_ticket_types(code_ticket_type_ids, conn)?;
+        }
+
+        let event_artists: Vec<EventArtist> = event_artists::table
+            .filter(event_artists::event_id.eq(self.id))
+            .order_by(event_artists::rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load artists for event")?;
+        for event_artist in event_artists {
+            EventArtist::create(
+                event.id,
+                event_artist.artist_id,
+                event_artist.rank,
+                shift_date(event_artist.set_time),
+                event_artist.importance,
+                event_artist.stage_id,
+            )
+            .commit(Some(current_user_id), conn)?;
+        }
+        event.update_genres(Some(current_user_id), conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventDuplicated,
+            format!("Event '{}' duplicated from '{}'", &event.name, &self.name),
+            Tables::Events,
+            Some(event.id),
+            Some(current_user_id),
+            Some(json!({ "source_event_id": self.id })),
+        )
+        .commit(conn)?;
+
+        Ok(event)
+    }
+
     pub fn regenerate_drip_actions(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         DomainAction::create(
             None,
@@ -1540,9 +1810,10 @@ impl Event {
                 , sql::<Timestamp>("ticket_instances.updated_at AS updated_at")
                 , sql::<Nullable<Text>>("CASE WHEN ticket_instances.redeemed_by_user_id IS NOT NULL THEN (SELECT CONCAT(u2.first_name, ' ', u2.last_name) FROM users u2 WHERE u2.id = ticket_instances.redeemed_by_user_id) ELSE NULL END  AS redeemed_by")
                 , sql::<Nullable<Timestamp>>("ticket_instances.redeemed_at AS redeemed_at")
+                , sql::<Nullable<Text>>(&format!("{} AS seat_label", TICKET_INSTANCE_SEAT_LABEL_SQL))
             ))
-            .paginate(paging.page as i64)
-            .per_page(paging.limit as i64)
+            .paginate(paging.page() as i64)
+            .per_page(paging.limit() as i64)
             .load_and_count_pages(conn);
 
         DatabaseError::wrap(ErrorCode::QueryError, "Unable to load all redeemable tickets", results)
@@ -1836,8 +2107,8 @@ impl Event {
             .distinct()
             .order_by(sql::<()>(&format!("{} {}", sort_column, sort_direction)))
             .then_order_by(events::name.asc())
-            .paginate(paging.page as i64)
-            .per_page(paging.limit as i64)
+            .paginate(paging.page() as i64)
+            .per_page(paging.limit() as i64)
             .load_and_count_pages(conn);
 
         DatabaseError::wrap(ErrorCode::QueryError, "Unable to load all events", result)
@@ -2,7 +2,7 @@ use chrono::NaiveDateTime;
 use diesel::sql_types::{BigInt, Nullable, Timestamp, Uuid as dUuid};
 use uuid::Uuid;
 
-#[derive(Clone, Debug, PartialEq, Queryable, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
 pub struct DisplayFan {
     pub user_id: Uuid,
     pub first_name: Option<String>,
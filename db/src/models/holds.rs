@@ -85,6 +85,7 @@ impl Hold {
         end_at: Option<NaiveDateTime>,
         max_per_user: Option<u32>,
         quantity: u32,
+        seat_ids: Option<Vec<Uuid>>,
         conn: &PgConnection,
     ) -> Result<Hold, DatabaseError> {
         let hold = Hold::find(hold_id, conn)?;
@@ -106,6 +107,9 @@ impl Hold {
         let new_hold = new_hold.commit(current_user_id, conn)?;
 
         new_hold.set_quantity(current_user_id, quantity, conn)?;
+        if let Some(seat_ids) = seat_ids {
+            new_hold.add_seats(current_user_id, seat_ids, conn)?;
+        }
 
         Ok(new_hold)
     }
@@ -176,7 +180,7 @@ impl Hold {
                 .filter(holds::hold_type.nullable().eq(hold_type).or(hold_type.is_none()))
                 .order_by((holds::hold_type, holds::name))
                 .limit(limit as i64)
-                .offset((page * limit) as i64)
+                .offset(page as i64 * limit as i64)
                 .load(conn)
                 .to_db_error(ErrorCode::QueryError, "Could not retrieve holds")?,
             paging,
@@ -280,6 +284,7 @@ impl Hold {
             self.ticket_type_id,
             quantity,
             Some(self.id),
+            None,
             conn,
         )?;
         Ok(new_hold)
@@ -333,6 +338,7 @@ impl Hold {
                 self.ticket_type_id,
                 quantity - count,
                 self.parent_hold_id,
+                None,
                 conn,
             )?;
             DomainEvent::create(
@@ -353,6 +359,7 @@ impl Hold {
                     self.ticket_type_id,
                     count - quantity,
                     Some(self.id),
+                    None,
                     conn,
                 )?;
                 DomainEvent::create(
@@ -381,6 +388,45 @@ impl Hold {
         Ok(())
     }
 
+    /// Moves the tickets bound to `seat_ids` into this hold from either the main pool or the
+    /// parent hold, increasing the hold quantity by the number of seats.
+    pub fn add_seats(
+        &self,
+        user_id: Option<Uuid>,
+        seat_ids: Vec<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if seat_ids.is_empty() {
+            return Ok(());
+        }
+
+        let (count, _available) = self.quantity(conn)?;
+        TicketInstance::add_to_hold(
+            user_id,
+            self.id,
+            self.ticket_type_id,
+            seat_ids.len() as u32,
+            self.parent_hold_id,
+            Some(seat_ids.clone()),
+            conn,
+        )?;
+        DomainEvent::create(
+            DomainEventTypes::HoldQuantityChanged,
+            format!(
+                "Hold quantity increased from {} to {}",
+                count,
+                count + seat_ids.len() as u32
+            ),
+            Tables::Holds,
+            Some(self.id),
+            user_id,
+            Some(json!({"old_quantity": count, "new_quantity": count + seat_ids.len() as u32, "seat_ids": seat_ids})),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
     pub fn remove_available_quantity(
         &self,
         current_user_id: Option<Uuid>,
@@ -1,16 +1,20 @@
 pub use self::activities::*;
+pub use self::api_keys::*;
 pub use self::artists::*;
 pub use self::assets::*;
 pub use self::broadcasts::*;
 pub use self::codes::*;
 pub use self::communication::*;
+pub use self::domain_action_workers::*;
 pub use self::domain_actions::*;
 pub use self::domain_event_publishers::*;
 pub use self::domain_events::*;
 pub use self::enums::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
+pub use self::event_redemption_keys::*;
 pub use self::event_report_subscribers::*;
+pub use self::event_series::*;
 pub use self::event_users::*;
 pub use self::events::*;
 pub use self::external_logins::FACEBOOK_SITE;
@@ -23,6 +27,10 @@ pub use self::genres::*;
 pub use self::history_item::*;
 pub use self::holds::*;
 pub use self::notes::*;
+pub use self::oauth_authorization_codes::*;
+pub use self::oauth_clients::*;
+pub use self::oauth_consents::*;
+pub use self::offline_redemptions::*;
 pub use self::order_items::*;
 pub use self::orders::*;
 pub use self::organization_interactions::*;
@@ -32,6 +40,7 @@ pub use self::organizations::*;
 pub use self::paging::*;
 pub use self::payment_methods::*;
 pub use self::payments::*;
+pub use self::pending_settlement_adjustments::*;
 pub use self::platforms::*;
 pub use self::push_notification_tokens::*;
 pub use self::redeemable_ticket::*;
@@ -40,23 +49,40 @@ pub use self::refunded_tickets::*;
 pub use self::refunds::*;
 pub use self::regions::*;
 pub use self::reports::*;
+pub use self::resale_listings::*;
+pub use self::resale_payouts::*;
+pub use self::saved_report_subscribers::*;
+pub use self::saved_reports::*;
 pub use self::scopes::*;
+pub use self::season_pass_entitlements::*;
+pub use self::season_pass_settlement_entries::*;
+pub use self::season_passes::*;
+pub use self::seats::*;
 pub use self::settlement_adjustments::*;
 pub use self::settlement_entries::*;
 pub use self::settlements::*;
 pub use self::slugs::*;
+pub use self::stage_sections::*;
 pub use self::stages::*;
+pub use self::tax_rules::*;
 pub use self::temporary_users::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
+pub use self::ticket_pricing_rules::*;
 pub use self::ticket_type_codes::*;
 pub use self::ticket_types::*;
 pub use self::transfer_tickets::*;
 pub use self::transfers::*;
+pub use self::two_factor_challenges::*;
+pub use self::two_factor_credentials::*;
+pub use self::two_factor_recovery_codes::*;
+pub use self::user_sessions::*;
 pub use self::users::*;
 pub use self::venues::*;
+pub use self::waitlist_entries::*;
 pub use self::wallets::*;
+pub use self::webhook_deliveries::*;
 
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
@@ -64,19 +90,23 @@ use serde_json::Value;
 pub mod concerns;
 
 mod activities;
+mod api_keys;
 pub mod analytics;
 mod artists;
 mod assets;
 mod broadcasts;
 mod codes;
 mod communication;
+mod domain_action_workers;
 mod domain_actions;
 mod domain_event_publishers;
 mod domain_events;
 pub mod enums;
 mod event_artists;
 mod event_interest;
+mod event_redemption_keys;
 mod event_report_subscribers;
+mod event_series;
 mod event_users;
 mod events;
 mod external_logins;
@@ -88,6 +118,10 @@ mod genres;
 mod history_item;
 mod holds;
 mod notes;
+mod oauth_authorization_codes;
+mod oauth_clients;
+mod oauth_consents;
+mod offline_redemptions;
 mod order_items;
 mod orders;
 mod organization_interactions;
@@ -97,6 +131,7 @@ mod organizations;
 mod paging;
 mod payment_methods;
 mod payments;
+mod pending_settlement_adjustments;
 mod platforms;
 mod push_notification_tokens;
 mod redeemable_ticket;
@@ -105,22 +140,39 @@ mod refunded_tickets;
 mod refunds;
 mod regions;
 mod reports;
+mod resale_listings;
+mod resale_payouts;
+mod saved_report_subscribers;
+mod saved_reports;
 pub mod scopes;
+mod season_pass_entitlements;
+mod season_pass_settlement_entries;
+mod season_passes;
+mod seats;
 mod settlement_adjustments;
 mod settlement_entries;
 mod settlements;
 mod slugs;
+mod stage_sections;
 mod stages;
+mod tax_rules;
 mod temporary_users;
 mod ticket_instances;
 mod ticket_pricing;
+mod ticket_pricing_rules;
 mod ticket_type_codes;
 mod ticket_types;
 mod transfer_tickets;
 mod transfers;
+mod two_factor_challenges;
+mod two_factor_credentials;
+mod two_factor_recovery_codes;
+mod user_sessions;
 mod users;
 mod venues;
+mod waitlist_entries;
 mod wallets;
+mod webhook_deliveries;
 
 pub fn deserialize_unless_blank<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
 where
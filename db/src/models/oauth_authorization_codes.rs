@@ -0,0 +1,168 @@
+use base64;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use ring::digest;
+use schema::oauth_authorization_codes;
+use std::str::FromStr;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+const CODE_LENGTH: usize = 40;
+const CODE_EXPIRY_MINUTES: i64 = 10;
+const CODE_VERIFIER_MIN_LENGTH: usize = 43;
+const CODE_VERIFIER_MAX_LENGTH: usize = 128;
+
+/// Single use code issued once a user consents to a client, exchanged for tokens by presenting the
+/// PKCE code verifier matching the S256 `code_challenge`.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "oauth_authorization_codes"]
+pub struct OAuthAuthorizationCode {
+    pub id: Uuid,
+    pub oauth_consent_id: Uuid,
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "oauth_authorization_codes"]
+pub struct NewOAuthAuthorizationCode {
+    pub oauth_consent_id: Uuid,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: String,
+    pub expires_at: NaiveDateTime,
+}
+
+impl NewOAuthAuthorizationCode {
+    /// Creates the authorization code, returning it alongside the plain text code for the redirect
+    pub fn commit(self, conn: &PgConnection) -> Result<(OAuthAuthorizationCode, String), DatabaseError> {
+        let code = random_alpha_string(CODE_LENGTH);
+        let result: OAuthAuthorizationCode = diesel::insert_into(oauth_authorization_codes::table)
+            .values((&self, oauth_authorization_codes::code_hash.eq(sha256::digest(&code))))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create OAuth authorization code")?;
+
+        Ok((result, code))
+    }
+}
+
+impl OAuthAuthorizationCode {
+    pub fn create(
+        oauth_consent_id: Uuid,
+        redirect_uri: String,
+        scopes: Vec<Scopes>,
+        code_challenge: String,
+    ) -> NewOAuthAuthorizationCode {
+        NewOAuthAuthorizationCode {
+            oauth_consent_id,
+            redirect_uri,
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            code_challenge,
+            expires_at: Utc::now().naive_utc() + Duration::minutes(CODE_EXPIRY_MINUTES),
+        }
+    }
+
+    /// Exchanges the code for the consent it was issued under. The code can only be used once, the
+    /// redirect URI must match the one used to request it and the code verifier must match its challenge.
+    /// Presenting an already used code revokes the consent as the code has likely been intercepted.
+    pub fn redeem(
+        code: &str,
+        oauth_client: &OAuthClient,
+        redirect_uri: &str,
+        code_verifier: &str,
+        conn: &PgConnection,
+    ) -> Result<(OAuthAuthorizationCode, OAuthConsent), DatabaseError> {
+        let authorization_code: OAuthAuthorizationCode = oauth_authorization_codes::table
+            .filter(oauth_authorization_codes::code_hash.eq(sha256::digest(code)))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading OAuth authorization code")?;
+        let consent = OAuthConsent::find(authorization_code.oauth_consent_id, conn)?;
+
+        if consent.oauth_client_id != oauth_client.id {
+            return DatabaseError::business_process_error("Authorization code was not issued to this client");
+        }
+        if authorization_code.used_at.is_some() {
+            consent.revoke(None, conn)?;
+            return DatabaseError::business_process_error("Authorization code has already been used");
+        }
+        if !consent.is_active() {
+            return DatabaseError::business_process_error("Authorization has been revoked");
+        }
+        if authorization_code.expires_at < Utc::now().naive_utc() {
+            return DatabaseError::business_process_error("Authorization code has expired");
+        }
+        if authorization_code.redirect_uri != redirect_uri {
+            return DatabaseError::business_process_error("Redirect URI does not match authorization request");
+        }
+        if !OAuthAuthorizationCode::verify_code_challenge(&authorization_code.code_challenge, code_verifier) {
+            return DatabaseError::business_process_error("Code verifier does not match code challenge");
+        }
+
+        // Conditional update guards against the code being redeemed concurrently
+        let result: Option<OAuthAuthorizationCode> = diesel::update(
+            oauth_authorization_codes::table
+                .filter(oauth_authorization_codes::id.eq(authorization_code.id))
+                .filter(oauth_authorization_codes::used_at.is_null()),
+        )
+        .set((
+            oauth_authorization_codes::used_at.eq(dsl::now.nullable()),
+            oauth_authorization_codes::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not redeem OAuth authorization code")?;
+
+        match result {
+            Some(authorization_code) => Ok((authorization_code, consent)),
+            None => DatabaseError::business_process_error("Authorization code has already been used"),
+        }
+    }
+
+    pub fn scopes(&self) -> Result<Vec<Scopes>, DatabaseError> {
+        let mut scopes = Vec::new();
+        for scope in &self.scopes {
+            scopes.push(Scopes::from_str(scope)?);
+        }
+        Ok(scopes)
+    }
+
+    /// Verifies an S256 PKCE code challenge, the challenge is the unpadded base64url encoded SHA-256
+    /// digest of the code verifier
+    pub fn verify_code_challenge(code_challenge: &str, code_verifier: &str) -> bool {
+        if code_verifier.len() < CODE_VERIFIER_MIN_LENGTH || code_verifier.len() > CODE_VERIFIER_MAX_LENGTH {
+            return false;
+        }
+
+        let verifier_digest = digest::digest(&digest::SHA256, code_verifier.as_bytes());
+        base64::encode_config(verifier_digest.as_ref(), base64::URL_SAFE_NO_PAD) == code_challenge
+    }
+}
+
+#[test]
+fn verify_code_challenge() {
+    // Example from RFC 7636 Appendix B
+    let code_verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    let code_challenge = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+    assert!(OAuthAuthorizationCode::verify_code_challenge(
+        code_challenge,
+        code_verifier
+    ));
+    assert!(!OAuthAuthorizationCode::verify_code_challenge(
+        code_challenge,
+        "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXj"
+    ));
+    assert!(!OAuthAuthorizationCode::verify_code_challenge(code_challenge, "short"));
+}
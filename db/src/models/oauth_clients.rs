@@ -0,0 +1,250 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{oauth_clients, oauth_consents};
+use std::str::FromStr;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validators::{self, *};
+
+const CLIENT_ID_LENGTH: usize = 32;
+const CLIENT_SECRET_LENGTH: usize = 48;
+
+/// Third-party application registered by an organization which users can authorize to act on their behalf.
+/// Confidential clients authenticate with a client secret, public clients rely on PKCE alone.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "oauth_clients"]
+pub struct OAuthClient {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub client_id: String,
+    #[serde(skip_serializing)]
+    pub client_secret_hash: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub created_by_user_id: Uuid,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "oauth_clients"]
+pub struct NewOAuthClient {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub client_id: String,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub created_by_user_id: Uuid,
+}
+
+impl NewOAuthClient {
+    pub fn commit(self, conn: &PgConnection) -> Result<OAuthClient, DatabaseError> {
+        self.validate_record()?;
+        let result: OAuthClient = diesel::insert_into(oauth_clients::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create OAuth client")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OAuthClientCreated,
+            format!("OAuth client '{}' created", result.name),
+            Tables::OAuthClients,
+            Some(result.id),
+            Some(result.created_by_user_id),
+            Some(json!({
+                "organization_id": result.organization_id,
+                "redirect_uris": result.redirect_uris,
+                "scopes": result.scopes
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if self.name.trim().is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "name",
+                Err(create_validation_error("required", "Name is required")),
+            );
+        }
+        if self.redirect_uris.is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "redirect_uris",
+                Err(create_validation_error(
+                    "required",
+                    "At least one redirect URI is required",
+                )),
+            );
+        } else {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "redirect_uris",
+                validate_urls(&self.redirect_uris),
+            );
+        }
+        if self.scopes.is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "scopes",
+                Err(create_validation_error("required", "At least one scope is required")),
+            );
+        } else if self.scopes.iter().any(|s| Scopes::from_str(s).is_err()) {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "scopes",
+                Err(create_validation_error("invalid_scope", "Scope is not recognized")),
+            );
+        }
+
+        Ok(validation_errors?)
+    }
+}
+
+impl OAuthClient {
+    pub fn create(
+        organization_id: Uuid,
+        name: String,
+        redirect_uris: Vec<String>,
+        scopes: Vec<Scopes>,
+        created_by_user_id: Uuid,
+    ) -> NewOAuthClient {
+        NewOAuthClient {
+            organization_id,
+            name,
+            client_id: random_alpha_string(CLIENT_ID_LENGTH),
+            redirect_uris,
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            created_by_user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OAuthClient, DatabaseError> {
+        oauth_clients::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading OAuth client")
+    }
+
+    /// Finds the client by its public identifier, revoked clients are not returned
+    pub fn find_active_by_client_id(client_id: &str, conn: &PgConnection) -> Result<OAuthClient, DatabaseError> {
+        oauth_clients::table
+            .filter(oauth_clients::client_id.eq(client_id))
+            .filter(oauth_clients::revoked_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading OAuth client")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OAuthClient>, DatabaseError> {
+        oauth_clients::table
+            .filter(oauth_clients::organization_id.eq(organization_id))
+            .order_by(oauth_clients::created_at)
+            .then_order_by(oauth_clients::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load OAuth clients for organization")
+    }
+
+    pub fn scopes(&self) -> Result<Vec<Scopes>, DatabaseError> {
+        let mut scopes = Vec::new();
+        for scope in &self.scopes {
+            scopes.push(Scopes::from_str(scope)?);
+        }
+        Ok(scopes)
+    }
+
+    pub fn is_confidential(&self) -> bool {
+        self.client_secret_hash.is_some()
+    }
+
+    /// Confidential clients must present their secret, public clients must not have one
+    pub fn authenticate(&self, client_secret: Option<&str>) -> bool {
+        match (&self.client_secret_hash, client_secret) {
+            (Some(client_secret_hash), Some(client_secret)) => *client_secret_hash == sha256::digest(client_secret),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Redirect URIs must match one of the registered URIs exactly
+    pub fn is_valid_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|r| r == redirect_uri)
+    }
+
+    /// Generates a new client secret making the client confidential, any previous secret stops working
+    pub fn generate_secret(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(OAuthClient, String), DatabaseError> {
+        let client_secret = random_alpha_string(CLIENT_SECRET_LENGTH);
+        let result: OAuthClient = diesel::update(self)
+            .set((
+                oauth_clients::client_secret_hash.eq(sha256::digest(&client_secret)),
+                oauth_clients::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not generate OAuth client secret")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OAuthClientSecretGenerated,
+            format!("OAuth client '{}' secret generated", result.name),
+            Tables::OAuthClients,
+            Some(result.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok((result, client_secret))
+    }
+
+    /// Revokes the client along with every consent granted to it
+    pub fn revoke(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<OAuthClient, DatabaseError> {
+        if self.revoked_at.is_some() {
+            return Ok(self.clone());
+        }
+
+        let result: OAuthClient = diesel::update(self)
+            .set((
+                oauth_clients::revoked_at.eq(dsl::now.nullable()),
+                oauth_clients::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke OAuth client")?;
+
+        let consents: Vec<OAuthConsent> = oauth_consents::table
+            .filter(oauth_consents::oauth_client_id.eq(self.id))
+            .filter(oauth_consents::revoked_at.is_null())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load OAuth consents for client")?;
+        for consent in consents {
+            consent.revoke(current_user_id, conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::OAuthClientRevoked,
+            format!("OAuth client '{}' revoked", result.name),
+            Tables::OAuthClients,
+            Some(result.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
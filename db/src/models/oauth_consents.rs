@@ -0,0 +1,179 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{oauth_clients, oauth_consents};
+use std::str::FromStr;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Record of the scopes a user has granted to an OAuth client. Tokens issued to the client reference
+/// the consent so revoking it invalidates them.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "oauth_consents"]
+pub struct OAuthConsent {
+    pub id: Uuid,
+    pub oauth_client_id: Uuid,
+    pub user_id: Uuid,
+    pub scopes: Vec<String>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "oauth_consents"]
+pub struct NewOAuthConsent {
+    pub oauth_client_id: Uuid,
+    pub user_id: Uuid,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayOAuthConsent {
+    pub id: Uuid,
+    pub client_name: String,
+    pub organization_id: Uuid,
+    pub scopes: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl OAuthConsent {
+    /// Records the user's consent to the client, adding the scopes to any consent already granted
+    pub fn grant(
+        oauth_client: &OAuthClient,
+        user_id: Uuid,
+        scopes: Vec<Scopes>,
+        conn: &PgConnection,
+    ) -> Result<OAuthConsent, DatabaseError> {
+        let client_scopes = oauth_client.scopes()?;
+        if scopes.is_empty() || scopes.iter().any(|s| !client_scopes.contains(s)) {
+            return DatabaseError::business_process_error("Requested scopes are not allowed for this client");
+        }
+
+        let existing_consent = OAuthConsent::find_active(oauth_client.id, user_id, conn).optional()?;
+        let result: OAuthConsent = match existing_consent {
+            Some(consent) => {
+                let mut granted_scopes = consent.scopes()?;
+                granted_scopes.extend(scopes);
+                granted_scopes.sort();
+                granted_scopes.dedup();
+                let granted_scopes: Vec<String> = granted_scopes.iter().map(|s| s.to_string()).collect();
+                if granted_scopes == consent.scopes {
+                    return Ok(consent);
+                }
+
+                diesel::update(&consent)
+                    .set((
+                        oauth_consents::scopes.eq(granted_scopes),
+                        oauth_consents::updated_at.eq(dsl::now),
+                    ))
+                    .get_result(conn)
+                    .to_db_error(ErrorCode::UpdateError, "Could not update OAuth consent")?
+            }
+            None => diesel::insert_into(oauth_consents::table)
+                .values(NewOAuthConsent {
+                    oauth_client_id: oauth_client.id,
+                    user_id,
+                    scopes: scopes.iter().map(|s| s.to_string()).collect(),
+                })
+                .get_result(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create OAuth consent")?,
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::OAuthConsentGranted,
+            format!("Access granted to OAuth client '{}'", oauth_client.name),
+            Tables::OAuthConsents,
+            Some(result.id),
+            Some(user_id),
+            Some(json!({ "oauth_client_id": oauth_client.id, "scopes": result.scopes })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OAuthConsent, DatabaseError> {
+        oauth_consents::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading OAuth consent")
+    }
+
+    pub fn find_active(
+        oauth_client_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OAuthConsent, DatabaseError> {
+        oauth_consents::table
+            .filter(oauth_consents::oauth_client_id.eq(oauth_client_id))
+            .filter(oauth_consents::user_id.eq(user_id))
+            .filter(oauth_consents::revoked_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading OAuth consent")
+    }
+
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<DisplayOAuthConsent>, DatabaseError> {
+        let results: Vec<(OAuthConsent, OAuthClient)> = oauth_consents::table
+            .inner_join(oauth_clients::table)
+            .filter(oauth_consents::user_id.eq(user_id))
+            .filter(oauth_consents::revoked_at.is_null())
+            .order_by(oauth_consents::created_at)
+            .then_order_by(oauth_consents::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load OAuth consents for user")?;
+
+        Ok(results
+            .into_iter()
+            .map(|(consent, client)| DisplayOAuthConsent {
+                id: consent.id,
+                client_name: client.name,
+                organization_id: client.organization_id,
+                scopes: consent.scopes,
+                created_at: consent.created_at,
+                updated_at: consent.updated_at,
+            })
+            .collect())
+    }
+
+    pub fn scopes(&self) -> Result<Vec<Scopes>, DatabaseError> {
+        let mut scopes = Vec::new();
+        for scope in &self.scopes {
+            scopes.push(Scopes::from_str(scope)?);
+        }
+        Ok(scopes)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    pub fn revoke(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<OAuthConsent, DatabaseError> {
+        if self.revoked_at.is_some() {
+            return Ok(self.clone());
+        }
+
+        let result: OAuthConsent = diesel::update(self)
+            .set((
+                oauth_consents::revoked_at.eq(dsl::now.nullable()),
+                oauth_consents::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke OAuth consent")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OAuthConsentRevoked,
+            "OAuth consent revoked".to_string(),
+            Tables::OAuthConsents,
+            Some(result.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::prelude::*;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use models::*;
+use schema::{assets, ticket_instances, ticket_types};
+use tari_client::*;
+use utils::errors::*;
+use utils::hash::hmac_sha256;
+use uuid::Uuid;
+
+/// QR code payload for a ticket. The signature is made with the event's `EventRedemptionKey` so
+/// scanners holding the event's public key can verify it without a connection.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SignedTicketPayload {
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub redeem_key: String,
+    pub signature: String,
+}
+
+/// Signed snapshot of the redeemable tickets for an event exported to scanners before doors open.
+/// Redeem keys are only included as hashes, see `OfflineRedemptionTicket::redeem_key_hash`.
+/// The bundle does not carry its public key or hash secret, scanners verify it with the key from
+/// `OfflineRedemptionKey` which they fetch separately.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemptionBundle {
+    pub event_id: Uuid,
+    pub generated_at: NaiveDateTime,
+    pub tickets: Vec<OfflineRedemptionTicket>,
+    pub signature: String,
+}
+
+/// Public key scanners use to verify the offline redemption data of an event, and the secret they
+/// hash scanned redeem keys with to compare them against the bundle
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemptionKey {
+    pub event_id: Uuid,
+    pub public_key: String,
+    pub hash_secret: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemptionTicket {
+    pub ticket_instance_id: Uuid,
+    pub redeem_key_hash: String,
+    pub redeemed: bool,
+}
+
+/// Check in recorded by a scanner while offline
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemption {
+    pub ticket_instance_id: Uuid,
+    pub redeem_key: String,
+    pub redeemed_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemptionResult {
+    pub ticket_instance_id: Uuid,
+    pub status: OfflineRedemptionStatus,
+    pub redeemed_at: NaiveDateTime,
+    pub first_redeemed_at: Option<NaiveDateTime>,
+    pub first_redeemed_by_user_id: Option<Uuid>,
+}
+
+impl SignedTicketPayload {
+    pub fn create(ticket: &TicketInstance, conn: &PgConnection) -> Result<SignedTicketPayload, DatabaseError> {
+        let redeem_key = match ticket.redeem_key.clone() {
+            Some(redeem_key) => redeem_key,
+            None => return DatabaseError::business_process_error("Ticket does not have a redeem key"),
+        };
+        let event_id = ticket.ticket_type(conn)?.event_id;
+        let redemption_key = EventRedemptionKey::find_or_create_for_event(event_id, conn)?;
+        let message = SignedTicketPayload::message(ticket.id, event_id, &redeem_key);
+
+        Ok(SignedTicketPayload {
+            ticket_instance_id: ticket.id,
+            event_id,
+            redeem_key,
+            signature: redemption_key.sign(&message)?,
+        })
+    }
+
+    fn message(ticket_instance_id: Uuid, event_id: Uuid, redeem_key: &str) -> String {
+        let mut message: String = ticket_instance_id.to_string();
+        message.push_str(event_id.to_string().as_str());
+        message.push_str(redeem_key);
+        message
+    }
+
+    pub fn verify(&self, public_key: &String) -> bool {
+        cryptographic_verify(
+            &convert_hexstring_to_bytes(&self.signature),
+            &SignedTicketPayload::message(self.ticket_instance_id, self.event_id, &self.redeem_key),
+            &convert_hexstring_to_bytes(public_key),
+        )
+    }
+}
+
+impl OfflineRedemptionBundle {
+    pub fn create_for_event(event: &Event, conn: &PgConnection) -> Result<OfflineRedemptionBundle, DatabaseError> {
+        let rows: Vec<(Uuid, Option<String>, TicketInstanceStatus)> = ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+            .filter(ticket_types::event_id.eq(event.id))
+            .filter(
+                ticket_instances::status
+                    .eq(TicketInstanceStatus::Purchased)
+                    .or(ticket_instances::status.eq(TicketInstanceStatus::Redeemed)),
+            )
+            .filter(ticket_instances::redeem_key.is_not_null())
+            .filter(sql::<Bool>(
+                "NOT EXISTS (
+                    SELECT 1
+                    FROM transfer_tickets tt
+                    JOIN transfers t ON tt.transfer_id = t.id
+                    WHERE tt.ticket_instance_id = ticket_instances.id
+                    AND t.status = 'Pending'
+                )",
+            ))
+            .select((
+                ticket_instances::id,
+                ticket_instances::redeem_key,
+                ticket_instances::status,
+            ))
+            .order_by(ticket_instances::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tickets for offline redemption")?;
+
+        let redemption_key = EventRedemptionKey::find_or_create_for_event(event.id, conn)?;
+        let tickets = rows
+            .into_iter()
+            .map(|(ticket_instance_id, redeem_key, status)| OfflineRedemptionTicket {
+                ticket_instance_id,
+                redeem_key_hash: OfflineRedemptionTicket::redeem_key_hash(
+                    &redemption_key.hash_secret,
+                    ticket_instance_id,
+                    &redeem_key.unwrap_or("".to_string()),
+                ),
+                redeemed: status == TicketInstanceStatus::Redeemed,
+            })
+            .collect();
+
+        let mut bundle = OfflineRedemptionBundle {
+            event_id: event.id,
+            generated_at: Utc::now().naive_utc(),
+            tickets,
+            signature: "".to_string(),
+        };
+        bundle.signature = redemption_key.sign(&bundle.message())?;
+
+        Ok(bundle)
+    }
+
+    /// Message signed for the bundle, the event, generation time and every ticket entry in order
+    fn message(&self) -> String {
+        let mut message: String = self.event_id.to_string();
+        message.push_str(self.generated_at.timestamp().to_string().as_str());
+        for ticket in &self.tickets {
+            message.push_str(ticket.ticket_instance_id.to_string().as_str());
+            message.push_str(ticket.redeem_key_hash.as_str());
+            message.push_str(ticket.redeemed.to_string().as_str());
+        }
+        message
+    }
+
+    /// Verifies the bundle against the event's public key. The key must come from a trusted source,
+    /// such as `OfflineRedemptionKey` fetched when the scanner was set up for the event.
+    pub fn verify(&self, public_key: &String) -> bool {
+        cryptographic_verify(
+            &convert_hexstring_to_bytes(&self.signature),
+            &self.message(),
+            &convert_hexstring_to_bytes(public_key),
+        )
+    }
+}
+
+impl OfflineRedemptionKey {
+    pub fn for_event(event: &Event, conn: &PgConnection) -> Result<OfflineRedemptionKey, DatabaseError> {
+        let redemption_key = EventRedemptionKey::find_or_create_for_event(event.id, conn)?;
+        Ok(OfflineRedemptionKey {
+            event_id: event.id,
+            public_key: redemption_key.public_key,
+            hash_secret: redemption_key.hash_secret,
+        })
+    }
+}
+
+impl OfflineRedemptionTicket {
+    /// Hash scanners compare against a scanned redeem key. Redeem keys are short so the hash is an
+    /// HMAC keyed with the event's hash secret rather than a plain hash anyone holding the bundle
+    /// could reverse by trying every key. The ticket id is included so equal redeem keys on
+    /// different tickets do not share a hash.
+    pub fn redeem_key_hash(hash_secret: &str, ticket_instance_id: Uuid, redeem_key: &str) -> String {
+        let mut message: String = ticket_instance_id.to_string();
+        message.push_str(redeem_key);
+        hmac_sha256::sign(hash_secret, &message)
+    }
+}
+
+impl OfflineRedemption {
+    /// Reconciles check ins made offline. Redemptions are applied in the order they were scanned,
+    /// the earliest scan of a ticket wins and any later scan is reported as a double scan.
+    pub fn sync(
+        event_id: Uuid,
+        mut redemptions: Vec<OfflineRedemption>,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OfflineRedemptionResult>, DatabaseError> {
+        redemptions.sort_by_key(|r| r.redeemed_at);
+        let now = Utc::now().naive_utc();
+        let mut results: Vec<OfflineRedemptionResult> = vec![];
+
+        for redemption in redemptions {
+            // Scanner clocks can drift, check ins cannot be recorded in the future
+            let redeemed_at = if redemption.redeemed_at > now {
+                now
+            } else {
+                redemption.redeemed_at
+            };
+
+            let belongs_to_event = TicketInstance::find_for_processing(redemption.ticket_instance_id, event_id, conn)
+                .optional()?
+                .is_some();
+            let status = if !belongs_to_event {
+                OfflineRedemptionStatus::Invalid
+            } else {
+                match TicketInstance::redeem_ticket_at(
+                    redemption.ticket_instance_id,
+                    redemption.redeem_key.clone(),
+                    user_id,
+                    CheckInSource::Scanned,
+                    redeemed_at,
+                    conn,
+                )? {
+                    RedeemResults::TicketRedeemSuccess => OfflineRedemptionStatus::Redeemed,
+                    RedeemResults::TicketAlreadyRedeemed => OfflineRedemptionStatus::DoubleScan,
+                    RedeemResults::TicketInvalid => OfflineRedemptionStatus::Invalid,
+                    RedeemResults::TicketTransferInProcess => OfflineRedemptionStatus::TransferInProcess,
+                    RedeemResults::TicketListedForResale => OfflineRedemptionStatus::ListedForResale,
+                }
+            };
+
+            let (first_redeemed_at, first_redeemed_by_user_id) = if status == OfflineRedemptionStatus::DoubleScan {
+                let ticket = TicketInstance::find(redemption.ticket_instance_id, conn)?;
+                (ticket.redeemed_at, ticket.redeemed_by_user_id)
+            } else {
+                (None, None)
+            };
+
+            results.push(OfflineRedemptionResult {
+                ticket_instance_id: redemption.ticket_instance_id,
+                status,
+                redeemed_at,
+                first_redeemed_at,
+                first_redeemed_by_user_id,
+            });
+        }
+
+        Ok(results)
+    }
+}
@@ -39,6 +39,12 @@ pub struct OrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub season_pass_id: Option<Uuid>,
+    pub settlement_id: Option<Uuid>,
+    pub currency: String,
+    pub tax_rule_id: Option<Uuid>,
+    /// Tax already contained in the unit price of the parent item when the tax rule is inclusive
+    pub included_tax_in_cents: i64,
 }
 
 impl OrderItem {
@@ -67,6 +73,15 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item discount")
     }
 
+    pub fn find_tax_item(&self, conn: &PgConnection) -> Result<Option<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item tax")
+    }
+
     pub fn description(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
         use models::OrderItemTypes::*;
         let res = match self.item_type {
@@ -80,6 +95,21 @@ impl OrderItem {
             }
             Discount => "Discount".to_string(),
             CreditCardFees => "Credit Card Fees".to_string(),
+            Tax => match self.tax_rule_id {
+                Some(tax_rule_id) => TaxRule::find(tax_rule_id, conn)?.name,
+                None => "Tax".to_string(),
+            },
+            SeasonPasses => match self.season_pass_id {
+                Some(season_pass_id) => format!("Season Pass - {}", SeasonPass::find(season_pass_id, conn)?.name),
+                None => "Season Pass".to_string(),
+            },
+            ResaleTickets => {
+                let ticket_type = self.ticket_type(conn)?;
+                match ticket_type {
+                    Some(t) => format!("{} - {} (Resale)", t.event(conn)?.name, t.name),
+                    None => "Resale Ticket".to_string(),
+                }
+            }
             _ => {
                 let ticket_type = self.ticket_type(conn)?;
                 match ticket_type {
@@ -114,8 +144,13 @@ impl OrderItem {
         }
 
         let mut refund_amount_in_cents = self.unit_price_in_cents + discount_amount;
+        // Tax is refunded in proportion to the units refunded
+        if let Some(mut tax_item) = self.find_tax_item(conn)? {
+            refund_amount_in_cents += tax_item.refund_one_unit(true, conn)?;
+        }
         // Refund fees if ticket is being refunded
-        if refund_fees && self.item_type == OrderItemTypes::Tickets {
+        if refund_fees && (self.item_type == OrderItemTypes::Tickets || self.item_type == OrderItemTypes::SeasonPasses)
+        {
             let fee_item = self.find_fee_item(conn)?;
             if let Some(mut fee_item) = fee_item {
                 refund_amount_in_cents += fee_item.refund_one_unit(true, conn)?;
@@ -156,6 +191,7 @@ impl OrderItem {
             || self.item_type == OrderItemTypes::EventFees
             || self.item_type == OrderItemTypes::Discount
             || self.item_type == OrderItemTypes::CreditCardFees
+            || self.item_type == OrderItemTypes::Tax
         {
             return Ok(());
         }
@@ -229,7 +265,9 @@ impl OrderItem {
     }
 
     pub(crate) fn update_fees(&self, order: &Order, conn: &PgConnection) -> Result<(), DatabaseError> {
-        if self.item_type != OrderItemTypes::Tickets {
+        if self.item_type == OrderItemTypes::SeasonPasses {
+            return self.update_season_pass_fees(conn);
+        } else if self.item_type != OrderItemTypes::Tickets {
             return Ok(());
         }
 
@@ -303,6 +341,78 @@ impl OrderItem {
         }
     }
 
+    /// Adds tax for this item under the given rule, existing tax items are removed by the order beforehand
+    pub(crate) fn update_tax(&self, tax_rule: &TaxRule, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let tax_in_cents = match self.item_type {
+            OrderItemTypes::Tickets | OrderItemTypes::ResaleTickets => {
+                let unit_price_with_discount = match self.find_discount_item(conn)? {
+                    Some(di) => self.unit_price_in_cents + di.unit_price_in_cents,
+                    None => self.unit_price_in_cents,
+                };
+                tax_rule.tax_in_cents(unit_price_with_discount, tax_rule.ticket_tax_percent)
+            }
+            item_type if item_type.is_fee() => {
+                tax_rule.tax_in_cents(self.unit_price_in_cents, tax_rule.fee_tax_percent)
+            }
+            _ => 0,
+        };
+
+        if tax_in_cents <= 0 {
+            return Ok(());
+        }
+
+        NewTaxOrderItem {
+            order_id: self.order_id,
+            item_type: OrderItemTypes::Tax,
+            event_id: self.event_id,
+            quantity: self.quantity,
+            unit_price_in_cents: if tax_rule.inclusive { 0 } else { tax_in_cents },
+            included_tax_in_cents: if tax_rule.inclusive { tax_in_cents } else { 0 },
+            tax_rule_id: Some(tax_rule.id),
+            parent_id: Some(self.id),
+        }
+        .commit(conn)?;
+        Ok(())
+    }
+
+    /// Season passes are not tied to an event so fees come from the owning organization's fee schedule
+    fn update_season_pass_fees(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let season_pass = match self.season_pass_id {
+            Some(season_pass_id) => SeasonPass::find(season_pass_id, conn)?,
+            None => {
+                return DatabaseError::no_results("Order item does not have a valid season pass");
+            }
+        };
+        let organization = Organization::find(season_pass.organization_id, conn)?;
+        let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, conn)?;
+        let fee_schedule_range = fee_schedule.get_range(self.unit_price_in_cents, conn).optional()?;
+
+        match (self.find_fee_item(conn)?, fee_schedule_range) {
+            (Some(mut fee_item), Some(fee_schedule_range)) => {
+                fee_item.quantity = self.quantity;
+                fee_item.unit_price_in_cents = fee_schedule_range.fee_in_cents;
+                fee_item.update(conn)
+            }
+            (None, Some(fee_schedule_range)) => {
+                NewFeesOrderItem {
+                    order_id: self.order_id,
+                    item_type: OrderItemTypes::PerUnitFees,
+                    event_id: None,
+                    unit_price_in_cents: fee_schedule_range.fee_in_cents,
+                    fee_schedule_range_id: Some(fee_schedule_range.id),
+                    company_fee_in_cents: fee_schedule_range.company_fee_in_cents,
+                    client_fee_in_cents: fee_schedule_range.client_fee_in_cents,
+                    quantity: self.quantity,
+                    parent_id: Some(self.id),
+                }
+                .commit(conn)?;
+                Ok(())
+            }
+            (Some(fee_item), None) => self.order(conn)?.destroy_item(fee_item.id, conn),
+            (None, None) => Ok(()),
+        }
+    }
+
     pub(crate) fn update(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         self.validate_record(conn)?;
         diesel::update(self)
@@ -468,6 +578,8 @@ impl OrderItem {
             refunded_quantity: i64,
             #[sql_type = "BigInt"]
             unit_price_in_cents: i64,
+            #[sql_type = "BigInt"]
+            included_tax_in_cents: i64,
             #[sql_type = "Text"]
             item_type: OrderItemTypes,
             #[sql_type = "Text"]
@@ -476,8 +588,10 @@ impl OrderItem {
             redemption_code: Option<String>,
             #[sql_type = "Nullable<Text>"]
             cart_item_status: Option<CartItemStatus>,
-            #[sql_type = "dUuid"]
-            event_id: Uuid,
+            #[sql_type = "Nullable<dUuid>"]
+            event_id: Option<Uuid>,
+            #[sql_type = "Nullable<dUuid>"]
+            season_pass_id: Option<Uuid>,
             #[sql_type = "dUuid"]
             order_id: Uuid,
         }
@@ -487,17 +601,21 @@ impl OrderItem {
         SELECT
            oi.id,
            oi.parent_id,
-           tt.id                      AS ticket_type_id,
+           COALESCE(tt.id, rtt.id)    AS ticket_type_id,
            tp.id                      AS ticket_pricing_id,
            oi.quantity,
            oi.refunded_quantity,
            oi.unit_price_in_cents,
+           oi.included_tax_in_cents,
            oi.item_type,
            CASE
              WHEN item_type = 'PerUnitFees' THEN 'Ticket Fees'
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
              WHEN item_type = 'Discount' THEN 'Discount'
              WHEN item_type = 'CreditCardFees' THEN 'Credit Card Fees'
+             WHEN item_type = 'Tax' THEN COALESCE(tr.name, 'Tax')
+             WHEN item_type = 'ResaleTickets' THEN e.name || ' - ' || rtt.name || ' (Resale)'
+             WHEN item_type = 'SeasonPasses' THEN 'Season Pass - ' || sp.name
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
@@ -512,6 +630,7 @@ impl OrderItem {
              ELSE 'Valid'
            END AS cart_item_status,
            e.id AS event_id,
+           oi.season_pass_id,
            oi.order_id
         FROM order_items oi
            JOIN orders o ON oi.order_id = o.id
@@ -520,6 +639,8 @@ impl OrderItem {
            LEFT JOIN users u on u.id = $3
            LEFT JOIN organization_users ou ON ou.organization_id = e.organization_id and ou.user_id = $3
            LEFT JOIN ticket_types tt ON tp.ticket_type_id = tt.id
+           -- Resale items are not priced from ticket pricing
+           LEFT JOIN ticket_types rtt ON oi.item_type = 'ResaleTickets' AND oi.ticket_type_id = rtt.id
            LEFT JOIN holds h ON oi.hold_id = h.id
            LEFT JOIN event_users ep ON u.id = ep.user_id and ep.event_id = e.id
            LEFT JOIN ticket_instances ti ON ti.id = (
@@ -532,6 +653,8 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN season_passes sp ON oi.season_pass_id = sp.id
+           LEFT JOIN tax_rules tr ON oi.tax_rule_id = tr.id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -575,11 +698,13 @@ impl OrderItem {
                     quantity: item.quantity,
                     refunded_quantity: item.refunded_quantity,
                     unit_price_in_cents: item.unit_price_in_cents,
+                    included_tax_in_cents: item.included_tax_in_cents,
                     item_type: item.item_type,
                     description: item.description,
                     redemption_code: item.redemption_code,
                     cart_item_status: item.cart_item_status,
                     event_id: item.event_id,
+                    season_pass_id: item.season_pass_id,
                 });
             }
             order_items.insert(order_id, display_items);
@@ -635,8 +760,9 @@ pub(crate) struct NewTicketsOrderItem {
 impl NewTicketsOrderItem {
     pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
         self.validate_record(conn)?;
+        let currency = Order::currency_for(self.order_id, conn)?;
         diesel::insert_into(order_items::table)
-            .values(self)
+            .values((self, order_items::currency.eq(currency)))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create order item")
     }
@@ -683,8 +809,9 @@ pub(crate) struct NewFeesOrderItem {
 
 impl NewFeesOrderItem {
     pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
         diesel::insert_into(order_items::table)
-            .values(self)
+            .values((self, order_items::currency.eq(currency)))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create order item")
     }
@@ -705,8 +832,75 @@ pub(crate) struct NewDiscountOrderItem {
 
 impl NewDiscountOrderItem {
     pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
+        diesel::insert_into(order_items::table)
+            .values((self, order_items::currency.eq(currency)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewResaleTicketsOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub ticket_type_id: Uuid,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub company_fee_in_cents: i64,
+    pub client_fee_in_cents: i64,
+}
+
+impl NewResaleTicketsOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
         diesel::insert_into(order_items::table)
-            .values(self)
+            .values((self, order_items::currency.eq(currency)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewSeasonPassOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub season_pass_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+}
+
+impl NewSeasonPassOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
+        diesel::insert_into(order_items::table)
+            .values((self, order_items::currency.eq(currency)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub included_tax_in_cents: i64,
+    pub tax_rule_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
+        diesel::insert_into(order_items::table)
+            .values((self, order_items::currency.eq(currency)))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create order item")
     }
@@ -728,6 +922,8 @@ pub struct DisplayOrderItem {
     pub refunded_quantity: i64,
     #[sql_type = "BigInt"]
     pub unit_price_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub included_tax_in_cents: i64,
     #[sql_type = "Text"]
     pub item_type: OrderItemTypes,
     #[sql_type = "Text"]
@@ -738,6 +934,10 @@ pub struct DisplayOrderItem {
     #[serde(skip_deserializing)]
     #[sql_type = "Nullable<Text>"]
     pub cart_item_status: Option<CartItemStatus>,
-    #[sql_type = "dUuid"]
-    pub event_id: Uuid,
+    /// Null for season pass items as they are not sold for a single event, items for tickets and their fees
+    /// always have an event. Clients reading `event_id` need to handle null once season passes are on sale.
+    #[sql_type = "Nullable<dUuid>"]
+    pub event_id: Option<Uuid>,
+    #[sql_type = "Nullable<dUuid>"]
+    pub season_pass_id: Option<Uuid>,
 }
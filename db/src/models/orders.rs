@@ -12,7 +12,7 @@ use log::Level::{self, Debug};
 use models::*;
 use schema::{
     event_users, events, order_items, order_transfers, orders, organization_users, organizations, payments, refunds,
-    transfers, users,
+    season_passes, transfers, users,
 };
 use serde_json;
 use serde_json::Value;
@@ -26,7 +26,6 @@ use utils::iterators::*;
 use utils::regexes;
 use uuid::Uuid;
 use validator::*;
-use validators::*;
 
 pub const CART_EXPIRY_TIME_MINUTES: i64 = 15;
 const ORDER_NUMBER_LENGTH: usize = 8;
@@ -62,6 +61,8 @@ pub struct Order {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub referrer: Option<String>,
+    pub disputed_at: Option<NaiveDateTime>,
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -131,36 +132,6 @@ struct MatchData<'a> {
 }
 
 impl Order {
-    pub fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
-        let validation_errors = append_validation_error(
-            Ok(()),
-            "event_id",
-            Order::order_contains_items_from_only_one_event(self.id, conn)?,
-        );
-
-        Ok(validation_errors?)
-    }
-
-    pub fn order_contains_items_from_only_one_event(
-        id: Uuid,
-        conn: &PgConnection,
-    ) -> Result<Result<(), ValidationError>, DatabaseError> {
-        let event_count = order_items::table
-            .filter(order_items::order_id.eq(id))
-            .filter(order_items::event_id.is_not_null())
-            .select(sql::<BigInt>("count(distinct event_id) AS event_count"))
-            .get_result::<i64>(conn)
-            .to_db_error(ErrorCode::QueryError, "Could not get count of unique events in cart")?;
-
-        if event_count > 1 {
-            let mut validation_error =
-                create_validation_error("cart_event_limit_reached", "Cart limited to one event for purchasing");
-            validation_error.add_param(Cow::from("order_id"), &id);
-            return Ok(Err(validation_error.into()));
-        }
-        Ok(Ok(()))
-    }
-
     pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
         let cart_user: Option<User> = users::table
             .filter(users::last_cart_id.eq(self.id))
@@ -202,7 +173,15 @@ impl Order {
             );
         }
 
-        // delete children order items
+        // delete children order items, fee items can have tax items of their own
+        let child_ids: Vec<Uuid> = order_items::table
+            .filter(order_items::parent_id.eq(item_id))
+            .select(order_items::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child order items")?;
+        diesel::delete(order_items::table.filter(order_items::parent_id.eq_any(&child_ids)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
             .map(|_| ())
@@ -230,11 +209,7 @@ impl Order {
         event_ids.sort();
         event_ids.dedup();
 
-        if event_ids.length() > 1 {
-            //Currently we only allow a single event per order, so this should never be more than 1
-            jlog!(Level::Warn, "Found more than 1 event in an order, Orders::event_slug() is at least 1 place that needs to be updated to allow for more than a single event per cart.");
-        }
-
+        // Orders can span several events, links back to the event use the first one
         if event_ids.length() > 0 {
             let slug = Event::find(event_ids[0], conn)?.slug(conn)?;
             return Ok(slug);
@@ -286,6 +261,12 @@ impl Order {
             let mut order_item = OrderItem::find(refund_datum.order_item_id, conn)?;
             if order_item.item_type == OrderItemTypes::Discount {
                 return DatabaseError::business_process_error("Discount order items can not be refunded");
+            } else if order_item.item_type == OrderItemTypes::Tax {
+                return DatabaseError::business_process_error(
+                    "Tax order items can not be refunded, they are refunded with the item they apply to",
+                );
+            } else if order_item.item_type == OrderItemTypes::ResaleTickets {
+                return DatabaseError::business_process_error("Resale order items can not be refunded");
             } else if order_item.order_id != self.id {
                 return DatabaseError::business_process_error("Order item id does not belong to this order");
             }
@@ -308,6 +289,26 @@ impl Order {
                             Order::refund_ticket_instance(&ticket_instance, &mut order_item, user_id, conn)?;
                     }
                 }
+            } else if order_item.item_type == OrderItemTypes::SeasonPasses {
+                // Refund the first entitlement that has not been used yet
+                let mut refundable_entitlement: Option<SeasonPassEntitlement> = None;
+                for entitlement in SeasonPassEntitlement::find_for_order_item(order_item.id, conn)? {
+                    if entitlement.is_refundable(conn)? {
+                        refundable_entitlement = Some(entitlement);
+                        break;
+                    }
+                }
+                match refundable_entitlement {
+                    Some(entitlement) => {
+                        entitlement.refund(Some(user_id), conn)?;
+                    }
+                    None => {
+                        return DatabaseError::business_process_error(
+                            "No refundable season passes remain on order item",
+                        );
+                    }
+                }
+                total_to_be_refunded += order_item.refund_one_unit(true, conn)?;
             } else {
                 total_to_be_refunded += order_item.refund_one_unit(true, conn)?;
             }
@@ -374,6 +375,7 @@ impl Order {
         if ticket_instance.status == TicketInstanceStatus::Purchased && order_item.item_type == OrderItemTypes::Tickets
         {
             ticket_instance.release(TicketInstanceStatus::Purchased, user_id, conn)?;
+            WaitlistEntry::offer_available_tickets(ticket_instance.ticket_type(conn)?.id, Some(user_id), conn)?;
         }
 
         order_item.refund_one_unit(refund_fees, conn)
@@ -456,7 +458,7 @@ impl Order {
     }
 
     pub fn organizations(&self, conn: &PgConnection) -> Result<Vec<Organization>, DatabaseError> {
-        organizations::table
+        let mut organizations: Vec<Organization> = organizations::table
             .inner_join(events::table.on(events::organization_id.eq(organizations::id)))
             .inner_join(order_items::table.on(order_items::event_id.eq(events::id.nullable())))
             .filter(order_items::order_id.eq(self.id))
@@ -464,7 +466,24 @@ impl Order {
             .order_by(organizations::name.asc())
             .distinct()
             .load(conn)
-            .to_db_error(ErrorCode::QueryError, "Error loading organizations")
+            .to_db_error(ErrorCode::QueryError, "Error loading organizations")?;
+
+        // Season passes are not linked to an event
+        let season_pass_organizations: Vec<Organization> = organizations::table
+            .inner_join(season_passes::table.on(season_passes::organization_id.eq(organizations::id)))
+            .inner_join(order_items::table.on(order_items::season_pass_id.eq(season_passes::id.nullable())))
+            .filter(order_items::order_id.eq(self.id))
+            .select(organizations::all_columns)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading organizations")?;
+        if !season_pass_organizations.is_empty() {
+            organizations.extend(season_pass_organizations);
+            organizations.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+            organizations.dedup_by_key(|o| o.id);
+        }
+
+        Ok(organizations)
     }
 
     pub fn is_expired(&self) -> bool {
@@ -489,13 +508,27 @@ impl Order {
         }
 
         for item in self.items(conn)? {
-            if item.item_type != OrderItemTypes::Tickets {
+            if item.item_type == OrderItemTypes::ResaleTickets {
+                match ResaleListing::find_for_order_item(item.id, conn)? {
+                    Some(listing) => {
+                        listing.reserve(item.id, new_expires_at, conn)?;
+                    }
+                    None => return DatabaseError::business_process_error("Resale listing is no longer available"),
+                }
+                continue;
+            } else if item.item_type != OrderItemTypes::Tickets {
                 continue;
             } else if item.ticket_type_id.is_none() {
                 // Sanity check given unwrap below
                 return DatabaseError::business_process_error("Ticket type required for order refresh");
             }
 
+            // Seated items attempt to reserve the same seats again
+            let seat_ids: Vec<Uuid> = TicketInstance::find_for_order_item(item.id, conn)?
+                .iter()
+                .filter_map(|t| t.seat_id)
+                .collect();
+
             // Sanity check: clear unexpired tickets (should affect 0; it inherits expires_at from order)
             let quantity = item.calculate_quantity(conn)?;
             TicketInstance::release_tickets(&item, quantity as u32, current_user_id, conn)?;
@@ -507,6 +540,11 @@ impl Order {
                 item.ticket_type_id.unwrap(),
                 item.hold_id,
                 item.quantity as u32,
+                if seat_ids.len() as i64 == item.quantity {
+                    Some(seat_ids)
+                } else {
+                    None
+                },
                 conn,
             )?;
         }
@@ -884,11 +922,11 @@ impl Order {
             total: i64,
         }
 
-        let limit = paging.limit.unwrap_or(100) as i64;
+        let limit = paging.limit() as i64;
         query = query
             .sql(format!(") t LIMIT ${}  OFFSET ${}", bind_no + 1, bind_no + 2))
             .bind::<sql_types::BigInt, _>(limit)
-            .bind::<sql_types::BigInt, _>(paging.page.unwrap_or(0) as i64 * limit);
+            .bind::<sql_types::BigInt, _>(paging.page() as i64 * limit);
         let order_data: Vec<R> = query
             .load(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load orders")?;
@@ -956,7 +994,11 @@ impl Order {
         let order_items = OrderItem::find_for_order(self.id, conn)?;
 
         for item in &order_items {
-            TicketInstance::update_reserved_time(item, expires_at, conn)?;
+            if item.item_type == OrderItemTypes::ResaleTickets {
+                ResaleListing::update_reserved_time(item.id, expires_at, conn)?;
+            } else {
+                TicketInstance::update_reserved_time(item, expires_at, conn)?;
+            }
         }
 
         Ok(())
@@ -1024,13 +1066,23 @@ impl Order {
         self.lock_version(conn)?;
 
         for current_line in self.items(conn)? {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            if current_line.item_type == OrderItemTypes::ResaleTickets {
+                ResaleListing::release_for_order_item(current_line.id, conn)?;
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            } else if current_line.item_type == OrderItemTypes::SeasonPasses {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            } else if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
             // Use calculated quantity as reserved may have been taken in the meantime no longer pointing to this order item
             let quantity = current_line.calculate_quantity(conn)?;
             TicketInstance::release_tickets(&current_line, quantity as u32, Some(user_id), conn)?;
             self.destroy_item(current_line.id, conn)?;
+            if let Some(ticket_type_id) = current_line.ticket_type_id {
+                WaitlistEntry::offer_available_tickets(ticket_type_id, Some(user_id), conn)?;
+            }
         }
         Ok(())
     }
@@ -1058,6 +1110,14 @@ impl Order {
         let mut mapped = vec![];
         for (index, item) in items.iter().enumerate() {
             let ticket_type = TicketType::find(item.ticket_type_id, conn)?;
+            if let Some(ref seat_ids) = item.seat_ids {
+                if seat_ids.len() as u32 != item.quantity {
+                    return DatabaseError::validation_error(
+                        "seat_ids",
+                        "Quantity must match the number of seats selected",
+                    );
+                }
+            }
             mapped.push(match &item.redemption_code {
                 Some(r) => match Hold::find_by_redemption_code(r, Some(ticket_type.event_id), conn).optional()? {
                     Some(hold) => {
@@ -1104,8 +1164,17 @@ impl Order {
             });
         }
 
+        let mut released_ticket_type_ids: Vec<Uuid> = vec![];
         for mut current_line in current_items {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            if current_line.item_type == OrderItemTypes::ResaleTickets && remove_others {
+                jlog!(Level::Debug, "Removing resale ticket because remove others was called.", { "order_item.id": current_line.id });
+                ResaleListing::release_for_order_item(current_line.id, conn)?;
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            } else if current_line.item_type == OrderItemTypes::SeasonPasses && remove_others {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            } else if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
 
@@ -1119,69 +1188,83 @@ impl Order {
                 });
 
                 if let Some(match_data) = matching_result {
-                    jlog!(Level::Debug, "Found an existing cart item, replacing");
-                    index_to_remove = match_data.index;
-                    if current_line.quantity as u32 > match_data.update_order_item.quantity {
-                        jlog!(Level::Debug, "Reducing quantity of cart item");
-                        TicketInstance::release_tickets(
-                            &current_line,
-                            current_line.quantity as u32 - match_data.update_order_item.quantity,
-                            Some(current_user_id),
-                            conn,
-                        )?;
-                        current_line.quantity = match_data.update_order_item.quantity as i64;
-                        current_line.update(conn)?;
-                        if current_line.quantity == 0 {
-                            jlog!(Level::Debug, "Cart item has 0 quantity, deleting it");
-                            self.destroy_item(current_line.id, conn)?;
-                        }
-                    } else if (current_line.quantity as u32) < match_data.update_order_item.quantity {
-                        jlog!(Level::Debug, "Increasing quantity of cart item");
-                        // Ticket pricing might have changed since we added the previous item.
-                        // In future we may want to use the ticket pricing at the time the order was created.
-
-                        // TODO: Fetch the ticket type and pricing in one go.
-                        let ticket_type_id = current_line.ticket_type_id.unwrap();
-                        let ticket_pricing =
-                            TicketPricing::get_current_ticket_pricing(ticket_type_id, box_office_pricing, false, conn)?;
-                        let ticket_type = TicketType::find(ticket_type_id, conn)?;
-                        check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
-
-                        // TODO: Move this to an external processer
-                        if Some(ticket_pricing.id) != current_line.ticket_pricing_id {
-                            let price_in_cents = ticket_pricing.price_in_cents;
-
-                            let order_item = NewTicketsOrderItem {
-                                order_id: self.id,
-                                item_type: OrderItemTypes::Tickets,
-                                quantity: match_data.update_order_item.quantity as i64,
-                                ticket_type_id: ticket_type.id,
-                                ticket_pricing_id: ticket_pricing.id,
-                                event_id: Some(ticket_type.event_id),
-                                unit_price_in_cents: price_in_cents,
-                                hold_id: match_data.hold_id,
-                                code_id: match_data.code_id,
-                            }
-                            .commit(conn)?;
-                            TicketInstance::reserve_tickets(
-                                &order_item,
-                                self.expires_at,
-                                ticket_type_id,
-                                match_data.hold_id,
-                                match_data.update_order_item.quantity - current_line.quantity as u32,
-                                conn,
-                            )?;
-                        } else {
-                            TicketInstance::reserve_tickets(
+                    if match_data.update_order_item.seat_ids.is_some() {
+                        // Seat selections replace the existing line, the new selection is reserved below
+                        jlog!(Level::Debug, "Found an existing seated cart item, releasing its seats");
+                        let quantity = current_line.calculate_quantity(conn)?;
+                        TicketInstance::release_tickets(&current_line, quantity as u32, Some(current_user_id), conn)?;
+                        self.destroy_item(current_line.id, conn)?;
+                        released_ticket_type_ids.extend(current_line.ticket_type_id);
+                    } else {
+                        jlog!(Level::Debug, "Found an existing cart item, replacing");
+                        index_to_remove = match_data.index;
+                        if current_line.quantity as u32 > match_data.update_order_item.quantity {
+                            jlog!(Level::Debug, "Reducing quantity of cart item");
+                            TicketInstance::release_tickets(
                                 &current_line,
-                                self.expires_at,
-                                ticket_type_id,
-                                match_data.hold_id,
-                                match_data.update_order_item.quantity - current_line.quantity as u32,
+                                current_line.quantity as u32 - match_data.update_order_item.quantity,
+                                Some(current_user_id),
                                 conn,
                             )?;
+                            released_ticket_type_ids.extend(current_line.ticket_type_id);
                             current_line.quantity = match_data.update_order_item.quantity as i64;
                             current_line.update(conn)?;
+                            if current_line.quantity == 0 {
+                                jlog!(Level::Debug, "Cart item has 0 quantity, deleting it");
+                                self.destroy_item(current_line.id, conn)?;
+                            }
+                        } else if (current_line.quantity as u32) < match_data.update_order_item.quantity {
+                            jlog!(Level::Debug, "Increasing quantity of cart item");
+                            // Ticket pricing might have changed since we added the previous item.
+                            // In future we may want to use the ticket pricing at the time the order was created.
+
+                            // TODO: Fetch the ticket type and pricing in one go.
+                            let ticket_type_id = current_line.ticket_type_id.unwrap();
+                            let ticket_type = TicketType::find(ticket_type_id, conn)?;
+                            let ticket_pricing = ticket_type.current_ticket_pricing(box_office_pricing, conn)?;
+                            check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
+
+                            // TODO: Move this to an external processer
+                            if Some(ticket_pricing.id) != current_line.ticket_pricing_id {
+                                // Tickets already in the cart keep their price, only the additional
+                                // tickets are added at the new price
+                                let price_in_cents = ticket_pricing.price_in_cents;
+
+                                let order_item = NewTicketsOrderItem {
+                                    order_id: self.id,
+                                    item_type: OrderItemTypes::Tickets,
+                                    quantity: (match_data.update_order_item.quantity - current_line.quantity as u32)
+                                        as i64,
+                                    ticket_type_id: ticket_type.id,
+                                    ticket_pricing_id: ticket_pricing.id,
+                                    event_id: Some(ticket_type.event_id),
+                                    unit_price_in_cents: price_in_cents,
+                                    hold_id: match_data.hold_id,
+                                    code_id: match_data.code_id,
+                                }
+                                .commit(conn)?;
+                                TicketInstance::reserve_tickets(
+                                    &order_item,
+                                    self.expires_at,
+                                    ticket_type_id,
+                                    match_data.hold_id,
+                                    match_data.update_order_item.quantity - current_line.quantity as u32,
+                                    None,
+                                    conn,
+                                )?;
+                            } else {
+                                TicketInstance::reserve_tickets(
+                                    &current_line,
+                                    self.expires_at,
+                                    ticket_type_id,
+                                    match_data.hold_id,
+                                    match_data.update_order_item.quantity - current_line.quantity as u32,
+                                    None,
+                                    conn,
+                                )?;
+                                current_line.quantity = match_data.update_order_item.quantity as i64;
+                                current_line.update(conn)?;
+                            }
                         }
                     }
                 } else if remove_others {
@@ -1194,6 +1277,7 @@ impl Order {
                         conn,
                     )?;
                     self.destroy_item(current_line.id, conn)?;
+                    released_ticket_type_ids.extend(current_line.ticket_type_id);
                 }
             }
             if let Some(index) = index_to_remove {
@@ -1212,17 +1296,12 @@ impl Order {
             }
 
             jlog!(Level::Debug, "Adding new cart items");
-            let ticket_pricing = TicketPricing::get_current_ticket_pricing(
-                match_data.update_order_item.ticket_type_id,
-                box_office_pricing,
-                false,
-                conn,
-            )?;
-
             let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
+            let ticket_pricing = ticket_type.current_ticket_pricing(box_office_pricing, conn)?;
             check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
 
             let price_in_cents = ticket_pricing.price_in_cents;
+            self.use_currency(&ticket_pricing.currency, conn)?;
 
             // TODO: Move this to an external processer
             let order_item = NewTicketsOrderItem {
@@ -1244,6 +1323,7 @@ impl Order {
                 match_data.update_order_item.ticket_type_id,
                 match_data.hold_id,
                 match_data.update_order_item.quantity,
+                match_data.update_order_item.seat_ids.clone(),
                 conn,
             )?;
         }
@@ -1289,12 +1369,21 @@ impl Order {
             }
         }
         self.update_fees_and_discounts(conn)?;
-        self.validate_record(conn)?;
         // Beware there could be multiple orders that meet this condition
         for (ticket_type_id, remaining) in self.ticket_types(conn)? {
+            let ticket_type = TicketType::find(ticket_type_id, conn)?;
             if remaining == 0 {
-                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+                ticket_type.check_for_sold_out_triggers(Some(current_user_id), conn)?;
             }
+            // Reserved tickets count towards pricing rules, switching the tier for the next purchase
+            TicketPricingRule::apply_for_ticket_type(&ticket_type, conn)?;
+        }
+
+        // Tickets returned to the pool are offered to fans on the waitlist before anyone else
+        released_ticket_type_ids.sort();
+        released_ticket_type_ids.dedup();
+        for ticket_type_id in released_ticket_type_ids {
+            WaitlistEntry::offer_available_tickets(ticket_type_id, Some(current_user_id), conn)?;
         }
 
         Ok(())
@@ -1329,6 +1418,152 @@ impl Order {
         check_ticket_limits
     }
 
+    /// Adds a resale listing to the cart, reserving it until the cart expires. Resale fees are
+    /// deducted from the seller's payout so the buyer pays the listing price.
+    pub fn add_resale_listing(
+        &mut self,
+        current_user_id: Uuid,
+        listing: &ResaleListing,
+        conn: &PgConnection,
+    ) -> Result<OrderItem, DatabaseError> {
+        self.lock_version(conn)?;
+
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Resale tickets can only be added to a draft order");
+        } else if self.box_office_pricing {
+            return DatabaseError::business_process_error("Resale tickets cannot be purchased with box office pricing");
+        } else if listing.status != ResaleListingStatus::Active {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        } else if listing.seller_user_id == self.on_behalf_of_user_id.unwrap_or(self.user_id) {
+            return DatabaseError::business_process_error("Cannot purchase your own resale listing");
+        }
+
+        let ticket = listing.ticket_instance(conn)?;
+        if ticket.status != TicketInstanceStatus::Purchased {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        }
+        let ticket_type = ticket.ticket_type(conn)?;
+        let organization = ticket.organization(conn)?;
+        self.use_currency(&ticket_type.event(conn)?.currency, conn)?;
+
+        // Set cart expiration time if not currently set (empty carts have no expiration)
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, false, conn)?;
+        }
+
+        let (company_fee_in_cents, client_fee_in_cents) = listing.fees_in_cents(&organization);
+        let order_item = NewResaleTicketsOrderItem {
+            order_id: self.id,
+            item_type: OrderItemTypes::ResaleTickets,
+            event_id: Some(ticket_type.event_id),
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            unit_price_in_cents: listing.price_in_cents,
+            company_fee_in_cents,
+            client_fee_in_cents,
+        }
+        .commit(conn)?;
+
+        listing.reserve(order_item.id, self.expires_at.unwrap(), conn)?;
+
+        self.update_fees_and_discounts(conn)?;
+
+        Ok(order_item)
+    }
+
+    /// Sets the quantity of a season pass in the cart, a quantity of 0 removes it. Season passes
+    /// are not tied to an event so they can be purchased alongside tickets.
+    pub fn add_season_pass(
+        &mut self,
+        current_user_id: Uuid,
+        season_pass: &SeasonPass,
+        quantity: u32,
+        conn: &PgConnection,
+    ) -> Result<Option<OrderItem>, DatabaseError> {
+        self.lock_version(conn)?;
+
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Season passes can only be added to a draft order");
+        } else if self.box_office_pricing {
+            return DatabaseError::business_process_error("Season passes cannot be purchased with box office pricing");
+        }
+
+        let existing_item = self
+            .items(conn)?
+            .into_iter()
+            .find(|i| i.item_type == OrderItemTypes::SeasonPasses && i.season_pass_id == Some(season_pass.id));
+
+        let order_item = match (existing_item, quantity) {
+            (Some(item), 0) => {
+                self.destroy_item(item.id, conn)?;
+                None
+            }
+            (None, 0) => None,
+            (Some(mut item), _) => {
+                item.quantity = quantity as i64;
+                item.unit_price_in_cents = season_pass.price_in_cents;
+                item.update(conn)?;
+                Some(OrderItem::find(item.id, conn)?)
+            }
+            (None, _) => {
+                self.use_currency(&season_pass.organization(conn)?.currency, conn)?;
+                // Set cart expiration time if not currently set (empty carts have no expiration)
+                if self.expires_at.is_none() {
+                    self.set_expiry(Some(current_user_id), None, false, conn)?;
+                }
+
+                Some(
+                    NewSeasonPassOrderItem {
+                        order_id: self.id,
+                        item_type: OrderItemTypes::SeasonPasses,
+                        season_pass_id: Some(season_pass.id),
+                        quantity: quantity as i64,
+                        unit_price_in_cents: season_pass.price_in_cents,
+                    }
+                    .commit(conn)?,
+                )
+            }
+        };
+
+        self.update_fees_and_discounts(conn)?;
+
+        Ok(order_item)
+    }
+
+    /// Orders are paid in a single currency which is set by the first item added to the cart. Items in another
+    /// currency can only be added once the cart has been emptied.
+    pub(crate) fn use_currency(&mut self, currency: &str, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.currency.as_ref().map(|c| c.as_str()) == Some(currency) {
+            return Ok(());
+        }
+        if self.currency.is_some() && self.has_items(conn)? {
+            return DatabaseError::business_process_error(
+                "Items in a different currency cannot be added to this order, please complete or clear the cart first",
+            );
+        }
+
+        *self = diesel::update(orders::table.filter(orders::id.eq(self.id)))
+            .set((orders::currency.eq(currency), orders::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not set order currency")?;
+        Ok(())
+    }
+
+    /// Currency of the order without loading it, used by the items, payments and refunds created for it
+    pub(crate) fn currency_for(order_id: Uuid, conn: &PgConnection) -> Result<String, DatabaseError> {
+        let currency: Option<String> = orders::table
+            .filter(orders::id.eq(order_id))
+            .select(orders::currency)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load order currency")?;
+        currency.ok_or_else(|| {
+            DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some("Order currency has not been set".to_string()),
+            )
+        })
+    }
+
     pub fn has_items(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
         select(exists(order_items::table.filter(order_items::order_id.eq(self.id))))
             .get_result(conn)
@@ -1336,6 +1571,10 @@ impl Order {
     }
 
     pub fn update_fees_and_discounts(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        // Taxes depend on the final fees and discounts so they are recalculated last
+        for o in self.items(conn)?.iter().filter(|i| i.item_type == OrderItemTypes::Tax) {
+            self.destroy_item(o.id, conn)?;
+        }
         let items = self.items(conn)?;
 
         for o in items {
@@ -1349,7 +1588,15 @@ impl Order {
 
         // Box office purchased tickets do not have fees at this time
         if self.box_office_pricing {
-            return Ok(());
+            return self.update_taxes(conn);
+        }
+
+        for o in self
+            .items(conn)?
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::SeasonPasses)
+        {
+            o.update_fees(&self, conn)?;
         }
 
         let mut per_event_fees_included: HashMap<Uuid, bool> = HashMap::new();
@@ -1428,7 +1675,14 @@ impl Order {
                 }
 
                 if org.cc_fee_percent > 0f32 {
-                    let cc_fee = (self.calculate_total(conn)? as f32 * (org.cc_fee_percent / 100f32)).round() as i64;
+                    // Orders can span events from several organizations, each charges its fee on its own items
+                    let event_total: i64 = self
+                        .items(conn)?
+                        .iter()
+                        .filter(|i| i.event_id == Some(event.id) && i.item_type != OrderItemTypes::CreditCardFees)
+                        .map(|i| i.unit_price_in_cents * i.quantity)
+                        .sum();
+                    let cc_fee = (event_total as f32 * (org.cc_fee_percent / 100f32)).round() as i64;
                     NewFeesOrderItem {
                         order_id: self.id,
                         item_type: OrderItemTypes::CreditCardFees,
@@ -1445,6 +1699,24 @@ impl Order {
             }
         }
 
+        self.update_taxes(conn)
+    }
+
+    fn update_taxes(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut tax_rules: HashMap<Uuid, Option<TaxRule>> = HashMap::new();
+        for o in self.items(conn)?.iter().filter(|i| {
+            i.event_id.is_some() && i.item_type != OrderItemTypes::Tax && i.item_type != OrderItemTypes::Discount
+        }) {
+            let event_id = o.event_id.unwrap();
+            if !tax_rules.contains_key(&event_id) {
+                let event = Event::find(event_id, conn)?;
+                tax_rules.insert(event_id, TaxRule::find_for_event(&event, conn)?);
+            }
+            if let Some(Some(tax_rule)) = tax_rules.get(&event_id) {
+                o.update_tax(tax_rule, conn)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1726,6 +1998,8 @@ impl Order {
             checkout_url: Option<String>,
             #[sql_type = "Nullable<Timestamp>"]
             checkout_url_expires: Option<NaiveDateTime>,
+            #[sql_type = "Nullable<Timestamp>"]
+            disputed_at: Option<NaiveDateTime>,
             #[sql_type = "Nullable<Array<Text>>"]
             payment_methods: Option<Vec<PaymentMethods>>,
             #[sql_type = "Nullable<Array<Text>>"]
@@ -1756,6 +2030,7 @@ impl Order {
                 o.platform,
                 o.checkout_url,
                 o.checkout_url_expires,
+                o.disputed_at,
                 p.payment_methods,
                 p.providers,
                 CAST(COALESCE(SUM(oi.unit_price_in_cents * oi.quantity), 0) as BigInt) as total_in_cents,
@@ -1796,6 +2071,7 @@ impl Order {
                 o.expires_at,
                 o.checkout_url_expires,
                 o.checkout_url,
+                o.disputed_at,
                 p.payment_methods,
                 p.providers
             ORDER BY o.order_date desc
@@ -1864,6 +2140,33 @@ impl Order {
                 Vec::new()
             };
 
+            // Group the visible items by event, items without an event such as season passes are not grouped
+            let mut events: Vec<DisplayOrderEvent> = Vec::new();
+            for (event_id, event_items) in &items
+                .iter()
+                .filter(|i| i.event_id.is_some())
+                .sorted_by_key(|i| i.event_id)
+                .into_iter()
+                .group_by(|i| i.event_id.unwrap())
+            {
+                let event = event_map.get(&event_id).ok_or_else(|| {
+                    DatabaseError::new(
+                        ErrorCode::BusinessProcessError,
+                        Some("Order can't load event data".to_string()),
+                    )
+                })?;
+                let event_items: Vec<&DisplayOrderItem> = event_items.collect();
+                events.push(DisplayOrderEvent {
+                    id: event.id,
+                    name: event.name.clone(),
+                    organization_id: event.organization_id,
+                    event_start: event.event_start,
+                    item_ids: event_items.iter().map(|i| i.id).collect(),
+                    total_in_cents: event_items.iter().map(|i| i.unit_price_in_cents * i.quantity).sum(),
+                });
+            }
+            events.sort_by(|a, b| a.event_start.cmp(&b.event_start).then(a.name.cmp(&b.name)));
+
             let mut limited_tickets_remaining: Vec<TicketsRemaining> = Vec::new();
             if let Some(event_ids) = result.event_ids {
                 for event_id in event_ids {
@@ -1921,6 +2224,11 @@ impl Order {
                         provider: PaymentProviders::Globee,
                         display_name: "Pay with crypto".to_string(),
                     }),
+                    PaymentProviders::HostedCheckout => Some(AllowedPaymentMethod {
+                        method: "Provider".to_string(),
+                        provider: PaymentProviders::HostedCheckout,
+                        display_name: "Pay online".to_string(),
+                    }),
                     _ => None,
                 })
                 .collect();
@@ -1970,6 +2278,7 @@ impl Order {
                 user,
                 order_number: result.order_number,
                 paid_at: result.paid_at,
+                disputed_at: result.disputed_at,
                 checkout_url: if result
                     .checkout_url_expires
                     .unwrap_or(NaiveDateTime::from_timestamp(0, 0))
@@ -1988,6 +2297,7 @@ impl Order {
                 on_behalf_of_user_id: result.on_behalf_of_user_id,
                 on_behalf_of_user,
                 items,
+                events,
             });
         }
 
@@ -2217,6 +2527,131 @@ impl Order {
         Ok(())
     }
 
+    /// Flags the order as disputed and deducts the disputed sales from each organization's pending
+    /// settlement. Organizations without a pending settlement have the deduction added to their next
+    /// settlement. Repeated notifications for the same dispute are ignored.
+    pub fn record_chargeback(
+        &mut self,
+        note: String,
+        nullify_tickets: bool,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<SettlementAdjustment>, DatabaseError> {
+        self.lock_version(conn)?;
+        if self.disputed_at.is_some() {
+            return SettlementAdjustment::find_for_order(self.id, conn);
+        }
+
+        let disputed_at = Utc::now().naive_utc();
+        diesel::update(&*self)
+            .set((orders::disputed_at.eq(disputed_at), orders::updated_at.eq(dsl::now)))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not flag order as disputed")?;
+        self.disputed_at = Some(disputed_at);
+
+        let mut adjustments = Vec::new();
+        let mut pending_adjustments = Vec::new();
+        for (organization_id, amount_in_cents) in self.settlement_amounts_by_organization(conn)? {
+            if amount_in_cents <= 0 {
+                continue;
+            }
+            match Settlement::find_pending_for_organization(organization_id, conn)? {
+                Some(settlement) => {
+                    let mut adjustment = SettlementAdjustment::create(
+                        settlement.id,
+                        SettlementAdjustmentTypes::Chargeback,
+                        Some(note.clone()),
+                        amount_in_cents,
+                    );
+                    adjustment.order_id = Some(self.id);
+                    adjustments.push(adjustment.commit(conn)?);
+                }
+                None => {
+                    let currency = match self.currency.clone() {
+                        Some(currency) => currency,
+                        None => Organization::find(organization_id, conn)?.currency,
+                    };
+                    let mut pending_adjustment = PendingSettlementAdjustment::create(
+                        organization_id,
+                        SettlementAdjustmentTypes::Chargeback,
+                        Some(note.clone()),
+                        amount_in_cents,
+                        currency,
+                    );
+                    pending_adjustment.order_id = Some(self.id);
+                    pending_adjustments.push(pending_adjustment.commit(conn)?);
+                }
+            }
+        }
+
+        let nullified_ticket_ids: Vec<Uuid> = if nullify_tickets {
+            TicketInstance::nullify_for_order(self.id, current_user_id, conn)?
+                .into_iter()
+                .map(|t| t.id)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::OrderDisputed,
+            note,
+            Tables::Orders,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "settlement_adjustment_ids": adjustments.iter().map(|a| a.id).collect::<Vec<Uuid>>(),
+                "pending_settlement_adjustment_ids": pending_adjustments.iter().map(|a| a.id).collect::<Vec<Uuid>>(),
+                "nullified_ticket_ids": nullified_ticket_ids,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(adjustments)
+    }
+
+    /// Amount of the order that is paid out to each organization, the face value of the tickets
+    /// and the organization's share of the fees less any refunds.
+    fn settlement_amounts_by_organization(&self, conn: &PgConnection) -> Result<Vec<(Uuid, i64)>, DatabaseError> {
+        let mut organization_ids: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut amounts: HashMap<Uuid, i64> = HashMap::new();
+        for item in self.items(conn)? {
+            let organization_id = match (item.event_id, item.season_pass_id) {
+                (Some(event_id), _) => match organization_ids.get(&event_id) {
+                    Some(organization_id) => *organization_id,
+                    None => {
+                        let organization_id = Event::find(event_id, conn)?.organization_id;
+                        organization_ids.insert(event_id, organization_id);
+                        organization_id
+                    }
+                },
+                (None, Some(season_pass_id)) => SeasonPass::find(season_pass_id, conn)?.organization_id,
+                (None, None) => continue,
+            };
+            let unit_amount = match item.item_type {
+                OrderItemTypes::Tickets | OrderItemTypes::Discount | OrderItemTypes::SeasonPasses => {
+                    item.unit_price_in_cents
+                }
+                OrderItemTypes::PerUnitFees | OrderItemTypes::EventFees | OrderItemTypes::ResaleTickets => {
+                    item.client_fee_in_cents
+                }
+                OrderItemTypes::CreditCardFees => 0,
+                // Tax collected on tickets is remitted by the organization, tax on fees is not theirs
+                OrderItemTypes::Tax => match item.parent_id {
+                    Some(parent_id) if OrderItem::find(parent_id, conn)?.item_type == OrderItemTypes::Tickets => {
+                        item.unit_price_in_cents
+                    }
+                    _ => 0,
+                },
+            };
+            *amounts.entry(organization_id).or_insert(0) += unit_amount * (item.quantity - item.refunded_quantity);
+        }
+
+        Ok(amounts
+            .into_iter()
+            .sorted_by_key(|(organization_id, _)| *organization_id))
+    }
+
     pub fn order_items_in_invalid_state(&self, conn: &PgConnection) -> Result<Vec<OrderItem>, DatabaseError> {
         let query = include_str!("../queries/order_items_in_invalid_state.sql");
         diesel::sql_query(query)
@@ -2286,9 +2721,40 @@ impl Order {
                 .collect_vec()
             {
                 TicketInstance::mark_as_purchased(item, self.on_behalf_of_user_id.unwrap_or(self.user_id), conn)?;
+                if let Some(hold_id) = item.hold_id {
+                    WaitlistEntry::mark_purchased_for_hold(hold_id, current_user_id, conn)?;
+                }
+            }
+
+            let mut ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
+            //Resale tickets move to the buyer like a transfer
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::ResaleTickets)
+                .collect_vec()
+            {
+                let listing = ResaleListing::complete_sale(
+                    item,
+                    self.on_behalf_of_user_id.unwrap_or(self.user_id),
+                    current_user_id,
+                    conn,
+                )?;
+                ticket_ids.push(listing.ticket_instance_id);
+            }
+
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::SeasonPasses)
+                .collect_vec()
+            {
+                SeasonPassEntitlement::create_for_order_item(
+                    item,
+                    self.on_behalf_of_user_id.unwrap_or(self.user_id),
+                    current_user_id,
+                    conn,
+                )?;
             }
 
-            let ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
             let domain_event = DomainEvent::create(
                 DomainEventTypes::OrderCompleted,
                 "Order completed".into(),
@@ -2359,10 +2825,18 @@ impl Order {
 
         let order_items = self.order_items_in_invalid_state(conn)?;
         for item in order_items {
+            if item.item_type == OrderItemTypes::ResaleTickets {
+                ResaleListing::release_for_order_item(item.id, conn)?;
+                self.destroy_item(item.id, conn)?;
+                continue;
+            }
             // Use calculated quantity as reserved may have been taken in the meantime
             let quantity = item.calculate_quantity(conn)?;
             TicketInstance::release_tickets(&item, quantity as u32, Some(user_id), conn)?;
             self.destroy_item(item.id, conn)?;
+            if let Some(ticket_type_id) = item.ticket_type_id {
+                WaitlistEntry::offer_available_tickets(ticket_type_id, Some(user_id), conn)?;
+            }
         }
 
         Ok(())
@@ -2560,6 +3034,7 @@ pub struct DisplayOrder {
     pub user: DisplayUser,
     pub order_number: String,
     pub paid_at: Option<NaiveDateTime>,
+    pub disputed_at: Option<NaiveDateTime>,
     pub checkout_url: Option<String>,
     pub allowed_payment_methods: Vec<AllowedPaymentMethod>,
     pub order_contains_other_tickets: bool,
@@ -2571,6 +3046,18 @@ pub struct DisplayOrder {
     pub payment_provider: Option<PaymentProviders>,
     pub on_behalf_of_user: Option<DisplayUser>,
     pub on_behalf_of_user_id: Option<Uuid>,
+    pub events: Vec<DisplayOrderEvent>,
+}
+
+/// Items of a display order belonging to a single event, ordered by event start
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayOrderEvent {
+    pub id: Uuid,
+    pub name: String,
+    pub organization_id: Uuid,
+    pub event_start: Option<NaiveDateTime>,
+    pub item_ids: Vec<Uuid>,
+    pub total_in_cents: i64,
 }
 
 impl DisplayOrder {
@@ -2600,6 +3087,8 @@ pub struct UpdateOrderItem {
     pub ticket_type_id: Uuid,
     pub quantity: u32,
     pub redemption_code: Option<String>,
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
 }
 
 #[test]
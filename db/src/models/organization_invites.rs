@@ -58,7 +58,7 @@ pub struct NewOrganizationInvite {
     pub event_ids: Option<Vec<Uuid>>,
 }
 
-#[derive(Debug, PartialEq, Queryable, Serialize, QueryableByName)]
+#[derive(Debug, Deserialize, PartialEq, Queryable, Serialize, QueryableByName)]
 pub struct DisplayInvite {
     #[sql_type = "dUuid"]
     pub id: Uuid,
@@ -282,7 +282,7 @@ impl OrganizationInvite {
                 .filter(organization_invites::accepted.is_null())
                 .order_by(organization_invites::user_email.asc())
                 .limit(limit as i64)
-                .offset((page * limit) as i64)
+                .offset(page as i64 * limit as i64)
                 .select((
                     organization_invites::id,
                     organizations::name,
@@ -19,6 +19,7 @@ use utils::errors::*;
 use utils::pagination::Paginate;
 use utils::text;
 use uuid::Uuid;
+use validators;
 
 const DEFAULT_SETTLEMENT_TIMEZONE: &str = "America/Los_Angeles";
 
@@ -52,6 +53,11 @@ pub struct Organization {
     pub max_additional_fee_in_cents: i64,
     pub settlement_type: SettlementTypes,
     pub slug_id: Option<Uuid>,
+    pub max_resale_price_percent: Option<i64>,
+    pub resale_company_fee_percent: f32,
+    pub resale_client_fee_percent: f32,
+    pub two_factor_required_roles: Vec<Roles>,
+    pub currency: String,
 }
 
 #[derive(Serialize)]
@@ -88,6 +94,8 @@ pub struct NewOrganization {
     pub globee_api_key: Option<String>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Serialize, Clone, Deserialize, Debug, PartialEq)]
@@ -104,6 +112,7 @@ impl NewOrganization {
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        self.validate_record()?;
         let mut updated_organisation = self;
         if encryption_key.len() > 0 {
             if let Some(key) = updated_organisation.sendgrid_api_key.clone() {
@@ -148,6 +157,7 @@ impl NewOrganization {
         diesel::update(fee_schedules::table.filter(fee_schedules::id.eq(org.fee_schedule_id)))
             .set((
                 fee_schedules::organization_id.eq(org.id),
+                fee_schedules::currency.eq(&org.currency),
                 fee_schedules::updated_at.eq(dsl::now),
             ))
             .execute(conn)
@@ -169,6 +179,19 @@ impl NewOrganization {
 
         Ok(org)
     }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if let Some(ref currency) = self.currency {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "currency",
+                validators::validate_currency(currency),
+            );
+        }
+
+        Ok(validation_errors?)
+    }
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
@@ -206,6 +229,11 @@ pub struct OrganizationEditableAttributes {
     #[serde(default)]
     pub max_additional_fee_in_cents: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default)]
+    pub max_resale_price_percent: Option<Option<i64>>,
+    pub resale_company_fee_percent: Option<f32>,
+    pub resale_client_fee_percent: Option<f32>,
+    pub two_factor_required_roles: Option<Vec<Roles>>,
 }
 
 impl Organization {
@@ -552,6 +580,17 @@ impl Organization {
         }
     }
 
+    /// Whether any of the user's roles, within the organization or globally, require two-factor authentication
+    pub fn requires_two_factor_for_user(&self, user: &User, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        if self.two_factor_required_roles.is_empty() {
+            return Ok(false);
+        }
+
+        let mut roles = self.get_roles_for_user(user, conn)?;
+        roles.extend(user.role.iter());
+        Ok(roles.iter().any(|r| self.two_factor_required_roles.contains(r)))
+    }
+
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<Organization, DatabaseError> {
         organizations::table
             .find(id)
@@ -721,6 +760,7 @@ impl Organization {
         diesel::update(fee_schedule)
             .set((
                 fee_schedules::organization_id.eq(self.id),
+                fee_schedules::currency.eq(&self.currency),
                 fee_schedules::updated_at.eq(dsl::now),
             ))
             .execute(conn)
@@ -1,9 +1,12 @@
 use bigneon_http::caching::{ETag, EntityTag, ToETag};
-use models::SortingDir;
+use models::{ReportFormats, SortingDir};
 use serde_json::Value;
 use std::collections::HashMap;
 use utils::hash::sha1;
 
+/// Most rows included in an export, which is not paged
+pub const MAXIMUM_EXPORT_ROWS: u32 = 10_000;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 ///struct used to indicate paging information and search query information
 pub struct Paging {
@@ -26,12 +29,43 @@ impl Paging {
             tags: HashMap::new(),
         }
     }
+
+    /// File format requested with the `format` tag, exports include up to `MAXIMUM_EXPORT_ROWS` rows instead of a
+    /// single page
+    pub fn export_format(&self) -> Option<ReportFormats> {
+        self.tags
+            .get("format")
+            .and_then(|f| f.as_str())
+            .and_then(|f| f.parse().ok())
+    }
+
+    /// Page to load, use this instead of `page` when querying so that exports start from the first row
+    pub fn page(&self) -> u32 {
+        if self.export_format().is_some() {
+            return 0;
+        }
+        self.page
+    }
+
+    /// Rows to load, use this instead of `limit` when querying so that exports are not limited to a page
+    pub fn limit(&self) -> u32 {
+        if self.export_format().is_some() {
+            return MAXIMUM_EXPORT_ROWS;
+        }
+        self.limit
+    }
 }
 
 impl From<PagingParameters> for Paging {
     fn from(received: PagingParameters) -> Self {
-        let default_page = if let Some(i) = received.page { i } else { 0 };
-        let default_limit = if let Some(i) = received.limit { i } else { 50 };
+        let default_page = received.page();
+        let default_limit = if received.export_format().is_some() {
+            received.limit()
+        } else if let Some(i) = received.limit {
+            i
+        } else {
+            50
+        };
         let default_sort = if let Some(ref i) = received.sort {
             i.clone()
         } else {
@@ -135,13 +169,25 @@ pub struct PagingParameters {
 
 impl PagingParameters {
     pub fn page(&self) -> u32 {
+        if self.export_format().is_some() {
+            return 0;
+        }
         self.page.unwrap_or(0)
     }
 
     pub fn limit(&self) -> u32 {
+        if self.export_format().is_some() {
+            return MAXIMUM_EXPORT_ROWS;
+        }
         self.limit.unwrap_or(100)
     }
 
+    /// File format requested with the `format` parameter, exports include up to `MAXIMUM_EXPORT_ROWS` rows instead
+    /// of a single page
+    pub fn export_format(&self) -> Option<ReportFormats> {
+        self.get_tag_as_str("format").and_then(|f| f.parse().ok())
+    }
+
     pub fn dir(&self) -> SortingDir {
         self.dir.unwrap_or(SortingDir::Asc)
     }
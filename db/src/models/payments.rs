@@ -27,6 +27,7 @@ pub struct Payment {
     updated_at: NaiveDateTime,
     pub url_nonce: Option<String>,
     pub refund_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl Payment {
@@ -96,6 +97,20 @@ impl Payment {
             .expect_single()
     }
 
+    pub fn find_by_external_reference(
+        provider: PaymentProviders,
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        payments::table
+            .filter(payments::provider.eq(provider))
+            .filter(payments::external_reference.eq(external_reference))
+            .filter(payments::refund_id.is_null())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment")
+            .expect_single()
+    }
+
     pub fn log_refund(
         &self,
         current_user_id: Uuid,
@@ -226,8 +241,56 @@ impl Payment {
         Ok(())
     }
     pub fn mark_pending_ipn(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        self.mark_pending(PaymentStatus::PendingIpn, current_user_id, conn)
+    }
+
+    /// Used when the customer has to confirm the payment with the provider (e.g. 3-D Secure),
+    /// the payment is then completed or failed by the provider's webhook.
+    pub fn mark_pending_confirmation(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.mark_pending(PaymentStatus::PendingConfirmation, current_user_id, conn)
+    }
+
+    /// Fails a payment that was awaiting confirmation, returning the order to draft so the customer
+    /// can try again.
+    pub fn mark_failed(
+        &self,
+        raw_data: serde_json::Value,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        match self.status {
+            PaymentStatus::PendingConfirmation => {
+                DomainEvent::create(
+                    DomainEventTypes::PaymentCancelled,
+                    "Payment confirmation failed".to_string(),
+                    Tables::Payments,
+                    Some(self.id),
+                    current_user_id,
+                    Some(raw_data),
+                )
+                .commit(conn)?;
+                self.update_status(PaymentStatus::Cancelled, current_user_id, conn)?;
+                self.order(conn)?.reset_to_draft(current_user_id, conn)
+            }
+            PaymentStatus::Cancelled => Ok(()),
+            _ => DatabaseError::business_process_error(
+                "Could not mark payment as failed because it is not awaiting confirmation",
+            ),
+        }
+    }
+
+    fn mark_pending(
+        &self,
+        status: PaymentStatus,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         if self.status != PaymentStatus::Completed {
-            self.update_status(PaymentStatus::PendingIpn, current_user_id, conn)?;
+            self.update_status(status, current_user_id, conn)?;
             let mut order = self.order(conn)?;
             order.update_status(current_user_id, OrderStatus::PendingPayment, conn)?;
             order.set_expiry(
@@ -293,8 +356,9 @@ pub struct NewPayment {
 
 impl NewPayment {
     pub(crate) fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Payment, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
         let res: Payment = diesel::insert_into(payments::table)
-            .values(&self)
+            .values((&self, payments::currency.eq(currency)))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create payment")?;
 
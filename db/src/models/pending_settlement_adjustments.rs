@@ -0,0 +1,116 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::pending_settlement_adjustments;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Adjustment for an organization that had no pending settlement when it was recorded. It is added to
+/// the organization's next settlement in the same currency.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "pending_settlement_adjustments"]
+pub struct PendingSettlementAdjustment {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub order_id: Option<Uuid>,
+    pub amount_in_cents: i64,
+    pub note: Option<String>,
+    pub settlement_adjustment_type: SettlementAdjustmentTypes,
+    pub currency: String,
+    pub settlement_adjustment_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Insertable, PartialEq)]
+#[table_name = "pending_settlement_adjustments"]
+pub struct NewPendingSettlementAdjustment {
+    pub organization_id: Uuid,
+    pub order_id: Option<Uuid>,
+    pub amount_in_cents: i64,
+    pub note: Option<String>,
+    pub settlement_adjustment_type: SettlementAdjustmentTypes,
+    pub currency: String,
+}
+
+impl NewPendingSettlementAdjustment {
+    pub fn commit(&self, conn: &PgConnection) -> Result<PendingSettlementAdjustment, DatabaseError> {
+        diesel::insert_into(pending_settlement_adjustments::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create pending settlement adjustment")
+    }
+}
+
+impl PendingSettlementAdjustment {
+    pub fn create(
+        organization_id: Uuid,
+        settlement_adjustment_type: SettlementAdjustmentTypes,
+        note: Option<String>,
+        amount_in_cents: i64,
+        currency: String,
+    ) -> NewPendingSettlementAdjustment {
+        NewPendingSettlementAdjustment {
+            organization_id,
+            order_id: None,
+            amount_in_cents,
+            note,
+            settlement_adjustment_type,
+            currency,
+        }
+    }
+
+    pub fn find_for_order(
+        order_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<PendingSettlementAdjustment>, DatabaseError> {
+        pending_settlement_adjustments::table
+            .filter(pending_settlement_adjustments::order_id.eq(order_id))
+            .order_by(pending_settlement_adjustments::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load pending settlement adjustments for order",
+            )
+    }
+
+    /// Moves the organization's pending adjustments in the settlement's currency onto the settlement
+    pub fn apply_to_settlement(
+        settlement: &Settlement,
+        conn: &PgConnection,
+    ) -> Result<Vec<SettlementAdjustment>, DatabaseError> {
+        let pending_adjustments: Vec<PendingSettlementAdjustment> = pending_settlement_adjustments::table
+            .filter(pending_settlement_adjustments::organization_id.eq(settlement.organization_id))
+            .filter(pending_settlement_adjustments::currency.eq(&settlement.currency))
+            .filter(pending_settlement_adjustments::settlement_adjustment_id.is_null())
+            .order_by(pending_settlement_adjustments::created_at)
+            .for_update()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load pending settlement adjustments")?;
+
+        let mut adjustments = Vec::new();
+        for pending_adjustment in pending_adjustments {
+            let mut adjustment = SettlementAdjustment::create(
+                settlement.id,
+                pending_adjustment.settlement_adjustment_type,
+                pending_adjustment.note.clone(),
+                pending_adjustment.amount_in_cents,
+            );
+            adjustment.order_id = pending_adjustment.order_id;
+            let adjustment = adjustment.commit(conn)?;
+
+            diesel::update(&pending_adjustment)
+                .set((
+                    pending_settlement_adjustments::settlement_adjustment_id.eq(adjustment.id),
+                    pending_settlement_adjustments::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not apply pending settlement adjustment")?;
+            adjustments.push(adjustment);
+        }
+
+        Ok(adjustments)
+    }
+}
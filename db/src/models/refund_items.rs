@@ -14,6 +14,7 @@ pub struct RefundItem {
     pub amount: i64,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub settlement_id: Option<Uuid>,
 }
 
 impl RefundItem {
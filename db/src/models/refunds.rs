@@ -17,6 +17,7 @@ pub struct Refund {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub manual_override: bool,
+    pub currency: String,
 }
 
 impl Refund {
@@ -59,8 +60,9 @@ pub struct NewRefund {
 
 impl NewRefund {
     pub fn commit(self, conn: &PgConnection) -> Result<Refund, DatabaseError> {
+        let currency = Order::currency_for(self.order_id, conn)?;
         let refund: Refund = diesel::insert_into(refunds::table)
-            .values(&self)
+            .values((&self, refunds::currency.eq(currency)))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not insert refund record")?;
 
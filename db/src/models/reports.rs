@@ -85,6 +85,8 @@ pub struct TicketSalesRow {
     pub per_order_total_fees_in_cents: i64,
     #[sql_type = "BigInt"]
     pub user_count: i64,
+    #[sql_type = "Nullable<Text>"]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -99,6 +101,8 @@ pub struct TicketSalesPerEventFees {
     pub per_order_client_online_fees: i64,
     #[sql_type = "BigInt"]
     pub per_order_total_fees_in_cents: i64,
+    #[sql_type = "Nullable<Text>"]
+    pub currency: Option<String>,
 }
 
 impl From<TicketSalesPerEventFees> for TicketSalesRow {
@@ -109,6 +113,7 @@ impl From<TicketSalesPerEventFees> for TicketSalesRow {
             per_order_company_online_fees: ticket_sales_per_event_fees.per_order_company_online_fees,
             per_order_client_online_fees: ticket_sales_per_event_fees.per_order_client_online_fees,
             per_order_total_fees_in_cents: ticket_sales_per_event_fees.per_order_total_fees_in_cents,
+            currency: ticket_sales_per_event_fees.currency,
             ..Default::default()
         }
     }
@@ -219,6 +224,18 @@ pub struct TransactionReportRow {
     #[sql_type = "Nullable<dUuid>"]
     pub fee_range_id: Option<Uuid>,
     #[sql_type = "Text"]
+    pub item_type: OrderItemTypes,
+    #[sql_type = "BigInt"]
+    pub resale_client_fee_in_cents: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents_total: i64,
+    #[sql_type = "Bool"]
+    pub tax_inclusive: bool,
+    #[sql_type = "Text"]
     pub order_type: OrderTypes,
     #[sql_type = "Nullable<Text>"]
     pub payment_method: Option<String>,
@@ -305,6 +322,8 @@ pub struct BoxOfficeSalesSummaryReportRow {
     pub revenue_share_value_in_cents: i64,
     #[sql_type = "BigInt"]
     pub total_sales_in_cents: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -316,6 +335,7 @@ pub struct BoxOfficeSalesSummaryReport {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct BoxOfficeSalesSummaryPaymentRow {
     pub payment_type: ExternalPaymentType,
+    pub currency: String,
     pub quantity: u32,
     pub total_sales_in_cents: u32,
 }
@@ -336,6 +356,7 @@ pub struct BoxOfficeSalesSummaryOperatorEventRow {
     pub face_value_in_cents: u32,
     pub revenue_share_value_in_cents: u32,
     pub total_sales_in_cents: u32,
+    pub currency: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -370,6 +391,8 @@ pub struct EventSummarySalesRow {
     pub total_gross_income_in_cents: i64,
     #[sql_type = "dUuid"]
     pub ticket_type_id: Uuid,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -398,6 +421,8 @@ pub struct EventSummaryFeesRow {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -414,21 +439,26 @@ pub struct EventSummaryOtherFees {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ReconciliationSummaryResult {
     pub payment_method: String,
     pub payment_provider: String,
+    pub currency: String,
     pub quantity: i64,
     pub unit_price_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub event_fee_in_cents: i64,
+    pub tax_in_cents: i64,
     pub sales_total: i64,
     pub refund_quantity: i64,
     pub refund_unit_price_in_cents: i64,
     pub refund_client_fee_in_cents: i64,
     pub refund_event_fee_in_cents: i64,
+    pub refund_tax_in_cents: i64,
     pub refund_total: i64,
     pub total: i64,
 }
@@ -465,6 +495,7 @@ pub struct ReconciliationDetailEventResult {
     pub event_id: Uuid,
     pub event_name: String,
     pub event_start: Option<NaiveDateTime>,
+    pub currency: String,
     pub entries: Vec<ReconciliationDetailResult>,
 }
 
@@ -683,35 +714,38 @@ impl Report {
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Could not fetch report results")?;
 
-        let mut payment_totals: HashMap<ExternalPaymentType, BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
+        let mut payment_totals: HashMap<(ExternalPaymentType, String), BoxOfficeSalesSummaryPaymentRow> =
+            HashMap::new();
         let mut operator_data: Vec<BoxOfficeSalesSummaryOperatorRow> = Vec::new();
         let mut operator_payments: HashMap<Uuid, Vec<BoxOfficeSalesSummaryPaymentRow>> = HashMap::new();
         for (operator_id, group) in &payment_box_office_summary_rows
             .into_iter()
             .group_by(|row| row.operator_id)
         {
-            let mut payments: HashMap<ExternalPaymentType, BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
+            let mut payments: HashMap<(ExternalPaymentType, String), BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
             for group_item in group {
                 if let Some(external_payment_type) = group_item.external_payment_type {
                     payment_totals
-                        .entry(external_payment_type)
+                        .entry((external_payment_type, group_item.currency.clone()))
                         .and_modify(|e| {
                             e.quantity += group_item.number_of_tickets as u32;
                             e.total_sales_in_cents += group_item.total_sales_in_cents as u32;
                         })
                         .or_insert_with(|| BoxOfficeSalesSummaryPaymentRow {
                             payment_type: external_payment_type,
+                            currency: group_item.currency.clone(),
                             quantity: group_item.number_of_tickets as u32,
                             total_sales_in_cents: group_item.total_sales_in_cents as u32,
                         });
                     payments
-                        .entry(external_payment_type)
+                        .entry((external_payment_type, group_item.currency.clone()))
                         .and_modify(|e| {
                             e.quantity += group_item.number_of_tickets as u32;
                             e.total_sales_in_cents += group_item.total_sales_in_cents as u32;
                         })
                         .or_insert_with(|| BoxOfficeSalesSummaryPaymentRow {
                             payment_type: external_payment_type,
+                            currency: group_item.currency.clone(),
                             quantity: group_item.number_of_tickets as u32,
                             total_sales_in_cents: group_item.total_sales_in_cents as u32,
                         });
@@ -722,7 +756,7 @@ impl Report {
                 .values()
                 .map(|v| (*v).clone())
                 .collect::<Vec<BoxOfficeSalesSummaryPaymentRow>>();
-            payments.sort_by_key(|p| p.payment_type.to_string());
+            payments.sort_by_key(|p| (p.payment_type.to_string(), p.currency.clone()));
             operator_payments.insert(operator_id, payments);
         }
 
@@ -741,6 +775,7 @@ impl Report {
                     face_value_in_cents: group_item.face_value_in_cents as u32,
                     revenue_share_value_in_cents: group_item.revenue_share_value_in_cents as u32,
                     total_sales_in_cents: group_item.total_sales_in_cents as u32,
+                    currency: group_item.currency.clone(),
                 });
             }
 
@@ -756,7 +791,7 @@ impl Report {
             .values()
             .map(|v| (*v).clone())
             .collect::<Vec<BoxOfficeSalesSummaryPaymentRow>>();
-        payment_totals.sort_by_key(|p| p.payment_type.to_string());
+        payment_totals.sort_by_key(|p| (p.payment_type.to_string(), p.currency.clone()));
 
         Ok(BoxOfficeSalesSummaryReport {
             operators: operator_data,
@@ -982,11 +1017,13 @@ impl Report {
                 let entry_exists = results.iter().any(|r| {
                     r.payment_method == row.payment_method.clone().unwrap()
                         && r.payment_provider == row.payment_provider.clone().unwrap()
+                        && r.currency == row.currency
                 });
                 if entry_exists {
                     if let Some(entry) = results.iter_mut().find(|r| {
                         r.payment_method == row.payment_method.clone().unwrap()
                             && r.payment_provider == row.payment_provider.clone().unwrap()
+                            && r.currency == row.currency
                     }) {
                         let ticket_face = row.unit_price_in_cents * row.actual_quantity;
                         let client_fee = row.client_fee_in_cents * row.actual_quantity;
@@ -994,17 +1031,25 @@ impl Report {
                         let refund_ticket_face = row.unit_price_in_cents * row.refunded_quantity;
                         let refund_client_fee = row.client_fee_in_cents * row.refunded_quantity;
                         let refund_event_fee = row.event_fee_client_in_cents * row.refunded_quantity;
-                        let sales_total = ticket_face + client_fee + event_fee;
-                        let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
+                        let tax = row.tax_in_cents * row.actual_quantity;
+                        let refund_tax = row.tax_in_cents * row.refunded_quantity;
+                        // Inclusive tax is already part of the face value and fees
+                        let (charged_tax, refund_charged_tax) =
+                            if row.tax_inclusive { (0, 0) } else { (tax, refund_tax) };
+                        let sales_total = ticket_face + client_fee + event_fee + charged_tax;
+                        let refund_total =
+                            refund_ticket_face + refund_client_fee + refund_event_fee + refund_charged_tax;
                         entry.quantity += row.actual_quantity;
                         entry.unit_price_in_cents += ticket_face;
                         entry.client_fee_in_cents += client_fee;
                         entry.event_fee_in_cents += event_fee;
+                        entry.tax_in_cents += tax;
                         entry.sales_total += sales_total;
                         entry.refund_quantity += row.refunded_quantity;
                         entry.refund_unit_price_in_cents += refund_ticket_face;
                         entry.refund_client_fee_in_cents += refund_client_fee;
                         entry.refund_event_fee_in_cents += refund_event_fee;
+                        entry.refund_tax_in_cents += refund_tax;
                         entry.refund_total += refund_total;
                         entry.total += sales_total - refund_total;
                     }
@@ -1015,20 +1060,27 @@ impl Report {
                     let refund_ticket_face = row.unit_price_in_cents * row.refunded_quantity;
                     let refund_client_fee = row.client_fee_in_cents * row.refunded_quantity;
                     let refund_event_fee = row.event_fee_client_in_cents * row.refunded_quantity;
-                    let sales_total = ticket_face + client_fee + event_fee;
-                    let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
+                    let tax = row.tax_in_cents * row.actual_quantity;
+                    let refund_tax = row.tax_in_cents * row.refunded_quantity;
+                    // Inclusive tax is already part of the face value and fees
+                    let (charged_tax, refund_charged_tax) = if row.tax_inclusive { (0, 0) } else { (tax, refund_tax) };
+                    let sales_total = ticket_face + client_fee + event_fee + charged_tax;
+                    let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee + refund_charged_tax;
                     results.push(ReconciliationSummaryResult {
                         payment_method: row.payment_method.unwrap(),
                         payment_provider: row.payment_provider.unwrap(),
+                        currency: row.currency,
                         quantity: row.actual_quantity,
                         unit_price_in_cents: ticket_face,
                         client_fee_in_cents: client_fee,
                         event_fee_in_cents: event_fee,
+                        tax_in_cents: tax,
                         sales_total,
                         refund_quantity: row.refunded_quantity,
                         refund_unit_price_in_cents: refund_ticket_face,
                         refund_client_fee_in_cents: refund_client_fee,
                         refund_event_fee_in_cents: refund_event_fee,
+                        refund_tax_in_cents: refund_tax,
                         refund_total,
                         total: sales_total - refund_total,
                     });
@@ -1122,6 +1174,7 @@ impl Report {
                     event_id: row.event_id.clone(),
                     event_name: row.event_name.clone(),
                     event_start: row.event_start.clone(),
+                    currency: row.currency.clone(),
                     entries: Vec::new(),
                 });
             }
@@ -0,0 +1,425 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{self, exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{assets, resale_listings, ticket_instances, ticket_types};
+use std::borrow::Cow;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "resale_listings"]
+pub struct ResaleListing {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub price_in_cents: i64,
+    pub status: ResaleListingStatus,
+    pub order_item_id: Option<Uuid>,
+    pub reserved_until: Option<NaiveDateTime>,
+    pub buyer_user_id: Option<Uuid>,
+    pub seller_payout_in_cents: Option<i64>,
+    pub sold_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "resale_listings"]
+pub struct NewResaleListing {
+    pub ticket_instance_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayResaleListing {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub ticket_type_name: String,
+    pub price_in_cents: i64,
+    pub face_value_in_cents: i64,
+    pub status: ResaleListingStatus,
+    pub available: bool,
+    pub seller_payout_in_cents: Option<i64>,
+    pub sold_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl NewResaleListing {
+    pub fn commit(self, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        let ticket = TicketInstance::find(self.ticket_instance_id, conn)?;
+        if ticket.status != TicketInstanceStatus::Purchased {
+            return DatabaseError::business_process_error(
+                "Only purchased tickets that have not been redeemed can be listed for resale",
+            );
+        } else if ticket.owner(conn)?.id != self.seller_user_id {
+            return DatabaseError::business_process_error("User does not own this ticket");
+        } else if ticket.has_pending_transfer(conn)? {
+            return DatabaseError::business_process_error(
+                "Ticket has a pending transfer and cannot be listed for resale",
+            );
+        }
+
+        let event = ticket.ticket_type(conn)?.event(conn)?;
+        if event.event_end.map(|e| e < Utc::now().naive_utc()).unwrap_or(false) {
+            return DatabaseError::business_process_error("Cannot list ticket for resale, event has ended.");
+        }
+
+        let organization = ticket.organization(conn)?;
+        let max_price_in_cents = match ResaleListing::max_price_in_cents(&ticket, &organization, conn)? {
+            Some(max_price_in_cents) => max_price_in_cents,
+            None => {
+                return DatabaseError::business_process_error("Resale is not enabled for this event");
+            }
+        };
+
+        if self.price_in_cents <= 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price must be greater than 0");
+        } else if self.price_in_cents > max_price_in_cents {
+            let mut error = ValidationError::new("resale_price_cap_exceeded");
+            error.message = Some(Cow::from(format!(
+                "Resale price cannot exceed {} cents",
+                max_price_in_cents
+            )));
+            error.add_param(Cow::from("max_price_in_cents"), &max_price_in_cents);
+            let mut errors = ValidationErrors::new();
+            errors.add("price_in_cents", error);
+            return Err(errors.into());
+        }
+
+        let already_listed: bool = select(exists(
+            resale_listings::table
+                .filter(resale_listings::ticket_instance_id.eq(self.ticket_instance_id))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if ticket is already listed")?;
+        if already_listed {
+            return DatabaseError::validation_error("ticket_instance_id", "Ticket is already listed for resale");
+        }
+
+        let listing: ResaleListing = diesel::insert_into(resale_listings::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create resale listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingCreated,
+            "Ticket listed for resale".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            Some(listing.seller_user_id),
+            Some(json!({"ticket_instance_id": listing.ticket_instance_id, "price_in_cents": listing.price_in_cents})),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+}
+
+impl ResaleListing {
+    pub fn create(ticket_instance_id: Uuid, seller_user_id: Uuid, price_in_cents: i64) -> NewResaleListing {
+        NewResaleListing {
+            ticket_instance_id,
+            seller_user_id,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        resale_listings::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listing")
+    }
+
+    pub fn find_for_order_item(
+        order_item_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::order_item_id.eq(order_item_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listing for order item")
+    }
+
+    /// Listings created by the user, most recent first
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::seller_user_id.eq(user_id))
+            .order_by(resale_listings::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listings for user")
+    }
+
+    /// Active listings for the event, cheapest first
+    pub fn find_active_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .inner_join(ticket_instances::table.on(ticket_instances::id.eq(resale_listings::ticket_instance_id)))
+            .inner_join(assets::table.on(assets::id.eq(ticket_instances::asset_id)))
+            .inner_join(ticket_types::table.on(ticket_types::id.eq(assets::ticket_type_id)))
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+            .select(resale_listings::all_columns)
+            .order_by(resale_listings::price_in_cents)
+            .then_order_by(resale_listings::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listings for event")
+    }
+
+    pub fn any_active_for_ticket_instance_ids(
+        ticket_instance_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        select(exists(
+            resale_listings::table
+                .filter(resale_listings::ticket_instance_id.eq_any(ticket_instance_ids))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if tickets are listed for resale",
+        )
+    }
+
+    /// Face value of the ticket as sold on its original order, before discounts and fees
+    pub fn face_value_in_cents(ticket: &TicketInstance, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        match ticket.order_item_id {
+            Some(order_item_id) => Ok(OrderItem::find(order_item_id, conn)?.unit_price_in_cents),
+            None => Ok(0),
+        }
+    }
+
+    /// Highest price the ticket can be listed at, `None` if the organization does not allow resale
+    pub fn max_price_in_cents(
+        ticket: &TicketInstance,
+        organization: &Organization,
+        conn: &PgConnection,
+    ) -> Result<Option<i64>, DatabaseError> {
+        match organization.max_resale_price_percent {
+            Some(max_resale_price_percent) => Ok(Some(
+                ResaleListing::face_value_in_cents(ticket, conn)? * max_resale_price_percent / 100,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Company and client fees deducted from the seller's payout
+    pub fn fees_in_cents(&self, organization: &Organization) -> (i64, i64) {
+        let company_fee_in_cents =
+            (self.price_in_cents as f32 * (organization.resale_company_fee_percent / 100f32)).round() as i64;
+        let client_fee_in_cents =
+            (self.price_in_cents as f32 * (organization.resale_client_fee_percent / 100f32)).round() as i64;
+        (company_fee_in_cents, client_fee_in_cents)
+    }
+
+    pub fn ticket_instance(&self, conn: &PgConnection) -> Result<TicketInstance, DatabaseError> {
+        TicketInstance::find(self.ticket_instance_id, conn)
+    }
+
+    pub fn is_reserved(&self) -> bool {
+        self.reserved_until.map(|r| r > Utc::now().naive_utc()).unwrap_or(false)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayResaleListing, DatabaseError> {
+        let ticket = self.ticket_instance(conn)?;
+        let ticket_type = ticket.ticket_type(conn)?;
+
+        Ok(DisplayResaleListing {
+            id: self.id,
+            event_id: ticket_type.event_id,
+            ticket_type_id: ticket_type.id,
+            ticket_type_name: ticket_type.name,
+            price_in_cents: self.price_in_cents,
+            face_value_in_cents: ResaleListing::face_value_in_cents(&ticket, conn)?,
+            status: self.status,
+            available: self.status == ResaleListingStatus::Active && !self.is_reserved(),
+            seller_payout_in_cents: self.seller_payout_in_cents,
+            sold_at: self.sold_at,
+            created_at: self.created_at,
+        })
+    }
+
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        if self.status != ResaleListingStatus::Active {
+            return DatabaseError::business_process_error("Resale listing is no longer active");
+        } else if self.is_reserved() {
+            return DatabaseError::business_process_error(
+                "Resale listing is being purchased and cannot be cancelled at this time",
+            );
+        }
+
+        let listing: ResaleListing = diesel::update(self)
+            .set((
+                resale_listings::status.eq(ResaleListingStatus::Cancelled),
+                resale_listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not cancel resale listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingCancelled,
+            "Resale listing cancelled".to_string(),
+            Tables::ResaleListings,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    /// Reserves the listing for a cart line until `reserved_until`. Fails if the listing is
+    /// reserved by another cart that has not yet expired.
+    pub(crate) fn reserve(
+        &self,
+        order_item_id: Uuid,
+        reserved_until: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        let listing: Option<ResaleListing> = diesel::update(
+            resale_listings::table
+                .filter(resale_listings::id.eq(self.id))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+                .filter(
+                    resale_listings::reserved_until
+                        .is_null()
+                        .or(resale_listings::reserved_until.lt(dsl::now.nullable()))
+                        .or(resale_listings::order_item_id.eq(order_item_id)),
+                ),
+        )
+        .set((
+            resale_listings::order_item_id.eq(order_item_id),
+            resale_listings::reserved_until.eq(reserved_until),
+            resale_listings::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not reserve resale listing")?;
+
+        match listing {
+            Some(listing) => Ok(listing),
+            None => DatabaseError::business_process_error("Resale listing is no longer available"),
+        }
+    }
+
+    pub(crate) fn update_reserved_time(
+        order_item_id: Uuid,
+        reserved_until: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        diesel::update(
+            resale_listings::table
+                .filter(resale_listings::order_item_id.eq(order_item_id))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active)),
+        )
+        .set((
+            resale_listings::reserved_until.eq(reserved_until),
+            resale_listings::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update resale listing reserved time")
+    }
+
+    /// Returns the listing to the market when its cart line is removed
+    pub(crate) fn release_for_order_item(order_item_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let no_reservation: Option<NaiveDateTime> = None;
+        diesel::update(
+            resale_listings::table
+                .filter(resale_listings::order_item_id.eq(order_item_id))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active)),
+        )
+        .set((
+            resale_listings::order_item_id.eq(None::<Uuid>),
+            resale_listings::reserved_until.eq(no_reservation),
+            resale_listings::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not release resale listing")
+    }
+
+    /// Completes the sale once the buyer's order is paid. The ticket is transferred directly to
+    /// the buyer with new redeem keys and a payout of the price less the resale fees is recorded
+    /// for the seller.
+    pub(crate) fn complete_sale(
+        order_item: &OrderItem,
+        buyer_user_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        let listing = match ResaleListing::find_for_order_item(order_item.id, conn)? {
+            Some(listing) => listing,
+            None => return DatabaseError::business_process_error("Resale listing is no longer available"),
+        };
+        if listing.status != ResaleListingStatus::Active {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        }
+        // Redeemed tickets cannot be transferred to the buyer
+        if listing.ticket_instance(conn)?.status != TicketInstanceStatus::Purchased {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        }
+
+        let seller_payout_in_cents =
+            listing.price_in_cents - order_item.company_fee_in_cents - order_item.client_fee_in_cents;
+        let no_reservation: Option<NaiveDateTime> = None;
+        let listing: ResaleListing = diesel::update(&listing)
+            .set((
+                resale_listings::status.eq(ResaleListingStatus::Sold),
+                resale_listings::buyer_user_id.eq(buyer_user_id),
+                resale_listings::seller_payout_in_cents.eq(seller_payout_in_cents),
+                resale_listings::sold_at.eq(Utc::now().naive_utc()),
+                resale_listings::reserved_until.eq(no_reservation),
+                resale_listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark resale listing as sold")?;
+
+        let currency = match order_item.event_id {
+            Some(event_id) => Event::find(event_id, conn)?.currency,
+            None => listing.ticket_instance(conn)?.ticket_type(conn)?.event(conn)?.currency,
+        };
+        let payout = ResalePayout::create(&listing, seller_payout_in_cents, currency).commit(conn)?;
+
+        let seller = User::find(listing.seller_user_id, conn)?;
+        let buyer = User::find(buyer_user_id, conn)?;
+        let (address, sent_via) = match (buyer.email.clone(), buyer.phone.clone()) {
+            (Some(email), _) => (email, TransferMessageType::Email),
+            (None, Some(phone)) => (phone, TransferMessageType::Phone),
+            (None, None) => (buyer.id.to_string(), TransferMessageType::Email),
+        };
+        let transfer = TicketInstance::direct_transfer(
+            &seller,
+            &[listing.ticket_instance_id],
+            &address,
+            sent_via,
+            buyer.id,
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingSold,
+            "Resale ticket sold".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            current_user_id,
+            Some(json!({
+                "buyer_user_id": buyer_user_id,
+                "order_item_id": order_item.id,
+                "transfer_id": transfer.id,
+                "price_in_cents": listing.price_in_cents,
+                "seller_payout_in_cents": seller_payout_in_cents,
+                "resale_payout_id": payout.id
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::resale_payouts;
+use utils::errors::*;
+use utils::pagination::Paginate;
+use uuid::Uuid;
+
+/// Amount owed to the seller of a resold ticket. The buyer's payment is collected by the platform,
+/// so the seller's share is recorded here when the sale completes and marked as paid once it has
+/// been paid out.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "resale_payouts"]
+pub struct ResalePayout {
+    pub id: Uuid,
+    pub resale_listing_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub amount_in_cents: i64,
+    pub currency: String,
+    pub status: ResalePayoutStatus,
+    pub paid_at: Option<NaiveDateTime>,
+    pub paid_by_user_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "resale_payouts"]
+pub struct NewResalePayout {
+    pub resale_listing_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub amount_in_cents: i64,
+    pub currency: String,
+}
+
+impl NewResalePayout {
+    pub fn commit(self, conn: &PgConnection) -> Result<ResalePayout, DatabaseError> {
+        diesel::insert_into(resale_payouts::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create resale payout")
+    }
+}
+
+impl ResalePayout {
+    pub(crate) fn create(listing: &ResaleListing, amount_in_cents: i64, currency: String) -> NewResalePayout {
+        NewResalePayout {
+            resale_listing_id: listing.id,
+            seller_user_id: listing.seller_user_id,
+            amount_in_cents,
+            currency,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ResalePayout, DatabaseError> {
+        resale_payouts::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale payout")
+    }
+
+    pub fn find_for_resale_listing(
+        resale_listing_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<ResalePayout>, DatabaseError> {
+        resale_payouts::table
+            .filter(resale_payouts::resale_listing_id.eq(resale_listing_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale payout")
+            .optional()
+    }
+
+    /// Payouts that have not been paid to their sellers yet, oldest first
+    pub fn find_pending(page: u32, limit: u32, conn: &PgConnection) -> Result<Payload<ResalePayout>, DatabaseError> {
+        let (payouts, total) = resale_payouts::table
+            .filter(resale_payouts::status.eq(ResalePayoutStatus::Pending))
+            .order_by(resale_payouts::created_at)
+            .then_order_by(resale_payouts::id)
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load pending resale payouts")?;
+
+        let mut payload = Payload::from_data(payouts, page, limit);
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    pub fn mark_paid(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ResalePayout, DatabaseError> {
+        if self.status != ResalePayoutStatus::Pending {
+            return DatabaseError::business_process_error("Resale payout has already been paid");
+        }
+
+        let payout: ResalePayout = diesel::update(self)
+            .set((
+                resale_payouts::status.eq(ResalePayoutStatus::Paid),
+                resale_payouts::paid_at.eq(dsl::now.nullable()),
+                resale_payouts::paid_by_user_id.eq(current_user_id),
+                resale_payouts::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark resale payout as paid")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResalePayoutPaid,
+            "Resale payout paid to seller".to_string(),
+            Tables::ResalePayouts,
+            Some(payout.id),
+            current_user_id,
+            Some(json!({
+                "resale_listing_id": payout.resale_listing_id,
+                "amount_in_cents": payout.amount_in_cents,
+                "currency": payout.currency
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(payout)
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::saved_report_subscribers;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "saved_report_subscribers"]
+pub struct SavedReportSubscriber {
+    pub id: Uuid,
+    pub saved_report_id: Uuid,
+    pub email: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub last_sent_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Validate)]
+#[table_name = "saved_report_subscribers"]
+pub struct NewSavedReportSubscriber {
+    pub saved_report_id: Uuid,
+    #[validate(email(message = "Email is invalid"))]
+    pub email: String,
+}
+
+impl NewSavedReportSubscriber {
+    pub fn commit(
+        mut self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<SavedReportSubscriber, DatabaseError> {
+        self.email = self.email.to_lowercase();
+        self.validate()?;
+        let subscriber: SavedReportSubscriber = diesel::insert_into(saved_report_subscribers::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create saved report subscriber")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SavedReportSubscriberCreated,
+            "Saved report subscriber created".to_string(),
+            Tables::SavedReports,
+            Some(subscriber.saved_report_id),
+            current_user_id,
+            Some(json!({"email": subscriber.email, "saved_report_subscriber_id": subscriber.id })),
+        )
+        .commit(conn)?;
+
+        Ok(subscriber)
+    }
+}
+
+impl SavedReportSubscriber {
+    pub fn create(saved_report_id: Uuid, email: String) -> NewSavedReportSubscriber {
+        NewSavedReportSubscriber { saved_report_id, email }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SavedReportSubscriber, DatabaseError> {
+        saved_report_subscribers::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading saved report subscriber")
+    }
+
+    pub fn find_all(saved_report_id: Uuid, conn: &PgConnection) -> Result<Vec<SavedReportSubscriber>, DatabaseError> {
+        saved_report_subscribers::table
+            .filter(saved_report_subscribers::saved_report_id.eq(saved_report_id))
+            .order_by(saved_report_subscribers::email)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading saved report subscribers")
+    }
+
+    /// Records that the subscriber was sent the saved report
+    pub fn mark_sent(&self, conn: &PgConnection) -> Result<SavedReportSubscriber, DatabaseError> {
+        diesel::update(self)
+            .set((
+                saved_report_subscribers::last_sent_at.eq(dsl::now.nullable()),
+                saved_report_subscribers::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update saved report subscriber")
+    }
+
+    /// Whether the subscriber already received the run scheduled at `run_at`
+    pub fn was_sent_run(&self, run_at: NaiveDateTime) -> bool {
+        self.last_sent_at
+            .map(|last_sent_at| last_sent_at >= run_at)
+            .unwrap_or(false)
+    }
+
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Error removing saved report subscriber")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SavedReportSubscriberDeleted,
+            "Saved report subscriber deleted".to_string(),
+            Tables::SavedReports,
+            Some(self.saved_report_id),
+            current_user_id,
+            Some(json!({"email": self.email, "saved_report_subscriber_id": self.id })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,580 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::saved_reports;
+use serde_json::{self, Value};
+use std::u32;
+use utils::cron::CronSchedule;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::ValidationErrors;
+use validators::{self, *};
+
+const PROMO_CODE_COLUMNS: &[&str] = &[
+    "organization_id",
+    "event_id",
+    "ticket_type_id",
+    "hold_id",
+    "ticket_name",
+    "ticket_status",
+    "event_name",
+    "hold_name",
+    "promo_redemption_code",
+    "ticket_pricing_name",
+    "ticket_pricing_price_in_cents",
+    "client_online_fees_in_cents",
+    "promo_code_discounted_ticket_price",
+    "box_office_order_count",
+    "online_order_count",
+    "box_office_refunded_count",
+    "online_refunded_count",
+    "box_office_sales_in_cents",
+    "online_sales_in_cents",
+    "box_office_face_sales_in_cents",
+    "online_face_sales_in_cents",
+    "box_office_sale_count",
+    "online_sale_count",
+    "online_fee_count",
+    "comp_sale_count",
+    "total_box_office_fees_in_cents",
+    "total_online_fees_in_cents",
+    "company_box_office_fees_in_cents",
+    "client_box_office_fees_in_cents",
+    "company_online_fees_in_cents",
+    "per_order_company_online_fees",
+    "per_order_client_online_fees",
+    "per_order_total_fees_in_cents",
+    "user_count",
+    "currency",
+];
+
+const TICKET_COUNTS_COLUMNS: &[&str] = &[
+    "organization_id",
+    "event_id",
+    "ticket_type_id",
+    "ticket_name",
+    "ticket_status",
+    "event_name",
+    "organization_name",
+    "allocation_count_including_nullified",
+    "allocation_count",
+    "unallocated_count",
+    "reserved_count",
+    "redeemed_count",
+    "purchased_count",
+    "purchased_yesterday_count",
+    "comp_purchased_yesterday_count",
+    "nullified_count",
+    "available_for_purchase_count",
+    "total_refunded_count",
+    "comp_count",
+    "comp_available_count",
+    "comp_redeemed_count",
+    "comp_purchased_count",
+    "comp_reserved_count",
+    "comp_nullified_count",
+    "hold_count",
+    "hold_available_count",
+    "hold_redeemed_count",
+    "hold_purchased_count",
+    "hold_reserved_count",
+    "hold_nullified_count",
+];
+
+const TRANSACTION_DETAILS_COLUMNS: &[&str] = &[
+    "event_name",
+    "ticket_name",
+    "quantity",
+    "actual_quantity",
+    "refunded_quantity",
+    "unit_price_in_cents",
+    "face_price_in_cents",
+    "face_price_in_cents_total",
+    "gross",
+    "client_fee_in_cents",
+    "client_fee_in_cents_total",
+    "fee_range_id",
+    "item_type",
+    "resale_client_fee_in_cents",
+    "currency",
+    "tax_in_cents",
+    "tax_in_cents_total",
+    "order_type",
+    "payment_method",
+    "payment_provider",
+    "transaction_date",
+    "redemption_code",
+    "order_id",
+    "event_id",
+    "user_id",
+    "first_name",
+    "last_name",
+    "email",
+    "event_start",
+    "promo_discount_value_in_cents",
+    "promo_quantity",
+    "promo_code_name",
+    "promo_redemption_code",
+    "source",
+    "medium",
+    "campaign",
+    "term",
+    "content",
+    "platform",
+    "check_in_source",
+];
+
+/// Report definition saved by an organization. When it has a cron `schedule` the report is run by a
+/// `SendSavedReport` domain action at `next_run_at` (in the organization's timezone) and emailed to its
+/// subscribers as an attachment.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "saved_reports"]
+pub struct SavedReport {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub report_type: SavedReportTypes,
+    pub filters: Value,
+    pub columns: Vec<String>,
+    pub format: ReportFormats,
+    pub schedule: Option<String>,
+    pub next_run_at: Option<NaiveDateTime>,
+    pub last_run_at: Option<NaiveDateTime>,
+    pub created_by_user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SavedReportFilters {
+    pub event_id: Option<Uuid>,
+    pub query: Option<String>,
+    /// Limits transaction details to the given number of days before the report is run
+    pub period_in_days: Option<u32>,
+}
+
+/// Report results limited to the selected columns, in order
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReportTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "saved_reports"]
+pub struct NewSavedReport {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub report_type: SavedReportTypes,
+    pub filters: Value,
+    pub columns: Vec<String>,
+    pub format: ReportFormats,
+    pub schedule: Option<String>,
+    pub created_by_user_id: Uuid,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "saved_reports"]
+pub struct SavedReportEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    pub filters: Option<Value>,
+    pub columns: Option<Vec<String>>,
+    pub format: Option<ReportFormats>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub schedule: Option<Option<String>>,
+}
+
+impl NewSavedReport {
+    pub fn commit(self, conn: &PgConnection) -> Result<SavedReport, DatabaseError> {
+        validate_record(
+            self.organization_id,
+            &self.name,
+            self.report_type,
+            &self.filters,
+            &self.columns,
+            self.schedule.as_ref(),
+            true,
+            conn,
+        )?;
+        let result: SavedReport = diesel::insert_into(saved_reports::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create saved report")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SavedReportCreated,
+            format!("Saved report '{}' created", result.name),
+            Tables::SavedReports,
+            Some(result.id),
+            Some(result.created_by_user_id),
+            Some(json!({
+                "organization_id": result.organization_id,
+                "report_type": result.report_type,
+                "schedule": result.schedule
+            })),
+        )
+        .commit(conn)?;
+
+        result.schedule_next_run(conn)
+    }
+}
+
+impl SavedReport {
+    pub fn create(
+        organization_id: Uuid,
+        name: String,
+        report_type: SavedReportTypes,
+        filters: SavedReportFilters,
+        columns: Vec<String>,
+        format: ReportFormats,
+        schedule: Option<String>,
+        created_by_user_id: Uuid,
+    ) -> NewSavedReport {
+        NewSavedReport {
+            organization_id,
+            name,
+            report_type,
+            filters: json!(filters),
+            columns,
+            format,
+            schedule,
+            created_by_user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SavedReport, DatabaseError> {
+        saved_reports::table
+            .filter(saved_reports::id.eq(id))
+            .filter(saved_reports::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading saved report")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<SavedReport>, DatabaseError> {
+        saved_reports::table
+            .filter(saved_reports::organization_id.eq(organization_id))
+            .filter(saved_reports::deleted_at.is_null())
+            .order_by(saved_reports::name)
+            .then_order_by(saved_reports::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load saved reports for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn filters(&self) -> Result<SavedReportFilters, DatabaseError> {
+        serde_json::from_value(self.filters.clone())
+            .map_err(|e| DatabaseError::business_process_error::<SavedReportFilters>(&e.to_string()).unwrap_err())
+    }
+
+    pub fn subscribers(&self, conn: &PgConnection) -> Result<Vec<SavedReportSubscriber>, DatabaseError> {
+        SavedReportSubscriber::find_all(self.id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: SavedReportEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<SavedReport, DatabaseError> {
+        validate_record(
+            self.organization_id,
+            attributes.name.as_ref().unwrap_or(&self.name),
+            self.report_type,
+            attributes.filters.as_ref().unwrap_or(&self.filters),
+            attributes.columns.as_ref().unwrap_or(&self.columns),
+            match attributes.schedule {
+                Some(ref schedule) => schedule.as_ref(),
+                None => self.schedule.as_ref(),
+            },
+            false,
+            conn,
+        )?;
+        let result: SavedReport = diesel::update(self)
+            .set((&attributes, saved_reports::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update saved report")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SavedReportUpdated,
+            format!("Saved report '{}' updated", result.name),
+            Tables::SavedReports,
+            Some(result.id),
+            current_user_id,
+            Some(json!({
+                "filters": attributes.filters,
+                "columns": attributes.columns,
+                "format": attributes.format,
+                "schedule": attributes.schedule
+            })),
+        )
+        .commit(conn)?;
+
+        result.schedule_next_run(conn)
+    }
+
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let result: SavedReport = diesel::update(self)
+            .set((
+                saved_reports::deleted_at.eq(dsl::now.nullable()),
+                saved_reports::next_run_at.eq(None::<NaiveDateTime>),
+                saved_reports::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete saved report")?;
+        result.cancel_pending_runs(None, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::SavedReportDeleted,
+            format!("Saved report '{}' deleted", result.name),
+            Tables::SavedReports,
+            Some(result.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Next time the schedule is due after now in the organization's timezone
+    pub fn next_run_date(&self, conn: &PgConnection) -> Result<Option<NaiveDateTime>, DatabaseError> {
+        let schedule: CronSchedule = match self.schedule {
+            Some(ref schedule) => schedule
+                .parse()
+                .map_err(|e: String| DatabaseError::business_process_error::<CronSchedule>(&e).unwrap_err())?,
+            None => return Ok(None),
+        };
+        let timezone = self.organization(conn)?.timezone()?;
+        Ok(schedule.next_after(Utc::now().naive_utc(), &timezone))
+    }
+
+    /// Replaces any pending `SendSavedReport` action with one at the next scheduled run
+    pub fn schedule_next_run(&self, conn: &PgConnection) -> Result<SavedReport, DatabaseError> {
+        self.schedule_run_after(None, conn)
+    }
+
+    /// Schedules the next run, `current_action_id` is the run in progress which is left to complete
+    fn schedule_run_after(
+        &self,
+        current_action_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<SavedReport, DatabaseError> {
+        self.cancel_pending_runs(current_action_id, conn)?;
+        let next_run_at = if self.deleted_at.is_none() {
+            self.next_run_date(conn)?
+        } else {
+            None
+        };
+
+        if let Some(next_run_at) = next_run_at {
+            let mut action = DomainAction::create(
+                None,
+                DomainActionTypes::SendSavedReport,
+                None,
+                json!({}),
+                Some(Tables::SavedReports),
+                Some(self.id),
+            );
+            action.schedule_at(next_run_at);
+            action.commit(conn)?;
+        }
+
+        diesel::update(self)
+            .set(saved_reports::next_run_at.eq(next_run_at))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not schedule saved report")
+    }
+
+    /// Records the run of the `SendSavedReport` action as sent and schedules the next run. Recipients that could not be
+    /// sent the report are recorded as `failed_recipients`.
+    pub fn mark_sent(
+        &self,
+        action_id: Uuid,
+        recipients: Vec<String>,
+        failed_recipients: Vec<String>,
+        conn: &PgConnection,
+    ) -> Result<SavedReport, DatabaseError> {
+        let result: SavedReport = diesel::update(self)
+            .set((
+                saved_reports::last_run_at.eq(dsl::now.nullable()),
+                saved_reports::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update saved report")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SavedReportSent,
+            format!("Saved report '{}' sent", result.name),
+            Tables::SavedReports,
+            Some(result.id),
+            None,
+            Some(json!({
+                "domain_action_id": action_id,
+                "recipients": recipients,
+                "failed_recipients": failed_recipients
+            })),
+        )
+        .commit(conn)?;
+
+        result.schedule_run_after(Some(action_id), conn)
+    }
+
+    /// Runs the underlying report and returns the selected columns, all available columns are
+    /// included when none were selected
+    pub fn run(&self, conn: &PgConnection) -> Result<ReportTable, DatabaseError> {
+        let filters = self.filters()?;
+        let organization_id = Some(self.organization_id);
+        let rows: Vec<Value> = match self.report_type {
+            SavedReportTypes::PromoCode => Report::promo_code_report(filters.event_id, organization_id, conn)?
+                .into_iter()
+                .map(|row| json!(row))
+                .collect(),
+            SavedReportTypes::TicketCounts => Report::ticket_count_report(filters.event_id, organization_id, conn)?
+                .counts
+                .into_iter()
+                .map(|row| json!(row))
+                .collect(),
+            SavedReportTypes::TransactionDetails => {
+                let end = Utc::now().naive_utc();
+                let start = filters.period_in_days.map(|days| end - Duration::days(days as i64));
+                Report::transaction_detail_report(
+                    filters.query.clone(),
+                    filters.event_id,
+                    organization_id,
+                    start,
+                    Some(end),
+                    0,
+                    u32::MAX,
+                    conn,
+                )?
+                .data
+                .into_iter()
+                .map(|row| json!(row))
+                .collect()
+            }
+        };
+
+        let columns = if self.columns.is_empty() {
+            self.report_type.available_columns()
+        } else {
+            self.columns.clone()
+        };
+        let rows = rows
+            .iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|column| row.get(column).cloned().unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect();
+
+        Ok(ReportTable { columns, rows })
+    }
+
+    fn cancel_pending_runs(&self, current_action_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        for action in DomainAction::find_by_resource(
+            Some(Tables::SavedReports),
+            Some(self.id),
+            DomainActionTypes::SendSavedReport,
+            DomainActionStatus::Pending,
+            conn,
+        )? {
+            // The running action is still pending until it completes
+            if Some(action.id) == current_action_id {
+                continue;
+            }
+            action.set_cancelled(conn)?;
+        }
+        Ok(())
+    }
+}
+
+impl SavedReportTypes {
+    pub fn available_columns(&self) -> Vec<String> {
+        let columns = match self {
+            SavedReportTypes::PromoCode => PROMO_CODE_COLUMNS,
+            SavedReportTypes::TicketCounts => TICKET_COUNTS_COLUMNS,
+            SavedReportTypes::TransactionDetails => TRANSACTION_DETAILS_COLUMNS,
+        };
+        columns.iter().map(|c| c.to_string()).collect()
+    }
+}
+
+fn validate_record(
+    organization_id: Uuid,
+    name: &str,
+    report_type: SavedReportTypes,
+    filters: &Value,
+    columns: &[String],
+    schedule: Option<&String>,
+    new_record: bool,
+    conn: &PgConnection,
+) -> Result<(), DatabaseError> {
+    let mut validation_errors: Result<(), ValidationErrors> = Ok(());
+    if name.trim().is_empty() {
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "name",
+            Err(create_validation_error("required", "Name is required")),
+        );
+    }
+
+    match serde_json::from_value::<SavedReportFilters>(filters.clone()) {
+        Ok(filters) => {
+            if let Some(event_id) = filters.event_id {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "filters",
+                    event_ids_belong_to_organization_validation(new_record, organization_id, &vec![event_id], conn)?,
+                );
+            }
+        }
+        Err(_) => {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "filters",
+                Err(create_validation_error("invalid_filters", "Filters are invalid")),
+            );
+        }
+    }
+
+    let available_columns = report_type.available_columns();
+    if columns.iter().any(|c| !available_columns.contains(c)) {
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "columns",
+            Err(create_validation_error(
+                "invalid_column",
+                "Column is not available for this report type",
+            )),
+        );
+    }
+
+    if let Some(schedule) = schedule {
+        if schedule.parse::<CronSchedule>().is_err() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "schedule",
+                Err(create_validation_error(
+                    "invalid_schedule",
+                    "Schedule must be a valid cron expression",
+                )),
+            );
+        }
+    }
+
+    Ok(validation_errors?)
+}
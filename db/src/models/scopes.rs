@@ -48,12 +48,14 @@ pub enum Scopes {
     OrgReadEvents,
     OrgReports,
     OrgUsers,
+    OrgWebhooks,
     OrgWrite,
     RedeemTicket,
     RegionWrite,
     SettlementRead,
     SettlementReadEarly,
     SettlementWrite,
+    TaxRuleWrite,
     TransferCancel,
     TransferCancelAccepted,
     TransferCancelOwn,
@@ -124,11 +126,13 @@ impl fmt::Display for Scopes {
             Scopes::OrgWrite => "org:write",
             Scopes::OrgAdminUsers => "org:admin-users",
             Scopes::OrgUsers => "org:users",
+            Scopes::OrgWebhooks => "org:webhooks",
             Scopes::RedeemTicket => "redeem:ticket",
             Scopes::RegionWrite => "region:write",
             Scopes::SettlementRead => "settlement:read",
             Scopes::SettlementReadEarly => "settlement:read-early",
             Scopes::SettlementWrite => "settlement:write",
+            Scopes::TaxRuleWrite => "tax-rule:write",
             Scopes::TicketAdmin => "ticket:admin",
             Scopes::TicketRead => "ticket:read",
             Scopes::TicketWrite => "ticket:write",
@@ -195,11 +199,13 @@ impl FromStr for Scopes {
             "org:write" => Scopes::OrgWrite,
             "org:admin-users" => Scopes::OrgAdminUsers,
             "org:users" => Scopes::OrgUsers,
+            "org:webhooks" => Scopes::OrgWebhooks,
             "redeem:ticket" => Scopes::RedeemTicket,
             "region:write" => Scopes::RegionWrite,
             "settlement:read" => Scopes::SettlementRead,
             "settlement:read-early" => Scopes::SettlementReadEarly,
             "settlement:write" => Scopes::SettlementWrite,
+            "tax-rule:write" => Scopes::TaxRuleWrite,
             "ticket:admin" => Scopes::TicketAdmin,
             "ticket:read" => Scopes::TicketRead,
             "ticket:write" => Scopes::TicketWrite,
@@ -360,6 +366,7 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
                 Scopes::OrgWrite,
                 Scopes::UserRead,
                 Scopes::OrgUsers,
+                Scopes::OrgWebhooks,
                 Scopes::EventDataRead,
                 Scopes::EventFinancialReports,
                 Scopes::EventReports,
@@ -386,6 +393,7 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
                 Scopes::RegionWrite,
                 Scopes::SettlementReadEarly,
                 Scopes::SettlementWrite,
+                Scopes::TaxRuleWrite,
                 Scopes::TransferCancelAccepted,
             ];
             roles.extend(get_scopes_for_role(OrgOwner));
@@ -444,6 +452,7 @@ fn get_scopes_for_role_test() {
             Scopes::OrgRead,
             Scopes::OrgReports,
             Scopes::OrgUsers,
+            Scopes::OrgWebhooks,
             Scopes::OrgWrite,
             Scopes::RedeemTicket,
             Scopes::SettlementRead,
@@ -517,6 +526,7 @@ fn get_scopes_test() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "settlement:read",
@@ -584,12 +594,14 @@ fn get_scopes_test() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "region:write",
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket-type:read",
             "ticket-type:write",
             "ticket:admin",
@@ -656,12 +668,14 @@ fn get_scopes_test() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "region:write",
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket-type:read",
             "ticket-type:write",
             "ticket:admin",
@@ -727,12 +741,14 @@ fn get_scopes_test() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "region:write",
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
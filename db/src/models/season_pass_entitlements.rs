@@ -0,0 +1,252 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{self, exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{season_pass_entitlements, season_pass_redemptions};
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A purchased season pass. Each entitlement admits its holder once to every event of the pass.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "season_pass_entitlements"]
+pub struct SeasonPassEntitlement {
+    pub id: Uuid,
+    pub season_pass_id: Uuid,
+    pub order_item_id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub redeem_key: String,
+    pub status: SeasonPassEntitlementStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "season_pass_entitlements"]
+struct NewSeasonPassEntitlement {
+    season_pass_id: Uuid,
+    order_item_id: Uuid,
+    user_id: Uuid,
+    redeem_key: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "season_pass_redemptions"]
+pub struct SeasonPassRedemption {
+    pub id: Uuid,
+    pub season_pass_entitlement_id: Uuid,
+    pub event_id: Uuid,
+    pub redeemed_by_user_id: Uuid,
+    pub check_in_source: CheckInSource,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "season_pass_redemptions"]
+struct NewSeasonPassRedemption {
+    season_pass_entitlement_id: Uuid,
+    event_id: Uuid,
+    redeemed_by_user_id: Uuid,
+    check_in_source: CheckInSource,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeasonPassEntitlement {
+    pub id: Uuid,
+    pub season_pass: DisplaySeasonPass,
+    pub redeem_key: String,
+    pub status: SeasonPassEntitlementStatus,
+    pub redeemed_event_ids: Vec<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+impl SeasonPassEntitlement {
+    /// Creates an entitlement per unit purchased on the order item
+    pub(crate) fn create_for_order_item(
+        order_item: &OrderItem,
+        user_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<SeasonPassEntitlement>, DatabaseError> {
+        let season_pass_id = match order_item.season_pass_id {
+            Some(season_pass_id) => season_pass_id,
+            None => return DatabaseError::business_process_error("Order item is not for a season pass"),
+        };
+
+        let new_entitlements: Vec<NewSeasonPassEntitlement> = (0..order_item.quantity)
+            .map(|_| NewSeasonPassEntitlement {
+                season_pass_id,
+                order_item_id: order_item.id,
+                user_id,
+                redeem_key: generate_redeem_key(9),
+            })
+            .collect();
+        let entitlements: Vec<SeasonPassEntitlement> = diesel::insert_into(season_pass_entitlements::table)
+            .values(&new_entitlements)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create season pass entitlements")?;
+
+        for entitlement in &entitlements {
+            DomainEvent::create(
+                DomainEventTypes::SeasonPassEntitlementPurchased,
+                "Season pass purchased".to_string(),
+                Tables::SeasonPassEntitlements,
+                Some(entitlement.id),
+                current_user_id,
+                Some(json!({"season_pass_id": season_pass_id, "order_item_id": order_item.id, "user_id": user_id})),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(entitlements)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SeasonPassEntitlement, DatabaseError> {
+        season_pass_entitlements::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading season pass entitlement")
+    }
+
+    pub fn find_for_order_item(
+        order_item_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<SeasonPassEntitlement>, DatabaseError> {
+        season_pass_entitlements::table
+            .filter(season_pass_entitlements::order_item_id.eq(order_item_id))
+            .order_by(season_pass_entitlements::created_at)
+            .then_order_by(season_pass_entitlements::id)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load season pass entitlements for order item",
+            )
+    }
+
+    /// Active entitlements held by the user, most recent first
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<SeasonPassEntitlement>, DatabaseError> {
+        season_pass_entitlements::table
+            .filter(season_pass_entitlements::user_id.eq(user_id))
+            .filter(season_pass_entitlements::status.eq(SeasonPassEntitlementStatus::Active))
+            .order_by(season_pass_entitlements::created_at.desc())
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load season pass entitlements for user",
+            )
+    }
+
+    pub fn season_pass(&self, conn: &PgConnection) -> Result<SeasonPass, DatabaseError> {
+        SeasonPass::find(self.season_pass_id, conn)
+    }
+
+    pub fn redemptions(&self, conn: &PgConnection) -> Result<Vec<SeasonPassRedemption>, DatabaseError> {
+        season_pass_redemptions::table
+            .filter(season_pass_redemptions::season_pass_entitlement_id.eq(self.id))
+            .order_by(season_pass_redemptions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load season pass redemptions")
+    }
+
+    /// Checks the holder in to one of the events covered by the season pass. Each event can only be
+    /// redeemed once per entitlement.
+    pub fn redeem(
+        id: Uuid,
+        event_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        check_in_source: CheckInSource,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        let entitlement = SeasonPassEntitlement::find(id, conn)?;
+        if entitlement.status != SeasonPassEntitlementStatus::Active
+            || entitlement.redeem_key != redeem_key
+            || !entitlement.season_pass(conn)?.includes_event(event_id, conn)?
+        {
+            return Ok(RedeemResults::TicketInvalid);
+        }
+
+        let inserted = diesel::insert_into(season_pass_redemptions::table)
+            .values(&NewSeasonPassRedemption {
+                season_pass_entitlement_id: entitlement.id,
+                event_id,
+                redeemed_by_user_id: user_id,
+                check_in_source,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not redeem season pass")?;
+        if inserted == 0 {
+            return Ok(RedeemResults::TicketAlreadyRedeemed);
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::SeasonPassEntitlementRedeemed,
+            "Season pass redeemed".to_string(),
+            Tables::SeasonPassEntitlements,
+            Some(entitlement.id),
+            Some(user_id),
+            Some(json!({ "event_id": event_id })),
+        )
+        .commit(conn)?;
+
+        Ok(RedeemResults::TicketRedeemSuccess)
+    }
+
+    /// Entitlements can only be refunded before they have been used for any event
+    pub fn is_refundable(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        if self.status != SeasonPassEntitlementStatus::Active {
+            return Ok(false);
+        }
+
+        let redeemed: bool = select(exists(
+            season_pass_redemptions::table.filter(season_pass_redemptions::season_pass_entitlement_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check season pass redemptions")?;
+        Ok(!redeemed)
+    }
+
+    pub(crate) fn refund(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<SeasonPassEntitlement, DatabaseError> {
+        if !self.is_refundable(conn)? {
+            return DatabaseError::business_process_error("Season pass has been used or is already refunded");
+        }
+
+        let entitlement: SeasonPassEntitlement = diesel::update(self)
+            .set((
+                season_pass_entitlements::status.eq(SeasonPassEntitlementStatus::Refunded),
+                season_pass_entitlements::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not refund season pass")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SeasonPassEntitlementRefunded,
+            "Season pass refunded".to_string(),
+            Tables::SeasonPassEntitlements,
+            Some(entitlement.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(entitlement)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplaySeasonPassEntitlement, DatabaseError> {
+        Ok(DisplaySeasonPassEntitlement {
+            id: self.id,
+            season_pass: self.season_pass(conn)?.for_display(conn)?,
+            redeem_key: self.redeem_key.clone(),
+            status: self.status,
+            redeemed_event_ids: self.redemptions(conn)?.into_iter().map(|r| r.event_id).collect(),
+            created_at: self.created_at,
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use models::*;
+use schema::{season_pass_settlement_entries, season_passes};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+/// Season pass sales and refunds included in a settlement. Season passes are not sold for a single event
+/// so they are settled separately from the event's `SettlementEntry` records.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "season_pass_settlement_entries"]
+pub struct SeasonPassSettlementEntry {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub season_pass_id: Uuid,
+    pub face_value_in_cents: i64,
+    pub revenue_share_value_in_cents: i64,
+    pub online_sold_quantity: i64,
+    pub fee_sold_quantity: i64,
+    pub total_sales_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplaySeasonPassSettlementEntry {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub season_pass_id: Uuid,
+    pub season_pass_name: String,
+    pub face_value_in_cents: i64,
+    pub revenue_share_value_in_cents: i64,
+    pub online_sold_quantity: i64,
+    pub fee_sold_quantity: i64,
+    pub total_sales_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl SeasonPassSettlementEntry {
+    pub fn find_for_settlement(
+        settlement: &Settlement,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplaySeasonPassSettlementEntry>, DatabaseError> {
+        season_pass_settlement_entries::table
+            .inner_join(season_passes::table)
+            .filter(season_pass_settlement_entries::settlement_id.eq(settlement.id))
+            .select((
+                season_pass_settlement_entries::id,
+                season_pass_settlement_entries::settlement_id,
+                season_pass_settlement_entries::season_pass_id,
+                season_passes::name,
+                season_pass_settlement_entries::face_value_in_cents,
+                season_pass_settlement_entries::revenue_share_value_in_cents,
+                season_pass_settlement_entries::online_sold_quantity,
+                season_pass_settlement_entries::fee_sold_quantity,
+                season_pass_settlement_entries::total_sales_in_cents,
+                season_pass_settlement_entries::created_at,
+                season_pass_settlement_entries::updated_at,
+            ))
+            .order_by(season_passes::name)
+            .then_order_by(season_pass_settlement_entries::face_value_in_cents)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load season pass settlement entries")
+    }
+}
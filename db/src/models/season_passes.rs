@@ -0,0 +1,239 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{self, exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{events, season_pass_events, season_passes};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "season_passes"]
+pub struct SeasonPass {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub price_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "season_passes"]
+pub struct NewSeasonPass {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub price_in_cents: i64,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "season_passes"]
+pub struct SeasonPassEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    pub price_in_cents: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[table_name = "season_pass_events"]
+struct NewSeasonPassEvent {
+    season_pass_id: Uuid,
+    event_id: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeasonPass {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub price_in_cents: i64,
+    pub events: Vec<DisplaySeasonPassEvent>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplaySeasonPassEvent {
+    pub id: Uuid,
+    pub name: String,
+    pub event_start: Option<NaiveDateTime>,
+}
+
+impl NewSeasonPass {
+    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<SeasonPass, DatabaseError> {
+        if self.price_in_cents < 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+
+        let season_pass: SeasonPass = diesel::insert_into(season_passes::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create season pass")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SeasonPassCreated,
+            "Season pass created".to_string(),
+            Tables::SeasonPasses,
+            Some(season_pass.id),
+            current_user_id,
+            Some(json!(&self)),
+        )
+        .commit(conn)?;
+
+        Ok(season_pass)
+    }
+}
+
+impl SeasonPass {
+    pub fn create(organization_id: Uuid, name: String, price_in_cents: i64) -> NewSeasonPass {
+        NewSeasonPass {
+            organization_id,
+            name,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SeasonPass, DatabaseError> {
+        season_passes::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading season pass")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<SeasonPass>, DatabaseError> {
+        season_passes::table
+            .filter(season_passes::organization_id.eq(organization_id))
+            .order_by(season_passes::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load season passes for organization")
+    }
+
+    pub fn update(
+        &self,
+        attributes: SeasonPassEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<SeasonPass, DatabaseError> {
+        if attributes.price_in_cents.map(|p| p < 0).unwrap_or(false) {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+
+        let season_pass: SeasonPass = diesel::update(self)
+            .set((&attributes, season_passes::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update season pass")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SeasonPassUpdated,
+            "Season pass updated".to_string(),
+            Tables::SeasonPasses,
+            Some(season_pass.id),
+            current_user_id,
+            Some(json!({"name": attributes.name, "price_in_cents": attributes.price_in_cents})),
+        )
+        .commit(conn)?;
+
+        Ok(season_pass)
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .inner_join(season_pass_events::table.on(season_pass_events::event_id.eq(events::id)))
+            .filter(season_pass_events::season_pass_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .select(events::all_columns)
+            .order_by(events::event_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for season pass")
+    }
+
+    /// Replaces the events covered by the season pass. Events must belong to the pass' organization.
+    pub fn set_events(
+        &self,
+        event_ids: &[Uuid],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let organization_event_count: i64 = events::table
+            .filter(events::id.eq_any(event_ids))
+            .filter(events::organization_id.eq(self.organization_id))
+            .filter(events::deleted_at.is_null())
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check events for season pass")?;
+        let mut unique_event_ids = event_ids.to_vec();
+        unique_event_ids.sort();
+        unique_event_ids.dedup();
+        if organization_event_count as usize != unique_event_ids.len() {
+            return DatabaseError::validation_error(
+                "event_ids",
+                "Season pass events must belong to the season pass organization",
+            );
+        }
+
+        diesel::delete(season_pass_events::table.filter(season_pass_events::season_pass_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove season pass events")?;
+
+        let new_events: Vec<NewSeasonPassEvent> = unique_event_ids
+            .iter()
+            .map(|event_id| NewSeasonPassEvent {
+                season_pass_id: self.id,
+                event_id: *event_id,
+            })
+            .collect();
+        if !new_events.is_empty() {
+            diesel::insert_into(season_pass_events::table)
+                .values(&new_events)
+                .execute(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not add season pass events")?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::SeasonPassUpdated,
+            "Season pass events updated".to_string(),
+            Tables::SeasonPasses,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "event_ids": unique_event_ids })),
+        )
+        .commit(conn)?;
+
+        self.events(conn)
+    }
+
+    pub fn includes_event(&self, event_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            season_pass_events::table
+                .filter(season_pass_events::season_pass_id.eq(self.id))
+                .filter(season_pass_events::event_id.eq(event_id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if season pass includes event")
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplaySeasonPass, DatabaseError> {
+        let events = events::table
+            .inner_join(season_pass_events::table.on(season_pass_events::event_id.eq(events::id)))
+            .filter(season_pass_events::season_pass_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .select((events::id, events::name, events::event_start))
+            .order_by(events::event_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for season pass")?;
+
+        Ok(DisplaySeasonPass {
+            id: self.id,
+            organization_id: self.organization_id,
+            name: self.name.clone(),
+            price_in_cents: self.price_in_cents,
+            events,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::NaiveDateTime;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use models::*;
+use schema::{assets, seats, stage_sections, stages, ticket_instances, ticket_types};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+/// SQL fragment resolving the human readable seat label (e.g. `Orchestra, Row C, Seat 12`) for
+/// `ticket_instances.seat_id`.
+pub(crate) const TICKET_INSTANCE_SEAT_LABEL_SQL: &str =
+    "(SELECT CONCAT(ss.name, ', Row ', s.row_name, ', Seat ', s.seat_number)
+    FROM seats s
+    INNER JOIN stage_sections ss ON ss.id = s.stage_section_id
+    WHERE s.id = ticket_instances.seat_id)";
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(StageSection)]
+#[table_name = "seats"]
+pub struct Seat {
+    pub id: Uuid,
+    pub stage_section_id: Uuid,
+    pub row_name: String,
+    pub seat_number: String,
+    pub rank: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seats"]
+pub struct NewSeat {
+    pub stage_section_id: Uuid,
+    pub row_name: String,
+    pub seat_number: String,
+    pub rank: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplayEventSeat {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub section_name: String,
+    pub row_name: String,
+    pub seat_number: String,
+    pub available: bool,
+}
+
+impl Seat {
+    pub fn create(stage_section_id: Uuid, row_name: String, seat_number: String, rank: i32) -> NewSeat {
+        NewSeat {
+            stage_section_id,
+            row_name,
+            seat_number,
+            rank,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        seats::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading seat")
+    }
+
+    pub fn find_by_ids(ids: &[Uuid], conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        seats::table
+            .filter(seats::id.eq_any(ids))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading seats")
+    }
+
+    /// Confirms every seat in `seat_ids` exists and belongs to a stage at `venue_id`.
+    pub fn validate_for_venue(
+        seat_ids: &[Uuid],
+        venue_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let mut unique_seat_ids = seat_ids.to_vec();
+        unique_seat_ids.sort();
+        unique_seat_ids.dedup();
+        if unique_seat_ids.len() != seat_ids.len() {
+            return DatabaseError::validation_error("seat_ids", "Seats can only be selected once");
+        }
+
+        let venue_id = match venue_id {
+            Some(venue_id) => venue_id,
+            None => return DatabaseError::validation_error("seat_ids", "Event does not have a venue with seating"),
+        };
+
+        let found: i64 = seats::table
+            .inner_join(stage_sections::table.inner_join(stages::table))
+            .filter(seats::id.eq_any(seat_ids))
+            .filter(stages::venue_id.eq(venue_id))
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not validate seats")?;
+
+        if found as usize != seat_ids.len() {
+            return DatabaseError::validation_error("seat_ids", "Seats do not belong to the event venue");
+        }
+
+        Ok(())
+    }
+
+    /// Locks the seats until the end of the transaction so concurrent assignments of the same seats
+    /// wait for each other rather than both passing the check for seats already assigned.
+    pub(crate) fn lock(seat_ids: &[Uuid], conn: &PgConnection) -> Result<(), DatabaseError> {
+        seats::table
+            .filter(seats::id.eq_any(seat_ids))
+            .order_by(seats::id)
+            .select(seats::id)
+            .for_update()
+            .load::<Uuid>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock seats")?;
+        Ok(())
+    }
+
+    /// Seats bound to ticket types of the event along with whether they can currently be reserved
+    /// from the general pool, ordered best available first.
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<DisplayEventSeat>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.inner_join(ticket_types::table))
+            .inner_join(seats::table.inner_join(stage_sections::table))
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(ticket_instances::status.ne(TicketInstanceStatus::Nullified))
+            .select((
+                seats::id,
+                ticket_types::id,
+                stage_sections::name,
+                seats::row_name,
+                seats::seat_number,
+                sql::<Bool>(
+                    "ticket_instances.hold_id IS NULL AND (ticket_instances.status = 'Available'
+                    OR (ticket_instances.status = 'Reserved' AND ticket_instances.reserved_until < now()))",
+                ),
+            ))
+            .order_by(stage_sections::rank)
+            .then_order_by(stage_sections::name)
+            .then_order_by(seats::rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats for event")
+    }
+}
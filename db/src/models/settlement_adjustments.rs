@@ -18,6 +18,7 @@ pub struct SettlementAdjustment {
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub order_id: Option<Uuid>,
 }
 
 impl SettlementAdjustment {
@@ -32,6 +33,7 @@ impl SettlementAdjustment {
             amount_in_cents,
             note,
             settlement_adjustment_type,
+            order_id: None,
         }
     }
 
@@ -42,6 +44,14 @@ impl SettlementAdjustment {
             .to_db_error(ErrorCode::QueryError, "Could not load Settlement Adjustment")
     }
 
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<SettlementAdjustment>, DatabaseError> {
+        settlement_adjustments::table
+            .filter(settlement_adjustments::order_id.eq(order_id))
+            .order_by(settlement_adjustments::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load Settlement Adjustments for order")
+    }
+
     pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
         diesel::delete(settlement_adjustments::table.filter(settlement_adjustments::id.eq(self.id)))
             .execute(conn)
@@ -56,6 +66,7 @@ pub struct NewSettlementAdjustment {
     pub amount_in_cents: i64,
     pub note: Option<String>,
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
+    pub order_id: Option<Uuid>,
 }
 impl NewSettlementAdjustment {
     pub fn commit(&self, conn: &PgConnection) -> Result<SettlementAdjustment, DatabaseError> {
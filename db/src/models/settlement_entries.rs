@@ -26,6 +26,8 @@ pub struct SettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Sales tax or VAT on the tickets in this entry, included in total_sales_in_cents only when exclusive
+    pub tax_in_cents: i64,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -40,6 +42,7 @@ pub struct DisplaySettlementEntry {
     pub online_sold_quantity: i64,
     pub fee_sold_quantity: i64,
     pub total_sales_in_cents: i64,
+    pub tax_in_cents: i64,
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -71,6 +74,7 @@ impl SettlementEntry {
                 settlement_entries::online_sold_quantity,
                 settlement_entries::fee_sold_quantity,
                 settlement_entries::total_sales_in_cents,
+                settlement_entries::tax_in_cents,
                 settlement_entries::settlement_entry_type,
                 settlement_entries::created_at,
                 settlement_entries::updated_at,
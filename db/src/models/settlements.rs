@@ -14,6 +14,7 @@ use uuid::Uuid;
 use validators;
 
 sql_function!(fn process_settlement_for_event(settlement_id: dUuid, event_id: dUuid, start_time: Nullable<Timestamp>, end_time: Nullable<Timestamp>));
+sql_function!(fn process_settlement_for_season_passes(settlement_id: dUuid, start_time: Timestamp, end_time: Timestamp));
 
 pub const DEFAULT_SETTLEMENT_PERIOD_IN_DAYS: i64 = 7;
 
@@ -29,9 +30,10 @@ pub struct Settlement {
     pub only_finished_events: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
 }
 
-#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "settlements"]
 pub struct NewSettlement {
     pub organization_id: Uuid,
@@ -40,6 +42,8 @@ pub struct NewSettlement {
     pub status: SettlementStatus,
     pub comment: Option<String>,
     pub only_finished_events: bool,
+    /// Only events in this currency are settled, defaults to the organization's currency
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -47,21 +51,27 @@ pub struct DisplaySettlement {
     pub settlement: Settlement,
     pub adjustments: Vec<SettlementAdjustment>,
     pub event_entries: Vec<EventGroupedSettlementEntry>,
+    pub season_pass_entries: Vec<DisplaySeasonPassSettlementEntry>,
 }
 
 impl NewSettlement {
     pub fn commit(&self, user: Option<User>, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
-        self.validate_record()?;
+        let mut new_settlement = self.clone();
+        if new_settlement.currency.is_none() {
+            new_settlement.currency = Some(Organization::find(self.organization_id, conn)?.currency);
+        }
+        new_settlement.validate_record()?;
 
         let settlement = DatabaseError::wrap(
             ErrorCode::InsertError,
             "Could not create new settlement",
             diesel::insert_into(settlements::table)
-                .values(self)
+                .values(&new_settlement)
                 .get_result::<Settlement>(conn),
         )?;
 
         settlement.create_entries(conn)?;
+        PendingSettlementAdjustment::apply_to_settlement(&settlement, conn)?;
 
         DomainEvent::create(
             DomainEventTypes::SettlementReportProcessed,
@@ -89,6 +99,14 @@ impl NewSettlement {
                 "end_time",
             ),
         );
+        let validation_errors = match self.currency {
+            Some(ref currency) => validators::append_validation_error(
+                validation_errors,
+                "currency",
+                validators::validate_currency(currency),
+            ),
+            None => validation_errors,
+        };
 
         Ok(validation_errors?)
     }
@@ -110,6 +128,7 @@ impl Settlement {
             status,
             comment,
             only_finished_events,
+            currency: None,
         }
     }
 
@@ -125,6 +144,20 @@ impl Settlement {
             .to_db_error(ErrorCode::QueryError, "Could not load settlement")
     }
 
+    /// Most recent settlement for the organization that has not yet been paid out
+    pub fn find_pending_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization_id))
+            .filter(settlements::status.eq(SettlementStatus::PendingSettlement))
+            .order_by(settlements::end_time.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load settlement")
+    }
+
     pub fn process_settlement_for_organization(
         organization: &Organization,
         settlement_period_in_days: Option<u32>,
@@ -181,6 +214,7 @@ impl Settlement {
             settlement: self.clone(),
             adjustments,
             event_entries: SettlementEntry::find_for_settlement_by_event(self, conn)?,
+            season_pass_entries: SeasonPassSettlementEntry::find_for_settlement(self, conn)?,
         })
     }
 
@@ -205,10 +239,17 @@ impl Settlement {
             EventStatus::Published,
             conn,
         )?;
+        let ending_events: Vec<Event> = ending_events
+            .into_iter()
+            .filter(|e| e.currency == self.currency)
+            .collect();
         let events = if self.only_finished_events {
             ending_events.clone()
         } else {
             Event::get_all_events_with_transactions_between(self.organization_id, self.start_time, self.end_time, conn)?
+                .into_iter()
+                .filter(|e| e.currency == self.currency)
+                .collect()
         };
 
         // Mark ending events as having been settled
@@ -220,6 +261,15 @@ impl Settlement {
             self.create_entries_from_event_transactions(&event, conn)?;
         }
 
+        // Season passes are not tied to an event so they are settled in the period they were sold
+        select(process_settlement_for_season_passes(
+            self.id,
+            self.start_time,
+            self.end_time,
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::InsertError, "Could not process settlement for season passes")?;
+
         Ok(())
     }
 
@@ -0,0 +1,141 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{seats, stage_sections, ticket_instances};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Stage)]
+#[table_name = "stage_sections"]
+pub struct StageSection {
+    pub id: Uuid,
+    pub stage_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "stage_sections"]
+pub struct NewStageSection {
+    pub stage_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayStageSection {
+    pub id: Uuid,
+    pub name: String,
+    pub rank: i32,
+    pub seats: Vec<Seat>,
+}
+
+impl NewStageSection {
+    pub fn commit(&self, conn: &PgConnection) -> Result<StageSection, DatabaseError> {
+        diesel::insert_into(stage_sections::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create stage section")
+    }
+}
+
+impl StageSection {
+    pub fn create(stage_id: Uuid, name: String, rank: i32) -> NewStageSection {
+        NewStageSection { stage_id, name, rank }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<StageSection, DatabaseError> {
+        stage_sections::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading stage section")
+    }
+
+    pub fn find_by_stage_id(stage_id: Uuid, conn: &PgConnection) -> Result<Vec<StageSection>, DatabaseError> {
+        stage_sections::table
+            .filter(stage_sections::stage_id.eq(stage_id))
+            .order_by(stage_sections::rank)
+            .then_order_by(stage_sections::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load stage sections")
+    }
+
+    pub fn stage(&self, conn: &PgConnection) -> Result<Stage, DatabaseError> {
+        Stage::find(self.stage_id, conn)
+    }
+
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        seats::table
+            .filter(seats::stage_section_id.eq(self.id))
+            .order_by(seats::rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats for stage section")
+    }
+
+    /// Adds a row of `seat_count` seats numbered from 1 to the end of this section. Seats are ranked
+    /// after any seats already in the section so best-available selection follows creation order.
+    pub fn add_row(&self, row_name: String, seat_count: u32, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        let existing_seat_count: i64 = seats::table
+            .filter(seats::stage_section_id.eq(self.id))
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to count seats for stage section")?;
+
+        let new_seats: Vec<NewSeat> = (0..seat_count)
+            .map(|i| {
+                Seat::create(
+                    self.id,
+                    row_name.clone(),
+                    (i + 1).to_string(),
+                    existing_seat_count as i32 + i as i32,
+                )
+            })
+            .collect();
+
+        diesel::insert_into(seats::table)
+            .values(&new_seats)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seats")
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayStageSection, DatabaseError> {
+        Ok(DisplayStageSection {
+            id: self.id,
+            name: self.name.clone(),
+            rank: self.rank,
+            seats: self.seats(conn)?,
+        })
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let seats_in_use: bool = select(exists(
+            ticket_instances::table
+                .inner_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+                .filter(seats::stage_section_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if seats are assigned to tickets",
+        )?;
+
+        if seats_in_use {
+            return DatabaseError::business_process_error(
+                "Unable to delete stage section, its seats are assigned to tickets",
+            );
+        }
+
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete stage section",
+            diesel::delete(self).execute(conn),
+        )
+    }
+}
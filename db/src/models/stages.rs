@@ -80,6 +80,18 @@ impl Stage {
             .to_db_error(ErrorCode::QueryError, "Unable to load all stages")
     }
 
+    pub fn sections(&self, conn: &PgConnection) -> Result<Vec<StageSection>, DatabaseError> {
+        StageSection::find_by_stage_id(self.id, conn)
+    }
+
+    pub fn seat_map(&self, conn: &PgConnection) -> Result<Vec<DisplayStageSection>, DatabaseError> {
+        let mut seat_map = Vec::new();
+        for section in self.sections(conn)? {
+            seat_map.push(section.for_display(conn)?);
+        }
+        Ok(seat_map)
+    }
+
     pub fn update(&self, attributes: StageEditableAttributes, conn: &PgConnection) -> Result<Stage, DatabaseError> {
         DatabaseError::wrap(
             ErrorCode::UpdateError,
@@ -0,0 +1,157 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::expression::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use models::*;
+use schema::tax_rules;
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+
+/// Sales tax / VAT charged on events held in a venue's jurisdiction
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+pub struct TaxRule {
+    pub id: Uuid,
+    pub name: String,
+    pub country: String,
+    /// Rules without a state apply to the whole country
+    pub state: Option<String>,
+    pub ticket_tax_percent: f32,
+    pub fee_tax_percent: f32,
+    /// Inclusive taxes are already part of the ticket and fee prices rather than added on top
+    pub inclusive: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "tax_rules"]
+pub struct TaxRuleEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub country: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub state: Option<Option<String>>,
+    pub ticket_tax_percent: Option<f32>,
+    pub fee_tax_percent: Option<f32>,
+    pub inclusive: Option<bool>,
+}
+
+#[derive(Default, Deserialize, Insertable, Serialize)]
+#[table_name = "tax_rules"]
+pub struct NewTaxRule {
+    pub name: String,
+    pub country: String,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub ticket_tax_percent: f32,
+    #[serde(default)]
+    pub fee_tax_percent: f32,
+    #[serde(default)]
+    pub inclusive: bool,
+}
+
+impl NewTaxRule {
+    pub fn commit(self, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        TaxRule::validate_record(self.ticket_tax_percent, self.fee_tax_percent)?;
+        diesel::insert_into(tax_rules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rule")
+    }
+}
+
+impl TaxRule {
+    pub fn create(
+        name: &str,
+        country: &str,
+        state: Option<String>,
+        ticket_tax_percent: f32,
+        fee_tax_percent: f32,
+    ) -> NewTaxRule {
+        NewTaxRule {
+            name: name.to_string(),
+            country: country.to_string(),
+            state,
+            ticket_tax_percent,
+            fee_tax_percent,
+            inclusive: false,
+        }
+    }
+
+    pub fn update(&self, attributes: TaxRuleEditableAttributes, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        TaxRule::validate_record(
+            attributes.ticket_tax_percent.unwrap_or(self.ticket_tax_percent),
+            attributes.fee_tax_percent.unwrap_or(self.fee_tax_percent),
+        )?;
+        diesel::update(self)
+            .set((attributes, tax_rules::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update tax rule")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        tax_rules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading tax rule")
+    }
+
+    pub fn all(conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .order_by(tax_rules::country.asc())
+            .then_order_by(tax_rules::state.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load all tax rules")
+    }
+
+    /// Finds the rule for the venue's state, falling back to the country wide rule
+    pub fn find_for_venue(venue: &Venue, conn: &PgConnection) -> Result<Option<TaxRule>, DatabaseError> {
+        let tax_rules: Vec<TaxRule> = tax_rules::table
+            .filter(sql("LOWER(country) = ").bind::<Text, _>(venue.country.to_lowercase()))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rules for venue")?;
+        let state = venue.state.to_lowercase();
+        let (state_rules, country_rules): (Vec<TaxRule>, Vec<TaxRule>) =
+            tax_rules.into_iter().partition(|r| r.state.is_some());
+
+        Ok(state_rules
+            .into_iter()
+            .find(|r| r.state.as_ref().map(|s| s.to_lowercase()) == Some(state.clone()))
+            .or_else(|| country_rules.into_iter().next()))
+    }
+
+    pub fn find_for_event(event: &Event, conn: &PgConnection) -> Result<Option<TaxRule>, DatabaseError> {
+        match event.venue(conn)? {
+            Some(venue) => TaxRule::find_for_venue(&venue, conn),
+            None => Ok(None),
+        }
+    }
+
+    /// Tax due per unit at the given percent, for inclusive rules the unit price already contains the tax
+    pub fn tax_in_cents(&self, unit_price_in_cents: i64, percent: f32) -> i64 {
+        if self.inclusive {
+            unit_price_in_cents - (unit_price_in_cents as f32 / (1f32 + percent / 100f32)).round() as i64
+        } else {
+            (unit_price_in_cents as f32 * (percent / 100f32)).round() as i64
+        }
+    }
+
+    fn validate_record(ticket_tax_percent: f32, fee_tax_percent: f32) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "ticket_tax_percent",
+            validators::validate_percent(ticket_tax_percent),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "fee_tax_percent",
+            validators::validate_percent(fee_tax_percent),
+        );
+        Ok(validation_errors?)
+    }
+}
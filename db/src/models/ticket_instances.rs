@@ -42,6 +42,7 @@ pub struct TicketInstance {
     pub first_name_override: Option<String>,
     pub last_name_override: Option<String>,
     pub check_in_source: Option<CheckInSource>,
+    pub seat_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Clone, Deserialize, Serialize)]
@@ -197,6 +198,7 @@ impl TicketInstance {
                 transfers::transfer_key.nullable(),
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
+                sql::<Nullable<Text>>(TICKET_INSTANCE_SEAT_LABEL_SQL),
             ))
             .first::<DisplayTicketIntermediary>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -298,6 +300,7 @@ impl TicketInstance {
                 transfers::transfer_key.nullable(),
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
+                sql::<Nullable<Text>>(TICKET_INSTANCE_SEAT_LABEL_SQL),
             ))
             .order_by(events::event_start.asc())
             .then_order_by(events::name.asc())
@@ -374,6 +377,7 @@ impl TicketInstance {
         ticket_type_id: Uuid,
         ticket_holding_id: Option<Uuid>,
         quantity: u32,
+        seat_ids: Option<Vec<Uuid>>,
         conn: &PgConnection,
     ) -> Result<Vec<TicketInstance>, DatabaseError> {
         let order_expires_at = expires_at.ok_or(DatabaseError::new(
@@ -387,7 +391,8 @@ impl TicketInstance {
             .bind::<sql_types::Timestamp, _>(order_expires_at)
             .bind::<sql_types::Uuid, _>(ticket_type_id)
             .bind::<sql_types::Nullable<sql_types::Uuid>, _>(ticket_holding_id)
-            .bind::<BigInt, _>(quantity as i64);
+            .bind::<BigInt, _>(quantity as i64)
+            .bind::<Nullable<Array<dUuid>>, _>(seat_ids);
         let tickets: Vec<TicketInstance> = q
             .get_results(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not reserve tickets")?;
@@ -501,6 +506,7 @@ impl TicketInstance {
         ticket_type_id: Uuid,
         quantity: u32,
         from_hold_id: Option<Uuid>,
+        seat_ids: Option<Vec<Uuid>>,
         conn: &PgConnection,
     ) -> Result<Vec<TicketInstance>, DatabaseError> {
         let query = include_str!("../queries/add_tickets_to_hold.sql");
@@ -508,7 +514,8 @@ impl TicketInstance {
             .bind::<sql_types::Uuid, _>(hold_id)
             .bind::<sql_types::Uuid, _>(ticket_type_id)
             .bind::<BigInt, _>(quantity as i64)
-            .bind::<Nullable<sql_types::Uuid>, _>(from_hold_id);
+            .bind::<Nullable<sql_types::Uuid>, _>(from_hold_id)
+            .bind::<Nullable<Array<dUuid>>, _>(seat_ids);
 
         let tickets: Vec<TicketInstance> = q
             .get_results(conn)
@@ -555,6 +562,59 @@ impl TicketInstance {
         Ok(tickets)
     }
 
+    /// Binds each seat in `seat_ids` to an available, unseated ticket of the ticket type.
+    pub(crate) fn assign_seats(
+        current_user_id: Option<Uuid>,
+        ticket_type_id: Uuid,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let query = include_str!("../queries/assign_seats_to_ticket_type.sql");
+        let tickets: Vec<TicketInstance> = diesel::sql_query(query)
+            .bind::<sql_types::Uuid, _>(ticket_type_id)
+            .bind::<Array<dUuid>, _>(seat_ids)
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign seats to tickets")?;
+
+        if tickets.len() != seat_ids.len() {
+            return DatabaseError::validation_error(
+                "seat_ids",
+                "Could not assign seats, not enough unseated tickets are available",
+            );
+        }
+
+        for ticket in tickets.iter() {
+            DomainEvent::create(
+                DomainEventTypes::TicketInstanceSeatAssigned,
+                "Seat assigned to ticket".to_string(),
+                Tables::TicketInstances,
+                Some(ticket.id),
+                current_user_id,
+                Some(json!({ "seat_id": ticket.seat_id })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(tickets)
+    }
+
+    /// Seats from `seat_ids` already bound to a ticket of the event.
+    pub fn find_assigned_seat_ids_for_event(
+        event_id: Uuid,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<Uuid>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(ticket_instances::seat_id.eq_any(seat_ids))
+            .filter(ticket_instances::status.ne(TicketInstanceStatus::Nullified))
+            .select(sql::<dUuid>("ticket_instances.seat_id"))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load assigned seats")
+    }
+
     pub fn release_from_hold(
         current_user_id: Option<Uuid>,
         hold_id: Uuid,
@@ -668,6 +728,46 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Could not load Ticket Instances")
     }
 
+    /// Nullifies the purchased tickets of an order, tickets that have been redeemed are left as is
+    pub fn nullify_for_order(
+        order_id: Uuid,
+        user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let ticket_ids = TicketInstance::find_ids_for_order(order_id, conn)?;
+        let ticket_instances: Vec<TicketInstance> = diesel::update(
+            ticket_instances::table
+                .filter(ticket_instances::id.eq_any(ticket_ids))
+                .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased)),
+        )
+        .set((
+            ticket_instances::status.eq(TicketInstanceStatus::Nullified),
+            ticket_instances::updated_at.eq(dsl::now),
+        ))
+        .get_results(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not nullify tickets")?;
+
+        for ticket_instance in &ticket_instances {
+            ticket_instance.create_nullified_domain_event(user_id, conn)?;
+        }
+        Ok(ticket_instances)
+    }
+
+    /// The earliest time a cart reservation for the ticket type lapses and returns its tickets to the pool
+    pub fn next_reservation_expiry_for_ticket_type(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<NaiveDateTime>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table)
+            .filter(assets::ticket_type_id.eq(ticket_type_id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved))
+            .filter(ticket_instances::reserved_until.gt(dsl::now.nullable()))
+            .select(dsl::min(ticket_instances::reserved_until))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load next reservation expiry")
+    }
+
     pub fn update_reserved_time(
         order_item: &OrderItem,
         reserved_time: NaiveDateTime,
@@ -771,6 +871,26 @@ impl TicketInstance {
         user_id: Uuid,
         check_in_source: CheckInSource,
         conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        TicketInstance::redeem_ticket_at(
+            ticket_id,
+            redeem_key,
+            user_id,
+            check_in_source,
+            Utc::now().naive_utc(),
+            conn,
+        )
+    }
+
+    /// Redeems the ticket recording `redeemed_at` as the check in time, used when reconciling
+    /// check ins that were made offline.
+    pub fn redeem_ticket_at(
+        ticket_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        check_in_source: CheckInSource,
+        redeemed_at: NaiveDateTime,
+        conn: &PgConnection,
     ) -> Result<RedeemResults, DatabaseError> {
         let ticket: TicketInstance = ticket_instances::table
             .find(ticket_id)
@@ -778,20 +898,31 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
         if ticket.has_pending_transfer(conn)? {
             return Ok(RedeemResults::TicketTransferInProcess);
+        } else if ResaleListing::any_active_for_ticket_instance_ids(&[ticket.id], conn)? {
+            return Ok(RedeemResults::TicketListedForResale);
         } else if ticket.status == TicketInstanceStatus::Purchased
             && ticket.redeem_key.is_some()
             && ticket.redeem_key.clone().unwrap() == redeem_key
         {
-            diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(ticket_id)))
-                .set((
-                    ticket_instances::status.eq(TicketInstanceStatus::Redeemed),
-                    ticket_instances::redeemed_by_user_id.eq(user_id),
-                    ticket_instances::redeemed_at.eq(dsl::now),
-                    ticket_instances::check_in_source.eq(check_in_source),
-                    ticket_instances::updated_at.eq(dsl::now),
-                ))
-                .execute(conn)
-                .to_db_error(ErrorCode::UpdateError, "Could not set ticket to Redeemed")?;
+            let updated = diesel::update(
+                ticket_instances::table
+                    .filter(ticket_instances::id.eq(ticket_id))
+                    .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased)),
+            )
+            .set((
+                ticket_instances::status.eq(TicketInstanceStatus::Redeemed),
+                ticket_instances::redeemed_by_user_id.eq(user_id),
+                ticket_instances::redeemed_at.eq(redeemed_at),
+                ticket_instances::check_in_source.eq(check_in_source),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not set ticket to Redeemed")?;
+
+            // Another scanner redeemed the ticket after it was loaded
+            if updated == 0 {
+                return Ok(RedeemResults::TicketAlreadyRedeemed);
+            }
 
             DomainEvent::create(
                 DomainEventTypes::TicketInstanceRedeemed,
@@ -908,6 +1039,11 @@ impl TicketInstance {
         //Confirm that tickets are purchased and owned by user
         let (wallet_id, ticket_ids_and_updated_at) =
             TicketInstance::verify_tickets_belong_to_user(user.id, ticket_ids, conn)?;
+        if ResaleListing::any_active_for_ticket_instance_ids(ticket_ids, conn)? {
+            return DatabaseError::business_process_error(
+                "Tickets listed for resale cannot be transferred, cancel the listing first",
+            );
+        }
 
         //Generate transfer_key and store keys and set transfer_expiry date
         let transfer_key = Uuid::new_v4();
@@ -1139,6 +1275,7 @@ pub struct DisplayTicket {
     pub transfer_key: Option<Uuid>,
     pub transfer_address: Option<String>,
     pub check_in_source: Option<CheckInSource>,
+    pub seat_label: Option<String>,
 }
 
 #[derive(Queryable, QueryableByName)]
@@ -1181,6 +1318,8 @@ pub struct DisplayTicketIntermediary {
     pub transfer_address: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub check_in_source: Option<CheckInSource>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_label: Option<String>,
 }
 
 impl From<DisplayTicketIntermediary> for DisplayTicket {
@@ -1223,6 +1362,7 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             transfer_key: ticket_intermediary.transfer_key,
             transfer_address: ticket_intermediary.transfer_address,
             check_in_source: ticket_intermediary.check_in_source,
+            seat_label: ticket_intermediary.seat_label,
         }
     }
 }
@@ -1282,9 +1422,10 @@ pub enum RedeemResults {
     TicketAlreadyRedeemed,
     TicketInvalid,
     TicketTransferInProcess,
+    TicketListedForResale,
 }
 
-fn generate_redeem_key(len: u32) -> String {
+pub(crate) fn generate_redeem_key(len: u32) -> String {
     let hash_char_list = vec![
         '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'M', 'N', 'P',
         'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
@@ -5,7 +5,7 @@ use diesel::dsl::{self, exists, select, sql};
 use diesel::prelude::*;
 use diesel::sql_types::{Bool, Timestamp, Uuid as dUuid};
 use models::*;
-use schema::{order_items, orders, ticket_pricing};
+use schema::{events, order_items, orders, ticket_pricing, ticket_types};
 use std::borrow::Cow;
 use utils::errors::*;
 use uuid::Uuid;
@@ -29,6 +29,7 @@ pub struct TicketPricing {
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
     pub previous_ticket_pricing_id: Option<Uuid>,
+    pub currency: String,
 }
 
 #[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
@@ -414,8 +415,15 @@ impl NewTicketPricing {
 
     pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<TicketPricing, DatabaseError> {
         self.validate_record()?;
+        // Prices are in the currency of the event
+        let currency: String = ticket_types::table
+            .inner_join(events::table)
+            .filter(ticket_types::id.eq(self.ticket_type_id))
+            .select(events::currency)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load currency for ticket pricing")?;
         let result: TicketPricing = diesel::insert_into(ticket_pricing::table)
-            .values(self)
+            .values((self, ticket_pricing::currency.eq(currency)))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create ticket pricing")?;
 
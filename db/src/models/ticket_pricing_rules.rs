@@ -0,0 +1,284 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{ticket_pricing, ticket_pricing_rules};
+use utils::errors::*;
+use uuid::Uuid;
+use validators::{self, *};
+
+/// Automatic price change for a ticket type, triggered once by sales velocity rather than a date range.
+/// `SoldQuantity` rules trigger when at least `threshold` tickets are sold or reserved and
+/// `RemainingInventory` rules when fewer than `threshold` tickets remain available.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "ticket_pricing_rules"]
+pub struct TicketPricingRule {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub name: String,
+    pub rule_type: TicketPricingRuleTypes,
+    pub threshold: i64,
+    pub price_in_cents: Option<i64>,
+    pub price_increase_percent: Option<i32>,
+    pub ticket_pricing_id: Option<Uuid>,
+    pub triggered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "ticket_pricing_rules"]
+pub struct NewTicketPricingRule {
+    pub ticket_type_id: Uuid,
+    pub name: String,
+    pub rule_type: TicketPricingRuleTypes,
+    pub threshold: i64,
+    pub price_in_cents: Option<i64>,
+    pub price_increase_percent: Option<i32>,
+}
+
+impl NewTicketPricingRule {
+    pub fn commit(
+        self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketPricingRule, DatabaseError> {
+        self.validate_record()?;
+        let result: TicketPricingRule = diesel::insert_into(ticket_pricing_rules::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ticket pricing rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketPricingRuleCreated,
+            format!("Ticket pricing rule '{}' created", result.name),
+            Tables::TicketTypes,
+            Some(result.ticket_type_id),
+            current_user_id,
+            Some(json!(result)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut validation_errors = validators::append_validation_error(
+            Ok(()),
+            "threshold",
+            validators::validate_greater_than_or_equal(
+                self.threshold,
+                0,
+                "number_must_be_positive",
+                "Threshold must be positive",
+            ),
+        );
+
+        match (self.price_in_cents, self.price_increase_percent) {
+            (Some(price_in_cents), None) => {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "price_in_cents",
+                    validators::validate_greater_than_or_equal(
+                        price_in_cents,
+                        0,
+                        "number_must_be_positive",
+                        "Price must be positive",
+                    ),
+                );
+            }
+            (None, Some(price_increase_percent)) => {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "price_increase_percent",
+                    validators::validate_greater_than(
+                        price_increase_percent,
+                        0,
+                        "number_must_be_positive",
+                        "Price increase must be positive",
+                    ),
+                );
+            }
+            _ => {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "price_in_cents",
+                    Err(create_validation_error(
+                        "price_or_increase_required",
+                        "Either a price or a price increase percentage is required",
+                    )),
+                );
+            }
+        }
+
+        Ok(validation_errors?)
+    }
+}
+
+impl TicketPricingRule {
+    pub fn create(
+        ticket_type_id: Uuid,
+        name: String,
+        rule_type: TicketPricingRuleTypes,
+        threshold: i64,
+        price_in_cents: Option<i64>,
+        price_increase_percent: Option<i32>,
+    ) -> NewTicketPricingRule {
+        NewTicketPricingRule {
+            ticket_type_id,
+            name,
+            rule_type,
+            threshold,
+            price_in_cents,
+            price_increase_percent,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TicketPricingRule, DatabaseError> {
+        ticket_pricing_rules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading ticket pricing rule")
+    }
+
+    pub fn find_for_ticket_type(
+        ticket_type_id: Uuid,
+        pending_only: bool,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketPricingRule>, DatabaseError> {
+        let mut query = ticket_pricing_rules::table
+            .filter(ticket_pricing_rules::ticket_type_id.eq(ticket_type_id))
+            .order_by(ticket_pricing_rules::created_at)
+            .then_order_by(ticket_pricing_rules::id)
+            .into_boxed();
+        if pending_only {
+            query = query.filter(ticket_pricing_rules::triggered_at.is_null());
+        }
+        query.load(conn).to_db_error(
+            ErrorCode::QueryError,
+            "Could not load ticket pricing rules for ticket type",
+        )
+    }
+
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Error removing ticket pricing rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketPricingRuleDeleted,
+            format!("Ticket pricing rule '{}' deleted", self.name),
+            Tables::TicketTypes,
+            Some(self.ticket_type_id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    pub fn is_met(&self, sold_quantity: i64, remaining_quantity: i64) -> bool {
+        match self.rule_type {
+            TicketPricingRuleTypes::SoldQuantity => sold_quantity >= self.threshold,
+            TicketPricingRuleTypes::RemainingInventory => remaining_quantity < self.threshold,
+        }
+    }
+
+    /// Triggers every pending rule of the ticket type whose threshold has been reached
+    pub(crate) fn apply_for_ticket_type(ticket_type: &TicketType, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let rules = TicketPricingRule::find_for_ticket_type(ticket_type.id, true, conn)?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let sold_quantity = ticket_type.valid_sold_and_reserved_ticket_count(conn)? as i64;
+        let remaining_quantity = ticket_type.valid_available_ticket_count(conn)? as i64;
+        for rule in rules {
+            if rule.is_met(sold_quantity, remaining_quantity) {
+                rule.trigger(conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Switches the current pricing of the ticket type to the rule's price. Pricing referenced by
+    /// existing order items is replaced rather than changed so carts keep the price they were given.
+    fn trigger(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        // Lock the rule first so concurrent evaluations only switch the pricing once
+        let pending: Option<TicketPricingRule> = ticket_pricing_rules::table
+            .filter(ticket_pricing_rules::id.eq(self.id))
+            .filter(ticket_pricing_rules::triggered_at.is_null())
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock ticket pricing rule")
+            .optional()?;
+        if pending.is_none() {
+            return Ok(());
+        }
+
+        // Without a current price there is nothing to switch, the rule stays pending
+        let ticket_pricing =
+            match TicketPricing::get_current_ticket_pricing(self.ticket_type_id, false, false, conn).optional()? {
+                Some(ticket_pricing) => ticket_pricing,
+                None => return Ok(()),
+            };
+        let price_in_cents = match self.price_increase_percent {
+            Some(percent) => (ticket_pricing.price_in_cents as f64 * (100 + percent) as f64 / 100.0).round() as i64,
+            None => self.price_in_cents.unwrap_or(ticket_pricing.price_in_cents),
+        };
+
+        let new_ticket_pricing = if ticket_pricing.affected_order_count(conn)? == 0 {
+            diesel::update(&ticket_pricing)
+                .set((
+                    ticket_pricing::name.eq(&self.name),
+                    ticket_pricing::price_in_cents.eq(price_in_cents),
+                    ticket_pricing::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update ticket_pricing")?
+        } else {
+            let new_ticket_pricing = TicketPricing::create(
+                self.ticket_type_id,
+                self.name.clone(),
+                ticket_pricing.start_date,
+                ticket_pricing.end_date,
+                price_in_cents,
+                ticket_pricing.is_box_office_only,
+                Some(ticket_pricing.status),
+                Some(ticket_pricing.id),
+            );
+            ticket_pricing.destroy(None, conn)?;
+            new_ticket_pricing.commit(None, conn)?
+        };
+
+        // Only marked as triggered once the pricing has been switched
+        diesel::update(self)
+            .set((
+                ticket_pricing_rules::ticket_pricing_id.eq(new_ticket_pricing.id),
+                ticket_pricing_rules::triggered_at.eq(dsl::now.nullable()),
+                ticket_pricing_rules::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket pricing rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketPricingUpdated,
+            format!("Ticket pricing rule '{}' triggered", self.name),
+            Tables::TicketPricing,
+            Some(new_ticket_pricing.id),
+            None,
+            Some(json!({
+                "ticket_pricing_rule_id": self.id,
+                "previous_ticket_pricing_id": ticket_pricing.id,
+                "old_price_in_cents": ticket_pricing.price_in_cents,
+                "new_price_in_cents": price_in_cents
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+}
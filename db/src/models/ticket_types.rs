@@ -598,6 +598,25 @@ impl TicketType {
         Ok(valid_available_ticket_count as u32)
     }
 
+    /// Binds seats from the event venue's seat map to available, unseated tickets of this ticket
+    /// type. Each seat can only be bound to one ticket per event.
+    pub fn assign_seats(
+        &self,
+        seat_ids: &[Uuid],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let event = self.event(conn)?;
+        Seat::validate_for_venue(seat_ids, event.venue_id, conn)?;
+        Seat::lock(seat_ids, conn)?;
+
+        if !TicketInstance::find_assigned_seat_ids_for_event(event.id, seat_ids, conn)?.is_empty() {
+            return DatabaseError::validation_error("seat_ids", "Seats are already assigned for this event");
+        }
+
+        TicketInstance::assign_seats(current_user_id, self.id, seat_ids, conn)
+    }
+
     pub fn current_ticket_pricing(
         &self,
         box_office_pricing: bool,
@@ -606,6 +625,10 @@ impl TicketType {
         TicketPricing::get_current_ticket_pricing(self.id, box_office_pricing, false, conn)
     }
 
+    pub fn pricing_rules(&self, conn: &PgConnection) -> Result<Vec<TicketPricingRule>, DatabaseError> {
+        TicketPricingRule::find_for_ticket_type(self.id, false, conn)
+    }
+
     pub fn ticket_pricing(
         &self,
         include_default: bool,
@@ -0,0 +1,137 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::two_factor_challenges;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+const TOKEN_LENGTH: usize = 40;
+const CHALLENGE_EXPIRY_MINUTES: i64 = 5;
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+const MAX_FAILED_ATTEMPTS_PER_USER: i32 = 10;
+const LOCKOUT_MINUTES: i64 = 15;
+
+/// Short lived second login step issued once the user's password has been verified. The challenge is completed
+/// with a code from the user's authenticator app or a recovery code and stops accepting attempts after too many
+/// failures. Failures are also counted across the user's recent challenges so signing in again does not allow
+/// more guesses, see `TwoFactorChallenge::is_locked_out`.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "two_factor_challenges"]
+pub struct TwoFactorChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub failed_attempts: i32,
+    pub expires_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "two_factor_challenges"]
+pub struct NewTwoFactorChallenge {
+    pub user_id: Uuid,
+    pub expires_at: NaiveDateTime,
+}
+
+impl NewTwoFactorChallenge {
+    /// Creates the challenge, returning it alongside the plain text token handed to the client
+    pub fn commit(self, conn: &PgConnection) -> Result<(TwoFactorChallenge, String), DatabaseError> {
+        let token = random_alpha_string(TOKEN_LENGTH);
+        let result: TwoFactorChallenge = diesel::insert_into(two_factor_challenges::table)
+            .values((&self, two_factor_challenges::token_hash.eq(sha256::digest(&token))))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create two-factor challenge")?;
+
+        Ok((result, token))
+    }
+}
+
+impl TwoFactorChallenge {
+    pub fn create(user_id: Uuid) -> NewTwoFactorChallenge {
+        NewTwoFactorChallenge {
+            user_id,
+            expires_at: Utc::now().naive_utc() + Duration::minutes(CHALLENGE_EXPIRY_MINUTES),
+        }
+    }
+
+    /// Finds the challenge for the token provided it can still be completed
+    pub fn find_active_by_token(token: &str, conn: &PgConnection) -> Result<TwoFactorChallenge, DatabaseError> {
+        two_factor_challenges::table
+            .filter(two_factor_challenges::token_hash.eq(sha256::digest(token)))
+            .filter(two_factor_challenges::completed_at.is_null())
+            .filter(two_factor_challenges::expires_at.gt(dsl::now))
+            .filter(two_factor_challenges::failed_attempts.lt(MAX_FAILED_ATTEMPTS))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading two-factor challenge")
+    }
+
+    /// Whether the user has failed too many two-factor attempts across the challenges they used in the
+    /// last `LOCKOUT_MINUTES`
+    pub fn is_locked_out(user_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let failed_attempts: Vec<i32> = two_factor_challenges::table
+            .filter(two_factor_challenges::user_id.eq(user_id))
+            .filter(two_factor_challenges::failed_attempts.gt(0))
+            .filter(two_factor_challenges::updated_at.gt(Utc::now().naive_utc() - Duration::minutes(LOCKOUT_MINUTES)))
+            .select(two_factor_challenges::failed_attempts)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load failed two-factor attempts")?;
+
+        Ok(failed_attempts.iter().sum::<i32>() >= MAX_FAILED_ATTEMPTS_PER_USER)
+    }
+
+    /// Completes the challenge if the code from the user's authenticator app or the recovery code is valid,
+    /// otherwise the failed attempt is recorded. Codes are not checked while the user is locked out.
+    pub fn verify(
+        &self,
+        code: Option<&str>,
+        recovery_code: Option<&str>,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        if TwoFactorChallenge::is_locked_out(self.user_id, conn)? {
+            return Ok(false);
+        }
+
+        let credential = TwoFactorCredential::find_enabled_by_user_id(self.user_id, conn)?;
+        let verified = match (code, recovery_code) {
+            (Some(code), _) => credential.verify_code(code, encryption_key, conn)?,
+            (None, Some(recovery_code)) => TwoFactorRecoveryCode::redeem(self.user_id, recovery_code, conn)?,
+            (None, None) => false,
+        };
+
+        if !verified {
+            diesel::update(self)
+                .set((
+                    two_factor_challenges::failed_attempts.eq(two_factor_challenges::failed_attempts + 1),
+                    two_factor_challenges::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update two-factor challenge")?;
+            return Ok(false);
+        }
+
+        // Conditional update guards against the challenge being completed concurrently
+        let result: Option<TwoFactorChallenge> = diesel::update(
+            two_factor_challenges::table
+                .filter(two_factor_challenges::id.eq(self.id))
+                .filter(two_factor_challenges::completed_at.is_null()),
+        )
+        .set((
+            two_factor_challenges::completed_at.eq(dsl::now.nullable()),
+            two_factor_challenges::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not complete two-factor challenge")?;
+
+        Ok(result.is_some())
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::two_factor_credentials;
+use utils::encryption::{decrypt, encrypt};
+use utils::errors::*;
+use utils::totp;
+use uuid::Uuid;
+
+/// TOTP secret shared with the user's authenticator app. Two-factor authentication is only required once the
+/// user has confirmed enrollment with a valid code.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "two_factor_credentials"]
+pub struct TwoFactorCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub enabled_at: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub last_used_time_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "two_factor_credentials"]
+struct NewTwoFactorCredential {
+    user_id: Uuid,
+    secret: String,
+}
+
+impl TwoFactorCredential {
+    /// Starts enrollment with a newly generated secret, replacing any enrollment which was never confirmed.
+    /// The plain text secret is returned for display to the user.
+    pub fn start_enrollment(
+        user_id: Uuid,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<(TwoFactorCredential, String), DatabaseError> {
+        let existing_credential = TwoFactorCredential::find_by_user_id(user_id, conn).optional()?;
+        if existing_credential.as_ref().map(|c| c.is_enabled()).unwrap_or(false) {
+            return DatabaseError::business_process_error("Two-factor authentication is already enabled");
+        }
+
+        let secret = totp::generate_secret()?;
+        let stored_secret = if encryption_key.len() > 0 {
+            encrypt(&secret, encryption_key)?
+        } else {
+            secret.clone()
+        };
+
+        let result: TwoFactorCredential = match existing_credential {
+            Some(credential) => diesel::update(&credential)
+                .set((
+                    two_factor_credentials::secret.eq(stored_secret),
+                    two_factor_credentials::last_used_time_step.eq(None::<i64>),
+                    two_factor_credentials::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update two-factor credential")?,
+            None => diesel::insert_into(two_factor_credentials::table)
+                .values(NewTwoFactorCredential {
+                    user_id,
+                    secret: stored_secret,
+                })
+                .get_result(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create two-factor credential")?,
+        };
+
+        Ok((result, secret))
+    }
+
+    pub fn find_by_user_id(user_id: Uuid, conn: &PgConnection) -> Result<TwoFactorCredential, DatabaseError> {
+        two_factor_credentials::table
+            .filter(two_factor_credentials::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading two-factor credential")
+    }
+
+    pub fn find_enabled_by_user_id(user_id: Uuid, conn: &PgConnection) -> Result<TwoFactorCredential, DatabaseError> {
+        two_factor_credentials::table
+            .filter(two_factor_credentials::user_id.eq(user_id))
+            .filter(two_factor_credentials::enabled_at.is_not_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading two-factor credential")
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    pub fn secret(&self, encryption_key: &str) -> Result<String, DatabaseError> {
+        if encryption_key.len() > 0 {
+            decrypt(&self.secret, encryption_key)
+        } else {
+            Ok(self.secret.clone())
+        }
+    }
+
+    /// Confirms enrollment with a code from the authenticator app, returning the user's new recovery codes
+    pub fn enable(
+        &self,
+        code: &str,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<(TwoFactorCredential, Vec<String>), DatabaseError> {
+        if self.is_enabled() {
+            return DatabaseError::business_process_error("Two-factor authentication is already enabled");
+        }
+        if !self.verify_code(code, encryption_key, conn)? {
+            return DatabaseError::business_process_error("Two-factor code is invalid");
+        }
+
+        let result: TwoFactorCredential = diesel::update(self)
+            .set((
+                two_factor_credentials::enabled_at.eq(dsl::now.nullable()),
+                two_factor_credentials::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not enable two-factor authentication")?;
+        let recovery_codes = TwoFactorRecoveryCode::generate_for_user(self.user_id, Some(self.user_id), conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::TwoFactorEnabled,
+            "Two-factor authentication enabled".to_string(),
+            Tables::Users,
+            Some(self.user_id),
+            Some(self.user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok((result, recovery_codes))
+    }
+
+    /// Verifies a code from the authenticator app. Each code is only accepted once to prevent replay.
+    pub fn verify_code(&self, code: &str, encryption_key: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let time_step = match totp::verify_code(&self.secret(encryption_key)?, code, Utc::now().timestamp())? {
+            Some(time_step) => time_step,
+            None => return Ok(false),
+        };
+
+        let result: Option<TwoFactorCredential> = diesel::update(
+            two_factor_credentials::table
+                .filter(two_factor_credentials::id.eq(self.id))
+                .filter(
+                    two_factor_credentials::last_used_time_step
+                        .is_null()
+                        .or(two_factor_credentials::last_used_time_step.lt(time_step)),
+                ),
+        )
+        .set((
+            two_factor_credentials::last_used_time_step.eq(time_step),
+            two_factor_credentials::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not verify two-factor code")?;
+
+        Ok(result.is_some())
+    }
+
+    /// Removes the credential along with the user's recovery codes
+    pub fn disable(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        TwoFactorRecoveryCode::destroy_for_user(self.user_id, conn)?;
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not disable two-factor authentication")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TwoFactorDisabled,
+            "Two-factor authentication disabled".to_string(),
+            Tables::Users,
+            Some(self.user_id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+}
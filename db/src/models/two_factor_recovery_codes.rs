@@ -0,0 +1,121 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::two_factor_recovery_codes;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// Single use code allowing a user to complete two-factor authentication without their authenticator app
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "two_factor_recovery_codes"]
+pub struct TwoFactorRecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "two_factor_recovery_codes"]
+struct NewTwoFactorRecoveryCode {
+    user_id: Uuid,
+    code_hash: String,
+}
+
+impl TwoFactorRecoveryCode {
+    /// Replaces the user's recovery codes, the plain text codes are returned so they can be shown to the user once
+    pub fn generate_for_user(
+        user_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        TwoFactorRecoveryCode::destroy_for_user(user_id, conn)?;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| random_alpha_string(RECOVERY_CODE_LENGTH).to_lowercase())
+            .collect();
+        let new_codes: Vec<NewTwoFactorRecoveryCode> = codes
+            .iter()
+            .map(|code| NewTwoFactorRecoveryCode {
+                user_id,
+                code_hash: sha256::digest(code),
+            })
+            .collect();
+        diesel::insert_into(two_factor_recovery_codes::table)
+            .values(&new_codes)
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create two-factor recovery codes")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TwoFactorRecoveryCodesGenerated,
+            "Two-factor recovery codes generated".to_string(),
+            Tables::Users,
+            Some(user_id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(codes)
+    }
+
+    /// Marks the code as used, returning false if it does not belong to the user or was already used
+    pub fn redeem(user_id: Uuid, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let code = code.trim().to_lowercase();
+        let result: Option<TwoFactorRecoveryCode> = diesel::update(
+            two_factor_recovery_codes::table
+                .filter(two_factor_recovery_codes::user_id.eq(user_id))
+                .filter(two_factor_recovery_codes::code_hash.eq(sha256::digest(&code)))
+                .filter(two_factor_recovery_codes::used_at.is_null()),
+        )
+        .set((
+            two_factor_recovery_codes::used_at.eq(dsl::now.nullable()),
+            two_factor_recovery_codes::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not redeem two-factor recovery code")?;
+
+        if result.is_none() {
+            return Ok(false);
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::TwoFactorRecoveryCodeUsed,
+            "Two-factor recovery code used".to_string(),
+            Tables::Users,
+            Some(user_id),
+            Some(user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(true)
+    }
+
+    pub fn remaining_for_user(user_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        two_factor_recovery_codes::table
+            .filter(two_factor_recovery_codes::user_id.eq(user_id))
+            .filter(two_factor_recovery_codes::used_at.is_null())
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count two-factor recovery codes")
+    }
+
+    pub fn destroy_for_user(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(two_factor_recovery_codes::table.filter(two_factor_recovery_codes::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove two-factor recovery codes")?;
+        Ok(())
+    }
+}
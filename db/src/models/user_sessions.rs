@@ -0,0 +1,150 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::user_sessions;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Signed in device for a user. Tokens issued at login reference the session so it can be revoked without
+/// requiring the user to change their password.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "user_sessions"]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_used_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "user_sessions"]
+pub struct NewUserSession {
+    pub user_id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl NewUserSession {
+    pub fn commit(self, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        let result: UserSession = diesel::insert_into(user_sessions::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create user session")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserSessionCreated,
+            "User session created".to_string(),
+            Tables::UserSessions,
+            Some(result.id),
+            Some(result.user_id),
+            Some(json!({
+                "user_id": result.user_id,
+                "ip_address": result.ip_address,
+                "user_agent": result.user_agent
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl UserSession {
+    pub fn create(user_id: Uuid, ip_address: Option<String>, user_agent: Option<String>) -> NewUserSession {
+        NewUserSession {
+            user_id,
+            ip_address,
+            user_agent,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        user_sessions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading user session")
+    }
+
+    /// Sessions which have not been revoked, most recently used first
+    pub fn find_active_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<UserSession>, DatabaseError> {
+        user_sessions::table
+            .filter(user_sessions::user_id.eq(user_id))
+            .filter(user_sessions::revoked_at.is_null())
+            .order_by(user_sessions::last_used_at.desc())
+            .then_order_by(user_sessions::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load user sessions")
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    /// Records the session being used to refresh the user's access token
+    pub fn mark_used(&self, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        let result: UserSession = diesel::update(self)
+            .set((
+                user_sessions::last_used_at.eq(dsl::now),
+                user_sessions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update user session")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserSessionRefreshed,
+            "User session refreshed".to_string(),
+            Tables::UserSessions,
+            Some(result.id),
+            Some(result.user_id),
+            Some(json!({ "user_id": result.user_id })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn revoke(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        if self.revoked_at.is_some() {
+            return Ok(self.clone());
+        }
+
+        let result: UserSession = diesel::update(self)
+            .set((
+                user_sessions::revoked_at.eq(dsl::now.nullable()),
+                user_sessions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke user session")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserSessionRevoked,
+            "User session revoked".to_string(),
+            Tables::UserSessions,
+            Some(result.id),
+            current_user_id,
+            Some(json!({ "user_id": result.user_id })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Signs the user out everywhere by revoking each of their active sessions
+    pub fn revoke_all_for_user(
+        user_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<UserSession>, DatabaseError> {
+        let mut revoked_sessions = Vec::new();
+        for session in UserSession::find_active_for_user(user_id, conn)? {
+            revoked_sessions.push(session.revoke(current_user_id, conn)?);
+        }
+        Ok(revoked_sessions)
+    }
+}
@@ -7,7 +7,7 @@ use diesel::expression::sql_literal::sql;
 use diesel::pg::types::sql_types::{Array, Jsonb};
 use diesel::prelude::*;
 use diesel::sql_query;
-use diesel::sql_types::{BigInt, Bool, Nullable, Text, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Integer, Nullable, Text, Timestamp, Uuid as dUuid};
 use models::*;
 use schema::{event_users, events, genres, organization_users, organizations, user_genres, users};
 use serde_json::Value;
@@ -152,8 +152,8 @@ impl User {
             "Unable to load all users",
             users::table
                 .order_by(users::created_at)
-                .paginate(paging.page.unwrap_or(0) as i64)
-                .per_page(paging.limit.unwrap_or(100) as i64)
+                .paginate(paging.page() as i64)
+                .per_page(paging.limit() as i64)
                 .load_and_count_pages(conn),
         )
     }
@@ -509,6 +509,12 @@ impl User {
             total: i64,
             #[sql_type = "Nullable<Text>"]
             facebook_event_id: Option<String>,
+            #[sql_type = "Nullable<dUuid>"]
+            event_series_id: Option<Uuid>,
+            #[sql_type = "Nullable<Integer>"]
+            event_series_index: Option<i32>,
+            #[sql_type = "Text"]
+            currency: String,
         }
 
         let mut query = sql_query(
@@ -598,6 +604,9 @@ impl User {
             settled_at: event.settled_at,
             slug_id: Some(event.slug_id),
             facebook_event_id: event.facebook_event_id,
+            event_series_id: event.event_series_id,
+            event_series_index: event.event_series_index,
+            currency: event.currency,
         });
 
         let mut result: Vec<ActivitySummary> = Vec::new();
@@ -0,0 +1,420 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl::{self, exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::waitlist_entries;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// How long a fan has to purchase the tickets held for them once they reach the front of the queue
+pub const WAITLIST_OFFER_EXPIRY_IN_HOURS: i64 = 24;
+const WAITLIST_REDEMPTION_CODE_LENGTH: usize = 10;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub status: WaitlistEntryStatus,
+    pub hold_id: Option<Uuid>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "waitlist_entries"]
+pub struct NewWaitlistEntry {
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayWaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub status: WaitlistEntryStatus,
+    pub position: Option<i64>,
+    pub redemption_code: Option<String>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl NewWaitlistEntry {
+    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if ticket_type.status == TicketTypeStatus::Cancelled || ticket_type.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Ticket type is no longer available");
+        }
+        if ticket_type.valid_available_ticket_count(conn)? > 0 {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Tickets are still available for this ticket type",
+            );
+        }
+
+        let already_waiting: bool = select(exists(
+            waitlist_entries::table
+                .filter(waitlist_entries::ticket_type_id.eq(self.ticket_type_id))
+                .filter(waitlist_entries::user_id.eq(self.user_id))
+                .filter(
+                    waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]),
+                ),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if user is already on the waitlist",
+        )?;
+        if already_waiting {
+            return DatabaseError::validation_error("user_id", "User is already on the waitlist for this ticket type");
+        }
+
+        let entry: WaitlistEntry = diesel::insert_into(waitlist_entries::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not join waitlist")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCreated,
+            "User joined the waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            current_user_id,
+            Some(json!({"ticket_type_id": entry.ticket_type_id, "user_id": entry.user_id})),
+        )
+        .commit(conn)?;
+
+        WaitlistEntry::schedule_reservation_expiry_offers(entry.ticket_type_id, conn)?;
+
+        Ok(entry)
+    }
+}
+
+impl WaitlistEntry {
+    pub fn create(ticket_type_id: Uuid, user_id: Uuid) -> NewWaitlistEntry {
+        NewWaitlistEntry {
+            ticket_type_id,
+            user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        waitlist_entries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entry")
+    }
+
+    /// Waitlist entries for the user that are still waiting or holding an offer
+    pub fn find_active_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .filter(waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]))
+            .order_by(waitlist_entries::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entries for user")
+    }
+
+    pub fn find_waiting_for_ticket_type(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .order_by(waitlist_entries::created_at)
+            .then_order_by(waitlist_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist for ticket type")
+    }
+
+    /// Position in the queue starting at 1, `None` once the entry is no longer waiting
+    pub fn position(&self, conn: &PgConnection) -> Result<Option<i64>, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting {
+            return Ok(None);
+        }
+
+        let ahead: i64 = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(self.ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .filter(waitlist_entries::created_at.lt(self.created_at))
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not calculate waitlist position")?;
+        Ok(Some(ahead + 1))
+    }
+
+    pub fn hold(&self, conn: &PgConnection) -> Result<Option<Hold>, DatabaseError> {
+        match self.hold_id {
+            Some(hold_id) => Ok(Some(Hold::find(hold_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayWaitlistEntry, DatabaseError> {
+        let redemption_code = if self.status == WaitlistEntryStatus::Offered {
+            self.hold(conn)?.and_then(|h| h.redemption_code)
+        } else {
+            None
+        };
+
+        Ok(DisplayWaitlistEntry {
+            id: self.id,
+            ticket_type_id: self.ticket_type_id,
+            status: self.status,
+            position: self.position(conn)?,
+            redemption_code,
+            offer_expires_at: self.offer_expires_at,
+            created_at: self.created_at,
+        })
+    }
+
+    /// Removes the entry from the queue. An outstanding offer is withdrawn and passed on to the
+    /// next fan in line.
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting && self.status != WaitlistEntryStatus::Offered {
+            return DatabaseError::business_process_error("Waitlist entry is no longer active");
+        }
+
+        if let Some(hold) = self.hold(conn)? {
+            hold.remove_available_quantity(current_user_id, conn)?;
+        }
+        let entry = self.update_status(WaitlistEntryStatus::Cancelled, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCancelled,
+            "User left the waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        WaitlistEntry::offer_available_tickets(self.ticket_type_id, current_user_id, conn)?;
+
+        Ok(entry)
+    }
+
+    /// Offers tickets that have returned to the general pool to the fans at the front of the
+    /// ticket type's waitlist, one ticket each.
+    pub fn offer_available_tickets(
+        ticket_type_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        let waiting = WaitlistEntry::find_waiting_for_ticket_type(ticket_type_id, conn)?;
+        if waiting.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let available = ticket_type.valid_available_ticket_count(conn)? as usize;
+
+        let mut offered = vec![];
+        for entry in waiting.into_iter().take(available) {
+            offered.push(entry.offer(&ticket_type, current_user_id, conn)?);
+        }
+        Ok(offered)
+    }
+
+    /// Cart reservations lapse without being released, so the tickets they return to the pool are
+    /// offered to the waitlist when the earliest reservation for the ticket type expires.
+    pub fn schedule_reservation_expiry_offers(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if DomainAction::has_pending_action(
+            DomainActionTypes::OfferWaitlistTickets,
+            Tables::TicketTypes,
+            ticket_type_id,
+            conn,
+        )? {
+            return Ok(());
+        }
+
+        WaitlistEntry::schedule_next_reservation_expiry_offers(ticket_type_id, conn)
+    }
+
+    /// Offers the tickets of expired cart reservations to the waitlist, checking again when the next
+    /// reservation expires while fans are still waiting.
+    pub fn offer_expired_reservations(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        WaitlistEntry::offer_available_tickets(ticket_type_id, None, conn)?;
+        if WaitlistEntry::find_waiting_for_ticket_type(ticket_type_id, conn)?.is_empty() {
+            return Ok(());
+        }
+
+        WaitlistEntry::schedule_next_reservation_expiry_offers(ticket_type_id, conn)
+    }
+
+    fn schedule_next_reservation_expiry_offers(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if let Some(reserved_until) = TicketInstance::next_reservation_expiry_for_ticket_type(ticket_type_id, conn)? {
+            let mut action = DomainAction::create(
+                None,
+                DomainActionTypes::OfferWaitlistTickets,
+                None,
+                json!({}),
+                Some(Tables::TicketTypes),
+                Some(ticket_type_id),
+            );
+            action.schedule_at(reserved_until);
+            action.commit(conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Closes the offer once the tickets held for the fan have been paid for
+    pub fn mark_purchased_for_hold(
+        hold_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let entry: Option<WaitlistEntry> = waitlist_entries::table
+            .filter(waitlist_entries::hold_id.eq(hold_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Offered))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry for hold")
+            .optional()?;
+        if let Some(entry) = entry {
+            entry.update_status(WaitlistEntryStatus::Purchased, conn)?;
+            DomainEvent::create(
+                DomainEventTypes::WaitlistOfferPurchased,
+                "Waitlist offer purchased".to_string(),
+                Tables::WaitlistEntries,
+                Some(entry.id),
+                current_user_id,
+                Some(json!({ "hold_id": hold_id })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn offer(
+        &self,
+        ticket_type: &TicketType,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<WaitlistEntry, DatabaseError> {
+        let user = User::find(self.user_id, conn)?;
+        let offer_expires_at = Utc::now().naive_utc() + Duration::hours(WAITLIST_OFFER_EXPIRY_IN_HOURS);
+        let redemption_code = random_alpha_string(WAITLIST_REDEMPTION_CODE_LENGTH).to_uppercase();
+
+        let mut new_hold = Hold::create_hold(
+            format!("Waitlist offer for {}", user.full_name()),
+            ticket_type.event_id,
+            Some(redemption_code),
+            Some(0),
+            Some(offer_expires_at),
+            Some(1),
+            HoldTypes::Discount,
+            ticket_type.id,
+        );
+        new_hold.email = user.email.clone();
+        new_hold.phone = user.phone.clone();
+        let hold = new_hold.commit(current_user_id, conn)?;
+        hold.set_quantity(current_user_id, 1, conn)?;
+
+        let entry: WaitlistEntry = diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(WaitlistEntryStatus::Offered),
+                waitlist_entries::hold_id.eq(hold.id),
+                waitlist_entries::offer_expires_at.eq(offer_expires_at),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistOfferCreated,
+            "Tickets offered to waitlisted user".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            current_user_id,
+            Some(json!({"hold_id": hold.id, "offer_expires_at": offer_expires_at})),
+        )
+        .commit(conn)?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendWaitlistOfferCommunication,
+            None,
+            json!({}),
+            Some(Tables::WaitlistEntries),
+            Some(entry.id),
+        )
+        .commit(conn)?;
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ExpireWaitlistOffer,
+            None,
+            json!({}),
+            Some(Tables::WaitlistEntries),
+            Some(entry.id),
+        );
+        action.schedule_at(offer_expires_at);
+        action.commit(conn)?;
+
+        Ok(entry)
+    }
+
+    /// Closes an offer once its window has passed. Unpurchased tickets are returned to the pool
+    /// and offered to the next fan in line. Returns `false` if the entry had no open offer.
+    pub fn expire_offer(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Offered {
+            return Ok(false);
+        }
+        if self
+            .offer_expires_at
+            .map(|e| e > Utc::now().naive_utc())
+            .unwrap_or(false)
+        {
+            return DatabaseError::business_process_error("Waitlist offer has not expired yet");
+        }
+
+        let mut purchased = false;
+        if let Some(hold) = self.hold(conn)? {
+            let (quantity, available) = hold.quantity(conn)?;
+            purchased = quantity > available;
+            hold.remove_available_quantity(None, conn)?;
+        }
+
+        if purchased {
+            self.update_status(WaitlistEntryStatus::Purchased, conn)?;
+        } else {
+            self.update_status(WaitlistEntryStatus::Expired, conn)?;
+            DomainEvent::create(
+                DomainEventTypes::WaitlistOfferExpired,
+                "Waitlist offer expired".to_string(),
+                Tables::WaitlistEntries,
+                Some(self.id),
+                None,
+                None,
+            )
+            .commit(conn)?;
+        }
+
+        WaitlistEntry::offer_available_tickets(self.ticket_type_id, None, conn)?;
+
+        Ok(true)
+    }
+
+    fn update_status(&self, status: WaitlistEntryStatus, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(status),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")
+    }
+}
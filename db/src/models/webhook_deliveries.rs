@@ -0,0 +1,145 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::webhook_deliveries;
+use utils::errors::*;
+use utils::pagination::Paginate;
+use uuid::Uuid;
+
+/// Response bodies are truncated to this many characters before being stored
+const MAX_RESPONSE_BODY_LENGTH: usize = 4096;
+
+/// A single attempt at delivering a webhook to a domain event publisher
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "webhook_deliveries"]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Option<Uuid>,
+    pub domain_action_id: Option<Uuid>,
+    pub webhook_url: String,
+    pub attempt: i64,
+    pub status_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl WebhookDelivery {
+    pub fn create(
+        domain_event_publisher_id: Uuid,
+        domain_event_id: Option<Uuid>,
+        domain_action_id: Option<Uuid>,
+        webhook_url: String,
+        attempt: i64,
+    ) -> NewWebhookDelivery {
+        NewWebhookDelivery {
+            domain_event_publisher_id,
+            domain_event_id,
+            domain_action_id,
+            webhook_url,
+            attempt,
+            status_code: None,
+            response_body: None,
+            error: None,
+            succeeded: false,
+        }
+    }
+
+    pub fn find_by_domain_event_publisher_id(
+        domain_event_publisher_id: Uuid,
+        page: i64,
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Payload<WebhookDelivery>, DatabaseError> {
+        let (deliveries, total) = webhook_deliveries::table
+            .filter(webhook_deliveries::domain_event_publisher_id.eq(domain_event_publisher_id))
+            .order_by(webhook_deliveries::created_at.desc())
+            .then_order_by(webhook_deliveries::attempt.desc())
+            .paginate(page)
+            .per_page(limit)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook deliveries")?;
+
+        let mut payload = Payload::from_data(deliveries, page as u32, limit as u32);
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    pub fn find_by_domain_event_id(
+        domain_event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        webhook_deliveries::table
+            .filter(webhook_deliveries::domain_event_id.eq(domain_event_id))
+            .order_by(webhook_deliveries::created_at.asc())
+            .then_order_by(webhook_deliveries::attempt.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook deliveries")
+    }
+}
+
+/// Delivery as shown to organization users, the response body is left out as it contains whatever
+/// the webhook URL served and is only available to admins
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayWebhookDelivery {
+    pub id: Uuid,
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Option<Uuid>,
+    pub domain_action_id: Option<Uuid>,
+    pub webhook_url: String,
+    pub attempt: i64,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<WebhookDelivery> for DisplayWebhookDelivery {
+    fn from(webhook_delivery: WebhookDelivery) -> Self {
+        DisplayWebhookDelivery {
+            id: webhook_delivery.id,
+            domain_event_publisher_id: webhook_delivery.domain_event_publisher_id,
+            domain_event_id: webhook_delivery.domain_event_id,
+            domain_action_id: webhook_delivery.domain_action_id,
+            webhook_url: webhook_delivery.webhook_url,
+            attempt: webhook_delivery.attempt,
+            status_code: webhook_delivery.status_code,
+            error: webhook_delivery.error,
+            succeeded: webhook_delivery.succeeded,
+            created_at: webhook_delivery.created_at,
+            updated_at: webhook_delivery.updated_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "webhook_deliveries"]
+pub struct NewWebhookDelivery {
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Option<Uuid>,
+    pub domain_action_id: Option<Uuid>,
+    pub webhook_url: String,
+    pub attempt: i64,
+    pub status_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+}
+
+impl NewWebhookDelivery {
+    pub fn commit(mut self, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        self.response_body = self
+            .response_body
+            .map(|body| body.chars().take(MAX_RESPONSE_BODY_LENGTH).collect());
+
+        diesel::insert_into(webhook_deliveries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not insert webhook delivery")
+    }
+}
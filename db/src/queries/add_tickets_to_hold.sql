@@ -5,6 +5,7 @@ WITH r AS (SELECT t.id
              AND a.ticket_type_id = $2
              AND coalesce($4, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11') =
                  coalesce(t.hold_id, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11') -- dummy guid
+             AND ($5::uuid[] IS NULL OR t.seat_id = ANY ($5))
            ORDER BY t.status, t.reserved_until -- Grab available tickets first, then old reserved
            LIMIT $3 FOR UPDATE OF t SKIP LOCKED)
 UPDATE ticket_instances
@@ -13,4 +14,3 @@ SET hold_id    = $1,
 FROM r
 WHERE ticket_instances.id = r.id RETURNING
     ticket_instances.*;
-
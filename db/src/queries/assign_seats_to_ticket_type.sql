@@ -0,0 +1,19 @@
+WITH t AS (SELECT ti.id
+           FROM ticket_instances AS ti
+                    INNER JOIN assets AS a ON ti.asset_id = a.id
+           WHERE a.ticket_type_id = $1
+             AND ti.seat_id IS NULL
+             AND ti.status = 'Available'
+             AND ti.hold_id IS NULL
+           ORDER BY ti.token_id
+           LIMIT cardinality($2::uuid[]) FOR UPDATE OF ti SKIP LOCKED),
+     numbered_tickets AS (SELECT t.id, row_number() OVER (ORDER BY t.id) AS rn
+                          FROM t),
+     numbered_seats AS (SELECT u.seat_id, u.rn
+                        FROM unnest($2::uuid[]) WITH ORDINALITY AS u(seat_id, rn))
+UPDATE ticket_instances
+SET seat_id    = numbered_seats.seat_id,
+    updated_at = now()
+FROM numbered_tickets
+         INNER JOIN numbered_seats ON numbered_tickets.rn = numbered_seats.rn
+WHERE ticket_instances.id = numbered_tickets.id RETURNING ticket_instances.*;
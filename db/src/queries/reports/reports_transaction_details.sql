@@ -25,6 +25,18 @@ SELECT COUNT(*) OVER ()
                (COALESCE(oi_event_fees.quantity, 0) -
                 COALESCE(oi_event_fees.refunded_quantity, 0)) AS BIGINT)                                  AS event_fee_client_in_cents_total,
        oi_fees.fee_schedule_range_id                                                                      AS fee_range_id,
+       oi.item_type                                                                                       AS item_type,
+       -- Resale fees are deducted from the seller's payout rather than charged to the buyer
+       CAST(CASE oi.item_type WHEN 'ResaleTickets' THEN oi.client_fee_in_cents ELSE 0 END AS BIGINT)      AS resale_client_fee_in_cents,
+       oi.currency                                                                                        AS currency,
+       -- Tax on the ticket and its per unit fees, inclusive tax is already part of the prices above
+       CAST(COALESCE(oi_tax.unit_price_in_cents + oi_tax.included_tax_in_cents, 0)
+       + COALESCE(oi_fees_tax.unit_price_in_cents + oi_fees_tax.included_tax_in_cents, 0) AS BIGINT)     AS tax_in_cents,
+       CAST(COALESCE(oi_tax.unit_price_in_cents + oi_tax.included_tax_in_cents, 0) *
+            (COALESCE(oi_tax.quantity, 0) - COALESCE(oi_tax.refunded_quantity, 0))
+       + COALESCE(oi_fees_tax.unit_price_in_cents + oi_fees_tax.included_tax_in_cents, 0) *
+            (COALESCE(oi_fees_tax.quantity, 0) - COALESCE(oi_fees_tax.refunded_quantity, 0)) AS BIGINT) AS tax_in_cents_total,
+       COALESCE(tr.inclusive, false)                                                                      AS tax_inclusive,
        o.paid_at                                                                                          AS transaction_date,
        o.order_type,
        p.payment_method,
@@ -50,12 +62,15 @@ SELECT COUNT(*) OVER ()
        o.platform,
        ti_agg.check_in_source
 FROM orders o
-         LEFT JOIN order_items oi ON (o.id = oi.order_id AND oi.item_type = 'Tickets')
+         LEFT JOIN order_items oi ON (o.id = oi.order_id AND oi.item_type IN ('Tickets', 'ResaleTickets'))
          LEFT JOIN order_items oi_fees ON (oi_fees.item_type = 'PerUnitFees' AND oi.id = oi_fees.parent_id)
           LEFT JOIN order_items oi_event_fees
                    ON (oi_event_fees.item_type = 'EventFees' AND o.id = oi_event_fees.order_id)
          LEFT JOIN order_items oi_promo_code
                    ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
+         LEFT JOIN order_items oi_tax ON (oi_tax.item_type = 'Tax' AND oi.id = oi_tax.parent_id)
+         LEFT JOIN order_items oi_fees_tax ON (oi_fees_tax.item_type = 'Tax' AND oi_fees.id = oi_fees_tax.parent_id)
+         LEFT JOIN tax_rules tr ON tr.id = COALESCE(oi_tax.tax_rule_id, oi_fees_tax.tax_rule_id)
          LEFT JOIN codes c ON oi.code_id = c.id
          LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
          LEFT JOIN (SELECT order_id,
@@ -76,7 +91,7 @@ WHERE o.status = 'Paid'
   AND ($2 IS NULL OR e.organization_id = $2)
   AND ($3 IS NULL OR o.paid_at >= $3)
   AND ($4 IS NULL OR o.paid_at <= $4)
-  AND (oi.item_type = 'Tickets')
+  AND (oi.item_type IN ('Tickets', 'ResaleTickets'))
   AND (
         $5 IS NULL
         OR u.email ILIKE concat('%', $5, '%')
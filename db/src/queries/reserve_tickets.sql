@@ -1,10 +1,15 @@
 WITH r AS (SELECT t.id
            FROM ticket_instances AS t
                     INNER JOIN assets AS a ON t.asset_id = a.id
+                    LEFT JOIN seats AS s ON t.seat_id = s.id
+                    LEFT JOIN stage_sections AS ss ON s.stage_section_id = ss.id
            WHERE ((t.reserved_until < now() AND t.status = 'Reserved') OR t.status = 'Available')
              AND a.ticket_type_id = $3
              AND coalesce($4, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11') =
                  coalesce(t.hold_id, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11') -- dummy guid
+             AND ($6::uuid[] IS NULL OR t.seat_id = ANY ($6))
+           -- Best available seats first, unseated inventory is unaffected
+           ORDER BY ss.rank, ss.name, s.rank
            LIMIT $5 FOR UPDATE OF t SKIP LOCKED)
 
 UPDATE ticket_instances
@@ -15,4 +20,3 @@ SET order_item_id  = $1,
     updated_at     = now()
 FROM r
 WHERE ticket_instances.id = r.id RETURNING ticket_instances.*;
-
@@ -22,6 +22,25 @@ table! {
     }
 }
 
+table! {
+    api_keys (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        name -> Text,
+        key_prefix -> Text,
+        key_hash -> Text,
+        scopes -> Array<Text>,
+        expires_at -> Nullable<Timestamp>,
+        last_used_at -> Nullable<Timestamp>,
+        last_used_ip -> Nullable<Text>,
+        rotated_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     artist_genres (id) {
         id -> Uuid,
@@ -108,6 +127,19 @@ table! {
     }
 }
 
+table! {
+    domain_action_workers (id) {
+        id -> Uuid,
+        name -> Text,
+        worker_type -> Text,
+        is_leader -> Bool,
+        last_heartbeat_at -> Timestamp,
+        stopped_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     domain_actions (id) {
         id -> Uuid,
@@ -127,6 +159,8 @@ table! {
         blocked_until -> Timestamp,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        priority -> Int4,
+        locked_by_worker_id -> Nullable<Uuid>,
     }
 }
 
@@ -152,6 +186,7 @@ table! {
         deleted_at -> Nullable<Timestamp>,
         adapter -> Nullable<Varchar>,
         adapter_config -> Nullable<Jsonb>,
+        signing_secret -> Text,
     }
 }
 
@@ -205,6 +240,18 @@ table! {
     }
 }
 
+table! {
+    event_redemption_keys (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        secret_key -> Text,
+        public_key -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        hash_secret -> Text,
+    }
+}
+
 table! {
     event_report_subscribers (id) {
         id -> Uuid,
@@ -216,6 +263,20 @@ table! {
     }
 }
 
+table! {
+    event_series (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        template_event_id -> Uuid,
+        frequency -> Text,
+        frequency_interval -> Int4,
+        occurrences -> Int4,
+        created_by_user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     events (id) {
         id -> Uuid,
@@ -252,6 +313,9 @@ table! {
         slug_id -> Nullable<Uuid>,
         facebook_event_id -> Nullable<Text>,
         settled_at -> Nullable<Timestamp>,
+        event_series_id -> Nullable<Uuid>,
+        event_series_index -> Nullable<Int4>,
+        currency -> Text,
     }
 }
 
@@ -301,6 +365,7 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         organization_id -> Uuid,
+        currency -> Text,
     }
 }
 
@@ -347,6 +412,49 @@ table! {
     }
 }
 
+table! {
+    oauth_authorization_codes (id) {
+        id -> Uuid,
+        oauth_consent_id -> Uuid,
+        code_hash -> Text,
+        redirect_uri -> Text,
+        scopes -> Array<Text>,
+        code_challenge -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    oauth_clients (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        client_id -> Text,
+        client_secret_hash -> Nullable<Text>,
+        redirect_uris -> Array<Text>,
+        scopes -> Array<Text>,
+        created_by_user_id -> Uuid,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    oauth_consents (id) {
+        id -> Uuid,
+        oauth_client_id -> Uuid,
+        user_id -> Uuid,
+        scopes -> Array<Text>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     order_items (id) {
         id -> Uuid,
@@ -366,6 +474,11 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        season_pass_id -> Nullable<Uuid>,
+        settlement_id -> Nullable<Uuid>,
+        currency -> Text,
+        tax_rule_id -> Nullable<Uuid>,
+        included_tax_in_cents -> Int8,
     }
 }
 
@@ -397,6 +510,8 @@ table! {
         platform -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         referrer -> Nullable<Text>,
+        disputed_at -> Nullable<Timestamp>,
+        currency -> Nullable<Text>,
     }
 }
 
@@ -467,6 +582,11 @@ table! {
         max_additional_fee_in_cents -> Int8,
         settlement_type -> Text,
         slug_id -> Nullable<Uuid>,
+        max_resale_price_percent -> Nullable<Int8>,
+        resale_company_fee_percent -> Float4,
+        resale_client_fee_percent -> Float4,
+        two_factor_required_roles -> Array<Text>,
+        currency -> Text,
     }
 }
 
@@ -509,6 +629,22 @@ table! {
         updated_at -> Timestamp,
         url_nonce -> Nullable<Text>,
         refund_id -> Nullable<Uuid>,
+        currency -> Text,
+    }
+}
+
+table! {
+    pending_settlement_adjustments (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        order_id -> Nullable<Uuid>,
+        amount_in_cents -> Int8,
+        note -> Nullable<Text>,
+        settlement_adjustment_type -> Text,
+        currency -> Text,
+        settlement_adjustment_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -544,6 +680,7 @@ table! {
         amount -> Int8,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        settlement_id -> Nullable<Uuid>,
     }
 }
 
@@ -557,6 +694,7 @@ table! {
         reason -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         manual_override -> Bool,
+        currency -> Text,
     }
 }
 
@@ -569,6 +707,68 @@ table! {
     }
 }
 
+table! {
+    resale_listings (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        seller_user_id -> Uuid,
+        price_in_cents -> Int8,
+        status -> Text,
+        order_item_id -> Nullable<Uuid>,
+        reserved_until -> Nullable<Timestamp>,
+        buyer_user_id -> Nullable<Uuid>,
+        seller_payout_in_cents -> Nullable<Int8>,
+        sold_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    resale_payouts (id) {
+        id -> Uuid,
+        resale_listing_id -> Uuid,
+        seller_user_id -> Uuid,
+        amount_in_cents -> Int8,
+        currency -> Text,
+        status -> Text,
+        paid_at -> Nullable<Timestamp>,
+        paid_by_user_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    saved_report_subscribers (id) {
+        id -> Uuid,
+        saved_report_id -> Uuid,
+        email -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        last_sent_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    saved_reports (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        report_type -> Text,
+        filters -> Jsonb,
+        columns -> Array<Text>,
+        format -> Text,
+        schedule -> Nullable<Text>,
+        next_run_at -> Nullable<Timestamp>,
+        last_run_at -> Nullable<Timestamp>,
+        created_by_user_id -> Uuid,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     settlement_adjustments (id) {
         id -> Uuid,
@@ -578,6 +778,7 @@ table! {
         settlement_adjustment_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        order_id -> Nullable<Uuid>,
     }
 }
 
@@ -595,6 +796,80 @@ table! {
         settlement_entry_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        tax_in_cents -> Int8,
+    }
+}
+
+table! {
+    season_pass_entitlements (id) {
+        id -> Uuid,
+        season_pass_id -> Uuid,
+        order_item_id -> Uuid,
+        user_id -> Uuid,
+        redeem_key -> Text,
+        status -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    season_pass_events (id) {
+        id -> Uuid,
+        season_pass_id -> Uuid,
+        event_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    season_pass_redemptions (id) {
+        id -> Uuid,
+        season_pass_entitlement_id -> Uuid,
+        event_id -> Uuid,
+        redeemed_by_user_id -> Uuid,
+        check_in_source -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    season_pass_settlement_entries (id) {
+        id -> Uuid,
+        settlement_id -> Uuid,
+        season_pass_id -> Uuid,
+        face_value_in_cents -> Int8,
+        revenue_share_value_in_cents -> Int8,
+        online_sold_quantity -> Int8,
+        fee_sold_quantity -> Int8,
+        total_sales_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    season_passes (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        price_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seats (id) {
+        id -> Uuid,
+        stage_section_id -> Uuid,
+        row_name -> Text,
+        seat_number -> Text,
+        rank -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -609,6 +884,7 @@ table! {
         only_finished_events -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        currency -> Text,
     }
 }
 
@@ -626,6 +902,17 @@ table! {
     }
 }
 
+table! {
+    stage_sections (id) {
+        id -> Uuid,
+        stage_id -> Uuid,
+        name -> Text,
+        rank -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     stages (id) {
         id -> Uuid,
@@ -638,6 +925,20 @@ table! {
     }
 }
 
+table! {
+    tax_rules (id) {
+        id -> Uuid,
+        name -> Text,
+        country -> Text,
+        state -> Nullable<Text>,
+        ticket_tax_percent -> Float4,
+        fee_tax_percent -> Float4,
+        inclusive -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     temporary_user_links (temporary_user_id, user_id) {
         temporary_user_id -> Uuid,
@@ -675,6 +976,7 @@ table! {
         first_name_override -> Nullable<Text>,
         last_name_override -> Nullable<Text>,
         check_in_source -> Nullable<Text>,
+        seat_id -> Nullable<Uuid>,
     }
 }
 
@@ -691,6 +993,23 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         previous_ticket_pricing_id -> Nullable<Uuid>,
+        currency -> Text,
+    }
+}
+
+table! {
+    ticket_pricing_rules (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        name -> Text,
+        rule_type -> Text,
+        threshold -> Int8,
+        price_in_cents -> Nullable<Int8>,
+        price_increase_percent -> Nullable<Int4>,
+        ticket_pricing_id -> Nullable<Uuid>,
+        triggered_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -758,6 +1077,42 @@ table! {
     }
 }
 
+table! {
+    two_factor_challenges (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Text,
+        failed_attempts -> Int4,
+        expires_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    two_factor_credentials (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        secret -> Text,
+        enabled_at -> Nullable<Timestamp>,
+        last_used_time_step -> Nullable<Int8>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    two_factor_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     user_genres (id) {
         id -> Uuid,
@@ -768,6 +1123,19 @@ table! {
     }
 }
 
+table! {
+    user_sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        ip_address -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+        last_used_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     users (id) {
         id -> Uuid,
@@ -817,6 +1185,19 @@ table! {
     }
 }
 
+table! {
+    waitlist_entries (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        user_id -> Uuid,
+        status -> Text,
+        hold_id -> Nullable<Uuid>,
+        offer_expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     wallets (id) {
         id -> Uuid,
@@ -831,6 +1212,25 @@ table! {
     }
 }
 
+table! {
+    webhook_deliveries (id) {
+        id -> Uuid,
+        domain_event_publisher_id -> Uuid,
+        domain_event_id -> Nullable<Uuid>,
+        domain_action_id -> Nullable<Uuid>,
+        webhook_url -> Text,
+        attempt -> Int8,
+        status_code -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        error -> Nullable<Text>,
+        succeeded -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+joinable!(api_keys -> organizations (organization_id));
+joinable!(api_keys -> users (user_id));
 joinable!(artist_genres -> artists (artist_id));
 joinable!(artist_genres -> genres (genre_id));
 joinable!(artists -> genres (main_genre_id));
@@ -838,6 +1238,7 @@ joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
 joinable!(broadcasts -> events (event_id));
 joinable!(codes -> events (event_id));
+joinable!(domain_actions -> domain_action_workers (locked_by_worker_id));
 joinable!(domain_actions -> domain_events (domain_event_id));
 joinable!(domain_event_published -> domain_event_publishers (domain_event_publisher_id));
 joinable!(domain_event_published -> domain_events (domain_event_id));
@@ -851,20 +1252,32 @@ joinable!(event_genres -> events (event_id));
 joinable!(event_genres -> genres (genre_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
+joinable!(event_redemption_keys -> events (event_id));
 joinable!(event_report_subscribers -> events (event_id));
+joinable!(event_series -> organizations (organization_id));
+joinable!(event_series -> users (created_by_user_id));
 joinable!(event_users -> events (event_id));
 joinable!(event_users -> users (user_id));
+joinable!(events -> event_series (event_series_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
 joinable!(fee_schedule_ranges -> fee_schedules (fee_schedule_id));
 joinable!(holds -> events (event_id));
 joinable!(holds -> ticket_types (ticket_type_id));
+joinable!(oauth_authorization_codes -> oauth_consents (oauth_consent_id));
+joinable!(oauth_clients -> organizations (organization_id));
+joinable!(oauth_clients -> users (created_by_user_id));
+joinable!(oauth_consents -> oauth_clients (oauth_client_id));
+joinable!(oauth_consents -> users (user_id));
 joinable!(order_items -> codes (code_id));
 joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> season_passes (season_pass_id));
+joinable!(order_items -> settlements (settlement_id));
+joinable!(order_items -> tax_rules (tax_rule_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
 joinable!(order_transfers -> orders (order_id));
@@ -880,45 +1293,84 @@ joinable!(payment_methods -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> refunds (refund_id));
 joinable!(payments -> users (created_by));
+joinable!(pending_settlement_adjustments -> orders (order_id));
+joinable!(pending_settlement_adjustments -> organizations (organization_id));
+joinable!(pending_settlement_adjustments -> settlement_adjustments (settlement_adjustment_id));
 joinable!(push_notification_tokens -> users (user_id));
 joinable!(refund_items -> order_items (order_item_id));
 joinable!(refund_items -> refunds (refund_id));
+joinable!(refund_items -> settlements (settlement_id));
 joinable!(refunded_tickets -> order_items (order_item_id));
 joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
 joinable!(refunds -> orders (order_id));
 joinable!(refunds -> settlements (settlement_id));
 joinable!(refunds -> users (user_id));
+joinable!(resale_listings -> order_items (order_item_id));
+joinable!(resale_listings -> ticket_instances (ticket_instance_id));
+joinable!(resale_payouts -> resale_listings (resale_listing_id));
+joinable!(saved_report_subscribers -> saved_reports (saved_report_id));
+joinable!(saved_reports -> organizations (organization_id));
+joinable!(saved_reports -> users (created_by_user_id));
+joinable!(season_pass_entitlements -> order_items (order_item_id));
+joinable!(season_pass_entitlements -> season_passes (season_pass_id));
+joinable!(season_pass_entitlements -> users (user_id));
+joinable!(season_pass_events -> events (event_id));
+joinable!(season_pass_events -> season_passes (season_pass_id));
+joinable!(season_pass_redemptions -> events (event_id));
+joinable!(season_pass_redemptions -> season_pass_entitlements (season_pass_entitlement_id));
+joinable!(season_pass_redemptions -> users (redeemed_by_user_id));
+joinable!(season_pass_settlement_entries -> season_passes (season_pass_id));
+joinable!(season_pass_settlement_entries -> settlements (settlement_id));
+joinable!(season_passes -> organizations (organization_id));
+joinable!(seats -> stage_sections (stage_section_id));
+joinable!(settlement_adjustments -> orders (order_id));
 joinable!(settlement_adjustments -> settlements (settlement_id));
 joinable!(settlement_entries -> events (event_id));
 joinable!(settlement_entries -> settlements (settlement_id));
 joinable!(settlement_entries -> ticket_types (ticket_type_id));
 joinable!(settlements -> organizations (organization_id));
+joinable!(stage_sections -> stages (stage_id));
 joinable!(temporary_user_links -> temporary_users (temporary_user_id));
 joinable!(temporary_user_links -> users (user_id));
 joinable!(ticket_instances -> assets (asset_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> order_items (order_item_id));
+joinable!(ticket_instances -> seats (seat_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
+joinable!(ticket_pricing_rules -> ticket_pricing (ticket_pricing_id));
+joinable!(ticket_pricing_rules -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
 joinable!(ticket_type_codes -> ticket_types (ticket_type_id));
 joinable!(ticket_types -> events (event_id));
 joinable!(transfer_tickets -> ticket_instances (ticket_instance_id));
 joinable!(transfer_tickets -> transfers (transfer_id));
+joinable!(two_factor_challenges -> users (user_id));
+joinable!(two_factor_credentials -> users (user_id));
+joinable!(two_factor_recovery_codes -> users (user_id));
 joinable!(user_genres -> genres (genre_id));
 joinable!(user_genres -> users (user_id));
+joinable!(user_sessions -> users (user_id));
 joinable!(venues -> organizations (organization_id));
 joinable!(venues -> regions (region_id));
+joinable!(waitlist_entries -> holds (hold_id));
+joinable!(waitlist_entries -> ticket_types (ticket_type_id));
+joinable!(waitlist_entries -> users (user_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
+joinable!(webhook_deliveries -> domain_actions (domain_action_id));
+joinable!(webhook_deliveries -> domain_event_publishers (domain_event_publisher_id));
+joinable!(webhook_deliveries -> domain_events (domain_event_id));
 
 allow_tables_to_appear_in_same_query!(
     analytics_page_views,
+    api_keys,
     artist_genres,
     artists,
     assets,
     broadcasts,
     codes,
+    domain_action_workers,
     domain_actions,
     domain_event_published,
     domain_event_publishers,
@@ -926,7 +1378,9 @@ allow_tables_to_appear_in_same_query!(
     event_artists,
     event_genres,
     event_interest,
+    event_redemption_keys,
     event_report_subscribers,
+    event_series,
     events,
     event_users,
     external_logins,
@@ -935,6 +1389,9 @@ allow_tables_to_appear_in_same_query!(
     genres,
     holds,
     notes,
+    oauth_authorization_codes,
+    oauth_clients,
+    oauth_consents,
     order_items,
     orders,
     order_transfers,
@@ -944,26 +1401,46 @@ allow_tables_to_appear_in_same_query!(
     organization_users,
     payment_methods,
     payments,
+    pending_settlement_adjustments,
     push_notification_tokens,
     refunded_tickets,
     refund_items,
     refunds,
     regions,
+    resale_listings,
+    resale_payouts,
+    saved_report_subscribers,
+    saved_reports,
+    season_pass_entitlements,
+    season_pass_events,
+    season_pass_redemptions,
+    season_pass_settlement_entries,
+    season_passes,
+    seats,
     settlement_adjustments,
     settlement_entries,
     settlements,
     slugs,
+    stage_sections,
     stages,
+    tax_rules,
     temporary_user_links,
     temporary_users,
     ticket_instances,
     ticket_pricing,
+    ticket_pricing_rules,
     ticket_type_codes,
     ticket_types,
     transfers,
     transfer_tickets,
+    two_factor_challenges,
+    two_factor_credentials,
+    two_factor_recovery_codes,
     user_genres,
+    user_sessions,
     users,
     venues,
+    waitlist_entries,
     wallets,
+    webhook_deliveries,
 );
@@ -19,11 +19,13 @@ pub use self::payment_builder::*;
 pub use self::payment_method_builder::*;
 pub use self::refund_builder::*;
 pub use self::region_builder::*;
+pub use self::saved_report_builder::*;
 pub use self::settlement_adjustment_builder::*;
 pub use self::settlement_builder::*;
 pub use self::settlement_entry_builder::*;
 pub use self::slug_builder::*;
 pub use self::stage_builder::*;
+pub use self::tax_rule_builder::*;
 pub use self::ticket_type_builder::*;
 pub use self::user_builder::*;
 pub use self::venue_builder::*;
@@ -49,11 +51,13 @@ mod payment_builder;
 mod payment_method_builder;
 mod refund_builder;
 mod region_builder;
+mod saved_report_builder;
 mod settlement_adjustment_builder;
 mod settlement_builder;
 mod settlement_entry_builder;
 mod slug_builder;
 mod stage_builder;
+mod tax_rule_builder;
 mod ticket_type_builder;
 mod user_builder;
 mod venue_builder;
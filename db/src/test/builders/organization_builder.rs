@@ -18,6 +18,7 @@ pub struct OrganizationBuilder<'a> {
     additional_fee: i64,
     timezone: Option<String>,
     settlement_type: Option<SettlementTypes>,
+    currency: Option<String>,
 }
 
 impl<'a> OrganizationBuilder<'a> {
@@ -36,6 +37,7 @@ impl<'a> OrganizationBuilder<'a> {
             additional_fee: 0,
             timezone: None,
             settlement_type: None,
+            currency: None,
         }
     }
 
@@ -59,6 +61,11 @@ impl<'a> OrganizationBuilder<'a> {
         self
     }
 
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
     pub fn with_name(mut self, name: String) -> Self {
         self.name = name;
         self
@@ -107,6 +114,7 @@ impl<'a> OrganizationBuilder<'a> {
 
         let mut organization = Organization::create(&self.name, self.fee_schedule.unwrap().id);
         organization.settlement_type = self.settlement_type;
+        organization.currency = self.currency;
         let mut organization = organization
             .commit(None, "encryption_key", None, self.connection)
             .unwrap();
@@ -0,0 +1,91 @@
+use diesel::prelude::*;
+use models::*;
+use rand::prelude::*;
+use test::builders::*;
+use uuid::Uuid;
+
+pub struct SavedReportBuilder<'a> {
+    organization_id: Option<Uuid>,
+    created_by_user_id: Option<Uuid>,
+    name: String,
+    report_type: SavedReportTypes,
+    filters: SavedReportFilters,
+    columns: Vec<String>,
+    format: ReportFormats,
+    schedule: Option<String>,
+    connection: &'a PgConnection,
+}
+
+impl<'a> SavedReportBuilder<'a> {
+    pub fn new(connection: &'a PgConnection) -> SavedReportBuilder<'a> {
+        let x: u32 = random();
+        SavedReportBuilder {
+            organization_id: None,
+            created_by_user_id: None,
+            name: format!("Saved report {}", x),
+            report_type: SavedReportTypes::TransactionDetails,
+            filters: SavedReportFilters::default(),
+            columns: Vec::new(),
+            format: ReportFormats::Csv,
+            schedule: None,
+            connection,
+        }
+    }
+
+    pub fn with_organization(mut self, organization: &Organization) -> Self {
+        self.organization_id = Some(organization.id);
+        self
+    }
+
+    pub fn with_created_by(mut self, user: &User) -> Self {
+        self.created_by_user_id = Some(user.id);
+        self
+    }
+
+    pub fn with_report_type(mut self, report_type: SavedReportTypes) -> Self {
+        self.report_type = report_type;
+        self
+    }
+
+    pub fn with_filters(mut self, filters: SavedReportFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: Vec<&str>) -> Self {
+        self.columns = columns.into_iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn with_format(mut self, format: ReportFormats) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: &str) -> Self {
+        self.schedule = Some(schedule.to_string());
+        self
+    }
+
+    pub fn finish(self) -> SavedReport {
+        let organization_id = self
+            .organization_id
+            .unwrap_or_else(|| OrganizationBuilder::new(self.connection).finish().id);
+        let created_by_user_id = self
+            .created_by_user_id
+            .unwrap_or_else(|| UserBuilder::new(self.connection).finish().id);
+
+        SavedReport::create(
+            organization_id,
+            self.name,
+            self.report_type,
+            self.filters,
+            self.columns,
+            self.format,
+            self.schedule,
+            created_by_user_id,
+        )
+        .commit(self.connection)
+        .unwrap()
+    }
+}
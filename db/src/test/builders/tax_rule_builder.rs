@@ -0,0 +1,70 @@
+use diesel::prelude::*;
+use models::*;
+use rand::prelude::*;
+
+pub struct TaxRuleBuilder<'a> {
+    name: String,
+    country: String,
+    state: Option<String>,
+    ticket_tax_percent: f32,
+    fee_tax_percent: f32,
+    inclusive: bool,
+    connection: &'a PgConnection,
+}
+
+impl<'a> TaxRuleBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> TaxRuleBuilder {
+        let x: u32 = random();
+        TaxRuleBuilder {
+            connection,
+            name: format!("Tax Rule {}", x).into(),
+            country: "US".into(),
+            state: None,
+            ticket_tax_percent: 10f32,
+            fee_tax_percent: 0f32,
+            inclusive: false,
+        }
+    }
+
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_country(mut self, country: String) -> Self {
+        self.country = country;
+        self
+    }
+
+    pub fn with_state(mut self, state: String) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn with_ticket_tax_percent(mut self, ticket_tax_percent: f32) -> Self {
+        self.ticket_tax_percent = ticket_tax_percent;
+        self
+    }
+
+    pub fn with_fee_tax_percent(mut self, fee_tax_percent: f32) -> Self {
+        self.fee_tax_percent = fee_tax_percent;
+        self
+    }
+
+    pub fn inclusive(mut self) -> Self {
+        self.inclusive = true;
+        self
+    }
+
+    pub fn finish(self) -> TaxRule {
+        let mut tax_rule = TaxRule::create(
+            &self.name,
+            &self.country,
+            self.state,
+            self.ticket_tax_percent,
+            self.fee_tax_percent,
+        );
+        tax_rule.inclusive = self.inclusive;
+        tax_rule.commit(self.connection).unwrap()
+    }
+}
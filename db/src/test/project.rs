@@ -123,10 +123,18 @@ impl TestProject {
         RegionBuilder::new(&self.connection)
     }
 
+    pub fn create_saved_report(&self) -> SavedReportBuilder {
+        SavedReportBuilder::new(&self.connection)
+    }
+
     pub fn create_slug(&self) -> SlugBuilder {
         SlugBuilder::new(&self.connection)
     }
 
+    pub fn create_tax_rule(&self) -> TaxRuleBuilder {
+        TaxRuleBuilder::new(&self.connection)
+    }
+
     pub fn create_user(&self) -> UserBuilder {
         UserBuilder::new(&self.connection)
     }
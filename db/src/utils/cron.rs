@@ -0,0 +1,214 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use std::str::FromStr;
+
+/// Number of days searched for the next occurrence, long enough to cover schedules like `0 0 29 2 *`
+const MAX_SEARCH_DAYS: i64 = 366 * 8;
+
+/// Five field cron expression (minute, hour, day of month, month and day of week) supporting `*`,
+/// single values, ranges, lists and steps, e.g. `0 6 * * 1-5` or `*/15 8-18 1,15 * *`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    days_of_week: Vec<u32>,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Cron expression must have 5 fields, found {}", fields.len()));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if days_of_week.contains(&7) {
+            days_of_week.retain(|d| *d != 7);
+            if !days_of_week.contains(&0) {
+                days_of_week.insert(0, 0);
+            }
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            days_of_month_restricted: !fields[2].starts_with('*'),
+            days_of_week_restricted: !fields[4].starts_with('*'),
+        })
+    }
+}
+
+impl CronSchedule {
+    /// Returns the first time strictly after `after` (UTC) matching the schedule when evaluated in
+    /// `timezone`. Local times skipped by daylight saving transitions are not run.
+    pub fn next_after<T: TimeZone>(&self, after: NaiveDateTime, timezone: &T) -> Option<NaiveDateTime> {
+        let local_after = timezone.from_utc_datetime(&after).naive_local();
+        let start = local_after.date().and_hms(local_after.hour(), local_after.minute(), 0) + Duration::minutes(1);
+
+        let mut date = start.date();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                for hour in &self.hours {
+                    for minute in &self.minutes {
+                        let local = date.and_hms(*hour, *minute, 0);
+                        if local < start {
+                            continue;
+                        }
+                        if let Some(time) = timezone.from_local_datetime(&local).earliest() {
+                            let time = time.naive_utc();
+                            if time > after {
+                                return Some(time);
+                            }
+                        }
+                    }
+                }
+            }
+            date = date.succ();
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let day_of_month = self.days_of_month.contains(&date.day());
+        let day_of_week = self.days_of_week.contains(&date.weekday().num_days_from_sunday());
+        // As with cron, when both day fields are restricted either may match
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>, String> {
+    let mut values = vec![];
+    for part in field.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(index) => (&part[..index], Some(parse_value(&part[index + 1..])?)),
+            None => (part, None),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some(index) = range.find('-') {
+            (parse_value(&range[..index])?, parse_value(&range[index + 1..])?)
+        } else {
+            let value = parse_value(range)?;
+            // `5/10` runs from 5 to the maximum
+            (value, if step.is_some() { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("Cron value '{}' must be between {} and {}", part, min, max));
+        }
+        let step = step.unwrap_or(1);
+        if step == 0 {
+            return Err(format!("Cron step in '{}' must be greater than 0", part));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort();
+    values.dedup();
+    Ok(values)
+}
+
+fn parse_value(value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid cron value '{}'", value))
+}
+
+#[test]
+fn parse() {
+    let schedule: CronSchedule = "*/15 8-10 1,15 * 1-5".parse().unwrap();
+    assert_eq!(schedule.minutes, vec![0, 15, 30, 45]);
+    assert_eq!(schedule.hours, vec![8, 9, 10]);
+    assert_eq!(schedule.days_of_month, vec![1, 15]);
+    assert_eq!(schedule.months.len(), 12);
+    assert_eq!(schedule.days_of_week, vec![1, 2, 3, 4, 5]);
+
+    let schedule: CronSchedule = "5/20 0 * * 7".parse().unwrap();
+    assert_eq!(schedule.minutes, vec![5, 25, 45]);
+    assert_eq!(schedule.days_of_week, vec![0]);
+}
+
+#[test]
+fn parse_invalid() {
+    assert!("* * * *".parse::<CronSchedule>().is_err());
+    assert!("* * * * * *".parse::<CronSchedule>().is_err());
+    assert!("60 * * * *".parse::<CronSchedule>().is_err());
+    assert!("* * 0 * *".parse::<CronSchedule>().is_err());
+    assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+    assert!("10-5 * * * *".parse::<CronSchedule>().is_err());
+    assert!("a * * * *".parse::<CronSchedule>().is_err());
+}
+
+#[test]
+fn next_after() {
+    let schedule: CronSchedule = "30 6 * * *".parse().unwrap();
+    let after = NaiveDate::from_ymd(2020, 1, 10).and_hms(6, 30, 0);
+    assert_eq!(
+        schedule.next_after(after, &Utc),
+        Some(NaiveDate::from_ymd(2020, 1, 11).and_hms(6, 30, 0))
+    );
+    let after = NaiveDate::from_ymd(2020, 1, 10).and_hms(6, 29, 59);
+    assert_eq!(
+        schedule.next_after(after, &Utc),
+        Some(NaiveDate::from_ymd(2020, 1, 10).and_hms(6, 30, 0))
+    );
+
+    // Weekdays only, 2020-01-10 is a Friday
+    let schedule: CronSchedule = "0 9 * * 1-5".parse().unwrap();
+    assert_eq!(
+        schedule.next_after(NaiveDate::from_ymd(2020, 1, 10).and_hms(10, 0, 0), &Utc),
+        Some(NaiveDate::from_ymd(2020, 1, 13).and_hms(9, 0, 0))
+    );
+
+    // Either day field matches when both are restricted
+    let schedule: CronSchedule = "0 0 15 * 6".parse().unwrap();
+    assert_eq!(
+        schedule.next_after(after, &Utc),
+        Some(NaiveDate::from_ymd(2020, 1, 11).and_hms(0, 0, 0))
+    );
+
+    let schedule: CronSchedule = "0 0 29 2 *".parse().unwrap();
+    assert_eq!(
+        schedule.next_after(NaiveDate::from_ymd(2020, 3, 1).and_hms(0, 0, 0), &Utc),
+        Some(NaiveDate::from_ymd(2024, 2, 29).and_hms(0, 0, 0))
+    );
+    let schedule: CronSchedule = "0 0 31 2 *".parse().unwrap();
+    assert_eq!(schedule.next_after(after, &Utc), None);
+}
+
+#[test]
+fn next_after_in_timezone() {
+    use chrono_tz::America::Los_Angeles;
+
+    let schedule: CronSchedule = "0 4 * * *".parse().unwrap();
+    let after = NaiveDate::from_ymd(2020, 1, 10).and_hms(0, 0, 0);
+    // 4AM PST is 12PM UTC
+    assert_eq!(
+        schedule.next_after(after, &Los_Angeles),
+        Some(NaiveDate::from_ymd(2020, 1, 10).and_hms(12, 0, 0))
+    );
+
+    // 2:30AM does not exist on the day daylight saving starts
+    let schedule: CronSchedule = "30 2 * * *".parse().unwrap();
+    let after = NaiveDate::from_ymd(2020, 3, 8).and_hms(0, 0, 0);
+    assert_eq!(
+        schedule.next_after(after, &Los_Angeles),
+        Some(NaiveDate::from_ymd(2020, 3, 9).and_hms(9, 30, 0))
+    );
+}
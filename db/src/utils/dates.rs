@@ -1,5 +1,8 @@
 use chrono::prelude::*;
 use chrono::Duration;
+use chrono::LocalResult;
+use chrono_tz::Tz;
+use std::cmp;
 
 pub struct DateBuilder {
     date: NaiveDateTime,
@@ -50,3 +53,99 @@ impl IntoDateBuilder for NaiveDateTime {
         DateBuilder { date: self }
     }
 }
+
+/// Moves UTC dates by whole months and a duration measured in local time, so a show starting at 9PM keeps
+/// starting at 9PM in its timezone when the shift crosses a daylight saving change
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DateShift {
+    months: i32,
+    duration: Duration,
+    timezone: Tz,
+}
+
+impl DateShift {
+    pub fn new(months: i32, duration: Duration, timezone: Tz) -> DateShift {
+        DateShift {
+            months,
+            duration,
+            timezone,
+        }
+    }
+
+    /// Shift that moves `from` to `to`
+    pub fn between(from: NaiveDateTime, to: NaiveDateTime, timezone: Tz) -> DateShift {
+        let local_from = timezone.from_utc_datetime(&from).naive_local();
+        let local_to = timezone.from_utc_datetime(&to).naive_local();
+        DateShift::new(0, local_to - local_from, timezone)
+    }
+
+    pub fn apply(&self, date: NaiveDateTime) -> NaiveDateTime {
+        let local = add_months(self.timezone.from_utc_datetime(&date).naive_local(), self.months) + self.duration;
+        match self.timezone.from_local_datetime(&local) {
+            LocalResult::Single(shifted) => shifted.naive_utc(),
+            LocalResult::Ambiguous(earliest, _) => earliest.naive_utc(),
+            // Local times skipped when clocks go forward are moved past the gap
+            LocalResult::None => self
+                .timezone
+                .from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+                .map(|shifted| shifted.naive_utc())
+                .unwrap_or(date + self.duration),
+        }
+    }
+}
+
+/// Adds calendar months, using the last day of the month when the day does not exist, e.g. 31 January plus
+/// one month is 29 February in a leap year
+fn add_months(date: NaiveDateTime, months: i32) -> NaiveDateTime {
+    if months == 0 {
+        return date;
+    }
+    let total_months = date.year() * 12 + date.month0() as i32 + months;
+    let year = total_months / 12;
+    let month = (total_months % 12) as u32 + 1;
+    let day = (1..=cmp::min(date.day(), 31))
+        .rev()
+        .find(|day| NaiveDate::from_ymd_opt(year, month, *day).is_some())
+        .unwrap_or(1);
+    NaiveDate::from_ymd(year, month, day).and_time(date.time())
+}
+
+#[test]
+fn date_shift_keeps_local_time() {
+    let timezone: Tz = "America/New_York".parse().unwrap();
+    // 9PM local on 2020-03-06 (EST) moved a week later lands after clocks go forward on 2020-03-08
+    let start = NaiveDate::from_ymd(2020, 3, 7).and_hms(2, 0, 0);
+    let shift = DateShift::new(0, Duration::weeks(1), timezone);
+    assert_eq!(shift.apply(start), NaiveDate::from_ymd(2020, 3, 14).and_hms(1, 0, 0));
+
+    let shift = DateShift::between(start, NaiveDate::from_ymd(2020, 3, 14).and_hms(1, 0, 0), timezone);
+    assert_eq!(shift, DateShift::new(0, Duration::weeks(1), timezone));
+    // Dates before the event keep their local time too
+    assert_eq!(
+        shift.apply(NaiveDate::from_ymd(2020, 3, 1).and_hms(17, 0, 0)),
+        NaiveDate::from_ymd(2020, 3, 8).and_hms(16, 0, 0)
+    );
+
+    // 2:30AM local does not exist on 2020-03-08
+    let shift = DateShift::new(0, Duration::days(7), timezone);
+    assert_eq!(
+        shift.apply(NaiveDate::from_ymd(2020, 3, 1).and_hms(7, 30, 0)),
+        NaiveDate::from_ymd(2020, 3, 8).and_hms(7, 30, 0)
+    );
+}
+
+#[test]
+fn date_shift_months() {
+    let timezone: Tz = "UTC".parse().unwrap();
+    let shift = DateShift::new(1, Duration::zero(), timezone);
+    assert_eq!(
+        shift.apply(NaiveDate::from_ymd(2020, 1, 31).and_hms(20, 0, 0)),
+        NaiveDate::from_ymd(2020, 2, 29).and_hms(20, 0, 0)
+    );
+    let shift = DateShift::new(13, Duration::zero(), timezone);
+    assert_eq!(
+        shift.apply(NaiveDate::from_ymd(2020, 12, 15).and_hms(20, 0, 0)),
+        NaiveDate::from_ymd(2022, 1, 15).and_hms(20, 0, 0)
+    );
+}
@@ -16,3 +16,46 @@ pub mod sha1 {
         assert_eq!(sha, "3abef1a14ccecd20d6ce892cbe042ae6d74946c8");
     }
 }
+
+pub mod sha256 {
+    use ring::digest;
+
+    pub fn digest(s: &str) -> String {
+        let sha = digest::digest(&digest::SHA256, s.as_bytes());
+        sha.as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>()
+            .join("")
+    }
+
+    #[test]
+    fn sha256_digest() {
+        let sha = digest("testme");
+        assert_eq!(sha, "3bcc367a3488e113dca68b67e5fa262fe4fd2df48b1b72fd3292b30358911aab");
+    }
+}
+
+pub mod hmac_sha256 {
+    use ring::{digest, hmac};
+
+    pub fn sign(key: &str, s: &str) -> String {
+        let key = hmac::SigningKey::new(&digest::SHA256, key.as_bytes());
+        let signature = hmac::sign(&key, s.as_bytes());
+        signature
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>()
+            .join("")
+    }
+
+    #[test]
+    fn hmac_sha256_sign() {
+        let signature = sign("key", "The quick brown fox jumps over the lazy dog");
+        assert_eq!(
+            signature,
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
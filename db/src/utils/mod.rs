@@ -1,3 +1,4 @@
+pub mod cron;
 pub mod dates;
 pub mod encryption;
 pub mod errors;
@@ -10,4 +11,5 @@ pub mod passwords;
 pub mod rand;
 pub mod regexes;
 pub mod text;
+pub mod totp;
 pub use self::math::*;
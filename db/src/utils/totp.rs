@@ -0,0 +1,100 @@
+use base32::{self, Alphabet};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{digest, hmac};
+use url::form_urlencoded;
+use utils::errors::*;
+
+const SECRET_LENGTH: usize = 20;
+const TIME_STEP_SECONDS: i64 = 30;
+const CODE_DIGITS: u32 = 6;
+/// Number of time steps either side of the current step accepted to allow for clock drift
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+const SECRET_ALPHABET: Alphabet = Alphabet::RFC4648 { padding: false };
+
+/// Generates a random base32 encoded secret suitable for authenticator apps
+pub fn generate_secret() -> Result<String, DatabaseError> {
+    let mut secret = vec![0; SECRET_LENGTH];
+    SystemRandom::new().fill(&mut secret)?;
+    Ok(base32::encode(SECRET_ALPHABET, &secret))
+}
+
+/// URI encoded into the QR code scanned by authenticator apps during enrollment
+pub fn provisioning_uri(secret: &str, account_name: &str, issuer: &str) -> String {
+    let label: String = form_urlencoded::byte_serialize(format!("{}:{}", issuer, account_name).as_bytes()).collect();
+    let query = form_urlencoded::Serializer::new(String::new())
+        .append_pair("secret", secret)
+        .append_pair("issuer", issuer)
+        .append_pair("digits", &CODE_DIGITS.to_string())
+        .append_pair("period", &TIME_STEP_SECONDS.to_string())
+        .finish();
+    format!("otpauth://totp/{}?{}", label, query)
+}
+
+pub fn time_step(unix_timestamp: i64) -> i64 {
+    unix_timestamp / TIME_STEP_SECONDS
+}
+
+/// Generates the code for the given time step as per RFC 6238 using HMAC-SHA1
+pub fn generate_code(secret: &str, time_step: i64) -> Result<String, DatabaseError> {
+    let secret = match base32::decode(SECRET_ALPHABET, secret) {
+        Some(secret) => secret,
+        None => {
+            return Err(DatabaseError::new(
+                ErrorCode::InternalError,
+                Some("Two-factor secret is invalid".to_string()),
+            ))
+        }
+    };
+    let key = hmac::SigningKey::new(&digest::SHA1, &secret);
+    let signature = hmac::sign(&key, &(time_step as u64).to_be_bytes());
+    let hash = signature.as_ref();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = ((hash[offset] as u32 & 0x7f) << 24)
+        | ((hash[offset + 1] as u32) << 16)
+        | ((hash[offset + 2] as u32) << 8)
+        | (hash[offset + 3] as u32);
+    Ok(format!(
+        "{:0width$}",
+        binary % 10u32.pow(CODE_DIGITS),
+        width = CODE_DIGITS as usize
+    ))
+}
+
+/// Returns the time step the code is valid for if it matches the current step or an adjacent one
+pub fn verify_code(secret: &str, code: &str, unix_timestamp: i64) -> Result<Option<i64>, DatabaseError> {
+    let code = code.trim();
+    if code.len() != CODE_DIGITS as usize {
+        return Ok(None);
+    }
+
+    let current_step = time_step(unix_timestamp);
+    for step in (current_step - ALLOWED_DRIFT_STEPS)..=(current_step + ALLOWED_DRIFT_STEPS) {
+        if generate_code(secret, step)? == code {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
+#[test]
+fn generate_code_test_vectors() {
+    // SHA1 test vectors from RFC 6238 Appendix B truncated to six digits
+    let secret = base32::encode(SECRET_ALPHABET, b"12345678901234567890");
+    assert_eq!(generate_code(&secret, time_step(59)).unwrap(), "287082");
+    assert_eq!(generate_code(&secret, time_step(1111111109)).unwrap(), "081804");
+    assert_eq!(generate_code(&secret, time_step(1234567890)).unwrap(), "005924");
+    assert_eq!(generate_code(&secret, time_step(2000000000)).unwrap(), "279037");
+}
+
+#[test]
+fn verify_code_with_drift() {
+    let secret = generate_secret().unwrap();
+    let now = 1_580_000_000;
+    let code = generate_code(&secret, time_step(now)).unwrap();
+    assert_eq!(verify_code(&secret, &code, now).unwrap(), Some(time_step(now)));
+    assert_eq!(verify_code(&secret, &code, now + 30).unwrap(), Some(time_step(now)));
+    assert_eq!(verify_code(&secret, &code, now + 90).unwrap(), None);
+    assert_eq!(verify_code(&secret, "12345", now).unwrap(), None);
+}
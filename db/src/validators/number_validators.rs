@@ -74,6 +74,15 @@ pub fn validate_less_than_or_equal<T: std::cmp::Ord + serde::Serialize>(
     }
 }
 
+pub fn validate_percent(percent: f32) -> Result<(), ValidationError> {
+    if percent < 0f32 || percent > 100f32 {
+        let mut validation_error = create_validation_error("percent", "Percent must be between 0 and 100");
+        validation_error.add_param(Cow::from("percent"), &percent);
+        return Err(validation_error);
+    }
+    Ok(())
+}
+
 #[test]
 fn validate_greater_than_returns_ok() {
     assert_eq!(validate_greater_than(3, 2, "test_example", "test example"), Ok(()),);
@@ -0,0 +1,179 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let (api_key, key) = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office".to_string(),
+        vec![Scopes::EventDataRead, Scopes::OrderRead],
+        None,
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(api_key.organization_id, organization.id);
+    assert_eq!(api_key.user_id, user.id);
+    assert_eq!(
+        api_key.scopes().unwrap(),
+        vec![Scopes::EventDataRead, Scopes::OrderRead]
+    );
+    assert!(key.starts_with(&format!("bn_{}_", api_key.key_prefix)));
+    assert_ne!(api_key.key_hash, key);
+    assert!(api_key.is_active());
+
+    let domain_events = DomainEvent::find(
+        Tables::ApiKeys,
+        Some(api_key.id),
+        Some(DomainEventTypes::ApiKeyCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let result = ApiKey::create(organization.id, user.id, "".to_string(), vec![], None).commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let (api_key, _) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    ApiKey::create(
+        organization2.id,
+        user.id,
+        "CRM".to_string(),
+        vec![Scopes::OrgFans],
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    assert_eq!(
+        ApiKey::find_for_organization(organization.id, connection).unwrap(),
+        vec![api_key]
+    );
+}
+
+#[test]
+fn find_active_by_key() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let (api_key, key) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(ApiKey::find_active_by_key(&key, connection).unwrap(), api_key);
+    assert!(ApiKey::find_active_by_key("bn_invalid_key", connection).is_err());
+
+    // Expired keys are not returned
+    let (_, expired_key) = ApiKey::create(
+        organization.id,
+        user.id,
+        "Expired".to_string(),
+        vec![Scopes::OrgFans],
+        Some(Utc::now().naive_utc() - Duration::days(1)),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert!(ApiKey::find_active_by_key(&expired_key, connection).is_err());
+
+    // Revoked keys are not returned
+    api_key.revoke(None, connection).unwrap();
+    assert!(ApiKey::find_active_by_key(&key, connection).is_err());
+}
+
+#[test]
+fn rotate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let (api_key, key) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    let (rotated_api_key, rotated_key) = api_key.rotate(Some(user.id), connection).unwrap();
+    assert_eq!(rotated_api_key.id, api_key.id);
+    assert_eq!(rotated_api_key.scopes, api_key.scopes);
+    assert_ne!(rotated_key, key);
+    assert!(rotated_api_key.rotated_at.is_some());
+    assert!(ApiKey::find_active_by_key(&key, connection).is_err());
+    assert_eq!(
+        ApiKey::find_active_by_key(&rotated_key, connection).unwrap().id,
+        api_key.id
+    );
+
+    // Revoked keys cannot be rotated
+    let revoked_api_key = rotated_api_key.revoke(Some(user.id), connection).unwrap();
+    assert!(revoked_api_key.rotate(Some(user.id), connection).is_err());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let (api_key, _) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    let api_key = api_key.revoke(Some(user.id), connection).unwrap();
+    assert!(api_key.revoked_at.is_some());
+    assert!(!api_key.is_active());
+
+    // Revoking again does not change the key
+    assert_eq!(api_key.revoke(Some(user.id), connection).unwrap(), api_key);
+    let domain_events = DomainEvent::find(
+        Tables::ApiKeys,
+        Some(api_key.id),
+        Some(DomainEventTypes::ApiKeyRevoked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn mark_used() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let (api_key, _) = ApiKey::create(organization.id, user.id, "CRM".to_string(), vec![Scopes::OrgFans], None)
+        .commit(None, connection)
+        .unwrap();
+    assert!(api_key.last_used_at.is_none());
+
+    let api_key = api_key.mark_used(Some("127.0.0.1".to_string()), connection).unwrap();
+    assert!(api_key.last_used_at.is_some());
+    assert_eq!(api_key.last_used_ip, Some("127.0.0.1".to_string()));
+}
@@ -542,11 +542,13 @@ pub fn find_number_of_uses() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -582,11 +584,13 @@ pub fn find_number_of_uses() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
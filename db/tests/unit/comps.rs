@@ -1,6 +1,9 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
+use bigneon_db::schema::ticket_instances;
 use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use diesel::prelude::*;
+use uuid::Uuid;
 
 #[test]
 fn create() {
@@ -16,11 +19,81 @@ fn create() {
         None,
         None,
         5,
+        None,
         db.get_connection(),
     )
     .unwrap();
 }
 
+#[test]
+fn create_with_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let section = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    let seats = section.add_row("A".to_string(), 3, connection).unwrap();
+    let seat_ids: Vec<Uuid> = seats.iter().map(|s| s.id).collect();
+    ticket_type.assign_seats(&seat_ids, None, connection).unwrap();
+    let hold = project
+        .create_hold()
+        .with_hold_type(HoldTypes::Comp)
+        .with_ticket_type_id(ticket_type.id)
+        .with_quantity(0)
+        .finish();
+    hold.add_seats(None, seat_ids.clone(), connection).unwrap();
+
+    let comp = Hold::create_comp_for_person(
+        "test".into(),
+        None,
+        hold.id,
+        None,
+        None,
+        "SEATEDCOMP".to_string(),
+        None,
+        None,
+        0,
+        Some(vec![seat_ids[0], seat_ids[1]]),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(comp.quantity(connection).unwrap(), (2, 2));
+    assert_eq!(hold.quantity(connection).unwrap(), (1, 1));
+
+    let mut comp_seat_ids: Vec<Uuid> = ticket_instances::table
+        .filter(ticket_instances::hold_id.eq(comp.id))
+        .select(ticket_instances::seat_id)
+        .load::<Option<Uuid>>(connection)
+        .unwrap()
+        .into_iter()
+        .filter_map(|s| s)
+        .collect();
+    comp_seat_ids.sort();
+    let mut expected_seat_ids = vec![seat_ids[0], seat_ids[1]];
+    expected_seat_ids.sort();
+    assert_eq!(comp_seat_ids, expected_seat_ids);
+
+    // Seats already comped cannot be comped again
+    assert!(Hold::create_comp_for_person(
+        "test2".into(),
+        None,
+        hold.id,
+        None,
+        None,
+        "SEATEDCOMP2".to_string(),
+        None,
+        None,
+        0,
+        Some(vec![seat_ids[0]]),
+        connection,
+    )
+    .is_err());
+}
+
 #[test]
 pub fn create_with_validation_errors() {
     let db = TestProject::new();
@@ -35,6 +108,7 @@ pub fn create_with_validation_errors() {
         None,
         None,
         11,
+        None,
         db.get_connection(),
     );
 
@@ -0,0 +1,182 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::schema::domain_action_workers;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+
+fn set_last_heartbeat_at(
+    worker: &DomainActionWorker,
+    last_heartbeat_at: NaiveDateTime,
+    connection: &PgConnection,
+) -> DomainActionWorker {
+    diesel::update(worker)
+        .set(domain_action_workers::last_heartbeat_at.eq(last_heartbeat_at))
+        .get_result(connection)
+        .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(worker.name, "api-1:42");
+    assert_eq!(worker.worker_type, DomainActionWorkerTypes::Actions);
+    assert!(!worker.is_leader);
+    assert_eq!(worker.stopped_at, None);
+    assert!(worker.is_healthy());
+    assert_eq!(DomainActionWorker::find(worker.id, connection).unwrap(), worker);
+}
+
+#[test]
+fn heartbeat() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Events)
+        .commit(connection)
+        .unwrap();
+    let worker = set_last_heartbeat_at(&worker, Utc::now().naive_utc() - Duration::minutes(5), connection);
+    assert!(!worker.is_healthy());
+
+    let worker = worker.heartbeat(true, connection).unwrap();
+    assert!(worker.is_healthy());
+    assert!(worker.is_leader);
+}
+
+#[test]
+fn set_stopped() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Events)
+        .commit(connection)
+        .unwrap();
+    let worker = worker.heartbeat(true, connection).unwrap();
+
+    let worker = worker.set_stopped(connection).unwrap();
+    assert!(worker.stopped_at.is_some());
+    assert!(!worker.is_leader);
+    assert!(!worker.is_healthy());
+}
+
+#[test]
+fn find_running() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let actions_worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    let events_worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Events)
+        .commit(connection)
+        .unwrap();
+    let unresponsive_worker = DomainActionWorker::create("api-2:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    let unresponsive_worker = set_last_heartbeat_at(
+        &unresponsive_worker,
+        Utc::now().naive_utc() - Duration::minutes(5),
+        connection,
+    );
+    let stopped_worker = DomainActionWorker::create("api-3:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    stopped_worker.set_stopped(connection).unwrap();
+    let long_dead_worker = DomainActionWorker::create("api-4:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    set_last_heartbeat_at(
+        &long_dead_worker,
+        Utc::now().naive_utc() - Duration::days(1),
+        connection,
+    );
+
+    let workers = DomainActionWorker::find_running(connection).unwrap();
+    assert_eq!(workers, vec![actions_worker, unresponsive_worker, events_worker]);
+}
+
+#[test]
+fn release_actions_from_dead_workers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    let dead_worker = DomainActionWorker::create("api-2:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    let domain_action = project.create_domain_action().finish();
+    domain_action
+        .set_busy_for_worker(60, Some(worker.id), connection)
+        .unwrap();
+    let dead_worker_action = project.create_domain_action().finish();
+    dead_worker_action
+        .set_busy_for_worker(60, Some(dead_worker.id), connection)
+        .unwrap();
+
+    // Both workers are alive
+    assert_eq!(
+        DomainActionWorker::release_actions_from_dead_workers(connection).unwrap(),
+        0
+    );
+
+    set_last_heartbeat_at(&dead_worker, Utc::now().naive_utc() - Duration::minutes(5), connection);
+    assert_eq!(
+        DomainActionWorker::release_actions_from_dead_workers(connection).unwrap(),
+        1
+    );
+
+    let domain_action = DomainAction::find(domain_action.id, connection).unwrap();
+    assert_eq!(domain_action.locked_by_worker_id, Some(worker.id));
+    assert!(domain_action.blocked_until > Utc::now().naive_utc());
+    let dead_worker_action = DomainAction::find(dead_worker_action.id, connection).unwrap();
+    assert_eq!(dead_worker_action.locked_by_worker_id, None);
+    assert!(dead_worker_action.blocked_until <= Utc::now().naive_utc());
+    assert!(DomainAction::find_pending(None, connection)
+        .unwrap()
+        .iter()
+        .any(|a| a.id == dead_worker_action.id));
+
+    // Gracefully stopped workers are also released
+    let domain_action = project.create_domain_action().finish();
+    domain_action
+        .set_busy_for_worker(60, Some(worker.id), connection)
+        .unwrap();
+    worker.set_stopped(connection).unwrap();
+    assert_eq!(
+        DomainActionWorker::release_actions_from_dead_workers(connection).unwrap(),
+        2
+    );
+}
+
+#[test]
+fn delete_stale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let worker = DomainActionWorker::create("api-1:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    let stale_worker = DomainActionWorker::create("api-2:42".to_string(), DomainActionWorkerTypes::Actions)
+        .commit(connection)
+        .unwrap();
+    set_last_heartbeat_at(&stale_worker, Utc::now().naive_utc() - Duration::days(8), connection);
+
+    assert_eq!(DomainActionWorker::delete_stale(connection).unwrap(), 1);
+    assert!(DomainActionWorker::find(worker.id, connection).is_ok());
+    assert!(DomainActionWorker::find(stale_worker.id, connection).is_err());
+}
+
+#[test]
+fn event_publisher_leadership() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    assert!(!DomainActionWorker::holds_event_publisher_leadership(connection).unwrap());
+    assert!(DomainActionWorker::try_acquire_event_publisher_leadership(connection).unwrap());
+    assert!(DomainActionWorker::holds_event_publisher_leadership(connection).unwrap());
+    assert!(DomainActionWorker::release_event_publisher_leadership(connection).unwrap());
+    assert!(!DomainActionWorker::holds_event_publisher_leadership(connection).unwrap());
+}
@@ -22,6 +22,10 @@ fn commit() {
     let domain_action = domain_action.commit(conn).unwrap();
     assert!(!domain_action.id.is_nil());
     assert_eq!(DomainActionTypes::Communication, domain_action.domain_action_type);
+    assert_eq!(
+        DomainActionTypes::Communication.default_priority(),
+        domain_action.priority
+    );
 }
 
 #[test]
@@ -119,6 +123,201 @@ fn find_pending() {
     assert_eq!(pending_example.id, pending_actions[0].id);
 }
 
+#[test]
+fn find_pending_ordered_by_priority() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let the_past = Utc::now().naive_utc() - Duration::minutes(5);
+    let broadcast = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::BroadcastPushNotification)
+        .with_scheduled_at(the_past)
+        .finish();
+    let communication = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .finish();
+    let purchase_completed = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendPurchaseCompletedCommunication)
+        .finish();
+    let earlier_communication = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .with_scheduled_at(the_past)
+        .finish();
+
+    let pending_actions = DomainAction::find_pending(None, conn).unwrap();
+    assert_eq!(
+        pending_actions.iter().map(|a| a.id).collect::<Vec<Uuid>>(),
+        vec![
+            purchase_completed.id,
+            earlier_communication.id,
+            communication.id,
+            broadcast.id
+        ]
+    );
+}
+
+#[test]
+fn find_dead_letters() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let dead_letter = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+    let dead_letter2 = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendWebhook)
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+    project.create_domain_action().finish();
+    project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Errored)
+        .finish();
+
+    let dead_letters = DomainAction::find_dead_letters(None, 0, 100, conn).unwrap();
+    assert_eq!(dead_letters.paging.total, 2);
+    let mut ids: Vec<Uuid> = dead_letters.data.iter().map(|a| a.id).collect();
+    ids.sort();
+    let mut expected_ids = vec![dead_letter.id, dead_letter2.id];
+    expected_ids.sort();
+    assert_eq!(ids, expected_ids);
+
+    let dead_letters = DomainAction::find_dead_letters(Some(DomainActionTypes::SendWebhook), 0, 100, conn).unwrap();
+    assert_eq!(dead_letters.data, vec![dead_letter2]);
+
+    let dead_letters = DomainAction::find_dead_letters(None, 0, 1, conn).unwrap();
+    assert_eq!(dead_letters.data.len(), 1);
+    assert_eq!(dead_letters.paging.total, 2);
+}
+
+#[test]
+fn busy_counts_by_type() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let broadcast = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::BroadcastPushNotification)
+        .finish();
+    let broadcast2 = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::BroadcastPushNotification)
+        .finish();
+    let communication = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .finish();
+    assert!(DomainAction::busy_counts_by_type(conn).unwrap().is_empty());
+
+    broadcast.set_busy(60, conn).unwrap();
+    broadcast2.set_busy(60, conn).unwrap();
+    communication.set_busy(60, conn).unwrap();
+    let busy_counts = DomainAction::busy_counts_by_type(conn).unwrap();
+    assert_eq!(busy_counts.get(&DomainActionTypes::BroadcastPushNotification), Some(&2));
+    assert_eq!(busy_counts.get(&DomainActionTypes::Communication), Some(&1));
+
+    // Finished actions are no longer busy
+    communication.set_done(conn).unwrap();
+    let busy_counts = DomainAction::busy_counts_by_type(conn).unwrap();
+    assert_eq!(busy_counts.get(&DomainActionTypes::Communication), None);
+}
+
+#[test]
+fn is_dead_letter() {
+    let project = TestProject::new();
+
+    let domain_action = project.create_domain_action().finish();
+    assert!(!domain_action.is_dead_letter());
+
+    let domain_action = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+    assert!(domain_action.is_dead_letter());
+}
+
+#[test]
+fn is_stuck() {
+    let project = TestProject::new();
+
+    let domain_action = project.create_domain_action().finish();
+    assert!(!domain_action.is_stuck());
+
+    let domain_action = project
+        .create_domain_action()
+        .with_blocked_until(Utc::now().naive_utc() - Duration::hours(1))
+        .finish();
+    assert!(domain_action.is_stuck());
+}
+
+#[test]
+fn update_payload() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    // Actions still being processed cannot be edited
+    let domain_action = project.create_domain_action().finish();
+    let result = domain_action.update_payload(json!({"to": "new@example.com"}), conn);
+    assert_eq!(result.unwrap_err().error_code, ErrorCode::BusinessProcessError);
+
+    let domain_action = project
+        .create_domain_action()
+        .with_payload(json!({"to": "old@example.com"}))
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+    let domain_action = domain_action
+        .update_payload(json!({"to": "new@example.com"}), conn)
+        .unwrap();
+    assert_eq!(domain_action.payload, json!({"to": "new@example.com"}));
+    assert_eq!(domain_action.status, DomainActionStatus::RetriesExceeded);
+}
+
+#[test]
+fn requeue() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let domain_action = project.create_domain_action().finish();
+    let result = domain_action.requeue(conn);
+    assert_eq!(result.unwrap_err().error_code, ErrorCode::BusinessProcessError);
+
+    let the_past = Utc::now().naive_utc() - Duration::days(2);
+    let domain_action = project
+        .create_domain_action()
+        .with_scheduled_at(the_past)
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .with_attempt_count(3)
+        .finish();
+    assert!(DomainAction::find_pending(None, conn)
+        .unwrap()
+        .iter()
+        .all(|a| a.id != domain_action.id));
+
+    let domain_action = domain_action.requeue(conn).unwrap();
+    assert_eq!(domain_action.status, DomainActionStatus::Pending);
+    assert_eq!(domain_action.attempt_count, 0);
+    assert!(domain_action.expires_at > Utc::now().naive_utc());
+    assert!(DomainAction::find_pending(None, conn)
+        .unwrap()
+        .iter()
+        .any(|a| a.id == domain_action.id));
+
+    // Stuck actions can also be requeued
+    let domain_action = project
+        .create_domain_action()
+        .with_blocked_until(Utc::now().naive_utc() - Duration::hours(1))
+        .finish();
+    let domain_action = domain_action.requeue(conn).unwrap();
+    assert!(!domain_action.is_stuck());
+}
+
 #[test]
 fn has_pending_action() {
     let project = TestProject::new();
@@ -243,6 +442,38 @@ fn set_failed() {
     assert!(updated.blocked_until.timestamp() <= Utc::now().naive_utc().timestamp());
 }
 
+#[test]
+fn set_failed_and_retry_at() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let example = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Pending)
+        .finish();
+    let retry_at = Utc::now().naive_utc() + Duration::minutes(10);
+    example.set_failed_and_retry_at("test", Some(retry_at), conn).unwrap();
+
+    let updated = DomainAction::find(example.id, conn).unwrap();
+    assert_eq!("test", updated.last_failure_reason.unwrap());
+    assert_eq!(DomainActionStatus::Pending, updated.status);
+    assert_eq!(1, updated.attempt_count);
+    assert_eq!(retry_at.timestamp(), updated.blocked_until.timestamp());
+
+    // Exceeding max failures ignores the retry
+    let example = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Pending)
+        .with_attempt_count(1)
+        .with_max_attempt_count(2)
+        .finish();
+    example.set_failed_and_retry_at("test2", Some(retry_at), conn).unwrap();
+
+    let updated = DomainAction::find(example.id, conn).unwrap();
+    assert_eq!(DomainActionStatus::RetriesExceeded, updated.status);
+    assert!(updated.blocked_until.timestamp() <= Utc::now().naive_utc().timestamp());
+}
+
 #[test]
 fn set_errored() {
     let project = TestProject::new();
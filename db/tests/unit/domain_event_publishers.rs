@@ -2,9 +2,11 @@ use bigneon_db::dev::TestProject;
 use bigneon_db::prelude::*;
 use bigneon_db::schema::domain_events;
 use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
 use diesel;
 use diesel::prelude::*;
 use diesel::RunQueryDsl;
+use serde_json;
 use uuid::Uuid;
 
 #[test]
@@ -140,6 +142,7 @@ fn find_with_unpublished_domain_events() {
     let parameters = DomainEventPublisherEditableAttributes {
         webhook_url: None,
         import_historic_events: Some(false),
+        ..Default::default()
     };
     let global_domain_event_publisher = global_domain_event_publisher.update(&parameters, connection).unwrap();
 
@@ -234,6 +237,51 @@ fn find_with_unpublished_domain_events() {
     );
 }
 
+#[test]
+fn find_with_unpublished_domain_events_for_organization_records() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let organization_domain_event_publisher = DomainEventPublisher::create(
+        Some(organization.id),
+        vec![DomainEventTypes::EventPublished],
+        "http://localhost:7644/webhook".to_string(),
+        true,
+    )
+    .commit(connection)
+    .unwrap();
+    let event = project.create_event().with_organization(&organization).finish();
+    let event2 = project.create_event().with_organization(&organization2).finish();
+
+    // Events table domain events are not linked to a user
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::EventPublished,
+        "Event published".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(domain_event.organization_id, Some(organization.id));
+    DomainEvent::create(
+        DomainEventTypes::EventPublished,
+        "Event published".to_string(),
+        Tables::Events,
+        Some(event2.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let publisher_data = DomainEventPublisher::find_with_unpublished_domain_events(100, connection).unwrap();
+    let organization_domain_events = publisher_data.get(&organization_domain_event_publisher).unwrap();
+    assert_eq!(organization_domain_events, &vec![domain_event]);
+}
+
 #[test]
 fn publish() {
     let project = TestProject::new();
@@ -266,6 +314,160 @@ fn publish() {
     domain_event_publisher
         .publish(&domain_event, &"".to_string(), connection)
         .unwrap();
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::DomainEventPublishers),
+        Some(domain_event_publisher.id),
+        DomainActionTypes::SendWebhook,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(domain_actions[0].domain_event_id, Some(domain_event.id));
+    let payload: SendWebhookPayload = serde_json::from_value(domain_actions[0].payload.clone()).unwrap();
+    assert_eq!(payload.attempt, 1);
+    assert_eq!(payload.body["webhook_event_type"], json!("purchase_ticket"));
+
+    // Already published events are not queued again
+    domain_event_publisher
+        .publish(&domain_event, &"".to_string(), connection)
+        .unwrap();
+    assert_eq!(
+        DomainAction::find_by_resource(
+            Some(Tables::DomainEventPublishers),
+            Some(domain_event_publisher.id),
+            DomainActionTypes::SendWebhook,
+            DomainActionStatus::Pending,
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn queue_webhook() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+
+    let domain_action = domain_event_publisher
+        .queue_webhook(None, json!({"webhook_event_type": "user_created"}), 1, connection)
+        .unwrap();
+    assert_eq!(domain_action.domain_action_type, DomainActionTypes::SendWebhook);
+    assert_eq!(domain_action.main_table, Some(Tables::DomainEventPublishers));
+    assert_eq!(domain_action.main_table_id, Some(domain_event_publisher.id));
+    assert!(domain_action.scheduled_at <= dates::now().finish());
+
+    // Retries are scheduled after the backoff
+    let domain_action = domain_event_publisher
+        .queue_webhook(None, json!({"webhook_event_type": "user_created"}), 3, connection)
+        .unwrap();
+    let payload: SendWebhookPayload = serde_json::from_value(domain_action.payload.clone()).unwrap();
+    assert_eq!(payload.attempt, 3);
+    assert!(domain_action.scheduled_at > dates::now().add_seconds(50).finish());
+    assert!(domain_action.scheduled_at <= dates::now().add_seconds(60).finish());
+    assert!(domain_action.expires_at > domain_action.scheduled_at);
+}
+
+#[test]
+fn webhook_retry_delay() {
+    assert_eq!(DomainEventPublisher::webhook_retry_delay(1), 0);
+    assert_eq!(DomainEventPublisher::webhook_retry_delay(2), 30);
+    assert_eq!(DomainEventPublisher::webhook_retry_delay(3), 60);
+    assert_eq!(DomainEventPublisher::webhook_retry_delay(4), 120);
+    assert_eq!(DomainEventPublisher::webhook_retry_delay(WEBHOOK_MAX_ATTEMPTS), 1920);
+}
+
+#[test]
+fn sign() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let domain_event_publisher2 = project.create_domain_event_publisher().finish();
+    assert_eq!(domain_event_publisher.signing_secret.len(), 64);
+    assert_ne!(
+        domain_event_publisher.signing_secret,
+        domain_event_publisher2.signing_secret
+    );
+
+    let body = r#"{"webhook_event_type":"user_created"}"#;
+    let signature = domain_event_publisher.sign(1583398800, body);
+    assert_eq!(signature.len(), 64);
+    assert_eq!(signature, domain_event_publisher.sign(1583398800, body));
+    assert_ne!(signature, domain_event_publisher.sign(1583398801, body));
+    assert_ne!(signature, domain_event_publisher2.sign(1583398800, body));
+
+    // Signing secret is never serialized
+    let json = serde_json::to_value(&domain_event_publisher).unwrap();
+    assert!(json.get("signing_secret").is_none());
+    let domain_event_publisher = DomainEventPublisher::find(domain_event_publisher.id, connection).unwrap();
+    assert_eq!(signature, domain_event_publisher.sign(1583398800, body));
+}
+
+#[test]
+fn replay_from_seq() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let mut domain_event_publisher = DomainEventPublisher::create(
+        None,
+        vec![DomainEventTypes::OrderCompleted],
+        "http://localhost:7644/webhook".to_string(),
+        true,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let publisher_data = DomainEventPublisher::find_with_unpublished_domain_events(100, connection).unwrap();
+    let domain_event = publisher_data.get(&domain_event_publisher).unwrap()[0].clone();
+    domain_event_publisher
+        .publish(&domain_event, &"".to_string(), connection)
+        .unwrap();
+    domain_event_publisher
+        .update_last_domain_event_seq(domain_event.seq, connection)
+        .unwrap();
+    let publisher_data = DomainEventPublisher::find_with_unpublished_domain_events(100, connection).unwrap();
+    assert!(publisher_data.get(&domain_event_publisher).is_none());
+
+    // Replaying later events leaves earlier ones published
+    domain_event_publisher
+        .replay_from_seq(domain_event.seq + 1, connection)
+        .unwrap();
+    assert_eq!(domain_event_publisher.last_domain_event_seq, Some(domain_event.seq));
+    let publisher_data = DomainEventPublisher::find_with_unpublished_domain_events(100, connection).unwrap();
+    assert!(publisher_data.get(&domain_event_publisher).is_none());
+
+    domain_event_publisher
+        .replay_from_seq(domain_event.seq, connection)
+        .unwrap();
+    assert_eq!(domain_event_publisher.last_domain_event_seq, Some(domain_event.seq - 1));
+    let domain_event_publisher = DomainEventPublisher::find(domain_event_publisher.id, connection).unwrap();
+    assert_eq!(domain_event_publisher.last_domain_event_seq, Some(domain_event.seq - 1));
+    let publisher_data = DomainEventPublisher::find_with_unpublished_domain_events(100, connection).unwrap();
+    assert_eq!(
+        publisher_data
+            .get(&domain_event_publisher)
+            .unwrap()
+            .iter()
+            .map(|d| d.id)
+            .collect::<Vec<Uuid>>(),
+        vec![domain_event.id]
+    );
 }
 
 #[test]
@@ -327,9 +529,121 @@ fn update() {
     let parameters = DomainEventPublisherEditableAttributes {
         webhook_url: Some(new_webhook_url.clone()),
         import_historic_events: Some(false),
+        ..Default::default()
     };
     let domain_event_publisher = domain_event_publisher.update(&parameters, connection).unwrap();
 
     assert_eq!(domain_event_publisher.webhook_url, new_webhook_url);
     assert_eq!(domain_event_publisher.import_historic_events, false);
+
+    let parameters = DomainEventPublisherEditableAttributes {
+        event_types: Some(vec![DomainEventTypes::EventPublished, DomainEventTypes::OrderCompleted]),
+        adapter: Some(Some(WebhookAdapters::CustomerIo)),
+        adapter_config: Some(Some(json!({"site_id": "site", "api_key": "key"}))),
+        ..Default::default()
+    };
+    let domain_event_publisher = domain_event_publisher.update(&parameters, connection).unwrap();
+    assert_eq!(
+        domain_event_publisher.event_types,
+        vec![DomainEventTypes::EventPublished, DomainEventTypes::OrderCompleted]
+    );
+    assert_eq!(domain_event_publisher.adapter, Some(WebhookAdapters::CustomerIo));
+
+    // Clearing the adapter config leaves the Customer.io adapter without credentials
+    let parameters = DomainEventPublisherEditableAttributes {
+        adapter_config: Some(None),
+        ..Default::default()
+    };
+    let result = domain_event_publisher.update(&parameters, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("adapter_config"));
+                assert_eq!(errors["adapter_config"].len(), 1);
+                assert_eq!(errors["adapter_config"][0].code, "invalid_adapter_config");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let parameters = DomainEventPublisherEditableAttributes {
+        adapter: Some(None),
+        adapter_config: Some(None),
+        ..Default::default()
+    };
+    let domain_event_publisher = domain_event_publisher.update(&parameters, connection).unwrap();
+    assert_eq!(domain_event_publisher.adapter, None);
+    assert_eq!(domain_event_publisher.adapter_config, None);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let mut domain_event_publisher =
+        DomainEventPublisher::create(None, vec![], "http://localhost:7644/webhook".to_string(), true);
+    domain_event_publisher.adapter = Some(WebhookAdapters::CustomerIo);
+    let result = domain_event_publisher.commit(connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_types"));
+                assert_eq!(errors["event_types"][0].code, "required");
+                assert!(errors.contains_key("adapter_config"));
+                assert_eq!(errors["adapter_config"][0].code, "invalid_adapter_config");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let domain_event_publisher = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+    let domain_event_publisher2 = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+    project
+        .create_domain_event_publisher()
+        .with_organization(&organization2)
+        .finish();
+    project.create_domain_event_publisher().finish();
+
+    assert_eq!(
+        DomainEventPublisher::find_for_organization(organization.id, connection).unwrap(),
+        vec![domain_event_publisher.clone(), domain_event_publisher2.clone()]
+    );
+
+    domain_event_publisher2.delete(connection).unwrap();
+    assert_eq!(
+        DomainEventPublisher::find_for_organization(organization.id, connection).unwrap(),
+        vec![domain_event_publisher]
+    );
+}
+
+#[test]
+fn rotate_signing_secret() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let rotated_domain_event_publisher = domain_event_publisher.rotate_signing_secret(connection).unwrap();
+    assert_eq!(rotated_domain_event_publisher.signing_secret.len(), 64);
+    assert_ne!(
+        rotated_domain_event_publisher.signing_secret,
+        domain_event_publisher.signing_secret
+    );
 }
@@ -443,6 +443,37 @@ fn webhook_payloads() {
     );
 }
 
+#[test]
+fn webhook_payloads_for_event_type_without_detailed_payload() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::EventPublished,
+        "Event published".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        Some(json!({"name": event.name})),
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(domain_event.organization_id, Some(organization.id));
+
+    let payloads = domain_event
+        .webhook_payloads("http://localhost:5432", connection)
+        .unwrap();
+    assert_eq!(payloads.len(), 1);
+    let payload = &payloads[0];
+    assert_eq!(payload["webhook_event_type"], json!("event_published"));
+    assert_eq!(payload["domain_event_id"], json!(domain_event.id));
+    assert_eq!(payload["main_table"], json!(Tables::Events));
+    assert_eq!(payload["main_id"], json!(event.id));
+    assert_eq!(payload["organization_id"], json!(organization.id));
+    assert_eq!(payload["event_data"], json!({"name": event.name}));
+}
+
 #[test]
 fn serialize() {
     let project = TestProject::new();
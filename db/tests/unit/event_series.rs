@@ -0,0 +1,289 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::DatabaseError;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+
+    let event_series = EventSeries::create(&event, RecurrenceFrequencies::Weekly, 2, 3, user.id)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(event_series.template_event_id, event.id);
+    assert_eq!(event_series.organization_id, event.organization_id);
+
+    let events = event_series.events(connection).unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].id, event.id);
+    for (index, occurrence) in events.iter().enumerate() {
+        assert_eq!(occurrence.event_series_id, Some(event_series.id));
+        assert_eq!(occurrence.event_series_index, Some(index as i32));
+        assert_eq!(
+            occurrence.event_start,
+            event.event_start.map(|d| d + Duration::weeks(2 * index as i64))
+        );
+        assert_eq!(occurrence.ticket_types(true, None, connection).unwrap().len(), 1);
+    }
+    assert_eq!(events[1].status, EventStatus::Draft);
+
+    let domain_events = DomainEvent::find(
+        Tables::EventSeries,
+        Some(event_series.id),
+        Some(DomainEventTypes::EventSeriesCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn commit_with_invalid_data() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().finish();
+
+    let result = EventSeries::create(&event, RecurrenceFrequencies::Daily, 0, 1, user.id).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("frequency_interval"));
+                assert_eq!(errors["frequency_interval"][0].code, "frequency_interval_lt_1");
+                assert!(errors.contains_key("occurrences"));
+                assert_eq!(errors["occurrences"][0].code, "occurrences_lt_2");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Events can only belong to one series
+    EventSeries::create(&event, RecurrenceFrequencies::Daily, 1, 2, user.id)
+        .commit(connection)
+        .unwrap();
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(
+        EventSeries::create(&event, RecurrenceFrequencies::Daily, 1, 2, user.id).commit(connection),
+        DatabaseError::business_process_error("Event already belongs to a series")
+    );
+}
+
+#[test]
+fn shift_for() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_event_start(NaiveDate::from_ymd(2030, 1, 31).and_hms(20, 0, 0))
+        .with_event_end(NaiveDate::from_ymd(2030, 2, 1).and_hms(2, 0, 0))
+        .finish();
+
+    let event_series = EventSeries::create(&event, RecurrenceFrequencies::Monthly, 1, 3, user.id)
+        .commit(connection)
+        .unwrap();
+    let event_starts: Vec<Option<NaiveDateTime>> = event_series
+        .events(connection)
+        .unwrap()
+        .iter()
+        .map(|e| e.event_start)
+        .collect();
+    assert_eq!(
+        event_starts,
+        vec![
+            Some(NaiveDate::from_ymd(2030, 1, 31).and_hms(20, 0, 0)),
+            Some(NaiveDate::from_ymd(2030, 2, 28).and_hms(20, 0, 0)),
+            Some(NaiveDate::from_ymd(2030, 3, 31).and_hms(20, 0, 0)),
+        ]
+    );
+}
+
+#[test]
+fn update_following() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().finish();
+    let event_series = EventSeries::create(&event, RecurrenceFrequencies::Weekly, 1, 3, user.id)
+        .commit(connection)
+        .unwrap();
+    let events = event_series.events(connection).unwrap();
+
+    let event_start = events[1].event_start.unwrap() + Duration::hours(1);
+    let attributes = EventEditableAttributes {
+        name: Some("Weekly".to_string()),
+        event_start: Some(event_start),
+        ..Default::default()
+    };
+    let updated = event_series
+        .update_following(&events[1], attributes, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(updated.len(), 2);
+
+    let events = event_series.events(connection).unwrap();
+    assert_eq!(events[0].name, event.name);
+    assert_eq!(events[0].event_start, event.event_start);
+    assert_eq!(events[1].name, "Weekly");
+    assert_eq!(events[1].event_start, Some(event_start));
+    assert_eq!(events[2].name, "Weekly");
+    assert_eq!(events[2].event_start, Some(event_start + Duration::weeks(1)));
+
+    // Events outside of the series are rejected
+    let other_event = project.create_event().finish();
+    assert_eq!(
+        event_series.update_following(&other_event, Default::default(), Some(user.id), connection),
+        DatabaseError::business_process_error("Event does not belong to this series")
+    );
+}
+
+#[test]
+fn update_following_copies_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_ticket_pricing()
+        .finish();
+    let event_series = EventSeries::create(&event, RecurrenceFrequencies::Weekly, 1, 3, user.id)
+        .commit(connection)
+        .unwrap();
+    let events = event_series.events(connection).unwrap();
+    let ticket_types = |event: &Event| -> Vec<TicketType> { event.ticket_types(false, None, connection).unwrap() };
+    let original_ticket_types = ticket_types(&events[0]);
+
+    // Rename the first ticket type and change the pricing of the second
+    let mut occurrence_ticket_types = ticket_types(&events[1]);
+    occurrence_ticket_types
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                name: Some("Renamed".to_string()),
+                limit_per_person: Some(4),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    let repriced_ticket_type = occurrence_ticket_types.remove(0);
+    let mut ticket_pricing = repriced_ticket_type.valid_ticket_pricing(false, connection).unwrap();
+    assert_eq!(ticket_pricing.len(), 2);
+    let standard_pricing = ticket_pricing.pop().unwrap();
+    assert_eq!(standard_pricing.name, "Standard");
+    ticket_pricing[0].destroy(Some(user.id), connection).unwrap();
+    let standard_pricing = standard_pricing
+        .update(
+            TicketPricingEditableAttributes {
+                price_in_cents: Some(200),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+
+    // Add a new ticket type
+    let vip_start = standard_pricing.start_date;
+    let vip_end = standard_pricing.end_date;
+    let vip_ticket_type = events[1]
+        .add_ticket_type(
+            "VIP".to_string(),
+            None,
+            10,
+            Some(vip_start),
+            Some(vip_end),
+            TicketTypeEndDateType::Manual,
+            None,
+            None,
+            2,
+            500,
+            TicketTypeVisibility::Always,
+            None,
+            0,
+            true,
+            true,
+            true,
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    vip_ticket_type
+        .add_ticket_pricing(
+            "VIP".to_string(),
+            vip_start,
+            vip_end,
+            500,
+            false,
+            None,
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+
+    // Holds and codes are managed per occurrence
+    project.create_hold().with_event(&events[1]).finish();
+    project.create_code().with_event(&events[1]).finish();
+
+    let attributes = EventEditableAttributes {
+        name: Some("Weekly".to_string()),
+        ..Default::default()
+    };
+    event_series
+        .update_following(&events[1], attributes, Some(user.id), connection)
+        .unwrap();
+
+    let events = event_series.events(connection).unwrap();
+    assert_eq!(events[2].name, "Weekly");
+    let following_ticket_types = ticket_types(&events[2]);
+    assert_eq!(following_ticket_types.len(), 3);
+    assert_eq!(following_ticket_types[0].name, "Renamed");
+    assert_eq!(following_ticket_types[0].limit_per_person, 4);
+    assert_eq!(following_ticket_types[0].valid_ticket_count(connection).unwrap(), 100);
+
+    let following_pricing = following_ticket_types[1]
+        .valid_ticket_pricing(false, connection)
+        .unwrap();
+    assert_eq!(following_pricing.len(), 1);
+    assert_eq!(following_pricing[0].name, "Standard");
+    assert_eq!(following_pricing[0].price_in_cents, 200);
+    assert_eq!(
+        following_pricing[0].start_date,
+        standard_pricing.start_date + Duration::weeks(1)
+    );
+    assert_eq!(
+        following_pricing[0].end_date,
+        standard_pricing.end_date + Duration::weeks(1)
+    );
+
+    let vip_ticket_type = following_ticket_types.iter().find(|tt| tt.name == "VIP").unwrap();
+    assert_eq!(vip_ticket_type.valid_ticket_count(connection).unwrap(), 10);
+    assert_eq!(vip_ticket_type.start_date, Some(vip_start + Duration::weeks(1)));
+    let vip_pricing = vip_ticket_type.valid_ticket_pricing(false, connection).unwrap();
+    assert_eq!(vip_pricing.len(), 1);
+    assert_eq!(vip_pricing[0].price_in_cents, 500);
+    assert_eq!(vip_pricing[0].start_date, vip_start + Duration::weeks(1));
+
+    // Earlier occurrences keep their ticket types
+    let template_ticket_types = ticket_types(&events[0]);
+    assert_eq!(template_ticket_types.len(), 2);
+    assert_eq!(template_ticket_types[0].name, original_ticket_types[0].name);
+    assert_eq!(
+        template_ticket_types[1]
+            .valid_ticket_pricing(false, connection)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    assert!(Hold::find_for_event(events[2].id, true, connection).unwrap().is_empty());
+    assert!(Code::find_for_event(events[2].id, None, connection).unwrap().is_empty());
+    assert_eq!(Hold::find_for_event(events[1].id, true, connection).unwrap().len(), 1);
+}
@@ -1,6 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
-use bigneon_db::schema::{orders, refunds};
+use bigneon_db::schema::{codes, events, orders, refunds};
 use bigneon_db::services::CountryLookup;
 use bigneon_db::utils::dates;
 use bigneon_db::utils::errors::DatabaseError;
@@ -308,6 +308,7 @@ fn eligible_for_deletion() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -797,6 +798,7 @@ fn delete() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1585,6 +1587,67 @@ fn guest_list() {
     assert_eq!(first_ticket.id, guest_list_item.ticket.id);
 }
 
+#[test]
+fn create_with_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("EUR").finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    assert_eq!(organization.currency, "EUR".to_string());
+    assert_eq!(event.currency, organization.currency);
+
+    let mut new_event = Event::create(
+        "name",
+        EventStatus::Draft,
+        organization.id,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    new_event.currency = Some("USD".to_string());
+    let result = new_event.commit(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"].len(), 1);
+                assert_eq!(errors["currency"][0].code, "currency_does_not_match_fee_schedule");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let mut new_event = Event::create(
+        "name",
+        EventStatus::Draft,
+        organization.id,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    new_event.currency = Some("eur".to_string());
+    let result = new_event.commit(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"][0].code, "currency");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn publish_fails_without_required_fields() {
     let project = TestProject::new();
@@ -1912,6 +1975,7 @@ fn get_sales_by_date_range() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1938,6 +2002,7 @@ fn get_sales_by_date_range() {
                 ticket_type_id: ticket_type.id,
                 quantity: 5,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -2091,6 +2156,7 @@ fn find_by_order_item_ids() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2115,6 +2181,7 @@ fn find_by_order_item_ids() {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4321,3 +4388,117 @@ fn checked_in_users() {
     let users = Event::checked_in_users(event.id, connection).unwrap();
     assert_eq!(users[0], user);
 }
+
+#[test]
+fn duplicate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let hold = project
+        .create_hold()
+        .with_ticket_type_id(ticket_type.id)
+        .with_quantity(5)
+        .finish();
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .finish();
+    let artist = project.create_artist().finish();
+    project
+        .create_event_artist()
+        .with_event(&event)
+        .with_artist(&artist)
+        .finish();
+
+    let event_start = event.event_start.unwrap() + Duration::days(7);
+    let new_event = event
+        .duplicate(event_start, Some("Next week".to_string()), user.id, connection)
+        .unwrap();
+    assert_ne!(new_event.id, event.id);
+    assert_eq!(new_event.name, "Next week");
+    assert_eq!(new_event.status, EventStatus::Draft);
+    assert_eq!(new_event.event_start, Some(event_start));
+    assert_eq!(new_event.event_end, event.event_end.map(|d| d + Duration::days(7)));
+    assert_eq!(new_event.door_time, event.door_time.map(|d| d + Duration::days(7)));
+
+    let new_ticket_types = new_event.ticket_types(true, None, connection).unwrap();
+    assert_eq!(new_ticket_types.len(), 1);
+    let new_ticket_type = &new_ticket_types[0];
+    assert_eq!(new_ticket_type.name, ticket_type.name);
+    assert_eq!(
+        new_ticket_type.valid_ticket_count(connection).unwrap(),
+        ticket_type.valid_ticket_count(connection).unwrap()
+    );
+    assert_eq!(
+        new_ticket_type.start_date,
+        ticket_type.start_date.map(|d| d + Duration::days(7))
+    );
+    let ticket_pricing = ticket_type.valid_ticket_pricing(false, connection).unwrap();
+    let new_ticket_pricing = new_ticket_type.valid_ticket_pricing(false, connection).unwrap();
+    assert_eq!(new_ticket_pricing.len(), ticket_pricing.len());
+    for (pricing, new_pricing) in ticket_pricing.iter().zip(new_ticket_pricing.iter()) {
+        assert_eq!(new_pricing.name, pricing.name);
+        assert_eq!(new_pricing.price_in_cents, pricing.price_in_cents);
+        assert_eq!(new_pricing.start_date, pricing.start_date + Duration::days(7));
+        assert_eq!(new_pricing.end_date, pricing.end_date + Duration::days(7));
+    }
+
+    let new_hold =
+        Hold::find_by_redemption_code(hold.redemption_code.as_ref().unwrap(), Some(new_event.id), connection).unwrap();
+    assert_eq!(new_hold.ticket_type_id, new_ticket_type.id);
+    assert_eq!(new_hold.quantity(connection).unwrap(), (5, 5));
+
+    let new_codes: Vec<Code> = codes::table
+        .filter(codes::event_id.eq(new_event.id))
+        .load(connection)
+        .unwrap();
+    assert_eq!(new_codes.len(), 1);
+    assert_eq!(new_codes[0].redemption_code, code.redemption_code);
+    assert_eq!(new_codes[0].start_date, code.start_date + Duration::days(7));
+    assert_eq!(
+        TicketType::find_for_code(new_codes[0].id, connection).unwrap()[0].id,
+        new_ticket_type.id
+    );
+
+    let new_artists = new_event.artists(connection).unwrap();
+    assert_eq!(new_artists.len(), 1);
+    assert_eq!(new_artists[0].artist.id, artist.id);
+
+    // Original is unchanged
+    assert_eq!(Event::find(event.id, connection).unwrap(), event);
+}
+
+#[test]
+fn duplicate_requires_event_start() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().finish();
+    let event: Event = diesel::update(&event)
+        .set(events::event_start.eq(None::<NaiveDateTime>))
+        .get_result(connection)
+        .unwrap();
+
+    assert_eq!(
+        event.duplicate(dates::now().add_days(7).finish(), None, user.id, connection),
+        DatabaseError::business_process_error("Event must have an event start to be duplicated")
+    );
+}
+
+#[test]
+fn timezone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project
+        .create_venue()
+        .with_timezone("Africa/Johannesburg".to_string())
+        .finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    assert_eq!(event.timezone(connection).unwrap(), chrono_tz::Africa::Johannesburg);
+
+    let event = project.create_event().finish();
+    assert_eq!(event.timezone(connection).unwrap(), chrono_tz::UTC);
+}
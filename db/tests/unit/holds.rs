@@ -45,6 +45,7 @@ fn quantity_and_children_quantity() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -351,6 +352,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: comp.redemption_code,
+            seat_ids: None,
         }],
         false,
         false,
@@ -388,6 +390,7 @@ fn remove_available_quantity() {
         None,
         None,
         2,
+        None,
         connection,
     )
     .unwrap();
@@ -402,11 +405,13 @@ fn remove_available_quantity() {
                 ticket_type_id: ticket_type.id,
                 quantity: 4,
                 redemption_code: hold.redemption_code.clone(),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: child_hold.redemption_code.clone(),
+                seat_ids: None,
             },
         ],
         false,
@@ -432,6 +437,7 @@ fn remove_available_quantity() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -1,4 +1,5 @@
 pub mod activities;
+pub mod api_keys;
 pub mod artists;
 pub mod assets;
 pub mod broadcasts;
@@ -6,12 +7,14 @@ pub mod codes;
 pub mod communication;
 pub mod comps;
 pub mod concerns;
+pub mod domain_action_workers;
 pub mod domain_actions;
 pub mod domain_event_publishers;
 pub mod domain_events;
 pub mod event_artists;
 pub mod event_interest;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod event_users;
 pub mod events;
 pub mod external_logins;
@@ -20,6 +23,10 @@ pub mod fee_schedules;
 pub mod genres;
 pub mod holds;
 pub mod notes;
+pub mod oauth_authorization_codes;
+pub mod oauth_clients;
+pub mod oauth_consents;
+pub mod offline_redemptions;
 pub mod order_items;
 pub mod orders;
 pub mod organization_interactions;
@@ -35,18 +42,32 @@ pub mod refunded_tickets;
 pub mod refunds;
 pub mod regions;
 pub mod reports;
+pub mod resale_listings;
+pub mod resale_payouts;
+pub mod saved_report_subscribers;
+pub mod saved_reports;
+pub mod season_passes;
 pub mod services;
 pub mod settlement_adjustments;
 pub mod settlement_entries;
 pub mod settlements;
 pub mod slugs;
+pub mod stage_sections;
 pub mod stages;
+pub mod tax_rules;
 pub mod temporary_users;
 pub mod ticket_instances;
 pub mod ticket_pricing;
+pub mod ticket_pricing_rules;
 pub mod ticket_type_codes;
 pub mod ticket_types;
 pub mod transfer_tickets;
 pub mod transfers;
+pub mod two_factor_challenges;
+pub mod two_factor_credentials;
+pub mod two_factor_recovery_codes;
+pub mod user_sessions;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
+pub mod webhook_deliveries;
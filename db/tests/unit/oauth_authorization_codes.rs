@@ -0,0 +1,160 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::oauth_authorization_codes;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+
+// Example from RFC 7636 Appendix B
+const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+const REDIRECT_URI: &str = "https://example.com/callback";
+
+fn create_consent(project: &TestProject) -> (OAuthClient, OAuthConsent) {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec![REDIRECT_URI.to_string()],
+        vec![Scopes::EventRead, Scopes::OrderRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let consent = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::EventRead], connection).unwrap();
+    (oauth_client, consent)
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_, consent) = create_consent(&project);
+
+    let (authorization_code, code) = OAuthAuthorizationCode::create(
+        consent.id,
+        REDIRECT_URI.to_string(),
+        vec![Scopes::EventRead],
+        CODE_CHALLENGE.to_string(),
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(authorization_code.oauth_consent_id, consent.id);
+    assert_eq!(authorization_code.scopes().unwrap(), vec![Scopes::EventRead]);
+    assert_eq!(code.len(), 40);
+    assert_ne!(authorization_code.code_hash, code);
+    assert!(authorization_code.used_at.is_none());
+    assert!(authorization_code.expires_at > Utc::now().naive_utc());
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (oauth_client, consent) = create_consent(&project);
+    let (authorization_code, code) = OAuthAuthorizationCode::create(
+        consent.id,
+        REDIRECT_URI.to_string(),
+        vec![Scopes::EventRead],
+        CODE_CHALLENGE.to_string(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Redirect URI and code verifier must match the authorization request
+    assert!(OAuthAuthorizationCode::redeem(
+        &code,
+        &oauth_client,
+        "https://example.com/other",
+        CODE_VERIFIER,
+        connection
+    )
+    .is_err());
+    assert!(OAuthAuthorizationCode::redeem(
+        &code,
+        &oauth_client,
+        REDIRECT_URI,
+        "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXj",
+        connection
+    )
+    .is_err());
+
+    let (redeemed_code, redeemed_consent) =
+        OAuthAuthorizationCode::redeem(&code, &oauth_client, REDIRECT_URI, CODE_VERIFIER, connection).unwrap();
+    assert_eq!(redeemed_code.id, authorization_code.id);
+    assert!(redeemed_code.used_at.is_some());
+    assert_eq!(redeemed_consent, consent);
+}
+
+#[test]
+fn redeem_reused_code_revokes_consent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (oauth_client, consent) = create_consent(&project);
+    let (_, code) = OAuthAuthorizationCode::create(
+        consent.id,
+        REDIRECT_URI.to_string(),
+        vec![Scopes::EventRead],
+        CODE_CHALLENGE.to_string(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    OAuthAuthorizationCode::redeem(&code, &oauth_client, REDIRECT_URI, CODE_VERIFIER, connection).unwrap();
+    let result = OAuthAuthorizationCode::redeem(&code, &oauth_client, REDIRECT_URI, CODE_VERIFIER, connection);
+    assert_eq!(
+        result.unwrap_err().cause,
+        Some("Authorization code has already been used".to_string())
+    );
+    assert!(!OAuthConsent::find(consent.id, connection).unwrap().is_active());
+}
+
+#[test]
+fn redeem_other_client() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_, consent) = create_consent(&project);
+    let (oauth_client2, _) = create_consent(&project);
+    let (_, code) = OAuthAuthorizationCode::create(
+        consent.id,
+        REDIRECT_URI.to_string(),
+        vec![Scopes::EventRead],
+        CODE_CHALLENGE.to_string(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let result = OAuthAuthorizationCode::redeem(&code, &oauth_client2, REDIRECT_URI, CODE_VERIFIER, connection);
+    assert_eq!(
+        result.unwrap_err().cause,
+        Some("Authorization code was not issued to this client".to_string())
+    );
+}
+
+#[test]
+fn redeem_expired() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (oauth_client, consent) = create_consent(&project);
+    let (authorization_code, code) = OAuthAuthorizationCode::create(
+        consent.id,
+        REDIRECT_URI.to_string(),
+        vec![Scopes::EventRead],
+        CODE_CHALLENGE.to_string(),
+    )
+    .commit(connection)
+    .unwrap();
+    diesel::update(&authorization_code)
+        .set(oauth_authorization_codes::expires_at.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+        .execute(connection)
+        .unwrap();
+
+    let result = OAuthAuthorizationCode::redeem(&code, &oauth_client, REDIRECT_URI, CODE_VERIFIER, connection);
+    assert_eq!(
+        result.unwrap_err().cause,
+        Some("Authorization code has expired".to_string())
+    );
+}
@@ -0,0 +1,171 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead, Scopes::OrderRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(oauth_client.organization_id, organization.id);
+    assert_eq!(oauth_client.client_id.len(), 32);
+    assert_eq!(
+        oauth_client.scopes().unwrap(),
+        vec![Scopes::EventRead, Scopes::OrderRead]
+    );
+    assert!(!oauth_client.is_confidential());
+
+    let domain_events = DomainEvent::find(
+        Tables::OAuthClients,
+        Some(oauth_client.id),
+        Some(DomainEventTypes::OAuthClientCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let result = OAuthClient::create(
+        organization.id,
+        "".to_string(),
+        vec!["not a url".to_string()],
+        vec![],
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+                assert!(errors.contains_key("redirect_uris"));
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    OAuthClient::create(
+        organization2.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        OAuthClient::find_for_organization(organization.id, connection).unwrap(),
+        vec![oauth_client]
+    );
+}
+
+#[test]
+fn authenticate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Public client
+    assert!(oauth_client.authenticate(None));
+    assert!(!oauth_client.authenticate(Some("secret")));
+
+    let (oauth_client, client_secret) = oauth_client.generate_secret(Some(user.id), connection).unwrap();
+    assert!(oauth_client.is_confidential());
+    assert!(oauth_client.authenticate(Some(&client_secret)));
+    assert!(!oauth_client.authenticate(Some("secret")));
+    assert!(!oauth_client.authenticate(None));
+
+    // Previous secret no longer works once regenerated
+    let (oauth_client, new_client_secret) = oauth_client.generate_secret(Some(user.id), connection).unwrap();
+    assert!(oauth_client.authenticate(Some(&new_client_secret)));
+    assert!(!oauth_client.authenticate(Some(&client_secret)));
+}
+
+#[test]
+fn is_valid_redirect_uri() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    assert!(oauth_client.is_valid_redirect_uri("https://example.com/callback"));
+    assert!(!oauth_client.is_valid_redirect_uri("https://example.com/callback/other"));
+    assert!(!oauth_client.is_valid_redirect_uri("https://attacker.com/callback"));
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let consent = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::EventRead], connection).unwrap();
+
+    let oauth_client = oauth_client.revoke(Some(user.id), connection).unwrap();
+    assert!(oauth_client.revoked_at.is_some());
+    assert!(OAuthClient::find_active_by_client_id(&oauth_client.client_id, connection).is_err());
+
+    // Consents granted to the client are revoked with it
+    let consent = OAuthConsent::find(consent.id, connection).unwrap();
+    assert!(!consent.is_active());
+}
@@ -0,0 +1,113 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn grant() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead, Scopes::OrderRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let consent = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::EventRead], connection).unwrap();
+    assert_eq!(consent.oauth_client_id, oauth_client.id);
+    assert_eq!(consent.user_id, user.id);
+    assert_eq!(consent.scopes().unwrap(), vec![Scopes::EventRead]);
+
+    // Granting additional scopes extends the existing consent
+    let consent2 = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::OrderRead], connection).unwrap();
+    assert_eq!(consent2.id, consent.id);
+    assert_eq!(consent2.scopes().unwrap(), vec![Scopes::EventRead, Scopes::OrderRead]);
+
+    let domain_events = DomainEvent::find(
+        Tables::OAuthConsents,
+        Some(consent.id),
+        Some(DomainEventTypes::OAuthConsentGranted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+
+    // Scopes not registered for the client cannot be granted
+    let result = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::OrgAdmin], connection);
+    assert!(result.is_err());
+    let result = OAuthConsent::grant(&oauth_client, user.id, vec![], connection);
+    assert!(result.is_err());
+}
+
+#[test]
+fn find_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let consent = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::EventRead], connection).unwrap();
+    OAuthConsent::grant(&oauth_client, user2.id, vec![Scopes::EventRead], connection).unwrap();
+
+    let consents = OAuthConsent::find_for_user(user.id, connection).unwrap();
+    assert_eq!(consents.len(), 1);
+    assert_eq!(consents[0].id, consent.id);
+    assert_eq!(consents[0].client_name, "Ticket widget".to_string());
+    assert_eq!(consents[0].organization_id, organization.id);
+
+    consent.revoke(Some(user.id), connection).unwrap();
+    assert!(OAuthConsent::find_for_user(user.id, connection).unwrap().is_empty());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let oauth_client = OAuthClient::create(
+        organization.id,
+        "Ticket widget".to_string(),
+        vec!["https://example.com/callback".to_string()],
+        vec![Scopes::EventRead],
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let consent = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::EventRead], connection).unwrap();
+    assert!(consent.is_active());
+
+    let consent = consent.revoke(Some(user.id), connection).unwrap();
+    assert!(!consent.is_active());
+    assert!(OAuthConsent::find_active(oauth_client.id, user.id, connection).is_err());
+
+    // Revoking again is a no-op
+    let revoked_at = consent.revoked_at;
+    let consent = consent.revoke(Some(user.id), connection).unwrap();
+    assert_eq!(consent.revoked_at, revoked_at);
+    let domain_events = DomainEvent::find(
+        Tables::OAuthConsents,
+        Some(consent.id),
+        Some(DomainEventTypes::OAuthConsentRevoked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // A fresh consent is created after revocation
+    let new_consent = OAuthConsent::grant(&oauth_client, user.id, vec![Scopes::EventRead], connection).unwrap();
+    assert_ne!(new_consent.id, consent.id);
+}
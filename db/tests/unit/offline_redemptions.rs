@@ -0,0 +1,209 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+
+#[test]
+fn signed_ticket_payload() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(user.id, connection).unwrap()[0];
+    let public_key = OfflineRedemptionKey::for_event(&event, connection).unwrap().public_key;
+
+    let payload = SignedTicketPayload::create(ticket, connection).unwrap();
+    assert_eq!(payload.ticket_instance_id, ticket.id);
+    assert_eq!(payload.event_id, event.id);
+    assert_eq!(Some(payload.redeem_key.clone()), ticket.redeem_key);
+    assert!(payload.verify(&public_key));
+
+    // Tampered payloads fail verification
+    let mut tampered = payload.clone();
+    tampered.redeem_key = "WRONGKEY".to_string();
+    assert!(!tampered.verify(&public_key));
+
+    // Payloads are signed per event rather than with the organization's wallet
+    let organization_wallet = Wallet::find_default_for_organization(event.organization_id, connection).unwrap();
+    assert!(!payload.verify(&organization_wallet.public_key));
+    let other_event = project
+        .create_event()
+        .with_organization(&event.organization(connection).unwrap())
+        .finish();
+    let other_public_key = OfflineRedemptionKey::for_event(&other_event, connection)
+        .unwrap()
+        .public_key;
+    assert_ne!(other_public_key, public_key);
+    assert!(!payload.verify(&other_public_key));
+}
+
+#[test]
+fn redemption_key_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+
+    // The key pair is generated once and reused for the event
+    let key = OfflineRedemptionKey::for_event(&event, connection).unwrap();
+    assert_eq!(key.event_id, event.id);
+    assert_eq!(OfflineRedemptionKey::for_event(&event, connection).unwrap(), key);
+    let redemption_key = EventRedemptionKey::find_or_create_for_event(event.id, connection).unwrap();
+    assert_eq!(redemption_key.public_key, key.public_key);
+    assert_eq!(redemption_key.hash_secret, key.hash_secret);
+    assert_eq!(key.hash_secret.len(), 64);
+
+    // Each event has its own hash secret
+    let other_event = project.create_event().finish();
+    let other_key = OfflineRedemptionKey::for_event(&other_event, connection).unwrap();
+    assert_ne!(other_key.hash_secret, key.hash_secret);
+}
+
+#[test]
+fn create_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = &tickets[0];
+    let ticket2 = &tickets[1];
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::GuestList,
+        connection,
+    )
+    .unwrap();
+
+    let bundle = OfflineRedemptionBundle::create_for_event(&event, connection).unwrap();
+    let key = OfflineRedemptionKey::for_event(&event, connection).unwrap();
+    assert_eq!(bundle.event_id, event.id);
+    assert_eq!(bundle.tickets.len(), 2);
+    assert!(bundle.verify(&key.public_key));
+
+    // Only the event's key verifies the bundle
+    let organization_wallet = Wallet::find_default_for_organization(event.organization_id, connection).unwrap();
+    assert!(!bundle.verify(&organization_wallet.public_key));
+
+    let entry = bundle
+        .tickets
+        .iter()
+        .find(|t| t.ticket_instance_id == ticket.id)
+        .unwrap();
+    assert!(entry.redeemed);
+    assert_eq!(
+        entry.redeem_key_hash,
+        OfflineRedemptionTicket::redeem_key_hash(&key.hash_secret, ticket.id, &ticket.redeem_key.clone().unwrap())
+    );
+    let entry2 = bundle
+        .tickets
+        .iter()
+        .find(|t| t.ticket_instance_id == ticket2.id)
+        .unwrap();
+    assert!(!entry2.redeemed);
+
+    // Tampered bundles fail verification
+    let mut tampered = bundle.clone();
+    tampered.tickets[0].redeemed = !tampered.tickets[0].redeemed;
+    assert!(!tampered.verify(&key.public_key));
+
+    // Tickets with pending transfers are excluded
+    TicketInstance::create_transfer(&user, &[ticket2.id], None, None, false, connection).unwrap();
+    let bundle = OfflineRedemptionBundle::create_for_event(&event, connection).unwrap();
+    assert_eq!(bundle.tickets.len(), 1);
+}
+
+#[test]
+fn sync() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let scanner = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let event_tickets: Vec<&TicketInstance> = tickets
+        .iter()
+        .filter(|t| t.ticket_type(connection).unwrap().event_id == event.id)
+        .collect();
+    let ticket = event_tickets[0];
+    let ticket2 = event_tickets[1];
+    let other_event_ticket = tickets
+        .iter()
+        .find(|t| t.ticket_type(connection).unwrap().event_id == other_event.id)
+        .unwrap();
+
+    let first_scan = NaiveDateTime::from_timestamp(Utc::now().timestamp() - 1800, 0);
+    let second_scan = NaiveDateTime::from_timestamp(Utc::now().timestamp() - 600, 0);
+    let redemptions = vec![
+        // Scanned again at another door, submitted out of order
+        OfflineRedemption {
+            ticket_instance_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            redeemed_at: second_scan,
+        },
+        OfflineRedemption {
+            ticket_instance_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            redeemed_at: first_scan,
+        },
+        OfflineRedemption {
+            ticket_instance_id: ticket2.id,
+            redeem_key: "WRONGKEY".to_string(),
+            redeemed_at: first_scan,
+        },
+        OfflineRedemption {
+            ticket_instance_id: other_event_ticket.id,
+            redeem_key: other_event_ticket.redeem_key.clone().unwrap(),
+            redeemed_at: first_scan,
+        },
+    ];
+
+    let results = OfflineRedemption::sync(event.id, redemptions, scanner.id, connection).unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].ticket_instance_id, ticket.id);
+    assert_eq!(results[0].status, OfflineRedemptionStatus::Redeemed);
+    assert_eq!(results[0].redeemed_at, first_scan);
+    assert_eq!(results[1].ticket_instance_id, ticket2.id);
+    assert_eq!(results[1].status, OfflineRedemptionStatus::Invalid);
+    assert_eq!(results[2].ticket_instance_id, other_event_ticket.id);
+    assert_eq!(results[2].status, OfflineRedemptionStatus::Invalid);
+    assert_eq!(results[3].ticket_instance_id, ticket.id);
+    assert_eq!(results[3].status, OfflineRedemptionStatus::DoubleScan);
+    assert_eq!(results[3].first_redeemed_at, Some(first_scan));
+    assert_eq!(results[3].first_redeemed_by_user_id, Some(scanner.id));
+
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(ticket.redeemed_at, Some(first_scan));
+    assert_eq!(ticket.check_in_source, Some(CheckInSource::Scanned));
+    let other_event_ticket = TicketInstance::find(other_event_ticket.id, connection).unwrap();
+    assert_eq!(other_event_ticket.status, TicketInstanceStatus::Purchased);
+}
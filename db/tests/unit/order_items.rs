@@ -24,6 +24,7 @@ fn find_fee_item() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -71,6 +72,7 @@ fn order() {
             ticket_type_id: ticket.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -113,11 +115,13 @@ fn code() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -168,6 +172,7 @@ fn confirm_code_valid() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -230,6 +235,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 6,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -263,6 +269,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -300,6 +307,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -330,6 +338,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code),
+            seat_ids: None,
         }],
         false,
         false,
@@ -356,6 +365,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code),
+            seat_ids: None,
         }],
         false,
         false,
@@ -400,6 +410,7 @@ fn calculate_quantity() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -198,6 +198,7 @@ fn main_event_id() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             true,
@@ -251,6 +252,7 @@ fn try_refresh_expired_cart() {
                 ticket_type_id: ticket_type.id,
                 quantity: 99,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -318,6 +320,7 @@ fn try_refresh_expired_cart() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -362,6 +365,7 @@ fn try_refresh_expired_cart() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -603,6 +607,7 @@ fn find_or_create_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -714,6 +719,7 @@ fn set_remove_expiry() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -762,6 +768,7 @@ fn update_fees() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -838,6 +845,7 @@ fn update_fees() {
             ticket_type_id: comp.ticket_type_id,
             quantity: 2,
             redemption_code: comp.redemption_code,
+            seat_ids: None,
         }],
         false,
         true,
@@ -881,6 +889,7 @@ fn refund_can_refund_previously_refunded_and_repurchased_tickets() {
                 ticket_type_id: ticket_types[0].id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -948,11 +957,13 @@ fn quantity_for_user_for_event() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -994,6 +1005,7 @@ fn quantity_for_user_for_event() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1067,11 +1079,13 @@ fn items() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1130,11 +1144,13 @@ fn tickets() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1200,11 +1216,13 @@ fn items_for_display() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1248,11 +1266,13 @@ fn find_item_by_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1307,11 +1327,13 @@ fn add_provider_payment() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1381,11 +1403,13 @@ fn add_credit_card_payment() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1502,6 +1526,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1530,6 +1555,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1559,6 +1585,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -1592,6 +1619,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -1624,6 +1652,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1717,11 +1746,13 @@ fn total_paid() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1793,11 +1824,13 @@ fn clear_invalid_items() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1866,6 +1899,7 @@ fn calculate_total() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2037,6 +2071,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2063,6 +2098,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 4,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2075,6 +2111,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -2105,6 +2142,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2126,6 +2164,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2147,6 +2186,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code.clone(),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2159,6 +2199,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         true,
@@ -2193,6 +2234,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code.clone(),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2216,6 +2258,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 3,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         true,
@@ -2261,6 +2304,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2283,6 +2327,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2305,6 +2350,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2317,6 +2363,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -2352,6 +2399,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2375,6 +2423,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 3,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -2416,6 +2465,7 @@ fn add_tickets() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2441,6 +2491,7 @@ fn add_tickets() {
             ticket_type_id: ticket.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2455,6 +2506,303 @@ fn add_tickets() {
     assert_eq!(order_item.calculate_quantity(connection), Ok(15));
 }
 
+#[test]
+fn add_tickets_in_different_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let organization2 = project.create_organization().with_currency("EUR").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization2)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert_eq!(cart.currency, None);
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency, Some(organization.currency.clone()));
+    let items = cart.items(connection).unwrap();
+    assert!(items.iter().all(|i| i.currency == organization.currency));
+
+    let result = cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type2.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error(
+            "Items in a different currency cannot be added to this order, please complete or clear the cart first",
+        )
+    );
+
+    // Replacing the cart contents switches its currency
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type2.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency, Some("EUR".to_string()));
+    let items = cart.items(connection).unwrap();
+    assert!(items.iter().all(|i| i.currency == "EUR"));
+}
+
+#[test]
+fn add_tickets_with_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project
+        .create_venue()
+        .with_country("US".to_string())
+        .with_state("California".to_string())
+        .finish();
+    let tax_rule = project
+        .create_tax_rule()
+        .with_country("US".to_string())
+        .with_state("California".to_string())
+        .with_ticket_tax_percent(10f32)
+        .with_fee_tax_percent(5f32)
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    let fee_tax_item = fee_item.find_tax_item(connection).unwrap().unwrap();
+    assert_eq!(tax_item.item_type, OrderItemTypes::Tax);
+    assert_eq!(tax_item.tax_rule_id, Some(tax_rule.id));
+    assert_eq!(tax_item.quantity, 2);
+    assert_eq!(
+        tax_item.unit_price_in_cents,
+        (order_item.unit_price_in_cents as f32 * 0.1).round() as i64
+    );
+    assert_eq!(tax_item.included_tax_in_cents, 0);
+    assert_eq!(
+        fee_tax_item.unit_price_in_cents,
+        (fee_item.unit_price_in_cents as f32 * 0.05).round() as i64
+    );
+    assert_eq!(tax_item.description(connection).unwrap(), tax_rule.name);
+
+    // Exclusive tax is charged on top of the ticket and fees
+    let items_total: i64 = items
+        .iter()
+        .filter(|i| i.item_type != OrderItemTypes::Tax)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        items_total + 2 * (tax_item.unit_price_in_cents + fee_tax_item.unit_price_in_cents)
+    );
+
+    // Recalculating fees does not duplicate tax items
+    cart.update_fees_and_discounts(connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    assert_eq!(items.iter().filter(|i| i.item_type == OrderItemTypes::Tax).count(), 2);
+
+    // Removing the tickets removes the tax on the ticket and its fees
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 0,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    assert!(items.iter().all(|i| i.item_type != OrderItemTypes::Tax));
+}
+
+#[test]
+fn add_tickets_with_inclusive_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().with_country("GB".to_string()).finish();
+    project
+        .create_tax_rule()
+        .with_country("GB".to_string())
+        .with_ticket_tax_percent(20f32)
+        .inclusive()
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    assert_eq!(tax_item.unit_price_in_cents, 0);
+    assert_eq!(
+        tax_item.included_tax_in_cents,
+        order_item.unit_price_in_cents - (order_item.unit_price_in_cents as f32 / 1.2).round() as i64
+    );
+
+    // Inclusive tax is part of the ticket price so does not change the total
+    let items_total: i64 = items.iter().map(|i| i.unit_price_in_cents * i.quantity).sum();
+    assert_eq!(cart.calculate_total(connection).unwrap(), items_total);
+}
+
+#[test]
+fn refund_with_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().with_country("US".to_string()).finish();
+    project
+        .create_tax_rule()
+        .with_country("US".to_string())
+        .with_ticket_tax_percent(10f32)
+        .with_fee_tax_percent(10f32)
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut order = project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    let fee_tax_item = fee_item.find_tax_item(connection).unwrap().unwrap();
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+
+    // Tax items are refunded with the item they apply to
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: tax_item.id,
+        ticket_instance_id: None,
+    }];
+    assert_eq!(
+        DatabaseError::business_process_error(
+            "Tax order items can not be refunded, they are refunded with the item they apply to",
+        ),
+        order.refund(&refund_items, user.id, None, false, connection)
+    );
+
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (refund, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(
+        amount,
+        order_item.unit_price_in_cents
+            + fee_item.unit_price_in_cents
+            + tax_item.unit_price_in_cents
+            + fee_tax_item.unit_price_in_cents
+    );
+
+    let refund_items = refund.items(connection).unwrap();
+    assert_eq!(refund_items.len(), 4);
+    let found_tax_item = refund_items.iter().find(|ri| ri.order_item_id == tax_item.id).unwrap();
+    assert_eq!(found_tax_item.quantity, 1);
+    assert_eq!(found_tax_item.amount, tax_item.unit_price_in_cents);
+    let found_fee_tax_item = refund_items
+        .iter()
+        .find(|ri| ri.order_item_id == fee_tax_item.id)
+        .unwrap();
+    assert_eq!(found_fee_tax_item.amount, fee_tax_item.unit_price_in_cents);
+
+    let tax_item = OrderItem::find(tax_item.id, connection).unwrap();
+    assert_eq!(tax_item.refunded_quantity, 1);
+}
+
 #[test]
 fn add_tickets_below_min_fee() {
     let project = TestProject::new();
@@ -2494,6 +2842,7 @@ fn add_tickets_below_min_fee() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2521,6 +2870,7 @@ fn add_tickets_below_min_fee() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2559,6 +2909,7 @@ fn events() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2592,6 +2943,7 @@ fn purchase_metadata() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2656,6 +3008,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2706,6 +3059,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -2734,6 +3088,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -2774,6 +3129,7 @@ fn partially_visible_order() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2834,6 +3190,7 @@ fn details() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3619,6 +3976,7 @@ fn organizations() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3643,6 +4001,7 @@ fn payments() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3702,6 +4061,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3728,6 +4088,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3741,6 +4102,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3773,6 +4135,7 @@ fn clear_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3822,6 +4185,7 @@ fn replace_tickets_for_box_office() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3850,6 +4214,7 @@ fn replace_tickets_for_box_office() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         true,
         true,
@@ -3891,6 +4256,7 @@ fn replace_tickets() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3916,6 +4282,7 @@ fn replace_tickets() {
             ticket_type_id: ticket.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -3958,6 +4325,7 @@ fn replace_tickets_with_code_pricing() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -3987,6 +4355,7 @@ fn replace_tickets_with_code_pricing() {
             ticket_type_id: ticket_type.id,
             quantity: 15,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -4018,6 +4387,7 @@ fn deleted_hold_not_applied() {
             ticket_type_id,
             quantity: 1,
             redemption_code,
+            seat_ids: None,
         }],
         false,
         true,
@@ -4041,6 +4411,7 @@ fn remove_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4067,6 +4438,7 @@ fn remove_tickets() {
                 ticket_type_id: ticket_type.id,
                 quantity: 6,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4087,6 +4459,7 @@ fn remove_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4111,6 +4484,7 @@ fn clear_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4144,6 +4518,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4160,6 +4535,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4177,6 +4553,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4214,6 +4591,7 @@ fn find_item() {
                 ticket_type_id: ticket_type.id,
                 quantity: 5,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4228,6 +4606,7 @@ fn find_item() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4309,6 +4688,7 @@ fn has_items() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4352,6 +4732,7 @@ fn calculate_cart_total() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4368,6 +4749,7 @@ fn calculate_cart_total() {
             ticket_type_id: ticket.id,
             quantity: 30,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4404,6 +4786,7 @@ fn add_external_payment() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4474,6 +4857,7 @@ fn add_external_payment_for_expired_code() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -4770,6 +5154,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4784,6 +5169,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type2.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4822,6 +5208,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type3.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4857,6 +5244,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type4.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4884,6 +5272,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type5.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -4907,6 +5296,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type5.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -5054,50 +5444,119 @@ fn for_display_with_invalid_items() {
 }
 
 #[test]
-fn validate_record() {
+fn multiple_events() {
     let project = TestProject::new();
     let connection = project.get_connection();
     let user = project.create_user().finish();
-    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
-    let event2 = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let organization = project
+        .create_organization()
+        .with_cc_fee(5f32)
+        .with_event_fee()
+        .with_fees()
+        .finish();
+    let organization2 = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization2)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
     let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
     let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
     let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
-    let result = cart.update_quantities(
+    cart.update_quantities(
         user.id,
         &[
             UpdateOrderItem {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
-                quantity: 1,
+                quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
         false,
         connection,
+    )
+    .unwrap();
+
+    // Each event has its own event fee, credit card fees only apply to the organization charging them
+    let items = cart.items(connection).unwrap();
+    let event_fees: Vec<&OrderItem> = items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::EventFees)
+        .collect();
+    assert_eq!(event_fees.len(), 2);
+    assert!(event_fees.iter().any(|i| i.event_id == Some(event.id)));
+    assert!(event_fees.iter().any(|i| i.event_id == Some(event2.id)));
+    let cc_fees: Vec<&OrderItem> = items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::CreditCardFees)
+        .collect();
+    assert_eq!(cc_fees.len(), 1);
+    assert_eq!(cc_fees[0].event_id, Some(event.id));
+    let event_total: i64 = items
+        .iter()
+        .filter(|i| i.event_id == Some(event.id) && i.item_type != OrderItemTypes::CreditCardFees)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(
+        cc_fees[0].unit_price_in_cents,
+        (event_total as f32 * 0.05).round() as i64
     );
-    match result {
-        Ok(_) => {
-            panic!("Expected validation error");
-        }
-        Err(error) => match &error.error_code {
-            ValidationError { errors } => {
-                assert!(errors.contains_key("event_id"));
-                assert_eq!(errors["event_id"].len(), 1);
-                assert_eq!(errors["event_id"][0].code, "cart_event_limit_reached");
-                assert_eq!(
-                    &errors["event_id"][0].message.clone().unwrap().into_owned(),
-                    "Cart limited to one event for purchasing"
-                );
-            }
-            _ => panic!("Expected validation error"),
-        },
+
+    let mut organizations = vec![organization.id, organization2.id];
+    organizations.sort();
+    let mut order_organizations: Vec<Uuid> = cart.organizations(connection).unwrap().iter().map(|o| o.id).collect();
+    order_organizations.sort();
+    assert_eq!(order_organizations, organizations);
+
+    // Display order groups the items by event
+    let display_order = cart.for_display(None, user.id, connection).unwrap();
+    assert_eq!(display_order.events.len(), 2);
+    for display_event in &display_order.events {
+        let event_items: Vec<&DisplayOrderItem> = display_order
+            .items
+            .iter()
+            .filter(|i| i.event_id == Some(display_event.id))
+            .collect();
+        assert_eq!(display_event.item_ids.len(), event_items.len());
+        assert_eq!(
+            display_event.total_in_cents,
+            event_items
+                .iter()
+                .map(|i| i.unit_price_in_cents * i.quantity)
+                .sum::<i64>()
+        );
     }
+    assert_eq!(
+        display_order.events.iter().map(|e| e.total_in_cents).sum::<i64>(),
+        display_order.total_in_cents
+    );
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert_eq!(TicketInstance::find_for_user(user.id, connection).unwrap().len(), 3);
 }
 
 #[test]
@@ -5129,6 +5588,7 @@ fn for_display_with_organization_id_and_event_id_filters() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5221,6 +5681,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket1.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5245,6 +5706,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket2.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -5268,6 +5730,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket3.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -5922,3 +6385,110 @@ pub fn additional_fee() {
 
     assert_eq!(fees_item.unit_price_in_cents, 10050);
 }
+
+#[test]
+pub fn record_chargeback() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let other_organization = project.create_organization().finish();
+    let other_settlement = project
+        .create_settlement()
+        .with_organization(&other_organization)
+        .finish();
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let expected_amount: i64 = order
+        .items(connection)
+        .unwrap()
+        .iter()
+        .map(|i| match i.item_type {
+            OrderItemTypes::Tickets => i.unit_price_in_cents * i.quantity,
+            OrderItemTypes::PerUnitFees | OrderItemTypes::EventFees => i.client_fee_in_cents * i.quantity,
+            _ => 0,
+        })
+        .sum();
+
+    // Without a pending settlement the chargeback is added to the next settlement
+    let adjustments = order
+        .record_chargeback("Chargeback".to_string(), false, None, connection)
+        .unwrap();
+    assert!(adjustments.is_empty());
+    assert!(order.disputed_at.is_some());
+    let order = Order::find(order.id, connection).unwrap();
+    assert!(order.disputed_at.is_some());
+    let pending_adjustments = PendingSettlementAdjustment::find_for_order(order.id, connection).unwrap();
+    assert_eq!(pending_adjustments.len(), 1);
+    assert_eq!(pending_adjustments[0].organization_id, organization.id);
+    assert_eq!(pending_adjustments[0].amount_in_cents, expected_amount);
+    assert_eq!(pending_adjustments[0].currency, event.currency);
+    assert_eq!(pending_adjustments[0].settlement_adjustment_id, None);
+    let disputed_order_id = order.id;
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::OrderDisputed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let applied_adjustments = settlement.adjustments(connection).unwrap();
+    assert_eq!(applied_adjustments.len(), 1);
+    assert_eq!(applied_adjustments[0].order_id, Some(disputed_order_id));
+    assert_eq!(applied_adjustments[0].amount_in_cents, expected_amount);
+    assert_eq!(
+        applied_adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    let pending_adjustments = PendingSettlementAdjustment::find_for_order(disputed_order_id, connection).unwrap();
+    assert_eq!(
+        pending_adjustments[0].settlement_adjustment_id,
+        Some(applied_adjustments[0].id)
+    );
+
+    let adjustments = order
+        .record_chargeback("Chargeback".to_string(), true, None, connection)
+        .unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(adjustments[0].settlement_id, settlement.id);
+    assert_ne!(adjustments[0].settlement_id, other_settlement.id);
+    assert_eq!(adjustments[0].order_id, Some(order.id));
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustments[0].amount_in_cents, expected_amount);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+
+    // Repeated notifications don't create more adjustments
+    let mut order = Order::find(order.id, connection).unwrap();
+    let repeated_adjustments = order
+        .record_chargeback("Chargeback".to_string(), true, None, connection)
+        .unwrap();
+    assert_eq!(repeated_adjustments, adjustments);
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 2);
+}
@@ -450,6 +450,7 @@ fn find_by_order_item_ids() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -463,6 +464,7 @@ fn find_by_order_item_ids() {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -521,6 +523,7 @@ fn has_fan() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1017,6 +1020,37 @@ pub fn get_roles_for_user() {
     assert!(organization.get_roles_for_user(&user4, connection).unwrap().is_empty());
 }
 
+#[test]
+pub fn requires_two_factor_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let user3 = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .with_member(&user2, Roles::OrgMember)
+        .finish();
+    assert!(!organization.requires_two_factor_for_user(&user, connection).unwrap());
+
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                two_factor_required_roles: Some(vec![Roles::OrgOwner]),
+                ..Default::default()
+            },
+            None,
+            &"".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization.two_factor_required_roles, vec![Roles::OrgOwner]);
+    assert!(organization.requires_two_factor_for_user(&user, connection).unwrap());
+    assert!(!organization.requires_two_factor_for_user(&user2, connection).unwrap());
+    assert!(!organization.requires_two_factor_for_user(&user3, connection).unwrap());
+}
+
 #[test]
 pub fn get_scopes_for_user() {
     let project = TestProject::new();
@@ -1089,6 +1123,7 @@ pub fn get_scopes_for_user() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "settlement:read",
@@ -1152,6 +1187,7 @@ pub fn get_scopes_for_user() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "settlement:read",
@@ -1380,6 +1416,7 @@ pub fn get_scopes_for_user() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "settlement:read",
@@ -2260,6 +2297,7 @@ fn credit_card_fees() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2288,6 +2326,7 @@ fn credit_card_fees() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -83,6 +83,42 @@ fn limit() {
     assert_eq!(paging_parameters.limit(), 2);
 }
 
+#[test]
+fn export_format() {
+    let mut paging_parameters = PagingParameters::default();
+    paging_parameters.page = Some(2);
+    paging_parameters.limit = Some(10);
+    assert_eq!(paging_parameters.export_format(), None);
+
+    paging_parameters.tags.insert("format".to_string(), json!("pdf"));
+    assert_eq!(paging_parameters.export_format(), None);
+    assert_eq!(paging_parameters.page(), 2);
+    assert_eq!(paging_parameters.limit(), 10);
+
+    // Exports are not paged, up to the export row limit
+    paging_parameters.tags.insert("format".to_string(), json!("xlsx"));
+    assert_eq!(paging_parameters.export_format(), Some(ReportFormats::Xlsx));
+    assert_eq!(paging_parameters.page(), 0);
+    assert_eq!(paging_parameters.limit(), MAXIMUM_EXPORT_ROWS);
+    let paging: Paging = paging_parameters.into();
+    assert_eq!(paging.page, 0);
+    assert_eq!(paging.limit, MAXIMUM_EXPORT_ROWS);
+}
+
+#[test]
+fn paging_export_format() {
+    let mut paging = Paging::new(2, 10);
+    assert_eq!(paging.export_format(), None);
+    assert_eq!(paging.page(), 2);
+    assert_eq!(paging.limit(), 10);
+
+    // Paging built from other query parameters is lifted when the format tag is set
+    paging.tags.insert("format".to_string(), json!("csv"));
+    assert_eq!(paging.export_format(), Some(ReportFormats::Csv));
+    assert_eq!(paging.page(), 0);
+    assert_eq!(paging.limit(), MAXIMUM_EXPORT_ROWS);
+}
+
 #[test]
 fn dir() {
     let mut paging_parameters = PagingParameters::default();
@@ -47,6 +47,36 @@ fn find_by_order() {
     assert_eq!(payment, found_payment);
 }
 
+#[test]
+fn find_by_external_reference() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+    let found_payment = Payment::find_by_external_reference(PaymentProviders::External, "Test", connection).unwrap();
+    assert_eq!(payment, found_payment);
+
+    // Refunds share the external reference of the payment
+    let refund = project.create_refund().finish();
+    payment.log_refund(user.id, &refund, 100, None, connection).unwrap();
+    let found_payment = Payment::find_by_external_reference(PaymentProviders::External, "Test", connection).unwrap();
+    assert_eq!(payment, found_payment);
+
+    assert!(Payment::find_by_external_reference(PaymentProviders::Stripe, "Test", connection).is_err());
+}
+
 #[test]
 fn find() {
     let project = TestProject::new();
@@ -184,6 +214,62 @@ fn mark_pending_ipn() {
     assert!(payment.mark_pending_ipn(Some(user.id), connection).is_ok(), true)
 }
 
+#[test]
+fn mark_pending_confirmation() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .with_status(PaymentStatus::Authorized)
+        .finish();
+    payment.mark_pending_confirmation(Some(user.id), connection).unwrap();
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::PendingConfirmation);
+    let order = Order::find(payment.order_id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::PendingPayment);
+}
+
+#[test]
+fn mark_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .with_status(PaymentStatus::Authorized)
+        .finish();
+    assert!(payment.mark_failed(json!(null), None, connection).is_err());
+
+    payment.mark_pending_confirmation(Some(user.id), connection).unwrap();
+    let payment = Payment::find(payment.id, connection).unwrap();
+    payment.mark_failed(json!(null), None, connection).unwrap();
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Cancelled);
+    let order = Order::find(payment.order_id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+}
+
 #[test]
 fn mark_cancelled() {
     let project = TestProject::new();
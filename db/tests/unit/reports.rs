@@ -539,6 +539,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -571,6 +572,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -593,6 +595,7 @@ fn ticket_count_report() {
             online_face_sales_in_cents: 200,
             comp_sale_count: 2,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -624,6 +627,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -657,6 +661,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 24,
             client_online_fees_in_cents: 36,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -688,6 +693,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -720,6 +726,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -735,6 +742,7 @@ fn ticket_count_report() {
             per_order_company_online_fees: 500,
             per_order_client_online_fees: 750,
             per_order_total_fees_in_cents: 1250,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -845,6 +853,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -877,6 +886,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -899,6 +909,7 @@ fn ticket_count_report() {
             online_face_sales_in_cents: 200,
             comp_sale_count: 2,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -930,6 +941,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -963,6 +975,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 24,
             client_online_fees_in_cents: 36,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -994,6 +1007,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -1026,6 +1040,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -1051,6 +1066,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 16,
             client_online_fees_in_cents: 24,
             user_count: 1,
+            currency: Some(event2.currency.clone()),
             ..Default::default()
         })
     );
@@ -1066,6 +1082,7 @@ fn ticket_count_report() {
             per_order_company_online_fees: 500,
             per_order_client_online_fees: 750,
             per_order_total_fees_in_cents: 1250,
+            currency: Some(event.currency.clone()),
             ..Default::default()
         })
     );
@@ -1081,6 +1098,7 @@ fn ticket_count_report() {
             per_order_company_online_fees: 100,
             per_order_client_online_fees: 150,
             per_order_total_fees_in_cents: 250,
+            currency: Some(event2.currency.clone()),
             ..Default::default()
         })
     );
@@ -1579,6 +1597,7 @@ fn box_office_sales_summary_report() {
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
                         total_sales_in_cents: 300,
+                        currency: organization.currency.clone(),
                     },
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event2".to_string()),
@@ -1587,21 +1606,25 @@ fn box_office_sales_summary_report() {
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
                         total_sales_in_cents: 300,
+                        currency: organization.currency.clone(),
                     },
                 ],
                 payments: vec![
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::Cash,
+                        currency: organization.currency.clone(),
                         quantity: 1,
                         total_sales_in_cents: 150,
                     },
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::CreditCard,
+                        currency: organization.currency.clone(),
                         quantity: 1,
                         total_sales_in_cents: 150,
                     },
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::Voucher,
+                        currency: organization.currency.clone(),
                         quantity: 2,
                         total_sales_in_cents: 300,
                     },
@@ -1618,6 +1641,7 @@ fn box_office_sales_summary_report() {
                         face_value_in_cents: 140,
                         revenue_share_value_in_cents: 0,
                         total_sales_in_cents: 140,
+                        currency: organization.currency.clone(),
                     },
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
@@ -1626,10 +1650,12 @@ fn box_office_sales_summary_report() {
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
                         total_sales_in_cents: 300,
+                        currency: organization.currency.clone(),
                     },
                 ],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::Cash,
+                    currency: organization.currency.clone(),
                     quantity: 3,
                     total_sales_in_cents: 440,
                 }],
@@ -1638,16 +1664,19 @@ fn box_office_sales_summary_report() {
         payments: vec![
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::Cash,
+                currency: organization.currency.clone(),
                 quantity: 4,
                 total_sales_in_cents: 590,
             },
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::CreditCard,
+                currency: organization.currency.clone(),
                 quantity: 1,
                 total_sales_in_cents: 150,
             },
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::Voucher,
+                currency: organization.currency.clone(),
                 quantity: 2,
                 total_sales_in_cents: 300,
             },
@@ -1685,6 +1714,12 @@ fn build_transaction_report_row(
         event_fee_client_in_cents: organization.client_event_fee_in_cents,
         event_fee_client_in_cents_total: organization.client_event_fee_in_cents,
         fee_range_id: Some(fee_schedule_range.id),
+        item_type: OrderItemTypes::Tickets,
+        resale_client_fee_in_cents: 0,
+        currency: event.currency.clone(),
+        tax_in_cents: 0,
+        tax_in_cents_total: 0,
+        tax_inclusive: false,
         order_type: OrderTypes::Cart,
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
         payment_provider: Some(PaymentProviders::Stripe.to_string()),
@@ -1786,11 +1821,13 @@ fn promo_code_report() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         true,
@@ -1820,11 +1857,13 @@ fn promo_code_report() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
         ],
         true,
@@ -1854,6 +1893,7 @@ fn promo_code_report() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code2.redemption_code.clone()),
+            seat_ids: None,
         }],
         true,
         false,
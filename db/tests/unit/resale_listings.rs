@@ -0,0 +1,381 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::ticket_instances;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use diesel;
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+fn enable_resale(organization: &Organization, connection: &PgConnection) -> Organization {
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_price_percent: Some(Some(150)),
+                resale_company_fee_percent: Some(5.0),
+                resale_client_fee_percent: Some(5.0),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+
+    // Organization has not enabled resale
+    assert!(ResaleListing::create(ticket.id, seller.id, 100)
+        .commit(connection)
+        .is_err());
+
+    let organization = enable_resale(&organization, connection);
+    let max_price_in_cents = ResaleListing::max_price_in_cents(ticket, &organization, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        max_price_in_cents,
+        ResaleListing::face_value_in_cents(ticket, connection).unwrap() * 150 / 100
+    );
+
+    // Price above the cap
+    let result = ResaleListing::create(ticket.id, seller.id, max_price_in_cents + 1).commit(connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("price_in_cents"));
+                assert_eq!(errors["price_in_cents"][0].code, "resale_price_cap_exceeded");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Only the owner can list the ticket
+    let other_user = project.create_user().finish();
+    assert!(ResaleListing::create(ticket.id, other_user.id, max_price_in_cents)
+        .commit(connection)
+        .is_err());
+
+    let listing = ResaleListing::create(ticket.id, seller.id, max_price_in_cents)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Active);
+    let domain_events = DomainEvent::find(
+        Tables::ResaleListings,
+        Some(listing.id),
+        Some(DomainEventTypes::ResaleListingCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Ticket can only be listed once
+    assert!(ResaleListing::create(ticket.id, seller.id, max_price_in_cents)
+        .commit(connection)
+        .is_err());
+
+    // Listed tickets cannot be transferred
+    assert!(TicketInstance::create_transfer(&seller, &[ticket.id], None, None, false, connection).is_err());
+}
+
+#[test]
+fn purchase() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let organization = enable_resale(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        ResaleListing::find_active_for_event(event.id, connection).unwrap(),
+        vec![listing.clone()]
+    );
+
+    // Seller cannot purchase their own listing
+    let mut seller_cart = Order::find_or_create_cart(&seller, connection).unwrap();
+    assert!(seller_cart.add_resale_listing(seller.id, &listing, connection).is_err());
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    let order_item = cart.add_resale_listing(buyer.id, &listing, connection).unwrap();
+    assert_eq!(order_item.item_type, OrderItemTypes::ResaleTickets);
+    assert_eq!(order_item.unit_price_in_cents, 200);
+    assert_eq!(order_item.company_fee_in_cents, 10);
+    assert_eq!(order_item.client_fee_in_cents, 10);
+    let display_cart = cart.for_display(None, buyer.id, connection).unwrap();
+    assert_eq!(display_cart.items.len(), 1);
+    assert_eq!(
+        display_cart.items[0].ticket_type_id,
+        Some(ticket.ticket_type(connection).unwrap().id)
+    );
+    assert!(display_cart.items[0].description.ends_with("(Resale)"));
+
+    // Reserved listings cannot be reserved by another buyer or cancelled
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert!(listing.is_reserved());
+    assert_eq!(listing.order_item_id, Some(order_item.id));
+    assert!(listing.cancel(Some(seller.id), connection).is_err());
+    let buyer2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&buyer2, connection).unwrap();
+    assert!(cart2.add_resale_listing(buyer2.id, &listing, connection).is_err());
+
+    // Buyer pays the listing price only
+    let total = cart.calculate_total(connection).unwrap();
+    assert_eq!(total, 200);
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Sold);
+    assert_eq!(listing.buyer_user_id, Some(buyer.id));
+    assert_eq!(listing.seller_payout_in_cents, Some(180));
+    assert!(listing.sold_at.is_some());
+
+    // The seller's share is owed to them until it is paid out
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(payout.seller_user_id, seller.id);
+    assert_eq!(payout.amount_in_cents, 180);
+    assert_eq!(payout.currency, event.currency);
+    assert_eq!(payout.status, ResalePayoutStatus::Pending);
+
+    let transferred_ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(transferred_ticket.owner(connection).unwrap().id, buyer.id);
+    assert_ne!(transferred_ticket.redeem_key, ticket.redeem_key);
+
+    let domain_events = DomainEvent::find(
+        Tables::ResaleListings,
+        Some(listing.id),
+        Some(DomainEventTypes::ResaleListingSold),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn purchase_after_redeem_attempt() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let organization = enable_resale(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, &listing, connection).unwrap();
+
+    // Seller cannot redeem the ticket while it is listed
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        seller.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketListedForResale);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Sold);
+    let transferred_ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(transferred_ticket.owner(connection).unwrap().id, buyer.id);
+
+    // Buyer can redeem the ticket with the new redeem key
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        transferred_ticket.redeem_key.unwrap(),
+        buyer.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketRedeemSuccess);
+}
+
+#[test]
+fn purchase_fails_for_redeemed_ticket() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let organization = enable_resale(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, &listing, connection).unwrap();
+
+    // Ticket was redeemed before the buyer's payment completed
+    diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(ticket.id)))
+        .set(ticket_instances::status.eq(TicketInstanceStatus::Redeemed))
+        .execute(connection)
+        .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    assert!(cart
+        .add_external_payment(
+            Some("test".to_string()),
+            ExternalPaymentType::CreditCard,
+            buyer.id,
+            total,
+            connection,
+        )
+        .is_err());
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Active);
+}
+
+#[test]
+fn clear_cart_releases_listing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let organization = enable_resale(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, &listing, connection).unwrap();
+    cart.clear_cart(buyer.id, connection).unwrap();
+
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert!(!listing.is_reserved());
+    assert_eq!(listing.order_item_id, None);
+    assert_eq!(cart.items(connection).unwrap().len(), 0);
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let organization = enable_resale(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .unwrap();
+
+    let listing = listing.cancel(Some(seller.id), connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Cancelled);
+    assert!(ResaleListing::find_active_for_event(event.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(listing.cancel(Some(seller.id), connection).is_err());
+
+    // Ticket can be listed again once the previous listing is cancelled
+    assert!(ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .is_ok());
+}
@@ -0,0 +1,99 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+fn sell_resale_listing(project: &TestProject) -> ResaleListing {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_price_percent: Some(Some(150)),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, seller.id, 200)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, &listing, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    ResaleListing::find(listing.id, connection).unwrap()
+}
+
+#[test]
+fn find_pending() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let listing = sell_resale_listing(&project);
+    let listing2 = sell_resale_listing(&project);
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection)
+        .unwrap()
+        .unwrap();
+    let payout2 = ResalePayout::find_for_resale_listing(listing2.id, connection)
+        .unwrap()
+        .unwrap();
+
+    let pending = ResalePayout::find_pending(0, 100, connection).unwrap();
+    assert_eq!(pending.data, vec![payout.clone(), payout2.clone()]);
+    assert_eq!(pending.paging.total, 2);
+
+    payout.mark_paid(None, connection).unwrap();
+    let pending = ResalePayout::find_pending(0, 100, connection).unwrap();
+    assert_eq!(pending.data, vec![payout2]);
+}
+
+#[test]
+fn mark_paid() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let listing = sell_resale_listing(&project);
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(payout.amount_in_cents, listing.seller_payout_in_cents.unwrap());
+
+    let payout = payout.mark_paid(Some(admin.id), connection).unwrap();
+    assert_eq!(payout.status, ResalePayoutStatus::Paid);
+    assert!(payout.paid_at.is_some());
+    assert_eq!(payout.paid_by_user_id, Some(admin.id));
+
+    let domain_events = DomainEvent::find(
+        Tables::ResalePayouts,
+        Some(payout.id),
+        Some(DomainEventTypes::ResalePayoutPaid),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Payouts are only paid once
+    assert!(payout.mark_paid(Some(admin.id), connection).is_err());
+}
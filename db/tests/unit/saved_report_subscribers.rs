@@ -0,0 +1,96 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let saved_report = project.create_saved_report().finish();
+
+    let subscriber = SavedReportSubscriber::create(saved_report.id, "Subscriber@tari.com".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(subscriber.saved_report_id, saved_report.id);
+    assert_eq!(subscriber.email, "subscriber@tari.com");
+    let domain_events = DomainEvent::find(
+        Tables::SavedReports,
+        Some(saved_report.id),
+        Some(DomainEventTypes::SavedReportSubscriberCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Subscribers are unique per saved report
+    let result = SavedReportSubscriber::create(saved_report.id, "subscriber@tari.com".to_string())
+        .commit(Some(user.id), connection);
+    assert!(result.is_err());
+
+    let result =
+        SavedReportSubscriber::create(saved_report.id, "not-an-email".to_string()).commit(Some(user.id), connection);
+    assert!(result.is_err());
+}
+
+#[test]
+fn find_all() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let saved_report = project.create_saved_report().finish();
+    let subscriber = SavedReportSubscriber::create(saved_report.id, "b@tari.com".to_string())
+        .commit(None, connection)
+        .unwrap();
+    let subscriber2 = SavedReportSubscriber::create(saved_report.id, "a@tari.com".to_string())
+        .commit(None, connection)
+        .unwrap();
+    let other_saved_report = project.create_saved_report().finish();
+    SavedReportSubscriber::create(other_saved_report.id, "a@tari.com".to_string())
+        .commit(None, connection)
+        .unwrap();
+
+    assert_eq!(
+        SavedReportSubscriber::find_all(saved_report.id, connection).unwrap(),
+        vec![subscriber2, subscriber]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let saved_report = project.create_saved_report().finish();
+    let subscriber = SavedReportSubscriber::create(saved_report.id, "subscriber@tari.com".to_string())
+        .commit(None, connection)
+        .unwrap();
+
+    subscriber.destroy(None, connection).unwrap();
+    assert!(SavedReportSubscriber::find(subscriber.id, connection).is_err());
+    let domain_events = DomainEvent::find(
+        Tables::SavedReports,
+        Some(saved_report.id),
+        Some(DomainEventTypes::SavedReportSubscriberDeleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn mark_sent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let saved_report = project.create_saved_report().finish();
+    let subscriber = SavedReportSubscriber::create(saved_report.id, "subscriber@tari.com".to_string())
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(subscriber.last_sent_at, None);
+    let run_at = Utc::now().naive_utc() - Duration::minutes(1);
+    assert!(!subscriber.was_sent_run(run_at));
+
+    let subscriber = subscriber.mark_sent(connection).unwrap();
+    assert!(subscriber.last_sent_at.is_some());
+    assert!(subscriber.was_sent_run(run_at));
+    assert!(!subscriber.was_sent_run(Utc::now().naive_utc() + Duration::days(1)));
+}
@@ -0,0 +1,301 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::schema::orders;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+
+fn pending_runs(saved_report: &SavedReport, connection: &PgConnection) -> Vec<DomainAction> {
+    DomainAction::find_by_resource(
+        Some(Tables::SavedReports),
+        Some(saved_report.id),
+        DomainActionTypes::SendSavedReport,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let saved_report = SavedReport::create(
+        organization.id,
+        "Weekly sales".to_string(),
+        SavedReportTypes::TransactionDetails,
+        SavedReportFilters::default(),
+        vec!["event_name".to_string(), "gross".to_string()],
+        ReportFormats::Xlsx,
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(saved_report.name, "Weekly sales");
+    assert_eq!(saved_report.format, ReportFormats::Xlsx);
+    assert_eq!(saved_report.next_run_at, None);
+    assert!(pending_runs(&saved_report, connection).is_empty());
+    let domain_events = DomainEvent::find(
+        Tables::SavedReports,
+        Some(saved_report.id),
+        Some(DomainEventTypes::SavedReportCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let other_event = project.create_event().finish();
+    let result = SavedReport::create(
+        organization.id,
+        "".to_string(),
+        SavedReportTypes::TicketCounts,
+        SavedReportFilters {
+            event_id: Some(other_event.id),
+            ..Default::default()
+        },
+        vec!["gross".to_string()],
+        ReportFormats::Csv,
+        Some("every monday".to_string()),
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+                assert!(errors.contains_key("filters"));
+                assert!(errors.contains_key("columns"));
+                assert!(errors.contains_key("schedule"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn commit_with_schedule() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let saved_report = project.create_saved_report().with_schedule("0 6 * * *").finish();
+
+    let next_run_at = saved_report.next_run_at.unwrap();
+    assert!(next_run_at > Utc::now().naive_utc());
+    assert!(next_run_at <= Utc::now().naive_utc() + Duration::days(1));
+    let runs = pending_runs(&saved_report, connection);
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].scheduled_at, next_run_at);
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let saved_report = project.create_saved_report().with_organization(&organization).finish();
+    let saved_report2 = project.create_saved_report().with_organization(&organization).finish();
+    project.create_saved_report().finish();
+
+    let mut expected = vec![saved_report.clone(), saved_report2];
+    expected.sort_by_key(|s| s.name.clone());
+    assert_eq!(
+        SavedReport::find_for_organization(organization.id, connection).unwrap(),
+        expected
+    );
+
+    saved_report.destroy(None, connection).unwrap();
+    assert_eq!(
+        SavedReport::find_for_organization(organization.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let saved_report = project.create_saved_report().with_schedule("0 6 * * *").finish();
+    let original_run = pending_runs(&saved_report, connection).remove(0);
+
+    let attributes = SavedReportEditableAttributes {
+        name: Some("Monthly sales".to_string()),
+        columns: Some(vec!["email".to_string()]),
+        schedule: Some(Some("0 6 1 * *".to_string())),
+        ..Default::default()
+    };
+    let saved_report = saved_report.update(attributes, Some(user.id), connection).unwrap();
+    assert_eq!(saved_report.name, "Monthly sales");
+    assert_eq!(saved_report.columns, vec!["email".to_string()]);
+    assert_eq!(saved_report.next_run_at.unwrap().day(), 1);
+    let runs = pending_runs(&saved_report, connection);
+    assert_eq!(runs.len(), 1);
+    assert_ne!(runs[0].id, original_run.id);
+    assert_eq!(
+        DomainAction::find(original_run.id, connection).unwrap().status,
+        DomainActionStatus::Cancelled
+    );
+
+    // Removing the schedule stops future runs
+    let attributes = SavedReportEditableAttributes {
+        schedule: Some(None),
+        ..Default::default()
+    };
+    let saved_report = saved_report.update(attributes, Some(user.id), connection).unwrap();
+    assert_eq!(saved_report.schedule, None);
+    assert_eq!(saved_report.next_run_at, None);
+    assert!(pending_runs(&saved_report, connection).is_empty());
+
+    let attributes = SavedReportEditableAttributes {
+        columns: Some(vec!["not_a_column".to_string()]),
+        ..Default::default()
+    };
+    assert!(saved_report.update(attributes, Some(user.id), connection).is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let saved_report = project.create_saved_report().with_schedule("0 6 * * *").finish();
+    assert_eq!(pending_runs(&saved_report, connection).len(), 1);
+
+    saved_report.destroy(Some(user.id), connection).unwrap();
+    assert!(SavedReport::find(saved_report.id, connection).is_err());
+    assert!(pending_runs(&saved_report, connection).is_empty());
+    let domain_events = DomainEvent::find(
+        Tables::SavedReports,
+        Some(saved_report.id),
+        Some(DomainEventTypes::SavedReportDeleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn mark_sent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let saved_report = project.create_saved_report().with_schedule("0 6 * * *").finish();
+    let original_run = pending_runs(&saved_report, connection).remove(0);
+
+    let saved_report = saved_report
+        .mark_sent(
+            original_run.id,
+            vec!["subscriber@tari.com".to_string()],
+            vec!["failed@tari.com".to_string()],
+            connection,
+        )
+        .unwrap();
+    assert!(saved_report.last_run_at.is_some());
+    // The run being marked as sent is left for the executor to complete
+    let runs = pending_runs(&saved_report, connection);
+    assert_eq!(runs.len(), 2);
+    assert!(runs.iter().any(|run| run.id == original_run.id));
+    assert_eq!(
+        DomainAction::find(original_run.id, connection).unwrap().status,
+        DomainActionStatus::Pending
+    );
+
+    // Other pending runs are replaced by the next run
+    let next_run = runs.into_iter().find(|run| run.id != original_run.id).unwrap();
+    saved_report
+        .mark_sent(original_run.id, vec![], vec![], connection)
+        .unwrap();
+    assert_eq!(
+        DomainAction::find(next_run.id, connection).unwrap().status,
+        DomainActionStatus::Cancelled
+    );
+    assert_eq!(pending_runs(&saved_report, connection).len(), 2);
+    let domain_events = DomainEvent::find(
+        Tables::SavedReports,
+        Some(saved_report.id),
+        Some(DomainEventTypes::SavedReportSent),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+    assert!(domain_events.iter().any(|domain_event| domain_event.event_data
+        == Some(json!({
+            "domain_action_id": original_run.id,
+            "recipients": ["subscriber@tari.com"],
+            "failed_recipients": ["failed@tari.com"]
+        }))));
+}
+
+#[test]
+fn run() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_name("Event1".to_string())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().with_email("recent@tari.com".to_string()).finish();
+    let user2 = project.create_user().with_email("old@tari.com".to_string()).finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user2)
+        .is_paid()
+        .finish();
+    diesel::update(orders::table.filter(orders::id.eq(order.id)))
+        .set(orders::paid_at.eq(Utc::now().naive_utc() - Duration::days(10)))
+        .execute(connection)
+        .unwrap();
+
+    let saved_report = project
+        .create_saved_report()
+        .with_organization(&organization)
+        .with_columns(vec!["email", "event_name"])
+        .finish();
+    let table = saved_report.run(connection).unwrap();
+    assert_eq!(table.columns, vec!["email".to_string(), "event_name".to_string()]);
+    assert!(table.rows.contains(&vec![json!("recent@tari.com"), json!("Event1")]));
+    assert!(table.rows.contains(&vec![json!("old@tari.com"), json!("Event1")]));
+
+    // Transactions are limited to the period before the report runs
+    let saved_report = project
+        .create_saved_report()
+        .with_organization(&organization)
+        .with_columns(vec!["email"])
+        .with_filters(SavedReportFilters {
+            period_in_days: Some(7),
+            ..Default::default()
+        })
+        .finish();
+    let table = saved_report.run(connection).unwrap();
+    assert!(table.rows.contains(&vec![json!("recent@tari.com")]));
+    assert!(!table.rows.contains(&vec![json!("old@tari.com")]));
+
+    // All columns are included when none are selected
+    let saved_report = project
+        .create_saved_report()
+        .with_organization(&organization)
+        .with_report_type(SavedReportTypes::TicketCounts)
+        .finish();
+    let table = saved_report.run(connection).unwrap();
+    assert_eq!(table.columns, SavedReportTypes::TicketCounts.available_columns());
+    assert!(!table.rows.is_empty());
+    assert!(table.rows.iter().all(|row| row.len() == table.columns.len()));
+}
@@ -0,0 +1,412 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use diesel::PgConnection;
+
+fn purchase_season_pass(
+    project: &TestProject,
+    season_pass: &SeasonPass,
+    quantity: u32,
+    connection: &PgConnection,
+) -> (User, Order) {
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_season_pass(user.id, season_pass, quantity, connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    (user, cart)
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    assert!(SeasonPass::create(organization.id, "Season".to_string(), -1)
+        .commit(Some(user.id), connection)
+        .is_err());
+
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(season_pass.price_in_cents, 3000);
+    let domain_events = DomainEvent::find(
+        Tables::SeasonPasses,
+        Some(season_pass.id),
+        Some(DomainEventTypes::SeasonPassCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let season_pass = season_pass
+        .update(
+            SeasonPassEditableAttributes {
+                name: Some("Full Season".to_string()),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(season_pass.name, "Full Season".to_string());
+    assert_eq!(season_pass.price_in_cents, 3000);
+}
+
+#[test]
+fn set_events() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let event2 = project.create_event().with_organization(&organization).finish();
+    let other_event = project.create_event().finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+
+    // Events from another organization cannot be added
+    let result = season_pass.set_events(&[event.id, other_event.id], None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let events = season_pass
+        .set_events(&[event.id, event2.id, event.id], None, connection)
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(season_pass.includes_event(event.id, connection).unwrap());
+    assert!(!season_pass.includes_event(other_event.id, connection).unwrap());
+
+    let events = season_pass.set_events(&[event2.id], None, connection).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, event2.id);
+    assert!(!season_pass.includes_event(event.id, connection).unwrap());
+    assert_eq!(season_pass.for_display(connection).unwrap().events.len(), 1);
+}
+
+#[test]
+fn purchase() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass.set_events(&[event.id], None, connection).unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let order_item = cart
+        .add_season_pass(user.id, &season_pass, 2, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(order_item.item_type, OrderItemTypes::SeasonPasses);
+    assert_eq!(order_item.season_pass_id, Some(season_pass.id));
+    assert_eq!(order_item.event_id, None);
+    assert_eq!(order_item.quantity, 2);
+    assert!(cart.expires_at.is_some());
+
+    let fee_schedule_range = FeeSchedule::find(organization.fee_schedule_id, connection)
+        .unwrap()
+        .get_range(3000, connection)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.unit_price_in_cents, fee_schedule_range.fee_in_cents);
+    assert_eq!(fee_item.quantity, 2);
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        2 * (3000 + fee_schedule_range.fee_in_cents)
+    );
+    let organizations = cart.organizations(connection).unwrap();
+    assert_eq!(organizations.len(), 1);
+    assert_eq!(organizations[0].id, organization.id);
+
+    let display_cart = cart.for_display(None, user.id, connection).unwrap();
+    assert!(display_cart
+        .items
+        .iter()
+        .any(|i| i.description == "Season Pass - Season".to_string()));
+
+    // Season passes can be bought alongside event tickets
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(
+        cart.items(connection)
+            .unwrap()
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::SeasonPasses)
+            .count(),
+        1
+    );
+
+    // Updating the quantity replaces the existing line
+    let order_item = cart
+        .add_season_pass(user.id, &season_pass, 1, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(order_item.quantity, 1);
+    assert_eq!(order_item.find_fee_item(connection).unwrap().unwrap().quantity, 1);
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let entitlements = SeasonPassEntitlement::find_for_user(user.id, connection).unwrap();
+    assert_eq!(entitlements.len(), 1);
+    assert_eq!(entitlements[0].season_pass_id, season_pass.id);
+    assert_eq!(entitlements[0].order_item_id, order_item.id);
+    assert_eq!(entitlements[0].status, SeasonPassEntitlementStatus::Active);
+    let domain_events = DomainEvent::find(
+        Tables::SeasonPassEntitlements,
+        Some(entitlements[0].id),
+        Some(DomainEventTypes::SeasonPassEntitlementPurchased),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn remove_from_cart() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_season_pass(user.id, &season_pass, 1, connection).unwrap();
+    assert!(cart
+        .add_season_pass(user.id, &season_pass, 0, connection)
+        .unwrap()
+        .is_none());
+    assert_eq!(cart.items(connection).unwrap().len(), 0);
+
+    cart.add_season_pass(user.id, &season_pass, 1, connection).unwrap();
+    cart.clear_cart(user.id, connection).unwrap();
+    assert_eq!(cart.items(connection).unwrap().len(), 0);
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let scanner = project.create_user().finish();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let event2 = project.create_event().with_organization(&organization).finish();
+    let other_event = project.create_event().with_organization(&organization).finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass
+        .set_events(&[event.id, event2.id], None, connection)
+        .unwrap();
+    let (user, _) = purchase_season_pass(&project, &season_pass, 1, connection);
+    let entitlement = &SeasonPassEntitlement::find_for_user(user.id, connection).unwrap()[0];
+
+    assert_eq!(
+        SeasonPassEntitlement::redeem(
+            entitlement.id,
+            event.id,
+            "WRONGKEY".to_string(),
+            scanner.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap(),
+        RedeemResults::TicketInvalid
+    );
+    assert_eq!(
+        SeasonPassEntitlement::redeem(
+            entitlement.id,
+            other_event.id,
+            entitlement.redeem_key.clone(),
+            scanner.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap(),
+        RedeemResults::TicketInvalid
+    );
+
+    // One entry per event covered by the pass
+    for event_id in &[event.id, event2.id] {
+        assert_eq!(
+            SeasonPassEntitlement::redeem(
+                entitlement.id,
+                *event_id,
+                entitlement.redeem_key.clone(),
+                scanner.id,
+                CheckInSource::Scanned,
+                connection,
+            )
+            .unwrap(),
+            RedeemResults::TicketRedeemSuccess
+        );
+    }
+    assert_eq!(
+        SeasonPassEntitlement::redeem(
+            entitlement.id,
+            event.id,
+            entitlement.redeem_key.clone(),
+            scanner.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap(),
+        RedeemResults::TicketAlreadyRedeemed
+    );
+
+    let redemptions = entitlement.redemptions(connection).unwrap();
+    assert_eq!(redemptions.len(), 2);
+    assert_eq!(redemptions[0].redeemed_by_user_id, scanner.id);
+    let display = entitlement.for_display(connection).unwrap();
+    assert_eq!(display.redeemed_event_ids.len(), 2);
+
+    // Used entitlements cannot be refunded
+    assert!(!entitlement.is_refundable(connection).unwrap());
+}
+
+#[test]
+fn refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass.set_events(&[event.id], None, connection).unwrap();
+    let (user, mut order) = purchase_season_pass(&project, &season_pass, 2, connection);
+    let entitlements = SeasonPassEntitlement::find_for_user(user.id, connection).unwrap();
+    assert_eq!(entitlements.len(), 2);
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::SeasonPasses)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+
+    // Redeem one of the entitlements, the other remains refundable
+    let redeemed = &entitlements[0];
+    SeasonPassEntitlement::redeem(
+        redeemed.id,
+        event.id,
+        redeemed.redeem_key.clone(),
+        user.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: None,
+    }];
+    let (_, refund_amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(refund_amount, 3000 + fee_item.unit_price_in_cents);
+    let order_item = OrderItem::find(order_item.id, connection).unwrap();
+    assert_eq!(order_item.refunded_quantity, 1);
+
+    let entitlements = SeasonPassEntitlement::find_for_user(user.id, connection).unwrap();
+    assert_eq!(entitlements.len(), 1);
+    assert_eq!(entitlements[0].id, redeemed.id);
+
+    // No unused entitlements remain
+    assert!(order.refund(&refund_items, user.id, None, false, connection).is_err());
+}
+
+#[test]
+fn settlement() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let season_pass = SeasonPass::create(organization.id, "Season".to_string(), 3000)
+        .commit(None, connection)
+        .unwrap();
+    season_pass.set_events(&[event.id], None, connection).unwrap();
+    let (user, mut order) = purchase_season_pass(&project, &season_pass, 2, connection);
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::SeasonPasses)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: None,
+    }];
+    order.refund(&refund_items, user.id, None, false, connection).unwrap();
+
+    // Sales and refunds are settled in the period they happened in
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let display_settlement = settlement.for_display(connection).unwrap();
+    assert!(display_settlement.event_entries.is_empty());
+    assert_eq!(display_settlement.season_pass_entries.len(), 1);
+    let entry = &display_settlement.season_pass_entries[0];
+    assert_eq!(entry.season_pass_id, season_pass.id);
+    assert_eq!(entry.season_pass_name, "Season".to_string());
+    assert_eq!(entry.face_value_in_cents, 3000);
+    assert_eq!(entry.revenue_share_value_in_cents, fee_item.client_fee_in_cents);
+    assert_eq!(entry.online_sold_quantity, 1);
+    assert_eq!(entry.fee_sold_quantity, 1);
+    assert_eq!(entry.total_sales_in_cents, 3000 + fee_item.client_fee_in_cents);
+    let order_item = OrderItem::find(order_item.id, connection).unwrap();
+    assert_eq!(order_item.settlement_id, Some(settlement.id));
+
+    // Settled sales are not included again
+    let other_settlement = project.create_settlement().with_organization(&organization).finish();
+    assert!(other_settlement
+        .for_display(connection)
+        .unwrap()
+        .season_pass_entries
+        .is_empty());
+}
@@ -1,6 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
-use bigneon_db::schema::{orders, refunds};
+use bigneon_db::schema::{orders, refunds, settlements};
 use bigneon_db::utils::dates;
 use bigneon_db::utils::errors::ErrorCode::ValidationError;
 use chrono::prelude::*;
@@ -39,6 +39,30 @@ fn find_last_settlement_for_organization() {
     );
 }
 
+#[test]
+fn find_pending_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    assert!(Settlement::find_pending_for_organization(organization.id, connection)
+        .unwrap()
+        .is_none());
+
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    assert_eq!(
+        Settlement::find_pending_for_organization(organization.id, connection).unwrap(),
+        Some(settlement.clone())
+    );
+
+    diesel::update(settlements::table.filter(settlements::id.eq(settlement.id)))
+        .set(settlements::status.eq(SettlementStatus::SettledInFull))
+        .execute(connection)
+        .unwrap();
+    assert!(Settlement::find_pending_for_organization(organization.id, connection)
+        .unwrap()
+        .is_none());
+}
+
 #[test]
 fn process_settlement_for_organization() {
     let project = TestProject::new();
@@ -1238,3 +1262,124 @@ fn find_for_organization() {
     assert_eq!(settlements.len(), 1);
     assert_eq!(settlements[0].id, settlement.id);
 }
+
+#[test]
+fn create_entries_for_order_spanning_organizations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_settlement_type(SettlementTypes::PostEvent)
+        .finish();
+    let organization2 = project
+        .create_organization()
+        .with_event_fee()
+        .with_settlement_type(SettlementTypes::PostEvent)
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_event_start(dates::now().add_days(-15).finish())
+        .with_event_end(dates::now().add_days(-6).finish())
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization2)
+        .with_ticket_pricing()
+        .with_event_start(dates::now().add_days(-15).finish())
+        .with_event_end(dates::now().add_days(-6).finish())
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+                seat_ids: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: ticket_type2.id,
+                quantity: 3,
+                redemption_code: None,
+                seat_ids: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-7).finish(),
+        dates::now().finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        true,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let display_settlement = settlement.clone().for_display(connection).unwrap();
+    assert_eq!(display_settlement.event_entries.len(), 1);
+    assert_eq!(display_settlement.event_entries[0].event.id, event.id);
+    let ticket_type_entry = display_settlement.event_entries[0]
+        .entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::TicketType)
+        .unwrap();
+    assert_eq!(ticket_type_entry.online_sold_quantity, 2);
+
+    // Only the items for the settled organization's event are marked as settled
+    for item in cart.items(connection).unwrap() {
+        if item.event_id == Some(event.id) && item.item_type == OrderItemTypes::Tickets {
+            assert_eq!(item.settlement_id, Some(settlement.id));
+        } else if item.event_id == Some(event2.id) {
+            assert_eq!(item.settlement_id, None);
+        }
+    }
+
+    // The second organization can still settle its share of the order
+    let settlement2 = Settlement::create(
+        organization2.id,
+        dates::now().add_days(-7).finish(),
+        dates::now().finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        true,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let display_settlement = settlement2.clone().for_display(connection).unwrap();
+    assert_eq!(display_settlement.event_entries.len(), 1);
+    assert_eq!(display_settlement.event_entries[0].event.id, event2.id);
+    let ticket_type_entry = display_settlement.event_entries[0]
+        .entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::TicketType)
+        .unwrap();
+    assert_eq!(ticket_type_entry.online_sold_quantity, 3);
+
+    for item in cart.items(connection).unwrap() {
+        if item.event_id == Some(event2.id) && item.item_type == OrderItemTypes::Tickets {
+            assert_eq!(item.settlement_id, Some(settlement2.id));
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn add_row() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let stage = project.create_stage().finish();
+    let section = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+
+    let seats = section.add_row("A".to_string(), 3, connection).unwrap();
+    assert_eq!(
+        seats.iter().map(|s| s.seat_number.as_str()).collect::<Vec<&str>>(),
+        vec!["1", "2", "3"]
+    );
+    let seats = section.add_row("B".to_string(), 2, connection).unwrap();
+    assert_eq!(seats.iter().map(|s| s.rank).collect::<Vec<i32>>(), vec![3, 4]);
+
+    let found_seats = section.seats(connection).unwrap();
+    assert_eq!(found_seats.len(), 5);
+    assert_eq!(found_seats[0].row_name, "A");
+    assert_eq!(found_seats[4].row_name, "B");
+}
+
+#[test]
+fn seat_map() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let stage = project.create_stage().finish();
+    let balcony = StageSection::create(stage.id, "Balcony".to_string(), 1)
+        .commit(connection)
+        .unwrap();
+    balcony.add_row("A".to_string(), 2, connection).unwrap();
+    let orchestra = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    orchestra.add_row("A".to_string(), 4, connection).unwrap();
+
+    let seat_map = stage.seat_map(connection).unwrap();
+    assert_eq!(seat_map.len(), 2);
+    assert_eq!(seat_map[0].id, orchestra.id);
+    assert_eq!(seat_map[0].seats.len(), 4);
+    assert_eq!(seat_map[1].id, balcony.id);
+    assert_eq!(seat_map[1].seats.len(), 2);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let section = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    let seats = section.add_row("A".to_string(), 2, connection).unwrap();
+    let unused_section = StageSection::create(stage.id, "Balcony".to_string(), 1)
+        .commit(connection)
+        .unwrap();
+    unused_section.add_row("A".to_string(), 2, connection).unwrap();
+
+    ticket_type
+        .assign_seats(&seats.iter().map(|s| s.id).collect::<Vec<Uuid>>(), None, connection)
+        .unwrap();
+    assert_eq!(
+        section.destroy(connection),
+        DatabaseError::business_process_error("Unable to delete stage section, its seats are assigned to tickets",)
+    );
+
+    assert_eq!(unused_section.destroy(connection).unwrap(), 1);
+    assert!(StageSection::find(unused_section.id, connection).is_err());
+}
+
+#[test]
+fn reserve_selected_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let section = StageSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    let seats = section.add_row("A".to_string(), 4, connection).unwrap();
+    let seat_ids: Vec<Uuid> = seats.iter().map(|s| s.id).collect();
+    ticket_type.assign_seats(&seat_ids, None, connection).unwrap();
+
+    // Seats can only be assigned once per event
+    assert!(ticket_type.assign_seats(&seat_ids[0..1], None, connection).is_err());
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Quantity must match the seats selected
+    assert!(cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: Some(vec![seat_ids[2], seat_ids[3]]),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_err());
+
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: Some(vec![seat_ids[2], seat_ids[3]]),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = &cart.items(connection).unwrap()[0];
+    let mut reserved_seat_ids: Vec<Uuid> = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.seat_id)
+        .collect();
+    reserved_seat_ids.sort();
+    let mut expected_seat_ids = vec![seat_ids[2], seat_ids[3]];
+    expected_seat_ids.sort();
+    assert_eq!(reserved_seat_ids, expected_seat_ids);
+
+    let event_seats = Seat::find_for_event(event.id, connection).unwrap();
+    assert_eq!(
+        event_seats
+            .iter()
+            .filter(|s| s.available)
+            .map(|s| s.id)
+            .collect::<Vec<Uuid>>(),
+        vec![seat_ids[0], seat_ids[1]]
+    );
+
+    // Seats already reserved by another cart cannot be selected
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    assert!(cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: Some(vec![seat_ids[2]]),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_err());
+}
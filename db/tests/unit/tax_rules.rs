@@ -0,0 +1,134 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::{TaxRule, TaxRuleEditableAttributes};
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let tax_rule = TaxRule::create("Sales Tax", "US", Some("California".to_string()), 7.25, 0f32)
+        .commit(project.get_connection())
+        .unwrap();
+
+    assert_eq!(tax_rule.name, "Sales Tax");
+    assert_eq!(tax_rule.country, "US");
+    assert_eq!(tax_rule.state, Some("California".to_string()));
+    assert_eq!(tax_rule.ticket_tax_percent, 7.25);
+    assert_eq!(tax_rule.fee_tax_percent, 0f32);
+    assert!(!tax_rule.inclusive);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let result = TaxRule::create("Sales Tax", "US", None, 101f32, -1f32).commit(project.get_connection());
+    match result {
+        Ok(_) => {
+            panic!("Expected error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_tax_percent"));
+                assert_eq!(errors["ticket_tax_percent"][0].code, "percent");
+                assert!(errors.contains_key("fee_tax_percent"));
+                assert_eq!(errors["fee_tax_percent"][0].code, "percent");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let tax_rule = project.create_tax_rule().finish();
+
+    let parameters = TaxRuleEditableAttributes {
+        name: Some("VAT".to_string()),
+        ticket_tax_percent: Some(20f32),
+        inclusive: Some(true),
+        ..Default::default()
+    };
+
+    let updated_tax_rule = tax_rule.update(parameters, project.get_connection()).unwrap();
+    assert_eq!(updated_tax_rule.name, "VAT");
+    assert_eq!(updated_tax_rule.ticket_tax_percent, 20f32);
+    assert!(updated_tax_rule.inclusive);
+
+    let parameters = TaxRuleEditableAttributes {
+        fee_tax_percent: Some(150f32),
+        ..Default::default()
+    };
+    assert!(updated_tax_rule.update(parameters, project.get_connection()).is_err());
+}
+
+#[test]
+fn find() {
+    let project = TestProject::new();
+    let tax_rule = project.create_tax_rule().finish();
+
+    let found_tax_rule = TaxRule::find(tax_rule.id, project.get_connection()).unwrap();
+    assert_eq!(tax_rule, found_tax_rule);
+}
+
+#[test]
+fn all() {
+    let project = TestProject::new();
+    let tax_rule = project.create_tax_rule().with_country("GB".to_string()).finish();
+    let tax_rule2 = project.create_tax_rule().with_country("US".to_string()).finish();
+
+    assert_eq!(
+        vec![tax_rule, tax_rule2],
+        TaxRule::all(project.get_connection()).unwrap()
+    );
+}
+
+#[test]
+fn find_for_venue() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let country_tax_rule = project.create_tax_rule().with_country("US".to_string()).finish();
+    let state_tax_rule = project
+        .create_tax_rule()
+        .with_country("US".to_string())
+        .with_state("California".to_string())
+        .finish();
+
+    let venue = project
+        .create_venue()
+        .with_country("us".to_string())
+        .with_state("california".to_string())
+        .finish();
+    assert_eq!(
+        TaxRule::find_for_venue(&venue, connection).unwrap(),
+        Some(state_tax_rule)
+    );
+
+    let venue = project
+        .create_venue()
+        .with_country("US".to_string())
+        .with_state("Nevada".to_string())
+        .finish();
+    assert_eq!(
+        TaxRule::find_for_venue(&venue, connection).unwrap(),
+        Some(country_tax_rule)
+    );
+
+    let venue = project.create_venue().with_country("CA".to_string()).finish();
+    assert_eq!(TaxRule::find_for_venue(&venue, connection).unwrap(), None);
+}
+
+#[test]
+fn tax_in_cents() {
+    let project = TestProject::new();
+    let tax_rule = project.create_tax_rule().with_country("US".to_string()).finish();
+    assert_eq!(tax_rule.tax_in_cents(1000, 10f32), 100);
+    assert_eq!(tax_rule.tax_in_cents(1000, 0f32), 0);
+
+    let tax_rule = project
+        .create_tax_rule()
+        .with_country("GB".to_string())
+        .inclusive()
+        .finish();
+    assert_eq!(tax_rule.tax_in_cents(1200, 20f32), 200);
+    assert_eq!(tax_rule.tax_in_cents(1000, 0f32), 0);
+}
@@ -498,6 +498,7 @@ fn cant_reserve_more_than_tickets_available() {
         order_item.ticket_type_id.unwrap(),
         None,
         1,
+        None,
         connection,
     );
 
@@ -540,6 +541,7 @@ fn cant_reserve_nullifed_tickets() {
         order_item.ticket_type_id.unwrap(),
         None,
         1,
+        None,
         connection,
     );
 
@@ -587,6 +589,7 @@ fn release() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -746,6 +749,7 @@ fn find() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -777,6 +781,7 @@ fn find() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        seat_label: None,
     };
     assert_eq!(
         (display_event, None, expected_ticket),
@@ -809,6 +814,7 @@ fn find_show_no_token() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -847,6 +853,7 @@ fn find_show_no_token() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        seat_label: None,
     };
     let (found_event, found_user, found_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
     assert_eq!(
@@ -888,6 +895,7 @@ fn find_for_user() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -926,6 +934,7 @@ fn release_tickets() {
                 ticket_type_id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -978,6 +987,7 @@ fn release_tickets_cancelled_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1044,6 +1054,7 @@ fn mark_as_purchased() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1376,6 +1387,7 @@ fn create_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1533,6 +1545,7 @@ fn receive_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -502,6 +502,7 @@ fn update_with_affected_orders() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
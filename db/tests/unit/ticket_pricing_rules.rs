@@ -0,0 +1,253 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let pricing_rule = TicketPricingRule::create(
+        ticket_type.id,
+        "Second tier".to_string(),
+        TicketPricingRuleTypes::SoldQuantity,
+        10,
+        Some(5000),
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(pricing_rule.ticket_type_id, ticket_type.id);
+    assert_eq!(pricing_rule.triggered_at, None);
+    assert_eq!(ticket_type.pricing_rules(connection).unwrap(), vec![pricing_rule]);
+
+    // Rules must either set a price or increase the current price
+    let result = TicketPricingRule::create(
+        ticket_type.id,
+        "Invalid".to_string(),
+        TicketPricingRuleTypes::RemainingInventory,
+        10,
+        Some(5000),
+        Some(10),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("price_in_cents"));
+                assert_eq!(errors["price_in_cents"][0].code, "price_or_increase_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = TicketPricingRule::create(
+        ticket_type.id,
+        "Invalid".to_string(),
+        TicketPricingRuleTypes::RemainingInventory,
+        10,
+        None,
+        Some(-10),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("price_increase_percent"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn sold_quantity_rule() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    let pricing_rule = TicketPricingRule::create(
+        ticket_type.id,
+        "Second tier".to_string(),
+        TicketPricingRuleTypes::SoldQuantity,
+        2,
+        Some(5000),
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // Threshold reached, pricing switches to the next tier
+    let new_ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_ne!(new_ticket_pricing.id, ticket_pricing.id);
+    assert_eq!(new_ticket_pricing.price_in_cents, 5000);
+    assert_eq!(new_ticket_pricing.name, "Second tier".to_string());
+    assert_eq!(new_ticket_pricing.previous_ticket_pricing_id, Some(ticket_pricing.id));
+
+    let pricing_rule = TicketPricingRule::find(pricing_rule.id, connection).unwrap();
+    assert!(pricing_rule.triggered_at.is_some());
+    assert_eq!(pricing_rule.ticket_pricing_id, Some(new_ticket_pricing.id));
+
+    let domain_events = DomainEvent::find(
+        Tables::TicketPricing,
+        Some(new_ticket_pricing.id),
+        Some(DomainEventTypes::TicketPricingUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Tickets already in the cart keep their price, additional tickets use the new price
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 3,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items: Vec<OrderItem> = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(items.len(), 2);
+    let original_item = items
+        .iter()
+        .find(|i| i.ticket_pricing_id == Some(ticket_pricing.id))
+        .unwrap();
+    assert_eq!(original_item.quantity, 2);
+    assert_eq!(original_item.unit_price_in_cents, ticket_pricing.price_in_cents);
+    let new_item = items
+        .iter()
+        .find(|i| i.ticket_pricing_id == Some(new_ticket_pricing.id))
+        .unwrap();
+    assert_eq!(new_item.quantity, 1);
+    assert_eq!(new_item.unit_price_in_cents, 5000);
+
+    // Rules only trigger once
+    let domain_events = DomainEvent::find(
+        Tables::TicketPricing,
+        Some(new_ticket_pricing.id),
+        Some(DomainEventTypes::TicketPricingUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(
+        ticket_type.current_ticket_pricing(false, connection).unwrap().id,
+        new_ticket_pricing.id
+    );
+}
+
+#[test]
+fn rules_only_applied_when_reserving_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    let pricing_rule = TicketPricingRule::create(
+        ticket_type.id,
+        "Second tier".to_string(),
+        TicketPricingRuleTypes::SoldQuantity,
+        0,
+        Some(5000),
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    // Reading the current pricing does not switch tiers even though the threshold is met
+    assert_eq!(
+        ticket_type.current_ticket_pricing(false, connection).unwrap(),
+        ticket_pricing
+    );
+    let pricing_rule = TicketPricingRule::find(pricing_rule.id, connection).unwrap();
+    assert_eq!(pricing_rule.triggered_at, None);
+    assert_eq!(pricing_rule.ticket_pricing_id, None);
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let new_ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(new_ticket_pricing.price_in_cents, 5000);
+    let pricing_rule = TicketPricingRule::find(pricing_rule.id, connection).unwrap();
+    assert!(pricing_rule.triggered_at.is_some());
+    assert_eq!(pricing_rule.ticket_pricing_id, Some(new_ticket_pricing.id));
+}
+
+#[test]
+fn remaining_inventory_rule() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    let available = ticket_type.valid_available_ticket_count(connection).unwrap();
+    TicketPricingRule::create(
+        ticket_type.id,
+        "Last tickets".to_string(),
+        TicketPricingRuleTypes::RemainingInventory,
+        available as i64,
+        None,
+        Some(50),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    // No tickets sold yet
+    assert_eq!(
+        ticket_type
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        ticket_pricing.price_in_cents
+    );
+
+    project.create_order().for_event(&event).quantity(1).is_paid().finish();
+    let new_ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(
+        new_ticket_pricing.price_in_cents,
+        (ticket_pricing.price_in_cents as f64 * 1.5).round() as i64
+    );
+}
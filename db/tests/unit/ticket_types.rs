@@ -295,6 +295,7 @@ fn valid_unsold_ticket_count() {
             ticket_type_id: ticket_type.id,
             quantity: 50,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -326,6 +327,7 @@ fn valid_unsold_ticket_count() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -688,6 +690,7 @@ pub fn remaining_ticket_count() {
                 ticket_type_id: ticket_type.id,
                 quantity: 20,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -703,6 +706,7 @@ pub fn remaining_ticket_count() {
                 ticket_type_id: ticket_type.id,
                 quantity: 16,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
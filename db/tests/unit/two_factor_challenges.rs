@@ -0,0 +1,114 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::totp;
+use chrono::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let (challenge, token) = TwoFactorChallenge::create(user.id).commit(connection).unwrap();
+    assert_eq!(challenge.user_id, user.id);
+    assert_ne!(challenge.token_hash, token);
+    assert!(challenge.expires_at > Utc::now().naive_utc());
+    assert_eq!(
+        TwoFactorChallenge::find_active_by_token(&token, connection).unwrap(),
+        challenge
+    );
+    assert!(TwoFactorChallenge::find_active_by_token("invalid", connection).is_err());
+}
+
+#[test]
+fn verify() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+    let time_step = totp::time_step(Utc::now().timestamp());
+    credential
+        .enable(&totp::generate_code(&secret, time_step).unwrap(), "", connection)
+        .unwrap();
+    let (challenge, token) = TwoFactorChallenge::create(user.id).commit(connection).unwrap();
+
+    let invalid_code = totp::generate_code(&secret, time_step + 5).unwrap();
+    assert!(!challenge.verify(Some(&invalid_code), None, "", connection).unwrap());
+    assert!(!challenge.verify(None, None, "", connection).unwrap());
+    let challenge = TwoFactorChallenge::find_active_by_token(&token, connection).unwrap();
+    assert_eq!(challenge.failed_attempts, 2);
+
+    let code = totp::generate_code(&secret, time_step + 1).unwrap();
+    assert!(challenge.verify(Some(&code), None, "", connection).unwrap());
+
+    // Completed challenges cannot be used again
+    assert!(TwoFactorChallenge::find_active_by_token(&token, connection).is_err());
+}
+
+#[test]
+fn verify_with_recovery_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+    let code = totp::generate_code(&secret, totp::time_step(Utc::now().timestamp())).unwrap();
+    let (_, recovery_codes) = credential.enable(&code, "", connection).unwrap();
+    let (challenge, _) = TwoFactorChallenge::create(user.id).commit(connection).unwrap();
+
+    assert!(challenge
+        .verify(None, Some(&recovery_codes[0]), "", connection)
+        .unwrap());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        9
+    );
+}
+
+#[test]
+fn find_active_by_token() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+    let time_step = totp::time_step(Utc::now().timestamp());
+    credential
+        .enable(&totp::generate_code(&secret, time_step).unwrap(), "", connection)
+        .unwrap();
+    let (challenge, token) = TwoFactorChallenge::create(user.id).commit(connection).unwrap();
+
+    // Challenges stop accepting attempts after too many failures
+    for _ in 0..5 {
+        assert!(!challenge.verify(None, Some("invalid"), "", connection).unwrap());
+    }
+    assert!(TwoFactorChallenge::find_active_by_token(&token, connection).is_err());
+}
+
+#[test]
+fn is_locked_out() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+    let time_step = totp::time_step(Utc::now().timestamp());
+    credential
+        .enable(&totp::generate_code(&secret, time_step).unwrap(), "", connection)
+        .unwrap();
+    assert!(!TwoFactorChallenge::is_locked_out(user.id, connection).unwrap());
+
+    // Failures are counted across challenges so new challenges do not allow more guesses
+    for _ in 0..2 {
+        let (challenge, _) = TwoFactorChallenge::create(user.id).commit(connection).unwrap();
+        for _ in 0..5 {
+            assert!(!challenge.verify(None, Some("invalid"), "", connection).unwrap());
+        }
+    }
+    assert!(TwoFactorChallenge::is_locked_out(user.id, connection).unwrap());
+    assert!(!TwoFactorChallenge::is_locked_out(user2.id, connection).unwrap());
+
+    // Valid codes are rejected while the user is locked out
+    let (challenge, token) = TwoFactorChallenge::create(user.id).commit(connection).unwrap();
+    let code = totp::generate_code(&secret, time_step + 1).unwrap();
+    assert!(!challenge.verify(Some(&code), None, "", connection).unwrap());
+    assert!(TwoFactorChallenge::find_active_by_token(&token, connection).is_ok());
+}
@@ -0,0 +1,113 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::totp;
+use chrono::prelude::*;
+
+fn code_for(secret: &str, offset: i64) -> String {
+    totp::generate_code(secret, totp::time_step(Utc::now().timestamp()) + offset).unwrap()
+}
+
+#[test]
+fn start_enrollment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let encryption_key = "encryption_key";
+
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, encryption_key, connection).unwrap();
+    assert_eq!(credential.user_id, user.id);
+    assert!(!credential.is_enabled());
+    assert_ne!(credential.secret, secret);
+    assert_eq!(credential.secret(encryption_key).unwrap(), secret);
+
+    // Restarting enrollment replaces the pending secret
+    let (credential2, secret2) = TwoFactorCredential::start_enrollment(user.id, encryption_key, connection).unwrap();
+    assert_eq!(credential2.id, credential.id);
+    assert_ne!(secret2, secret);
+
+    credential2
+        .enable(&code_for(&secret2, 0), encryption_key, connection)
+        .unwrap();
+    let result = TwoFactorCredential::start_enrollment(user.id, encryption_key, connection);
+    assert_eq!(
+        result.err().unwrap().cause,
+        Some("Two-factor authentication is already enabled".to_string())
+    );
+}
+
+#[test]
+fn enable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+    assert!(TwoFactorCredential::find_enabled_by_user_id(user.id, connection).is_err());
+
+    let result = credential.enable(&code_for(&secret, 5), "", connection);
+    assert_eq!(
+        result.err().unwrap().cause,
+        Some("Two-factor code is invalid".to_string())
+    );
+
+    let (credential, recovery_codes) = credential.enable(&code_for(&secret, 0), "", connection).unwrap();
+    assert!(credential.is_enabled());
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(
+        TwoFactorCredential::find_enabled_by_user_id(user.id, connection).unwrap(),
+        credential
+    );
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        10
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::TwoFactorEnabled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn verify_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+
+    assert!(!credential.verify_code(&code_for(&secret, 5), "", connection).unwrap());
+    assert!(credential.verify_code(&code_for(&secret, 0), "", connection).unwrap());
+
+    // Codes cannot be replayed, including older codes within the drift window
+    assert!(!credential.verify_code(&code_for(&secret, 0), "", connection).unwrap());
+    assert!(!credential.verify_code(&code_for(&secret, -1), "", connection).unwrap());
+    assert!(credential.verify_code(&code_for(&secret, 1), "", connection).unwrap());
+}
+
+#[test]
+fn disable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (credential, secret) = TwoFactorCredential::start_enrollment(user.id, "", connection).unwrap();
+    let (credential, _) = credential.enable(&code_for(&secret, 0), "", connection).unwrap();
+
+    credential.disable(Some(user.id), connection).unwrap();
+    assert!(TwoFactorCredential::find_by_user_id(user.id, connection).is_err());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        0
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::TwoFactorDisabled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
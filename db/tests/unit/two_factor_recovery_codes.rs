@@ -0,0 +1,63 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+
+#[test]
+fn generate_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let recovery_codes = TwoFactorRecoveryCode::generate_for_user(user.id, Some(user.id), connection).unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        10
+    );
+
+    // Regenerating replaces the previous codes
+    let recovery_codes2 = TwoFactorRecoveryCode::generate_for_user(user.id, Some(user.id), connection).unwrap();
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        10
+    );
+    assert!(!TwoFactorRecoveryCode::redeem(user.id, &recovery_codes[0], connection).unwrap());
+    assert!(TwoFactorRecoveryCode::redeem(user.id, &recovery_codes2[0], connection).unwrap());
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::TwoFactorRecoveryCodesGenerated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let recovery_codes = TwoFactorRecoveryCode::generate_for_user(user.id, Some(user.id), connection).unwrap();
+
+    // Codes belong to a single user
+    assert!(!TwoFactorRecoveryCode::redeem(user2.id, &recovery_codes[0], connection).unwrap());
+
+    // Codes are not case sensitive and can only be used once
+    assert!(TwoFactorRecoveryCode::redeem(user.id, &recovery_codes[0].to_uppercase(), connection).unwrap());
+    assert!(!TwoFactorRecoveryCode::redeem(user.id, &recovery_codes[0], connection).unwrap());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        9
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::TwoFactorRecoveryCodeUsed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
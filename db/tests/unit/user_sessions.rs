@@ -0,0 +1,118 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::schema::user_sessions;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let session = UserSession::create(user.id, Some("127.0.0.1".to_string()), Some("Browser".to_string()))
+        .commit(connection)
+        .unwrap();
+    assert_eq!(session.user_id, user.id);
+    assert_eq!(session.ip_address, Some("127.0.0.1".to_string()));
+    assert_eq!(session.user_agent, Some("Browser".to_string()));
+    assert!(session.is_active());
+
+    let domain_events = DomainEvent::find(
+        Tables::UserSessions,
+        Some(session.id),
+        Some(DomainEventTypes::UserSessionCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn find_active_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let session2 = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let session3 = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user2.id, None, None).commit(connection).unwrap();
+    session3.revoke(None, connection).unwrap();
+
+    // Most recently used first
+    let session: UserSession = diesel::update(&session)
+        .set(user_sessions::last_used_at.eq(Utc::now().naive_utc() - Duration::days(1)))
+        .get_result(connection)
+        .unwrap();
+    assert_eq!(
+        UserSession::find_active_for_user(user.id, connection).unwrap(),
+        vec![session2, session]
+    );
+}
+
+#[test]
+fn mark_used() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    let updated_session = session.mark_used(connection).unwrap();
+    assert!(updated_session.last_used_at >= session.last_used_at);
+
+    let domain_events = DomainEvent::find(
+        Tables::UserSessions,
+        Some(session.id),
+        Some(DomainEventTypes::UserSessionRefreshed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    let session = session.revoke(Some(user.id), connection).unwrap();
+    assert!(!session.is_active());
+
+    // Revoking again has no effect
+    assert_eq!(session.revoke(Some(user.id), connection).unwrap(), session);
+    let domain_events = DomainEvent::find(
+        Tables::UserSessions,
+        Some(session.id),
+        Some(DomainEventTypes::UserSessionRevoked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn revoke_all_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let admin = project.create_user().finish();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user2.id, None, None).commit(connection).unwrap();
+
+    let revoked_sessions = UserSession::revoke_all_for_user(user.id, Some(admin.id), connection).unwrap();
+    assert_eq!(revoked_sessions.len(), 2);
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        UserSession::find_active_for_user(user2.id, connection).unwrap().len(),
+        1
+    );
+}
@@ -920,6 +920,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1417,6 +1418,7 @@ fn get_history_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1468,6 +1470,7 @@ fn get_history_for_organization() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -2036,6 +2039,7 @@ fn get_scopes_by_organization() {
             Scopes::OrgReadEvents,
             Scopes::OrgReports,
             Scopes::OrgUsers,
+            Scopes::OrgWebhooks,
             Scopes::OrgWrite,
             Scopes::RedeemTicket,
             Scopes::SettlementRead,
@@ -2197,12 +2201,14 @@ fn get_global_scopes() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "region:write",
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -2267,12 +2273,14 @@ fn get_global_scopes() {
             "org:read-events",
             "org:reports",
             "org:users",
+            "org:webhooks",
             "org:write",
             "redeem:ticket",
             "region:write",
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -0,0 +1,323 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::{ticket_instances, waitlist_entries};
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use time::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+
+    // Tickets are still available
+    let result = WaitlistEntry::create(ticket_type.id, user.id).commit(Some(user.id), connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+                assert_eq!(
+                    &errors["ticket_type_id"][0].message.clone().unwrap().into_owned(),
+                    "Tickets are still available for this ticket type"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Waiting);
+    assert_eq!(entry.position(connection).unwrap(), Some(1));
+
+    let domain_events = DomainEvent::find(
+        Tables::WaitlistEntries,
+        Some(entry.id),
+        Some(DomainEventTypes::WaitlistEntryCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Users can only join once
+    assert!(WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .is_err());
+
+    let user2 = project.create_user().finish();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id)
+        .commit(Some(user2.id), connection)
+        .unwrap();
+    assert_eq!(entry2.position(connection).unwrap(), Some(2));
+}
+
+#[test]
+fn offer_available_tickets_on_release() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(2)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_user(&buyer)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .finish();
+
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id)
+        .commit(Some(user2.id), connection)
+        .unwrap();
+
+    // Buyer releases one ticket, first fan in line receives an offer
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Offered);
+    assert!(entry.offer_expires_at.is_some());
+    let hold = entry.hold(connection).unwrap().unwrap();
+    assert_eq!(hold.quantity(connection).unwrap(), (1, 1));
+    assert_eq!(hold.max_per_user, Some(1));
+    assert!(entry.for_display(connection).unwrap().redemption_code.is_some());
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 0);
+
+    let entry2 = WaitlistEntry::find(entry2.id, connection).unwrap();
+    assert_eq!(entry2.status, WaitlistEntryStatus::Waiting);
+    assert_eq!(entry2.position(connection).unwrap(), Some(1));
+
+    let actions = DomainAction::find_by_resource(
+        Some(Tables::WaitlistEntries),
+        Some(entry.id),
+        DomainActionTypes::SendWaitlistOfferCommunication,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(actions.len(), 1);
+    let actions = DomainAction::find_by_resource(
+        Some(Tables::WaitlistEntries),
+        Some(entry.id),
+        DomainActionTypes::ExpireWaitlistOffer,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(Some(actions[0].scheduled_at), entry.offer_expires_at);
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = project.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+    let buyer_id = cart.user_id;
+
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id)
+        .commit(Some(user2.id), connection)
+        .unwrap();
+
+    cart.clear_cart(buyer_id, connection).unwrap();
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Offered);
+
+    // Declining the offer passes it to the next fan
+    let entry = entry.cancel(Some(user.id), connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Cancelled);
+    let entry2 = WaitlistEntry::find(entry2.id, connection).unwrap();
+    assert_eq!(entry2.status, WaitlistEntryStatus::Offered);
+
+    assert!(entry.cancel(Some(user.id), connection).is_err());
+}
+
+#[test]
+fn expire_offer() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = project.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+    let buyer_id = cart.user_id;
+
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id)
+        .commit(Some(user2.id), connection)
+        .unwrap();
+    cart.clear_cart(buyer_id, connection).unwrap();
+
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert!(entry.expire_offer(connection).is_err());
+
+    let entry: WaitlistEntry = diesel::update(&entry)
+        .set(waitlist_entries::offer_expires_at.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+        .get_result(connection)
+        .unwrap();
+    assert!(entry.expire_offer(connection).unwrap());
+
+    let hold = entry.hold(connection).unwrap().unwrap();
+    assert_eq!(hold.quantity(connection).unwrap(), (0, 0));
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Expired);
+    let entry2 = WaitlistEntry::find(entry2.id, connection).unwrap();
+    assert_eq!(entry2.status, WaitlistEntryStatus::Offered);
+
+    // Already expired
+    assert!(!entry.expire_offer(connection).unwrap());
+}
+
+#[test]
+fn offer_expired_reservations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let cart = project.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id)
+        .commit(Some(user2.id), connection)
+        .unwrap();
+
+    // A single check is scheduled for when the cart reservation lapses
+    let actions = DomainAction::find_by_resource(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::OfferWaitlistTickets,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        Some(actions[0].scheduled_at),
+        Order::find(cart.id, connection).unwrap().expires_at
+    );
+
+    // Nothing to offer while the reservation is still held
+    WaitlistEntry::offer_expired_reservations(ticket_type.id, connection).unwrap();
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Waiting);
+
+    diesel::update(ticket_instances::table.filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved)))
+        .set(ticket_instances::reserved_until.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+        .execute(connection)
+        .unwrap();
+    WaitlistEntry::offer_expired_reservations(ticket_type.id, connection).unwrap();
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Offered);
+    let entry2 = WaitlistEntry::find(entry2.id, connection).unwrap();
+    assert_eq!(entry2.status, WaitlistEntryStatus::Waiting);
+}
+
+#[test]
+fn mark_purchased_for_hold() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = project.create_order().for_tickets(ticket_type.id).quantity(1).finish();
+    let buyer_id = cart.user_id;
+
+    let user = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    cart.clear_cart(buyer_id, connection).unwrap();
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Offered);
+    let redemption_code = entry.for_display(connection).unwrap().redemption_code.unwrap();
+
+    project
+        .create_order()
+        .for_user(&user)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .with_redemption_code(redemption_code)
+        .is_paid()
+        .finish();
+
+    // The offer is closed as soon as the tickets are paid for
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Purchased);
+    assert_eq!(entry.for_display(connection).unwrap().redemption_code, None);
+    let domain_events = DomainEvent::find(
+        Tables::WaitlistEntries,
+        Some(entry.id),
+        Some(DomainEventTypes::WaitlistOfferPurchased),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert!(!entry.expire_offer(connection).unwrap());
+}
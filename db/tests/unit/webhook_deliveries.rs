@@ -0,0 +1,76 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::WebhookDelivery;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+
+    let mut delivery = WebhookDelivery::create(
+        domain_event_publisher.id,
+        None,
+        None,
+        domain_event_publisher.webhook_url.clone(),
+        1,
+    );
+    delivery.status_code = Some(500);
+    delivery.response_body = Some("x".repeat(5000));
+    let delivery = delivery.commit(connection).unwrap();
+
+    assert_eq!(delivery.domain_event_publisher_id, domain_event_publisher.id);
+    assert_eq!(delivery.webhook_url, domain_event_publisher.webhook_url);
+    assert_eq!(delivery.attempt, 1);
+    assert_eq!(delivery.status_code, Some(500));
+    assert_eq!(delivery.response_body.unwrap().len(), 4096);
+    assert!(!delivery.succeeded);
+}
+
+#[test]
+fn find_by_domain_event_publisher_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let domain_event_publisher2 = project.create_domain_event_publisher().finish();
+
+    let mut delivery = WebhookDelivery::create(
+        domain_event_publisher.id,
+        None,
+        None,
+        domain_event_publisher.webhook_url.clone(),
+        1,
+    );
+    delivery.error = Some("Error making webhook request".to_string());
+    let delivery = delivery.commit(connection).unwrap();
+    let mut delivery2 = WebhookDelivery::create(
+        domain_event_publisher.id,
+        None,
+        None,
+        domain_event_publisher.webhook_url.clone(),
+        2,
+    );
+    delivery2.status_code = Some(200);
+    delivery2.succeeded = true;
+    let delivery2 = delivery2.commit(connection).unwrap();
+    WebhookDelivery::create(
+        domain_event_publisher2.id,
+        None,
+        None,
+        domain_event_publisher2.webhook_url.clone(),
+        1,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let deliveries =
+        WebhookDelivery::find_by_domain_event_publisher_id(domain_event_publisher.id, 0, 100, connection).unwrap();
+    assert_eq!(deliveries.paging.total, 2);
+    assert_eq!(deliveries.data, vec![delivery2.clone(), delivery.clone()]);
+    assert_eq!(
+        domain_event_publisher
+            .webhook_deliveries(0, 100, connection)
+            .unwrap()
+            .data,
+        vec![delivery2, delivery]
+    );
+}
@@ -0,0 +1,19 @@
+extern crate hosted_checkout;
+extern crate logging;
+
+use hosted_checkout::mock;
+use std::env;
+
+const HOSTED_CHECKOUT_MOCK_ADDRESS: &str = "HOSTED_CHECKOUT_MOCK_ADDRESS";
+const HOSTED_CHECKOUT_API_KEY: &str = "HOSTED_CHECKOUT_API_KEY";
+const HOSTED_CHECKOUT_SECRET: &str = "HOSTED_CHECKOUT_SECRET";
+
+/// Runs a local hosted checkout provider so redirect checkouts can be tested offline.
+/// Point HOSTED_CHECKOUT_BASE_URL of the API at the address of this server.
+fn main() {
+    logging::setup_logger();
+    let address = env::var(HOSTED_CHECKOUT_MOCK_ADDRESS).unwrap_or_else(|_| "127.0.0.1:7100".to_string());
+    let api_key = env::var(HOSTED_CHECKOUT_API_KEY).unwrap_or_else(|_| "mock_api_key".to_string());
+    let secret = env::var(HOSTED_CHECKOUT_SECRET).unwrap_or_else(|_| "mock_secret".to_string());
+    mock::run(&address, &api_key, &secret);
+}
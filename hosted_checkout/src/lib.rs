@@ -0,0 +1,253 @@
+//! Client for redirect style payment providers (PayPal-style hosted checkouts).
+//!
+//! The customer is sent to the provider's `redirect_url` and returned to the success or cancel url
+//! with the checkout id and a signature appended to the query string. Payment notifications (IPNs)
+//! are posted as JSON with the signature of the raw body in the `X-Signature` header. Signatures
+//! are hex encoded HMAC-SHA256 digests using the shared secret of the merchant account.
+extern crate actix;
+extern crate actix_web;
+extern crate chrono;
+#[macro_use]
+extern crate derive_error;
+extern crate hex;
+extern crate log;
+#[macro_use]
+extern crate logging;
+extern crate reqwest;
+extern crate ring;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+extern crate url;
+extern crate uuid;
+
+use chrono::prelude::*;
+use log::Level::Debug;
+use reqwest::header::AUTHORIZATION;
+use reqwest::StatusCode;
+use ring::{digest, hmac};
+
+pub mod mock;
+
+/// Header containing the signature of an IPN body
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+pub struct HostedCheckoutClient {
+    api_key: String,
+    base_url: String,
+}
+
+impl HostedCheckoutClient {
+    pub fn new(api_key: String, base_url: String) -> HostedCheckoutClient {
+        HostedCheckoutClient {
+            api_key,
+            base_url: if base_url.ends_with("/") {
+                base_url
+            } else {
+                format!("{}/", base_url)
+            },
+        }
+    }
+
+    pub fn create_checkout(&self, request: &CheckoutRequest) -> Result<Checkout, HostedCheckoutError> {
+        jlog!(Debug, "Sending checkout request to hosted checkout provider", { "request": request });
+        let resp = reqwest::Client::new()
+            .post(&format!("{}checkouts", &self.base_url))
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .json(request)
+            .send()?;
+        HostedCheckoutClient::read_checkout(resp)
+    }
+
+    pub fn get_checkout(&self, id: &str) -> Result<Checkout, HostedCheckoutError> {
+        jlog!(Debug, "Retrieving checkout from hosted checkout provider", { "id": id });
+        let resp = reqwest::Client::new()
+            .get(&format!("{}checkouts/{}", &self.base_url, id))
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .send()?;
+        HostedCheckoutClient::read_checkout(resp)
+    }
+
+    pub fn refund(&self, id: &str, amount_in_cents: i64) -> Result<Checkout, HostedCheckoutError> {
+        jlog!(Debug, "Refunding checkout with hosted checkout provider", { "id": id, "amount_in_cents": amount_in_cents });
+        let resp = reqwest::Client::new()
+            .post(&format!("{}checkouts/{}/refunds", &self.base_url, id))
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .json(&RefundRequest { amount_in_cents })
+            .send()?;
+        HostedCheckoutClient::read_checkout(resp)
+    }
+
+    fn read_checkout(mut resp: reqwest::Response) -> Result<Checkout, HostedCheckoutError> {
+        let status = resp.status();
+        let value: serde_json::Value = resp.json()?;
+        jlog!(Debug, "Response from hosted checkout provider", { "status": status.as_u16(), "response": &value });
+        if status != StatusCode::OK {
+            let error: ErrorResponse = serde_json::from_value(value).unwrap_or(ErrorResponse {
+                error: format!("Unexpected status code from hosted checkout provider: {}", status),
+            });
+            return Err(HostedCheckoutError::ProviderError(error.error));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HostedCheckoutError {
+    HttpError(reqwest::Error),
+    #[error(msg_embedded, no_from, non_std)]
+    ProviderError(String),
+    DeserializationError(serde_json::Error),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckoutStatus {
+    Pending,
+    Paid,
+    Cancelled,
+    Expired,
+    Refunded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CheckoutRequest {
+    pub amount_in_cents: i64,
+    pub currency: String,
+    pub email: String,
+    /// A reference that links the checkout back to our system, returned in callbacks and IPNs.
+    pub reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipn_url: Option<String>,
+    pub success_url: String,
+    pub cancel_url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Checkout {
+    pub id: String,
+    pub status: CheckoutStatus,
+    pub amount_in_cents: i64,
+    pub refunded_in_cents: i64,
+    pub currency: String,
+    pub email: String,
+    pub reference: String,
+    pub redirect_url: String,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RefundRequest {
+    pub amount_in_cents: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Message signed when returning the customer to the success or cancel url
+pub fn return_message(checkout_id: &str, reference: &str, success: bool) -> String {
+    format!("{}:{}:{}", checkout_id, reference, success)
+}
+
+pub fn sign(secret: &str, message: &str) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    hex::encode(hmac::sign(&key, message.as_bytes()).as_ref())
+}
+
+pub fn verify(secret: &str, message: &str, signature: &str) -> bool {
+    let signature = match hex::decode(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let key = hmac::VerificationKey::new(&digest::SHA256, secret.as_bytes());
+    hmac::verify(&key, message.as_bytes(), &signature).is_ok()
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    pub fn sign_message() {
+        assert_eq!(
+            sign("key", "The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    pub fn verify_signature() {
+        let message = return_message("checkout-1", "order-1", true);
+        let signature = sign("secret", &message);
+        assert!(verify("secret", &message, &signature));
+        assert!(!verify("other secret", &message, &signature));
+        assert!(!verify(
+            "secret",
+            &return_message("checkout-1", "order-1", false),
+            &signature
+        ));
+        assert!(!verify("secret", &message, "not hex"));
+    }
+
+    #[test]
+    pub fn checkout_with_mock_server() {
+        let base_url = mock::start("api_key", "secret");
+        let request = CheckoutRequest {
+            amount_in_cents: 1500,
+            currency: "USD".to_string(),
+            email: "customer@tari.com".to_string(),
+            reference: "order-1".to_string(),
+            ipn_url: None,
+            success_url: "http://localhost/success".to_string(),
+            cancel_url: "http://localhost/cancel".to_string(),
+        };
+
+        let client = HostedCheckoutClient::new("wrong_key".to_string(), base_url.clone());
+        assert!(client.create_checkout(&request).is_err());
+
+        let client = HostedCheckoutClient::new("api_key".to_string(), base_url);
+        let checkout = client.create_checkout(&request).unwrap();
+        assert_eq!(checkout.status, CheckoutStatus::Pending);
+        assert_eq!(checkout.amount_in_cents, 1500);
+        assert_eq!(checkout.reference, "order-1");
+
+        // Customer pays on the hosted page and is returned to the success url
+        let resp = reqwest::Client::builder()
+            .redirect(reqwest::RedirectPolicy::none())
+            .build()
+            .unwrap()
+            .get(&checkout.redirect_url)
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        let location = url::Url::parse(resp.headers()["Location"].to_str().unwrap()).unwrap();
+        assert_eq!(location.path(), "/success");
+        let signature = location
+            .query_pairs()
+            .find(|(k, _)| k == "signature")
+            .map(|(_, v)| v.to_string())
+            .unwrap();
+        assert!(verify(
+            "secret",
+            &return_message(&checkout.id, "order-1", true),
+            &signature
+        ));
+
+        let checkout = client.get_checkout(&checkout.id).unwrap();
+        assert_eq!(checkout.status, CheckoutStatus::Paid);
+
+        // Refunds can't exceed the amount paid
+        assert!(client.refund(&checkout.id, 2000).is_err());
+        let checkout = client.refund(&checkout.id, 500).unwrap();
+        assert_eq!(checkout.status, CheckoutStatus::Paid);
+        assert_eq!(checkout.refunded_in_cents, 500);
+        let checkout = client.refund(&checkout.id, 1000).unwrap();
+        assert_eq!(checkout.status, CheckoutStatus::Refunded);
+        assert_eq!(checkout.refunded_in_cents, 1500);
+    }
+}
@@ -0,0 +1,248 @@
+//! In-memory hosted checkout provider for local development and tests.
+//!
+//! Visiting a checkout's `redirect_url` simulates the customer completing the hosted payment page,
+//! `?outcome=cancel` simulates the customer abandoning it. The IPN is posted before the customer is
+//! redirected back, failures to deliver it are logged and otherwise ignored.
+use super::*;
+use actix_web::http::header::LOCATION;
+use actix_web::{server, App, HttpRequest, HttpResponse, Json, Path, Query};
+use chrono::Duration;
+use log::Level::{Info, Warn};
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct MockState {
+    api_key: String,
+    secret: String,
+    checkouts: Arc<Mutex<HashMap<String, MockCheckout>>>,
+}
+
+#[derive(Clone)]
+struct MockCheckout {
+    checkout: Checkout,
+    ipn_url: Option<String>,
+    success_url: String,
+    cancel_url: String,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayOutcome {
+    Success,
+    Cancel,
+}
+
+#[derive(Deserialize)]
+pub struct PayParameters {
+    pub outcome: Option<PayOutcome>,
+}
+
+impl MockState {
+    pub fn new(api_key: &str, secret: &str) -> MockState {
+        MockState {
+            api_key: api_key.to_string(),
+            secret: secret.to_string(),
+            checkouts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+pub fn create_app(state: MockState) -> App<MockState> {
+    App::with_state(state)
+        .resource("/checkouts", |r| r.post().with(create_checkout))
+        .resource("/checkouts/{id}", |r| r.get().with(get_checkout))
+        .resource("/checkouts/{id}/pay", |r| r.get().with(pay))
+        .resource("/checkouts/{id}/refunds", |r| r.post().with(refund))
+}
+
+/// Runs the mock server on the given address, blocking until the server is stopped
+pub fn run(address: &str, api_key: &str, secret: &str) {
+    let system = actix::System::new("hosted-checkout-mock");
+    let state = MockState::new(api_key, secret);
+    server::new(move || create_app(state.clone()))
+        .bind(address)
+        .expect(&format!("Could not bind mock hosted checkout server to {}", address))
+        .start();
+    jlog!(Info, &format!("Mock hosted checkout server listening on {}", address));
+    system.run();
+}
+
+/// Starts the mock server on a random local port in the background and returns its base url
+pub fn start(api_key: &str, secret: &str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind mock hosted checkout server");
+    let address = listener
+        .local_addr()
+        .expect("Could not read mock hosted checkout server address");
+    let state = MockState::new(api_key, secret);
+    thread::spawn(move || {
+        let system = actix::System::new("hosted-checkout-mock");
+        server::new(move || create_app(state.clone())).listen(listener).start();
+        system.run();
+    });
+    format!("http://{}/", address)
+}
+
+fn error(mut response: actix_web::dev::HttpResponseBuilder, message: &str) -> HttpResponse {
+    response.json(ErrorResponse {
+        error: message.to_string(),
+    })
+}
+
+fn is_authorized(request: &HttpRequest<MockState>) -> bool {
+    let expected = format!("Bearer {}", request.state().api_key);
+    request
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|h| h == expected)
+        .unwrap_or(false)
+}
+
+fn create_checkout((request, data): (HttpRequest<MockState>, Json<CheckoutRequest>)) -> HttpResponse {
+    if !is_authorized(&request) {
+        return error(HttpResponse::Unauthorized(), "Invalid API key");
+    }
+    let data = data.into_inner();
+    if data.amount_in_cents <= 0 {
+        return error(HttpResponse::UnprocessableEntity(), "Amount must be greater than zero");
+    }
+
+    let id = Uuid::new_v4().simple().to_string();
+    let redirect_url = {
+        let connection_info = request.connection_info();
+        format!(
+            "{}://{}/checkouts/{}/pay",
+            connection_info.scheme(),
+            connection_info.host(),
+            id
+        )
+    };
+    let now = Utc::now().naive_utc();
+    let checkout = Checkout {
+        id: id.clone(),
+        status: CheckoutStatus::Pending,
+        amount_in_cents: data.amount_in_cents,
+        refunded_in_cents: 0,
+        currency: data.currency,
+        email: data.email,
+        reference: data.reference,
+        redirect_url,
+        expires_at: now + Duration::minutes(15),
+        created_at: now,
+    };
+    request.state().checkouts.lock().unwrap().insert(
+        id,
+        MockCheckout {
+            checkout: checkout.clone(),
+            ipn_url: data.ipn_url,
+            success_url: data.success_url,
+            cancel_url: data.cancel_url,
+        },
+    );
+    HttpResponse::Ok().json(checkout)
+}
+
+fn get_checkout((request, id): (HttpRequest<MockState>, Path<String>)) -> HttpResponse {
+    if !is_authorized(&request) {
+        return error(HttpResponse::Unauthorized(), "Invalid API key");
+    }
+    match request.state().checkouts.lock().unwrap().get(id.as_str()) {
+        Some(c) => HttpResponse::Ok().json(&c.checkout),
+        None => error(HttpResponse::NotFound(), "Checkout not found"),
+    }
+}
+
+fn pay((request, id, query): (HttpRequest<MockState>, Path<String>, Query<PayParameters>)) -> HttpResponse {
+    let success = query.outcome.unwrap_or(PayOutcome::Success) == PayOutcome::Success;
+    let mock_checkout = {
+        let mut checkouts = request.state().checkouts.lock().unwrap();
+        let mock_checkout = match checkouts.get_mut(id.as_str()) {
+            Some(c) => c,
+            None => return error(HttpResponse::NotFound(), "Checkout not found"),
+        };
+        if mock_checkout.checkout.status != CheckoutStatus::Pending {
+            return error(HttpResponse::UnprocessableEntity(), "Checkout is no longer pending");
+        }
+        mock_checkout.checkout.status = if mock_checkout.checkout.expires_at < Utc::now().naive_utc() {
+            CheckoutStatus::Expired
+        } else if success {
+            CheckoutStatus::Paid
+        } else {
+            CheckoutStatus::Cancelled
+        };
+        mock_checkout.clone()
+    };
+    let checkout = &mock_checkout.checkout;
+    let secret = &request.state().secret;
+
+    if let Some(ref ipn_url) = mock_checkout.ipn_url {
+        send_ipn(secret, ipn_url, checkout);
+    }
+
+    let success = checkout.status == CheckoutStatus::Paid;
+    let return_url = if success {
+        &mock_checkout.success_url
+    } else {
+        &mock_checkout.cancel_url
+    };
+    let mut return_url = match Url::parse(return_url) {
+        Ok(url) => url,
+        Err(_) => return error(HttpResponse::UnprocessableEntity(), "Invalid return url"),
+    };
+    return_url
+        .query_pairs_mut()
+        .append_pair("checkout_id", &checkout.id)
+        .append_pair(
+            "signature",
+            &sign(secret, &return_message(&checkout.id, &checkout.reference, success)),
+        );
+    HttpResponse::Found().header(LOCATION, return_url.as_str()).finish()
+}
+
+fn refund((request, id, data): (HttpRequest<MockState>, Path<String>, Json<RefundRequest>)) -> HttpResponse {
+    if !is_authorized(&request) {
+        return error(HttpResponse::Unauthorized(), "Invalid API key");
+    }
+    let mut checkouts = request.state().checkouts.lock().unwrap();
+    let mock_checkout = match checkouts.get_mut(id.as_str()) {
+        Some(c) => c,
+        None => return error(HttpResponse::NotFound(), "Checkout not found"),
+    };
+    let checkout = &mut mock_checkout.checkout;
+    if checkout.status != CheckoutStatus::Paid {
+        return error(
+            HttpResponse::UnprocessableEntity(),
+            "Only paid checkouts can be refunded",
+        );
+    }
+    if data.amount_in_cents <= 0 || checkout.refunded_in_cents + data.amount_in_cents > checkout.amount_in_cents {
+        return error(
+            HttpResponse::UnprocessableEntity(),
+            "Refund amount exceeds the remaining balance",
+        );
+    }
+    checkout.refunded_in_cents += data.amount_in_cents;
+    if checkout.refunded_in_cents == checkout.amount_in_cents {
+        checkout.status = CheckoutStatus::Refunded;
+    }
+    HttpResponse::Ok().json(&*checkout)
+}
+
+fn send_ipn(secret: &str, ipn_url: &str, checkout: &Checkout) {
+    let body = json!(checkout).to_string();
+    let result = reqwest::Client::new()
+        .post(ipn_url)
+        .header(SIGNATURE_HEADER, sign(secret, &body))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .and_then(|r| r.error_for_status());
+    if let Err(e) = result {
+        jlog!(Warn, "Mock hosted checkout could not deliver IPN", { "ipn_url": ipn_url, "error": e.to_string() });
+    }
+}
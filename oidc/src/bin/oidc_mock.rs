@@ -0,0 +1,19 @@
+extern crate logging;
+extern crate oidc;
+
+use oidc::mock;
+use std::env;
+
+const OIDC_MOCK_ADDRESS: &str = "OIDC_MOCK_ADDRESS";
+const OIDC_MOCK_CLIENT_ID: &str = "OIDC_MOCK_CLIENT_ID";
+const OIDC_MOCK_CLIENT_SECRET: &str = "OIDC_MOCK_CLIENT_SECRET";
+
+/// Runs a local OpenID Connect issuer so social and SSO logins can be tested offline.
+/// Configure a provider in the API with this server's address as its issuer.
+fn main() {
+    logging::setup_logger();
+    let address = env::var(OIDC_MOCK_ADDRESS).unwrap_or_else(|_| "127.0.0.1:7200".to_string());
+    let client_id = env::var(OIDC_MOCK_CLIENT_ID).unwrap_or_else(|_| "mock_client_id".to_string());
+    let client_secret = env::var(OIDC_MOCK_CLIENT_SECRET).unwrap_or_else(|_| "mock_client_secret".to_string());
+    mock::run(&address, &client_id, &client_secret);
+}
@@ -0,0 +1,654 @@
+//! OpenID Connect relying party used for social login and enterprise single sign-on.
+//!
+//! Provider endpoints are located through discovery and ID tokens are validated against the signing
+//! keys the provider publishes at its `jwks_uri`. Only RS256 signed ID tokens are accepted, which
+//! covers Google, Apple and the common enterprise identity providers. Signing keys are cached for as
+//! long as the provider's `Cache-Control` header allows.
+extern crate actix;
+extern crate actix_web;
+extern crate base64;
+#[macro_use]
+extern crate derive_error;
+extern crate jsonwebtoken as jwt;
+#[macro_use]
+extern crate lazy_static;
+extern crate log;
+#[macro_use]
+extern crate logging;
+extern crate reqwest;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+extern crate url;
+extern crate uuid;
+
+use jwt::{Algorithm, Validation};
+use log::Level::Debug;
+use reqwest::header::{HeaderMap, AGE, CACHE_CONTROL};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub mod mock;
+
+const DISCOVERY_PATH: &str = ".well-known/openid-configuration";
+/// Cached keys are refreshed for an unknown key id at most this often, providers add keys before signing with them
+const JWKS_MIN_REFRESH_SECONDS: u64 = 60;
+
+lazy_static! {
+    /// Signing keys by `jwks_uri`
+    static ref JWKS_CACHE: Mutex<HashMap<String, CachedJwks>> = Mutex::new(HashMap::new());
+}
+
+struct CachedJwks {
+    jwks: Jwks,
+    fetched_at: Instant,
+    expires_at: Instant,
+}
+
+pub struct OidcClient {
+    issuer: String,
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+impl OidcClient {
+    pub fn new(issuer: String, client_id: String, client_secret: Option<String>) -> OidcClient {
+        OidcClient {
+            issuer,
+            client_id,
+            client_secret,
+        }
+    }
+
+    pub fn discover(&self) -> Result<ProviderMetadata, OidcError> {
+        let url = format!("{}/{}", self.issuer.trim_end_matches('/'), DISCOVERY_PATH);
+        jlog!(Debug, "Retrieving OpenID Connect provider metadata", { "url": &url });
+        let metadata: ProviderMetadata = OidcClient::read_response(reqwest::Client::new().get(&url).send()?)?;
+        if metadata.issuer != self.issuer {
+            return Err(OidcError::ProviderError(
+                "Provider metadata does not match the configured issuer".to_string(),
+            ));
+        }
+        Ok(metadata)
+    }
+
+    /// Retrieves the provider's signing keys, caching them when the response allows it
+    pub fn jwks(&self, metadata: &ProviderMetadata) -> Result<Jwks, OidcError> {
+        jlog!(Debug, "Retrieving OpenID Connect provider signing keys", { "url": &metadata.jwks_uri });
+        let resp = reqwest::Client::new().get(&metadata.jwks_uri).send()?;
+        let max_age = cache_max_age(resp.headers());
+        let jwks: Jwks = OidcClient::read_response(resp)?;
+
+        let mut cache = JWKS_CACHE.lock().unwrap();
+        match max_age {
+            Some(max_age) => {
+                let now = Instant::now();
+                cache.insert(
+                    metadata.jwks_uri.clone(),
+                    CachedJwks {
+                        jwks: jwks.clone(),
+                        fetched_at: now,
+                        expires_at: now + max_age,
+                    },
+                );
+            }
+            None => {
+                cache.remove(&metadata.jwks_uri);
+            }
+        }
+        Ok(jwks)
+    }
+
+    /// Finds the signing key using the cached keys when they have not expired. The keys are retrieved again
+    /// when the key id is not found as the provider may have rotated its keys.
+    fn signing_key(&self, metadata: &ProviderMetadata, kid: Option<&str>) -> Result<Option<Jwk>, OidcError> {
+        {
+            let cache = JWKS_CACHE.lock().unwrap();
+            if let Some(cached) = cache.get(&metadata.jwks_uri) {
+                let now = Instant::now();
+                if cached.expires_at > now {
+                    let key = cached.jwks.find_signing_key(kid).cloned();
+                    if key.is_some() || cached.fetched_at + Duration::from_secs(JWKS_MIN_REFRESH_SECONDS) > now {
+                        return Ok(key);
+                    }
+                }
+            }
+        }
+
+        Ok(self.jwks(metadata)?.find_signing_key(kid).cloned())
+    }
+
+    /// Exchanges an authorization code returned to the redirect URI for the user's tokens
+    pub fn exchange_code(
+        &self,
+        metadata: &ProviderMetadata,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<TokenResponse, OidcError> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", &self.client_id),
+        ];
+        if let Some(ref client_secret) = self.client_secret {
+            params.push(("client_secret", client_secret));
+        }
+        if let Some(code_verifier) = code_verifier {
+            params.push(("code_verifier", code_verifier));
+        }
+
+        jlog!(Debug, "Exchanging authorization code with OpenID Connect provider", { "url": &metadata.token_endpoint });
+        let resp = reqwest::Client::new()
+            .post(&metadata.token_endpoint)
+            .form(&params)
+            .send()?;
+        OidcClient::read_response(resp)
+    }
+
+    /// Validates the ID token signature against the provider's published keys along with the issuer,
+    /// audience, expiry and, when one was sent with the authentication request, the nonce
+    pub fn validate_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+        nonce: Option<&str>,
+    ) -> Result<IdTokenClaims, OidcError> {
+        let header = jwt::decode_header(id_token)?;
+        if header.alg != Algorithm::RS256 {
+            return Err(OidcError::InvalidIdToken(
+                "ID token must be signed using RS256".to_string(),
+            ));
+        }
+
+        let key = match self.signing_key(metadata, header.kid.as_ref().map(|k| k.as_str()))? {
+            Some(key) => key,
+            None => {
+                return Err(OidcError::InvalidIdToken(
+                    "ID token signing key was not found".to_string(),
+                ))
+            }
+        };
+        let claims =
+            jwt::decode::<IdTokenClaims>(id_token, &key.rsa_public_key_der()?, &Validation::new(Algorithm::RS256))?
+                .claims;
+
+        if claims.iss != self.issuer {
+            return Err(OidcError::InvalidIdToken("ID token issuer is invalid".to_string()));
+        }
+        if !claims.aud.contains(&self.client_id) {
+            return Err(OidcError::InvalidIdToken("ID token audience is invalid".to_string()));
+        }
+        if claims.azp.as_ref().map(|azp| *azp != self.client_id).unwrap_or(false) {
+            return Err(OidcError::InvalidIdToken(
+                "ID token authorized party is invalid".to_string(),
+            ));
+        }
+        if let Some(nonce) = nonce {
+            if claims.nonce.as_ref().map(|n| n.as_str()) != Some(nonce) {
+                return Err(OidcError::InvalidIdToken("ID token nonce is invalid".to_string()));
+            }
+        }
+
+        Ok(claims)
+    }
+
+    fn read_response<T: DeserializeOwned>(mut resp: reqwest::Response) -> Result<T, OidcError> {
+        let status = resp.status();
+        let value: serde_json::Value = resp.json()?;
+        // Responses contain tokens so only the status is logged
+        jlog!(Debug, "Response from OpenID Connect provider", { "status": status.as_u16() });
+        if status != StatusCode::OK {
+            let error: ErrorResponse = serde_json::from_value(value).unwrap_or(ErrorResponse {
+                error: format!("Unexpected status code from OpenID Connect provider: {}", status),
+                error_description: None,
+            });
+            return Err(OidcError::ProviderError(error.error_description.unwrap_or(error.error)));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum OidcError {
+    HttpError(reqwest::Error),
+    DeserializationError(serde_json::Error),
+    TokenError(jwt::errors::Error),
+    #[error(msg_embedded, no_from, non_std)]
+    InvalidIdToken(String),
+    #[error(msg_embedded, no_from, non_std)]
+    ProviderError(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+    #[serde(default)]
+    pub id_token_signing_alg_values_supported: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+impl Jwks {
+    /// Finds the RSA signing key with the given id, tokens without a key id can only be matched when the
+    /// provider publishes a single key
+    pub fn find_signing_key(&self, kid: Option<&str>) -> Option<&Jwk> {
+        let mut keys = self
+            .keys
+            .iter()
+            .filter(|k| k.kty == "RSA" && k.key_use.as_ref().map(|u| u == "sig").unwrap_or(true));
+        match kid {
+            Some(kid) => keys.find(|k| k.kid.as_ref().map(|k| k.as_str()) == Some(kid)),
+            None => {
+                let keys: Vec<&Jwk> = keys.collect();
+                if keys.len() == 1 {
+                    Some(keys[0])
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    #[serde(default, rename = "use", skip_serializing_if = "Option::is_none")]
+    pub key_use: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+}
+
+impl Jwk {
+    /// DER encoded PKCS#1 public key as expected when verifying RS256 signatures
+    pub fn rsa_public_key_der(&self) -> Result<Vec<u8>, OidcError> {
+        match (&self.n, &self.e) {
+            (Some(n), Some(e)) => Ok(rsa_public_key_der(&decode_key_component(n)?, &decode_key_component(e)?)),
+            _ => Err(OidcError::ProviderError(
+                "Signing key is missing its modulus or exponent".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: Audience,
+    pub exp: u64,
+    pub iat: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Apple sends the verification flag as a string
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_or_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub email_verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// Email address if the provider has verified the user owns it
+    pub fn verified_email(&self) -> Option<String> {
+        match self.email_verified {
+            Some(true) => self.email.clone(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    pub fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Audience::Single(aud) => aud == client_id,
+            Audience::Multiple(auds) => auds.iter().any(|aud| aud == client_id),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub id_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorResponse {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+fn deserialize_bool_or_string<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+
+    Ok(match Option::<BoolOrString>::deserialize(deserializer)? {
+        Some(BoolOrString::Bool(value)) => Some(value),
+        Some(BoolOrString::String(value)) => Some(value == "true"),
+        None => None,
+    })
+}
+
+/// How long a response may be cached according to its `Cache-Control` max age less its `Age`, responses
+/// without a max age or that must be revalidated are not cached
+fn cache_max_age(headers: &HeaderMap) -> Option<Duration> {
+    let cache_control = headers.get(CACHE_CONTROL)?.to_str().ok()?.to_lowercase();
+    let mut max_age = None;
+    for directive in cache_control.split(',').map(|d| d.trim()) {
+        match directive {
+            "no-cache" | "no-store" => return None,
+            d if d.starts_with("max-age=") => max_age = d["max-age=".len()..].trim_matches('"').parse::<u64>().ok(),
+            _ => {}
+        }
+    }
+    let age = headers
+        .get(AGE)
+        .and_then(|age| age.to_str().ok())
+        .and_then(|age| age.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    match max_age {
+        Some(max_age) if max_age > age => Some(Duration::from_secs(max_age - age)),
+        _ => None,
+    }
+}
+
+fn decode_key_component(value: &str) -> Result<Vec<u8>, OidcError> {
+    base64::decode_config(value.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+        .map_err(|_| OidcError::ProviderError("Signing key is not valid base64".to_string()))
+}
+
+/// Encodes the modulus and exponent as an ASN.1 RSAPublicKey sequence
+pub fn rsa_public_key_der(modulus: &[u8], exponent: &[u8]) -> Vec<u8> {
+    let mut content = der_integer(modulus);
+    content.extend(der_integer(exponent));
+    der_element(0x30, &content)
+}
+
+fn der_integer(value: &[u8]) -> Vec<u8> {
+    // Integers are positive and minimally encoded, a zero byte is added when the high bit is set
+    let value: Vec<u8> = value.iter().cloned().skip_while(|b| *b == 0).collect();
+    let mut content = Vec::new();
+    if value.first().map(|b| b & 0x80 != 0).unwrap_or(true) {
+        content.push(0);
+    }
+    content.extend(value);
+    der_element(0x02, &content)
+}
+
+fn der_element(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut element = vec![tag];
+    if content.len() < 0x80 {
+        element.push(content.len() as u8);
+    } else {
+        let length: Vec<u8> = (content.len() as u64)
+            .to_be_bytes()
+            .iter()
+            .cloned()
+            .skip_while(|b| *b == 0)
+            .collect();
+        element.push(0x80 | length.len() as u8);
+        element.extend(length);
+    }
+    element.extend_from_slice(content);
+    element
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    pub fn jwk_rsa_public_key_der() {
+        let jwks = mock::jwks();
+        let key = jwks.find_signing_key(Some(mock::MOCK_KEY_ID)).unwrap();
+        assert_eq!(
+            key.rsa_public_key_der().unwrap(),
+            include_bytes!("mock_public_key.der").to_vec()
+        );
+        assert!(jwks.find_signing_key(Some("unknown")).is_none());
+        // A single published key is used for tokens without a key id
+        assert!(jwks.find_signing_key(None).is_some());
+    }
+
+    #[test]
+    pub fn jwks_cache_max_age() {
+        let headers = |cache_control: &str, age: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, cache_control.parse().unwrap());
+            if let Some(age) = age {
+                headers.insert(AGE, age.parse().unwrap());
+            }
+            headers
+        };
+        assert_eq!(
+            cache_max_age(&headers("public, max-age=21600, must-revalidate", None)),
+            Some(Duration::from_secs(21600))
+        );
+        assert_eq!(
+            cache_max_age(&headers("max-age=3600", Some("600"))),
+            Some(Duration::from_secs(3000))
+        );
+        assert_eq!(cache_max_age(&headers("max-age=3600", Some("3600"))), None);
+        assert_eq!(cache_max_age(&headers("no-cache, max-age=3600", None)), None);
+        assert_eq!(cache_max_age(&headers("no-store", None)), None);
+        assert_eq!(cache_max_age(&headers("public", None)), None);
+        assert_eq!(cache_max_age(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    pub fn jwks_cached() {
+        let issuer = mock::start("client_id", "client_secret");
+        let client = OidcClient::new(issuer.clone(), "client_id".to_string(), None);
+        let metadata = client.discover().unwrap();
+        assert!(!JWKS_CACHE.lock().unwrap().contains_key(&metadata.jwks_uri));
+
+        let id_token = mock::sign_id_token(&mock::id_token_claims(&issuer, "client_id", "user-1"));
+        assert!(client.validate_id_token(&metadata, &id_token, None).is_ok());
+        assert!(JWKS_CACHE.lock().unwrap().contains_key(&metadata.jwks_uri));
+
+        // Cached keys are used without contacting the provider
+        let unreachable_metadata = ProviderMetadata {
+            jwks_uri: "http://127.0.0.1:1/jwks".to_string(),
+            ..metadata.clone()
+        };
+        assert!(client
+            .signing_key(&unreachable_metadata, Some(mock::MOCK_KEY_ID))
+            .is_err());
+        let now = Instant::now();
+        JWKS_CACHE.lock().unwrap().insert(
+            unreachable_metadata.jwks_uri.clone(),
+            CachedJwks {
+                jwks: mock::jwks(),
+                fetched_at: now,
+                expires_at: now + Duration::from_secs(60),
+            },
+        );
+        assert!(client
+            .signing_key(&unreachable_metadata, Some(mock::MOCK_KEY_ID))
+            .unwrap()
+            .is_some());
+        // Unknown keys are not retrieved again until the minimum refresh time has passed
+        assert!(client
+            .signing_key(&unreachable_metadata, Some("unknown"))
+            .unwrap()
+            .is_none());
+
+        // Expired keys are retrieved again
+        JWKS_CACHE
+            .lock()
+            .unwrap()
+            .get_mut(&unreachable_metadata.jwks_uri)
+            .unwrap()
+            .expires_at = now;
+        assert!(client
+            .signing_key(&unreachable_metadata, Some(mock::MOCK_KEY_ID))
+            .is_err());
+    }
+
+    #[test]
+    pub fn deserialize_email_verified() {
+        let claims: IdTokenClaims = serde_json::from_value(json!({
+            "iss": "https://appleid.apple.com",
+            "sub": "001",
+            "aud": ["client", "other"],
+            "exp": 1,
+            "iat": 1,
+            "email": "user@tari.com",
+            "email_verified": "true"
+        }))
+        .unwrap();
+        assert_eq!(claims.email_verified, Some(true));
+        assert_eq!(claims.verified_email(), Some("user@tari.com".to_string()));
+        assert!(claims.aud.contains("client"));
+        assert!(!claims.aud.contains("unknown"));
+
+        let claims: IdTokenClaims = serde_json::from_value(json!({
+            "iss": "https://accounts.google.com",
+            "sub": "002",
+            "aud": "client",
+            "exp": 1,
+            "iat": 1,
+            "email": "user@tari.com"
+        }))
+        .unwrap();
+        assert_eq!(claims.email_verified, None);
+        assert_eq!(claims.verified_email(), None);
+    }
+
+    #[test]
+    pub fn validate_id_token_with_mock_issuer() {
+        let issuer = mock::start("client_id", "client_secret");
+        let client = OidcClient::new(issuer.clone(), "client_id".to_string(), None);
+        let metadata = client.discover().unwrap();
+        assert_eq!(metadata.issuer, issuer);
+
+        let mut claims = mock::id_token_claims(&issuer, "client_id", "user-1");
+        claims.nonce = Some("nonce".to_string());
+        let id_token = mock::sign_id_token(&claims);
+        assert_eq!(
+            client.validate_id_token(&metadata, &id_token, Some("nonce")).unwrap(),
+            claims
+        );
+        assert!(client.validate_id_token(&metadata, &id_token, None).is_ok());
+        assert!(client.validate_id_token(&metadata, &id_token, Some("other")).is_err());
+
+        // Tokens issued to other clients are rejected
+        let other_client = OidcClient::new(issuer.clone(), "other_client_id".to_string(), None);
+        assert!(other_client.validate_id_token(&metadata, &id_token, None).is_err());
+
+        // Expired tokens are rejected
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut claims = mock::id_token_claims(&issuer, "client_id", "user-1");
+        claims.exp = now - 60;
+        let id_token = mock::sign_id_token(&claims);
+        assert!(client.validate_id_token(&metadata, &id_token, None).is_err());
+
+        // Tokens from another issuer are rejected
+        let claims = mock::id_token_claims("https://accounts.google.com", "client_id", "user-1");
+        let id_token = mock::sign_id_token(&claims);
+        assert!(client.validate_id_token(&metadata, &id_token, None).is_err());
+
+        // Tampered tokens are rejected
+        let claims = mock::id_token_claims(&issuer, "client_id", "user-1");
+        let id_token = mock::sign_id_token(&claims);
+        let parts: Vec<&str> = id_token.split('.').collect();
+        let mut tampered_claims = claims.clone();
+        tampered_claims.sub = "user-2".to_string();
+        let tampered_payload =
+            base64::encode_config(&serde_json::to_vec(&tampered_claims).unwrap(), base64::URL_SAFE_NO_PAD);
+        let tampered_token = format!("{}.{}.{}", parts[0], tampered_payload, parts[2]);
+        assert!(client.validate_id_token(&metadata, &tampered_token, None).is_err());
+    }
+
+    #[test]
+    pub fn exchange_code_with_mock_issuer() {
+        let issuer = mock::start("client_id", "client_secret");
+        let redirect_uri = "http://localhost/callback";
+        let code = mock::authorize(
+            &issuer,
+            "client_id",
+            redirect_uri,
+            "user-1",
+            Some("user@tari.com"),
+            None,
+        );
+
+        let client = OidcClient::new(issuer.clone(), "client_id".to_string(), Some("wrong".to_string()));
+        let metadata = client.discover().unwrap();
+        assert!(client.exchange_code(&metadata, &code, redirect_uri, None).is_err());
+
+        let code = mock::authorize(
+            &issuer,
+            "client_id",
+            redirect_uri,
+            "user-1",
+            Some("user@tari.com"),
+            Some("nonce"),
+        );
+        let client = OidcClient::new(
+            issuer.clone(),
+            "client_id".to_string(),
+            Some("client_secret".to_string()),
+        );
+        let token_response = client.exchange_code(&metadata, &code, redirect_uri, None).unwrap();
+        let claims = client
+            .validate_id_token(&metadata, &token_response.id_token, Some("nonce"))
+            .unwrap();
+        assert_eq!(claims.sub, "user-1");
+        assert_eq!(claims.verified_email(), Some("user@tari.com".to_string()));
+
+        // Codes can only be exchanged once
+        assert!(client.exchange_code(&metadata, &code, redirect_uri, None).is_err());
+    }
+}
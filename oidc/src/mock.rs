@@ -0,0 +1,294 @@
+//! In-memory OpenID Connect provider for local development and tests.
+//!
+//! Visiting `/authorize` simulates the user signing in at the provider, the identity to sign in as is
+//! taken from the `sub`, `email`, `email_verified`, `given_name` and `family_name` query parameters.
+//! ID tokens are signed with a fixed RSA key that is published at `/jwks`, it must never be trusted
+//! outside of development and tests.
+use super::*;
+use actix_web::http::header::{CACHE_CONTROL, LOCATION};
+use actix_web::{server, App, Form, HttpRequest, HttpResponse, Query};
+use log::Level::Info;
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+use uuid::Uuid;
+
+pub const MOCK_KEY_ID: &str = "oidc-mock";
+const MOCK_PRIVATE_KEY: &[u8] = include_bytes!("mock_private_key.der");
+const MOCK_MODULUS: &str = "\
+    0CLi8qcPXJrNCOUN-t7rXzY-3KxWAm725JPsmMMM3S3234fUEOs6QgN85Prf353IVOzkMIrMxIJOcmoXMP1n6PaZ8pH4vhCA\
+     84rpozCWkShaf63OsV9Wxi8nbSqpa1Y70LZfqe9wnhsBM_pPmgEfslN651h8emjOKX7CM0cOYm_Et0D3ZTZBdmN_5A-f3bLm\
+     3n6kiTtA8bX5waaxjmi1-gGPn-c3qnI_5d4h4n8qpLmcQ63Rmvl9H6OVeS8O1CX_zceBdcQTpi4Xw4fMBSeKB8Kja86-OMx_\
+     AO_XuEB597mB0l7hJLxjwCP0VEbmdp5YAgVwAXXgaleTpgJiAwM5OQ";
+const MOCK_EXPONENT: &str = "AQAB";
+
+#[derive(Clone)]
+pub struct MockState {
+    issuer: String,
+    client_id: String,
+    client_secret: String,
+    codes: Arc<Mutex<HashMap<String, MockAuthorization>>>,
+}
+
+#[derive(Clone)]
+struct MockAuthorization {
+    redirect_uri: String,
+    claims: IdTokenClaims,
+}
+
+#[derive(Deserialize)]
+pub struct AuthorizeParameters {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub state: Option<String>,
+    pub nonce: Option<String>,
+    pub sub: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TokenParameters {
+    pub grant_type: String,
+    pub code: String,
+    pub redirect_uri: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+impl MockState {
+    pub fn new(issuer: &str, client_id: &str, client_secret: &str) -> MockState {
+        MockState {
+            issuer: issuer.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            codes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+pub fn create_app(state: MockState) -> App<MockState> {
+    App::with_state(state)
+        .resource("/.well-known/openid-configuration", |r| r.get().f(configuration))
+        .resource("/jwks", |r| {
+            r.get().f(|_| {
+                HttpResponse::Ok()
+                    .header(CACHE_CONTROL, "public, max-age=3600")
+                    .json(jwks())
+            })
+        })
+        .resource("/authorize", |r| r.get().with(authorize))
+        .resource("/token", |r| r.post().with(token))
+}
+
+/// Runs the mock server on the given address, blocking until the server is stopped
+pub fn run(address: &str, client_id: &str, client_secret: &str) {
+    let system = actix::System::new("oidc-mock");
+    let state = MockState::new(&format!("http://{}", address), client_id, client_secret);
+    server::new(move || create_app(state.clone()))
+        .bind(address)
+        .expect(&format!("Could not bind mock OpenID Connect server to {}", address))
+        .start();
+    jlog!(Info, &format!("Mock OpenID Connect server listening on {}", address));
+    system.run();
+}
+
+/// Starts the mock server on a random local port in the background and returns its issuer
+pub fn start(client_id: &str, client_secret: &str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind mock OpenID Connect server");
+    let address = listener
+        .local_addr()
+        .expect("Could not read mock OpenID Connect server address");
+    let issuer = format!("http://{}", address);
+    let state = MockState::new(&issuer, client_id, client_secret);
+    thread::spawn(move || {
+        let system = actix::System::new("oidc-mock");
+        server::new(move || create_app(state.clone())).listen(listener).start();
+        system.run();
+    });
+    issuer
+}
+
+pub fn jwks() -> Jwks {
+    Jwks {
+        keys: vec![Jwk {
+            kty: "RSA".to_string(),
+            kid: Some(MOCK_KEY_ID.to_string()),
+            alg: Some("RS256".to_string()),
+            key_use: Some("sig".to_string()),
+            n: Some(MOCK_MODULUS.to_string()),
+            e: Some(MOCK_EXPONENT.to_string()),
+        }],
+    }
+}
+
+/// Claims for an ID token issued now and valid for an hour
+pub fn id_token_claims(issuer: &str, client_id: &str, sub: &str) -> IdTokenClaims {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    IdTokenClaims {
+        iss: issuer.to_string(),
+        sub: sub.to_string(),
+        aud: Audience::Single(client_id.to_string()),
+        exp: now + 3600,
+        iat: now,
+        azp: None,
+        nonce: None,
+        email: None,
+        email_verified: None,
+        name: None,
+        given_name: None,
+        family_name: None,
+    }
+}
+
+/// Signs the claims with the mock provider's key
+pub fn sign_id_token(claims: &IdTokenClaims) -> String {
+    let mut header = jwt::Header::new(Algorithm::RS256);
+    header.kid = Some(MOCK_KEY_ID.to_string());
+    jwt::encode(&header, claims, MOCK_PRIVATE_KEY).expect("Could not sign mock ID token")
+}
+
+/// Signs in at the mock provider without following the redirect and returns the authorization code
+pub fn authorize(
+    issuer: &str,
+    client_id: &str,
+    redirect_uri: &str,
+    sub: &str,
+    email: Option<&str>,
+    nonce: Option<&str>,
+) -> String {
+    let mut url = Url::parse(&format!("{}/authorize", issuer)).expect("Invalid mock issuer");
+    url.query_pairs_mut()
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("sub", sub);
+    if let Some(nonce) = nonce {
+        url.query_pairs_mut().append_pair("nonce", nonce);
+    }
+    if let Some(email) = email {
+        url.query_pairs_mut()
+            .append_pair("email", email)
+            .append_pair("email_verified", "true");
+    }
+
+    let resp = reqwest::Client::builder()
+        .redirect(reqwest::RedirectPolicy::none())
+        .build()
+        .and_then(|client| client.get(url.as_str()).send())
+        .expect("Could not authorize with mock OpenID Connect server");
+    let location = resp
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|l| l.to_str().ok())
+        .and_then(|l| Url::parse(l).ok())
+        .expect("Mock OpenID Connect server did not redirect");
+    let code = location
+        .query_pairs()
+        .find(|(key, _)| key == "code")
+        .map(|(_, code)| code.to_string());
+    code.expect("Mock OpenID Connect server did not return a code")
+}
+
+fn error(mut response: actix_web::dev::HttpResponseBuilder, error: &str, description: &str) -> HttpResponse {
+    response.json(ErrorResponse {
+        error: error.to_string(),
+        error_description: Some(description.to_string()),
+    })
+}
+
+fn configuration(request: &HttpRequest<MockState>) -> HttpResponse {
+    let issuer = &request.state().issuer;
+    HttpResponse::Ok().json(ProviderMetadata {
+        issuer: issuer.clone(),
+        authorization_endpoint: format!("{}/authorize", issuer),
+        token_endpoint: format!("{}/token", issuer),
+        jwks_uri: format!("{}/jwks", issuer),
+        id_token_signing_alg_values_supported: vec!["RS256".to_string()],
+    })
+}
+
+fn authorize((request, query): (HttpRequest<MockState>, Query<AuthorizeParameters>)) -> HttpResponse {
+    let state = request.state();
+    if query.client_id != state.client_id {
+        return error(HttpResponse::BadRequest(), "unauthorized_client", "Unknown client");
+    }
+    let mut redirect_uri = match Url::parse(&query.redirect_uri) {
+        Ok(url) => url,
+        Err(_) => return error(HttpResponse::BadRequest(), "invalid_request", "Invalid redirect uri"),
+    };
+
+    let sub = query.sub.clone().unwrap_or(Uuid::new_v4().to_string());
+    let mut claims = id_token_claims(&state.issuer, &state.client_id, &sub);
+    claims.nonce = query.nonce.clone();
+    claims.email = query.email.clone();
+    claims.email_verified = query.email_verified;
+    claims.given_name = query.given_name.clone();
+    claims.family_name = query.family_name.clone();
+
+    let code = Uuid::new_v4().simple().to_string();
+    state.codes.lock().unwrap().insert(
+        code.clone(),
+        MockAuthorization {
+            redirect_uri: query.redirect_uri.clone(),
+            claims,
+        },
+    );
+
+    {
+        let mut query_pairs = redirect_uri.query_pairs_mut();
+        query_pairs.append_pair("code", &code);
+        if let Some(ref state) = query.state {
+            query_pairs.append_pair("state", state);
+        }
+    }
+    HttpResponse::Found().header(LOCATION, redirect_uri.as_str()).finish()
+}
+
+fn token((request, form): (HttpRequest<MockState>, Form<TokenParameters>)) -> HttpResponse {
+    let state = request.state();
+    if form.client_id != state.client_id || form.client_secret.as_ref() != Some(&state.client_secret) {
+        return error(
+            HttpResponse::Unauthorized(),
+            "invalid_client",
+            "Client authentication failed",
+        );
+    }
+    if form.grant_type != "authorization_code" {
+        return error(
+            HttpResponse::BadRequest(),
+            "unsupported_grant_type",
+            "Grant type is not supported",
+        );
+    }
+
+    // Codes are single use whether or not the exchange succeeds
+    let authorization = match state.codes.lock().unwrap().remove(&form.code) {
+        Some(authorization) => authorization,
+        None => {
+            return error(
+                HttpResponse::BadRequest(),
+                "invalid_grant",
+                "Authorization code is invalid",
+            )
+        }
+    };
+    if authorization.redirect_uri != form.redirect_uri {
+        return error(
+            HttpResponse::BadRequest(),
+            "invalid_grant",
+            "Redirect uri does not match",
+        );
+    }
+
+    HttpResponse::Ok().json(TokenResponse {
+        access_token: Uuid::new_v4().simple().to_string(),
+        token_type: "Bearer".to_string(),
+        id_token: sign_id_token(&authorization.claims),
+        expires_in: Some(3600),
+    })
+}
@@ -4,6 +4,7 @@ use StripeError;
 
 pub struct Customer {
     pub id: String,
+    pub default_source: Option<String>,
     pub raw_data: String,
 }
 
@@ -16,10 +17,12 @@ impl Customer {
         #[derive(Deserialize)]
         struct R {
             id: String,
+            default_source: Option<String>,
         }
         let result: R = serde_json::from_str(&raw)?;
         Ok(Customer {
             id: result.id,
+            default_source: result.default_source,
             raw_data: raw,
         })
     }
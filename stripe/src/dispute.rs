@@ -0,0 +1,46 @@
+use serde_json;
+use StripeError;
+
+#[derive(Deserialize, Serialize)]
+pub struct Dispute {
+    pub id: String,
+    pub amount: i64,
+    pub currency: String,
+    /// The disputed charge
+    pub charge: String,
+    /// Set when the disputed charge was created by a payment intent
+    pub payment_intent: Option<String>,
+    pub reason: String,
+    pub status: String,
+    pub raw_data: String,
+}
+
+impl Dispute {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+
+    pub fn from_raw(raw: &str) -> Result<Dispute, StripeError> {
+        #[derive(Deserialize)]
+        struct R {
+            id: String,
+            amount: i64,
+            currency: String,
+            charge: String,
+            payment_intent: Option<String>,
+            reason: String,
+            status: String,
+        }
+        let result: R = serde_json::from_str(raw)?;
+        Ok(Dispute {
+            id: result.id,
+            amount: result.amount,
+            currency: result.currency,
+            charge: result.charge,
+            payment_intent: result.payment_intent,
+            reason: result.reason,
+            status: result.status,
+            raw_data: raw.to_string(),
+        })
+    }
+}
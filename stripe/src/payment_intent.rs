@@ -0,0 +1,58 @@
+use reqwest;
+use serde_json;
+use StripeError;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentIntentStatus {
+    RequiresPaymentMethod,
+    RequiresConfirmation,
+    RequiresAction,
+    Processing,
+    RequiresCapture,
+    Canceled,
+    Succeeded,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PaymentIntent {
+    pub id: String,
+    pub status: PaymentIntentStatus,
+    pub amount: i64,
+    /// Passed to Stripe.js so the customer can complete any required action such as 3-D Secure
+    pub client_secret: Option<String>,
+    pub raw_data: String,
+}
+
+impl PaymentIntent {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+
+    pub fn from_response(mut resp: reqwest::Response) -> Result<PaymentIntent, StripeError> {
+        let raw: String = resp.text()?;
+        PaymentIntent::from_raw(&raw)
+    }
+
+    pub fn from_raw(raw: &str) -> Result<PaymentIntent, StripeError> {
+        #[derive(Deserialize)]
+        struct R {
+            id: String,
+            status: PaymentIntentStatus,
+            amount: i64,
+            client_secret: Option<String>,
+        }
+        let result: R = serde_json::from_str(raw)?;
+        Ok(PaymentIntent {
+            id: result.id,
+            status: result.status,
+            amount: result.amount,
+            client_secret: result.client_secret,
+            raw_data: raw.to_string(),
+        })
+    }
+
+    pub fn requires_action(&self) -> bool {
+        self.status == PaymentIntentStatus::RequiresAction
+    }
+}
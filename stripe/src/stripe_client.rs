@@ -1,6 +1,7 @@
 use reqwest;
 use ChargeResult;
 use Customer;
+use PaymentIntent;
 use RefundResult;
 use StripeError;
 
@@ -48,7 +49,15 @@ impl StripeClient {
 
         let client = reqwest::Client::new();
         let mut resp = client
-            .post(&format!("https://api.stripe.com/v1/charges/{}", charge_id))
+            .post(&format!(
+                "https://api.stripe.com/v1/{}/{}",
+                if is_payment_intent(charge_id) {
+                    "payment_intents"
+                } else {
+                    "charges"
+                },
+                charge_id
+            ))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -102,7 +111,7 @@ impl StripeClient {
     }
 
     pub fn refund(&self, charge_id: &str) -> Result<RefundResult, StripeError> {
-        let params = vec![("charge".to_string(), charge_id.to_string())];
+        let params = vec![(refund_target(charge_id), charge_id.to_string())];
 
         let client = reqwest::Client::new();
         let mut resp = client
@@ -120,7 +129,7 @@ impl StripeClient {
 
     pub fn partial_refund(&self, charge_id: &str, amount: i64) -> Result<RefundResult, StripeError> {
         let params = vec![
-            ("charge".to_string(), charge_id.to_string()),
+            (refund_target(charge_id), charge_id.to_string()),
             ("amount".to_string(), amount.to_string()),
         ];
 
@@ -153,6 +162,145 @@ impl StripeClient {
         }
     }
 
+    /// Creates and confirms a manually captured payment intent. If the card requires 3-D Secure the
+    /// intent is returned with a status of `requires_action` and must be confirmed by the customer
+    /// using the intent's client secret before it can be captured.
+    pub fn create_payment_intent(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntent, StripeError> {
+        let mut params = vec![
+            ("currency".to_string(), currency.to_string()),
+            ("amount".to_string(), amount.to_string()),
+            ("description".to_string(), description.to_string()),
+            ("capture_method".to_string(), "manual".to_string()),
+            ("confirm".to_string(), "true".to_string()),
+        ];
+        params.append(&mut self.payment_method_params(token)?);
+
+        for key_value in metadata {
+            params.push((format!("metadata[{}]", key_value.0), key_value.1));
+        }
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post("https://api.stripe.com/v1/payment_intents")
+            .basic_auth(&self.api_key, Some(""))
+            .form(&params)
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    pub fn retrieve_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .get(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    pub fn confirm_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}/confirm",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    pub fn capture_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}/capture",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    pub fn cancel_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}/cancel",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    /// Payment methods (`pm_`) are used directly, card tokens (`tok_`) are converted into a payment
+    /// method and saved customers (`cus_`) are charged using their default card.
+    fn payment_method_params(&self, token: &str) -> Result<Vec<(String, String)>, StripeError> {
+        if token.starts_with("pm_") {
+            return Ok(vec![("payment_method".to_string(), token.to_string())]);
+        }
+        if token.starts_with("tok_") {
+            return Ok(vec![
+                ("payment_method_data[type]".to_string(), "card".to_string()),
+                ("payment_method_data[card][token]".to_string(), token.to_string()),
+            ]);
+        }
+
+        let mut params = vec![("customer".to_string(), token.to_string())];
+        if let Some(default_source) = self.retrieve_customer(token)?.default_source {
+            params.push(("payment_method".to_string(), default_source));
+        }
+        Ok(params)
+    }
+
+    pub fn retrieve_customer(&self, client_id: &str) -> Result<Customer, StripeError> {
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .get(&format!("https://api.stripe.com/v1/customers/{}", client_id))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return Customer::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
     pub fn update_customer(
         &self,
         client_id: &str,
@@ -210,3 +358,16 @@ impl StripeClient {
         }
     }
 }
+
+fn is_payment_intent(id: &str) -> bool {
+    id.starts_with("pi_")
+}
+
+/// Refunds are created against either a payment intent or a legacy charge
+fn refund_target(id: &str) -> String {
+    if is_payment_intent(id) {
+        "payment_intent".to_string()
+    } else {
+        "charge".to_string()
+    }
+}
@@ -0,0 +1,105 @@
+use hex;
+use ring::{digest, hmac};
+use serde_json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use StripeError;
+
+/// Header containing the timestamp and signatures of a webhook body
+pub const SIGNATURE_HEADER: &str = "Stripe-Signature";
+/// Maximum age in seconds of a signed webhook before it is considered a replay
+const SIGNATURE_TOLERANCE: i64 = 300;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created: i64,
+    pub data: WebhookEventData,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct WebhookEventData {
+    pub object: serde_json::Value,
+}
+
+impl WebhookEvent {
+    pub fn from_raw(raw: &str) -> Result<WebhookEvent, StripeError> {
+        Ok(serde_json::from_str(raw)?)
+    }
+}
+
+/// Verifies a `Stripe-Signature` header of the form `t=<timestamp>,v1=<signature>` against the raw
+/// body. Signatures are hex encoded HMAC-SHA256 digests of `<timestamp>.<body>` keyed with the
+/// endpoint's signing secret.
+pub fn verify_signature(secret: &str, signature_header: &str, payload: &str) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    verify_signature_at(secret, signature_header, payload, now)
+}
+
+fn verify_signature_at(secret: &str, signature_header: &str, payload: &str, now: i64) -> bool {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in signature_header.split(',') {
+        let mut key_value = part.trim().splitn(2, '=');
+        match (key_value.next(), key_value.next()) {
+            (Some("t"), Some(value)) => timestamp = value.parse::<i64>().ok(),
+            (Some("v1"), Some(value)) => signatures.push(value),
+            _ => (),
+        }
+    }
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => return false,
+    };
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE {
+        return false;
+    }
+
+    let key = hmac::VerificationKey::new(&digest::SHA256, secret.as_bytes());
+    let signed_payload = format!("{}.{}", timestamp, payload);
+    signatures.iter().any(|signature| match hex::decode(signature) {
+        Ok(signature) => hmac::verify(&key, signed_payload.as_bytes(), &signature).is_ok(),
+        Err(_) => false,
+    })
+}
+
+/// Creates a `Stripe-Signature` header value, used to simulate webhooks in tests
+pub fn sign(secret: &str, timestamp: i64, payload: &str) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes());
+    format!("t={},v1={}", timestamp, hex::encode(signature.as_ref()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verify_webhook_signature() {
+        let payload = r#"{"id":"evt_1","type":"payment_intent.succeeded"}"#;
+        let header = sign("whsec_test", 1_580_000_000, payload);
+        assert!(verify_signature_at("whsec_test", &header, payload, 1_580_000_010));
+        assert!(!verify_signature_at("whsec_other", &header, payload, 1_580_000_010));
+        assert!(!verify_signature_at("whsec_test", &header, "{}", 1_580_000_010));
+        // Too old
+        assert!(!verify_signature_at("whsec_test", &header, payload, 1_580_001_000));
+        // Multiple signatures are sent while secrets are being rolled
+        let rolled = format!("{},v1=abcdef", header);
+        assert!(verify_signature_at("whsec_test", &rolled, payload, 1_580_000_010));
+        assert!(!verify_signature_at("whsec_test", "v1=abcdef", payload, 1_580_000_010));
+    }
+
+    #[test]
+    fn parse_webhook_event() {
+        let event = WebhookEvent::from_raw(
+            r#"{"id":"evt_1","type":"payment_intent.succeeded","created":1580000000,"data":{"object":{"id":"pi_1"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(event.event_type, "payment_intent.succeeded");
+        assert_eq!(event.data.object["id"], "pi_1");
+    }
+}